@@ -0,0 +1,2 @@
+-- Timestamp of the most recent inferred Omega -> Alpha transition; NULL while Omega
+ALTER TABLE characters ADD COLUMN omega_lapsed_at TEXT;
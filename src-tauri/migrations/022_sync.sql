@@ -0,0 +1,15 @@
+-- Stable cross-device identities for synced entities; row ids differ per install
+ALTER TABLE skill_plans ADD COLUMN sync_id TEXT;
+ALTER TABLE plan_groups ADD COLUMN sync_id TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_skill_plans_sync_id ON skill_plans(sync_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_plan_groups_sync_id ON plan_groups(sync_id);
+
+-- Per-entity state as of the last successful sync. A content hash that no
+-- longer matches marks a local edit; a missing local entity marks a deletion.
+CREATE TABLE IF NOT EXISTS sync_state (
+    entity_key TEXT PRIMARY KEY,
+    content_hash TEXT,
+    modified_at INTEGER NOT NULL,
+    deleted INTEGER NOT NULL DEFAULT 0
+);
@@ -0,0 +1,15 @@
+-- Comment threads on plan entries. Keyed by (skill, level) rather than
+-- entry_id because entries are deleted and re-created on reorder/replace.
+CREATE TABLE IF NOT EXISTS skill_plan_entry_comments (
+    comment_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    plan_id INTEGER NOT NULL,
+    skill_type_id INTEGER NOT NULL,
+    planned_level INTEGER NOT NULL,
+    author TEXT NOT NULL,
+    body TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (plan_id) REFERENCES skill_plans (plan_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_skill_plan_entry_comments_entry
+    ON skill_plan_entry_comments (plan_id, skill_type_id, planned_level);
@@ -0,0 +1,11 @@
+-- Per-character skill farm settings for the rotation advisor. Prices are
+-- app-wide and live in app_settings under 'farm_prices'.
+CREATE TABLE IF NOT EXISTS farm_settings (
+  character_id INTEGER PRIMARY KEY,
+  next_extraction_at INTEGER, -- unix seconds; NULL = not scheduled
+  cycle_days INTEGER NOT NULL DEFAULT 30,
+  wormhole_risk INTEGER NOT NULL DEFAULT 0,
+  nullsec_risk INTEGER NOT NULL DEFAULT 0,
+  updated_at INTEGER NOT NULL,
+  FOREIGN KEY (character_id) REFERENCES characters(character_id) ON DELETE CASCADE
+);
@@ -0,0 +1,22 @@
+-- Last skill queue seen per character, used to spot edits made in game
+-- between refreshes. entries is a JSON array in queue order.
+CREATE TABLE IF NOT EXISTS skill_queue_snapshots (
+  character_id INTEGER PRIMARY KEY,
+  entries TEXT NOT NULL,
+  captured_at INTEGER NOT NULL, -- unix seconds
+  FOREIGN KEY (character_id) REFERENCES characters(character_id) ON DELETE CASCADE
+);
+
+-- Queue changes not explained by training completing.
+CREATE TABLE IF NOT EXISTS skill_queue_journal (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  character_id INTEGER NOT NULL,
+  change_kind TEXT NOT NULL, -- 'added' | 'removed' | 'reordered'
+  skill_id INTEGER, -- NULL for 'reordered'
+  level INTEGER,
+  created_at INTEGER NOT NULL, -- unix seconds
+  FOREIGN KEY (character_id) REFERENCES characters(character_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_skill_queue_journal_character
+  ON skill_queue_journal(character_id, created_at);
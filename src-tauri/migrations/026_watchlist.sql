@@ -0,0 +1,31 @@
+-- Characters the user follows but does not own, kept up to date from public
+-- ESI. Not tied to the characters table.
+CREATE TABLE IF NOT EXISTS watched_characters (
+  character_id INTEGER PRIMARY KEY,
+  character_name TEXT NOT NULL,
+  corporation_id INTEGER NOT NULL,
+  corporation_name TEXT NOT NULL,
+  alliance_id INTEGER,
+  alliance_name TEXT,
+  portrait_url TEXT NOT NULL,
+  added_at INTEGER NOT NULL, -- unix seconds
+  last_checked_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS watched_character_changes (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  character_id INTEGER NOT NULL,
+  old_corporation_id INTEGER NOT NULL,
+  old_corporation_name TEXT NOT NULL,
+  old_alliance_id INTEGER,
+  old_alliance_name TEXT,
+  new_corporation_id INTEGER NOT NULL,
+  new_corporation_name TEXT NOT NULL,
+  new_alliance_id INTEGER,
+  new_alliance_name TEXT,
+  changed_at INTEGER NOT NULL,
+  FOREIGN KEY (character_id) REFERENCES watched_characters(character_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_watched_character_changes_character
+  ON watched_character_changes(character_id, changed_at);
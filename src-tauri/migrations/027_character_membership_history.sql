@@ -0,0 +1,15 @@
+-- Corporation/alliance memberships observed for owned characters, one row
+-- per membership; the newest row is the current one.
+CREATE TABLE IF NOT EXISTS character_membership_history (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  character_id INTEGER NOT NULL,
+  corporation_id INTEGER NOT NULL,
+  corporation_name TEXT NOT NULL,
+  alliance_id INTEGER,
+  alliance_name TEXT,
+  observed_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY (character_id) REFERENCES characters(character_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_character_membership_history_character
+  ON character_membership_history(character_id, id);
@@ -0,0 +1,26 @@
+-- Attribute snapshots recorded whenever a character's attributes, implant
+-- bonuses, accelerator or remap state change. Totals are what ESI reports;
+-- implant_* and accelerator are the contributions detected at the time.
+CREATE TABLE IF NOT EXISTS character_attribute_history (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  character_id INTEGER NOT NULL,
+  charisma INTEGER NOT NULL,
+  intelligence INTEGER NOT NULL,
+  memory INTEGER NOT NULL,
+  perception INTEGER NOT NULL,
+  willpower INTEGER NOT NULL,
+  implant_charisma INTEGER NOT NULL DEFAULT 0,
+  implant_intelligence INTEGER NOT NULL DEFAULT 0,
+  implant_memory INTEGER NOT NULL DEFAULT 0,
+  implant_perception INTEGER NOT NULL DEFAULT 0,
+  implant_willpower INTEGER NOT NULL DEFAULT 0,
+  accelerator INTEGER NOT NULL DEFAULT 0,
+  bonus_remaps INTEGER,
+  last_remap_date TEXT,
+  change_kind TEXT NOT NULL, -- 'initial' | 'remap' | 'bonus_remap' | 'implants' | 'accelerator' | 'other'
+  observed_at INTEGER NOT NULL, -- unix seconds
+  FOREIGN KEY (character_id) REFERENCES characters(character_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_character_attribute_history_character
+  ON character_attribute_history(character_id, id);
@@ -0,0 +1,3 @@
+-- Which subset of the SDE the current build was imported with, so switching
+-- between minimal and full mode re-imports the same build.
+ALTER TABLE sde_metadata ADD COLUMN import_mode TEXT NOT NULL DEFAULT 'full';
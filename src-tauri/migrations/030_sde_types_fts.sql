@@ -0,0 +1,11 @@
+-- Full-text index over type names and descriptions. It reads its content
+-- from sde_types and is rebuilt after every SDE import.
+CREATE VIRTUAL TABLE IF NOT EXISTS sde_types_fts USING fts5(
+  name,
+  description,
+  content = 'sde_types',
+  content_rowid = 'type_id',
+  tokenize = 'unicode61 remove_diacritics 2'
+);
+
+INSERT INTO sde_types_fts (sde_types_fts) VALUES ('rebuild');
@@ -0,0 +1,3 @@
+-- Entries kept in a plan but left out of training time, comparisons and
+-- optimisation, e.g. skills that will be injected rather than trained.
+ALTER TABLE skill_plan_entries ADD COLUMN excluded INTEGER NOT NULL DEFAULT 0;
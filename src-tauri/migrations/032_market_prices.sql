@@ -0,0 +1,11 @@
+-- Latest market prices for the tracked type ids, one row per type. Prices are
+-- ISK and NULL when the region had no orders on that side.
+CREATE TABLE IF NOT EXISTS market_prices (
+  type_id INTEGER PRIMARY KEY,
+  region_id INTEGER NOT NULL,
+  sell_min REAL,
+  buy_max REAL,
+  sell_volume INTEGER NOT NULL DEFAULT 0,
+  buy_volume INTEGER NOT NULL DEFAULT 0,
+  updated_at INTEGER NOT NULL -- unix seconds
+);
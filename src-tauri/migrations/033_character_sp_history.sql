@@ -0,0 +1,12 @@
+-- One row per character per UTC day with its SP at the latest refresh that
+-- day. skills is a JSON object of skill id -> skillpoints_in_skill.
+CREATE TABLE IF NOT EXISTS character_sp_history (
+  character_id INTEGER NOT NULL,
+  day TEXT NOT NULL, -- YYYY-MM-DD, UTC
+  total_sp INTEGER NOT NULL,
+  unallocated_sp INTEGER NOT NULL DEFAULT 0,
+  skills TEXT NOT NULL,
+  recorded_at INTEGER NOT NULL, -- unix seconds
+  PRIMARY KEY (character_id, day),
+  FOREIGN KEY (character_id) REFERENCES characters(character_id) ON DELETE CASCADE
+);
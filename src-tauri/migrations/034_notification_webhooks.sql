@@ -0,0 +1,10 @@
+-- Discord webhooks notifications are also posted to. notification_types is a
+-- JSON array of the types routed to the webhook; NULL routes every type.
+CREATE TABLE IF NOT EXISTS notification_webhooks (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL,
+  url TEXT NOT NULL,
+  enabled INTEGER NOT NULL DEFAULT 1,
+  notification_types TEXT,
+  created_at INTEGER NOT NULL -- unix seconds
+);
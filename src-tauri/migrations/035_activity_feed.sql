@@ -0,0 +1,13 @@
+-- Human-readable log of what background work changed, newest last.
+-- character_id is NULL for app-wide entries such as SDE updates.
+CREATE TABLE IF NOT EXISTS activity_feed (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  character_id INTEGER,
+  kind TEXT NOT NULL, -- 'skills_fetched' | 'queue_changed' | 'sde_updated'
+  message TEXT NOT NULL,
+  created_at INTEGER NOT NULL, -- unix seconds
+  FOREIGN KEY (character_id) REFERENCES characters(character_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_activity_feed_created_at
+  ON activity_feed(created_at);
@@ -0,0 +1,12 @@
+-- Destructive commands the user ran, so an unexpected deletion can be
+-- explained and matched against a backup. No foreign keys: rows must outlive
+-- the characters and plans they describe.
+CREATE TABLE IF NOT EXISTS audit_log (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  command TEXT NOT NULL,
+  params TEXT NOT NULL, -- JSON object of the command's arguments
+  created_at INTEGER NOT NULL -- unix seconds
+);
+
+CREATE INDEX IF NOT EXISTS idx_audit_log_created_at
+  ON audit_log(created_at);
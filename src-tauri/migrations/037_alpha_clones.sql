@@ -0,0 +1,10 @@
+-- NULL: infer Omega/Alpha from ESI on every refresh; 0/1: set by the user,
+-- and kept over whatever the refresher infers.
+ALTER TABLE characters ADD COLUMN omega_override INTEGER;
+
+-- Highest level an Alpha clone can train each skill to, from the SDE's clone
+-- grades. Skills not listed are Omega-only.
+CREATE TABLE IF NOT EXISTS sde_alpha_skills (
+  type_id INTEGER PRIMARY KEY,
+  max_level INTEGER NOT NULL
+);
@@ -0,0 +1,13 @@
+-- Characters expected to train a plan (e.g. a doctrine), so their progress
+-- can be tracked together.
+CREATE TABLE IF NOT EXISTS plan_assignments (
+  plan_id INTEGER NOT NULL,
+  character_id INTEGER NOT NULL,
+  assigned_at INTEGER NOT NULL, -- unix seconds
+  PRIMARY KEY (plan_id, character_id),
+  FOREIGN KEY (plan_id) REFERENCES skill_plans(plan_id) ON DELETE CASCADE,
+  FOREIGN KEY (character_id) REFERENCES characters(character_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_plan_assignments_character
+  ON plan_assignments(character_id);
@@ -0,0 +1,40 @@
+-- Covering indexes so prerequisite lookups in either direction never touch
+-- the table itself.
+DROP INDEX IF EXISTS idx_sde_skill_requirements_required;
+CREATE INDEX IF NOT EXISTS idx_sde_skill_requirements_required
+  ON sde_skill_requirements(required_skill_id, skill_type_id, required_level);
+CREATE INDEX IF NOT EXISTS idx_sde_skill_requirements_skill
+  ON sde_skill_requirements(skill_type_id, required_skill_id, required_level);
+
+-- Published types requiring each skill, with their group and category, for
+-- the "required for" section of the skill details. Core skills are required
+-- by thousands of types, so the joins are done once per SDE import rather
+-- than on every lookup.
+CREATE TABLE IF NOT EXISTS sde_required_for (
+  required_skill_id INTEGER NOT NULL,
+  type_id INTEGER NOT NULL,
+  required_level INTEGER NOT NULL,
+  category_id INTEGER,
+  category_name TEXT,
+  group_id INTEGER NOT NULL,
+  group_name TEXT,
+  PRIMARY KEY (required_skill_id, type_id)
+) WITHOUT ROWID;
+
+INSERT INTO sde_required_for
+  (required_skill_id, type_id, required_level, category_id, category_name, group_id, group_name)
+SELECT
+  sr.required_skill_id,
+  sr.skill_type_id,
+  MAX(sr.required_level),
+  COALESCE(t.category_id, g.category_id),
+  COALESCE(c.name, cg.name),
+  t.group_id,
+  g.name
+FROM sde_skill_requirements sr
+JOIN sde_types t ON sr.skill_type_id = t.type_id
+LEFT JOIN sde_groups g ON t.group_id = g.group_id
+LEFT JOIN sde_categories c ON t.category_id = c.category_id
+LEFT JOIN sde_categories cg ON g.category_id = cg.category_id
+WHERE t.published = 1
+GROUP BY sr.required_skill_id, sr.skill_type_id;
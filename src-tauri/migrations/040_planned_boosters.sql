@@ -0,0 +1,15 @@
+-- Cerebral accelerators planned into a skill plan. Like plan remaps, each one
+-- is anchored after a skill and level of the plan; NULL means the plan start.
+CREATE TABLE IF NOT EXISTS planned_boosters (
+    booster_id INTEGER PRIMARY KEY,
+    plan_id INTEGER NOT NULL,
+    type_id INTEGER, -- the accelerator's SDE type, if it is a known one
+    bonus INTEGER NOT NULL CHECK (bonus > 0),
+    duration_seconds INTEGER NOT NULL CHECK (duration_seconds > 0),
+    after_skill_type_id INTEGER,
+    after_skill_level INTEGER CHECK (after_skill_level BETWEEN 0 AND 5),
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    FOREIGN KEY (plan_id) REFERENCES skill_plans (plan_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_planned_boosters_plan_id ON planned_boosters (plan_id);
@@ -0,0 +1,11 @@
+-- Stages of a multi-stage notification (e.g. skill_queue_low at 72h/24h/4h)
+-- that have fired in the current cycle. A row is removed once the value
+-- rises back above its stage, so the stage can fire again next time.
+CREATE TABLE IF NOT EXISTS notification_stages (
+  character_id INTEGER NOT NULL,
+  notification_type TEXT NOT NULL,
+  stage REAL NOT NULL,
+  fired_at INTEGER NOT NULL, -- unix seconds
+  PRIMARY KEY (character_id, notification_type, stage),
+  FOREIGN KEY (character_id) REFERENCES characters(character_id) ON DELETE CASCADE
+);
@@ -0,0 +1,8 @@
+-- Data modules switched off for a character (see CharacterModule); every
+-- module without a row here is on.
+CREATE TABLE IF NOT EXISTS character_disabled_modules (
+  character_id INTEGER NOT NULL,
+  module TEXT NOT NULL,
+  PRIMARY KEY (character_id, module),
+  FOREIGN KEY (character_id) REFERENCES characters(character_id) ON DELETE CASCADE
+);
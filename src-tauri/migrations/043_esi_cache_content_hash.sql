@@ -0,0 +1,3 @@
+-- SHA-256 of response_body, so refreshes can tell a changed response from a
+-- 304 or an identical 200. NULL for entries written before this column.
+ALTER TABLE esi_cache ADD COLUMN content_hash TEXT;
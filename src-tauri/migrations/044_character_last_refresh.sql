@@ -0,0 +1,5 @@
+-- Unix seconds of the character's last refresh pass where any ESI fetch
+-- succeeded. Existing characters start counting from this migration.
+ALTER TABLE characters ADD COLUMN last_refresh_success_at INTEGER;
+
+UPDATE characters SET last_refresh_success_at = CAST(strftime('%s', 'now') AS INTEGER);
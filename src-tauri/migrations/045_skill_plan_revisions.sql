@@ -0,0 +1,15 @@
+-- Undo history for plan entries. Each row is the plan's entries as they were
+-- just before one change, so reverting to it undoes that change and every
+-- later one.
+CREATE TABLE IF NOT EXISTS skill_plan_revisions (
+    revision_id INTEGER PRIMARY KEY AUTOINCREMENT,
+    plan_id INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    entries TEXT NOT NULL, -- JSON array of entries in plan order
+    entry_count INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (plan_id) REFERENCES skill_plans (plan_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_skill_plan_revisions_plan_id
+    ON skill_plan_revisions (plan_id, revision_id);
@@ -0,0 +1,4 @@
+-- The character a plan is normally trained on. Commands that take an optional
+-- character fall back to it; cleared when the character is removed.
+ALTER TABLE skill_plans ADD COLUMN owner_character_id INTEGER
+  REFERENCES characters(character_id) ON DELETE SET NULL;
@@ -0,0 +1,4 @@
+-- EVEMon-style entry priority, 1 (highest) to 5 (lowest). The optimizer uses
+-- it to order entries that are otherwise equally good to train next.
+ALTER TABLE skill_plan_entries ADD COLUMN priority INTEGER NOT NULL DEFAULT 3
+  CHECK (priority BETWEEN 1 AND 5);
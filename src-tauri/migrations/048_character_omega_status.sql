@@ -0,0 +1,6 @@
+-- is_omega as last confirmed by a refresh or an override; NULL until the
+-- first one, so a character added as Alpha is not mistaken for a lapse.
+-- is_omega itself stays NOT NULL (rebuilding characters would cascade into
+-- every table that references it).
+ALTER TABLE characters ADD COLUMN omega_status BOOLEAN;
+UPDATE characters SET omega_status = is_omega WHERE last_refresh_success_at IS NOT NULL;
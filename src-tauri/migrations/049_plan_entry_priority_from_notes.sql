@@ -0,0 +1,12 @@
+-- EVEMon imports used to keep a non-default priority as a "Priority N" first
+-- line in the entry's notes: the line alone, or followed by a newline and the
+-- EVEMon notes. Move it into the priority column. Only that exact line counts,
+-- never with 3 (the default was not written) and only while the column still
+-- holds the default, so free text such as "Priority 2 after Cybernetics V" is
+-- left alone.
+UPDATE skill_plan_entries
+SET priority = CAST(substr(notes, 10, 1) AS INTEGER),
+    notes = NULLIF(substr(notes, 12), '')
+WHERE priority = 3
+  AND (notes GLOB 'Priority [1245]'
+       OR notes GLOB 'Priority [1245]' || char(10) || '*');
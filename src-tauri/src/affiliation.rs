@@ -0,0 +1,211 @@
+//! Corporation/alliance membership from public ESI endpoints. Needs no token,
+//! so it works for characters the user does not own as well as for owned ones.
+//! Public requests are cached and rate-limited under character id 0, like the
+//! universe lookups in `esi_helpers`.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::ts_types::i64_ts;
+use crate::{db, esi};
+
+const PUBLIC_RATE_LIMIT_ID: i64 = 0;
+
+#[derive(Debug, Deserialize)]
+struct EsiPublicCharacter {
+    name: String,
+    corporation_id: i64,
+    alliance_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EsiCorporation {
+    name: String,
+    alliance_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EsiAlliance {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct EsiResolvedName {
+    id: i64,
+    name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct EsiUniverseIds {
+    #[serde(default)]
+    characters: Vec<EsiResolvedName>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Affiliation {
+    pub corporation_id: i64_ts,
+    pub corporation_name: String,
+    pub alliance_id: Option<i64_ts>,
+    pub alliance_name: Option<String>,
+}
+
+impl Affiliation {
+    /// Human-readable "Corp [Alliance]" label for notifications.
+    pub fn label(&self) -> String {
+        match &self.alliance_name {
+            Some(alliance) => format!("{} [{}]", self.corporation_name, alliance),
+            None => self.corporation_name.clone(),
+        }
+    }
+
+    /// True when corporation or alliance differ; names alone may be renamed.
+    pub fn differs_from(&self, other: &Affiliation) -> bool {
+        self.corporation_id != other.corporation_id || self.alliance_id != other.alliance_id
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PublicCharacter {
+    pub character_id: i64,
+    pub name: String,
+    pub affiliation: Affiliation,
+}
+
+pub fn portrait_url(character_id: i64) -> String {
+    format!(
+        "https://images.evetech.net/characters/{}/portrait?size=128",
+        character_id
+    )
+}
+
+async fn fetch_public<T: serde::de::DeserializeOwned>(
+    pool: &db::Pool,
+    client: &reqwest::Client,
+    endpoint_path: String,
+    rate_limits: &esi::RateLimitStore,
+) -> Result<Option<T>> {
+    let cache_key = format!("{}:0", endpoint_path);
+    esi::fetch_cached(
+        pool,
+        client,
+        &endpoint_path,
+        &cache_key,
+        rate_limits,
+        PUBLIC_RATE_LIMIT_ID,
+    )
+    .await
+}
+
+/// Public info for one character, with corporation and alliance names
+/// resolved. `None` when ESI does not know the character (e.g. biomassed).
+pub async fn fetch_public_character(
+    pool: &db::Pool,
+    client: &reqwest::Client,
+    rate_limits: &esi::RateLimitStore,
+    character_id: i64,
+) -> Result<Option<PublicCharacter>> {
+    let Some(character) = fetch_public::<EsiPublicCharacter>(
+        pool,
+        client,
+        format!("characters/{}", character_id),
+        rate_limits,
+    )
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    let corporation = fetch_public::<EsiCorporation>(
+        pool,
+        client,
+        format!("corporations/{}", character.corporation_id),
+        rate_limits,
+    )
+    .await?;
+
+    // The character endpoint is cached for longer than the corporation one, so
+    // trust the corporation's alliance when both are available.
+    let alliance_id = match &corporation {
+        Some(corporation) => corporation.alliance_id,
+        None => character.alliance_id,
+    };
+    let alliance_name = match alliance_id {
+        Some(alliance_id) => fetch_public::<EsiAlliance>(
+            pool,
+            client,
+            format!("alliances/{}", alliance_id),
+            rate_limits,
+        )
+        .await?
+        .map(|a| a.name),
+        None => None,
+    };
+
+    Ok(Some(PublicCharacter {
+        character_id,
+        name: character.name,
+        affiliation: Affiliation {
+            corporation_id: character.corporation_id,
+            corporation_name: corporation
+                .map(|c| c.name)
+                .unwrap_or_else(|| format!("Corporation {}", character.corporation_id)),
+            alliance_id,
+            alliance_name,
+        },
+    }))
+}
+
+/// Resolves a character id from either a numeric id or an exact name.
+pub async fn resolve_character_id(
+    client: &reqwest::Client,
+    rate_limits: &esi::RateLimitStore,
+    name_or_id: &str,
+) -> Result<Option<i64>> {
+    let name_or_id = name_or_id.trim();
+    if let Ok(id) = name_or_id.parse::<i64>() {
+        return Ok(Some(id));
+    }
+
+    let resolved: EsiUniverseIds = esi::post_json(
+        client,
+        "universe/ids",
+        &[name_or_id],
+        rate_limits,
+        PUBLIC_RATE_LIMIT_ID,
+    )
+    .await?;
+    Ok(resolved
+        .characters
+        .into_iter()
+        .find(|c| c.name.eq_ignore_ascii_case(name_or_id))
+        .map(|c| c.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn affiliation(corporation_id: i64, alliance_id: Option<i64>) -> Affiliation {
+        Affiliation {
+            corporation_id,
+            corporation_name: "Corp".into(),
+            alliance_id,
+            alliance_name: alliance_id.map(|_| "Alliance".into()),
+        }
+    }
+
+    #[test]
+    fn renames_are_not_membership_changes() {
+        let before = affiliation(1, Some(10));
+        let renamed = Affiliation {
+            corporation_name: "New Corp Name".into(),
+            ..before.clone()
+        };
+        assert!(!renamed.differs_from(&before));
+        assert!(affiliation(2, Some(10)).differs_from(&before));
+        assert!(affiliation(1, None).differs_from(&before));
+        assert_eq!(before.label(), "Corp [Alliance]");
+    }
+}
@@ -0,0 +1,93 @@
+//! The app's log file. Records sent through the `log` facade are written to
+//! `skillmon.log` in the app log directory, started fresh on each launch.
+//! Debug records, such as the optimizer's timings, are only written while the
+//! `debug_logging` setting is on.
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::SecondsFormat;
+use log::{LevelFilter, Log, Metadata, Record};
+
+const LOG_FILE_NAME: &str = "skillmon.log";
+pub const DEBUG_LOGGING_SETTING: &str = "debug_logging";
+
+struct FileLogger {
+    file: Mutex<File>,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!(
+            "{} {:<5} {}: {}\n",
+            chrono::Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.write_all(line.as_bytes());
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.flush();
+        }
+    }
+}
+
+/// Installs the file logger. Called once at startup.
+pub fn init(log_dir: &Path) -> Result<()> {
+    fs::create_dir_all(log_dir)
+        .with_context(|| format!("Failed to create log directory {}", log_dir.display()))?;
+    let path = log_dir.join(LOG_FILE_NAME);
+    let file = File::create(&path)
+        .with_context(|| format!("Failed to create log file {}", path.display()))?;
+    let logger: &'static FileLogger = Box::leak(Box::new(FileLogger {
+        file: Mutex::new(file),
+    }));
+    log::set_logger(logger).map_err(|_| anyhow!("A logger is already installed"))?;
+    set_debug(false);
+    Ok(())
+}
+
+/// Turns debug records on or off from now on.
+pub fn set_debug(enabled: bool) {
+    log::set_max_level(if enabled {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    });
+}
+
+/// Logs how long a step took, at debug level, when dropped.
+pub struct Span {
+    name: &'static str,
+    started: Instant,
+}
+
+pub fn span(name: &'static str) -> Span {
+    log::debug!("{}: started", name);
+    Span {
+        name,
+        started: Instant::now(),
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        log::debug!("{}: finished in {:.1?}", self.name, self.started.elapsed());
+    }
+}
@@ -1,7 +1,6 @@
 use axum::{extract::Query, http::StatusCode, response::Html, routing::get, Router};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tauri::Emitter;
 use tower::ServiceBuilder;
 use tower_http::cors::CorsLayer;
 
@@ -38,7 +37,7 @@ impl CallbackServer {
                                 {
                                     Ok(_) => {}
                                     Err(e) => {
-                                        let _ = app_handle_inner.emit("auth-error", e.to_string());
+                                        let _ = crate::events::emit_auth_error(&app_handle_inner, &e.to_string());
                                     }
                                 }
                             });
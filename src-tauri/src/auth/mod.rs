@@ -1,6 +1,8 @@
 pub mod callback_server;
 pub mod oauth;
 pub mod pkce;
+pub mod reauth;
+pub mod token_store;
 pub mod types;
 
 pub use oauth::{
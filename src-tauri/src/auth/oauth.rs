@@ -5,6 +5,7 @@ use serde_json::Value;
 use std::collections::HashSet;
 
 use super::pkce::generate_pkce_pair;
+use super::token_store;
 use super::types::{CharacterInfo, TokenResponse};
 use crate::db::{self, Pool};
 use crate::esi::EsiScope;
@@ -17,6 +18,8 @@ const EVE_SSO_AUTHORIZE_URL: &str = "https://login.eveonline.com/v2/oauth/author
 pub struct AuthState {
     pub code_verifier: String,
     pub state: String,
+    /// Set for a re-login, which must come back as the same character.
+    pub character_id: Option<i64>,
 }
 
 pub fn generate_auth_url(
@@ -48,6 +51,7 @@ pub fn generate_auth_url(
         AuthState {
             code_verifier: pkce.code_verifier,
             state,
+            character_id: None,
         },
     )
 }
@@ -118,7 +122,7 @@ pub async fn refresh_access_token(client_id: &str, refresh_token: &str) -> Resul
 }
 
 pub async fn ensure_valid_access_token(pool: &Pool, character_id: i64) -> Result<String> {
-    let tokens = db::get_tokens(pool, character_id)
+    let tokens = token_store::get_tokens(pool, character_id)
         .await
         .context("Failed to retrieve tokens from database")?;
 
@@ -140,7 +144,7 @@ pub async fn ensure_valid_access_token(pool: &Pool, character_id: i64) -> Result
         let scopes =
             extract_scopes_from_jwt(&token_response.access_token).unwrap_or_else(|_| Vec::new());
 
-        db::update_tokens(
+        token_store::update_tokens(
             pool,
             character_id,
             &token_response.access_token,
@@ -257,7 +261,6 @@ pub fn extract_character_from_jwt(access_token: &str) -> Result<CharacterInfo> {
 /// Check if a token has the required scopes.
 /// Returns a list of missing scopes, or empty vector if all required scopes are present.
 /// Logs missing scopes for graceful degradation.
-#[allow(dead_code)]
 pub async fn check_token_scopes(
     pool: &Pool,
     character_id: i64,
@@ -0,0 +1,147 @@
+//! Characters whose token predates a feature enabled since they logged in,
+//! and the scopes a fresh login for them should ask for.
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+use serde::Serialize;
+use typeshare::typeshare;
+
+use crate::db::{self, Pool};
+use crate::esi::{self, EsiScope};
+use crate::features::{self, FeatureId};
+use crate::ts_types::i64_ts;
+
+/// Payload of the reauth-required event: enabled features the character's
+/// token cannot serve, and the scopes they lack.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReauthRequired {
+    pub character_id: i64_ts,
+    pub features: Vec<FeatureId>,
+    pub missing_scopes: Vec<String>,
+}
+
+fn granted_scopes(scopes_json: Option<&str>) -> Vec<String> {
+    scopes_json
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default()
+}
+
+fn push_unique(scopes: &mut Vec<EsiScope>, scope: EsiScope) {
+    if !scopes.contains(&scope) {
+        scopes.push(scope);
+    }
+}
+
+/// Base scopes plus those of every enabled optional feature.
+pub async fn requested_scopes(pool: &Pool) -> Result<Vec<EsiScope>> {
+    let mut scopes = esi::BASE_SCOPES.to_vec();
+    let enabled_features = db::get_enabled_features(pool).await?;
+    for feature in features::get_optional_features() {
+        if enabled_features.contains(&feature.id) {
+            for scope in feature.scopes {
+                push_unique(&mut scopes, scope);
+            }
+        }
+    }
+    Ok(scopes)
+}
+
+/// `None` when the token covers every enabled feature (or there is no token).
+pub async fn reauth_required(pool: &Pool, character_id: i64) -> Result<Option<ReauthRequired>> {
+    let Some(tokens) = db::get_tokens(pool, character_id).await? else {
+        return Ok(None);
+    };
+    let granted: HashSet<String> = granted_scopes(tokens.scopes.as_deref())
+        .into_iter()
+        .collect();
+    let enabled_features = db::get_enabled_features(pool).await?;
+
+    let mut required = ReauthRequired {
+        character_id,
+        features: Vec::new(),
+        missing_scopes: Vec::new(),
+    };
+    for feature in features::get_optional_features() {
+        if !enabled_features.contains(&feature.id) {
+            continue;
+        }
+        let missing: Vec<&str> = feature
+            .scopes
+            .iter()
+            .map(|s| s.as_str())
+            .filter(|s| !granted.contains(*s))
+            .collect();
+        if missing.is_empty() {
+            continue;
+        }
+        required.features.push(feature.id);
+        for scope in missing {
+            if !required.missing_scopes.iter().any(|s| s == scope) {
+                required.missing_scopes.push(scope.to_string());
+            }
+        }
+    }
+
+    Ok((!required.features.is_empty()).then_some(required))
+}
+
+/// What a re-login for the character should request: everything the app
+/// needs now, everything the old token had, and `additional`, so logging in
+/// again never narrows the token.
+pub async fn reauthorize_scopes(
+    pool: &Pool,
+    character_id: i64,
+    additional: &[EsiScope],
+) -> Result<Vec<EsiScope>> {
+    let mut scopes = requested_scopes(pool).await?;
+    if let Some(tokens) = db::get_tokens(pool, character_id).await? {
+        for scope in granted_scopes(tokens.scopes.as_deref()) {
+            if let Ok(scope) = serde_plain::from_str::<EsiScope>(&scope) {
+                push_unique(&mut scopes, scope);
+            }
+        }
+    }
+    for scope in additional {
+        push_unique(&mut scopes, *scope);
+    }
+    Ok(scopes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    #[tokio::test]
+    async fn reports_features_the_token_predates_and_keeps_granted_scopes() {
+        let db = TestDb::new().await.unwrap();
+        db::add_character(&db.pool, 1, "Veteran").await.unwrap();
+        let granted = vec![
+            EsiScope::ReadSkillsV1.as_str().to_string(),
+            EsiScope::ReadMailV1.as_str().to_string(),
+        ];
+        db::set_tokens(&db.pool, 1, "access", "refresh", i64::MAX, Some(&granted))
+            .await
+            .unwrap();
+        assert_eq!(reauth_required(&db.pool, 1).await.unwrap(), None);
+
+        db::set_feature_enabled(&db.pool, FeatureId::Fittings, true)
+            .await
+            .unwrap();
+        let required = reauth_required(&db.pool, 1).await.unwrap().unwrap();
+        assert_eq!(required.features, vec![FeatureId::Fittings]);
+        assert!(required
+            .missing_scopes
+            .contains(&EsiScope::ReadFittingsV1.as_str().to_string()));
+
+        let scopes = reauthorize_scopes(&db.pool, 1, &[EsiScope::OpenWindowV1])
+            .await
+            .unwrap();
+        assert!(scopes.contains(&EsiScope::ReadFittingsV1));
+        assert!(scopes.contains(&EsiScope::ReadMailV1));
+        assert!(scopes.contains(&EsiScope::OpenWindowV1));
+        assert!(scopes.contains(&EsiScope::ReadSkillqueueV1));
+    }
+}
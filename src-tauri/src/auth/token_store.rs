@@ -0,0 +1,489 @@
+//! Where access and refresh tokens live. The `tokens` row keeps expiry and
+//! scopes; the secrets go to the OS keychain, or, where there is none, into
+//! the row as AES-256-GCM blobs under a key file beside `storage.json`.
+//!
+//! Rows written before this existed hold plaintext tokens; they are moved
+//! into the store at startup and whenever one is read.
+
+use std::fs;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, bail, Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::db::tokens::Tokens;
+use crate::db::{self, Pool};
+
+const KEYRING_SERVICE: &str = "skillmon";
+const INSTALL_ID_FILE_NAME: &str = "install.id";
+/// Stands in for both columns while the keychain holds the secrets.
+const KEYRING_MARKER: &str = "keyring:";
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+const NONCE_LEN: usize = 12;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenSecrets {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+/// What the `tokens` row holds in place of the secrets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SealedTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+pub trait TokenStore: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// Whether this store wrote `sealed`.
+    fn owns(&self, sealed: &SealedTokens) -> bool;
+    fn seal(&self, character_id: i64, secrets: &TokenSecrets) -> Result<SealedTokens>;
+    fn unseal(&self, character_id: i64, sealed: &SealedTokens) -> Result<TokenSecrets>;
+    fn forget(&self, character_id: i64) -> Result<()>;
+}
+
+/// Keychain entries are shared by every install under the OS user, so a
+/// portable install namespaces its own with an id kept in its data directory.
+/// The regular install keeps the original entry names.
+#[derive(Default)]
+pub struct KeyringStore {
+    namespace: Option<String>,
+}
+
+impl KeyringStore {
+    /// Namespaced by the id in `data_dir`, creating it on first use.
+    pub fn for_data_dir(data_dir: &Path) -> Result<Self> {
+        let path = data_dir.join(INSTALL_ID_FILE_NAME);
+        if let Ok(id) = fs::read_to_string(&path) {
+            if !id.trim().is_empty() {
+                return Ok(Self {
+                    namespace: Some(id.trim().to_string()),
+                });
+            }
+        }
+
+        let mut bytes = [0u8; 16];
+        rand::rng().fill_bytes(&mut bytes);
+        let id: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        fs::create_dir_all(data_dir)?;
+        fs::write(&path, &id)
+            .with_context(|| format!("Failed to write install id {}", path.display()))?;
+        Ok(Self {
+            namespace: Some(id),
+        })
+    }
+
+    fn account(&self, character_id: i64) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{}/character-{}", namespace, character_id),
+            None => format!("character-{}", character_id),
+        }
+    }
+
+    fn entry(&self, character_id: i64) -> Result<keyring::Entry> {
+        Ok(keyring::Entry::new(
+            KEYRING_SERVICE,
+            &self.account(character_id),
+        )?)
+    }
+
+    /// Round-trips a throwaway entry through separate handles, so a keychain
+    /// that accepts writes but does not persist them is not mistaken for one.
+    pub fn probe() -> bool {
+        let check = || -> Result<bool> {
+            keyring::Entry::new(KEYRING_SERVICE, "probe")?.set_password("probe")?;
+            let entry = keyring::Entry::new(KEYRING_SERVICE, "probe")?;
+            let found = entry.get_password()? == "probe";
+            entry.delete_credential()?;
+            Ok(found)
+        };
+        check().unwrap_or(false)
+    }
+}
+
+impl TokenStore for KeyringStore {
+    fn name(&self) -> &'static str {
+        "keyring"
+    }
+
+    fn owns(&self, sealed: &SealedTokens) -> bool {
+        sealed.access_token == KEYRING_MARKER
+    }
+
+    fn seal(&self, character_id: i64, secrets: &TokenSecrets) -> Result<SealedTokens> {
+        self.entry(character_id)?
+            .set_password(&serde_json::to_string(secrets)?)?;
+        Ok(SealedTokens {
+            access_token: KEYRING_MARKER.to_string(),
+            refresh_token: KEYRING_MARKER.to_string(),
+        })
+    }
+
+    fn unseal(&self, character_id: i64, _sealed: &SealedTokens) -> Result<TokenSecrets> {
+        let json = self
+            .entry(character_id)?
+            .get_password()
+            .context("Failed to read tokens from the OS keychain")?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    fn forget(&self, character_id: i64) -> Result<()> {
+        match self.entry(character_id)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Fallback when there is no usable keychain: each secret is encrypted into
+/// its own column as `enc:v1:` + base64(nonce || ciphertext).
+pub struct EncryptedStore {
+    key: [u8; 32],
+}
+
+impl EncryptedStore {
+    pub fn new(key: [u8; 32]) -> Self {
+        Self { key }
+    }
+
+    /// Reads the key file, creating it with a fresh random key on first use.
+    pub fn from_key_file(path: &Path) -> Result<Self> {
+        if let Ok(bytes) = fs::read(path) {
+            let key: [u8; 32] = bytes
+                .try_into()
+                .map_err(|_| anyhow!("Token key file {} is corrupt", path.display()))?;
+            return Ok(Self::new(key));
+        }
+
+        let mut key = [0u8; 32];
+        rand::rng().fill_bytes(&mut key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, key)
+            .with_context(|| format!("Failed to write token key file {}", path.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(Self::new(key))
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.key))
+    }
+
+    fn encrypt(&self, secret: &str) -> Result<String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::rng().fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher()
+            .encrypt(Nonce::from_slice(&nonce), secret.as_bytes())
+            .map_err(|_| anyhow!("Token encryption failed"))?;
+
+        let mut blob = nonce.to_vec();
+        blob.extend_from_slice(&ciphertext);
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, STANDARD.encode(blob)))
+    }
+
+    fn decrypt(&self, sealed: &str) -> Result<String> {
+        let Some(encoded) = sealed.strip_prefix(ENCRYPTED_PREFIX) else {
+            bail!("Token is not encrypted");
+        };
+        let blob = STANDARD.decode(encoded)?;
+        if blob.len() < NONCE_LEN {
+            bail!("Encrypted token is truncated");
+        }
+        let plaintext = self
+            .cipher()
+            .decrypt(Nonce::from_slice(&blob[..NONCE_LEN]), &blob[NONCE_LEN..])
+            .map_err(|_| anyhow!("Failed to decrypt token (key file replaced?)"))?;
+        Ok(String::from_utf8(plaintext)?)
+    }
+}
+
+impl TokenStore for EncryptedStore {
+    fn name(&self) -> &'static str {
+        "encrypted"
+    }
+
+    fn owns(&self, sealed: &SealedTokens) -> bool {
+        sealed.access_token.starts_with(ENCRYPTED_PREFIX)
+    }
+
+    fn seal(&self, _character_id: i64, secrets: &TokenSecrets) -> Result<SealedTokens> {
+        Ok(SealedTokens {
+            access_token: self.encrypt(&secrets.access_token)?,
+            refresh_token: self.encrypt(&secrets.refresh_token)?,
+        })
+    }
+
+    fn unseal(&self, _character_id: i64, sealed: &SealedTokens) -> Result<TokenSecrets> {
+        Ok(TokenSecrets {
+            access_token: self.decrypt(&sealed.access_token)?,
+            refresh_token: self.decrypt(&sealed.refresh_token)?,
+        })
+    }
+
+    fn forget(&self, _character_id: i64) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The store new tokens are written to, plus every store that may still own
+/// existing rows (the keychain can disappear, e.g. a headless session).
+struct Stores {
+    active: Box<dyn TokenStore>,
+    others: Vec<Box<dyn TokenStore>>,
+}
+
+impl Stores {
+    fn all(&self) -> impl Iterator<Item = &dyn TokenStore> {
+        std::iter::once(self.active.as_ref()).chain(self.others.iter().map(|s| s.as_ref()))
+    }
+
+    fn owner(&self, sealed: &SealedTokens) -> Option<&dyn TokenStore> {
+        self.all().find(|s| s.owns(sealed))
+    }
+}
+
+static STORES: OnceLock<Stores> = OnceLock::new();
+
+/// Picks `keyring` when the keychain works, else the encrypted fallback keyed
+/// by `key_path`. Called once at startup, before anything reads tokens.
+pub fn init(key_path: &Path, keyring: KeyringStore) -> Result<&'static str> {
+    let encrypted = EncryptedStore::from_key_file(key_path)?;
+    let stores = if KeyringStore::probe() {
+        Stores {
+            active: Box::new(keyring),
+            others: vec![Box::new(encrypted)],
+        }
+    } else {
+        Stores {
+            active: Box::new(encrypted),
+            others: vec![Box::new(keyring)],
+        }
+    };
+    let name = stores.active.name();
+    if STORES.set(stores).is_err() {
+        bail!("Token store is already initialized");
+    }
+    Ok(name)
+}
+
+/// Without [`init`] (tests) tokens are encrypted under a per-process key.
+fn stores() -> &'static Stores {
+    STORES.get_or_init(|| {
+        let mut key = [0u8; 32];
+        rand::rng().fill_bytes(&mut key);
+        Stores {
+            active: Box::new(EncryptedStore::new(key)),
+            others: Vec::new(),
+        }
+    })
+}
+
+/// Keychain calls can block on IPC, so they run off the async workers.
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce(&'static Stores) -> Result<T> + Send + 'static,
+) -> Result<T> {
+    tokio::task::spawn_blocking(move || f(stores())).await?
+}
+
+async fn seal(character_id: i64, access_token: &str, refresh_token: &str) -> Result<SealedTokens> {
+    let secrets = TokenSecrets {
+        access_token: access_token.to_string(),
+        refresh_token: refresh_token.to_string(),
+    };
+    blocking(move |stores| stores.active.seal(character_id, &secrets)).await
+}
+
+/// Moves one plaintext row into the active store; `false` if already sealed.
+async fn migrate_row(pool: &Pool, row: &Tokens) -> Result<bool> {
+    let sealed = SealedTokens {
+        access_token: row.access_token.clone(),
+        refresh_token: row.refresh_token.clone(),
+    };
+    if stores().owner(&sealed).is_some() {
+        return Ok(false);
+    }
+    let sealed = seal(row.character_id, &row.access_token, &row.refresh_token).await?;
+    sqlx::query(
+        "UPDATE tokens SET access_token = ?, refresh_token = ?
+         WHERE character_id = ? AND access_token = ?",
+    )
+    .bind(&sealed.access_token)
+    .bind(&sealed.refresh_token)
+    .bind(row.character_id)
+    .bind(&row.access_token)
+    .execute(pool)
+    .await?;
+    Ok(true)
+}
+
+/// Seals every row still holding plaintext tokens.
+pub async fn migrate_plaintext_tokens(pool: &Pool) -> Result<usize> {
+    let rows = sqlx::query_as::<_, Tokens>(
+        "SELECT character_id, access_token, refresh_token, expires_at, scopes FROM tokens",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut migrated = 0;
+    for row in &rows {
+        if migrate_row(pool, row).await? {
+            migrated += 1;
+        }
+    }
+    Ok(migrated)
+}
+
+/// Like [`db::get_tokens`], with the secrets unsealed.
+pub async fn get_tokens(pool: &Pool, character_id: i64) -> Result<Option<Tokens>> {
+    let Some(mut tokens) = db::get_tokens(pool, character_id).await? else {
+        return Ok(None);
+    };
+
+    let sealed = SealedTokens {
+        access_token: tokens.access_token.clone(),
+        refresh_token: tokens.refresh_token.clone(),
+    };
+    if stores().owner(&sealed).is_none() {
+        migrate_row(pool, &tokens).await?;
+        return Ok(Some(tokens));
+    }
+
+    let secrets = blocking(move |stores| {
+        let owner = stores
+            .owner(&sealed)
+            .ok_or_else(|| anyhow!("No token store owns these tokens"))?;
+        owner.unseal(character_id, &sealed)
+    })
+    .await?;
+    tokens.access_token = secrets.access_token;
+    tokens.refresh_token = secrets.refresh_token;
+    Ok(Some(tokens))
+}
+
+pub async fn set_tokens(
+    pool: &Pool,
+    character_id: i64,
+    access_token: &str,
+    refresh_token: &str,
+    expires_at: i64,
+    scopes: Option<&[String]>,
+) -> Result<()> {
+    let sealed = seal(character_id, access_token, refresh_token).await?;
+    db::set_tokens(
+        pool,
+        character_id,
+        &sealed.access_token,
+        &sealed.refresh_token,
+        expires_at,
+        scopes,
+    )
+    .await
+}
+
+pub async fn update_tokens(
+    pool: &Pool,
+    character_id: i64,
+    access_token: &str,
+    refresh_token: &str,
+    expires_at: i64,
+    scopes: Option<&[String]>,
+) -> Result<()> {
+    let sealed = seal(character_id, access_token, refresh_token).await?;
+    db::update_tokens(
+        pool,
+        character_id,
+        &sealed.access_token,
+        &sealed.refresh_token,
+        expires_at,
+        scopes,
+    )
+    .await
+}
+
+/// Removes the row and anything a store holds for the character. A store
+/// that cannot be reached is logged rather than blocking the removal.
+pub async fn delete_tokens(pool: &Pool, character_id: i64) -> Result<()> {
+    blocking(move |stores| {
+        for store in stores.all() {
+            if let Err(e) = store.forget(character_id) {
+                eprintln!("Failed to remove {} tokens: {}", store.name(), e);
+            }
+        }
+        Ok(())
+    })
+    .await?;
+
+    sqlx::query("DELETE FROM tokens WHERE character_id = ?")
+        .bind(character_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    #[test]
+    fn encrypted_store_round_trips_and_rejects_other_keys() {
+        let store = EncryptedStore::new([7; 32]);
+        let secrets = TokenSecrets {
+            access_token: "eyJ.access".to_string(),
+            refresh_token: "refresh".to_string(),
+        };
+
+        let sealed = store.seal(1, &secrets).unwrap();
+        assert!(store.owns(&sealed));
+        assert!(!sealed.access_token.contains("eyJ.access"));
+        assert_eq!(store.unseal(1, &sealed).unwrap(), secrets);
+        assert!(EncryptedStore::new([8; 32]).unseal(1, &sealed).is_err());
+    }
+
+    #[test]
+    fn keyring_entries_are_namespaced_per_data_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let first = KeyringStore::for_data_dir(&tmp.path().join("a")).unwrap();
+        let second = KeyringStore::for_data_dir(&tmp.path().join("b")).unwrap();
+
+        assert_ne!(first.account(1), second.account(1));
+        assert_ne!(first.account(1), KeyringStore::default().account(1));
+        assert_eq!(KeyringStore::default().account(1), "character-1");
+        // The id is kept, so a restart finds the same entries.
+        let reopened = KeyringStore::for_data_dir(&tmp.path().join("a")).unwrap();
+        assert_eq!(reopened.account(1), first.account(1));
+    }
+
+    #[tokio::test]
+    async fn plaintext_rows_are_sealed_on_read() {
+        let db = TestDb::new().await.unwrap();
+        db::add_character(&db.pool, 1, "Legacy").await.unwrap();
+        db::set_tokens(&db.pool, 1, "access", "refresh", i64::MAX, None)
+            .await
+            .unwrap();
+
+        let tokens = get_tokens(&db.pool, 1).await.unwrap().unwrap();
+        assert_eq!(tokens.access_token, "access");
+        assert_eq!(tokens.refresh_token, "refresh");
+
+        let raw = db::get_tokens(&db.pool, 1).await.unwrap().unwrap();
+        assert_ne!(raw.access_token, "access");
+        assert_ne!(raw.refresh_token, "refresh");
+        let tokens = get_tokens(&db.pool, 1).await.unwrap().unwrap();
+        assert_eq!(tokens.refresh_token, "refresh");
+        assert_eq!(migrate_plaintext_tokens(&db.pool).await.unwrap(), 0);
+    }
+}
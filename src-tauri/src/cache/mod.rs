@@ -33,6 +33,25 @@ pub async fn get_cached_response(pool: &Pool, cache_key: &str) -> Result<Option<
     Ok(entry)
 }
 
+/// SHA-256 of a response body, hex encoded.
+pub fn content_hash(response_body: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(response_body.as_bytes());
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Hash of the body currently cached under `cache_key`. It only moves when a
+/// 200 brings a different body; 304s and identical responses leave it as is.
+pub async fn get_content_hash(pool: &Pool, cache_key: &str) -> Result<Option<String>> {
+    let hash: Option<Option<String>> =
+        sqlx::query_scalar("SELECT content_hash FROM esi_cache WHERE cache_key = ?")
+            .bind(cache_key)
+            .fetch_optional(pool)
+            .await?;
+
+    Ok(hash.flatten())
+}
+
 pub async fn set_cached_response(
     pool: &Pool,
     cache_key: &str,
@@ -41,12 +60,14 @@ pub async fn set_cached_response(
     response_body: &str,
 ) -> Result<()> {
     sqlx::query(
-        "INSERT OR REPLACE INTO esi_cache (cache_key, etag, expires_at, response_body) VALUES (?, ?, ?, ?)",
+        "INSERT OR REPLACE INTO esi_cache (cache_key, etag, expires_at, response_body, content_hash)
+         VALUES (?, ?, ?, ?, ?)",
     )
     .bind(cache_key)
     .bind(etag)
     .bind(expires_at)
     .bind(response_body)
+    .bind(content_hash(response_body))
     .execute(pool)
     .await?;
 
@@ -63,11 +84,45 @@ pub async fn update_cache_expiration(pool: &Pool, cache_key: &str, expires_at: i
     Ok(())
 }
 
+/// Bumps an expired entry after a 304 Not Modified, taking the new ETag if
+/// the response carried one.
+pub async fn refresh_cache_entry(
+    pool: &Pool,
+    cache_key: &str,
+    etag: Option<&str>,
+    expires_at: i64,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE esi_cache SET etag = COALESCE(?, etag), expires_at = ? WHERE cache_key = ?",
+    )
+    .bind(etag)
+    .bind(expires_at)
+    .bind(cache_key)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// The ETag exactly as sent, quotes and weak prefix included, so it can be
+/// echoed back in `If-None-Match`.
 pub fn extract_etag(headers: &HeaderMap) -> Option<String> {
     headers
         .get("etag")
         .and_then(|v| v.to_str().ok())
-        .map(|s| s.trim_matches('"').to_string())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// `If-None-Match` value for a stored ETag. Entries cached before ETags were
+/// kept verbatim have their quotes stripped; a bare tag never matches, so
+/// those are quoted again.
+pub fn if_none_match(etag: &str) -> String {
+    if etag.starts_with('"') || etag.starts_with("W/") {
+        etag.to_string()
+    } else {
+        format!("\"{}\"", etag)
+    }
 }
 
 pub fn extract_expires(headers: &HeaderMap) -> i64 {
@@ -107,3 +162,49 @@ pub async fn clear_character_cache(pool: &Pool, character_id: i64) -> Result<()>
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn etags_round_trip_into_if_none_match() {
+        let mut headers = HeaderMap::new();
+        headers.insert("etag", HeaderValue::from_static("W/\"abc123\""));
+        let etag = extract_etag(&headers).unwrap();
+        assert_eq!(etag, "W/\"abc123\"");
+        assert_eq!(if_none_match(&etag), "W/\"abc123\"");
+
+        // Stored before ETags were kept verbatim.
+        assert_eq!(if_none_match("abc123"), "\"abc123\"");
+    }
+
+    #[tokio::test]
+    async fn content_hash_only_moves_when_the_body_changes() {
+        let db = TestDb::new().await.unwrap();
+        let key = build_cache_key("characters/1/skills", 1);
+        assert_eq!(get_content_hash(&db.pool, &key).await.unwrap(), None);
+
+        set_cached_response(&db.pool, &key, Some("\"a\""), 100, "{\"total_sp\":1}")
+            .await
+            .unwrap();
+        let first = get_content_hash(&db.pool, &key).await.unwrap();
+        assert!(first.is_some());
+
+        // A 304 bumps the entry but keeps the body.
+        refresh_cache_entry(&db.pool, &key, Some("\"b\""), 200)
+            .await
+            .unwrap();
+        set_cached_response(&db.pool, &key, Some("\"c\""), 300, "{\"total_sp\":1}")
+            .await
+            .unwrap();
+        assert_eq!(get_content_hash(&db.pool, &key).await.unwrap(), first);
+
+        set_cached_response(&db.pool, &key, Some("\"d\""), 400, "{\"total_sp\":2}")
+            .await
+            .unwrap();
+        assert_ne!(get_content_hash(&db.pool, &key).await.unwrap(), first);
+    }
+}
@@ -1,12 +1,32 @@
-use serde::Serialize;
-use tauri::State;
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
 use typeshare::typeshare;
 
-use crate::db;
+use crate::esi::schema::SkillQueueEntry;
+use crate::queue_math::{self, QueueItem};
+use crate::response_cache::ResponseCache;
 use crate::ts_types::i64_ts;
+use crate::{cache, db, events, utils};
 
+/// What a character is training, from the cached skill queue.
 #[typeshare]
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CharacterTrainingSummary {
+    pub skill_id: Option<i64_ts>,
+    pub skill_name: Option<String>,
+    pub finished_level: Option<i64_ts>,
+    /// RFC 3339 finish of the skill in training.
+    pub finish_date: Option<String>,
+    /// Hours left in the whole queue; `None` when paused.
+    pub queue_hours: Option<f64>,
+    pub is_paused: bool,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Character {
     pub character_id: i64_ts,
     pub character_name: String,
@@ -14,6 +34,8 @@ pub struct Character {
     pub account_id: Option<i64_ts>,
     pub sort_order: i64_ts,
     pub is_omega: bool,
+    /// Only filled when requested with `include_training`.
+    pub training: Option<CharacterTrainingSummary>,
 }
 
 impl From<db::Character> for Character {
@@ -25,12 +47,13 @@ impl From<db::Character> for Character {
             account_id: c.account_id,
             sort_order: c.sort_order,
             is_omega: c.is_omega,
+            training: None,
         }
     }
 }
 
 #[typeshare]
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountWithCharacters {
     pub id: i64_ts,
     pub name: String,
@@ -39,24 +62,132 @@ pub struct AccountWithCharacters {
 }
 
 #[typeshare]
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountsAndCharactersResponse {
     pub accounts: Vec<AccountWithCharacters>,
     pub unassigned_characters: Vec<Character>,
+    /// Served from the response cache; a fresh copy follows as
+    /// `accounts:updated`.
+    #[serde(default)]
+    pub is_cached: bool,
+}
+
+/// Payload of `accounts:updated`.
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountsUpdated {
+    pub include_training: bool,
+    pub response: AccountsAndCharactersResponse,
+}
+
+fn accounts_cache_key(include_training: bool) -> String {
+    format!("accounts_and_characters:{}", include_training)
+}
+
+pub fn training_summary(queue: &[SkillQueueEntry], now: DateTime<Utc>) -> CharacterTrainingSummary {
+    let training = queue_math::currently_training(queue, now);
+    CharacterTrainingSummary {
+        skill_id: training.map(|item| item.skill_id),
+        skill_name: None,
+        finished_level: training.map(|item| item.finished_level),
+        finish_date: training
+            .and_then(|item| item.finish_date())
+            .map(|finish| finish.to_rfc3339()),
+        queue_hours: queue_math::remaining_training(queue, now)
+            .map(|left| left.num_seconds() as f64 / 3600.0),
+        is_paused: queue_math::is_paused(queue),
+    }
+}
+
+async fn load_training_summary(
+    pool: &db::Pool,
+    character_id: i64,
+    now: DateTime<Utc>,
+) -> anyhow::Result<Option<CharacterTrainingSummary>> {
+    let endpoint_path = format!("characters/{}/skillqueue", character_id);
+    let cache_key = cache::build_cache_key(&endpoint_path, character_id);
+    let Some(entry) = cache::get_cached_response(pool, &cache_key).await? else {
+        return Ok(None);
+    };
+    let queue: Vec<SkillQueueEntry> = serde_json::from_str(&entry.response_body)?;
+    Ok(Some(training_summary(&queue, now)))
 }
 
+/// Adds each character's training summary, read from cached queues only.
+async fn embed_training(pool: &db::Pool, characters: &mut [&mut Character]) -> Result<(), String> {
+    let now = Utc::now();
+    for character in characters.iter_mut() {
+        character.training = load_training_summary(pool, character.character_id, now)
+            .await
+            .map_err(|e| format!("Failed to read skill queue: {}", e))?;
+    }
+
+    let skill_ids: Vec<i64> = characters
+        .iter()
+        .filter_map(|c| c.training.as_ref()?.skill_id)
+        .collect();
+    let names: HashMap<i64, String> = utils::get_type_names(pool, &skill_ids).await?;
+    for training in characters.iter_mut().filter_map(|c| c.training.as_mut()) {
+        training.skill_name = training.skill_id.and_then(|id| names.get(&id).cloned());
+    }
+    Ok(())
+}
+
+/// `include_training` embeds a [`CharacterTrainingSummary`] per character so
+/// the first render does not need a queue request for each one.
+/// `allow_cached` returns the last response at once when there is one and
+/// sends the fresh one as `accounts:updated`.
 #[tauri::command]
 pub async fn get_accounts_and_characters(
+    app: AppHandle,
     pool: State<'_, db::Pool>,
+    cache: State<'_, ResponseCache>,
+    include_training: Option<bool>,
+    allow_cached: Option<bool>,
 ) -> Result<AccountsAndCharactersResponse, String> {
-    let accounts = db::get_all_accounts(&pool)
+    let include_training = include_training.unwrap_or(false);
+    let key = accounts_cache_key(include_training);
+
+    if allow_cached.unwrap_or(false) {
+        if let Some(mut cached) = cache.get::<AccountsAndCharactersResponse>(&key) {
+            cached.is_cached = true;
+            let pool = pool.inner().clone();
+            tauri::async_runtime::spawn(async move {
+                match load_accounts_and_characters(&pool, include_training).await {
+                    Ok(response) => {
+                        app.state::<ResponseCache>().put(&key, &response);
+                        let updated = AccountsUpdated {
+                            include_training,
+                            response,
+                        };
+                        if let Err(e) = events::emit_accounts_updated(&app, &updated) {
+                            log::warn!("Failed to emit accounts update: {}", e);
+                        }
+                    }
+                    Err(e) => log::warn!("Failed to refresh cached accounts: {}", e),
+                }
+            });
+            return Ok(cached);
+        }
+    }
+
+    let response = load_accounts_and_characters(&pool, include_training).await?;
+    cache.put(&key, &response);
+    Ok(response)
+}
+
+async fn load_accounts_and_characters(
+    pool: &db::Pool,
+    include_training: bool,
+) -> Result<AccountsAndCharactersResponse, String> {
+    let accounts = db::get_all_accounts(pool)
         .await
         .map_err(|e| format!("Failed to get accounts: {}", e))?;
 
     let mut accounts_with_characters = Vec::new();
 
     for account in accounts {
-        let characters = db::get_characters_for_account(&pool, account.id)
+        let characters = db::get_characters_for_account(pool, account.id)
             .await
             .map_err(|e| format!("Failed to get characters for account: {}", e))?;
 
@@ -68,17 +199,30 @@ pub async fn get_accounts_and_characters(
         });
     }
 
-    let unassigned_characters = db::get_unassigned_characters(&pool)
+    let unassigned_characters = db::get_unassigned_characters(pool)
         .await
         .map_err(|e| format!("Failed to get unassigned characters: {}", e))?;
 
-    Ok(AccountsAndCharactersResponse {
+    let mut response = AccountsAndCharactersResponse {
         accounts: accounts_with_characters,
         unassigned_characters: unassigned_characters
             .into_iter()
             .map(Character::from)
             .collect(),
-    })
+        is_cached: false,
+    };
+
+    if include_training {
+        let mut characters: Vec<&mut Character> = response
+            .accounts
+            .iter_mut()
+            .flat_map(|a| a.characters.iter_mut())
+            .chain(response.unassigned_characters.iter_mut())
+            .collect();
+        embed_training(pool, &mut characters).await?;
+    }
+
+    Ok(response)
 }
 
 #[tauri::command]
@@ -157,3 +301,102 @@ pub async fn reorder_unassigned_characters(
         .await
         .map_err(|e| format!("Failed to reorder unassigned characters: {}", e))
 }
+
+#[tauri::command]
+pub async fn bulk_create_accounts(
+    pool: State<'_, db::Pool>,
+    names: Vec<String>,
+) -> Result<Vec<i64>, String> {
+    db::accounts::bulk_create_accounts(&pool, &names)
+        .await
+        .map_err(|e| format!("Failed to create accounts: {}", e))
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountPrefixSuggestion {
+    pub prefix: String,
+    pub characters: Vec<Character>,
+}
+
+/// Name prefixes ("Miner" for "Miner 1", "Miner 2", ...) shared by two or more
+/// unassigned characters, for the user to pick from before grouping.
+#[tauri::command]
+pub async fn suggest_account_prefixes(
+    pool: State<'_, db::Pool>,
+) -> Result<Vec<AccountPrefixSuggestion>, String> {
+    let unassigned = db::get_unassigned_characters(&pool)
+        .await
+        .map_err(|e| format!("Failed to get unassigned characters: {}", e))?;
+
+    Ok(db::accounts::suggest_prefixes(&unassigned)
+        .into_iter()
+        .map(|(prefix, characters)| AccountPrefixSuggestion {
+            prefix,
+            characters: characters.into_iter().map(Character::from).collect(),
+        })
+        .collect())
+}
+
+/// Moves unassigned characters into one account per chosen prefix.
+#[tauri::command]
+pub async fn auto_group_characters_by_prefix(
+    pool: State<'_, db::Pool>,
+    prefixes: Vec<String>,
+) -> Result<db::accounts::PrefixGroupingOutcome, String> {
+    db::accounts::group_characters_by_prefix(&pool, &prefixes)
+        .await
+        .map_err(|e| format!("Failed to group characters: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn entry(
+        skill_id: i64,
+        start: DateTime<Utc>,
+        finish: Option<DateTime<Utc>>,
+    ) -> SkillQueueEntry {
+        SkillQueueEntry {
+            skill_id,
+            finished_level: 4,
+            queue_position: 0,
+            start_date: Some(start.to_rfc3339()),
+            finish_date: finish.map(|f| f.to_rfc3339()),
+            training_start_sp: None,
+            level_start_sp: None,
+            level_end_sp: None,
+        }
+    }
+
+    #[test]
+    fn summarizes_training_and_paused_queues() {
+        let now = Utc::now();
+        let queue = vec![
+            entry(
+                3300,
+                now - Duration::hours(1),
+                Some(now + Duration::hours(2)),
+            ),
+            entry(
+                3301,
+                now + Duration::hours(2),
+                Some(now + Duration::hours(6)),
+            ),
+        ];
+
+        let summary = training_summary(&queue, now);
+        assert_eq!(summary.skill_id, Some(3300));
+        assert_eq!(summary.finished_level, Some(4));
+        assert!((summary.queue_hours.unwrap() - 6.0).abs() < 0.01);
+        assert!(!summary.is_paused);
+
+        let paused = vec![entry(3300, now, None)];
+        let summary = training_summary(&paused, now);
+        assert_eq!(summary.skill_id, None);
+        assert_eq!(summary.queue_hours, None);
+        assert!(summary.is_paused);
+    }
+}
@@ -0,0 +1,16 @@
+use tauri::State;
+
+use crate::db;
+use crate::db::activity_feed::ActivityEntry;
+
+/// What background refreshes changed, newest first.
+#[tauri::command]
+pub async fn get_activity_feed(
+    read_pool: State<'_, db::ReadPool>,
+    limit: Option<i64>,
+) -> Result<Vec<ActivityEntry>, String> {
+    let pool: &db::Pool = &read_pool;
+    db::activity_feed::get_activity_feed(pool, limit.unwrap_or(100))
+        .await
+        .map_err(|e| format!("Failed to get activity feed: {}", e))
+}
@@ -0,0 +1,24 @@
+use tauri::State;
+
+use crate::db;
+use crate::db::audit_log::AuditEntry;
+
+/// Destructive commands run so far, newest first.
+#[tauri::command]
+pub async fn get_audit_log(
+    read_pool: State<'_, db::ReadPool>,
+    limit: Option<i64>,
+) -> Result<Vec<AuditEntry>, String> {
+    let pool: &db::Pool = &read_pool;
+    db::audit_log::get_audit_log(pool, limit.unwrap_or(100))
+        .await
+        .map_err(|e| format!("Failed to get audit log: {}", e))
+}
+
+/// Records a destructive command after it succeeded. The command already
+/// happened, so a failure to record is logged rather than returned.
+pub(crate) async fn audit(pool: &db::Pool, command: &str, params: serde_json::Value) {
+    if let Err(e) = db::audit_log::record_audit(pool, command, &params).await {
+        log::warn!("Failed to record {} in the audit log: {}", command, e);
+    }
+}
@@ -3,13 +3,15 @@ use std::sync::Mutex;
 use anyhow::{Context, Result};
 use chrono::Utc;
 use serde::Serialize;
-use tauri::{Emitter, Manager, State};
+use tauri::{Manager, State};
 use typeshare::typeshare;
 
 use crate::auth;
+use crate::auth::token_store;
 use crate::cache;
 use crate::db;
 use crate::esi;
+use crate::events;
 use crate::refresh;
 
 pub type AuthStateMap = std::sync::Mutex<std::collections::HashMap<String, auth::AuthState>>;
@@ -37,40 +39,26 @@ pub fn get_base_scope_strings() -> BaseScopeStrings {
     }
 }
 
-#[tauri::command]
-pub async fn start_eve_login(
-    app: tauri::AppHandle,
-    auth_states: State<'_, AuthStateMap>,
-    pool: State<'_, db::Pool>,
-) -> Result<String, String> {
-    let client_id = get_eve_client_id().map_err(|e| e.to_string())?;
-    let callback_url = std::env::var("EVE_CALLBACK_URL").unwrap_or_else(|_| {
+fn callback_url() -> String {
+    std::env::var("EVE_CALLBACK_URL").unwrap_or_else(|_| {
         if tauri::is_dev() {
             "http://localhost:1421/callback".to_string()
         } else {
             "eveauth-skillmon://callback".to_string()
         }
-    });
-
-    let mut scopes: Vec<crate::esi::EsiScope> = crate::esi::BASE_SCOPES.to_vec();
-
-    let enabled_features = db::get_enabled_features(&pool)
-        .await
-        .map_err(|e| format!("Failed to get enabled features: {}", e))?;
-
-    let optional_features = crate::features::get_optional_features();
-
-    for feature_id in enabled_features {
-        if let Some(feature) = optional_features.iter().find(|f| f.id == feature_id) {
-            for scope in &feature.scopes {
-                if !scopes.contains(scope) {
-                    scopes.push(*scope);
-                }
-            }
-        }
-    }
+    })
+}
 
-    let (auth_url, auth_state) = auth::generate_auth_url(&client_id, &scopes, &callback_url);
+/// Registers the PKCE state and opens the SSO page in the browser.
+fn open_login(
+    app: &tauri::AppHandle,
+    auth_states: &AuthStateMap,
+    scopes: &[esi::EsiScope],
+    character_id: Option<i64>,
+) -> Result<String, String> {
+    let client_id = get_eve_client_id().map_err(|e| e.to_string())?;
+    let (auth_url, mut auth_state) = auth::generate_auth_url(&client_id, scopes, &callback_url());
+    auth_state.character_id = character_id;
 
     let state_key = auth_state.state.clone();
     auth_states
@@ -93,6 +81,42 @@ pub async fn start_eve_login(
     }
 }
 
+#[tauri::command]
+pub async fn start_eve_login(
+    app: tauri::AppHandle,
+    auth_states: State<'_, AuthStateMap>,
+    pool: State<'_, db::Pool>,
+) -> Result<String, String> {
+    let scopes = auth::reauth::requested_scopes(&pool)
+        .await
+        .map_err(|e| format!("Failed to get enabled features: {}", e))?;
+
+    open_login(&app, &auth_states, &scopes, None)
+}
+
+/// Logs an existing character in again with its current scopes, those of
+/// every enabled feature and `additional_scopes`. The callback rejects a
+/// login as any other character.
+#[tauri::command]
+pub async fn reauthorize_character(
+    app: tauri::AppHandle,
+    auth_states: State<'_, AuthStateMap>,
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+    additional_scopes: Vec<esi::EsiScope>,
+) -> Result<String, String> {
+    db::get_character(&pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to get character: {}", e))?
+        .ok_or_else(|| format!("Character {} not found", character_id))?;
+
+    let scopes = auth::reauth::reauthorize_scopes(&pool, character_id, &additional_scopes)
+        .await
+        .map_err(|e| format!("Failed to collect scopes: {}", e))?;
+
+    open_login(&app, &auth_states, &scopes, Some(character_id))
+}
+
 pub async fn handle_oauth_callback(
     app: tauri::AppHandle,
     code: String,
@@ -102,14 +126,14 @@ pub async fn handle_oauth_callback(
     let auth_states = app.state::<AuthStateMap>();
     let pool = app.state::<db::Pool>();
 
-    let code_verifier = {
+    let (code_verifier, expected_character_id) = {
         let mut auth_states_guard = auth_states
             .lock()
             .map_err(|e| anyhow::anyhow!("Failed to lock auth state: {}", e))?;
         let auth_state = auth_states_guard
             .remove(&state)
             .ok_or_else(|| anyhow::anyhow!("Invalid state parameter"))?;
-        auth_state.code_verifier
+        (auth_state.code_verifier, auth_state.character_id)
     };
 
     let client_id = get_eve_client_id()?;
@@ -121,6 +145,15 @@ pub async fn handle_oauth_callback(
     let character_info = auth::extract_character_from_jwt(&token_response.access_token)
         .context("Failed to extract character info from JWT")?;
 
+    if let Some(expected) = expected_character_id {
+        if expected != character_info.character_id {
+            anyhow::bail!(
+                "Logged in as {} instead of the character being re-authorized",
+                character_info.character_name
+            );
+        }
+    }
+
     let scopes = auth::extract_scopes_from_jwt(&token_response.access_token)
         .context("Failed to extract scopes from JWT")?;
 
@@ -146,10 +179,13 @@ pub async fn handle_oauth_callback(
         .context("Failed to update character")?;
     }
 
+    // Only whether a row exists matters here; the raw row avoids decrypting
+    // tokens that may be sealed with a key this install no longer has, which
+    // is exactly when logging in again is the fix.
     let existing_tokens = db::get_tokens(&pool, character_info.character_id).await?;
 
     if existing_tokens.is_none() {
-        db::set_tokens(
+        token_store::set_tokens(
             &pool,
             character_info.character_id,
             &token_response.access_token,
@@ -160,7 +196,7 @@ pub async fn handle_oauth_callback(
         .await
         .context("Failed to set tokens")?;
     } else {
-        db::update_tokens(
+        token_store::update_tokens(
             &pool,
             character_info.character_id,
             &token_response.access_token,
@@ -179,6 +215,7 @@ pub async fn handle_oauth_callback(
     // Spawn (or re-spawn) the background refresher for this character
     if let Some(supervisor) = app.try_state::<Mutex<refresh::RefreshSupervisor>>() {
         let rate_limits = app.state::<esi::RateLimitStore>().inner().clone();
+        let activity = app.state::<refresh::WindowActivityStore>().inner().clone();
         let old_handle = supervisor
             .lock()
             .unwrap()
@@ -191,12 +228,13 @@ pub async fn handle_oauth_callback(
             pool.inner().clone(),
             app.clone(),
             rate_limits,
+            activity,
         );
     }
 
     tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
 
-    match app.emit("auth-success", character_info.character_id) {
+    match events::emit_auth_success(&app, character_info.character_id) {
         Ok(_) => {}
         Err(e) => {
             return Err(anyhow::anyhow!("Failed to emit auth success event: {}", e));
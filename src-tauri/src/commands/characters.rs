@@ -3,6 +3,7 @@ use std::sync::Mutex;
 use serde::Serialize;
 use tauri::State;
 
+use crate::auth::token_store;
 use crate::db;
 use crate::refresh;
 #[allow(dead_code)]
@@ -43,13 +44,73 @@ pub async fn logout_character(
         let _ = h.await;
     }
 
-    sqlx::query("DELETE FROM tokens WHERE character_id = ?")
-        .bind(character_id)
-        .execute(&*pool)
+    let character = db::get_character(&pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to get character: {}", e))?;
+
+    token_store::delete_tokens(&pool, character_id)
         .await
         .map_err(|e| format!("Failed to delete tokens: {}", e))?;
 
     db::delete_character(&pool, character_id)
         .await
-        .map_err(|e| format!("Failed to delete character: {}", e))
+        .map_err(|e| format!("Failed to delete character: {}", e))?;
+
+    super::audit_log::audit(
+        &pool,
+        "logout_character",
+        serde_json::json!({
+            "character_id": character_id,
+            "character_name": character.map(|c| c.character_name),
+        }),
+    )
+    .await;
+    Ok(())
+}
+
+/// Pins the character to Omega (`true`) or Alpha (`false`) for training
+/// projections; `None` goes back to inferring it from ESI.
+#[tauri::command]
+pub async fn set_character_omega_override(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+    is_omega: Option<bool>,
+) -> Result<(), String> {
+    db::characters::set_character_omega_override(&pool, character_id, is_omega)
+        .await
+        .map_err(|e| format!("Failed to set omega override: {}", e))
+}
+
+/// Corporation/alliance memberships seen for the character, newest first.
+#[tauri::command]
+pub async fn get_character_membership_history(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+) -> Result<Vec<db::membership::MembershipRecord>, String> {
+    db::membership::get_membership_history(&pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to get membership history: {}", e))
+}
+
+/// Attribute snapshots recorded for the character, newest first.
+#[tauri::command]
+pub async fn get_attribute_history(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+) -> Result<Vec<db::attribute_history::AttributeSnapshot>, String> {
+    db::attribute_history::get_attribute_history(&pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to get attribute history: {}", e))
+}
+
+/// Daily SP snapshots from the last `days` days, oldest first.
+#[tauri::command]
+pub async fn get_sp_history(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+    days: i64,
+) -> Result<Vec<db::sp_history::SpHistoryPoint>, String> {
+    db::sp_history::get_sp_history(&pool, character_id, days, chrono::Utc::now())
+        .await
+        .map_err(|e| format!("Failed to get SP history: {}", e))
 }
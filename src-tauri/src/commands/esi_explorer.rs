@@ -0,0 +1,46 @@
+use std::collections::BTreeMap;
+
+use tauri::State;
+
+use crate::esi::raw::{self, EsiMethod, RawEsiResponse};
+use crate::{db, esi};
+
+#[tauri::command]
+pub async fn esi_raw_request(
+    pool: State<'_, db::Pool>,
+    rate_limits: State<'_, esi::RateLimitStore>,
+    character_id: Option<i64>,
+    method: EsiMethod,
+    path: String,
+    query: Option<BTreeMap<String, String>>,
+) -> Result<RawEsiResponse, String> {
+    raw::explorer_request(
+        &pool,
+        &rate_limits,
+        character_id,
+        method,
+        &path,
+        &query.unwrap_or_default(),
+    )
+    .await
+    .map_err(|e| format!("Failed to send ESI request: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_esi_explorer_settings(
+    pool: State<'_, db::Pool>,
+) -> Result<db::app_settings::EsiExplorerSettings, String> {
+    db::app_settings::get_esi_explorer_settings(&pool)
+        .await
+        .map_err(|e| format!("Failed to get ESI explorer settings: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_esi_explorer_settings(
+    pool: State<'_, db::Pool>,
+    settings: db::app_settings::EsiExplorerSettings,
+) -> Result<(), String> {
+    db::app_settings::set_esi_explorer_settings(&pool, &settings)
+        .await
+        .map_err(|e| format!("Failed to save ESI explorer settings: {}", e))
+}
@@ -1,11 +1,13 @@
 use futures_util::future::join_all;
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 use typeshare::typeshare;
 
 use crate::db;
+use crate::events::emit_esi_snapshot_updated;
 use crate::refresh::enrichment;
 use crate::refresh::events;
+use crate::response_cache::ResponseCache;
 use crate::ts_types::i64_ts;
 
 #[typeshare]
@@ -20,28 +22,74 @@ pub struct CharacterSnapshot {
     pub clones: Vec<events::CloneInfo>,
     pub location: Option<events::LocationPayload>,
     pub remaps: Vec<db::remaps::Remap>,
+    /// Corporation/alliance memberships, newest (current) first.
+    pub membership_history: Vec<db::membership::MembershipRecord>,
     pub overview: Option<events::OverviewRow>,
+    /// Served from the response cache; the fresh snapshot follows as
+    /// `esi-snapshot:updated`.
+    #[serde(default)]
+    pub is_cached: bool,
 }
 
+const ESI_SNAPSHOT_KEY: &str = "esi_snapshot";
+
+/// `allow_cached` returns the last snapshot at once when there is one and
+/// sends the fresh one, whole, as `esi-snapshot:updated`. The frontend
+/// replaces what it has with it, so characters added or removed since the
+/// cached copy and fields that are now empty are reconciled too.
 #[tauri::command]
-pub async fn get_esi_snapshot(pool: State<'_, db::Pool>) -> Result<Vec<CharacterSnapshot>, String> {
-    let characters = db::get_all_characters(&pool)
+pub async fn get_esi_snapshot(
+    app: AppHandle,
+    pool: State<'_, db::Pool>,
+    cache: State<'_, ResponseCache>,
+    allow_cached: Option<bool>,
+) -> Result<Vec<CharacterSnapshot>, String> {
+    if allow_cached.unwrap_or(false) {
+        if let Some(mut cached) = cache.get::<Vec<CharacterSnapshot>>(ESI_SNAPSHOT_KEY) {
+            for snapshot in &mut cached {
+                snapshot.is_cached = true;
+            }
+            let pool = pool.inner().clone();
+            tauri::async_runtime::spawn(async move {
+                match build_esi_snapshot(&pool).await {
+                    Ok(snapshots) => {
+                        app.state::<ResponseCache>()
+                            .put(ESI_SNAPSHOT_KEY, &snapshots);
+                        if let Err(e) = emit_esi_snapshot_updated(&app, &snapshots) {
+                            log::warn!("Failed to emit ESI snapshot update: {}", e);
+                        }
+                    }
+                    Err(e) => log::warn!("Failed to refresh cached ESI snapshot: {}", e),
+                }
+            });
+            return Ok(cached);
+        }
+    }
+
+    let snapshots = build_esi_snapshot(&pool).await?;
+    cache.put(ESI_SNAPSHOT_KEY, &snapshots);
+    Ok(snapshots)
+}
+
+async fn build_esi_snapshot(pool: &db::Pool) -> Result<Vec<CharacterSnapshot>, String> {
+    let characters = db::get_all_characters(pool)
         .await
         .map_err(|e| e.to_string())?;
 
     let futures = characters.into_iter().map(|character| {
-        let pool = pool.inner().clone();
+        let pool = pool.clone();
         async move {
             let character_id = character.character_id;
             let character_name = character.character_name.clone();
 
-            let (queue, skills, attributes, clones_payload, location, remaps) = tokio::join!(
+            let (queue, skills, attributes, clones_payload, location, remaps, membership) = tokio::join!(
                 enrichment::enrich_queue_from_db(&pool, character_id),
                 enrichment::enrich_skills_from_db(&pool, character_id),
                 enrichment::enrich_attributes_from_db(&pool, character_id),
                 enrichment::enrich_clones(&pool, character_id),
                 enrichment::enrich_location_db_only(&pool, character_id),
                 db::remaps::get_character_remaps(&pool, character_id),
+                db::membership::get_membership_history(&pool, character_id),
             );
 
             let overview = match &queue {
@@ -57,6 +105,14 @@ pub async fn get_esi_snapshot(pool: State<'_, db::Pool>) -> Result<Vec<Character
                 vec![]
             });
 
+            let membership_history = membership.unwrap_or_else(|e| {
+                eprintln!(
+                    "esi_snapshot: membership fetch error for {}: {}",
+                    character_id, e
+                );
+                vec![]
+            });
+
             CharacterSnapshot {
                 character_id,
                 character_name,
@@ -66,7 +122,9 @@ pub async fn get_esi_snapshot(pool: State<'_, db::Pool>) -> Result<Vec<Character
                 clones: clones_payload.clones,
                 location,
                 remaps,
+                membership_history,
                 overview,
+                is_cached: false,
             }
         }
     });
@@ -0,0 +1,8 @@
+use crate::events::{self, EventContract};
+
+/// Developer aid: every event name with its payload type and direction, for
+/// checking frontend listeners against the backend.
+#[tauri::command]
+pub fn list_event_contracts() -> Vec<EventContract> {
+    events::contracts()
+}
@@ -0,0 +1,74 @@
+use tauri::State;
+
+use crate::db;
+use crate::db::farm::{FarmPrices, FarmSettings};
+use crate::esi;
+use crate::farm::{self, ExtractionSummary, FarmRotationAdvice};
+use crate::omega_budget::{self, OmegaBudget};
+
+#[tauri::command]
+pub async fn get_farm_settings(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+) -> Result<FarmSettings, String> {
+    db::farm::get_farm_settings(&pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to get farm settings: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_farm_settings(
+    pool: State<'_, db::Pool>,
+    settings: FarmSettings,
+) -> Result<(), String> {
+    if settings.cycle_days < 1 {
+        return Err("Extraction cycle must be at least one day".to_string());
+    }
+    db::farm::upsert_farm_settings(&pool, &settings)
+        .await
+        .map_err(|e| format!("Failed to save farm settings: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_farm_prices(pool: State<'_, db::Pool>) -> Result<FarmPrices, String> {
+    db::farm::get_farm_prices(&pool)
+        .await
+        .map_err(|e| format!("Failed to get farm prices: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_farm_prices(pool: State<'_, db::Pool>, prices: FarmPrices) -> Result<(), String> {
+    db::farm::set_farm_prices(&pool, &prices)
+        .await
+        .map_err(|e| format!("Failed to save farm prices: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_farm_rotation_advice(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+) -> Result<FarmRotationAdvice, String> {
+    farm::rotation_advice(&pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to build farm rotation advice: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_extraction_summary(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+) -> Result<ExtractionSummary, String> {
+    farm::extraction_summary(&pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to build extraction summary: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_omega_budget(
+    pool: State<'_, db::Pool>,
+    rate_limits: State<'_, esi::RateLimitStore>,
+) -> Result<OmegaBudget, String> {
+    omega_budget::omega_budget(&pool, &rate_limits)
+        .await
+        .map_err(|e| format!("Failed to estimate Omega budget: {}", e))
+}
@@ -0,0 +1,36 @@
+use tauri::State;
+
+use crate::fittings::{self, FittingSummary};
+use crate::{db, esi, sde};
+
+#[tauri::command]
+pub async fn get_fittings(
+    pool: State<'_, db::Pool>,
+    rate_limits: State<'_, esi::RateLimitStore>,
+    character_id: i64,
+) -> Result<Vec<FittingSummary>, String> {
+    sde::require_full_import(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let fits = fittings::fetch_fittings(&pool, &rate_limits, character_id)
+        .await
+        .map_err(|e| format!("Failed to fetch fittings: {}", e))?;
+    fittings::summarize(&pool, character_id, fits)
+        .await
+        .map_err(|e| format!("Failed to check fitting skills: {}", e))
+}
+
+#[tauri::command]
+pub async fn push_eft_fitting(
+    pool: State<'_, db::Pool>,
+    rate_limits: State<'_, esi::RateLimitStore>,
+    character_id: i64,
+    eft: String,
+) -> Result<i64, String> {
+    sde::require_full_import(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    fittings::push_eft(&pool, &rate_limits, character_id, &eft)
+        .await
+        .map_err(|e| format!("Failed to save fitting: {}", e))
+}
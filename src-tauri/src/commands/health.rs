@@ -0,0 +1,40 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tauri::State;
+
+use crate::db;
+use crate::esi;
+use crate::health::{self, AppHealth};
+use crate::refresh::RefreshSupervisor;
+use crate::scheduler::{self, Scheduler};
+
+/// Every subsystem's status in one call, for the diagnostics screen.
+#[tauri::command]
+pub async fn get_app_health(
+    read_pool: State<'_, db::ReadPool>,
+    rate_limits: State<'_, esi::RateLimitStore>,
+    scheduler: State<'_, Scheduler>,
+    supervisor: State<'_, Mutex<RefreshSupervisor>>,
+) -> Result<AppHealth, String> {
+    let pool: &db::Pool = &read_pool;
+    let characters = db::get_all_characters(pool)
+        .await
+        .map_err(|e| format!("Failed to get characters: {}", e))?;
+    let refresh: HashMap<i64, _> = {
+        let supervisor = supervisor.lock().unwrap();
+        characters
+            .iter()
+            .map(|c| (c.character_id, supervisor.health(c.character_id)))
+            .collect()
+    };
+    let settings = scheduler::get_scheduler_settings(pool)
+        .await
+        .map_err(|e| format!("Failed to get scheduler settings: {}", e))?;
+    let tasks = scheduler.status(&settings);
+    let esi = health::esi_health(&*rate_limits.read().await, chrono::Utc::now());
+
+    health::app_health(pool, &characters, &refresh, esi, tasks)
+        .await
+        .map_err(|e| format!("Failed to check app health: {}", e))
+}
@@ -0,0 +1,76 @@
+use tauri::State;
+use tauri_plugin_opener::OpenerExt;
+
+use crate::integrations::links::{self, ExternalLinks};
+use crate::integrations::pyfa::{self, PyfaCharacterExport};
+use crate::integrations::ui;
+use crate::{db, esi};
+
+#[tauri::command]
+pub async fn get_external_links(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+) -> Result<ExternalLinks, String> {
+    links::external_links(&pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to build external links: {}", e))
+}
+
+#[tauri::command]
+pub async fn open_character_on_zkill(
+    app: tauri::AppHandle,
+    character_id: i64,
+) -> Result<(), String> {
+    app.opener()
+        .open_url(
+            links::zkillboard_character_url(character_id),
+            None::<String>,
+        )
+        .map_err(|e| format!("Failed to open zKillboard: {}", e))
+}
+
+#[tauri::command]
+pub async fn open_market_window_ingame(
+    pool: State<'_, db::Pool>,
+    rate_limits: State<'_, esi::RateLimitStore>,
+    character_id: i64,
+    type_id: i64,
+) -> Result<(), String> {
+    ui::open_market_details(&pool, &rate_limits, character_id, type_id)
+        .await
+        .map_err(|e| format!("Failed to open market window: {}", e))
+}
+
+#[tauri::command]
+pub async fn show_info_ingame(
+    pool: State<'_, db::Pool>,
+    rate_limits: State<'_, esi::RateLimitStore>,
+    character_id: i64,
+    target_id: i64,
+) -> Result<(), String> {
+    ui::open_information(&pool, &rate_limits, character_id, target_id)
+        .await
+        .map_err(|e| format!("Failed to open info window: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_waypoint_to_clone(
+    pool: State<'_, db::Pool>,
+    rate_limits: State<'_, esi::RateLimitStore>,
+    character_id: i64,
+    clone_id: i64,
+) -> Result<(), String> {
+    ui::set_waypoint_to_clone(&pool, &rate_limits, character_id, clone_id)
+        .await
+        .map_err(|e| format!("Failed to set waypoint: {}", e))
+}
+
+#[tauri::command]
+pub async fn export_character_for_pyfa(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+) -> Result<PyfaCharacterExport, String> {
+    pyfa::export_character(&pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to export character for pyfa: {}", e))
+}
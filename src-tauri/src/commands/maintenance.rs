@@ -0,0 +1,11 @@
+use tauri::State;
+
+use crate::db::{self, maintenance::MaintenanceReport};
+use crate::maintenance;
+
+#[tauri::command]
+pub async fn run_maintenance_now(pool: State<'_, db::Pool>) -> Result<MaintenanceReport, String> {
+    maintenance::run(&pool)
+        .await
+        .map_err(|e| format!("Failed to run database maintenance: {}", e))
+}
@@ -0,0 +1,39 @@
+use tauri::State;
+
+use crate::db;
+use crate::db::market_prices::MarketPrice;
+use crate::esi;
+
+#[tauri::command]
+pub async fn get_market_prices(pool: State<'_, db::Pool>) -> Result<Vec<MarketPrice>, String> {
+    db::market_prices::get_market_prices(&pool)
+        .await
+        .map_err(|e| format!("Failed to get market prices: {}", e))
+}
+
+#[tauri::command]
+pub async fn refresh_market_prices(
+    pool: State<'_, db::Pool>,
+    rate_limits: State<'_, esi::RateLimitStore>,
+) -> Result<Vec<MarketPrice>, String> {
+    esi::markets::refresh_market_prices(&pool, &rate_limits)
+        .await
+        .map_err(|e| format!("Failed to refresh market prices: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_market_type_ids(pool: State<'_, db::Pool>) -> Result<Vec<i64>, String> {
+    db::market_prices::get_market_type_ids(&pool)
+        .await
+        .map_err(|e| format!("Failed to get tracked market types: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_market_type_ids(
+    pool: State<'_, db::Pool>,
+    type_ids: Vec<i64>,
+) -> Result<(), String> {
+    db::market_prices::set_market_type_ids(&pool, &type_ids)
+        .await
+        .map_err(|e| format!("Failed to save tracked market types: {}", e))
+}
@@ -1,14 +1,32 @@
 pub mod accounts;
+pub mod activity_feed;
+pub mod audit_log;
 pub mod auth;
 pub mod characters;
 pub mod clones;
+pub mod esi_explorer;
 pub mod esi_snapshot;
+pub mod events;
+pub mod farm;
+pub mod fittings;
+pub mod health;
+pub mod integrations;
+pub mod maintenance;
+pub mod markets;
 pub mod notifications;
+pub mod onboarding;
+pub mod plan_comments;
 pub mod plan_groups;
+pub mod plan_history;
 pub mod rate_limits;
 pub mod remaps;
+pub mod scheduler;
 pub mod sde;
 pub mod settings;
 pub mod skill_plans;
 pub mod skill_queues;
 pub mod skills;
+pub mod storage;
+pub mod sync;
+pub mod watchlist;
+pub mod widget;
@@ -4,7 +4,8 @@ use tauri::{AppHandle, State};
 use typeshare::typeshare;
 
 use crate::db;
-use crate::notifications;
+use crate::db::notification_webhooks::{NotificationWebhook, NotificationWebhookInput};
+use crate::notifications::{self, sinks};
 use crate::ts_types::i64_ts;
 
 #[typeshare]
@@ -127,7 +128,7 @@ pub async fn upsert_notification_setting(
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
 
     db::upsert_notification_setting(
-        &pool,
+        &*pool,
         character_id,
         &notification_type,
         enabled,
@@ -138,3 +139,64 @@ pub async fn upsert_notification_setting(
 
     Ok(())
 }
+
+#[tauri::command]
+pub async fn get_notification_webhooks(
+    pool: State<'_, db::Pool>,
+) -> Result<Vec<NotificationWebhook>, String> {
+    db::notification_webhooks::get_notification_webhooks(&pool)
+        .await
+        .map_err(|e| format!("Failed to get notification webhooks: {}", e))
+}
+
+#[tauri::command]
+pub async fn create_notification_webhook(
+    pool: State<'_, db::Pool>,
+    webhook: NotificationWebhookInput,
+) -> Result<i64_ts, String> {
+    db::notification_webhooks::create_notification_webhook(&pool, &webhook)
+        .await
+        .map_err(|e| format!("Failed to create notification webhook: {}", e))
+}
+
+#[tauri::command]
+pub async fn update_notification_webhook(
+    pool: State<'_, db::Pool>,
+    webhook_id: i64,
+    webhook: NotificationWebhookInput,
+) -> Result<(), String> {
+    db::notification_webhooks::update_notification_webhook(&pool, webhook_id, &webhook)
+        .await
+        .map_err(|e| format!("Failed to update notification webhook: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_notification_webhook(
+    pool: State<'_, db::Pool>,
+    webhook_id: i64,
+) -> Result<(), String> {
+    db::notification_webhooks::delete_notification_webhook(&pool, webhook_id)
+        .await
+        .map_err(|e| format!("Failed to delete notification webhook: {}", e))
+}
+
+/// Posts a sample message so the user can check the URL before relying on it.
+#[tauri::command]
+pub async fn test_notification_webhook(
+    pool: State<'_, db::Pool>,
+    webhook_id: i64,
+) -> Result<(), String> {
+    let webhook = db::notification_webhooks::get_notification_webhook(&pool, webhook_id)
+        .await
+        .map_err(|e| format!("Failed to get notification webhook: {}", e))?
+        .ok_or_else(|| "Notification webhook not found".to_string())?;
+    let notification = sinks::OutgoingNotification {
+        character_name: "skillmon".to_string(),
+        notification_type: "test",
+        title: "Test Notification",
+        message: "Notifications routed to this webhook will appear here",
+    };
+    sinks::post_to_webhook(&webhook, &notification)
+        .await
+        .map_err(|e| format!("Failed to send test notification: {}", e))
+}
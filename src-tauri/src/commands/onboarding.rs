@@ -0,0 +1,25 @@
+use tauri::State;
+
+use crate::db;
+use crate::onboarding::{self, OnboardingChoices, OnboardingResult, OnboardingSuggestions};
+
+#[tauri::command]
+pub async fn get_onboarding_suggestions(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+) -> Result<OnboardingSuggestions, String> {
+    onboarding::suggestions(&pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to build onboarding suggestions: {}", e))
+}
+
+#[tauri::command]
+pub async fn apply_onboarding_choices(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+    choices: OnboardingChoices,
+) -> Result<OnboardingResult, String> {
+    onboarding::apply(&pool, character_id, &choices)
+        .await
+        .map_err(|e| format!("Failed to apply onboarding choices: {}", e))
+}
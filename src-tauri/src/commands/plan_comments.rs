@@ -0,0 +1,63 @@
+use tauri::State;
+
+use crate::db;
+use crate::db::plan_comments::PlanEntryComment;
+
+#[tauri::command]
+pub async fn get_plan_entry_comments(
+    pool: State<'_, db::Pool>,
+    plan_id: i64,
+) -> Result<Vec<PlanEntryComment>, String> {
+    db::plan_comments::get_plan_comments(&*pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get comments: {}", e))
+}
+
+#[tauri::command]
+pub async fn add_plan_entry_comment(
+    pool: State<'_, db::Pool>,
+    plan_id: i64,
+    skill_type_id: i64,
+    planned_level: i64,
+    author: String,
+    body: String,
+) -> Result<i64, String> {
+    let author = author.trim();
+    let body = body.trim();
+    if author.is_empty() {
+        return Err("Author is required".to_string());
+    }
+    if body.is_empty() {
+        return Err("Comment cannot be empty".to_string());
+    }
+
+    let in_plan = db::skill_plans::get_plan_nodes_in_order(&pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get plan entries: {}", e))?
+        .contains(&(skill_type_id, planned_level));
+    if !in_plan {
+        return Err("This skill level is not part of the plan".to_string());
+    }
+
+    db::plan_comments::add_plan_comment(
+        &*pool,
+        plan_id,
+        skill_type_id,
+        planned_level,
+        author,
+        body,
+        chrono::Utc::now().timestamp(),
+    )
+    .await
+    .map_err(|e| format!("Failed to add comment: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_plan_entry_comment(
+    pool: State<'_, db::Pool>,
+    comment_id: i64,
+) -> Result<(), String> {
+    db::plan_comments::delete_plan_comment(&pool, comment_id)
+        .await
+        .map_err(|e| format!("Failed to delete comment: {}", e))
+}
@@ -49,3 +49,14 @@ pub async fn move_node(pool: State<'_, db::Pool>, payload: MoveNodePayload) -> R
         .await
         .map_err(|e| format!("Failed to move folder: {}", e))
 }
+
+#[tauri::command]
+pub async fn move_plan_to_group(
+    pool: State<'_, db::Pool>,
+    plan_id: i64,
+    group_id: Option<i64>,
+) -> Result<(), String> {
+    db::plan_groups::move_plan_to_group(&pool, plan_id, group_id)
+        .await
+        .map_err(|e| format!("Failed to move plan: {}", e))
+}
@@ -0,0 +1,99 @@
+use tauri::State;
+
+use crate::db;
+use crate::db::plan_revisions::PlanRevision;
+
+/// Snapshots the plan's entries before `action` changes them. History is a
+/// safety net, so a failure to record is logged and the change goes ahead.
+pub(crate) async fn record_revision(pool: &db::Pool, plan_id: i64, action: &str) {
+    if let Err(e) = db::plan_revisions::record_revision(pool, plan_id, action).await {
+        log::warn!(
+            "Failed to record plan {} history before {}: {}",
+            plan_id,
+            action,
+            e
+        );
+    }
+}
+
+/// Every recorded change to the plan's entries, newest first.
+#[tauri::command]
+pub async fn get_plan_history(
+    read_pool: State<'_, db::ReadPool>,
+    plan_id: i64,
+) -> Result<Vec<PlanRevision>, String> {
+    let pool: &db::Pool = &read_pool;
+    db::plan_revisions::get_plan_revisions(pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get plan history: {}", e))
+}
+
+/// Puts the plan's entries back the way they were before `revision_id`'s
+/// change. The revert is itself recorded, so it can be undone the same way.
+/// Remaps, boosters and comments are kept as they are.
+#[tauri::command]
+pub async fn revert_plan_to_revision(
+    pool: State<'_, db::Pool>,
+    plan_id: i64,
+    revision_id: i64,
+) -> Result<(), String> {
+    revert_plan_to_revision_inner(&pool, plan_id, revision_id).await
+}
+
+async fn revert_plan_to_revision_inner(
+    pool: &db::Pool,
+    plan_id: i64,
+    revision_id: i64,
+) -> Result<(), String> {
+    let entries = db::plan_revisions::get_revision_entries(pool, plan_id, revision_id)
+        .await
+        .map_err(|e| format!("Failed to get revision: {}", e))?
+        .ok_or_else(|| "Revision not found".to_string())?;
+
+    record_revision(pool, plan_id, "revert_plan_to_revision").await;
+    db::skill_plans::replace_plan_entries(pool, plan_id, &entries)
+        .await
+        .map_err(|e| format!("Failed to revert plan: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::{fixtures, TestDb};
+
+    #[tokio::test]
+    async fn reverting_restores_entries_and_can_itself_be_undone() {
+        let db = TestDb::new().await.unwrap();
+        let plan = fixtures::create_skill_plan(&db.pool, "Undo").await;
+        fixtures::add_plan_entry(&db.pool, plan, 3327, 1, "Planned").await;
+        fixtures::add_plan_entry(&db.pool, plan, 3300, 1, "Planned").await;
+
+        record_revision(&db.pool, plan, "clear_plan_entries").await;
+        db::skill_plans::clear_plan_entries(&db.pool, plan)
+            .await
+            .unwrap();
+        let cleared = db::plan_revisions::get_plan_revisions(&db.pool, plan)
+            .await
+            .unwrap()[0]
+            .revision_id;
+
+        revert_plan_to_revision_inner(&db.pool, plan, cleared)
+            .await
+            .unwrap();
+        let nodes = db::skill_plans::get_plan_nodes_in_order(&db.pool, plan)
+            .await
+            .unwrap();
+        assert_eq!(nodes, vec![(3327, 1), (3300, 1)]);
+
+        let history = db::plan_revisions::get_plan_revisions(&db.pool, plan)
+            .await
+            .unwrap();
+        assert_eq!(history[0].action, "revert_plan_to_revision");
+        assert_eq!(history[0].entry_count, 0);
+
+        assert_eq!(
+            revert_plan_to_revision_inner(&db.pool, plan, cleared + 100).await,
+            Err("Revision not found".to_string())
+        );
+    }
+}
@@ -1,9 +1,12 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 use tauri::State;
 use typeshare::typeshare;
 
 use crate::esi;
-use crate::ts_types::i64_ts;
+use crate::ts_types::{i64_ts, usize_ts};
 
 #[typeshare]
 #[derive(Debug, Clone, Serialize)]
@@ -40,6 +43,7 @@ pub async fn get_rate_limits(
 ) -> Result<Vec<CharacterRateLimits>, String> {
     let store = rate_limits.read().await;
     Ok(store
+        .limits
         .iter()
         .map(|(character_id, limits_map)| CharacterRateLimits {
             character_id: *character_id,
@@ -47,3 +51,191 @@ pub async fn get_rate_limits(
         })
         .collect())
 }
+
+/// One row of the rate-limit dashboard: an ESI rate-limit group (a family of
+/// routes sharing a bucket) aggregated across characters.
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct RateLimitGroupSummary {
+    pub group: String,
+    pub routes: Vec<String>,
+    pub limit: Option<i32>,
+    pub window_minutes: Option<i32>,
+    /// Lowest remaining budget of any character in this group.
+    pub min_remaining: Option<i32>,
+    pub character_count: usize_ts,
+    /// Earliest time a character's bucket is guaranteed to be full again
+    /// (last response + window).
+    pub soonest_reset: Option<String>,
+    /// 429 responses in the last hour.
+    pub rate_limited_count: usize_ts,
+    /// 420 (error limit) responses in the last hour.
+    pub error_limited_count: usize_ts,
+    pub last_throttled_at: Option<String>,
+}
+
+#[derive(Default)]
+struct GroupAccumulator {
+    routes: BTreeSet<String>,
+    limit: Option<i32>,
+    window_minutes: Option<i32>,
+    min_remaining: Option<i32>,
+    character_count: usize,
+    soonest_reset: Option<DateTime<Utc>>,
+    rate_limited_count: usize,
+    error_limited_count: usize,
+    last_throttled_at: Option<DateTime<Utc>>,
+}
+
+fn build_dashboard(state: &esi::RateLimitState) -> Vec<RateLimitGroupSummary> {
+    let mut groups: BTreeMap<String, GroupAccumulator> = BTreeMap::new();
+
+    for limits in state.limits.values() {
+        for info in limits.values() {
+            let acc = groups.entry(info.group.clone()).or_default();
+            acc.limit = Some(info.limit);
+            acc.window_minutes = Some(info.window_minutes);
+            acc.min_remaining = Some(
+                acc.min_remaining
+                    .map_or(info.remaining, |m| m.min(info.remaining)),
+            );
+            acc.character_count += 1;
+            let reset = info.updated_at + chrono::Duration::minutes(info.window_minutes as i64);
+            acc.soonest_reset = Some(acc.soonest_reset.map_or(reset, |r| r.min(reset)));
+        }
+    }
+    for (group, routes) in &state.group_routes {
+        groups
+            .entry(group.clone())
+            .or_default()
+            .routes
+            .extend(routes.iter().cloned());
+    }
+
+    for event in &state.throttled {
+        // Error-limited responses carry no group header; file them under the
+        // group their route was last seen in, or under the route itself.
+        let group = event.group.clone().unwrap_or_else(|| {
+            state
+                .group_routes
+                .iter()
+                .find(|(_, routes)| routes.contains(&event.route))
+                .map(|(group, _)| group.clone())
+                .unwrap_or_else(|| event.route.clone())
+        });
+        let acc = groups.entry(group).or_default();
+        acc.routes.insert(event.route.clone());
+        if event.status == 429 {
+            acc.rate_limited_count += 1;
+        } else {
+            acc.error_limited_count += 1;
+        }
+        acc.last_throttled_at = Some(acc.last_throttled_at.map_or(event.at, |t| t.max(event.at)));
+    }
+
+    let mut rows: Vec<RateLimitGroupSummary> = groups
+        .into_iter()
+        .map(|(group, acc)| RateLimitGroupSummary {
+            group,
+            routes: acc.routes.into_iter().collect(),
+            limit: acc.limit,
+            window_minutes: acc.window_minutes,
+            min_remaining: acc.min_remaining,
+            character_count: acc.character_count,
+            soonest_reset: acc.soonest_reset.map(|t| t.to_rfc3339()),
+            rate_limited_count: acc.rate_limited_count,
+            error_limited_count: acc.error_limited_count,
+            last_throttled_at: acc.last_throttled_at.map(|t| t.to_rfc3339()),
+        })
+        .collect();
+
+    // Most constrained groups first: by remaining share of the limit.
+    rows.sort_by(|a, b| {
+        let share = |r: &RateLimitGroupSummary| match (r.min_remaining, r.limit) {
+            (Some(remaining), Some(limit)) if limit > 0 => remaining as f64 / limit as f64,
+            _ => 1.0,
+        };
+        share(a)
+            .total_cmp(&share(b))
+            .then_with(|| a.group.cmp(&b.group))
+    });
+    rows
+}
+
+#[tauri::command]
+pub async fn get_rate_limit_dashboard(
+    rate_limits: State<'_, esi::RateLimitStore>,
+) -> Result<Vec<RateLimitGroupSummary>, String> {
+    let mut store = rate_limits.write().await;
+    store.prune_throttled(Utc::now());
+    Ok(build_dashboard(&store))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(group: &str, remaining: i32) -> esi::RateLimitInfo {
+        esi::RateLimitInfo {
+            group: group.to_string(),
+            limit: 150,
+            remaining,
+            window_minutes: 15,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn aggregates_groups_across_characters_and_counts_throttling() {
+        let mut state = esi::RateLimitState::default();
+        state
+            .limits
+            .entry(1)
+            .or_default()
+            .insert("char-detail".into(), info("char-detail", 120));
+        state
+            .limits
+            .entry(2)
+            .or_default()
+            .insert("char-detail".into(), info("char-detail", 40));
+        state
+            .limits
+            .entry(1)
+            .or_default()
+            .insert("char-social".into(), info("char-social", 150));
+        state
+            .group_routes
+            .entry("char-detail".into())
+            .or_default()
+            .insert("characters/{id}/skillqueue".into());
+        for status in [429, 420] {
+            state.throttled.push_back(esi::cached::ThrottleEvent {
+                route: "characters/{id}/skillqueue".into(),
+                group: None,
+                status,
+                at: Utc::now(),
+            });
+        }
+
+        let rows = build_dashboard(&state);
+        assert_eq!(rows[0].group, "char-detail");
+        assert_eq!(rows[0].min_remaining, Some(40));
+        assert_eq!(rows[0].character_count, 2);
+        assert_eq!(rows[0].rate_limited_count, 1);
+        assert_eq!(rows[0].error_limited_count, 1);
+        assert_eq!(rows[0].routes, vec!["characters/{id}/skillqueue"]);
+        assert_eq!(rows[1].group, "char-social");
+    }
+
+    #[test]
+    fn route_family_replaces_ids() {
+        assert_eq!(
+            esi::cached::route_family("characters/2112625428/skillqueue"),
+            "characters/{id}/skillqueue"
+        );
+        assert_eq!(
+            esi::cached::route_family("ui/openwindow/information?target_id=1"),
+            "ui/openwindow/information"
+        );
+    }
+}
@@ -2,7 +2,8 @@ use tauri::State;
 
 use crate::db;
 use crate::db::remaps::Remap;
-use crate::skill_plans::Attributes;
+use crate::skill_plans::remap_wizard::{self, RemapWizardResult};
+use crate::skill_plans::{Attributes, PlannedRemap};
 
 #[tauri::command]
 pub async fn save_remap(
@@ -25,6 +26,74 @@ pub async fn save_remap(
     .map_err(|e| format!("Failed to save remap: {}", e))
 }
 
+#[tauri::command]
+pub async fn update_remap(
+    pool: State<'_, db::Pool>,
+    remap_id: i64,
+    after_skill_type_id: Option<i64>,
+    after_skill_level: Option<i64>,
+    attributes: Attributes,
+) -> Result<(), String> {
+    db::remaps::update_remap(
+        &pool,
+        remap_id,
+        after_skill_type_id,
+        after_skill_level,
+        &attributes,
+    )
+    .await
+    .map_err(|e| format!("Failed to update remap: {}", e))
+}
+
+/// Stores an optimizer's recommended remaps as the plan's remap markers,
+/// replacing the ones it had. `entry_index` is resolved against the plan's
+/// current order, so a reordering result must be applied first.
+#[tauri::command]
+pub async fn save_planned_remaps(
+    pool: State<'_, db::Pool>,
+    plan_id: i64,
+    remaps: Vec<PlannedRemap>,
+) -> Result<Vec<Remap>, String> {
+    let entries = db::skill_plans::get_plan_entries(&*pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get plan entries: {}", e))?;
+    let order: Vec<(i64, i64)> = entries
+        .iter()
+        .map(|e| (e.skill_type_id, e.planned_level))
+        .collect();
+    let anchored = anchor_planned_remaps(&order, remaps)?;
+
+    db::remaps::replace_plan_remaps(&pool, plan_id, &anchored)
+        .await
+        .map_err(|e| format!("Failed to save remaps: {}", e))?;
+    db::remaps::get_plan_remaps(&pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get plan remaps: {}", e))
+}
+
+/// Turn each remap's `entry_index` into the `(skill_type_id, level)` of the
+/// entry it follows in `order`, the form remaps are stored in.
+pub(crate) fn anchor_planned_remaps(
+    order: &[(i64, i64)],
+    remaps: Vec<PlannedRemap>,
+) -> Result<Vec<(Option<i64>, Option<i64>, Attributes)>, String> {
+    let mut anchored = Vec::with_capacity(remaps.len());
+    for remap in remaps {
+        // A remap before entry `i` happens after entry `i - 1`.
+        let (after_skill_type_id, after_skill_level) = match remap.entry_index {
+            0 => (None, None),
+            i => {
+                let (skill_type_id, level) = order
+                    .get(i - 1)
+                    .ok_or_else(|| format!("Remap position {} is past the end of the plan", i))?;
+                (Some(*skill_type_id), Some(*level))
+            }
+        };
+        anchored.push((after_skill_type_id, after_skill_level, remap.attributes));
+    }
+    Ok(anchored)
+}
+
 #[tauri::command]
 pub async fn get_plan_remaps(
     pool: State<'_, db::Pool>,
@@ -51,3 +120,19 @@ pub async fn delete_remap(pool: State<'_, db::Pool>, remap_id: i64) -> Result<()
         .await
         .map_err(|e| format!("Failed to delete remap: {}", e))
 }
+
+#[tauri::command]
+pub async fn get_remap_wizard(
+    read_pool: State<'_, db::ReadPool>,
+    character_id: i64,
+    plan_ids: Vec<i64>,
+    days: i64,
+) -> Result<RemapWizardResult, String> {
+    if days < 1 {
+        return Err("The horizon must be at least one day".to_string());
+    }
+    let pool: &db::Pool = &read_pool;
+    remap_wizard::remap_wizard(pool, character_id, &plan_ids, days)
+        .await
+        .map_err(|e| format!("Failed to build remap advice: {}", e))
+}
@@ -0,0 +1,48 @@
+use tauri::State;
+
+use crate::db;
+use crate::scheduler::{self, ScheduledTask, ScheduledTaskStatus, Scheduler, SchedulerSettings};
+
+#[tauri::command]
+pub async fn get_scheduler_status(
+    pool: State<'_, db::Pool>,
+    scheduler: State<'_, Scheduler>,
+) -> Result<Vec<ScheduledTaskStatus>, String> {
+    let settings = scheduler::get_scheduler_settings(&pool)
+        .await
+        .map_err(|e| format!("Failed to get scheduler settings: {}", e))?;
+    Ok(scheduler.status(&settings))
+}
+
+#[tauri::command]
+pub async fn get_scheduler_settings(
+    pool: State<'_, db::Pool>,
+) -> Result<SchedulerSettings, String> {
+    scheduler::get_scheduler_settings(&pool)
+        .await
+        .map_err(|e| format!("Failed to get scheduler settings: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_scheduler_settings(
+    pool: State<'_, db::Pool>,
+    scheduler: State<'_, Scheduler>,
+    settings: SchedulerSettings,
+) -> Result<(), String> {
+    scheduler::set_scheduler_settings(&pool, &settings)
+        .await
+        .map_err(|e| format!("Failed to set scheduler settings: {}", e))?;
+    scheduler.settings_changed();
+    Ok(())
+}
+
+/// Pauses one task, or all of them when `task` is omitted.
+#[tauri::command]
+pub fn pause_scheduled_task(scheduler: State<'_, Scheduler>, task: Option<ScheduledTask>) {
+    scheduler.set_paused(task, true);
+}
+
+#[tauri::command]
+pub fn resume_scheduled_task(scheduler: State<'_, Scheduler>, task: Option<ScheduledTask>) {
+    scheduler.set_paused(task, false);
+}
@@ -21,6 +21,13 @@ pub async fn refresh_sde(app: tauri::AppHandle, pool: State<'_, db::Pool>) -> Re
         .map_err(|e| format!("Failed to refresh SDE: {}", e))
 }
 
+#[tauri::command]
+pub async fn get_sde_status(pool: State<'_, db::Pool>) -> Result<sde::SdeStatus, String> {
+    sde::status(&pool)
+        .await
+        .map_err(|e| format!("Failed to get SDE status: {}", e))
+}
+
 #[tauri::command]
 pub async fn get_type_names(
     pool: State<'_, db::Pool>,
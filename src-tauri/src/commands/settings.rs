@@ -1,10 +1,12 @@
+use crate::app_log;
 use crate::db;
 use crate::esi::EsiScope;
-use crate::features::{self, FeatureId, OptionalFeature};
+use crate::features::{self, CharacterModule, FeatureId, OptionalFeature, ScopeAudit};
+use crate::metrics::MetricsServer;
 use crate::ts_types::i64_ts;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tauri::State;
+use tauri::{AppHandle, State};
 use typeshare::typeshare;
 
 #[typeshare]
@@ -27,12 +29,14 @@ pub struct CharacterFeatureScopeStatus {
 #[serde(rename_all = "snake_case")]
 pub enum BooleanAppSettingKey {
     StartMinimized,
+    DebugLogging,
 }
 
 impl BooleanAppSettingKey {
     fn as_str(&self) -> &'static str {
         match self {
             BooleanAppSettingKey::StartMinimized => "start_minimized",
+            BooleanAppSettingKey::DebugLogging => app_log::DEBUG_LOGGING_SETTING,
         }
     }
 }
@@ -41,6 +45,8 @@ impl BooleanAppSettingKey {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
     pub start_minimized: bool,
+    /// Writes debug records, such as optimizer timings, to the log file.
+    pub debug_logging: bool,
 }
 
 #[tauri::command]
@@ -48,8 +54,14 @@ pub async fn get_app_settings(pool: State<'_, db::Pool>) -> Result<AppSettings,
     let start_minimized = db::get_boolean_app_setting(&pool, "start_minimized")
         .await
         .map_err(|e| format!("Failed to get app settings: {}", e))?;
+    let debug_logging = db::get_boolean_app_setting(&pool, app_log::DEBUG_LOGGING_SETTING)
+        .await
+        .map_err(|e| format!("Failed to get app settings: {}", e))?;
 
-    Ok(AppSettings { start_minimized })
+    Ok(AppSettings {
+        start_minimized,
+        debug_logging,
+    })
 }
 
 #[tauri::command]
@@ -96,7 +108,11 @@ pub async fn set_boolean_app_setting(
 ) -> Result<(), String> {
     db::set_boolean_app_setting(&pool, key.as_str(), value)
         .await
-        .map_err(|e| format!("Failed to set {}: {}", key.as_str(), e))
+        .map_err(|e| format!("Failed to set {}: {}", key.as_str(), e))?;
+    if let BooleanAppSettingKey::DebugLogging = key {
+        app_log::set_debug(value);
+    }
+    Ok(())
 }
 
 #[tauri::command]
@@ -117,6 +133,62 @@ pub async fn set_feature_enabled(
         .map_err(|e| format!("Failed to set feature enabled: {}", e))
 }
 
+/// The data modules the app fetches and checks for this character.
+#[tauri::command]
+pub async fn get_character_features(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+) -> Result<Vec<CharacterModule>, String> {
+    let disabled = db::character_modules::get_disabled_modules(&pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to get character features: {}", e))?;
+    Ok(CharacterModule::ALL
+        .into_iter()
+        .filter(|m| !disabled.contains(m))
+        .collect())
+}
+
+/// Keeps only `features` on for the character; refreshes and notification
+/// checks leave the other modules alone from their next run.
+#[tauri::command]
+pub async fn set_character_features(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+    features: Vec<CharacterModule>,
+) -> Result<(), String> {
+    db::character_modules::set_character_modules(&pool, character_id, &features)
+        .await
+        .map_err(|e| format!("Failed to set character features: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_metrics_server_settings(
+    pool: State<'_, db::Pool>,
+) -> Result<db::app_settings::MetricsServerSettings, String> {
+    db::app_settings::get_metrics_server_settings(&pool)
+        .await
+        .map_err(|e| format!("Failed to get metrics server settings: {}", e))
+}
+
+/// Saves the settings and starts, moves or stops the `/metrics` listener to
+/// match.
+#[tauri::command]
+pub async fn set_metrics_server_settings(
+    app: AppHandle,
+    pool: State<'_, db::Pool>,
+    metrics_server: State<'_, MetricsServer>,
+    settings: db::app_settings::MetricsServerSettings,
+) -> Result<(), String> {
+    if !u16::try_from(settings.port).is_ok_and(|port| port != 0) {
+        return Err(format!("Invalid port: {}", settings.port));
+    }
+    db::app_settings::set_metrics_server_settings(&pool, &settings)
+        .await
+        .map_err(|e| format!("Failed to save metrics server settings: {}", e))?;
+    metrics_server.apply(&app, &settings);
+    Ok(())
+}
+
 #[tauri::command]
 pub fn get_optional_features() -> Vec<OptionalFeature> {
     features::get_optional_features()
@@ -195,3 +267,132 @@ pub async fn get_character_feature_scope_status(
 
     Ok(result)
 }
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct CharacterScopeAudit {
+    pub character_id: i64_ts,
+    pub character_name: String,
+    /// False when the character has no stored token; everything then reads as
+    /// not granted.
+    pub has_token: bool,
+    pub audit: ScopeAudit,
+}
+
+#[tauri::command]
+pub async fn get_character_scopes(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+) -> Result<CharacterScopeAudit, String> {
+    let character = db::get_character(&pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to get character: {}", e))?
+        .ok_or_else(|| format!("Character {} not found", character_id))?;
+
+    let tokens = db::get_tokens(&pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to get tokens: {}", e))?;
+    let granted: Vec<String> = tokens
+        .as_ref()
+        .and_then(|t| t.scopes.as_deref())
+        .map(|json| serde_json::from_str(json).unwrap_or_default())
+        .unwrap_or_default();
+
+    let enabled_features = db::get_enabled_features(&pool)
+        .await
+        .map_err(|e| format!("Failed to get enabled features: {}", e))?;
+
+    Ok(CharacterScopeAudit {
+        character_id,
+        character_name: character.character_name,
+        has_token: tokens.is_some(),
+        audit: features::audit_scopes(&granted, &enabled_features),
+    })
+}
+
+#[tauri::command]
+pub async fn get_refresh_dampening(
+    pool: State<'_, db::Pool>,
+) -> Result<db::app_settings::RefreshDampening, String> {
+    db::app_settings::get_refresh_dampening(&pool)
+        .await
+        .map_err(|e| format!("Failed to get refresh dampening settings: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_refresh_dampening(
+    pool: State<'_, db::Pool>,
+    settings: db::app_settings::RefreshDampening,
+) -> Result<(), String> {
+    if settings.hidden_after_minutes < 0 || settings.dampened_interval_minutes < 1 {
+        return Err("Refresh dampening thresholds must be positive".to_string());
+    }
+    db::app_settings::set_refresh_dampening(&pool, &settings)
+        .await
+        .map_err(|e| format!("Failed to save refresh dampening settings: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_sde_download_settings(
+    pool: State<'_, db::Pool>,
+) -> Result<db::app_settings::SdeDownloadSettings, String> {
+    db::app_settings::get_sde_download_settings(&pool)
+        .await
+        .map_err(|e| format!("Failed to get SDE download settings: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_sde_download_settings(
+    pool: State<'_, db::Pool>,
+    settings: db::app_settings::SdeDownloadSettings,
+) -> Result<(), String> {
+    if !(1..=8).contains(&settings.connections) {
+        return Err("SDE download connections must be between 1 and 8".to_string());
+    }
+    if !(0..=20).contains(&settings.max_retries) {
+        return Err("SDE download retries must be between 0 and 20".to_string());
+    }
+    db::app_settings::set_sde_download_settings(&pool, &settings)
+        .await
+        .map_err(|e| format!("Failed to save SDE download settings: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_sde_import_mode(
+    pool: State<'_, db::Pool>,
+) -> Result<db::app_settings::SdeImportMode, String> {
+    db::app_settings::get_sde_import_mode(&pool)
+        .await
+        .map_err(|e| format!("Failed to get SDE import mode: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_level_format(
+    pool: State<'_, db::Pool>,
+) -> Result<db::app_settings::LevelFormat, String> {
+    db::app_settings::get_level_format(&pool)
+        .await
+        .map_err(|e| format!("Failed to get level format: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_level_format(
+    pool: State<'_, db::Pool>,
+    format: db::app_settings::LevelFormat,
+) -> Result<(), String> {
+    db::app_settings::set_level_format(&pool, format)
+        .await
+        .map_err(|e| format!("Failed to save level format: {}", e))
+}
+
+/// Saves the mode; the next SDE check (or `refresh_sde`) re-imports the
+/// current build with it.
+#[tauri::command]
+pub async fn set_sde_import_mode(
+    pool: State<'_, db::Pool>,
+    mode: db::app_settings::SdeImportMode,
+) -> Result<(), String> {
+    db::app_settings::set_sde_import_mode(&pool, mode)
+        .await
+        .map_err(|e| format!("Failed to save SDE import mode: {}", e))
+}
@@ -1,18 +1,35 @@
+use flate2::read::GzDecoder;
 use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
 use quick_xml::reader::Reader;
 use quick_xml::writer::Writer;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::io::Cursor;
-use tauri::State;
+use std::io::{Cursor, Read};
+use tauri::{AppHandle, State};
 use typeshare::typeshare;
 
 use crate::db;
+use crate::db::planned_boosters::PlannedBooster;
+use crate::db::remaps::Remap;
+use crate::skill_plans::alpha::{self, AlphaPlanWarnings, CloneState, OmegaOnlyEntry};
+use crate::skill_plans::clipboard::{self, ClipboardFormat};
+use crate::skill_plans::evemon::{self, EvemonImportResult};
 use crate::skill_plans::graph::{PlanDag, PlanNode};
-use crate::skill_plans::optimization::{self, OptimizationResult, ReorderOptimizationResult};
+use crate::skill_plans::import::{self, ConflictStrategy, ImportSummary, ImportedEntry};
+use crate::skill_plans::next_goals::{self, NextGoal};
+use crate::skill_plans::optimization::{
+    self, CrossPlanOptimizationResult, ImplantRecommendation, OptimizationControl,
+    OptimizationProgress, OptimizationRegistry, OptimizationResult, ReorderOptimizationResult,
+};
 use crate::skill_plans::plan_from_character::{self, PreviewPlanFromCharacterGroup};
+use crate::skill_plans::queue_sync::{self, PlanQueueSync};
+use crate::skill_plans::remap_compare::{self, RemapScheduleComparison};
 use crate::skill_plans::simulation::{self, SimulationProfile, SimulationResult};
-use crate::skill_plans::{Attributes, SkillmonPlan, SkillmonPlanEntry};
+use crate::skill_plans::sorting::{self, PlanSortPreset};
+use crate::skill_plans::what_if::{self, WhatIfOverrides, WhatIfTimeline};
+use crate::skill_plans::{
+    Attributes, PlannedRemap, SkillmonPlan, SkillmonPlanBooster, SkillmonPlanEntry,
+};
 use crate::ts_types::{i64_ts, usize_ts};
 use crate::utils;
 
@@ -21,26 +38,31 @@ pub async fn export_skill_plan_json(
     pool: State<'_, db::Pool>,
     plan_id: i64,
 ) -> Result<SkillmonPlan, String> {
-    let plan = db::skill_plans::get_skill_plan(&*pool, plan_id)
+    export_skill_plan_json_inner(&pool, plan_id).await
+}
+
+async fn export_skill_plan_json_inner(
+    pool: &db::Pool,
+    plan_id: i64,
+) -> Result<SkillmonPlan, String> {
+    let plan = db::skill_plans::get_skill_plan(pool, plan_id)
         .await
         .map_err(|e| format!("Failed to get plan: {}", e))?
         .ok_or_else(|| "Plan not found".to_string())?;
 
-    let entries = db::skill_plans::get_plan_entries(&*pool, plan_id)
+    let entries = db::skill_plans::get_plan_entries(pool, plan_id)
         .await
         .map_err(|e| format!("Failed to get entries: {}", e))?;
 
-    let json_entries = entries
-        .into_iter()
-        .map(|e| SkillmonPlanEntry {
-            skill_type_id: e.skill_type_id,
-            level: e.planned_level,
-            entry_type: e.entry_type,
-            notes: e.notes,
-        })
-        .collect();
+    let mut json_entries: Vec<SkillmonPlanEntry> =
+        entries.into_iter().map(SkillmonPlanEntry::from).collect();
+
+    let comments = db::plan_comments::get_plan_comments(pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get comments: {}", e))?;
+    crate::skill_plans::attach_comments(&mut json_entries, comments);
 
-    let remaps = db::remaps::get_plan_remaps(&pool, plan_id)
+    let remaps = db::remaps::get_plan_remaps(pool, plan_id)
         .await
         .map_err(|e| format!("Failed to get remaps: {}", e))?;
 
@@ -53,6 +75,10 @@ pub async fn export_skill_plan_json(
         })
         .collect();
 
+    let boosters = db::planned_boosters::get_plan_boosters(pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get planned boosters: {}", e))?;
+
     Ok(SkillmonPlan {
         version: SkillmonPlan::CURRENT_VERSION,
         name: plan.name,
@@ -60,6 +86,10 @@ pub async fn export_skill_plan_json(
         auto_prerequisites: plan.auto_prerequisites != 0,
         entries: json_entries,
         remaps: json_remaps,
+        boosters: boosters
+            .into_iter()
+            .map(SkillmonPlanBooster::from)
+            .collect(),
     })
 }
 
@@ -78,7 +108,7 @@ fn log_import_error(format: &str, detail: String) -> String {
 }
 
 fn is_user_facing_import_error(detail: &str) -> bool {
-    const USER_PREFIXES: [&str; 7] = [
+    const USER_PREFIXES: [&str; 10] = [
         "Unmatched skills:",
         "No valid entries found",
         "No entries found in XML",
@@ -86,6 +116,9 @@ fn is_user_facing_import_error(detail: &str) -> bool {
         "Invalid level in line:",
         "Level must be between 1 and 5",
         "Invalid plan:",
+        "Invalid .emp file:",
+        "Invalid fit:",
+        crate::sde::REQUIRES_FULL_SDE,
     ];
     USER_PREFIXES.iter().any(|p| detail.starts_with(p))
 }
@@ -95,15 +128,14 @@ pub async fn import_skill_plan_json(
     pool: State<'_, db::Pool>,
     plan: SkillmonPlan,
 ) -> Result<i64, String> {
-    import_skill_plan_json_inner(pool, plan)
+    import_skill_plan_json_inner(&pool, plan)
         .await
         .map_err(|e| log_import_error("json", e))
 }
 
-async fn import_skill_plan_json_inner(
-    pool: State<'_, db::Pool>,
-    plan: SkillmonPlan,
-) -> Result<i64, String> {
+async fn import_skill_plan_json_inner(pool: &db::Pool, plan: SkillmonPlan) -> Result<i64, String> {
+    let plan = plan.migrate().map_err(|e| e.to_string())?;
+
     // 1. Validate the plan first
     let mut dag = PlanDag::new();
     let mut proposed_nodes = Vec::new();
@@ -112,7 +144,7 @@ async fn import_skill_plan_json_inner(
             skill_type_id: entry.skill_type_id,
             level: entry.level,
         };
-        dag.add_node(&pool, node)
+        dag.add_node(pool, node)
             .await
             .map_err(|e| format!("Failed to build DAG for validation: {}", e))?;
         proposed_nodes.push(node);
@@ -146,7 +178,7 @@ async fn import_skill_plan_json_inner(
         }
 
         let type_names = if !all_type_ids.is_empty() {
-            utils::get_type_names(&pool, &all_type_ids.into_iter().collect::<Vec<_>>()).await?
+            utils::get_type_names(pool, &all_type_ids.into_iter().collect::<Vec<_>>()).await?
         } else {
             std::collections::HashMap::new()
         };
@@ -183,7 +215,7 @@ async fn import_skill_plan_json_inner(
 
     // 2. If valid, proceed with import
     let plan_id = db::skill_plans::create_skill_plan(
-        &pool,
+        pool,
         &plan.name,
         plan.description.as_deref(),
         plan.auto_prerequisites,
@@ -199,8 +231,8 @@ async fn import_skill_plan_json_inner(
 
     for (index, entry) in plan.entries.iter().enumerate() {
         sqlx::query(
-            "INSERT INTO skill_plan_entries (plan_id, skill_type_id, planned_level, sort_order, entry_type, notes)
-             VALUES (?, ?, ?, ?, ?, ?)"
+            "INSERT INTO skill_plan_entries (plan_id, skill_type_id, planned_level, sort_order, entry_type, notes, excluded, priority)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)"
         )
         .bind(plan_id)
         .bind(entry.skill_type_id)
@@ -208,9 +240,25 @@ async fn import_skill_plan_json_inner(
         .bind(index as i64)
         .bind(&entry.entry_type)
         .bind(&entry.notes)
+        .bind(entry.excluded)
+        .bind(entry.priority)
         .execute(&mut *tx)
         .await
         .map_err(|e| format!("Failed to insert entry: {}", e))?;
+
+        for comment in &entry.comments {
+            db::plan_comments::add_plan_comment(
+                &mut *tx,
+                plan_id,
+                entry.skill_type_id,
+                entry.level,
+                &comment.author,
+                &comment.body,
+                comment.created_at,
+            )
+            .await
+            .map_err(|e| format!("Failed to insert comment: {}", e))?;
+        }
     }
 
     for remap in &plan.remaps {
@@ -226,6 +274,20 @@ async fn import_skill_plan_json_inner(
         .map_err(|e| format!("Failed to insert remap: {}", e))?;
     }
 
+    for booster in &plan.boosters {
+        db::planned_boosters::save_planned_booster(
+            &mut *tx,
+            plan_id,
+            booster.type_id,
+            booster.bonus,
+            booster.duration_seconds,
+            booster.after_skill_type_id,
+            booster.after_skill_level,
+        )
+        .await
+        .map_err(|e| format!("Failed to insert planned booster: {}", e))?;
+    }
+
     tx.commit()
         .await
         .map_err(|e| format!("Failed to commit: {}", e))?;
@@ -244,6 +306,8 @@ pub struct SkillPlanResponse {
     pub updated_at: i64_ts,
     pub group_id: Option<i64_ts>,
     pub sort_order: i64_ts,
+    /// Default character for comparisons and exports; see [`set_plan_owner`].
+    pub owner_character_id: Option<i64_ts>,
 }
 
 impl From<db::skill_plans::SkillPlan> for SkillPlanResponse {
@@ -257,6 +321,7 @@ impl From<db::skill_plans::SkillPlan> for SkillPlanResponse {
             updated_at: p.updated_at,
             group_id: p.group_id,
             sort_order: p.sort_order,
+            owner_character_id: p.owner_character_id,
         }
     }
 }
@@ -272,8 +337,18 @@ pub struct SkillPlanEntryResponse {
     pub sort_order: i64_ts,
     pub entry_type: String,
     pub notes: Option<String>,
+    pub excluded: bool,
+    /// 1 (highest) to 5 (lowest).
+    pub priority: i64_ts,
     pub rank: Option<i64_ts>,
     pub skillpoints_for_level: i64_ts,
+    /// Only set when a character was given; see [`CharacterTraining`].
+    pub training_seconds: Option<i64_ts>,
+    /// Training time up to and including this entry.
+    pub cumulative_training_seconds: Option<i64_ts>,
+    /// The skill is flagged `canNotBeTrainedOnTrial`: Alpha clones cannot
+    /// train it at all.
+    pub requires_omega: bool,
 }
 
 #[typeshare]
@@ -281,6 +356,13 @@ pub struct SkillPlanEntryResponse {
 pub struct SkillPlanWithEntriesResponse {
     pub plan: SkillPlanResponse,
     pub entries: Vec<SkillPlanEntryResponse>,
+    /// Remap markers, anchored after an entry's skill and level.
+    pub remaps: Vec<Remap>,
+    /// The character training times are for: the one asked for, else the
+    /// plan's owner.
+    pub character_id: Option<i64_ts>,
+    /// That character's comparison, when requested with `include_comparison`.
+    pub comparison: Option<PlanComparisonResponse>,
 }
 
 #[typeshare]
@@ -296,6 +378,9 @@ pub struct PlanComparisonResponse {
     pub plan: SkillPlanResponse,
     pub character_id: i64_ts,
     pub entries: Vec<PlanComparisonEntry>,
+    /// Set for Alpha characters: entries they cannot train, which also get
+    /// no training time.
+    pub alpha_warnings: Option<AlphaPlanWarnings>,
 }
 
 #[typeshare]
@@ -314,6 +399,10 @@ pub struct PlanComparisonEntry {
     pub current_skillpoints: i64_ts,
     pub missing_skillpoints: i64_ts,
     pub status: String,
+    pub excluded: bool,
+    /// `None` when the character has no stored attributes yet.
+    pub training_seconds: Option<i64_ts>,
+    pub cumulative_training_seconds: Option<i64_ts>,
 }
 
 #[tauri::command]
@@ -369,17 +458,7 @@ async fn create_merged_skill_plan_inner(
 
         let entries = db::skill_plans::get_plan_entries(pool, *plan_id).await?;
 
-        sources.push(
-            entries
-                .into_iter()
-                .map(|e| SkillmonPlanEntry {
-                    skill_type_id: e.skill_type_id,
-                    level: e.planned_level,
-                    entry_type: e.entry_type,
-                    notes: e.notes,
-                })
-                .collect(),
-        );
+        sources.push(entries.into_iter().map(SkillmonPlanEntry::from).collect());
     }
 
     let merged = crate::skill_plans::merge::merge_plan_entries(&sources);
@@ -415,8 +494,8 @@ async fn create_merged_skill_plan_inner(
 
     for (index, entry) in merged.iter().enumerate() {
         sqlx::query(
-            "INSERT INTO skill_plan_entries (plan_id, skill_type_id, planned_level, sort_order, entry_type, notes)
-             VALUES (?, ?, ?, ?, ?, ?)",
+            "INSERT INTO skill_plan_entries (plan_id, skill_type_id, planned_level, sort_order, entry_type, notes, excluded, priority)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
         )
         .bind(plan_id)
         .bind(entry.skill_type_id)
@@ -424,6 +503,8 @@ async fn create_merged_skill_plan_inner(
         .bind(index as i64)
         .bind(&entry.entry_type)
         .bind(&entry.notes)
+        .bind(entry.excluded)
+        .bind(entry.priority)
         .execute(&mut *tx)
         .await?;
     }
@@ -433,6 +514,13 @@ async fn create_merged_skill_plan_inner(
     Ok(plan_id)
 }
 
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanImportResponse {
+    pub plan: SkillPlanWithEntriesResponse,
+    pub summary: ImportSummary,
+}
+
 #[typeshare]
 #[derive(Debug, Clone, Serialize)]
 pub struct MergeIntoPlanResponse {
@@ -450,7 +538,7 @@ pub async fn merge_plans_into(
         .await
         .map_err(|e| e.to_string())?;
 
-    let plan = get_skill_plan_with_entries(pool, target_plan_id)
+    let plan = get_skill_plan_with_entries(pool, target_plan_id, None, None)
         .await?
         .ok_or_else(|| "Failed to retrieve target plan after merge".to_string())?;
 
@@ -481,15 +569,7 @@ async fn merge_plans_into_inner(
     }
 
     let to_entries = |entries: Vec<db::skill_plans::SkillPlanEntry>| -> Vec<SkillmonPlanEntry> {
-        entries
-            .into_iter()
-            .map(|e| SkillmonPlanEntry {
-                skill_type_id: e.skill_type_id,
-                level: e.planned_level,
-                entry_type: e.entry_type,
-                notes: e.notes,
-            })
-            .collect()
+        entries.into_iter().map(SkillmonPlanEntry::from).collect()
     };
 
     // Read the target and sources, then append, all inside one transaction so a
@@ -548,11 +628,12 @@ async fn merge_plans_into_inner(
     if appended.is_empty() {
         return Ok(0);
     }
+    db::plan_revisions::record_revision_in(&mut tx, target_plan_id, "merge_plans_into").await?;
 
     for (offset, entry) in appended.iter().enumerate() {
         sqlx::query(
-            "INSERT INTO skill_plan_entries (plan_id, skill_type_id, planned_level, sort_order, entry_type, notes)
-             VALUES (?, ?, ?, ?, ?, ?)",
+            "INSERT INTO skill_plan_entries (plan_id, skill_type_id, planned_level, sort_order, entry_type, notes, excluded, priority)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
         )
         .bind(target_plan_id)
         .bind(entry.skill_type_id)
@@ -560,6 +641,8 @@ async fn merge_plans_into_inner(
         .bind((target_len + offset) as i64)
         .bind(&entry.entry_type)
         .bind(&entry.notes)
+        .bind(entry.excluded)
+        .bind(entry.priority)
         .execute(&mut *tx)
         .await?;
     }
@@ -573,6 +656,173 @@ async fn merge_plans_into_inner(
     Ok(appended.len())
 }
 
+#[typeshare]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MoveEntriesSummary {
+    /// Selected entries taken out of the source plan.
+    pub moved: usize_ts,
+    /// Prerequisites the target plan lacked, added alongside the moved entries.
+    pub prerequisites_added: usize_ts,
+}
+
+#[tauri::command]
+pub async fn move_entries_to_plan(
+    pool: State<'_, db::Pool>,
+    source_plan_id: i64,
+    entry_ids: Vec<i64>,
+    target_plan_id: i64,
+) -> Result<MoveEntriesSummary, String> {
+    move_entries_to_plan_inner(&pool, source_plan_id, &entry_ids, target_plan_id)
+        .await
+        .map_err(|e| format!("Failed to move entries: {}", e))
+}
+
+/// Move the selected source entries into the target plan, keeping their entry
+/// type, notes and exclusion, and add whatever prerequisites the target lacks.
+/// The target is re-sorted with its current order preferred and the moved
+/// entries after it. Refuses to move an entry that a remaining source entry
+/// depends on, so neither plan is left out of order. Levels the target already
+/// has are only removed from the source.
+async fn move_entries_to_plan_inner(
+    pool: &db::Pool,
+    source_plan_id: i64,
+    entry_ids: &[i64],
+    target_plan_id: i64,
+) -> anyhow::Result<MoveEntriesSummary> {
+    if entry_ids.is_empty() {
+        anyhow::bail!("Select at least one entry to move");
+    }
+    if source_plan_id == target_plan_id {
+        anyhow::bail!("Cannot move entries into the plan they are in");
+    }
+
+    let mut tx = pool.begin().await?;
+
+    for plan_id in [source_plan_id, target_plan_id] {
+        if db::skill_plans::get_skill_plan(&mut *tx, plan_id)
+            .await?
+            .is_none()
+        {
+            anyhow::bail!("Plan {} does not exist", plan_id);
+        }
+    }
+
+    let selected_ids: HashSet<i64> = entry_ids.iter().copied().collect();
+    let (selected, remaining): (Vec<_>, Vec<_>) =
+        db::skill_plans::get_plan_entries(&mut *tx, source_plan_id)
+            .await?
+            .into_iter()
+            .partition(|e| selected_ids.contains(&e.entry_id));
+    if selected.len() != selected_ids.len() {
+        anyhow::bail!("Some selected entries are not in the source plan");
+    }
+    let node = |e: &db::skill_plans::SkillPlanEntry| PlanNode {
+        skill_type_id: e.skill_type_id,
+        level: e.planned_level,
+    };
+    let selected_nodes: HashSet<PlanNode> = selected.iter().map(node).collect();
+
+    // The DAGs read only SDE data, so using the pool while the write tx is
+    // open is safe under WAL.
+    let mut source_dag = PlanDag::new();
+    for entry in &remaining {
+        source_dag.add_node(pool, node(entry)).await?;
+    }
+    let still_needed = remaining.iter().any(|entry| {
+        source_dag
+            .dependencies
+            .get(&node(entry))
+            .is_some_and(|deps| deps.iter().any(|d| selected_nodes.contains(d)))
+    });
+    if still_needed {
+        anyhow::bail!("A selected entry is still required by an entry left in the source plan");
+    }
+
+    let target_entries = db::skill_plans::get_plan_entries(&mut *tx, target_plan_id).await?;
+    let target_nodes: HashSet<PlanNode> = target_entries.iter().map(node).collect();
+    let mut target_dag = PlanDag::new();
+    for entry in &target_entries {
+        target_dag.add_node(pool, node(entry)).await?;
+    }
+    for entry in &selected {
+        target_dag.add_recursive(pool, node(entry)).await?;
+    }
+    let mut preferred_order: Vec<PlanNode> = target_entries.iter().map(node).collect();
+    preferred_order.extend(selected.iter().map(node));
+    let sorted = target_dag.topological_sort(&preferred_order);
+
+    for plan_id in [source_plan_id, target_plan_id] {
+        db::plan_revisions::record_revision_in(&mut tx, plan_id, "move_entries_to_plan").await?;
+    }
+    for entry in &selected {
+        sqlx::query("DELETE FROM skill_plan_entries WHERE entry_id = ?")
+            .bind(entry.entry_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    for (index, entry) in remaining.iter().enumerate() {
+        sqlx::query("UPDATE skill_plan_entries SET sort_order = ? WHERE entry_id = ?")
+            .bind(index as i64)
+            .bind(entry.entry_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    let moved_by_node: HashMap<PlanNode, &db::skill_plans::SkillPlanEntry> =
+        selected.iter().map(|e| (node(e), e)).collect();
+    let mut summary = MoveEntriesSummary {
+        moved: selected.len(),
+        ..MoveEntriesSummary::default()
+    };
+    for (index, target_node) in sorted.iter().enumerate() {
+        if target_nodes.contains(target_node) {
+            sqlx::query(
+                "UPDATE skill_plan_entries SET sort_order = ?
+                 WHERE plan_id = ? AND skill_type_id = ? AND planned_level = ?",
+            )
+            .bind(index as i64)
+            .bind(target_plan_id)
+            .bind(target_node.skill_type_id)
+            .bind(target_node.level)
+            .execute(&mut *tx)
+            .await?;
+            continue;
+        }
+        let moved = moved_by_node.get(target_node);
+        if moved.is_none() {
+            summary.prerequisites_added += 1;
+        }
+        sqlx::query(
+            "INSERT INTO skill_plan_entries (plan_id, skill_type_id, planned_level, sort_order, entry_type, notes, excluded, priority)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(target_plan_id)
+        .bind(target_node.skill_type_id)
+        .bind(target_node.level)
+        .bind(index as i64)
+        .bind(moved.map_or(db::skill_plans::ENTRY_TYPE_PREREQUISITE, |e| {
+            e.entry_type.as_str()
+        }))
+        .bind(moved.and_then(|e| e.notes.as_deref()))
+        .bind(moved.is_some_and(|e| e.excluded))
+        .bind(moved.map_or(db::skill_plans::DEFAULT_ENTRY_PRIORITY, |e| e.priority))
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    for plan_id in [source_plan_id, target_plan_id] {
+        sqlx::query("UPDATE skill_plans SET updated_at = ? WHERE plan_id = ?")
+            .bind(now)
+            .bind(plan_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    Ok(summary)
+}
+
 #[typeshare]
 #[derive(Debug, Clone, Deserialize)]
 pub struct ReplacePlanEntryInput {
@@ -580,6 +830,10 @@ pub struct ReplacePlanEntryInput {
     pub planned_level: i64_ts,
     pub entry_type: String,
     pub notes: Option<String>,
+    #[serde(default)]
+    pub excluded: bool,
+    /// Defaults to [`db::skill_plans::DEFAULT_ENTRY_PRIORITY`].
+    pub priority: Option<i64_ts>,
 }
 
 /// Replace a plan's entries with an exact supplied snapshot (clear + insert),
@@ -620,14 +874,19 @@ pub async fn replace_plan_entries(
             planned_level: e.planned_level,
             entry_type: e.entry_type,
             notes: e.notes,
+            excluded: e.excluded,
+            priority: e
+                .priority
+                .unwrap_or(db::skill_plans::DEFAULT_ENTRY_PRIORITY),
         })
         .collect();
 
+    super::plan_history::record_revision(&pool, plan_id, "replace_plan_entries").await;
     db::skill_plans::replace_plan_entries(&pool, plan_id, &rows)
         .await
         .map_err(|e| format!("Failed to replace plan entries: {}", e))?;
 
-    get_skill_plan_with_entries(pool, plan_id)
+    get_skill_plan_with_entries(pool, plan_id, None, None)
         .await?
         .ok_or_else(|| "Plan not found after replacing entries".to_string())
 }
@@ -724,6 +983,87 @@ pub async fn create_plan_from_character(
     Ok(plan_id)
 }
 
+#[tauri::command]
+pub async fn duplicate_skill_plan(
+    pool: State<'_, db::Pool>,
+    plan_id: i64,
+    new_name: String,
+) -> Result<i64, String> {
+    if new_name.trim().is_empty() {
+        return Err("Plan name is required".to_string());
+    }
+
+    db::skill_plans::copy_skill_plan(&pool, plan_id, new_name.trim(), &HashMap::new())
+        .await
+        .map_err(|e| format!("Failed to duplicate plan: {}", e))
+}
+
+/// Copies the template plan for `character_id`, leaving out every level the
+/// character has already trained. The copy is named after the template and
+/// the character.
+#[tauri::command]
+pub async fn create_plan_from_template(
+    pool: State<'_, db::Pool>,
+    template_id: i64,
+    character_id: i64,
+) -> Result<i64, String> {
+    let template = db::skill_plans::get_skill_plan(&*pool, template_id)
+        .await
+        .map_err(|e| format!("Failed to get template: {}", e))?
+        .ok_or_else(|| format!("Plan {} not found", template_id))?;
+    let character = db::get_character(&pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to get character: {}", e))?
+        .ok_or_else(|| format!("Character {} not found", character_id))?;
+    let trained: HashMap<i64, i64> = db::get_character_skills(&pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to get character skills: {}", e))?
+        .into_iter()
+        .map(|s| (s.skill_id, s.trained_skill_level))
+        .collect();
+
+    let name = format!("{} ({})", template.name, character.character_name);
+    let plan_id = db::skill_plans::copy_skill_plan(&pool, template_id, &name, &trained)
+        .await
+        .map_err(|e| format!("Failed to create plan from template: {}", e))?;
+    db::skill_plans::set_plan_owner(&*pool, plan_id, Some(character_id))
+        .await
+        .map_err(|e| format!("Failed to set plan owner: {}", e))?;
+
+    Ok(plan_id)
+}
+
+/// Up to five ships, weapons or drones that build on the character's strongest
+/// skill groups, with the levels each still needs.
+#[tauri::command]
+pub async fn suggest_next_goals(
+    read_pool: State<'_, db::ReadPool>,
+    character_id: i64,
+) -> Result<Vec<NextGoal>, String> {
+    let pool: &db::Pool = &read_pool;
+    crate::sde::require_full_import(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    next_goals::suggest_next_goals(pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to suggest next goals: {}", e))
+}
+
+/// Turns a suggested goal into a plan owned by the character.
+#[tauri::command]
+pub async fn create_plan_for_goal(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+    type_id: i64,
+) -> Result<i64, String> {
+    crate::sde::require_full_import(&pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    next_goals::create_goal_plan(&pool, character_id, type_id)
+        .await
+        .map_err(|e| format!("Failed to create plan for goal: {}", e))
+}
+
 #[tauri::command]
 pub async fn get_all_skill_plans(
     pool: State<'_, db::Pool>,
@@ -747,12 +1087,31 @@ pub async fn get_skill_plan(
     Ok(plan.map(SkillPlanResponse::from))
 }
 
+/// Without `character_id`, training times are for the plan's owner, if any.
+/// `include_comparison` adds that character's full comparison.
 #[tauri::command]
 pub async fn get_skill_plan_with_entries(
     pool: State<'_, db::Pool>,
     plan_id: i64,
+    character_id: Option<i64>,
+    include_comparison: Option<bool>,
 ) -> Result<Option<SkillPlanWithEntriesResponse>, String> {
-    let plan = db::skill_plans::get_skill_plan(&*pool, plan_id)
+    get_skill_plan_with_entries_inner(
+        &pool,
+        plan_id,
+        character_id,
+        include_comparison.unwrap_or(false),
+    )
+    .await
+}
+
+async fn get_skill_plan_with_entries_inner(
+    pool: &db::Pool,
+    plan_id: i64,
+    character_id: Option<i64>,
+    include_comparison: bool,
+) -> Result<Option<SkillPlanWithEntriesResponse>, String> {
+    let plan = db::skill_plans::get_skill_plan(pool, plan_id)
         .await
         .map_err(|e| format!("Failed to get skill plan: {}", e))?;
 
@@ -760,104 +1119,439 @@ pub async fn get_skill_plan_with_entries(
         Some(p) => p,
         None => return Ok(None),
     };
+    let character_id = character_id.or(plan.owner_character_id);
 
-    let entries = db::skill_plans::get_plan_entries(&*pool, plan_id)
+    let entries = db::skill_plans::get_plan_entries(pool, plan_id)
         .await
         .map_err(|e| format!("Failed to get plan entries: {}", e))?;
 
-    let skill_type_ids: Vec<i64> = entries.iter().map(|e| e.skill_type_id).collect();
-    let skill_attributes = utils::get_skill_attributes(&pool, &skill_type_ids)
-        .await
-        .map_err(|e| format!("Failed to get skill attributes: {}", e))?;
+    let training = match character_id {
+        Some(character_id) => Some(CharacterTraining::load(pool, character_id).await?),
+        None => None,
+    };
+    let entry_responses = plan_entry_responses(pool, entries, training.as_ref()).await?;
 
-    let skill_names = utils::get_type_names(&pool, &skill_type_ids)
+    let remaps = db::remaps::get_plan_remaps(pool, plan_id)
         .await
-        .map_err(|e| format!("Failed to get skill names: {}", e))?;
-
-    let mut entry_responses = Vec::new();
-    for entry in entries {
-        let skill_name = skill_names
-            .get(&entry.skill_type_id)
-            .cloned()
-            .unwrap_or_else(|| format!("Unknown Skill ({})", entry.skill_type_id));
-
-        let skill_attr = skill_attributes.get(&entry.skill_type_id);
-        let rank = skill_attr.and_then(|attr| attr.rank);
-        let skillpoints_for_level = if let Some(rank_val) = rank {
-            utils::sp_for_level_slice(rank_val, entry.planned_level as i32)
-        } else {
-            0
-        };
+        .map_err(|e| format!("Failed to get remaps: {}", e))?;
 
-        entry_responses.push(SkillPlanEntryResponse {
-            entry_id: entry.entry_id,
-            plan_id: entry.plan_id,
-            skill_type_id: entry.skill_type_id,
-            skill_name,
-            planned_level: entry.planned_level,
-            sort_order: entry.sort_order,
-            entry_type: entry.entry_type,
-            notes: entry.notes,
-            rank,
-            skillpoints_for_level,
-        });
-    }
+    let comparison = match character_id {
+        Some(character_id) if include_comparison => {
+            Some(compare_skill_plan_with_character_inner(pool, plan_id, character_id).await?)
+        }
+        _ => None,
+    };
 
     Ok(Some(SkillPlanWithEntriesResponse {
         plan: SkillPlanResponse::from(plan),
         entries: entry_responses,
+        remaps,
+        character_id,
+        comparison,
     }))
 }
 
+/// Makes `character_id` the plan's default character, or clears it with
+/// `None`. Entry lists and exports use it when no character is passed.
 #[tauri::command]
-pub async fn update_skill_plan(
+pub async fn set_plan_owner(
     pool: State<'_, db::Pool>,
     plan_id: i64,
-    name: String,
-    description: Option<String>,
-    auto_prerequisites: bool,
+    character_id: Option<i64>,
 ) -> Result<(), String> {
-    db::skill_plans::update_skill_plan(
-        &pool,
-        plan_id,
-        &name,
-        description.as_deref(),
-        auto_prerequisites,
-    )
-    .await
-    .map_err(|e| format!("Failed to update skill plan: {}", e))
+    set_plan_owner_inner(&pool, plan_id, character_id).await
 }
 
-#[tauri::command]
-pub async fn delete_skill_plan(pool: State<'_, db::Pool>, plan_id: i64) -> Result<(), String> {
-    db::skill_plans::delete_skill_plan(&pool, plan_id)
+async fn set_plan_owner_inner(
+    pool: &db::Pool,
+    plan_id: i64,
+    character_id: Option<i64>,
+) -> Result<(), String> {
+    db::skill_plans::get_skill_plan(pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get plan: {}", e))?
+        .ok_or_else(|| format!("Plan {} not found", plan_id))?;
+    if let Some(character_id) = character_id {
+        db::get_character(pool, character_id)
+            .await
+            .map_err(|e| format!("Failed to get character: {}", e))?
+            .ok_or_else(|| format!("Character {} not found", character_id))?;
+    }
+    db::skill_plans::set_plan_owner(pool, plan_id, character_id)
         .await
-        .map_err(|e| format!("Failed to delete skill plan: {}", e))
+        .map_err(|e| format!("Failed to set plan owner: {}", e))
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PlanEntryFilters {
+    pub entry_type: Option<String>,
+    /// `complete`, `in_progress` or `not_started`; needs `character_id`.
+    pub status: Option<String>,
+    pub character_id: Option<i64_ts>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanEntriesPage {
+    pub entries: Vec<SkillPlanEntryResponse>,
+    /// Entries matching the filters, across all pages.
+    pub total: i64_ts,
 }
 
+/// A slice of a plan's entries for long, virtualized plans. `offset` and
+/// `limit` count entries after filtering.
 #[tauri::command]
-pub async fn add_plan_entry(
-    pool: State<'_, db::Pool>,
+pub async fn get_plan_entries_page(
+    read_pool: State<'_, db::ReadPool>,
     plan_id: i64,
-    skill_type_id: i64,
-    planned_level: i64,
-    notes: Option<String>,
-) -> Result<SkillPlanWithEntriesResponse, String> {
-    if !(1..=5).contains(&planned_level) {
-        return Err("Planned level must be between 1 and 5".to_string());
+    offset: i64,
+    limit: i64,
+    filters: Option<PlanEntryFilters>,
+) -> Result<PlanEntriesPage, String> {
+    let pool: &db::Pool = &read_pool;
+    let filters = filters.unwrap_or_default();
+    if filters.status.is_some() && filters.character_id.is_none() {
+        return Err("Filtering by status requires a character".to_string());
     }
 
-    // 1. Build DAG and get current nodes
-    let (mut dag, current_nodes) = PlanDag::build_from_plan(&pool, plan_id)
-        .await
-        .map_err(|e| format!("Failed to build DAG: {}", e))?;
+    let filter = db::skill_plans::PlanEntryFilter {
+        entry_type: filters.entry_type,
+        status: filters.status,
+        character_id: filters.character_id,
+    };
+    let (entries, total) =
+        db::skill_plans::get_plan_entries_page(pool, plan_id, offset.max(0), limit.max(0), &filter)
+            .await
+            .map_err(|e| format!("Failed to get plan entries: {}", e))?;
 
-    let plan = db::skill_plans::get_skill_plan(&*pool, plan_id)
-        .await
-        .map_err(|e| format!("Failed to get plan: {}", e))?
-        .ok_or_else(|| "Plan not found".to_string())?;
+    Ok(PlanEntriesPage {
+        entries: plan_entry_responses(pool, entries, None).await?,
+        total,
+    })
+}
 
-    // 2. Add new node (recursively if enabled)
+/// A character's stored skills and attributes, for per-entry training time.
+/// ESI reports attributes with implants and boosters already applied.
+struct CharacterTraining {
+    skills: HashMap<i64, db::CharacterSkill>,
+    attributes: Option<db::CharacterAttributes>,
+    is_omega: bool,
+    /// Alpha level caps per skill; only loaded for Alpha characters.
+    alpha_caps: Option<HashMap<i64, i64>>,
+}
+
+impl CharacterTraining {
+    async fn load(pool: &db::Pool, character_id: i64) -> Result<Self, String> {
+        let character = db::get_character(pool, character_id)
+            .await
+            .map_err(|e| format!("Failed to get character: {}", e))?
+            .ok_or_else(|| "Character not found".to_string())?;
+        let skills = db::get_character_skills(pool, character_id)
+            .await
+            .map_err(|e| format!("Failed to get character skills: {}", e))?
+            .into_iter()
+            .map(|s| (s.skill_id, s))
+            .collect();
+        let attributes = db::get_character_attributes(pool, character_id)
+            .await
+            .map_err(|e| format!("Failed to get character attributes: {}", e))?;
+        let alpha_caps = if character.is_omega {
+            None
+        } else {
+            Some(
+                db::sde::get_alpha_skill_caps(pool)
+                    .await
+                    .map_err(|e| format!("Failed to get Alpha skill caps: {}", e))?,
+            )
+        };
+        Ok(Self {
+            skills,
+            attributes,
+            is_omega: character.is_omega,
+            alpha_caps,
+        })
+    }
+
+    /// SP still needed for `entry`; none for excluded entries or without SDE
+    /// data.
+    fn missing_sp(
+        &self,
+        entry: &db::skill_plans::SkillPlanEntry,
+        skill: Option<&utils::SkillAttributes>,
+    ) -> i64 {
+        let Some(rank) = skill.and_then(|s| s.rank).filter(|_| !entry.excluded) else {
+            return 0;
+        };
+        let char_skill = self.skills.get(&entry.skill_type_id);
+        missing_sp_for_level(
+            entry.planned_level,
+            char_skill.map_or(0, |s| s.trained_skill_level),
+            char_skill.map_or(0, |s| s.skillpoints_in_skill),
+            rank,
+        )
+    }
+
+    /// What the character cannot train while Alpha; `None` for Omega.
+    fn alpha_warnings(
+        &self,
+        entries: &[db::skill_plans::SkillPlanEntry],
+        skill_attributes: &HashMap<i64, utils::SkillAttributes>,
+    ) -> Option<AlphaPlanWarnings> {
+        let caps = self.alpha_caps.as_ref()?;
+        let missing: Vec<i64> = entries
+            .iter()
+            .map(|e| self.missing_sp(e, skill_attributes.get(&e.skill_type_id)))
+            .collect();
+        let total_sp = self.skills.values().map(|s| s.skillpoints_in_skill).sum();
+        Some(alpha::plan_warnings(entries, &missing, caps, total_sp))
+    }
+
+    /// [`Self::entry_seconds`] for every entry, leaving out entries an Alpha
+    /// cannot train.
+    fn entries_seconds(
+        &self,
+        entries: &[db::skill_plans::SkillPlanEntry],
+        skill_attributes: &HashMap<i64, utils::SkillAttributes>,
+    ) -> Vec<Option<i64>> {
+        let warnings = self.alpha_warnings(entries, skill_attributes);
+        entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                if warnings.as_ref().is_some_and(|w| w.blocks(entries, index)) {
+                    return None;
+                }
+                self.entry_seconds(entry, skill_attributes.get(&entry.skill_type_id))
+            })
+            .collect()
+    }
+
+    /// Seconds left to train `entry` from the character's current SP. Excluded
+    /// entries take none; `None` without stored attributes or SDE data.
+    fn entry_seconds(
+        &self,
+        entry: &db::skill_plans::SkillPlanEntry,
+        skill: Option<&utils::SkillAttributes>,
+    ) -> Option<i64> {
+        let attributes = self.attributes.as_ref()?;
+        let skill = skill?;
+        let rank = skill.rank?;
+        if entry.excluded {
+            return Some(0);
+        }
+        let value = |attribute: i64| match attribute {
+            164 => attributes.charisma,
+            165 => attributes.intelligence,
+            166 => attributes.memory,
+            167 => attributes.perception,
+            168 => attributes.willpower,
+            _ => 17, // default base
+        };
+        let rate = utils::calculate_sp_per_minute(
+            value(skill.primary_attribute?),
+            value(skill.secondary_attribute?),
+            self.is_omega,
+        );
+        if rate <= 0.0 {
+            return None;
+        }
+        let char_skill = self.skills.get(&entry.skill_type_id);
+        let missing = missing_sp_for_level(
+            entry.planned_level,
+            char_skill.map_or(0, |s| s.trained_skill_level),
+            char_skill.map_or(0, |s| s.skillpoints_in_skill),
+            rank,
+        );
+        Some((missing as f64 / rate * 60.0).round() as i64)
+    }
+}
+
+/// Running total of `seconds`, skipping entries without a time.
+fn cumulative_seconds(seconds: &[Option<i64>]) -> Vec<Option<i64>> {
+    let mut total = 0;
+    seconds
+        .iter()
+        .map(|s| {
+            s.map(|s| {
+                total += s;
+                total
+            })
+        })
+        .collect()
+}
+
+/// Adds skill names, ranks and level SP to stored entries for the frontend,
+/// and training times when `training` is given.
+async fn plan_entry_responses(
+    pool: &db::Pool,
+    entries: Vec<db::skill_plans::SkillPlanEntry>,
+    training: Option<&CharacterTraining>,
+) -> Result<Vec<SkillPlanEntryResponse>, String> {
+    let skill_type_ids: Vec<i64> = entries.iter().map(|e| e.skill_type_id).collect();
+    let skill_attributes = utils::get_skill_attributes(pool, &skill_type_ids)
+        .await
+        .map_err(|e| format!("Failed to get skill attributes: {}", e))?;
+
+    let skill_names = utils::get_type_names(pool, &skill_type_ids)
+        .await
+        .map_err(|e| format!("Failed to get skill names: {}", e))?;
+
+    let omega_only_skills = db::sde::get_omega_only_skills(pool, &skill_type_ids)
+        .await
+        .map_err(|e| format!("Failed to get Omega-only skills: {}", e))?;
+
+    let seconds: Vec<Option<i64>> = match training {
+        Some(training) => training.entries_seconds(&entries, &skill_attributes),
+        None => vec![None; entries.len()],
+    };
+    let cumulative = cumulative_seconds(&seconds);
+
+    let mut entry_responses = Vec::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let skill_name = skill_names
+            .get(&entry.skill_type_id)
+            .cloned()
+            .unwrap_or_else(|| format!("Unknown Skill ({})", entry.skill_type_id));
+
+        let skill_attr = skill_attributes.get(&entry.skill_type_id);
+        let rank = skill_attr.and_then(|attr| attr.rank);
+        let skillpoints_for_level = if let Some(rank_val) = rank {
+            utils::sp_for_level_slice(rank_val, entry.planned_level as i32)
+        } else {
+            0
+        };
+
+        entry_responses.push(SkillPlanEntryResponse {
+            entry_id: entry.entry_id,
+            plan_id: entry.plan_id,
+            skill_type_id: entry.skill_type_id,
+            skill_name,
+            planned_level: entry.planned_level,
+            sort_order: entry.sort_order,
+            entry_type: entry.entry_type,
+            notes: entry.notes,
+            excluded: entry.excluded,
+            priority: entry.priority,
+            rank,
+            skillpoints_for_level,
+            training_seconds: seconds[index],
+            cumulative_training_seconds: cumulative[index],
+            requires_omega: omega_only_skills.contains(&entry.skill_type_id),
+        });
+    }
+
+    Ok(entry_responses)
+}
+
+/// Entries an Alpha clone cannot train, in plan order.
+#[tauri::command]
+pub async fn validate_plan_for_alpha(
+    read_pool: State<'_, db::ReadPool>,
+    plan_id: i64,
+) -> Result<Vec<OmegaOnlyEntry>, String> {
+    let pool: &db::Pool = &read_pool;
+
+    let entries = db::skill_plans::get_plan_entries(pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get plan entries: {}", e))?;
+    let skill_type_ids: Vec<i64> = entries.iter().map(|e| e.skill_type_id).collect();
+    let omega_only_skills = db::sde::get_omega_only_skills(pool, &skill_type_ids)
+        .await
+        .map_err(|e| format!("Failed to get Omega-only skills: {}", e))?;
+    let alpha_caps = db::sde::get_alpha_skill_caps(pool)
+        .await
+        .map_err(|e| format!("Failed to get Alpha skill caps: {}", e))?;
+
+    Ok(alpha::alpha_blocked_entries(
+        &entries,
+        &alpha_caps,
+        &omega_only_skills,
+    ))
+}
+
+#[tauri::command]
+pub async fn update_skill_plan(
+    pool: State<'_, db::Pool>,
+    plan_id: i64,
+    name: String,
+    description: Option<String>,
+    auto_prerequisites: bool,
+) -> Result<(), String> {
+    db::skill_plans::update_skill_plan(
+        &pool,
+        plan_id,
+        &name,
+        description.as_deref(),
+        auto_prerequisites,
+    )
+    .await
+    .map_err(|e| format!("Failed to update skill plan: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_skill_plan(pool: State<'_, db::Pool>, plan_id: i64) -> Result<(), String> {
+    let plan = db::skill_plans::get_skill_plan(&*pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get skill plan: {}", e))?;
+    let entry_count = db::skill_plans::get_plan_entries(&*pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get plan entries: {}", e))?
+        .len();
+
+    db::skill_plans::delete_skill_plan(&pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to delete skill plan: {}", e))?;
+
+    super::audit_log::audit(
+        &pool,
+        "delete_skill_plan",
+        serde_json::json!({
+            "plan_id": plan_id,
+            "name": plan.map(|p| p.name),
+            "entry_count": entry_count,
+        }),
+    )
+    .await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn add_plan_entry(
+    pool: State<'_, db::Pool>,
+    plan_id: i64,
+    skill_type_id: i64,
+    planned_level: i64,
+    notes: Option<String>,
+) -> Result<SkillPlanWithEntriesResponse, String> {
+    if !(1..=5).contains(&planned_level) {
+        return Err("Planned level must be between 1 and 5".to_string());
+    }
+
+    super::plan_history::record_revision(&pool, plan_id, "add_plan_entry").await;
+    insert_plan_entry(pool, plan_id, skill_type_id, planned_level, notes).await
+}
+
+/// [`add_plan_entry`] without recording history, for commands that already
+/// recorded their own revision.
+async fn insert_plan_entry(
+    pool: State<'_, db::Pool>,
+    plan_id: i64,
+    skill_type_id: i64,
+    planned_level: i64,
+    notes: Option<String>,
+) -> Result<SkillPlanWithEntriesResponse, String> {
+    // 1. Build DAG and get current nodes
+    let (mut dag, current_nodes) = PlanDag::build_from_plan(&pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to build DAG: {}", e))?;
+
+    let plan = db::skill_plans::get_skill_plan(&*pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get plan: {}", e))?
+        .ok_or_else(|| "Plan not found".to_string())?;
+
+    // 2. Add new node (recursively if enabled)
     let new_node = PlanNode {
         skill_type_id,
         level: planned_level,
@@ -928,7 +1622,7 @@ pub async fn add_plan_entry(
         .await
         .map_err(|e| format!("Failed to commit: {}", e))?;
 
-    get_skill_plan_with_entries(pool, plan_id)
+    get_skill_plan_with_entries(pool, plan_id, None, None)
         .await?
         .ok_or_else(|| "Failed to retrieve updated plan after adding entry".to_string())
 }
@@ -940,6 +1634,7 @@ pub async fn update_plan_entry(
     planned_level: Option<i64>,
     entry_type: Option<String>,
     notes: Option<String>,
+    excluded: Option<bool>,
 ) -> Result<(), String> {
     if let Some(level) = planned_level {
         if !(1..=5).contains(&level) {
@@ -963,19 +1658,23 @@ pub async fn update_plan_entry(
         if let Some((plan_id, skill_type_id, old_level, old_entry_type)) = current_entry {
             // If it's a "Planned" entry and we're increasing the level, preserve the old level
             if old_entry_type == "Planned" && new_level > old_level {
+                super::plan_history::record_revision(&pool, plan_id, "update_plan_entry").await;
+
                 // Delete the old planned entry
                 db::skill_plans::delete_plan_entry(&pool, entry_id)
                     .await
                     .map_err(|e| format!("Failed to delete old entry: {}", e))?;
 
                 // Add the new planned entry using the same logic as add_plan_entry
-                add_plan_entry(pool, plan_id, skill_type_id, new_level, notes).await?;
+                insert_plan_entry(pool, plan_id, skill_type_id, new_level, notes).await?;
 
                 return Ok(());
             }
         }
     }
 
+    record_entry_revision(&pool, entry_id, "update_plan_entry").await?;
+
     // For other cases (decreasing level, same level, or not a Planned entry), use standard update
     db::skill_plans::update_plan_entry(
         &pool,
@@ -983,13 +1682,72 @@ pub async fn update_plan_entry(
         planned_level,
         entry_type.as_deref(),
         notes.as_deref(),
+        excluded,
     )
     .await
     .map_err(|e| format!("Failed to update plan entry: {}", e))
 }
 
+/// Records history for the plan `entry_id` belongs to.
+async fn record_entry_revision(pool: &db::Pool, entry_id: i64, action: &str) -> Result<(), String> {
+    let details = db::skill_plans::get_entry_details_by_id(pool, entry_id)
+        .await
+        .map_err(|e| format!("Failed to get entry details: {}", e))?;
+    if let Some((plan_id, ..)) = details {
+        super::plan_history::record_revision(pool, plan_id, action).await;
+    }
+    Ok(())
+}
+
+/// Sets the priority of `entry_ids` in the plan. The optimizer trains
+/// higher-priority entries first when the order is otherwise a tie.
+#[tauri::command]
+pub async fn set_plan_entry_priority(
+    pool: State<'_, db::Pool>,
+    plan_id: i64,
+    entry_ids: Vec<i64>,
+    priority: i64,
+) -> Result<(), String> {
+    set_plan_entry_priority_inner(&pool, plan_id, &entry_ids, priority).await
+}
+
+async fn set_plan_entry_priority_inner(
+    pool: &db::Pool,
+    plan_id: i64,
+    entry_ids: &[i64],
+    priority: i64,
+) -> Result<(), String> {
+    if !db::skill_plans::ENTRY_PRIORITIES.contains(&priority) {
+        return Err("Priority must be between 1 and 5".to_string());
+    }
+    let entries = db::skill_plans::get_plan_entries(pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get plan entries: {}", e))?;
+    if let Some(foreign) = entry_ids
+        .iter()
+        .find(|id| !entries.iter().any(|e| e.entry_id == **id))
+    {
+        return Err(format!("Entry {} is not part of plan {}", foreign, plan_id));
+    }
+    if entries
+        .iter()
+        .filter(|e| entry_ids.contains(&e.entry_id))
+        .all(|e| e.priority == priority)
+    {
+        return Ok(());
+    }
+
+    super::plan_history::record_revision(pool, plan_id, "set_plan_entry_priority").await;
+    db::skill_plans::set_entries_priority(pool, plan_id, entry_ids, priority)
+        .await
+        .map_err(|e| format!("Failed to set entry priority: {}", e))?;
+
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn delete_plan_entry(pool: State<'_, db::Pool>, entry_id: i64) -> Result<(), String> {
+    record_entry_revision(&pool, entry_id, "delete_plan_entry").await?;
     db::skill_plans::delete_plan_entry(&pool, entry_id)
         .await
         .map_err(|e| format!("Failed to delete plan entry: {}", e))
@@ -1028,6 +1786,8 @@ pub async fn remove_skill_level(pool: State<'_, db::Pool>, entry_id: i64) -> Res
         .await
         .map_err(|e| format!("Failed to build DAG: {}", e))?;
 
+    super::plan_history::record_revision(&pool, plan_id, "remove_skill_level").await;
+
     let node = PlanNode {
         skill_type_id,
         level: planned_level,
@@ -1057,6 +1817,7 @@ pub async fn remove_skill_level(pool: State<'_, db::Pool>, entry_id: i64) -> Res
                 None,
                 Some("Planned"),
                 None,
+                None,
             )
             .await
             .map_err(|e| format!("Failed to promote previous level: {}", e))?;
@@ -1077,7 +1838,7 @@ pub async fn remove_skill_level(pool: State<'_, db::Pool>, entry_id: i64) -> Res
 
     // 3. Keep as prerequisite or delete
     if is_required {
-        db::skill_plans::update_plan_entry(&pool, entry_id, None, Some("Prerequisite"), None)
+        db::skill_plans::update_plan_entry(&pool, entry_id, None, Some("Prerequisite"), None, None)
             .await
             .map_err(|e| format!("Failed to demote entry: {}", e))?;
     } else {
@@ -1130,6 +1891,7 @@ pub async fn remove_skill(
     }
 
     // Delete all levels
+    super::plan_history::record_revision(&pool, plan_id, "remove_skill").await;
     db::skill_plans::delete_skill_from_plan(&pool, plan_id, skill_type_id)
         .await
         .map_err(|e| format!("Failed to delete skill: {}", e))?;
@@ -1154,6 +1916,7 @@ pub async fn remove_skill_and_prerequisites(
         .collect();
 
     // 2. Clear all entries for the plan
+    super::plan_history::record_revision(&pool, plan_id, "remove_skill_and_prerequisites").await;
     db::skill_plans::clear_plan_entries(&pool, plan_id)
         .await
         .map_err(|e| format!("Failed to clear plan: {}", e))?;
@@ -1216,7 +1979,7 @@ pub async fn remove_skill_and_prerequisites(
         .await
         .map_err(|e| format!("Failed to commit: {}", e))?;
 
-    get_skill_plan_with_entries(pool, plan_id)
+    get_skill_plan_with_entries(pool, plan_id, None, None)
         .await?
         .ok_or_else(|| "Failed to retrieve updated plan".to_string())
 }
@@ -1415,6 +2178,30 @@ pub async fn validate_reorder(
     })
 }
 
+fn invalid_order_error(validation: ValidationResponse) -> String {
+    let error_msgs: Vec<String> = validation
+        .errors
+        .into_iter()
+        .map(|err| {
+            if err.variant == "Cycle" {
+                "Circular dependency detected".to_string()
+            } else if err.variant == "MissingPrerequisite" {
+                format!(
+                    "{} {} is missing prerequisite {} {}",
+                    err.node_skill_name, err.node_level, err.other_skill_name, err.other_level
+                )
+            } else {
+                format!(
+                    "{} {} would be trained before its prerequisite {} {}",
+                    err.node_skill_name, err.node_level, err.other_skill_name, err.other_level
+                )
+            }
+        })
+        .collect();
+
+    format!("Invalid order: {}", error_msgs.join(", "))
+}
+
 #[tauri::command]
 pub async fn reorder_plan_entries(
     pool: State<'_, db::Pool>,
@@ -1425,35 +2212,39 @@ pub async fn reorder_plan_entries(
     let validation = validate_reorder(pool.clone(), plan_id, entry_ids.clone()).await?;
 
     if !validation.is_valid {
-        let error_msgs: Vec<String> = validation
-            .errors
-            .into_iter()
-            .map(|err| {
-                if err.variant == "Cycle" {
-                    "Circular dependency detected".to_string()
-                } else if err.variant == "MissingPrerequisite" {
-                    format!(
-                        "{} {} is missing prerequisite {} {}",
-                        err.node_skill_name, err.node_level, err.other_skill_name, err.other_level
-                    )
-                } else {
-                    format!(
-                        "{} {} would be trained before its prerequisite {} {}",
-                        err.node_skill_name, err.node_level, err.other_skill_name, err.other_level
-                    )
-                }
-            })
-            .collect();
-
-        return Err(format!("Invalid order: {}", error_msgs.join(", ")));
+        return Err(invalid_order_error(validation));
     }
 
     // 2. Persist
+    super::plan_history::record_revision(&pool, plan_id, "reorder_plan_entries").await;
     db::skill_plans::reorder_plan_entries(&pool, plan_id, &entry_ids)
         .await
         .map_err(|e| format!("Failed to reorder plan entries: {}", e))
 }
 
+/// Re-sort a plan by a preset (training time, rank, attribute cluster or
+/// entry priority) without breaking prerequisite order.
+#[tauri::command]
+pub async fn sort_plan(
+    pool: State<'_, db::Pool>,
+    plan_id: i64,
+    preset: PlanSortPreset,
+    character_id: Option<i64>,
+) -> Result<SkillPlanWithEntriesResponse, String> {
+    let entry_ids = sorting::sort_plan(&pool, plan_id, preset, character_id)
+        .await
+        .map_err(|e| format!("Failed to sort plan: {}", e))?;
+
+    super::plan_history::record_revision(&pool, plan_id, "sort_plan").await;
+    db::skill_plans::reorder_plan_entries(&pool, plan_id, &entry_ids)
+        .await
+        .map_err(|e| format!("Failed to reorder plan entries: {}", e))?;
+
+    get_skill_plan_with_entries(pool, plan_id, None, None)
+        .await?
+        .ok_or_else(|| "Failed to retrieve plan after sorting".to_string())
+}
+
 /// Parse pasted skill-plan text into `(skill_name, level)` pairs.
 ///
 /// Accepts one entry per line with the level as the final whitespace-separated
@@ -1476,9 +2267,8 @@ fn parse_skill_plan_text(text: &str) -> Result<Vec<(String, i64)>, String> {
         }
 
         let level_str = parts[parts.len() - 1];
-        let level: i64 = level_str
-            .parse()
-            .map_err(|_| format!("Invalid level in line: {}", line))?;
+        let level = utils::parse_level(level_str)
+            .ok_or_else(|| format!("Invalid level in line: {}", line))?;
 
         // PyFA exports untrained skills at level 0 — skip rather than reject.
         if level == 0 {
@@ -1501,29 +2291,32 @@ pub async fn import_skill_plan_text(
     pool: State<'_, db::Pool>,
     plan_id: i64,
     text: String,
-) -> Result<SkillPlanWithEntriesResponse, String> {
-    import_skill_plan_text_inner(pool, plan_id, text)
+    conflict_strategy: Option<ConflictStrategy>,
+) -> Result<PlanImportResponse, String> {
+    let summary = import_skill_plan_text_inner(&pool, plan_id, &text, conflict_strategy)
         .await
-        .map_err(|e| log_import_error("text", e))
+        .map_err(|e| log_import_error("text", e))?;
+    plan_import_response(pool, plan_id, summary).await
 }
 
 async fn import_skill_plan_text_inner(
-    pool: State<'_, db::Pool>,
+    pool: &db::Pool,
     plan_id: i64,
-    text: String,
-) -> Result<SkillPlanWithEntriesResponse, String> {
+    text: &str,
+    conflict_strategy: Option<ConflictStrategy>,
+) -> Result<ImportSummary, String> {
     // Step 1: Collect all planned entries from import text
-    let mut planned_entries: Vec<(i64, i64)> = Vec::new();
+    let mut planned_entries: Vec<ImportedEntry> = Vec::new();
     let mut unmatched_skills = Vec::new();
 
-    for (skill_name, level) in parse_skill_plan_text(&text)? {
-        let skill_type_id = db::skill_plans::get_skill_type_id_by_name(&pool, &skill_name)
+    for (skill_name, level) in parse_skill_plan_text(text)? {
+        let skill_type_id = db::skill_plans::get_skill_type_id_by_name(pool, &skill_name)
             .await
             .map_err(|e| format!("Failed to lookup skill: {}", e))?;
 
         match skill_type_id {
             Some(id) => {
-                planned_entries.push((id, level));
+                planned_entries.push(ImportedEntry::planned(id, level));
             }
             None => {
                 unmatched_skills.push(skill_name);
@@ -1539,88 +2332,88 @@ async fn import_skill_plan_text_inner(
         return Err("No valid entries found in text".to_string());
     }
 
-    // 1. Build DAG and get current nodes
-    let (mut dag, current_nodes) = PlanDag::build_from_plan(&pool, plan_id)
-        .await
-        .map_err(|e| format!("Failed to build DAG: {}", e))?;
-
-    // 2. Add all imported entries recursively
-    for (skill_type_id, level) in &planned_entries {
-        dag.add_recursive(
-            &pool,
-            PlanNode {
-                skill_type_id: *skill_type_id,
-                level: *level,
-            },
-        )
-        .await
-        .map_err(|e| {
-            format!(
-                "Failed to add prerequisites for skill {}: {}",
-                skill_type_id, e
-            )
-        })?;
-    }
+    import_into_plan(pool, plan_id, &planned_entries, conflict_strategy).await
+}
 
-    // 3. Topological sort to get new order
-    let sorted_nodes = dag.topological_sort(&current_nodes);
+/// Adds `entries` and their missing prerequisites to the plan and re-sorts
+/// it, resolving skills the plan already has with `conflict_strategy`.
+async fn import_into_plan(
+    pool: &db::Pool,
+    plan_id: i64,
+    entries: &[ImportedEntry],
+    conflict_strategy: Option<ConflictStrategy>,
+) -> Result<ImportSummary, String> {
+    super::plan_history::record_revision(pool, plan_id, "import_into_plan").await;
+    import::import_entries(
+        pool,
+        plan_id,
+        entries,
+        conflict_strategy.unwrap_or_default(),
+    )
+    .await
+    .map_err(|e| format!("{:#}", e))
+}
 
-    // 4. Update database
-    let mut tx = pool
-        .begin()
+async fn plan_import_response(
+    pool: State<'_, db::Pool>,
+    plan_id: i64,
+    summary: ImportSummary,
+) -> Result<PlanImportResponse, String> {
+    let plan = get_skill_plan_with_entries(pool, plan_id, None, None)
+        .await?
+        .ok_or_else(|| "Failed to retrieve plan after import".to_string())?;
+    Ok(PlanImportResponse { plan, summary })
+}
+
+/// Populate a plan with every skill needed to fly an EFT fit (the format
+/// Pyfa and the in-game fitting window copy): the ship's and each item's
+/// requirements as Planned entries, plus their prerequisites.
+#[tauri::command]
+pub async fn import_plan_from_fitting(
+    pool: State<'_, db::Pool>,
+    plan_id: i64,
+    text: String,
+    conflict_strategy: Option<ConflictStrategy>,
+) -> Result<PlanImportResponse, String> {
+    let summary = import_plan_from_fitting_inner(&pool, plan_id, &text, conflict_strategy)
         .await
-        .map_err(|e| format!("Transaction failed: {}", e))?;
+        .map_err(|e| log_import_error("fitting", e))?;
+    plan_import_response(pool, plan_id, summary).await
+}
 
-    for (index, node) in sorted_nodes.iter().enumerate() {
-        let is_originally_planned = planned_entries.contains(&(node.skill_type_id, node.level));
+async fn import_plan_from_fitting_inner(
+    pool: &db::Pool,
+    plan_id: i64,
+    text: &str,
+    conflict_strategy: Option<ConflictStrategy>,
+) -> Result<ImportSummary, String> {
+    crate::sde::require_full_import(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    let (_fit, ship_type_id, items) = crate::fittings::resolve_eft(pool, text)
+        .await
+        .map_err(|e| format!("Invalid fit: {}", e))?;
 
-        let entry_type = if is_originally_planned {
-            "Planned"
-        } else {
-            let existing =
-                db::skill_plans::get_entry_type(&mut *tx, plan_id, node.skill_type_id, node.level)
-                    .await
-                    .map_err(|e| format!("DB Error: {}", e))?;
+    let mut type_ids: Vec<i64> = items.iter().map(|i| i.type_id).collect();
+    type_ids.push(ship_type_id);
+    type_ids.sort_unstable();
+    type_ids.dedup();
 
-            if let Some(et) = existing {
-                if et == "Planned" {
-                    "Planned"
-                } else {
-                    "Prerequisite"
-                }
-            } else {
-                "Prerequisite"
-            }
-        };
-
-        sqlx::query(
-            "INSERT INTO skill_plan_entries (plan_id, skill_type_id, planned_level, sort_order, entry_type, notes)
-             VALUES (?, ?, ?, ?, ?, ?)
-             ON CONFLICT(plan_id, skill_type_id, planned_level) DO UPDATE SET
-             sort_order = excluded.sort_order,
-             entry_type = CASE
-                WHEN excluded.entry_type = 'Planned' THEN 'Planned'
-                ELSE skill_plan_entries.entry_type
-             END"
-        )
-        .bind(plan_id)
-        .bind(node.skill_type_id)
-        .bind(node.level)
-        .bind(index as i64)
-        .bind(entry_type)
-        .bind(None::<String>)
-        .execute(&mut *tx)
+    let required = db::sde::get_direct_required_skills_for_types(pool, &type_ids)
         .await
-        .map_err(|e| format!("Failed to insert entry: {}", e))?;
+        .map_err(|e| format!("Failed to look up fit requirements: {}", e))?;
+    if required.is_empty() {
+        return Err("Invalid fit: nothing in it requires skills".to_string());
     }
 
-    tx.commit()
-        .await
-        .map_err(|e| format!("Failed to commit: {}", e))?;
+    let mut required: Vec<(i64, i64)> = required.into_iter().collect();
+    required.sort_unstable();
+    let planned_entries: Vec<ImportedEntry> = required
+        .into_iter()
+        .map(|(skill_type_id, level)| ImportedEntry::planned(skill_type_id, level))
+        .collect();
 
-    get_skill_plan_with_entries(pool, plan_id)
-        .await?
-        .ok_or_else(|| "Failed to retrieve plan after import".to_string())
+    import_into_plan(pool, plan_id, &planned_entries, conflict_strategy).await
 }
 
 #[tauri::command]
@@ -1628,18 +2421,61 @@ pub async fn import_skill_plan_xml(
     pool: State<'_, db::Pool>,
     plan_id: i64,
     xml: String,
-) -> Result<SkillPlanWithEntriesResponse, String> {
-    import_skill_plan_xml_inner(pool, plan_id, xml)
+    conflict_strategy: Option<ConflictStrategy>,
+) -> Result<PlanImportResponse, String> {
+    let summary = import_skill_plan_xml_inner(&pool, plan_id, &xml, conflict_strategy)
         .await
-        .map_err(|e| log_import_error("xml", e))
+        .map_err(|e| log_import_error("xml", e))?;
+    plan_import_response(pool, plan_id, summary).await
 }
 
-async fn import_skill_plan_xml_inner(
+/// Largest decompressed .emp accepted; real EVEMon plans are a few hundred KB.
+const MAX_EMP_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Imports an EVEMon `.emp` plan file: gzipped plan XML, fed through the XML
+/// importer.
+#[tauri::command]
+pub async fn import_skill_plan_emp(
     pool: State<'_, db::Pool>,
     plan_id: i64,
-    xml: String,
-) -> Result<SkillPlanWithEntriesResponse, String> {
-    let mut reader = Reader::from_str(&xml);
+    data: Vec<u8>,
+    conflict_strategy: Option<ConflictStrategy>,
+) -> Result<PlanImportResponse, String> {
+    let xml = decompress_emp(&data).map_err(|e| log_import_error("emp", e))?;
+    let summary = import_skill_plan_xml_inner(&pool, plan_id, &xml, conflict_strategy)
+        .await
+        .map_err(|e| log_import_error("emp", e))?;
+    plan_import_response(pool, plan_id, summary).await
+}
+
+/// Unpacks an .emp file. Uncompressed XML is passed through, so a plan that
+/// was already extracted (or saved without compression) still imports.
+fn decompress_emp(data: &[u8]) -> Result<String, String> {
+    let bytes = if data.starts_with(&[0x1f, 0x8b]) {
+        let mut out = Vec::new();
+        GzDecoder::new(data)
+            .take(MAX_EMP_SIZE + 1)
+            .read_to_end(&mut out)
+            .map_err(|e| format!("Invalid .emp file: {}", e))?;
+        if out.len() as u64 > MAX_EMP_SIZE {
+            return Err("Invalid .emp file: plan is too large".to_string());
+        }
+        out
+    } else {
+        data.to_vec()
+    };
+    let xml = String::from_utf8(bytes)
+        .map_err(|_| "Invalid .emp file: contents are not text".to_string())?;
+    Ok(xml.trim_start_matches('\u{feff}').to_string())
+}
+
+async fn import_skill_plan_xml_inner(
+    pool: &db::Pool,
+    plan_id: i64,
+    xml: &str,
+    conflict_strategy: Option<ConflictStrategy>,
+) -> Result<ImportSummary, String> {
+    let mut reader = Reader::from_str(xml);
 
     let mut entries = Vec::new();
     let mut buf = Vec::new();
@@ -1711,7 +2547,19 @@ async fn import_skill_plan_xml_inner(
                         } else {
                             Some(notes_text.clone())
                         };
-                        entries.push((skill_id, level, entry_type, notes));
+                        // EVEMon only distinguishes planned entries; anything
+                        // else is kept as a prerequisite.
+                        let entry_type = if entry_type == "Planned" {
+                            entry_type
+                        } else {
+                            "Prerequisite".to_string()
+                        };
+                        entries.push(ImportedEntry {
+                            skill_type_id: skill_id,
+                            level,
+                            entry_type,
+                            notes,
+                        });
                         notes_text.clear();
                     }
                 } else if e.name().as_ref() == b"notes" {
@@ -1728,115 +2576,89 @@ async fn import_skill_plan_xml_inner(
         return Err("No entries found in XML".to_string());
     }
 
-    // 1. Build DAG and get current nodes
-    let (mut dag, current_nodes) = PlanDag::build_from_plan(&pool, plan_id)
-        .await
-        .map_err(|e| format!("Failed to build DAG: {}", e))?;
+    import_into_plan(pool, plan_id, &entries, conflict_strategy).await
+}
 
-    // 2. Add all imported entries recursively
-    for (skill_id, level, _entry_type, _notes) in &entries {
-        dag.add_recursive(
-            &pool,
-            PlanNode {
-                skill_type_id: *skill_id,
-                level: *level,
-            },
-        )
+/// Import every plan from an EVEMon `settings.xml` backup into a new folder
+/// named after the file.
+#[tauri::command]
+pub async fn import_evemon_settings(
+    pool: State<'_, db::Pool>,
+    path: String,
+) -> Result<EvemonImportResult, String> {
+    let path = std::path::Path::new(&path);
+    let xml = tokio::fs::read_to_string(path)
         .await
-        .map_err(|e| format!("Failed to add prerequisites for skill {}: {}", skill_id, e))?;
-    }
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let source_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "settings.xml".to_string());
 
-    // 3. Topological sort to get new order
-    let sorted_nodes = dag.topological_sort(&current_nodes);
-
-    // 4. Update database
-    let mut tx = pool
-        .begin()
+    evemon::import(&pool, &source_name, &xml)
         .await
-        .map_err(|e| format!("Transaction failed: {}", e))?;
-
-    let imported_planned_nodes: HashSet<PlanNode> = entries
-        .iter()
-        .filter(|(_, _, et, _)| et == "Planned")
-        .map(|(sid, lvl, _, _)| PlanNode {
-            skill_type_id: *sid,
-            level: *lvl,
-        })
-        .collect();
-
-    for (index, node) in sorted_nodes.iter().enumerate() {
-        let is_originally_planned = imported_planned_nodes.contains(node);
-
-        let entry_type = if is_originally_planned {
-            "Planned"
-        } else {
-            let existing =
-                db::skill_plans::get_entry_type(&mut *tx, plan_id, node.skill_type_id, node.level)
-                    .await
-                    .map_err(|e| format!("DB Error: {}", e))?;
-
-            if let Some(et) = existing {
-                if et == "Planned" {
-                    "Planned"
-                } else {
-                    "Prerequisite"
-                }
-            } else {
-                "Prerequisite"
-            }
-        };
-
-        // Find notes if available in imported entries
-        let notes = entries
-            .iter()
-            .find(|(sid, lvl, _, _)| *sid == node.skill_type_id && *lvl == node.level)
-            .and_then(|(_, _, _, n)| n.clone());
+        .map_err(|e| format!("Failed to import EVEMon settings: {}", e))
+}
 
-        sqlx::query(
-            "INSERT INTO skill_plan_entries (plan_id, skill_type_id, planned_level, sort_order, entry_type, notes)
-             VALUES (?, ?, ?, ?, ?, ?)
-             ON CONFLICT(plan_id, skill_type_id, planned_level) DO UPDATE SET
-             sort_order = excluded.sort_order,
-             entry_type = CASE
-                WHEN excluded.entry_type = 'Planned' THEN 'Planned'
-                ELSE skill_plan_entries.entry_type
-             END,
-             notes = CASE WHEN excluded.notes IS NOT NULL THEN excluded.notes ELSE skill_plan_entries.notes END"
-        )
-        .bind(plan_id)
-        .bind(node.skill_type_id)
-        .bind(node.level)
-        .bind(index as i64)
-        .bind(entry_type)
-        .bind(notes)
-        .execute(&mut *tx)
+/// The entries an export writes. With a character (`character_id`, else the
+/// plan's owner), levels it has already trained are left out unless
+/// `include_trained` is set.
+async fn export_entries(
+    pool: &db::Pool,
+    plan_id: i64,
+    character_id: Option<i64>,
+    include_trained: bool,
+) -> Result<Vec<db::skill_plans::SkillPlanEntry>, String> {
+    let plan = db::skill_plans::get_skill_plan(pool, plan_id)
         .await
-        .map_err(|e| format!("Failed to insert entry: {}", e))?;
-    }
+        .map_err(|e| format!("Failed to get skill plan: {}", e))?
+        .ok_or_else(|| "Plan not found".to_string())?;
+    let entries = db::skill_plans::get_plan_entries(pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get plan entries: {}", e))?;
 
-    tx.commit()
+    let character_id = match character_id.or(plan.owner_character_id) {
+        Some(character_id) if !include_trained => character_id,
+        _ => return Ok(entries),
+    };
+    let trained: HashMap<i64, i64> = db::get_character_skills(pool, character_id)
         .await
-        .map_err(|e| format!("Failed to commit: {}", e))?;
+        .map_err(|e| format!("Failed to get character skills: {}", e))?
+        .into_iter()
+        .map(|s| (s.skill_id, s.trained_skill_level))
+        .collect();
 
-    get_skill_plan_with_entries(pool, plan_id)
-        .await?
-        .ok_or_else(|| "Failed to retrieve plan after import".to_string())
+    Ok(entries
+        .into_iter()
+        .filter(|e| e.planned_level > trained.get(&e.skill_type_id).copied().unwrap_or(0))
+        .collect())
 }
 
+/// Exports follow [`export_entries`]: only what the character (or the plan's
+/// owner) still has to train, unless `include_trained`.
 #[tauri::command]
 pub async fn export_skill_plan_text(
     pool: State<'_, db::Pool>,
     plan_id: i64,
+    character_id: Option<i64>,
+    include_trained: Option<bool>,
 ) -> Result<String, String> {
-    let entries = db::skill_plans::get_plan_entries(&*pool, plan_id)
-        .await
-        .map_err(|e| format!("Failed to get plan entries: {}", e))?;
+    let entries = export_entries(
+        &pool,
+        plan_id,
+        character_id,
+        include_trained.unwrap_or(false),
+    )
+    .await?;
 
     let mut lines = Vec::new();
     let skill_type_ids: Vec<i64> = entries.iter().map(|e| e.skill_type_id).collect();
     let skill_names = utils::get_type_names(&pool, &skill_type_ids)
         .await
         .map_err(|e| format!("Failed to get skill names: {}", e))?;
+    let level_format = db::app_settings::get_level_format(&pool)
+        .await
+        .map_err(|e| format!("Failed to get level format: {}", e))?;
 
     for entry in entries {
         let skill_name = skill_names
@@ -1844,26 +2666,66 @@ pub async fn export_skill_plan_text(
             .cloned()
             .unwrap_or_else(|| format!("Unknown Skill ({})", entry.skill_type_id));
 
-        lines.push(format!("{} {}", skill_name, entry.planned_level));
+        lines.push(format!(
+            "{} {}",
+            skill_name,
+            utils::format_level(entry.planned_level, level_format)
+        ));
     }
 
     Ok(lines.join("\n"))
 }
 
+/// The plan as text to paste into the EVE client, in `format`. Excluded
+/// entries are left out.
 #[tauri::command]
-pub async fn simulate_skill_plan(
+pub async fn export_skill_plan_clipboard(
     pool: State<'_, db::Pool>,
     plan_id: i64,
+    format: ClipboardFormat,
+    character_id: Option<i64>,
+    include_trained: Option<bool>,
+) -> Result<String, String> {
+    let entries = export_entries(
+        &pool,
+        plan_id,
+        character_id,
+        include_trained.unwrap_or(false),
+    )
+    .await?;
+
+    let levels: Vec<(i64, i64)> = entries
+        .iter()
+        .filter(|e| !e.excluded)
+        .map(|e| (e.skill_type_id, e.planned_level))
+        .collect();
+    let skill_type_ids: Vec<i64> = levels
+        .iter()
+        .map(|(skill_type_id, _)| *skill_type_id)
+        .collect();
+    let skill_names = utils::get_type_names(&pool, &skill_type_ids)
+        .await
+        .map_err(|e| format!("Failed to get skill names: {}", e))?;
+
+    Ok(clipboard::to_text(&levels, &skill_names, format))
+}
+
+#[tauri::command]
+pub async fn simulate_skill_plan(
+    read_pool: State<'_, db::ReadPool>,
+    plan_id: i64,
     profile: SimulationProfile,
     character_id: Option<i64>,
 ) -> Result<SimulationResult, String> {
-    let entries = db::skill_plans::get_plan_entries(&*pool, plan_id)
+    let pool: &db::Pool = &read_pool;
+
+    let entries = db::skill_plans::get_plan_entries(pool, plan_id)
         .await
         .map_err(|e| format!("Failed to get plan entries: {}", e))?;
 
     let mut current_sp_map = HashMap::new();
     if let Some(char_id) = character_id {
-        let character_skills = db::get_character_skills(&pool, char_id)
+        let character_skills = db::get_character_skills(pool, char_id)
             .await
             .map_err(|e| format!("Failed to get character skills: {}", e))?;
 
@@ -1872,27 +2734,135 @@ pub async fn simulate_skill_plan(
         }
     }
 
-    simulation::simulate(&pool, &entries, profile, Some(&current_sp_map))
+    let clone = CloneState::for_character(pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to get character: {}", e))?;
+
+    let boosters = db::planned_boosters::get_plan_boosters(pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get planned boosters: {}", e))?;
+    let mut profile = profile;
+    let planned = simulation::planned_booster_accelerators(&entries, &boosters);
+    profile.accelerators.extend(planned);
+
+    simulation::simulate(pool, &entries, profile, Some(&current_sp_map), clone)
         .await
         .map_err(|e| format!("Simulation failed: {}", e))
 }
 
+/// The plan's timeline for `character_id` with `overrides` applied to its
+/// current remap, implants and accelerator. Saves nothing.
 #[tauri::command]
-pub async fn optimize_plan_attributes(
+pub async fn simulate_plan_what_if(
+    read_pool: State<'_, db::ReadPool>,
+    plan_id: i64,
+    character_id: Option<i64>,
+    overrides: Option<WhatIfOverrides>,
+) -> Result<WhatIfTimeline, String> {
+    let pool: &db::Pool = &read_pool;
+    what_if::simulate_what_if(pool, plan_id, character_id, overrides.unwrap_or_default())
+        .await
+        .map_err(|e| format!("What-if simulation failed: {}", e))
+}
+
+/// Compares `plan_id` with the character's live skill queue: which entries it
+/// already covers, which are still to queue (also as paste-ready text), and
+/// which queued levels the plan does not have.
+#[tauri::command]
+pub async fn sync_plan_with_queue(
+    read_pool: State<'_, db::ReadPool>,
+    plan_id: i64,
+    character_id: i64,
+) -> Result<PlanQueueSync, String> {
+    let pool: &db::Pool = &read_pool;
+    queue_sync::sync_plan_with_queue(pool, plan_id, character_id, chrono::Utc::now())
+        .await
+        .map_err(|e| format!("Failed to compare plan with skill queue: {}", e))
+}
+
+/// Simulates `plan_id` under two remap schedules and reports how much time
+/// each attribute cluster, and the whole plan, gains or loses between them.
+#[tauri::command]
+pub async fn compare_remap_schedules(
+    read_pool: State<'_, db::ReadPool>,
+    plan_id: i64,
+    schedule_a: Vec<PlannedRemap>,
+    schedule_b: Vec<PlannedRemap>,
+    character_id: Option<i64>,
+) -> Result<RemapScheduleComparison, String> {
+    let pool: &db::Pool = &read_pool;
+    remap_compare::compare_remap_schedules(pool, plan_id, schedule_a, schedule_b, character_id)
+        .await
+        .map_err(|e| format!("Failed to compare remap schedules: {}", e))
+}
+
+/// Plans a cerebral accelerator into `plan_id`, taken right after the given
+/// entry (or at the plan start). Simulations of the plan include it.
+#[tauri::command]
+pub async fn save_planned_booster(
     pool: State<'_, db::Pool>,
     plan_id: i64,
+    type_id: Option<i64>,
+    bonus: i64,
+    duration_seconds: i64,
+    after_skill_type_id: Option<i64>,
+    after_skill_level: Option<i64>,
+) -> Result<i64, String> {
+    if bonus <= 0 || duration_seconds <= 0 {
+        return Err("A booster needs a positive bonus and duration".to_string());
+    }
+    db::planned_boosters::save_planned_booster(
+        &*pool,
+        plan_id,
+        type_id,
+        bonus,
+        duration_seconds,
+        after_skill_type_id,
+        after_skill_level,
+    )
+    .await
+    .map_err(|e| format!("Failed to save planned booster: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_planned_boosters(
+    pool: State<'_, db::Pool>,
+    plan_id: i64,
+) -> Result<Vec<PlannedBooster>, String> {
+    db::planned_boosters::get_plan_boosters(&pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get planned boosters: {}", e))
+}
+
+#[tauri::command]
+pub async fn delete_planned_booster(
+    pool: State<'_, db::Pool>,
+    booster_id: i64,
+) -> Result<(), String> {
+    db::planned_boosters::delete_planned_booster(&pool, booster_id)
+        .await
+        .map_err(|e| format!("Failed to delete planned booster: {}", e))
+}
+
+#[tauri::command]
+pub async fn optimize_plan_attributes(
+    read_pool: State<'_, db::ReadPool>,
+    plan_id: i64,
     implants: Attributes,
     baseline_remap: Attributes,
     accelerator_bonus: i64,
     character_id: Option<i64>,
+    compare_implants: Option<bool>,
 ) -> Result<OptimizationResult, String> {
-    let entries = db::skill_plans::get_plan_entries(&*pool, plan_id)
+    let pool: &db::Pool = &read_pool;
+
+    let entries = db::skill_plans::get_plan_entries(pool, plan_id)
         .await
         .map_err(|e| format!("Failed to get plan entries: {}", e))?;
 
     let mut current_sp_map = HashMap::new();
     if let Some(char_id) = character_id {
-        let character_skills = db::get_character_skills(&pool, char_id)
+        let character_skills = db::get_character_skills(pool, char_id)
             .await
             .map_err(|e| format!("Failed to get character skills: {}", e))?;
 
@@ -1901,31 +2871,116 @@ pub async fn optimize_plan_attributes(
         }
     }
 
+    let is_omega = CloneState::for_character(pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to get character: {}", e))?
+        .is_omega();
+
     optimization::optimize_plan_attributes(
-        &pool,
+        pool,
         &entries,
         &implants,
         &baseline_remap,
         accelerator_bonus,
+        is_omega,
         &current_sp_map,
+        compare_implants.unwrap_or(false),
     )
     .await
     .map_err(|e| format!("Optimization failed: {}", e))
 }
 
+/// The +3/+4/+5 attribute implants worth buying for a plan with `remap`,
+/// priced from the market price table and capped at `budget_isk`.
+#[tauri::command]
+pub async fn recommend_plan_implants(
+    read_pool: State<'_, db::ReadPool>,
+    plan_id: i64,
+    remap: Attributes,
+    accelerator_bonus: i64,
+    character_id: Option<i64>,
+    budget_isk: f64,
+) -> Result<ImplantRecommendation, String> {
+    let pool: &db::Pool = &read_pool;
+
+    let entries = db::skill_plans::get_plan_entries(pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get plan entries: {}", e))?;
+
+    let mut current_sp_map = HashMap::new();
+    if let Some(char_id) = character_id {
+        let character_skills = db::get_character_skills(pool, char_id)
+            .await
+            .map_err(|e| format!("Failed to get character skills: {}", e))?;
+
+        for skill in character_skills {
+            current_sp_map.insert(skill.skill_id, skill.skillpoints_in_skill);
+        }
+    }
+
+    let is_omega = CloneState::for_character(pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to get character: {}", e))?
+        .is_omega();
+
+    optimization::optimize_implants(
+        pool,
+        &entries,
+        &remap,
+        accelerator_bonus,
+        is_omega,
+        &current_sp_map,
+        budget_isk,
+    )
+    .await
+    .map_err(|e| format!("Implant recommendation failed: {}", e))
+}
+
+/// Control for one optimizer run. With an `optimization_id` the run can be
+/// cancelled through `cancel_optimization` and reports its progress as
+/// `optimization-progress` events; the caller must `finish` the id afterwards.
+fn optimization_control(
+    app: &AppHandle,
+    registry: &OptimizationRegistry,
+    optimization_id: Option<&str>,
+) -> OptimizationControl {
+    let Some(optimization_id) = optimization_id else {
+        return OptimizationControl::default();
+    };
+    let cancelled = registry.register(optimization_id);
+    let app = app.clone();
+    let optimization_id = optimization_id.to_string();
+    OptimizationControl::new(cancelled, move |evaluated, total| {
+        let progress = OptimizationProgress {
+            optimization_id: optimization_id.clone(),
+            evaluated,
+            total,
+        };
+        if let Err(e) = crate::events::emit_optimization_progress(&app, &progress) {
+            eprintln!("Failed to emit optimization progress: {}", e);
+        }
+    })
+}
+
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn optimize_plan_reordering(
-    pool: State<'_, db::Pool>,
+    app: AppHandle,
+    read_pool: State<'_, db::ReadPool>,
+    optimizations: State<'_, OptimizationRegistry>,
     plan_id: i64,
     implants: Attributes,
     baseline_remap: Attributes,
     accelerator_bonus: i64,
     character_id: Option<i64>,
     max_remaps: i64,
+    optimization_id: Option<String>,
 ) -> Result<ReorderOptimizationResult, String> {
+    let pool: &db::Pool = &read_pool;
+
     let mut current_sp_map = HashMap::new();
     if let Some(char_id) = character_id {
-        let character_skills = db::get_character_skills(&pool, char_id)
+        let character_skills = db::get_character_skills(pool, char_id)
             .await
             .map_err(|e| format!("Failed to get character skills: {}", e))?;
 
@@ -1934,32 +2989,132 @@ pub async fn optimize_plan_reordering(
         }
     }
 
-    optimization::optimize_plan_reordering(
-        &pool,
+    let is_omega = CloneState::for_character(pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to get character: {}", e))?
+        .is_omega();
+
+    let control = optimization_control(&app, &optimizations, optimization_id.as_deref());
+    let result = optimization::optimize_plan_reordering(
+        pool,
         plan_id,
         &implants,
         &baseline_remap,
         accelerator_bonus,
+        is_omega,
         &current_sp_map,
         max_remaps,
+        &control,
     )
-    .await
-    .map_err(|e| format!("Reorder optimization failed: {}", e))
+    .await;
+    if let Some(optimization_id) = &optimization_id {
+        optimizations.finish(optimization_id);
+    }
+    result.map_err(|e| format!("Reorder optimization failed: {}", e))
+}
+
+/// Persist an `optimize_plan_reordering` result: rewrites the plan's order to
+/// `optimized_entry_ids` and replaces its remaps with `remaps`, whose
+/// `entry_index` refers to the new order, in one transaction.
+#[tauri::command]
+pub async fn apply_plan_optimization(
+    pool: State<'_, db::Pool>,
+    plan_id: i64,
+    optimized_entry_ids: Vec<i64>,
+    remaps: Vec<PlannedRemap>,
+) -> Result<SkillPlanWithEntriesResponse, String> {
+    let entries = db::skill_plans::get_plan_entries(&*pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get entries: {}", e))?;
+    let entry_map: HashMap<i64, &db::skill_plans::SkillPlanEntry> =
+        entries.iter().map(|e| (e.entry_id, e)).collect();
+    let distinct: HashSet<i64> = optimized_entry_ids.iter().copied().collect();
+    if optimized_entry_ids.len() != entries.len()
+        || distinct.len() != entries.len()
+        || !distinct.iter().all(|id| entry_map.contains_key(id))
+    {
+        return Err(
+            "The optimized order must list every entry of the plan exactly once".to_string(),
+        );
+    }
+
+    let validation = validate_reorder(pool.clone(), plan_id, optimized_entry_ids.clone()).await?;
+    if !validation.is_valid {
+        return Err(invalid_order_error(validation));
+    }
+
+    let order: Vec<(i64, i64)> = optimized_entry_ids
+        .iter()
+        .map(|id| (entry_map[id].skill_type_id, entry_map[id].planned_level))
+        .collect();
+    let anchored = super::remaps::anchor_planned_remaps(&order, remaps)?;
+
+    super::plan_history::record_revision(&pool, plan_id, "apply_plan_optimization").await;
+    db::skill_plans::apply_plan_optimization(&pool, plan_id, &optimized_entry_ids, &anchored)
+        .await
+        .map_err(|e| format!("Failed to apply optimization: {}", e))?;
+
+    get_skill_plan_with_entries(pool, plan_id, None, None)
+        .await?
+        .ok_or_else(|| "Failed to retrieve plan after optimization".to_string())
+}
+
+/// One remap schedule for a character training `plan_ids` in that order.
+#[tauri::command]
+pub async fn optimize_across_plans(
+    app: AppHandle,
+    read_pool: State<'_, db::ReadPool>,
+    optimizations: State<'_, OptimizationRegistry>,
+    character_id: i64,
+    plan_ids: Vec<i64>,
+    max_remaps: i64,
+    optimization_id: Option<String>,
+) -> Result<CrossPlanOptimizationResult, String> {
+    let pool: &db::Pool = &read_pool;
+    let control = optimization_control(&app, &optimizations, optimization_id.as_deref());
+    let result =
+        optimization::optimize_across_plans(pool, character_id, &plan_ids, max_remaps, &control)
+            .await;
+    if let Some(optimization_id) = &optimization_id {
+        optimizations.finish(optimization_id);
+    }
+    result.map_err(|e| format!("Optimization failed: {}", e))
+}
+
+/// Stops the `optimize_plan_reordering` or `optimize_across_plans` call
+/// started with `optimization_id`; that call then fails with "Optimization
+/// cancelled".
+#[tauri::command]
+pub fn cancel_optimization(
+    optimizations: State<'_, OptimizationRegistry>,
+    optimization_id: String,
+) -> Result<(), String> {
+    if optimizations.cancel(&optimization_id) {
+        Ok(())
+    } else {
+        Err(format!("No running optimization {}", optimization_id))
+    }
 }
 
 #[tauri::command]
 pub async fn export_skill_plan_xml(
     pool: State<'_, db::Pool>,
     plan_id: i64,
+    character_id: Option<i64>,
+    include_trained: Option<bool>,
 ) -> Result<String, String> {
     let plan = db::skill_plans::get_skill_plan(&*pool, plan_id)
         .await
         .map_err(|e| format!("Failed to get skill plan: {}", e))?
         .ok_or_else(|| "Plan not found".to_string())?;
 
-    let entries = db::skill_plans::get_plan_entries(&*pool, plan_id)
-        .await
-        .map_err(|e| format!("Failed to get plan entries: {}", e))?;
+    let entries = export_entries(
+        &pool,
+        plan_id,
+        character_id,
+        include_trained.unwrap_or(false),
+    )
+    .await?;
 
     let mut writer = Writer::new(Cursor::new(Vec::new()));
 
@@ -1996,11 +3151,12 @@ pub async fn export_skill_plan_xml(
 
         let skill_id_str = entry.skill_type_id.to_string();
         let level_str = entry.planned_level.to_string();
+        let priority_str = entry.priority.to_string();
         let mut entry_elem = BytesStart::new("entry");
         entry_elem.push_attribute(("skillID", skill_id_str.as_str()));
         entry_elem.push_attribute(("skill", skill_name.as_str()));
         entry_elem.push_attribute(("level", level_str.as_str()));
-        entry_elem.push_attribute(("priority", "1"));
+        entry_elem.push_attribute(("priority", priority_str.as_str()));
         entry_elem.push_attribute(("type", entry.entry_type.as_str()));
 
         if entry.notes.is_some() {
@@ -2059,6 +3215,87 @@ pub async fn search_skills(
     Ok(results)
 }
 
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanSearchMatch {
+    /// `name`, `description`, `notes` or `skill`.
+    pub field: String,
+    pub snippet: String,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanSearchHit {
+    pub plan_id: i64_ts,
+    pub plan_name: String,
+    pub matches: Vec<PlanSearchMatch>,
+}
+
+/// Characters of context kept on each side of a match in a snippet.
+const SNIPPET_CONTEXT: usize = 40;
+
+/// The part of `text` around the first case-insensitive occurrence of
+/// `query`, with ellipses where it was cut. Falls back to the start of the
+/// text when the query does not occur verbatim (e.g. a skill level search).
+fn search_snippet(text: &str, query: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let lowered = text.to_lowercase();
+    let match_start = lowered
+        .find(&query.to_lowercase())
+        .map(|pos| lowered[..pos].chars().count())
+        .unwrap_or(0)
+        .min(chars.len());
+    let match_len = query.chars().count();
+
+    let start = match_start.saturating_sub(SNIPPET_CONTEXT);
+    let end = (match_start + match_len + SNIPPET_CONTEXT).min(chars.len());
+
+    let mut snippet: String = chars[start..end].iter().collect();
+    snippet = snippet.trim().to_string();
+    if start > 0 {
+        snippet.insert(0, '…');
+    }
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+/// Plans matching `query` in their name, description, entry notes or skill
+/// names, each with the snippets that matched.
+#[tauri::command]
+pub async fn search_plans(
+    read_pool: State<'_, db::ReadPool>,
+    query: String,
+) -> Result<Vec<PlanSearchHit>, String> {
+    let rows = db::skill_plans::search_plans(&read_pool, &query)
+        .await
+        .map_err(|e| format!("Failed to search plans: {}", e))?;
+
+    let query = query.trim();
+    let mut hits: Vec<PlanSearchHit> = Vec::new();
+    for row in rows {
+        let snippet = search_snippet(&row.text, query);
+        let found = match hits.last_mut() {
+            Some(hit) if hit.plan_id == row.plan_id => hit,
+            _ => {
+                hits.push(PlanSearchHit {
+                    plan_id: row.plan_id,
+                    plan_name: row.plan_name,
+                    matches: Vec::new(),
+                });
+                hits.last_mut().expect("just pushed")
+            }
+        };
+        found.matches.push(PlanSearchMatch {
+            field: row.field,
+            snippet,
+        });
+    }
+
+    Ok(hits)
+}
+
 #[typeshare]
 #[derive(Debug, Clone, Serialize)]
 pub struct PlanComparisonSummary {
@@ -2078,41 +3315,55 @@ pub struct MultiPlanComparisonResponse {
     pub comparisons: Vec<PlanComparisonSummary>,
 }
 
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanAssignmentMatrix {
+    pub plan: SkillPlanResponse,
+    /// One row per assigned character, in character order.
+    pub assignments: Vec<PlanComparisonSummary>,
+}
+
 #[tauri::command]
 pub async fn compare_skill_plan_with_character(
-    pool: State<'_, db::Pool>,
+    read_pool: State<'_, db::ReadPool>,
     plan_id: i64,
     character_id: i64,
 ) -> Result<PlanComparisonResponse, String> {
-    let plan = db::skill_plans::get_skill_plan(&*pool, plan_id)
+    compare_skill_plan_with_character_inner(&read_pool, plan_id, character_id).await
+}
+
+async fn compare_skill_plan_with_character_inner(
+    pool: &db::Pool,
+    plan_id: i64,
+    character_id: i64,
+) -> Result<PlanComparisonResponse, String> {
+    let plan = db::skill_plans::get_skill_plan(pool, plan_id)
         .await
         .map_err(|e| format!("Failed to get skill plan: {}", e))?
         .ok_or_else(|| "Plan not found".to_string())?;
 
-    let entries = db::skill_plans::get_plan_entries(&*pool, plan_id)
+    let entries = db::skill_plans::get_plan_entries(pool, plan_id)
         .await
         .map_err(|e| format!("Failed to get plan entries: {}", e))?;
 
-    let character_skills = db::get_character_skills(&pool, character_id)
-        .await
-        .map_err(|e| format!("Failed to get character skills: {}", e))?;
-
-    let character_skills_map: std::collections::HashMap<i64, db::CharacterSkill> = character_skills
-        .into_iter()
-        .map(|s| (s.skill_id, s))
-        .collect();
+    let training = CharacterTraining::load(pool, character_id).await?;
+    let character_skills_map = &training.skills;
 
     let skill_type_ids: Vec<i64> = entries.iter().map(|e| e.skill_type_id).collect();
-    let skill_attributes = utils::get_skill_attributes(&pool, &skill_type_ids)
+    let skill_attributes = utils::get_skill_attributes(pool, &skill_type_ids)
         .await
         .map_err(|e| format!("Failed to get skill attributes: {}", e))?;
 
-    let skill_names = utils::get_type_names(&pool, &skill_type_ids)
+    let skill_names = utils::get_type_names(pool, &skill_type_ids)
         .await
         .map_err(|e| format!("Failed to get skill names: {}", e))?;
 
+    let seconds = training.entries_seconds(&entries, &skill_attributes);
+    let cumulative = cumulative_seconds(&seconds);
+    let alpha_warnings = training.alpha_warnings(&entries, &skill_attributes);
+
     let mut comparison_entries = Vec::new();
-    for entry in entries {
+    for (index, entry) in entries.into_iter().enumerate() {
         let skill_name = skill_names
             .get(&entry.skill_type_id)
             .cloned()
@@ -2137,7 +3388,8 @@ pub async fn compare_skill_plan_with_character(
             })
             .unwrap_or((0, 0));
 
-        let missing_skillpoints = rank.map_or(0, |rank_val| {
+        // Excluded entries stay listed but never count as missing.
+        let missing_skillpoints = rank.filter(|_| !entry.excluded).map_or(0, |rank_val| {
             missing_sp_for_level(
                 entry.planned_level,
                 trained_level,
@@ -2168,6 +3420,9 @@ pub async fn compare_skill_plan_with_character(
             current_skillpoints: current_skillpoints_for_level,
             missing_skillpoints,
             status: status.to_string(),
+            excluded: entry.excluded,
+            training_seconds: seconds[index],
+            cumulative_training_seconds: cumulative[index],
         });
     }
 
@@ -2175,36 +3430,51 @@ pub async fn compare_skill_plan_with_character(
         plan: SkillPlanResponse::from(plan),
         character_id,
         entries: comparison_entries,
+        alpha_warnings,
     })
 }
 
 #[tauri::command]
 pub async fn compare_skill_plan_with_all_characters(
-    pool: State<'_, db::Pool>,
+    read_pool: State<'_, db::ReadPool>,
     plan_id: i64,
 ) -> Result<MultiPlanComparisonResponse, String> {
-    let plan = db::skill_plans::get_skill_plan(&*pool, plan_id)
+    let pool: &db::Pool = &read_pool;
+
+    let plan = db::skill_plans::get_skill_plan(pool, plan_id)
         .await
         .map_err(|e| format!("Failed to get skill plan: {}", e))?
         .ok_or_else(|| "Plan not found".to_string())?;
 
-    let entries = db::skill_plans::get_plan_entries(&*pool, plan_id)
+    let characters = db::get_all_characters(pool)
         .await
-        .map_err(|e| format!("Failed to get plan entries: {}", e))?;
+        .map_err(|e| format!("Failed to get characters: {}", e))?;
+
+    Ok(MultiPlanComparisonResponse {
+        plan: SkillPlanResponse::from(plan),
+        comparisons: plan_comparison_summaries(pool, plan_id, characters).await?,
+    })
+}
 
-    let characters = db::get_all_characters(&pool)
+/// How far each of `characters` is through the plan.
+async fn plan_comparison_summaries(
+    pool: &db::Pool,
+    plan_id: i64,
+    characters: Vec<db::Character>,
+) -> Result<Vec<PlanComparisonSummary>, String> {
+    let entries = db::skill_plans::get_plan_entries(pool, plan_id)
         .await
-        .map_err(|e| format!("Failed to get characters: {}", e))?;
+        .map_err(|e| format!("Failed to get plan entries: {}", e))?;
 
     let skill_type_ids: Vec<i64> = entries.iter().map(|e| e.skill_type_id).collect();
-    let skill_attributes = utils::get_skill_attributes(&pool, &skill_type_ids)
+    let skill_attributes = utils::get_skill_attributes(pool, &skill_type_ids)
         .await
         .map_err(|e| format!("Failed to get skill attributes: {}", e))?;
 
     let mut comparisons = Vec::new();
 
     for character in characters {
-        let character_skills = db::get_character_skills(&pool, character.character_id)
+        let character_skills = db::get_character_skills(pool, character.character_id)
             .await
             .map_err(|e| {
                 format!(
@@ -2219,7 +3489,7 @@ pub async fn compare_skill_plan_with_all_characters(
                 .map(|s| (s.skill_id, s))
                 .collect();
 
-        let attributes = db::get_character_attributes(&pool, character.character_id)
+        let attributes = db::get_character_attributes(pool, character.character_id)
             .await
             .map_err(|e| {
                 format!(
@@ -2233,7 +3503,7 @@ pub async fn compare_skill_plan_with_all_characters(
         let mut total_time_seconds = 0.0;
         let mut has_prerequisites = true;
 
-        for entry in &entries {
+        for entry in entries.iter().filter(|e| !e.excluded) {
             let char_skill = character_skills_map.get(&entry.skill_type_id);
             let trained_level = char_skill.map(|s| s.trained_skill_level).unwrap_or(0);
             let current_skillpoints = char_skill.map(|s| s.skillpoints_in_skill).unwrap_or(0);
@@ -2282,7 +3552,11 @@ pub async fn compare_skill_plan_with_all_characters(
                                     168 => attr.willpower,
                                     _ => 17, // default base
                                 };
-                                let sp_per_min = utils::calculate_sp_per_minute(p_val, s_val, true);
+                                let sp_per_min = utils::calculate_sp_per_minute(
+                                    p_val,
+                                    s_val,
+                                    character.is_omega,
+                                );
                                 if sp_per_min > 0.0 {
                                     total_time_seconds += (missing as f64 / sp_per_min) * 60.0;
                                 }
@@ -2298,7 +3572,7 @@ pub async fn compare_skill_plan_with_all_characters(
                     "SELECT required_skill_id, required_level FROM sde_skill_requirements WHERE skill_type_id = ?"
                 )
                 .bind(entry.skill_type_id)
-                .fetch_all(&*pool)
+                .fetch_all(pool)
                 .await
                 .map_err(|e| format!("Failed to fetch prereqs: {}", e))?;
 
@@ -2341,12 +3615,59 @@ pub async fn compare_skill_plan_with_all_characters(
         });
     }
 
-    Ok(MultiPlanComparisonResponse {
+    Ok(comparisons)
+}
+
+async fn plan_assignment_matrix(
+    pool: &db::Pool,
+    plan_id: i64,
+) -> Result<PlanAssignmentMatrix, String> {
+    let plan = db::skill_plans::get_skill_plan(pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get skill plan: {}", e))?
+        .ok_or_else(|| "Plan not found".to_string())?;
+    let assigned: HashSet<i64> = db::plan_assignments::get_plan_assignments(pool, plan_id)
+        .await
+        .map_err(|e| format!("Failed to get plan assignments: {}", e))?
+        .into_iter()
+        .collect();
+    let characters = db::get_all_characters(pool)
+        .await
+        .map_err(|e| format!("Failed to get characters: {}", e))?
+        .into_iter()
+        .filter(|c| assigned.contains(&c.character_id))
+        .collect();
+
+    Ok(PlanAssignmentMatrix {
         plan: SkillPlanResponse::from(plan),
-        comparisons,
+        assignments: plan_comparison_summaries(pool, plan_id, characters).await?,
     })
 }
 
+/// The characters assigned to the plan and how far each is through it.
+#[tauri::command]
+pub async fn get_plan_assignments(
+    read_pool: State<'_, db::ReadPool>,
+    plan_id: i64,
+) -> Result<PlanAssignmentMatrix, String> {
+    let pool: &db::Pool = &read_pool;
+    plan_assignment_matrix(pool, plan_id).await
+}
+
+/// Makes `character_ids` exactly the characters assigned to the plan, adding
+/// and removing assignments as needed, and returns the updated matrix.
+#[tauri::command]
+pub async fn set_plan_assignments(
+    pool: State<'_, db::Pool>,
+    plan_id: i64,
+    character_ids: Vec<i64>,
+) -> Result<PlanAssignmentMatrix, String> {
+    db::plan_assignments::set_plan_assignments(&pool, plan_id, &character_ids)
+        .await
+        .map_err(|e| format!("Failed to set plan assignments: {}", e))?;
+    plan_assignment_matrix(&pool, plan_id).await
+}
+
 fn trained_sp_for_level(planned_level: i64, current_skillpoints: i64, rank: i64) -> i64 {
     let total = utils::calculate_sp_for_level(rank, planned_level as i32);
     let previous = utils::calculate_sp_for_level(rank, (planned_level - 1) as i32);
@@ -2372,6 +3693,24 @@ fn missing_sp_for_level(
 mod tests {
     use super::*;
 
+    #[test]
+    fn decompress_emp_accepts_gzip_and_plain_xml() {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let xml = "\u{feff}<plan><entry skillID=\"3300\" level=\"1\" type=\"Planned\" /></plan>";
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(xml.as_bytes()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+
+        let expected = xml.trim_start_matches('\u{feff}');
+        assert_eq!(decompress_emp(&gzipped).unwrap(), expected);
+        assert_eq!(decompress_emp(xml.as_bytes()).unwrap(), expected);
+        assert!(decompress_emp(&[0x1f, 0x8b, 0, 1, 2])
+            .unwrap_err()
+            .starts_with("Invalid .emp file:"));
+    }
+
     #[test]
     fn missing_sp_scoped_to_single_level() {
         let rank = 1;
@@ -2496,62 +3835,317 @@ mod tests {
             "Abyssal Ore Processing 0\nAcceleration Control 5\nAccounting 0\nLight Missiles 5";
         let parsed = parse_skill_plan_text(text).unwrap();
         assert_eq!(
-            parsed,
-            vec![
-                ("Acceleration Control".to_string(), 5),
-                ("Light Missiles".to_string(), 5),
-            ]
+            parsed,
+            vec![
+                ("Acceleration Control".to_string(), 5),
+                ("Light Missiles".to_string(), 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_text_skips_level_zero_with_tab_separator() {
+        // Combined: tab-separated spreadsheet that also includes level-0 rows.
+        let text = "Accounting\t0\r\nCaldari Frigate\t5\r\n";
+        let parsed = parse_skill_plan_text(text).unwrap();
+        assert_eq!(parsed, vec![("Caldari Frigate".to_string(), 5)]);
+    }
+
+    #[test]
+    fn parse_text_rejects_level_above_five() {
+        let err = parse_skill_plan_text("Gunnery 6").unwrap_err();
+        assert!(err.contains("between 1 and 5"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_text_rejects_non_numeric_level() {
+        let err = parse_skill_plan_text("Gunnery X").unwrap_err();
+        assert!(err.contains("Invalid level"), "got: {err}");
+    }
+
+    #[test]
+    fn parse_text_reads_roman_levels_from_roman_exports() {
+        let parsed = parse_skill_plan_text("Gunnery V\nSmall Hybrid Turret iii").unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                ("Gunnery".to_string(), 5),
+                ("Small Hybrid Turret".to_string(), 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn user_facing_errors_pass_through_internal_ones_are_hidden() {
+        // Actionable input errors reach the user.
+        assert!(is_user_facing_import_error(
+            "Unmatched skills: Done Avionics, Evasive Manuevering"
+        ));
+        assert!(is_user_facing_import_error(
+            "Level must be between 1 and 5 in line: Gunnery 7"
+        ));
+        assert!(is_user_facing_import_error("Invalid plan: cycle detected"));
+        // Internal failures stay hidden behind the generic message.
+        assert!(!is_user_facing_import_error(
+            "Failed to lookup skill: pool timed out"
+        ));
+        assert!(!is_user_facing_import_error("Transaction failed: disk I/O"));
+        assert!(!is_user_facing_import_error(
+            "XML parsing error: unexpected EOF"
+        ));
+    }
+
+    #[test]
+    fn search_snippet_trims_long_text_around_the_match() {
+        assert_eq!(search_snippet("Marauders", "maraud"), "Marauders");
+
+        let notes = format!(
+            "{}needs Marauders V first{}",
+            "x".repeat(60),
+            "y".repeat(60)
+        );
+        let snippet = search_snippet(&notes, "marauders");
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(snippet.contains("needs Marauders V first"));
+        assert_eq!(
+            snippet.chars().count(),
+            2 + SNIPPET_CONTEXT * 2 + "marauders".len()
+        );
+    }
+
+    #[test]
+    fn parse_text_ignores_blank_lines() {
+        let text = "\n\nGunnery 3\n\n   \nDrones 4\n";
+        let parsed = parse_skill_plan_text(text).unwrap();
+        assert_eq!(
+            parsed,
+            vec![("Gunnery".to_string(), 3), ("Drones".to_string(), 4)]
+        );
+    }
+
+    #[tokio::test]
+    async fn import_plan_from_fitting_plans_requirements_and_prerequisites() {
+        use crate::testdata::{fixtures, TestDb};
+
+        // Rifter needs Minmatar Frigate I, which needs Spaceship Command I.
+        const SPACESHIP_COMMAND: i64 = 3327;
+        const MINMATAR_FRIGATE: i64 = 3329;
+
+        let db = TestDb::new_with_sde().await.unwrap();
+        let plan = fixtures::create_skill_plan(&db.pool, "Rifter").await;
+
+        import_plan_from_fitting_inner(
+            &db.pool,
+            plan,
+            "[Rifter, Tackle]\n[Empty Low slot]\n",
+            None,
+        )
+        .await
+        .unwrap();
+
+        let entries = db::skill_plans::get_plan_entries(&db.pool, plan)
+            .await
+            .unwrap();
+        let entry_type = |skill_id: i64| {
+            entries
+                .iter()
+                .find(|e| e.skill_type_id == skill_id && e.planned_level == 1)
+                .map(|e| e.entry_type.as_str())
+        };
+        assert_eq!(entry_type(MINMATAR_FRIGATE), Some("Planned"));
+        assert_eq!(entry_type(SPACESHIP_COMMAND), Some("Prerequisite"));
+
+        let err = import_plan_from_fitting_inner(&db.pool, plan, "[Not A Ship, Fit]", None)
+            .await
+            .unwrap_err();
+        assert!(err.starts_with("Invalid fit:"));
+        assert!(is_user_facing_import_error(&err));
+
+        sqlx::query("UPDATE sde_metadata SET import_mode = 'minimal'")
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        let err = import_plan_from_fitting_inner(&db.pool, plan, "[Rifter, Fit]", None)
+            .await
+            .unwrap_err();
+        assert_eq!(err, crate::sde::REQUIRES_FULL_SDE);
+        assert!(is_user_facing_import_error(&err));
+    }
+
+    #[tokio::test]
+    async fn excluded_entries_are_skipped_by_simulation_and_kept_in_json() {
+        use crate::testdata::{fixtures, TestDb};
+
+        const SPACESHIP_COMMAND: i64 = 3327;
+
+        let db = TestDb::new_with_sde().await.unwrap();
+        let plan = fixtures::create_skill_plan(&db.pool, "Injected").await;
+        let level_1 =
+            fixtures::add_plan_entry(&db.pool, plan, SPACESHIP_COMMAND, 1, "Planned").await;
+        fixtures::add_plan_entry(&db.pool, plan, SPACESHIP_COMMAND, 2, "Planned").await;
+        db::skill_plans::update_plan_entry(&db.pool, level_1, None, None, None, Some(true))
+            .await
+            .unwrap();
+
+        let entries = db::skill_plans::get_plan_entries(&db.pool, plan)
+            .await
+            .unwrap();
+        let profile = SimulationProfile {
+            implants: Attributes::default(),
+            remaps: Vec::new(),
+            accelerators: Vec::new(),
+            pauses: Vec::new(),
+        };
+        let result = simulation::simulate(&db.pool, &entries, profile, None, CloneState::Omega)
+            .await
+            .unwrap();
+
+        // Level II still trains, but only its own slice.
+        let rank = utils::get_skill_attributes(&db.pool, &[SPACESHIP_COMMAND])
+            .await
+            .unwrap()[&SPACESHIP_COMMAND]
+            .rank
+            .unwrap();
+        assert!(result.segments.iter().all(|s| s.entry_index == 1));
+        assert_eq!(
+            result.total_sp,
+            utils::calculate_sp_for_level(rank, 2) - utils::calculate_sp_for_level(rank, 1)
         );
-    }
 
-    #[test]
-    fn parse_text_skips_level_zero_with_tab_separator() {
-        // Combined: tab-separated spreadsheet that also includes level-0 rows.
-        let text = "Accounting\t0\r\nCaldari Frigate\t5\r\n";
-        let parsed = parse_skill_plan_text(text).unwrap();
-        assert_eq!(parsed, vec![("Caldari Frigate".to_string(), 5)]);
+        let exported: Vec<SkillmonPlanEntry> =
+            entries.into_iter().map(SkillmonPlanEntry::from).collect();
+        let json = serde_json::to_string(&exported).unwrap();
+        let reimported: Vec<SkillmonPlanEntry> = serde_json::from_str(&json).unwrap();
+        assert!(reimported[0].excluded);
+        assert!(!reimported[1].excluded);
+        assert!(!serde_json::to_value(&exported[1])
+            .unwrap()
+            .as_object()
+            .unwrap()
+            .contains_key("excluded"));
     }
 
-    #[test]
-    fn parse_text_rejects_level_above_five() {
-        let err = parse_skill_plan_text("Gunnery 6").unwrap_err();
-        assert!(err.contains("between 1 and 5"), "got: {err}");
-    }
+    #[tokio::test]
+    async fn planned_boosters_speed_up_entries_after_their_anchor() {
+        use crate::testdata::{fixtures, TestDb};
 
-    #[test]
-    fn parse_text_rejects_non_numeric_level() {
-        let err = parse_skill_plan_text("Gunnery V").unwrap_err();
-        assert!(err.contains("Invalid level"), "got: {err}");
-    }
+        let db = TestDb::new_with_sde().await.unwrap();
+        let plan = fixtures::create_skill_plan(&db.pool, "Boosted").await;
+        fixtures::add_plan_entry(&db.pool, plan, 3327, 1, "Planned").await;
+        fixtures::add_plan_entry(&db.pool, plan, 3327, 2, "Planned").await;
+        db::planned_boosters::save_planned_booster(
+            &db.pool,
+            plan,
+            None,
+            10,
+            86_400,
+            Some(3327),
+            Some(1),
+        )
+        .await
+        .unwrap();
+        // Anchored after an entry the plan no longer has.
+        db::planned_boosters::save_planned_booster(
+            &db.pool,
+            plan,
+            None,
+            10,
+            86_400,
+            Some(3327),
+            Some(5),
+        )
+        .await
+        .unwrap();
 
-    #[test]
-    fn user_facing_errors_pass_through_internal_ones_are_hidden() {
-        // Actionable input errors reach the user.
-        assert!(is_user_facing_import_error(
-            "Unmatched skills: Done Avionics, Evasive Manuevering"
-        ));
-        assert!(is_user_facing_import_error(
-            "Level must be between 1 and 5 in line: Gunnery 7"
-        ));
-        assert!(is_user_facing_import_error("Invalid plan: cycle detected"));
-        // Internal failures stay hidden behind the generic message.
-        assert!(!is_user_facing_import_error(
-            "Failed to lookup skill: pool timed out"
-        ));
-        assert!(!is_user_facing_import_error("Transaction failed: disk I/O"));
-        assert!(!is_user_facing_import_error(
-            "XML parsing error: unexpected EOF"
-        ));
-    }
+        let entries = db::skill_plans::get_plan_entries(&db.pool, plan)
+            .await
+            .unwrap();
+        let boosters = db::planned_boosters::get_plan_boosters(&db.pool, plan)
+            .await
+            .unwrap();
+        let accelerators = simulation::planned_booster_accelerators(&entries, &boosters);
+        assert_eq!(accelerators.len(), 1);
+        assert_eq!(accelerators[0].entry_index, 1);
+
+        let profile = |accelerators| SimulationProfile {
+            implants: Attributes::default(),
+            remaps: Vec::new(),
+            accelerators,
+            pauses: Vec::new(),
+        };
+        let plain = simulation::simulate(
+            &db.pool,
+            &entries,
+            profile(Vec::new()),
+            None,
+            CloneState::Omega,
+        )
+        .await
+        .unwrap();
+        let boosted = simulation::simulate(
+            &db.pool,
+            &entries,
+            profile(accelerators),
+            None,
+            CloneState::Omega,
+        )
+        .await
+        .unwrap();
 
-    #[test]
-    fn parse_text_ignores_blank_lines() {
-        let text = "\n\nGunnery 3\n\n   \nDrones 4\n";
-        let parsed = parse_skill_plan_text(text).unwrap();
         assert_eq!(
-            parsed,
-            vec![("Gunnery".to_string(), 3), ("Drones".to_string(), 4)]
+            boosted.segments[0].sp_per_minute,
+            plain.segments[0].sp_per_minute
         );
+        assert!(boosted.segments[1].sp_per_minute > plain.segments[1].sp_per_minute);
+        assert!(boosted.total_seconds < plain.total_seconds);
+    }
+
+    #[tokio::test]
+    async fn training_pauses_push_back_completion() {
+        use crate::testdata::{fixtures, TestDb};
+        use simulation::TrainingPause;
+
+        let db = TestDb::new_with_sde().await.unwrap();
+        let plan = fixtures::create_skill_plan(&db.pool, "Lapse").await;
+        fixtures::add_plan_entry(&db.pool, plan, 3327, 3, "Planned").await;
+        let entries = db::skill_plans::get_plan_entries(&db.pool, plan)
+            .await
+            .unwrap();
+        let profile = |pauses| SimulationProfile {
+            implants: Attributes::default(),
+            remaps: Vec::new(),
+            accelerators: Vec::new(),
+            pauses,
+        };
+
+        let continuous = simulation::simulate(
+            &db.pool,
+            &entries,
+            profile(Vec::new()),
+            None,
+            CloneState::Omega,
+        )
+        .await
+        .unwrap();
+        let paused = simulation::simulate(
+            &db.pool,
+            &entries,
+            profile(vec![TrainingPause {
+                start_seconds: 3_600,
+                duration_seconds: 86_400,
+            }]),
+            None,
+            CloneState::Omega,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(paused.paused_seconds, 86_400);
+        assert_eq!(paused.total_sp, continuous.total_sp);
+        assert!((paused.total_seconds - continuous.total_seconds - 86_400).abs() <= 1);
+        // Training stops at the pause and resumes when it ends.
+        assert_eq!(paused.segments[0].duration_seconds, 3_600);
+        assert_eq!(paused.segments[1].start_time_seconds, 3_600 + 86_400);
     }
 
     #[tokio::test]
@@ -2641,6 +4235,70 @@ mod tests {
         assert!(err.to_string().contains("two distinct"));
     }
 
+    #[test]
+    fn entry_training_time_uses_attributes_and_partial_progress() {
+        let training = CharacterTraining {
+            skills: HashMap::from([(
+                3300,
+                db::CharacterSkill {
+                    character_id: 1,
+                    skill_id: 3300,
+                    active_skill_level: 1,
+                    skillpoints_in_skill: 1_000,
+                    trained_skill_level: 1,
+                },
+            )]),
+            attributes: Some(db::CharacterAttributes {
+                character_id: 1,
+                charisma: 17,
+                intelligence: 17,
+                memory: 17,
+                perception: 27,
+                willpower: 22,
+                bonus_remaps: None,
+                accrued_remap_cooldown_date: None,
+                last_remap_date: None,
+            }),
+            is_omega: true,
+            alpha_caps: None,
+        };
+        let gunnery = utils::SkillAttributes {
+            primary_attribute: Some(167),
+            secondary_attribute: Some(168),
+            rank: Some(1),
+        };
+        let entry = |level, excluded| db::skill_plans::SkillPlanEntry {
+            entry_id: level,
+            plan_id: 1,
+            skill_type_id: 3300,
+            planned_level: level,
+            sort_order: level,
+            entry_type: "Planned".to_string(),
+            notes: None,
+            excluded,
+        };
+
+        // 38 SP/min; level II needs 1,415 SP, 415 of it still to go.
+        assert_eq!(
+            training.entry_seconds(&entry(2, false), Some(&gunnery)),
+            Some(655)
+        );
+        assert_eq!(
+            training.entry_seconds(&entry(1, false), Some(&gunnery)),
+            Some(0)
+        );
+        assert_eq!(
+            training.entry_seconds(&entry(3, true), Some(&gunnery)),
+            Some(0)
+        );
+        assert_eq!(training.entry_seconds(&entry(2, false), None), None);
+
+        assert_eq!(
+            cumulative_seconds(&[Some(10), None, Some(5)]),
+            vec![Some(10), None, Some(15)]
+        );
+    }
+
     #[tokio::test]
     async fn merge_plans_into_appends_in_place_and_skips_present() {
         use crate::testdata::{fixtures, TestDb};
@@ -2696,6 +4354,51 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn move_entries_to_plan_brings_prerequisites_along() {
+        use crate::testdata::{fixtures, TestDb};
+
+        // Skills 100 and 200 have no SDE requirements in the test database.
+        let db = TestDb::new().await.unwrap();
+        let source = fixtures::create_skill_plan(&db.pool, "Monster").await;
+        fixtures::add_plan_entry(&db.pool, source, 100, 1, "Planned").await;
+        let level_two = fixtures::add_plan_entry(&db.pool, source, 100, 2, "Planned").await;
+        let level_three = fixtures::add_plan_entry(&db.pool, source, 100, 3, "Planned").await;
+        fixtures::add_plan_entry(&db.pool, source, 200, 1, "Planned").await;
+        let target = fixtures::create_skill_plan(&db.pool, "Phase 2").await;
+
+        let err = move_entries_to_plan_inner(&db.pool, source, &[level_two], target)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("still required"));
+
+        let summary =
+            move_entries_to_plan_inner(&db.pool, source, &[level_two, level_three], target)
+                .await
+                .unwrap();
+        assert_eq!(summary.moved, 2);
+        assert_eq!(summary.prerequisites_added, 1);
+
+        let levels = |entries: Vec<db::skill_plans::SkillPlanEntry>| {
+            entries
+                .into_iter()
+                .map(|e| (e.skill_type_id, e.planned_level, e.sort_order))
+                .collect::<Vec<_>>()
+        };
+        let target_entries = db::skill_plans::get_plan_entries(&db.pool, target)
+            .await
+            .unwrap();
+        assert_eq!(target_entries[0].entry_type, "Prerequisite");
+        assert_eq!(
+            levels(target_entries),
+            vec![(100, 1, 0), (100, 2, 1), (100, 3, 2)]
+        );
+        let source_entries = db::skill_plans::get_plan_entries(&db.pool, source)
+            .await
+            .unwrap();
+        assert_eq!(levels(source_entries), vec![(100, 1, 0), (200, 1, 1)]);
+    }
+
     #[tokio::test]
     async fn merge_plans_into_preserves_existing_entry_ids() {
         use crate::testdata::{fixtures, TestDb};
@@ -2910,6 +4613,8 @@ mod tests {
                 planned_level: e.planned_level,
                 entry_type: e.entry_type.clone(),
                 notes: e.notes.clone(),
+                excluded: e.excluded,
+                priority: e.priority,
             })
             .collect();
         db::skill_plans::replace_plan_entries(&db.pool, plan, &rows)
@@ -3161,4 +4866,219 @@ mod tests {
             .unwrap_err();
         assert!(err.to_string().contains("does not exist"));
     }
+
+    #[tokio::test]
+    async fn entry_priority_is_validated_and_kept_by_history() {
+        use crate::testdata::{fixtures, TestDb};
+
+        let db = TestDb::new().await.unwrap();
+        let plan = fixtures::create_skill_plan(&db.pool, "Priorities").await;
+        let entry = fixtures::add_plan_entry(&db.pool, plan, 3327, 1, "Planned").await;
+        let other_plan = fixtures::create_skill_plan(&db.pool, "Other").await;
+        let foreign = fixtures::add_plan_entry(&db.pool, other_plan, 3327, 1, "Planned").await;
+
+        assert_eq!(
+            set_plan_entry_priority_inner(&db.pool, plan, &[entry], 6).await,
+            Err("Priority must be between 1 and 5".to_string())
+        );
+        assert_eq!(
+            set_plan_entry_priority_inner(&db.pool, plan, &[entry, foreign], 1).await,
+            Err(format!("Entry {} is not part of plan {}", foreign, plan))
+        );
+        let entries = db::skill_plans::get_plan_entries(&db.pool, plan)
+            .await
+            .unwrap();
+        assert_eq!(entries[0].priority, db::skill_plans::DEFAULT_ENTRY_PRIORITY);
+
+        set_plan_entry_priority_inner(&db.pool, plan, &[entry], 1)
+            .await
+            .unwrap();
+        let entries = db::skill_plans::get_plan_entries(&db.pool, plan)
+            .await
+            .unwrap();
+        assert_eq!(entries[0].priority, 1);
+        let entries = db::skill_plans::get_plan_entries(&db.pool, other_plan)
+            .await
+            .unwrap();
+        assert_eq!(entries[0].priority, db::skill_plans::DEFAULT_ENTRY_PRIORITY);
+
+        // Setting the same priority again changes nothing, so records nothing.
+        set_plan_entry_priority_inner(&db.pool, plan, &[entry], 1)
+            .await
+            .unwrap();
+        assert_eq!(
+            db::plan_revisions::get_plan_revisions(&db.pool, plan)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        set_plan_entry_priority_inner(&db.pool, plan, &[entry], 5)
+            .await
+            .unwrap();
+        let revision = db::plan_revisions::get_plan_revisions(&db.pool, plan)
+            .await
+            .unwrap()[0]
+            .revision_id;
+        let before = db::plan_revisions::get_revision_entries(&db.pool, plan, revision)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(before[0].priority, 1);
+    }
+
+    #[tokio::test]
+    async fn plan_owner_is_the_default_character_for_entries_and_exports() {
+        use crate::testdata::{fixtures, TestDb};
+
+        let db = TestDb::new_with_sde().await.unwrap();
+        db::add_character(&db.pool, 1, "Owner").await.unwrap();
+        db::set_character_skills(&db.pool, 1, &[(3327, 1, 250, 1)])
+            .await
+            .unwrap();
+        let plan = fixtures::create_skill_plan(&db.pool, "Mine").await;
+        fixtures::add_plan_entry(&db.pool, plan, 3327, 1, "Planned").await;
+        fixtures::add_plan_entry(&db.pool, plan, 3327, 2, "Planned").await;
+
+        let unowned = get_skill_plan_with_entries_inner(&db.pool, plan, None, true)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(unowned.character_id, None);
+        assert!(unowned.comparison.is_none());
+        assert_eq!(
+            export_entries(&db.pool, plan, None, false)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        assert_eq!(
+            set_plan_owner_inner(&db.pool, plan, Some(999)).await,
+            Err("Character 999 not found".to_string())
+        );
+        assert_eq!(
+            set_plan_owner_inner(&db.pool, plan + 1, Some(1)).await,
+            Err(format!("Plan {} not found", plan + 1))
+        );
+        set_plan_owner_inner(&db.pool, plan, Some(1)).await.unwrap();
+        let owned = get_skill_plan_with_entries_inner(&db.pool, plan, None, true)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(owned.plan.owner_character_id, Some(1));
+        assert_eq!(owned.character_id, Some(1));
+        assert_eq!(owned.comparison.unwrap().entries[0].status, "complete");
+
+        let remaining = export_entries(&db.pool, plan, None, false).await.unwrap();
+        assert_eq!(
+            remaining
+                .iter()
+                .map(|e| e.planned_level)
+                .collect::<Vec<_>>(),
+            vec![2]
+        );
+        assert_eq!(
+            export_entries(&db.pool, plan, None, true)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn json_export_round_trips_and_upgrades_old_versions() {
+        use crate::testdata::{fixtures, TestDb};
+
+        const SPACESHIP_COMMAND: i64 = 3327;
+
+        let db = TestDb::new_with_sde().await.unwrap();
+        let plan = fixtures::create_skill_plan(&db.pool, "Round trip").await;
+        let level_1 =
+            fixtures::add_plan_entry(&db.pool, plan, SPACESHIP_COMMAND, 1, "Planned").await;
+        fixtures::add_plan_entry(&db.pool, plan, SPACESHIP_COMMAND, 2, "Planned").await;
+        db::skill_plans::update_plan_entry(&db.pool, level_1, None, None, Some("first"), None)
+            .await
+            .unwrap();
+        db::skill_plans::set_entries_priority(&db.pool, plan, &[level_1], 1)
+            .await
+            .unwrap();
+        db::plan_comments::add_plan_comment(
+            &db.pool,
+            plan,
+            SPACESHIP_COMMAND,
+            2,
+            "Pilot",
+            "after the remap",
+            1_700_000_000,
+        )
+        .await
+        .unwrap();
+        let attributes = Attributes {
+            charisma: 17,
+            intelligence: 27,
+            memory: 21,
+            perception: 17,
+            willpower: 17,
+        };
+        db::remaps::save_remap(
+            &db.pool,
+            None,
+            Some(plan),
+            Some(SPACESHIP_COMMAND),
+            Some(1),
+            &attributes,
+        )
+        .await
+        .unwrap();
+        db::planned_boosters::save_planned_booster(&db.pool, plan, None, 10, 86_400, None, None)
+            .await
+            .unwrap();
+
+        let exported = export_skill_plan_json_inner(&db.pool, plan).await.unwrap();
+        let json = serde_json::to_string(&exported).unwrap();
+        let imported = import_skill_plan_json_inner(&db.pool, serde_json::from_str(&json).unwrap())
+            .await
+            .unwrap();
+        let reexported = export_skill_plan_json_inner(&db.pool, imported)
+            .await
+            .unwrap();
+        assert_eq!(
+            serde_json::to_value(&exported).unwrap(),
+            serde_json::to_value(&reexported).unwrap()
+        );
+        assert_eq!(reexported.boosters.len(), 1);
+        assert_eq!(reexported.entries[1].comments.len(), 1);
+        assert_eq!(reexported.entries[0].priority, 1);
+        assert!(!serde_json::to_value(&reexported.entries[1])
+            .unwrap()
+            .as_object()
+            .unwrap()
+            .contains_key("priority"));
+
+        // A v1 file has no comments or boosters and still imports.
+        let v1 = r#"{"version":1,"name":"Old","description":null,"auto_prerequisites":true,
+            "entries":[{"skill_type_id":3327,"level":1,"entry_type":"Planned","notes":null}],
+            "remaps":[]}"#;
+        let old = import_skill_plan_json_inner(&db.pool, serde_json::from_str(v1).unwrap())
+            .await
+            .unwrap();
+        let upgraded = export_skill_plan_json_inner(&db.pool, old).await.unwrap();
+        assert_eq!(upgraded.version, SkillmonPlan::CURRENT_VERSION);
+        assert_eq!(upgraded.entries.len(), 1);
+        assert_eq!(
+            upgraded.entries[0].priority,
+            db::skill_plans::DEFAULT_ENTRY_PRIORITY
+        );
+
+        let mut future: SkillmonPlan = serde_json::from_str(v1).unwrap();
+        future.version = SkillmonPlan::CURRENT_VERSION + 1;
+        let err = import_skill_plan_json_inner(&db.pool, future)
+            .await
+            .unwrap_err();
+        assert!(err.starts_with("Invalid plan:"));
+    }
 }
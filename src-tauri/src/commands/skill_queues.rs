@@ -22,3 +22,15 @@ pub async fn force_refresh_skill_queue(
 
     Ok(())
 }
+
+/// Queue edits made in game, newest first.
+#[tauri::command]
+pub async fn get_skill_queue_journal(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+    limit: Option<i64>,
+) -> Result<Vec<db::skill_queue_journal::QueueJournalEntry>, String> {
+    db::skill_queue_journal::get_queue_journal(&pool, character_id, limit.unwrap_or(100))
+        .await
+        .map_err(|e| format!("Failed to get skill queue journal: {}", e))
+}
@@ -1,10 +1,12 @@
 use std::collections::HashMap;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tauri::State;
 use typeshare::typeshare;
 
 use crate::db;
+use crate::skill_plans::next_level::{self, NextLevelEstimate};
+use crate::skill_summary::{self, SkillSummary};
 use crate::ts_types::i64_ts;
 use crate::utils;
 
@@ -414,24 +416,13 @@ pub async fn get_skill_details(
         });
     }
 
-    // Get reverse requirements (what requires this skill)
-    // Use LEFT JOINs to get both category and group names
-    // If type.category_id is NULL, use group.category_id to get the category
+    // Get reverse requirements (what requires this skill), precomputed at
+    // SDE import with the group and category already resolved
     let reverse_req_rows: Vec<ReverseReqRow> = sqlx::query_as(
         r#"
-            SELECT
-                sr.skill_type_id,
-                sr.required_level,
-                COALESCE(t.category_id, g.category_id) AS category_id,
-                COALESCE(c.name, cg.name) AS category_name,
-                g.name AS group_name,
-                t.group_id
-            FROM sde_skill_requirements sr
-            JOIN sde_types t ON sr.skill_type_id = t.type_id
-            LEFT JOIN sde_groups g ON t.group_id = g.group_id
-            LEFT JOIN sde_categories c ON t.category_id = c.category_id
-            LEFT JOIN sde_categories cg ON g.category_id = cg.category_id
-            WHERE sr.required_skill_id = ? AND t.published = 1
+            SELECT type_id, required_level, category_id, category_name, group_name, group_id
+            FROM sde_required_for
+            WHERE required_skill_id = ?
             "#,
     )
     .bind(skill_id)
@@ -508,3 +499,84 @@ pub async fn get_skill_details(
         requires_omega,
     })
 }
+
+#[tauri::command]
+pub async fn queue_next_level_estimate(
+    read_pool: State<'_, db::ReadPool>,
+    character_id: i64,
+    skill_id: i64,
+) -> Result<NextLevelEstimate, String> {
+    let pool: &db::Pool = &read_pool;
+    next_level::next_level_estimate(pool, character_id, skill_id)
+        .await
+        .map_err(|e| format!("Failed to estimate next level: {}", e))
+}
+
+/// Level V counts, specialist scores and the nearest level V skills for the
+/// character sheet.
+#[tauri::command]
+pub async fn get_skill_summary(
+    read_pool: State<'_, db::ReadPool>,
+    character_id: i64,
+) -> Result<SkillSummary, String> {
+    let pool: &db::Pool = &read_pool;
+    skill_summary::skill_summary(pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to summarize skills: {}", e))
+}
+
+/// Where an [`sp_needed`] calculation starts: a fully trained level, or the
+/// skill's exact SP (counting partial progress).
+#[typeshare]
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(tag = "type", content = "content")]
+pub enum SpStart {
+    Level(i64_ts),
+    Sp(i64_ts),
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct SpNeededResponse {
+    pub skill_type_id: i64_ts,
+    pub rank: i64_ts,
+    pub from_sp: i64_ts,
+    /// Level fully trained at `from_sp`.
+    pub from_level: i64_ts,
+    pub to_level: i64_ts,
+    /// Total SP in the skill once `to_level` is trained.
+    pub target_sp: i64_ts,
+    pub sp_needed: i64_ts,
+}
+
+#[tauri::command]
+pub async fn sp_needed(
+    read_pool: State<'_, db::ReadPool>,
+    skill_type_id: i64,
+    from: SpStart,
+    to_level: i64,
+) -> Result<SpNeededResponse, String> {
+    if !(1..=5).contains(&to_level) {
+        return Err("Target level must be between 1 and 5".to_string());
+    }
+    let pool: &db::Pool = &read_pool;
+    let rank = utils::get_skill_attributes(pool, &[skill_type_id])
+        .await?
+        .get(&skill_type_id)
+        .and_then(|attrs| attrs.rank)
+        .ok_or_else(|| format!("Skill {} has no training rank", skill_type_id))?;
+
+    let from_sp = match from {
+        SpStart::Level(level) => utils::calculate_sp_for_level(rank, level.clamp(0, 5) as i32),
+        SpStart::Sp(sp) => sp.max(0),
+    };
+    Ok(SpNeededResponse {
+        skill_type_id,
+        rank,
+        from_sp,
+        from_level: utils::level_for_sp(rank, from_sp) as i64,
+        to_level,
+        target_sp: utils::calculate_sp_for_level(rank, to_level as i32),
+        sp_needed: utils::sp_needed(rank, from_sp, to_level as i32),
+    })
+}
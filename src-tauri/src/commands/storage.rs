@@ -0,0 +1,246 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{Connection, SqliteConnection};
+use tauri::{AppHandle, Manager, State};
+use typeshare::typeshare;
+
+use crate::db::retention::{RetentionSettings, TableStorage};
+use crate::scheduler::{ScheduledTask, Scheduler};
+use crate::storage::{self, StorageLocation, DATABASE_FILE_NAME};
+use crate::ts_types::i64_ts;
+use crate::{db, esi, refresh};
+
+const RESTART_DELAY: Duration = Duration::from_millis(500);
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageInfo {
+    pub data_dir: String,
+    pub default_data_dir: String,
+    pub database_path: String,
+    pub portable: bool,
+    pub database_size_bytes: i64_ts,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageMigrationResult {
+    pub new_data_dir: String,
+    /// The old database is left in place as a backup; the UI can offer to
+    /// delete it once the app has restarted on the new location.
+    pub previous_data_dir: String,
+    /// Always set: the app restarts on its own shortly after replying.
+    pub restart_required: bool,
+}
+
+#[tauri::command]
+pub async fn get_storage_info(location: State<'_, StorageLocation>) -> Result<StorageInfo, String> {
+    let database_path = location.database_path();
+    let database_size_bytes = [
+        database_path.clone(),
+        database_path.with_extension("sqlite-wal"),
+    ]
+    .iter()
+    .filter_map(|p| std::fs::metadata(p).ok())
+    .map(|m| m.len() as i64)
+    .sum();
+
+    Ok(StorageInfo {
+        data_dir: location.data_dir.display().to_string(),
+        default_data_dir: location.default_data_dir.display().to_string(),
+        database_path: database_path.display().to_string(),
+        portable: location.portable,
+        database_size_bytes,
+    })
+}
+
+/// Copy the live database to `target_dir` and restart on it. The scheduler
+/// and refreshers are stopped first and the app restarts right after the
+/// switch is recorded, so nothing keeps writing to the old database once the
+/// copy is taken. The copy is integrity-checked before the switch, and a
+/// failure at any step leaves the current location untouched and restarts the
+/// background work.
+#[tauri::command]
+pub async fn migrate_data_directory(
+    app: AppHandle,
+    pool: State<'_, db::Pool>,
+    location: State<'_, StorageLocation>,
+    target_dir: String,
+) -> Result<StorageMigrationResult, String> {
+    let target = PathBuf::from(target_dir.trim());
+    storage::validate_migration_target(&location, &target).map_err(|e| e.to_string())?;
+
+    let paused = stop_background_writers(&app).await;
+    if let Err(e) = copy_and_switch(&pool, &location, &target).await {
+        resume_background_writers(&app, &pool, &paused).await;
+        return Err(e);
+    }
+    restart_after_reply(app);
+
+    Ok(StorageMigrationResult {
+        new_data_dir: target.display().to_string(),
+        previous_data_dir: location.data_dir.display().to_string(),
+        restart_required: true,
+    })
+}
+
+/// Move back to the platform default location. A database left there by an
+/// earlier migration is stale, so it is renamed aside rather than blocking.
+#[tauri::command]
+pub async fn reset_data_directory(
+    app: AppHandle,
+    pool: State<'_, db::Pool>,
+    location: State<'_, StorageLocation>,
+) -> Result<StorageMigrationResult, String> {
+    let stale = location.default_data_dir.join(DATABASE_FILE_NAME);
+    let mut backup = None;
+    if !location.portable && location.data_dir != location.default_data_dir && stale.exists() {
+        let renamed = stale.with_extension(format!(
+            "sqlite.bak-{}",
+            chrono::Utc::now().format("%Y%m%d%H%M%S")
+        ));
+        std::fs::rename(&stale, &renamed)
+            .map_err(|e| format!("Failed to back up old database: {}", e))?;
+        for suffix in ["sqlite-wal", "sqlite-shm"] {
+            std::fs::remove_file(stale.with_extension(suffix)).ok();
+        }
+        backup = Some(renamed);
+    }
+
+    // Recorded before the copy so the entry travels with the database.
+    let target = location.default_data_dir.display().to_string();
+    let params = serde_json::json!({
+        "previous_data_dir": location.data_dir.display().to_string(),
+        "target_data_dir": target,
+        "backup": backup.map(|b| b.display().to_string()),
+    });
+    super::audit_log::audit(&pool, "reset_data_directory", params).await;
+    migrate_data_directory(app, pool, location, target).await
+}
+
+async fn copy_and_switch(
+    pool: &db::Pool,
+    location: &StorageLocation,
+    target: &Path,
+) -> Result<(), String> {
+    let target_db = target.join(DATABASE_FILE_NAME);
+    let target_db_str = target_db
+        .to_str()
+        .ok_or_else(|| "Target path is not valid UTF-8".to_string())?;
+
+    sqlx::query("VACUUM INTO ?")
+        .bind(target_db_str)
+        .execute(pool)
+        .await
+        .map_err(|e| format!("Failed to copy database: {}", e))?;
+
+    if let Err(e) = verify_copy(&target_db).await {
+        std::fs::remove_file(&target_db).ok();
+        return Err(format!("Copied database failed verification: {}", e));
+    }
+
+    storage::write_config(&location.default_data_dir, target)
+        .map_err(|e| format!("Failed to save data directory: {}", e))
+}
+
+/// Pause the scheduler and stop every refresher, waiting for work in flight.
+/// Returns the scheduler tasks that were paused here.
+async fn stop_background_writers(app: &AppHandle) -> Vec<ScheduledTask> {
+    let paused = app.state::<Scheduler>().pause_all_and_wait().await;
+    let handles = app
+        .state::<Mutex<refresh::RefreshSupervisor>>()
+        .lock()
+        .unwrap()
+        .cancel_all();
+    for h in handles {
+        let _ = h.await;
+    }
+    paused
+}
+
+async fn resume_background_writers(app: &AppHandle, pool: &db::Pool, paused: &[ScheduledTask]) {
+    app.state::<Scheduler>().resume(paused);
+    let characters = db::get_all_characters(pool).await.unwrap_or_default();
+    let rate_limits = app.state::<esi::RateLimitStore>().inner().clone();
+    let activity = app.state::<refresh::WindowActivityStore>().inner().clone();
+    let mut sup = app
+        .state::<Mutex<refresh::RefreshSupervisor>>()
+        .lock()
+        .unwrap();
+    for character in characters {
+        sup.spawn_character(
+            character.character_id,
+            pool.clone(),
+            app.clone(),
+            rate_limits.clone(),
+            activity.clone(),
+        );
+    }
+}
+
+/// Restart once this command's reply has had time to reach the window.
+fn restart_after_reply(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(RESTART_DELAY).await;
+        app.restart();
+    });
+}
+
+#[tauri::command]
+pub async fn get_storage_usage(
+    read_pool: State<'_, db::ReadPool>,
+) -> Result<Vec<TableStorage>, String> {
+    let pool: &db::Pool = &read_pool;
+    db::retention::get_storage_usage(pool)
+        .await
+        .map_err(|e| format!("Failed to get storage usage: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_retention_settings(
+    pool: State<'_, db::Pool>,
+) -> Result<RetentionSettings, String> {
+    db::retention::get_retention_settings(&pool)
+        .await
+        .map_err(|e| format!("Failed to get retention settings: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_retention_settings(
+    pool: State<'_, db::Pool>,
+    settings: RetentionSettings,
+) -> Result<(), String> {
+    let windows = [
+        settings.sp_history_days,
+        settings.queue_journal_days,
+        settings.dismissed_notification_days,
+    ];
+    if windows.iter().flatten().any(|days| *days < 1) {
+        return Err("Retention periods must be at least one day".to_string());
+    }
+    db::retention::set_retention_settings(&pool, &settings)
+        .await
+        .map_err(|e| format!("Failed to save retention settings: {}", e))
+}
+
+#[tauri::command]
+pub fn restart_app(app: AppHandle) {
+    app.restart();
+}
+
+async fn verify_copy(path: &std::path::Path) -> anyhow::Result<()> {
+    let options = SqliteConnectOptions::new().filename(path).read_only(true);
+    let mut conn = SqliteConnection::connect_with(&options).await?;
+    let result: String = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_one(&mut conn)
+        .await?;
+    conn.close().await?;
+    if result != "ok" {
+        anyhow::bail!("integrity check reported: {}", result);
+    }
+    Ok(())
+}
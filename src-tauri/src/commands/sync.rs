@@ -0,0 +1,45 @@
+use tauri::State;
+
+use crate::db;
+use crate::sync::{self, SyncConfig, SyncConfigSummary, SyncReport};
+
+/// The stored config without its secrets; only whether each one is set.
+#[tauri::command]
+pub async fn get_sync_config(
+    pool: State<'_, db::Pool>,
+) -> Result<Option<SyncConfigSummary>, String> {
+    let config = sync::get_config(&pool)
+        .await
+        .map_err(|e| format!("Failed to get sync config: {}", e))?;
+    Ok(config.as_ref().map(SyncConfigSummary::from))
+}
+
+/// Pass `None` to disable sync on this device. Remote data is left untouched.
+/// Empty secrets keep the ones already stored.
+#[tauri::command]
+pub async fn set_sync_config(
+    pool: State<'_, db::Pool>,
+    mut config: Option<SyncConfig>,
+) -> Result<(), String> {
+    if let Some(config) = &mut config {
+        let stored = sync::get_config(&pool)
+            .await
+            .map_err(|e| format!("Failed to get sync config: {}", e))?;
+        if let Some(stored) = &stored {
+            config.keep_stored_secrets(stored);
+        }
+        if config.passphrase.trim().is_empty() {
+            return Err("Sync passphrase cannot be empty".to_string());
+        }
+    }
+    sync::set_config(&pool, config.as_ref())
+        .await
+        .map_err(|e| format!("Failed to set sync config: {}", e))
+}
+
+#[tauri::command]
+pub async fn sync_now(pool: State<'_, db::Pool>) -> Result<SyncReport, String> {
+    sync::sync_now(&pool)
+        .await
+        .map_err(|e| format!("Sync failed: {}", e))
+}
@@ -0,0 +1,59 @@
+use tauri::{AppHandle, State};
+
+use crate::db;
+use crate::db::watchlist::{WatchedCharacter, WatchedCharacterChange};
+use crate::esi;
+use crate::watchlist;
+
+#[tauri::command]
+pub async fn get_watchlist(pool: State<'_, db::Pool>) -> Result<Vec<WatchedCharacter>, String> {
+    db::watchlist::get_watched_characters(&pool)
+        .await
+        .map_err(|e| format!("Failed to get watchlist: {}", e))
+}
+
+/// Accepts an exact character name or a character id.
+#[tauri::command]
+pub async fn add_watched_character(
+    pool: State<'_, db::Pool>,
+    rate_limits: State<'_, esi::RateLimitStore>,
+    name_or_id: String,
+) -> Result<WatchedCharacter, String> {
+    watchlist::add(&pool, &rate_limits, &name_or_id)
+        .await
+        .map_err(|e| format!("Failed to watch character: {}", e))
+}
+
+#[tauri::command]
+pub async fn remove_watched_character(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+) -> Result<(), String> {
+    db::watchlist::remove_watched_character(&pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to remove watched character: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_watched_character_changes(
+    pool: State<'_, db::Pool>,
+    character_id: i64,
+) -> Result<Vec<WatchedCharacterChange>, String> {
+    db::watchlist::get_watched_character_changes(&pool, character_id)
+        .await
+        .map_err(|e| format!("Failed to get watched character changes: {}", e))
+}
+
+#[tauri::command]
+pub async fn refresh_watchlist(
+    app: AppHandle,
+    pool: State<'_, db::Pool>,
+    rate_limits: State<'_, esi::RateLimitStore>,
+) -> Result<Vec<WatchedCharacter>, String> {
+    watchlist::refresh_all(&app, &pool, &rate_limits)
+        .await
+        .map_err(|e| format!("Failed to refresh watchlist: {}", e))?;
+    db::watchlist::get_watched_characters(&pool)
+        .await
+        .map_err(|e| format!("Failed to get watchlist: {}", e))
+}
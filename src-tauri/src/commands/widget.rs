@@ -0,0 +1,147 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::State;
+use typeshare::typeshare;
+
+use crate::ts_types::i64_ts;
+use crate::{cache, db, esi, queue_math, utils};
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct WidgetCharacter {
+    pub character_id: i64_ts,
+    pub character_name: String,
+    pub training_skill_name: Option<String>,
+    pub training_level: Option<i64_ts>,
+    /// 0.0–1.0 progress through the level currently training.
+    pub percent_complete: Option<f64>,
+    pub finish_time: Option<String>,
+    pub queue_finish_time: Option<String>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct WidgetPayload {
+    pub generated_at: String,
+    pub characters: Vec<WidgetCharacter>,
+}
+
+struct TrainingSummary {
+    skill_id: i64,
+    level: i64,
+    percent_complete: f64,
+    finish_time: DateTime<Utc>,
+}
+
+/// Minimal per-character training state for small-surface consumers (OS
+/// widgets, the pop-out window). Reads only the ESI cache and one name lookup —
+/// never hits the network — so it is safe to poll frequently.
+#[tauri::command]
+pub async fn get_widget_payload(pool: State<'_, db::Pool>) -> Result<WidgetPayload, String> {
+    let characters = db::get_all_characters(&pool)
+        .await
+        .map_err(|e| format!("Failed to get characters: {}", e))?;
+
+    let now = Utc::now();
+    let mut rows = Vec::with_capacity(characters.len());
+    for character in characters {
+        let endpoint_path = format!("characters/{}/skillqueue", character.character_id);
+        let cache_key = cache::build_cache_key(&endpoint_path, character.character_id);
+        let queue = cache::get_cached_response(&pool, &cache_key)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|entry| {
+                serde_json::from_str::<Vec<esi::CharactersSkillqueueSkill>>(&entry.response_body)
+                    .ok()
+            })
+            .unwrap_or_default();
+
+        let training = current_training(&queue, now);
+        let queue_finish = queue.iter().filter_map(|item| item.finish_date).max();
+        rows.push((character, training, queue_finish));
+    }
+
+    let skill_ids: Vec<i64> = rows
+        .iter()
+        .filter_map(|(_, training, _)| training.as_ref().map(|t| t.skill_id))
+        .collect();
+    let names = utils::get_type_names(&pool, &skill_ids)
+        .await
+        .unwrap_or_default();
+
+    let characters = rows
+        .into_iter()
+        .map(|(character, training, queue_finish)| WidgetCharacter {
+            character_id: character.character_id,
+            character_name: character.character_name,
+            training_skill_name: training
+                .as_ref()
+                .and_then(|t| names.get(&t.skill_id).cloned()),
+            training_level: training.as_ref().map(|t| t.level),
+            percent_complete: training.as_ref().map(|t| t.percent_complete),
+            finish_time: training.as_ref().map(|t| t.finish_time.to_rfc3339()),
+            queue_finish_time: queue_finish.map(|f| f.to_rfc3339()),
+        })
+        .collect();
+
+    Ok(WidgetPayload {
+        generated_at: now.to_rfc3339(),
+        characters,
+    })
+}
+
+fn current_training(
+    queue: &[esi::CharactersSkillqueueSkill],
+    now: DateTime<Utc>,
+) -> Option<TrainingSummary> {
+    let item = queue_math::currently_training(queue, now)?;
+    Some(TrainingSummary {
+        skill_id: item.skill_id,
+        level: item.finished_level,
+        percent_complete: queue_math::level_progress(item, now),
+        finish_time: item.finish_date?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_accounts_for_sp_trained_before_the_current_session() {
+        let now = Utc::now();
+        // Level spans 0..1000 SP; 500 were already trained when this session
+        // started, and the session is halfway done.
+        let item = esi::CharactersSkillqueueSkill {
+            skill_id: 3300,
+            finished_level: 3,
+            queue_position: 0,
+            start_date: Some(now - chrono::Duration::minutes(30)),
+            finish_date: Some(now + chrono::Duration::minutes(30)),
+            training_start_sp: Some(500),
+            level_start_sp: Some(0),
+            level_end_sp: Some(1000),
+        };
+
+        let summary = current_training(&[item], now).unwrap();
+        assert_eq!(summary.skill_id, 3300);
+        assert_eq!(summary.level, 3);
+        assert!((summary.percent_complete - 0.75).abs() < 0.01);
+    }
+
+    #[test]
+    fn paused_queue_has_no_training_summary() {
+        let item = esi::CharactersSkillqueueSkill {
+            skill_id: 3300,
+            finished_level: 3,
+            queue_position: 0,
+            start_date: None,
+            finish_date: None,
+            training_start_sp: Some(0),
+            level_start_sp: Some(0),
+            level_end_sp: Some(1000),
+        };
+        assert!(current_training(&[item], Utc::now()).is_none());
+    }
+}
@@ -1,8 +1,12 @@
+use std::collections::BTreeMap;
+
 use anyhow::Result;
 use serde::Serialize;
 use sqlx::FromRow;
+use typeshare::typeshare;
 
 use super::{characters::Character, Pool};
+use crate::ts_types::i64_ts;
 
 #[derive(Debug, Clone, Serialize, FromRow)]
 pub struct Account {
@@ -186,3 +190,202 @@ pub async fn get_unassigned_characters(pool: &Pool) -> Result<Vec<Character>> {
 
     Ok(characters)
 }
+
+/// Creates one account per non-blank name, in order, after the existing ones.
+pub async fn bulk_create_accounts(pool: &Pool, names: &[String]) -> Result<Vec<i64>> {
+    let mut tx = pool.begin().await?;
+
+    let max_sort_order: Option<i64> = sqlx::query_scalar("SELECT MAX(sort_order) FROM accounts")
+        .fetch_one(&mut *tx)
+        .await?;
+    let mut next_sort_order = max_sort_order.unwrap_or(-1) + 1;
+
+    let mut ids = Vec::new();
+    for name in names.iter().map(|n| n.trim()).filter(|n| !n.is_empty()) {
+        let result = sqlx::query("INSERT INTO accounts (name, sort_order) VALUES (?, ?)")
+            .bind(name)
+            .bind(next_sort_order)
+            .execute(&mut *tx)
+            .await?;
+        ids.push(result.last_insert_rowid());
+        next_sort_order += 1;
+    }
+
+    tx.commit().await?;
+
+    Ok(ids)
+}
+
+/// The part of a character name before a trailing sequence marker, e.g.
+/// "Miner 1", "Miner02" and "Miner III" all give "Miner". `None` when the
+/// name has no such marker.
+pub fn name_prefix(name: &str) -> Option<String> {
+    let name = name.trim();
+    let without_digits = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    let stripped = if without_digits.len() < name.len() {
+        without_digits
+    } else {
+        // Roman numerals only count as their own word ("Miner II", not "Nikki").
+        let (head, last) = name.rsplit_once(' ')?;
+        if last.is_empty() || !last.chars().all(|c| matches!(c, 'I' | 'V' | 'X')) {
+            return None;
+        }
+        head
+    };
+    let prefix = stripped.trim_end_matches([' ', '-', '_', '.', '#']);
+    (!prefix.is_empty()).then(|| prefix.to_string())
+}
+
+/// Prefixes shared by at least two of `characters`, keyed case-insensitively
+/// and spelled as in the first name seen, with the matching characters.
+pub fn suggest_prefixes(characters: &[Character]) -> Vec<(String, Vec<Character>)> {
+    let mut groups: BTreeMap<String, (String, Vec<Character>)> = BTreeMap::new();
+    for character in characters {
+        if let Some(prefix) = name_prefix(&character.character_name) {
+            groups
+                .entry(prefix.to_lowercase())
+                .or_insert_with(|| (prefix, Vec::new()))
+                .1
+                .push(character.clone());
+        }
+    }
+    groups
+        .into_values()
+        .filter(|(_, members)| members.len() >= 2)
+        .collect()
+}
+
+/// Result of grouping characters into accounts by name prefix.
+#[typeshare]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PrefixGroupingOutcome {
+    pub accounts_created: i64_ts,
+    pub characters_assigned: i64_ts,
+}
+
+/// For each chosen prefix, assigns the unassigned characters whose
+/// [`name_prefix`] matches it (case-insensitively) to the account named after
+/// the prefix, creating that account if needed. Characters already in an
+/// account are left alone.
+pub async fn group_characters_by_prefix(
+    pool: &Pool,
+    prefixes: &[String],
+) -> Result<PrefixGroupingOutcome> {
+    let unassigned = get_unassigned_characters(pool).await?;
+    let mut tx = pool.begin().await?;
+    let mut outcome = PrefixGroupingOutcome::default();
+
+    for prefix in prefixes.iter().map(|p| p.trim()).filter(|p| !p.is_empty()) {
+        let members: Vec<&Character> = unassigned
+            .iter()
+            .filter(|c| {
+                name_prefix(&c.character_name).is_some_and(|p| p.eq_ignore_ascii_case(prefix))
+            })
+            .collect();
+        if members.is_empty() {
+            continue;
+        }
+
+        let existing: Option<i64> =
+            sqlx::query_scalar("SELECT id FROM accounts WHERE name = ? COLLATE NOCASE LIMIT 1")
+                .bind(prefix)
+                .fetch_optional(&mut *tx)
+                .await?;
+        let account_id = match existing {
+            Some(id) => id,
+            None => {
+                let max_sort_order: Option<i64> =
+                    sqlx::query_scalar("SELECT MAX(sort_order) FROM accounts")
+                        .fetch_one(&mut *tx)
+                        .await?;
+                outcome.accounts_created += 1;
+                sqlx::query("INSERT INTO accounts (name, sort_order) VALUES (?, ?)")
+                    .bind(prefix)
+                    .bind(max_sort_order.unwrap_or(-1) + 1)
+                    .execute(&mut *tx)
+                    .await?
+                    .last_insert_rowid()
+            }
+        };
+
+        let max_sort_order: Option<i64> =
+            sqlx::query_scalar("SELECT MAX(sort_order) FROM characters WHERE account_id = ?")
+                .bind(account_id)
+                .fetch_one(&mut *tx)
+                .await?;
+        let mut next_sort_order = max_sort_order.unwrap_or(-1) + 1;
+
+        let mut members = members;
+        members.sort_by(|a, b| a.character_name.cmp(&b.character_name));
+        for character in members {
+            sqlx::query(
+                "UPDATE characters SET account_id = ?, sort_order = ?
+                 WHERE character_id = ? AND account_id IS NULL",
+            )
+            .bind(account_id)
+            .bind(next_sort_order)
+            .bind(character.character_id)
+            .execute(&mut *tx)
+            .await?;
+            next_sort_order += 1;
+            outcome.characters_assigned += 1;
+        }
+    }
+
+    tx.commit().await?;
+
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    #[test]
+    fn name_prefix_strips_sequence_markers() {
+        assert_eq!(name_prefix("Miner 1").as_deref(), Some("Miner"));
+        assert_eq!(name_prefix("Miner02").as_deref(), Some("Miner"));
+        assert_eq!(name_prefix("Hauler-12").as_deref(), Some("Hauler"));
+        assert_eq!(name_prefix("Miner III").as_deref(), Some("Miner"));
+        assert_eq!(name_prefix("Nikki"), None);
+        assert_eq!(name_prefix("VIXI"), None);
+        assert_eq!(name_prefix("42"), None);
+    }
+
+    #[tokio::test]
+    async fn suggests_only_shared_prefixes() {
+        let db = TestDb::new().await.unwrap();
+        for (id, name) in [(1, "Miner 1"), (2, "miner 2"), (3, "Hauler 1"), (4, "Main")] {
+            crate::db::add_character(&db.pool, id, name).await.unwrap();
+        }
+        let unassigned = get_unassigned_characters(&db.pool).await.unwrap();
+        let suggestions = suggest_prefixes(&unassigned);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].1.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn groups_unassigned_characters_by_chosen_prefix() {
+        let db = TestDb::new().await.unwrap();
+        for (id, name) in [(1, "Miner 1"), (2, "Miner 2"), (3, "Hauler 1"), (4, "Main")] {
+            crate::db::add_character(&db.pool, id, name).await.unwrap();
+        }
+        let ids = bulk_create_accounts(&db.pool, &["Miner".into(), " ".into()])
+            .await
+            .unwrap();
+        assert_eq!(ids.len(), 1);
+
+        let outcome = group_characters_by_prefix(&db.pool, &["miner".into(), "Hauler".into()])
+            .await
+            .unwrap();
+        assert_eq!(outcome.accounts_created, 1);
+        assert_eq!(outcome.characters_assigned, 3);
+
+        let miners = get_characters_for_account(&db.pool, ids[0]).await.unwrap();
+        assert_eq!(miners.len(), 2);
+        let unassigned = get_unassigned_characters(&db.pool).await.unwrap();
+        assert_eq!(unassigned.len(), 1);
+        assert_eq!(unassigned[0].character_name, "Main");
+    }
+}
@@ -0,0 +1,121 @@
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::FromRow;
+use typeshare::typeshare;
+
+use super::Pool;
+use crate::ts_types::i64_ts;
+
+pub const ACTIVITY_SKILLS_FETCHED: &str = "skills_fetched";
+pub const ACTIVITY_QUEUE_CHANGED: &str = "queue_changed";
+pub const ACTIVITY_SDE_UPDATED: &str = "sde_updated";
+
+/// The feed is a recent-history view, not an audit log; older rows are
+/// dropped as new ones arrive.
+const MAX_ACTIVITY_ROWS: i64 = 1000;
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ActivityEntry {
+    pub id: i64_ts,
+    pub character_id: Option<i64_ts>,
+    pub character_name: Option<String>,
+    /// `skills_fetched`, `queue_changed` or `sde_updated`.
+    pub kind: String,
+    pub message: String,
+    /// Unix seconds.
+    pub created_at: i64_ts,
+}
+
+pub async fn record_activity(
+    pool: &Pool,
+    character_id: Option<i64>,
+    kind: &str,
+    message: &str,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "INSERT INTO activity_feed (character_id, kind, message, created_at)
+         VALUES (?, ?, ?, ?)",
+    )
+    .bind(character_id)
+    .bind(kind)
+    .bind(message)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "DELETE FROM activity_feed
+         WHERE id <= (SELECT MAX(id) FROM activity_feed) - ?",
+    )
+    .bind(MAX_ACTIVITY_ROWS)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Newest entries first.
+pub async fn get_activity_feed(pool: &Pool, limit: i64) -> Result<Vec<ActivityEntry>> {
+    let rows = sqlx::query_as::<_, ActivityEntry>(
+        "SELECT a.id, a.character_id, c.character_name, a.kind, a.message, a.created_at
+         FROM activity_feed a
+         LEFT JOIN characters c ON c.character_id = a.character_id
+         ORDER BY a.id DESC
+         LIMIT ?",
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    #[tokio::test]
+    async fn feed_lists_newest_first_and_stays_bounded() {
+        let db = TestDb::new().await.unwrap();
+        crate::db::add_character(&db.pool, 1, "Pilot")
+            .await
+            .unwrap();
+
+        record_activity(
+            &db.pool,
+            None,
+            ACTIVITY_SDE_UPDATED,
+            "SDE updated to build 1",
+        )
+        .await
+        .unwrap();
+        record_activity(
+            &db.pool,
+            Some(1),
+            ACTIVITY_QUEUE_CHANGED,
+            "Queue changed for Pilot: 2 added",
+        )
+        .await
+        .unwrap();
+
+        let feed = get_activity_feed(&db.pool, 10).await.unwrap();
+        assert_eq!(feed.len(), 2);
+        assert_eq!(feed[0].kind, ACTIVITY_QUEUE_CHANGED);
+        assert_eq!(feed[0].character_name.as_deref(), Some("Pilot"));
+        assert_eq!(feed[1].character_id, None);
+
+        for i in 0..MAX_ACTIVITY_ROWS {
+            record_activity(&db.pool, None, ACTIVITY_SKILLS_FETCHED, &i.to_string())
+                .await
+                .unwrap();
+        }
+        let feed = get_activity_feed(&db.pool, 2 * MAX_ACTIVITY_ROWS)
+            .await
+            .unwrap();
+        assert_eq!(feed.len() as i64, MAX_ACTIVITY_ROWS);
+        assert_eq!(feed[0].message, (MAX_ACTIVITY_ROWS - 1).to_string());
+    }
+}
@@ -1,5 +1,8 @@
 use super::Pool;
+use crate::ts_types::i64_ts;
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
 
 pub async fn get_app_setting(pool: &Pool, key: &str) -> Result<Option<String>> {
     let value = sqlx::query_scalar::<_, String>("SELECT value FROM app_settings WHERE key = ?")
@@ -18,6 +21,14 @@ pub async fn set_app_setting(pool: &Pool, key: &str, value: &str) -> Result<()>
     Ok(())
 }
 
+pub async fn delete_app_setting(pool: &Pool, key: &str) -> Result<()> {
+    sqlx::query("DELETE FROM app_settings WHERE key = ?")
+        .bind(key)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
 pub async fn get_boolean_app_setting(pool: &Pool, key: &str) -> Result<bool> {
     Ok(get_app_setting(pool, key)
         .await?
@@ -76,3 +87,186 @@ pub async fn set_excluded_comparison_characters(pool: &Pool, character_ids: &[i6
     let json = serde_json::to_string(character_ids)?;
     set_app_setting(pool, EXCLUDED_COMPARISON_CHARACTERS_KEY, &json).await
 }
+
+const REFRESH_DAMPENING_KEY: &str = "refresh_dampening";
+
+/// How refreshers slow down while the main window is hidden in the tray.
+/// `hidden_after_minutes = 0` turns dampening off.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RefreshDampening {
+    pub hidden_after_minutes: i64_ts,
+    /// Interval for non-critical data (skills, attributes, clones, location)
+    /// once dampened. The skill queue keeps its normal cadence.
+    pub dampened_interval_minutes: i64_ts,
+}
+
+impl Default for RefreshDampening {
+    fn default() -> Self {
+        Self {
+            hidden_after_minutes: 30,
+            dampened_interval_minutes: 60,
+        }
+    }
+}
+
+pub async fn get_refresh_dampening(pool: &Pool) -> Result<RefreshDampening> {
+    let Some(raw) = get_app_setting(pool, REFRESH_DAMPENING_KEY).await? else {
+        return Ok(RefreshDampening::default());
+    };
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+pub async fn set_refresh_dampening(pool: &Pool, settings: &RefreshDampening) -> Result<()> {
+    let json = serde_json::to_string(settings)?;
+    set_app_setting(pool, REFRESH_DAMPENING_KEY, &json).await
+}
+
+const SDE_DOWNLOAD_KEY: &str = "sde_download";
+
+/// How the SDE archive is fetched: `connections` byte ranges in parallel when
+/// the server supports ranges, each retried up to `max_retries` times.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SdeDownloadSettings {
+    pub connections: i64_ts,
+    pub max_retries: i64_ts,
+}
+
+impl Default for SdeDownloadSettings {
+    fn default() -> Self {
+        Self {
+            connections: 4,
+            max_retries: 5,
+        }
+    }
+}
+
+pub async fn get_sde_download_settings(pool: &Pool) -> Result<SdeDownloadSettings> {
+    let Some(raw) = get_app_setting(pool, SDE_DOWNLOAD_KEY).await? else {
+        return Ok(SdeDownloadSettings::default());
+    };
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+pub async fn set_sde_download_settings(pool: &Pool, settings: &SdeDownloadSettings) -> Result<()> {
+    let json = serde_json::to_string(settings)?;
+    set_app_setting(pool, SDE_DOWNLOAD_KEY, &json).await
+}
+
+const SDE_IMPORT_MODE_KEY: &str = "sde_import_mode";
+
+/// How much of the SDE is imported. `Minimal` keeps only skills and
+/// implants (accelerators included), which is all training plans need;
+/// `Full` also keeps ships and items for the fitting and item browsers.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SdeImportMode {
+    #[default]
+    Full,
+    Minimal,
+}
+
+impl SdeImportMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SdeImportMode::Full => "full",
+            SdeImportMode::Minimal => "minimal",
+        }
+    }
+}
+
+pub async fn get_sde_import_mode(pool: &Pool) -> Result<SdeImportMode> {
+    let Some(raw) = get_app_setting(pool, SDE_IMPORT_MODE_KEY).await? else {
+        return Ok(SdeImportMode::default());
+    };
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+pub async fn set_sde_import_mode(pool: &Pool, mode: SdeImportMode) -> Result<()> {
+    let json = serde_json::to_string(&mode)?;
+    set_app_setting(pool, SDE_IMPORT_MODE_KEY, &json).await
+}
+
+const LEVEL_FORMAT_KEY: &str = "level_format";
+
+/// How skill levels are written in text the app produces for the user
+/// (plan exports, import summaries): "Gunnery 4" or "Gunnery IV".
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LevelFormat {
+    #[default]
+    Arabic,
+    Roman,
+}
+
+pub async fn get_level_format(pool: &Pool) -> Result<LevelFormat> {
+    let Some(raw) = get_app_setting(pool, LEVEL_FORMAT_KEY).await? else {
+        return Ok(LevelFormat::default());
+    };
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+pub async fn set_level_format(pool: &Pool, format: LevelFormat) -> Result<()> {
+    let json = serde_json::to_string(&format)?;
+    set_app_setting(pool, LEVEL_FORMAT_KEY, &json).await
+}
+
+const ESI_EXPLORER_KEY: &str = "esi_explorer";
+
+/// Gate for the raw ESI explorer. Off by default; even when enabled only GET
+/// requests go out unless `allow_writes` is also set.
+#[typeshare]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EsiExplorerSettings {
+    pub enabled: bool,
+    pub allow_writes: bool,
+}
+
+pub async fn get_esi_explorer_settings(pool: &Pool) -> Result<EsiExplorerSettings> {
+    let Some(raw) = get_app_setting(pool, ESI_EXPLORER_KEY).await? else {
+        return Ok(EsiExplorerSettings::default());
+    };
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+pub async fn set_esi_explorer_settings(pool: &Pool, settings: &EsiExplorerSettings) -> Result<()> {
+    let json = serde_json::to_string(settings)?;
+    set_app_setting(pool, ESI_EXPLORER_KEY, &json).await
+}
+
+const METRICS_SERVER_KEY: &str = "metrics_server";
+
+/// Prometheus `/metrics` listener on localhost. Off by default.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricsServerSettings {
+    pub enabled: bool,
+    pub port: i64_ts,
+}
+
+impl Default for MetricsServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 9464,
+        }
+    }
+}
+
+pub async fn get_metrics_server_settings(pool: &Pool) -> Result<MetricsServerSettings> {
+    let Some(raw) = get_app_setting(pool, METRICS_SERVER_KEY).await? else {
+        return Ok(MetricsServerSettings::default());
+    };
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+pub async fn set_metrics_server_settings(
+    pool: &Pool,
+    settings: &MetricsServerSettings,
+) -> Result<()> {
+    let json = serde_json::to_string(settings)?;
+    set_app_setting(pool, METRICS_SERVER_KEY, &json).await
+}
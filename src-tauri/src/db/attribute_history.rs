@@ -0,0 +1,324 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use typeshare::typeshare;
+
+use super::Pool;
+use crate::ts_types::i64_ts;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeChangeKind {
+    Initial,
+    Remap,
+    BonusRemap,
+    Implants,
+    Accelerator,
+    Other,
+}
+
+impl AttributeChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttributeChangeKind::Initial => "initial",
+            AttributeChangeKind::Remap => "remap",
+            AttributeChangeKind::BonusRemap => "bonus_remap",
+            AttributeChangeKind::Implants => "implants",
+            AttributeChangeKind::Accelerator => "accelerator",
+            AttributeChangeKind::Other => "other",
+        }
+    }
+}
+
+/// Attributes as seen on one refresh, in charisma, intelligence, memory,
+/// perception, willpower order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObservedAttributes {
+    pub totals: [i64; 5],
+    pub implants: [i64; 5],
+    pub accelerator: i64,
+    pub bonus_remaps: Option<i64>,
+    pub last_remap_date: Option<String>,
+}
+
+impl ObservedAttributes {
+    /// Points neither implants nor the accelerator explain: base plus remap.
+    fn allocation(&self) -> [i64; 5] {
+        std::array::from_fn(|i| self.totals[i] - self.implants[i] - self.accelerator)
+    }
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct AttributeSnapshot {
+    pub id: i64_ts,
+    pub character_id: i64_ts,
+    pub charisma: i64_ts,
+    pub intelligence: i64_ts,
+    pub memory: i64_ts,
+    pub perception: i64_ts,
+    pub willpower: i64_ts,
+    pub implant_charisma: i64_ts,
+    pub implant_intelligence: i64_ts,
+    pub implant_memory: i64_ts,
+    pub implant_perception: i64_ts,
+    pub implant_willpower: i64_ts,
+    pub accelerator: i64_ts,
+    pub bonus_remaps: Option<i64_ts>,
+    pub last_remap_date: Option<String>,
+    /// `initial`, `remap`, `bonus_remap`, `implants`, `accelerator` or `other`.
+    pub change_kind: String,
+    /// Unix seconds.
+    pub observed_at: i64_ts,
+}
+
+impl AttributeSnapshot {
+    pub fn observed(&self) -> ObservedAttributes {
+        ObservedAttributes {
+            totals: [
+                self.charisma,
+                self.intelligence,
+                self.memory,
+                self.perception,
+                self.willpower,
+            ],
+            implants: [
+                self.implant_charisma,
+                self.implant_intelligence,
+                self.implant_memory,
+                self.implant_perception,
+                self.implant_willpower,
+            ],
+            accelerator: self.accelerator,
+            bonus_remaps: self.bonus_remaps,
+            last_remap_date: self.last_remap_date.clone(),
+        }
+    }
+
+    /// When the remap happened: ESI's date when it reported one, otherwise
+    /// when the change was first seen.
+    pub fn remapped_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.last_remap_date
+            .as_deref()
+            .and_then(|d| chrono::DateTime::parse_from_rfc3339(d).ok())
+            .map(|d| d.with_timezone(&chrono::Utc))
+            .or_else(|| chrono::DateTime::from_timestamp(self.observed_at, 0))
+    }
+}
+
+/// What changed between the last snapshot and `current`, or `None` when
+/// nothing did. A new remap date, or a shifted allocation with the same
+/// implants and accelerator, is a remap; it used a bonus remap when the
+/// bonus count dropped.
+pub fn classify_change(
+    previous: Option<&ObservedAttributes>,
+    current: &ObservedAttributes,
+) -> Option<AttributeChangeKind> {
+    let Some(previous) = previous else {
+        return Some(AttributeChangeKind::Initial);
+    };
+    if previous == current {
+        return None;
+    }
+
+    let same_boosts =
+        previous.implants == current.implants && previous.accelerator == current.accelerator;
+    let remapped = previous.last_remap_date != current.last_remap_date
+        || (same_boosts && previous.allocation() != current.allocation());
+    if remapped {
+        let used_bonus = matches!(
+            (previous.bonus_remaps, current.bonus_remaps),
+            (Some(before), Some(after)) if after < before
+        );
+        return Some(if used_bonus {
+            AttributeChangeKind::BonusRemap
+        } else {
+            AttributeChangeKind::Remap
+        });
+    }
+
+    if previous.implants != current.implants {
+        Some(AttributeChangeKind::Implants)
+    } else if previous.accelerator != current.accelerator {
+        Some(AttributeChangeKind::Accelerator)
+    } else {
+        Some(AttributeChangeKind::Other)
+    }
+}
+
+const SNAPSHOT_COLUMNS: &str = "id, character_id, charisma, intelligence, memory, perception, \
+     willpower, implant_charisma, implant_intelligence, implant_memory, implant_perception, \
+     implant_willpower, accelerator, bonus_remaps, last_remap_date, change_kind, observed_at";
+
+/// Newest first.
+pub async fn get_attribute_history(
+    pool: &Pool,
+    character_id: i64,
+) -> Result<Vec<AttributeSnapshot>> {
+    let sql = format!(
+        "SELECT {} FROM character_attribute_history WHERE character_id = ? ORDER BY id DESC",
+        SNAPSHOT_COLUMNS
+    );
+    let rows = sqlx::query_as::<_, AttributeSnapshot>(sqlx::AssertSqlSafe(sql.as_str()))
+        .bind(character_id)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows)
+}
+
+/// The most recent primary (non-bonus) remap seen for the character.
+pub async fn get_last_observed_remap(
+    pool: &Pool,
+    character_id: i64,
+) -> Result<Option<AttributeSnapshot>> {
+    let sql = format!(
+        "SELECT {} FROM character_attribute_history
+         WHERE character_id = ? AND change_kind = 'remap'
+         ORDER BY id DESC
+         LIMIT 1",
+        SNAPSHOT_COLUMNS
+    );
+    let row = sqlx::query_as::<_, AttributeSnapshot>(sqlx::AssertSqlSafe(sql.as_str()))
+        .bind(character_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(row)
+}
+
+/// Appends a snapshot when `current` differs from the latest one. Returns
+/// the kind of change recorded, if any.
+pub async fn record_attribute_snapshot(
+    pool: &Pool,
+    character_id: i64,
+    current: &ObservedAttributes,
+    now: i64,
+) -> Result<Option<AttributeChangeKind>> {
+    let mut tx = pool.begin().await?;
+
+    let sql = format!(
+        "SELECT {} FROM character_attribute_history
+         WHERE character_id = ?
+         ORDER BY id DESC
+         LIMIT 1",
+        SNAPSHOT_COLUMNS
+    );
+    let latest = sqlx::query_as::<_, AttributeSnapshot>(sqlx::AssertSqlSafe(sql.as_str()))
+        .bind(character_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+    let previous = latest.as_ref().map(|s| s.observed());
+    let Some(kind) = classify_change(previous.as_ref(), current) else {
+        return Ok(None);
+    };
+
+    sqlx::query(
+        "INSERT INTO character_attribute_history (
+             character_id, charisma, intelligence, memory, perception, willpower,
+             implant_charisma, implant_intelligence, implant_memory, implant_perception,
+             implant_willpower, accelerator, bonus_remaps, last_remap_date, change_kind,
+             observed_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(character_id)
+    .bind(current.totals[0])
+    .bind(current.totals[1])
+    .bind(current.totals[2])
+    .bind(current.totals[3])
+    .bind(current.totals[4])
+    .bind(current.implants[0])
+    .bind(current.implants[1])
+    .bind(current.implants[2])
+    .bind(current.implants[3])
+    .bind(current.implants[4])
+    .bind(current.accelerator)
+    .bind(current.bonus_remaps)
+    .bind(&current.last_remap_date)
+    .bind(kind.as_str())
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(Some(kind))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    fn observed(totals: [i64; 5], implants: [i64; 5]) -> ObservedAttributes {
+        ObservedAttributes {
+            totals,
+            implants,
+            accelerator: 0,
+            bonus_remaps: Some(2),
+            last_remap_date: None,
+        }
+    }
+
+    #[test]
+    fn classifies_remaps_and_implant_swaps() {
+        let before = observed([19, 27, 21, 20, 20], [0; 5]);
+        assert_eq!(
+            classify_change(None, &before),
+            Some(AttributeChangeKind::Initial)
+        );
+        assert_eq!(classify_change(Some(&before), &before), None);
+
+        let plugged = observed([19, 30, 24, 20, 20], [0, 3, 3, 0, 0]);
+        assert_eq!(
+            classify_change(Some(&before), &plugged),
+            Some(AttributeChangeKind::Implants)
+        );
+
+        let remapped = observed([17, 27, 17, 27, 19], [0; 5]);
+        assert_eq!(
+            classify_change(Some(&before), &remapped),
+            Some(AttributeChangeKind::Remap)
+        );
+
+        let bonus = ObservedAttributes {
+            bonus_remaps: Some(1),
+            last_remap_date: Some("2026-03-01T00:00:00Z".into()),
+            ..remapped.clone()
+        };
+        assert_eq!(
+            classify_change(Some(&before), &bonus),
+            Some(AttributeChangeKind::BonusRemap)
+        );
+
+        let boosted = ObservedAttributes {
+            totals: [22, 30, 24, 23, 23],
+            accelerator: 3,
+            ..before.clone()
+        };
+        assert_eq!(
+            classify_change(Some(&before), &boosted),
+            Some(AttributeChangeKind::Accelerator)
+        );
+    }
+
+    #[tokio::test]
+    async fn records_only_changes_and_finds_last_remap() {
+        let db = TestDb::new().await.unwrap();
+        crate::db::add_character(&db.pool, 1, "Alt").await.unwrap();
+
+        let before = observed([19, 27, 21, 20, 20], [0; 5]);
+        let remapped = observed([17, 27, 17, 27, 19], [0; 5]);
+        for (attrs, now) in [(&before, 100), (&before, 200), (&remapped, 300)] {
+            record_attribute_snapshot(&db.pool, 1, attrs, now)
+                .await
+                .unwrap();
+        }
+
+        let history = get_attribute_history(&db.pool, 1).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].change_kind, "remap");
+        assert_eq!(history[1].change_kind, "initial");
+
+        let remap = get_last_observed_remap(&db.pool, 1).await.unwrap().unwrap();
+        assert_eq!(remap.remapped_at().map(|d| d.timestamp()), Some(300));
+    }
+}
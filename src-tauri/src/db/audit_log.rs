@@ -0,0 +1,77 @@
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::FromRow;
+use typeshare::typeshare;
+
+use super::Pool;
+use crate::ts_types::i64_ts;
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AuditEntry {
+    pub id: i64_ts,
+    /// Name of the Tauri command, e.g. `delete_skill_plan`.
+    pub command: String,
+    /// JSON object of the arguments, plus names looked up before deleting.
+    pub params: String,
+    /// Unix seconds.
+    pub created_at: i64_ts,
+}
+
+pub async fn record_audit(pool: &Pool, command: &str, params: &serde_json::Value) -> Result<()> {
+    sqlx::query("INSERT INTO audit_log (command, params, created_at) VALUES (?, ?, ?)")
+        .bind(command)
+        .bind(params.to_string())
+        .bind(chrono::Utc::now().timestamp())
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Newest entries first.
+pub async fn get_audit_log(pool: &Pool, limit: i64) -> Result<Vec<AuditEntry>> {
+    let rows = sqlx::query_as::<_, AuditEntry>(
+        "SELECT id, command, params, created_at FROM audit_log ORDER BY id DESC LIMIT ?",
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    #[tokio::test]
+    async fn entries_outlive_the_rows_they_describe() {
+        let db = TestDb::new().await.unwrap();
+        crate::db::add_character(&db.pool, 1, "Pilot")
+            .await
+            .unwrap();
+        crate::db::delete_character(&db.pool, 1).await.unwrap();
+        record_audit(
+            &db.pool,
+            "logout_character",
+            &serde_json::json!({ "character_id": 1, "character_name": "Pilot" }),
+        )
+        .await
+        .unwrap();
+        record_audit(
+            &db.pool,
+            "delete_skill_plan",
+            &serde_json::json!({ "plan_id": 7 }),
+        )
+        .await
+        .unwrap();
+
+        let log = get_audit_log(&db.pool, 10).await.unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].command, "delete_skill_plan");
+        let params: serde_json::Value = serde_json::from_str(&log[1].params).unwrap();
+        assert_eq!(params["character_name"], "Pilot");
+    }
+}
@@ -0,0 +1,85 @@
+use super::Pool;
+use crate::features::CharacterModule;
+use anyhow::Result;
+use std::str::FromStr;
+
+pub async fn get_disabled_modules(pool: &Pool, character_id: i64) -> Result<Vec<CharacterModule>> {
+    let modules = sqlx::query_scalar::<_, String>(
+        "SELECT module FROM character_disabled_modules WHERE character_id = ?",
+    )
+    .bind(character_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(modules
+        .into_iter()
+        .filter_map(|s| CharacterModule::from_str(&s).ok())
+        .collect())
+}
+
+pub async fn is_module_enabled(
+    pool: &Pool,
+    character_id: i64,
+    module: CharacterModule,
+) -> Result<bool> {
+    Ok(!get_disabled_modules(pool, character_id)
+        .await?
+        .contains(&module))
+}
+
+/// Turn on exactly `enabled` for the character and switch off the rest.
+pub async fn set_character_modules(
+    pool: &Pool,
+    character_id: i64,
+    enabled: &[CharacterModule],
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM character_disabled_modules WHERE character_id = ?")
+        .bind(character_id)
+        .execute(&mut *tx)
+        .await?;
+    for module in CharacterModule::ALL {
+        if enabled.contains(&module) {
+            continue;
+        }
+        sqlx::query("INSERT INTO character_disabled_modules (character_id, module) VALUES (?, ?)")
+            .bind(character_id)
+            .bind(module.as_str())
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    #[tokio::test]
+    async fn modules_default_on_and_can_be_switched_off() {
+        let db = TestDb::new().await.unwrap();
+        crate::db::add_character(&db.pool, 1, "Alt").await.unwrap();
+
+        assert!(is_module_enabled(&db.pool, 1, CharacterModule::Wallet)
+            .await
+            .unwrap());
+
+        set_character_modules(&db.pool, 1, &[CharacterModule::Location])
+            .await
+            .unwrap();
+        let mut disabled = get_disabled_modules(&db.pool, 1).await.unwrap();
+        disabled.sort_by_key(|m| m.as_str());
+        assert_eq!(
+            disabled,
+            vec![CharacterModule::Clones, CharacterModule::Wallet]
+        );
+
+        set_character_modules(&db.pool, 1, &CharacterModule::ALL)
+            .await
+            .unwrap();
+        assert!(get_disabled_modules(&db.pool, 1).await.unwrap().is_empty());
+    }
+}
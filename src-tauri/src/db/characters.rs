@@ -51,15 +51,102 @@ pub async fn update_character_omega_status(
     character_id: i64,
     is_omega: bool,
 ) -> Result<()> {
-    sqlx::query("UPDATE characters SET is_omega = ? WHERE character_id = ?")
+    // Stamp the Omega -> Alpha transition so the lapse notification fires once
+    // per lapse; returning to Omega clears it. Only a confirmed Omega status
+    // counts as the "before", never the default a new character starts with.
+    // A user override wins over the inferred status.
+    sqlx::query(
+        "UPDATE characters SET
+            omega_lapsed_at = CASE
+                WHEN COALESCE(omega_override, ?) THEN NULL
+                WHEN omega_status THEN CURRENT_TIMESTAMP
+                ELSE omega_lapsed_at
+            END,
+            is_omega = COALESCE(omega_override, ?),
+            omega_status = COALESCE(omega_override, ?)
+         WHERE character_id = ?",
+    )
+    .bind(is_omega)
+    .bind(is_omega)
+    .bind(is_omega)
+    .bind(character_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Pins the character to Omega or Alpha regardless of what the refresher
+/// infers; `None` goes back to inferring, from the next refresh on.
+pub async fn set_character_omega_override(
+    pool: &Pool,
+    character_id: i64,
+    is_omega: Option<bool>,
+) -> Result<()> {
+    sqlx::query("UPDATE characters SET omega_override = ? WHERE character_id = ?")
         .bind(is_omega)
         .bind(character_id)
         .execute(pool)
         .await?;
+    if let Some(is_omega) = is_omega {
+        update_character_omega_status(pool, character_id, is_omega).await?;
+    }
+
+    Ok(())
+}
+
+/// The status the user pinned, if any; `None` when it is inferred.
+pub async fn get_character_omega_override(pool: &Pool, character_id: i64) -> Result<Option<bool>> {
+    let pinned = sqlx::query_scalar::<_, Option<bool>>(
+        "SELECT omega_override FROM characters WHERE character_id = ?",
+    )
+    .bind(character_id)
+    .fetch_optional(pool)
+    .await?
+    .flatten();
+
+    Ok(pinned)
+}
+
+pub async fn get_character_omega_lapsed_at(
+    pool: &Pool,
+    character_id: i64,
+) -> Result<Option<String>> {
+    let lapsed_at = sqlx::query_scalar::<_, Option<String>>(
+        "SELECT omega_lapsed_at FROM characters WHERE character_id = ?",
+    )
+    .bind(character_id)
+    .fetch_optional(pool)
+    .await?
+    .flatten();
+
+    Ok(lapsed_at)
+}
+
+/// Stamps a refresh pass where any ESI fetch succeeded.
+pub async fn record_refresh_success(pool: &Pool, character_id: i64, at: i64) -> Result<()> {
+    sqlx::query("UPDATE characters SET last_refresh_success_at = ? WHERE character_id = ?")
+        .bind(at)
+        .bind(character_id)
+        .execute(pool)
+        .await?;
 
     Ok(())
 }
 
+/// Unix seconds of the last successful refresh; `None` before the first one.
+pub async fn get_last_refresh_success_at(pool: &Pool, character_id: i64) -> Result<Option<i64>> {
+    let at = sqlx::query_scalar::<_, Option<i64>>(
+        "SELECT last_refresh_success_at FROM characters WHERE character_id = ?",
+    )
+    .bind(character_id)
+    .fetch_optional(pool)
+    .await?
+    .flatten();
+
+    Ok(at)
+}
+
 pub async fn update_character(pool: &Pool, character_id: i64, character_name: &str) -> Result<()> {
     sqlx::query("UPDATE characters SET character_name = ? WHERE character_id = ?")
         .bind(character_name)
@@ -92,3 +179,84 @@ pub async fn delete_character(pool: &Pool, character_id: i64) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::db::repository::CharacterRepository;
+    use crate::testdata::{MemoryRepository, TestDb};
+
+    async fn omega_lapse_round_trip(repo: &impl CharacterRepository) {
+        repo.add_character(1, "Pilot").await.unwrap();
+        assert_eq!(repo.get_character_omega_lapsed_at(1).await.unwrap(), None);
+
+        repo.update_character_omega_status(1, true).await.unwrap();
+        assert_eq!(repo.get_character_omega_lapsed_at(1).await.unwrap(), None);
+        repo.update_character_omega_status(1, false).await.unwrap();
+        let stamped = repo.get_character_omega_lapsed_at(1).await.unwrap();
+        assert!(stamped.is_some());
+
+        // Staying Alpha keeps the original lapse time.
+        repo.update_character_omega_status(1, false).await.unwrap();
+        assert_eq!(
+            repo.get_character_omega_lapsed_at(1).await.unwrap(),
+            stamped
+        );
+
+        repo.update_character_omega_status(1, true).await.unwrap();
+        assert_eq!(repo.get_character_omega_lapsed_at(1).await.unwrap(), None);
+        assert!(repo.get_character(1).await.unwrap().unwrap().is_omega);
+    }
+
+    #[tokio::test]
+    async fn omega_lapse_is_stamped_on_transition_and_cleared_on_return() {
+        let db = TestDb::new().await.unwrap();
+        omega_lapse_round_trip(&db.pool).await;
+        omega_lapse_round_trip(&MemoryRepository::default()).await;
+    }
+
+    async fn new_alpha_is_not_a_lapse(repo: &impl CharacterRepository) {
+        repo.add_character(1, "Pilot").await.unwrap();
+
+        // The first reading has nothing confirmed to compare against.
+        repo.update_character_omega_status(1, false).await.unwrap();
+        assert_eq!(repo.get_character_omega_lapsed_at(1).await.unwrap(), None);
+        assert!(!repo.get_character(1).await.unwrap().unwrap().is_omega);
+
+        repo.update_character_omega_status(1, false).await.unwrap();
+        assert_eq!(repo.get_character_omega_lapsed_at(1).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn newly_added_alpha_character_is_not_stamped_as_lapsed() {
+        let db = TestDb::new().await.unwrap();
+        new_alpha_is_not_a_lapse(&db.pool).await;
+        new_alpha_is_not_a_lapse(&MemoryRepository::default()).await;
+    }
+
+    async fn override_wins_over_inferred_status(repo: &impl CharacterRepository) {
+        repo.add_character(1, "Pilot").await.unwrap();
+        repo.update_character_omega_status(1, true).await.unwrap();
+
+        repo.set_character_omega_override(1, Some(false))
+            .await
+            .unwrap();
+        repo.update_character_omega_status(1, true).await.unwrap();
+        assert!(!repo.get_character(1).await.unwrap().unwrap().is_omega);
+        assert!(repo
+            .get_character_omega_lapsed_at(1)
+            .await
+            .unwrap()
+            .is_some());
+
+        repo.set_character_omega_override(1, None).await.unwrap();
+        repo.update_character_omega_status(1, true).await.unwrap();
+        assert!(repo.get_character(1).await.unwrap().unwrap().is_omega);
+    }
+
+    #[tokio::test]
+    async fn omega_override_wins_over_inferred_status() {
+        let db = TestDb::new().await.unwrap();
+        override_wins_over_inferred_status(&db.pool).await;
+        override_wins_over_inferred_status(&MemoryRepository::default()).await;
+    }
+}
@@ -0,0 +1,137 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteRow, FromRow, Row};
+use typeshare::typeshare;
+
+use super::app_settings::{get_app_setting, set_app_setting};
+use super::Pool;
+use crate::ts_types::i64_ts;
+
+pub const DEFAULT_CYCLE_DAYS: i64 = 30;
+
+const FARM_PRICES_KEY: &str = "farm_prices";
+
+/// Per-character skill farm settings. `next_extraction_at` is unix seconds.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FarmSettings {
+    pub character_id: i64_ts,
+    pub next_extraction_at: Option<i64_ts>,
+    pub cycle_days: i64_ts,
+    /// The character lives in wormhole space.
+    pub wormhole_risk: bool,
+    /// The character lives in null-sec.
+    pub nullsec_risk: bool,
+}
+
+impl FarmSettings {
+    pub fn default_for(character_id: i64) -> Self {
+        Self {
+            character_id,
+            next_extraction_at: None,
+            cycle_days: DEFAULT_CYCLE_DAYS,
+            wormhole_risk: false,
+            nullsec_risk: false,
+        }
+    }
+}
+
+impl<'r> FromRow<'r, SqliteRow> for FarmSettings {
+    fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+        Ok(FarmSettings {
+            character_id: row.get("character_id"),
+            next_extraction_at: row.get("next_extraction_at"),
+            cycle_days: row.get("cycle_days"),
+            wormhole_risk: row.get::<i64, _>("wormhole_risk") != 0,
+            nullsec_risk: row.get::<i64, _>("nullsec_risk") != 0,
+        })
+    }
+}
+
+/// Market prices (ISK) used for the farm math. App-wide; zero means unknown.
+#[typeshare]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FarmPrices {
+    pub large_skill_injector: f64,
+    pub skill_extractor: f64,
+    pub plus_four_set: f64,
+    pub plus_five_set: f64,
+    /// One PLEX; absent from prices saved before it was added.
+    #[serde(default)]
+    pub plex: f64,
+}
+
+/// Returns the character's farm settings, or defaults when none are stored.
+pub async fn get_farm_settings(pool: &Pool, character_id: i64) -> Result<FarmSettings> {
+    let settings = sqlx::query_as::<_, FarmSettings>(
+        "SELECT character_id, next_extraction_at, cycle_days, wormhole_risk, nullsec_risk
+         FROM farm_settings WHERE character_id = ?",
+    )
+    .bind(character_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(settings.unwrap_or_else(|| FarmSettings::default_for(character_id)))
+}
+
+pub async fn upsert_farm_settings(pool: &Pool, settings: &FarmSettings) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO farm_settings (character_id, next_extraction_at, cycle_days, wormhole_risk, nullsec_risk, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?)
+         ON CONFLICT(character_id) DO UPDATE SET
+             next_extraction_at = excluded.next_extraction_at,
+             cycle_days = excluded.cycle_days,
+             wormhole_risk = excluded.wormhole_risk,
+             nullsec_risk = excluded.nullsec_risk,
+             updated_at = excluded.updated_at",
+    )
+    .bind(settings.character_id)
+    .bind(settings.next_extraction_at)
+    .bind(settings.cycle_days)
+    .bind(settings.wormhole_risk as i64)
+    .bind(settings.nullsec_risk as i64)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_farm_prices(pool: &Pool) -> Result<FarmPrices> {
+    let Some(raw) = get_app_setting(pool, FARM_PRICES_KEY).await? else {
+        return Ok(FarmPrices::default());
+    };
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+pub async fn set_farm_prices(pool: &Pool, prices: &FarmPrices) -> Result<()> {
+    let json = serde_json::to_string(prices)?;
+    set_app_setting(pool, FARM_PRICES_KEY, &json).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    #[tokio::test]
+    async fn settings_default_then_round_trip() {
+        let db = TestDb::new().await.unwrap();
+        crate::db::add_character(&db.pool, 1, "Farm").await.unwrap();
+
+        assert_eq!(
+            get_farm_settings(&db.pool, 1).await.unwrap(),
+            FarmSettings::default_for(1)
+        );
+
+        let settings = FarmSettings {
+            character_id: 1,
+            next_extraction_at: Some(1_700_000_000),
+            cycle_days: 14,
+            wormhole_risk: true,
+            nullsec_risk: false,
+        };
+        upsert_farm_settings(&db.pool, &settings).await.unwrap();
+        assert_eq!(get_farm_settings(&db.pool, 1).await.unwrap(), settings);
+    }
+}
@@ -0,0 +1,165 @@
+use anyhow::Result;
+use serde::Serialize;
+use typeshare::typeshare;
+
+use super::Pool;
+use crate::ts_types::i64_ts;
+
+/// `PRAGMA auto_vacuum` value for incremental mode.
+const AUTO_VACUUM_INCREMENTAL: i64 = 2;
+/// How long the full VACUUM waits on writers before SQLite reports busy.
+const VACUUM_BUSY_TIMEOUT_MS: i64 = 30_000;
+/// Attempts at the full VACUUM before giving up until the next run.
+const VACUUM_ATTEMPTS: u32 = 4;
+const VACUUM_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(15);
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct MaintenanceReport {
+    pub size_before_bytes: i64_ts,
+    pub size_after_bytes: i64_ts,
+    pub reclaimed_bytes: i64_ts,
+    /// True when this run converted the database to incremental auto-vacuum,
+    /// which needs one full VACUUM.
+    pub full_vacuum: bool,
+    /// Rows deleted by retention settings before vacuuming.
+    pub pruned_rows: i64_ts,
+}
+
+async fn database_size(pool: &Pool) -> Result<i64> {
+    let page_count: i64 = sqlx::query_scalar("PRAGMA page_count")
+        .fetch_one(pool)
+        .await?;
+    let page_size: i64 = sqlx::query_scalar("PRAGMA page_size")
+        .fetch_one(pool)
+        .await?;
+    Ok(page_count * page_size)
+}
+
+/// Refresh planner statistics and return free pages to the filesystem.
+///
+/// Databases created before incremental auto-vacuum was enabled are switched
+/// over with a one-time full VACUUM; after that only `incremental_vacuum` runs,
+/// which is cheap and never rewrites the whole file.
+pub async fn run_maintenance(pool: &Pool) -> Result<MaintenanceReport> {
+    let size_before = database_size(pool).await?;
+
+    sqlx::query("ANALYZE").execute(pool).await?;
+    sqlx::query("PRAGMA optimize").execute(pool).await?;
+
+    let auto_vacuum: i64 = sqlx::query_scalar("PRAGMA auto_vacuum")
+        .fetch_one(pool)
+        .await?;
+    let full_vacuum = auto_vacuum != AUTO_VACUUM_INCREMENTAL;
+    if full_vacuum {
+        full_vacuum_with_retry(pool).await?;
+    } else {
+        sqlx::query("PRAGMA incremental_vacuum")
+            .execute(pool)
+            .await?;
+    }
+
+    // Fold the WAL back into the main file so the size change is real on disk.
+    sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+        .execute(pool)
+        .await?;
+
+    let size_after = database_size(pool).await?;
+
+    Ok(MaintenanceReport {
+        size_before_bytes: size_before,
+        size_after_bytes: size_after,
+        reclaimed_bytes: (size_before - size_after).max(0),
+        full_vacuum,
+        pruned_rows: 0,
+    })
+}
+
+/// The one-time conversion. VACUUM needs the write lock for as long as it
+/// rewrites the file, so it waits longer than the pool's default for the
+/// refreshers' transactions to finish and retries when they still hold it.
+async fn full_vacuum_with_retry(pool: &Pool) -> Result<()> {
+    let mut attempt = 1;
+    loop {
+        match full_vacuum(pool).await {
+            Err(e) if attempt < VACUUM_ATTEMPTS && is_busy(&e) => {
+                eprintln!(
+                    "maintenance: database busy during VACUUM (attempt {}), retrying",
+                    attempt
+                );
+                attempt += 1;
+                tokio::time::sleep(VACUUM_RETRY_DELAY).await;
+            }
+            result => return result.map_err(Into::into),
+        }
+    }
+}
+
+async fn full_vacuum(pool: &Pool) -> std::result::Result<(), sqlx::Error> {
+    // The new mode is held per connection until VACUUM applies it, so every
+    // statement must run on the same connection.
+    let mut conn = pool.acquire().await?;
+    let pool_timeout: i64 = sqlx::query_scalar("PRAGMA busy_timeout")
+        .fetch_one(&mut *conn)
+        .await?;
+    sqlx::query(sqlx::AssertSqlSafe(
+        format!("PRAGMA busy_timeout = {}", VACUUM_BUSY_TIMEOUT_MS).as_str(),
+    ))
+    .execute(&mut *conn)
+    .await?;
+    sqlx::query("PRAGMA auto_vacuum = INCREMENTAL")
+        .execute(&mut *conn)
+        .await?;
+    let result = sqlx::query("VACUUM").execute(&mut *conn).await.map(|_| ());
+    // The connection goes back to the pool with the pool's timeout.
+    sqlx::query(sqlx::AssertSqlSafe(
+        format!("PRAGMA busy_timeout = {}", pool_timeout).as_str(),
+    ))
+    .execute(&mut *conn)
+    .await?;
+    result
+}
+
+/// SQLITE_BUSY or SQLITE_LOCKED, including their extended codes.
+fn is_busy(e: &sqlx::Error) -> bool {
+    let sqlx::Error::Database(db) = e else {
+        return false;
+    };
+    db.code()
+        .and_then(|code| code.parse::<i32>().ok())
+        .is_some_and(|code| matches!(code & 0xff, 5 | 6))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    #[tokio::test]
+    async fn converts_to_incremental_once_then_stays_incremental() {
+        let db = TestDb::new().await.unwrap();
+
+        let first = run_maintenance(&db.pool).await.unwrap();
+        assert!(first.full_vacuum);
+
+        // Grow the file, then free the pages for the incremental vacuum.
+        let padding = "x".repeat(4096);
+        for i in 0..256 {
+            sqlx::query("INSERT INTO app_settings (key, value) VALUES (?, ?)")
+                .bind(format!("padding_{}", i))
+                .bind(&padding)
+                .execute(&db.pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("DELETE FROM app_settings WHERE key LIKE 'padding_%'")
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        let second = run_maintenance(&db.pool).await.unwrap();
+        assert!(!second.full_vacuum);
+        assert!(second.size_after_bytes < second.size_before_bytes);
+        assert!(second.reclaimed_bytes >= 256 * 4096);
+    }
+}
@@ -0,0 +1,125 @@
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::FromRow;
+use typeshare::typeshare;
+
+use super::app_settings::{get_app_setting, set_app_setting};
+use super::Pool;
+use crate::ts_types::i64_ts;
+
+const MARKET_TYPE_IDS_KEY: &str = "market_type_ids";
+
+/// PLEX, Skill Extractor and Large Skill Injector.
+pub const DEFAULT_MARKET_TYPE_IDS: [i64; 3] = [44992, 40519, 40520];
+
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow)]
+pub struct MarketPrice {
+    pub type_id: i64_ts,
+    pub region_id: i64_ts,
+    pub sell_min: Option<f64>,
+    pub buy_max: Option<f64>,
+    pub sell_volume: i64_ts,
+    pub buy_volume: i64_ts,
+    /// Unix seconds.
+    pub updated_at: i64_ts,
+}
+
+/// Type ids whose prices are tracked; the defaults until the user sets a list.
+pub async fn get_market_type_ids(pool: &Pool) -> Result<Vec<i64>> {
+    let Some(raw) = get_app_setting(pool, MARKET_TYPE_IDS_KEY).await? else {
+        return Ok(DEFAULT_MARKET_TYPE_IDS.to_vec());
+    };
+    Ok(serde_json::from_str(&raw).unwrap_or_else(|_| DEFAULT_MARKET_TYPE_IDS.to_vec()))
+}
+
+pub async fn set_market_type_ids(pool: &Pool, type_ids: &[i64]) -> Result<()> {
+    let json = serde_json::to_string(type_ids)?;
+    set_app_setting(pool, MARKET_TYPE_IDS_KEY, &json).await
+}
+
+pub async fn get_market_prices(pool: &Pool) -> Result<Vec<MarketPrice>> {
+    let rows = sqlx::query_as::<_, MarketPrice>(
+        "SELECT type_id, region_id, sell_min, buy_max, sell_volume, buy_volume, updated_at
+         FROM market_prices ORDER BY type_id",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn get_market_price(pool: &Pool, type_id: i64) -> Result<Option<MarketPrice>> {
+    let row = sqlx::query_as::<_, MarketPrice>(
+        "SELECT type_id, region_id, sell_min, buy_max, sell_volume, buy_volume, updated_at
+         FROM market_prices WHERE type_id = ?",
+    )
+    .bind(type_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
+
+pub async fn upsert_market_price(pool: &Pool, price: &MarketPrice) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO market_prices (type_id, region_id, sell_min, buy_max, sell_volume, buy_volume, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(type_id) DO UPDATE SET
+             region_id = excluded.region_id,
+             sell_min = excluded.sell_min,
+             buy_max = excluded.buy_max,
+             sell_volume = excluded.sell_volume,
+             buy_volume = excluded.buy_volume,
+             updated_at = excluded.updated_at",
+    )
+    .bind(price.type_id)
+    .bind(price.region_id)
+    .bind(price.sell_min)
+    .bind(price.buy_max)
+    .bind(price.sell_volume)
+    .bind(price.buy_volume)
+    .bind(price.updated_at)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    #[tokio::test]
+    async fn type_ids_default_then_round_trip() {
+        let db = TestDb::new().await.unwrap();
+
+        assert_eq!(
+            get_market_type_ids(&db.pool).await.unwrap(),
+            DEFAULT_MARKET_TYPE_IDS.to_vec()
+        );
+        set_market_type_ids(&db.pool, &[44992, 9941]).await.unwrap();
+        assert_eq!(
+            get_market_type_ids(&db.pool).await.unwrap(),
+            vec![44992, 9941]
+        );
+    }
+
+    #[tokio::test]
+    async fn upsert_replaces_the_previous_price() {
+        let db = TestDb::new().await.unwrap();
+        let mut price = MarketPrice {
+            type_id: 44992,
+            region_id: 10000002,
+            sell_min: Some(5_000_000.0),
+            buy_max: Some(4_900_000.0),
+            sell_volume: 10,
+            buy_volume: 20,
+            updated_at: 1_700_000_000,
+        };
+        upsert_market_price(&db.pool, &price).await.unwrap();
+        price.sell_min = None;
+        price.updated_at += 60;
+        upsert_market_price(&db.pool, &price).await.unwrap();
+
+        assert_eq!(get_market_prices(&db.pool).await.unwrap(), vec![price]);
+    }
+}
@@ -0,0 +1,150 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use typeshare::typeshare;
+
+use super::Pool;
+use crate::affiliation::Affiliation;
+use crate::ts_types::i64_ts;
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MembershipRecord {
+    pub id: i64_ts,
+    pub character_id: i64_ts,
+    pub corporation_id: i64_ts,
+    pub corporation_name: String,
+    pub alliance_id: Option<i64_ts>,
+    pub alliance_name: Option<String>,
+    /// SQLite `CURRENT_TIMESTAMP` when this membership was first seen.
+    pub observed_at: String,
+}
+
+impl MembershipRecord {
+    pub fn affiliation(&self) -> Affiliation {
+        Affiliation {
+            corporation_id: self.corporation_id,
+            corporation_name: self.corporation_name.clone(),
+            alliance_id: self.alliance_id,
+            alliance_name: self.alliance_name.clone(),
+        }
+    }
+}
+
+/// Newest first; the first row is the current membership.
+pub async fn get_membership_history(
+    pool: &Pool,
+    character_id: i64,
+) -> Result<Vec<MembershipRecord>> {
+    let rows = sqlx::query_as::<_, MembershipRecord>(
+        "SELECT id, character_id, corporation_id, corporation_name, alliance_id, alliance_name,
+                observed_at
+         FROM character_membership_history
+         WHERE character_id = ?
+         ORDER BY id DESC",
+    )
+    .bind(character_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Records the character's current membership. A new row is added on first
+/// sighting and whenever corporation or alliance changed; a rename only
+/// refreshes the names on the current row. Returns true for a change (not
+/// for the first sighting).
+pub async fn record_membership(
+    pool: &Pool,
+    character_id: i64,
+    affiliation: &Affiliation,
+) -> Result<bool> {
+    let mut tx = pool.begin().await?;
+
+    let current = sqlx::query_as::<_, MembershipRecord>(
+        "SELECT id, character_id, corporation_id, corporation_name, alliance_id, alliance_name,
+                observed_at
+         FROM character_membership_history
+         WHERE character_id = ?
+         ORDER BY id DESC
+         LIMIT 1",
+    )
+    .bind(character_id)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let changed = match &current {
+        Some(current) if !affiliation.differs_from(&current.affiliation()) => {
+            sqlx::query(
+                "UPDATE character_membership_history
+                 SET corporation_name = ?, alliance_name = ?
+                 WHERE id = ?",
+            )
+            .bind(&affiliation.corporation_name)
+            .bind(&affiliation.alliance_name)
+            .bind(current.id)
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+            return Ok(false);
+        }
+        Some(_) => true,
+        None => false,
+    };
+
+    sqlx::query(
+        "INSERT INTO character_membership_history
+             (character_id, corporation_id, corporation_name, alliance_id, alliance_name)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(character_id)
+    .bind(affiliation.corporation_id)
+    .bind(&affiliation.corporation_name)
+    .bind(affiliation.alliance_id)
+    .bind(&affiliation.alliance_name)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    fn affiliation(corporation_id: i64, corporation_name: &str) -> Affiliation {
+        Affiliation {
+            corporation_id,
+            corporation_name: corporation_name.to_string(),
+            alliance_id: None,
+            alliance_name: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn records_first_sighting_changes_and_renames() {
+        let db = TestDb::new().await.unwrap();
+        crate::db::add_character(&db.pool, 1, "Alt").await.unwrap();
+
+        assert!(!record_membership(&db.pool, 1, &affiliation(10, "Academy"))
+            .await
+            .unwrap());
+        assert!(
+            !record_membership(&db.pool, 1, &affiliation(10, "Renamed Academy"))
+                .await
+                .unwrap()
+        );
+        assert!(
+            record_membership(&db.pool, 1, &affiliation(1000167, "NPC Corp"))
+                .await
+                .unwrap()
+        );
+
+        let history = get_membership_history(&db.pool, 1).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].corporation_id, 1000167);
+        assert_eq!(history[1].corporation_name, "Renamed Academy");
+    }
+}
@@ -2,25 +2,49 @@ use std::fs;
 
 use anyhow::{Context, Result};
 use sqlx::{
-    sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous},
+    sqlite::{
+        SqliteAutoVacuum, SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions,
+        SqliteSynchronous,
+    },
     SqlitePool,
 };
-use tauri::Manager;
+
+use crate::storage::StorageLocation;
 
 pub mod accounts;
+pub mod activity_feed;
 pub mod app_settings;
+pub mod attribute_history;
+pub mod audit_log;
 pub mod character_attributes;
+pub mod character_modules;
 pub mod character_skills;
 pub mod characters;
 pub mod clones;
 pub mod enabled_features;
+pub mod farm;
 pub mod locations;
+pub mod maintenance;
+pub mod market_prices;
+pub mod membership;
+pub mod notification_webhooks;
 pub mod notifications;
+pub mod plan_assignments;
+pub mod plan_comments;
 pub mod plan_groups;
+pub mod plan_revisions;
+pub mod planned_boosters;
 pub mod remaps;
+#[cfg(test)]
+pub mod repository;
+pub mod retention;
 pub mod sde;
 pub mod skill_plans;
+pub mod skill_queue_journal;
+pub mod sp_history;
+pub mod sync;
 pub mod tokens;
+pub mod watchlist;
 
 pub use accounts::{
     add_character_to_account, create_account, delete_account, get_account, get_all_accounts,
@@ -38,7 +62,8 @@ pub use character_attributes::{
 pub use character_skills::{get_character_skills, set_character_skills, CharacterSkill};
 pub use characters::{
     add_character, delete_character, get_all_characters, get_character,
-    set_character_unallocated_sp, update_character, update_character_omega_status, Character,
+    get_character_omega_lapsed_at, get_character_omega_override, set_character_unallocated_sp,
+    update_character, update_character_omega_status, Character,
 };
 pub use clones::{
     find_clone_by_implants, get_character_clones, get_clone_implants,
@@ -49,30 +74,62 @@ pub use enabled_features::{
 };
 pub use locations::{get_station, get_structure, upsert_station, upsert_structure};
 pub use notifications::{
-    cleanup_old_dismissed_notifications, clear_notification, create_notification,
-    dismiss_notification, get_notification_setting, get_notification_settings, get_notifications,
-    has_active_notification, upsert_notification_setting, Notification, NotificationSetting,
+    clear_notification, create_notification, dismiss_notification, get_fired_stages,
+    get_notification_setting, get_notification_settings, get_notifications,
+    has_active_notification, has_notification_since, record_fired_stages, reset_fired_stages,
+    upsert_notification_setting, Notification, NotificationSetting,
 };
 pub use sde::{get_skill_groups_for_category, get_skills_for_group};
 pub use tokens::{get_tokens, set_tokens, update_tokens};
 
 pub type Pool = SqlitePool;
 
-pub async fn init_db(app: &tauri::AppHandle) -> Result<Pool> {
-    let app_data_dir = app
-        .path()
-        .app_data_dir()
-        .context("failed to resolve app data directory")?;
+/// Read-only connections to the same database, for long analytical queries
+/// (multi-character comparisons, simulations, optimisation). WAL lets these
+/// read concurrently with the writer pool, so a slow report never queues
+/// behind or ahead of interactive writes.
+#[derive(Clone)]
+pub struct ReadPool(pub Pool);
+
+impl std::ops::Deref for ReadPool {
+    type Target = Pool;
+
+    fn deref(&self) -> &Pool {
+        &self.0
+    }
+}
+
+/// Open the read-only pool. Must be called after `init_db` so the file exists
+/// and migrations have run.
+pub async fn init_read_pool(location: &StorageLocation) -> Result<ReadPool> {
+    let db_path = location.database_path();
+    let options = SqliteConnectOptions::new()
+        .filename(&db_path)
+        .read_only(true)
+        .pragma("query_only", "ON");
+
+    let pool = SqlitePoolOptions::new()
+        .max_connections(4)
+        .connect_with(options)
+        .await
+        .with_context(|| format!("failed to create read-only pool at {}", db_path.display()))?;
+
+    Ok(ReadPool(pool))
+}
 
-    fs::create_dir_all(&app_data_dir).context("failed to create app data directory")?;
+pub async fn init_db(location: &StorageLocation) -> Result<Pool> {
+    fs::create_dir_all(&location.data_dir).context("failed to create app data directory")?;
 
-    let db_path = app_data_dir.join("database.sqlite");
+    let db_path = location.database_path();
 
     let options = SqliteConnectOptions::new()
         .filename(&db_path)
         .create_if_missing(true)
         .journal_mode(SqliteJournalMode::Wal)
-        .synchronous(SqliteSynchronous::Normal);
+        .synchronous(SqliteSynchronous::Normal)
+        // Only applies to new databases; existing ones are converted by the
+        // first maintenance run (see maintenance.rs).
+        .auto_vacuum(SqliteAutoVacuum::Incremental);
 
     let pool = SqlitePoolOptions::new()
         .connect_with(options)
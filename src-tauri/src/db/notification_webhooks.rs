@@ -0,0 +1,223 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::{sqlite::SqliteRow, FromRow, Row};
+use typeshare::typeshare;
+
+use super::Pool;
+use crate::ts_types::i64_ts;
+
+const DISCORD_WEBHOOK_PREFIXES: [&str; 2] = [
+    "https://discord.com/api/webhooks/",
+    "https://discordapp.com/api/webhooks/",
+];
+
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NotificationWebhook {
+    pub id: i64_ts,
+    pub name: String,
+    pub url: String,
+    pub enabled: bool,
+    /// Types routed to this webhook; `None` routes every type.
+    pub notification_types: Option<Vec<String>>,
+}
+
+impl NotificationWebhook {
+    pub fn routes(&self, notification_type: &str) -> bool {
+        self.enabled
+            && self
+                .notification_types
+                .as_ref()
+                .is_none_or(|types| types.iter().any(|t| t == notification_type))
+    }
+}
+
+impl<'r> FromRow<'r, SqliteRow> for NotificationWebhook {
+    fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+        let types: Option<String> = row.get("notification_types");
+        Ok(NotificationWebhook {
+            id: row.get("id"),
+            name: row.get("name"),
+            url: row.get("url"),
+            enabled: row.get::<i64, _>("enabled") != 0,
+            notification_types: types.and_then(|raw| serde_json::from_str(&raw).ok()),
+        })
+    }
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotificationWebhookInput {
+    pub name: String,
+    pub url: String,
+    pub enabled: bool,
+    pub notification_types: Option<Vec<String>>,
+}
+
+fn validate(input: &NotificationWebhookInput) -> Result<()> {
+    if input.name.trim().is_empty() {
+        bail!("Webhook name is empty");
+    }
+    if !DISCORD_WEBHOOK_PREFIXES
+        .iter()
+        .any(|prefix| input.url.starts_with(prefix))
+    {
+        bail!("Not a Discord webhook URL");
+    }
+    Ok(())
+}
+
+fn types_json(input: &NotificationWebhookInput) -> Result<Option<String>> {
+    Ok(input
+        .notification_types
+        .as_ref()
+        .map(serde_json::to_string)
+        .transpose()?)
+}
+
+pub async fn get_notification_webhooks(pool: &Pool) -> Result<Vec<NotificationWebhook>> {
+    let webhooks = sqlx::query_as::<_, NotificationWebhook>(
+        "SELECT id, name, url, enabled, notification_types
+         FROM notification_webhooks ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(webhooks)
+}
+
+/// Enabled webhooks that `notification_type` is routed to.
+pub async fn get_webhooks_for_type(
+    pool: &Pool,
+    notification_type: &str,
+) -> Result<Vec<NotificationWebhook>> {
+    Ok(get_notification_webhooks(pool)
+        .await?
+        .into_iter()
+        .filter(|webhook| webhook.routes(notification_type))
+        .collect())
+}
+
+pub async fn get_notification_webhook(pool: &Pool, id: i64) -> Result<Option<NotificationWebhook>> {
+    let webhook = sqlx::query_as::<_, NotificationWebhook>(
+        "SELECT id, name, url, enabled, notification_types
+         FROM notification_webhooks WHERE id = ?",
+    )
+    .bind(id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(webhook)
+}
+
+pub async fn create_notification_webhook(
+    pool: &Pool,
+    input: &NotificationWebhookInput,
+) -> Result<i64> {
+    validate(input)?;
+    let result = sqlx::query(
+        "INSERT INTO notification_webhooks (name, url, enabled, notification_types, created_at)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(input.name.trim())
+    .bind(&input.url)
+    .bind(input.enabled)
+    .bind(types_json(input)?)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(pool)
+    .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn update_notification_webhook(
+    pool: &Pool,
+    id: i64,
+    input: &NotificationWebhookInput,
+) -> Result<()> {
+    validate(input)?;
+    sqlx::query(
+        "UPDATE notification_webhooks
+         SET name = ?, url = ?, enabled = ?, notification_types = ?
+         WHERE id = ?",
+    )
+    .bind(input.name.trim())
+    .bind(&input.url)
+    .bind(input.enabled)
+    .bind(types_json(input)?)
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_notification_webhook(pool: &Pool, id: i64) -> Result<()> {
+    sqlx::query("DELETE FROM notification_webhooks WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    fn input(name: &str, enabled: bool, types: Option<&[&str]>) -> NotificationWebhookInput {
+        NotificationWebhookInput {
+            name: name.to_string(),
+            url: "https://discord.com/api/webhooks/1/token".to_string(),
+            enabled,
+            notification_types: types.map(|t| t.iter().map(|s| s.to_string()).collect()),
+        }
+    }
+
+    #[tokio::test]
+    async fn routes_by_notification_type() {
+        let db = TestDb::new().await.unwrap();
+        let all = create_notification_webhook(&db.pool, &input("All", true, None))
+            .await
+            .unwrap();
+        let queue = create_notification_webhook(
+            &db.pool,
+            &input("Queue", true, Some(&["skill_queue_empty"])),
+        )
+        .await
+        .unwrap();
+        create_notification_webhook(&db.pool, &input("Off", false, None))
+            .await
+            .unwrap();
+
+        let ids = |webhooks: Vec<NotificationWebhook>| {
+            webhooks.into_iter().map(|w| w.id).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ids(get_webhooks_for_type(&db.pool, "skill_queue_empty")
+                .await
+                .unwrap()),
+            vec![all, queue]
+        );
+        assert_eq!(
+            ids(get_webhooks_for_type(&db.pool, "omega_lapsed")
+                .await
+                .unwrap()),
+            vec![all]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_urls_that_are_not_discord_webhooks() {
+        let db = TestDb::new().await.unwrap();
+        let mut bad = input("Bad", true, None);
+        bad.url = "https://example.com/hook".to_string();
+
+        assert!(create_notification_webhook(&db.pool, &bad).await.is_err());
+        assert!(get_notification_webhooks(&db.pool)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -66,13 +66,16 @@ pub async fn get_notification_setting(
     Ok(setting)
 }
 
-pub async fn upsert_notification_setting(
-    pool: &Pool,
+pub async fn upsert_notification_setting<'a, E>(
+    executor: E,
     character_id: i64,
     notification_type: &str,
     enabled: bool,
     config: Option<&str>,
-) -> Result<()> {
+) -> Result<()>
+where
+    E: sqlx::Executor<'a, Database = sqlx::Sqlite>,
+{
     sqlx::query(
         "INSERT INTO notification_settings (character_id, notification_type, enabled, config)
          VALUES (?, ?, ?, ?)
@@ -84,7 +87,7 @@ pub async fn upsert_notification_setting(
     .bind(config)
     .bind(if enabled { 1 } else { 0 })
     .bind(config)
-    .execute(pool)
+    .execute(executor)
     .await?;
 
     Ok(())
@@ -178,10 +181,31 @@ pub async fn has_active_notification(
     Ok(count > 0)
 }
 
-pub async fn cleanup_old_dismissed_notifications(pool: &Pool) -> Result<u64> {
+/// True when any notification of this type (active or dismissed) was raised at
+/// or after `since`, a SQLite `CURRENT_TIMESTAMP` string.
+pub async fn has_notification_since(
+    pool: &Pool,
+    character_id: i64,
+    notification_type: &str,
+    since: &str,
+) -> Result<bool> {
+    let count: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM notifications WHERE character_id = ? AND notification_type = ? AND created_at >= ?",
+    )
+    .bind(character_id)
+    .bind(notification_type)
+    .bind(since)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(count > 0)
+}
+
+pub async fn cleanup_old_dismissed_notifications(pool: &Pool, days: i64) -> Result<u64> {
     let result = sqlx::query(
-        "DELETE FROM notifications WHERE status = 'dismissed' AND created_at < datetime('now', '-14 days')",
+        "DELETE FROM notifications WHERE status = 'dismissed' AND created_at < datetime('now', ?)",
     )
+    .bind(format!("-{} days", days))
     .execute(pool)
     .await?;
 
@@ -201,3 +225,67 @@ pub async fn clear_notification(
 
     Ok(result.rows_affected() > 0)
 }
+
+/// Stages of `notification_type` that have fired in the current cycle.
+pub async fn get_fired_stages(
+    pool: &Pool,
+    character_id: i64,
+    notification_type: &str,
+) -> Result<Vec<f64>> {
+    let stages = sqlx::query_scalar::<_, f64>(
+        "SELECT stage FROM notification_stages WHERE character_id = ? AND notification_type = ?",
+    )
+    .bind(character_id)
+    .bind(notification_type)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(stages)
+}
+
+pub async fn record_fired_stages(
+    pool: &Pool,
+    character_id: i64,
+    notification_type: &str,
+    stages: &[f64],
+) -> Result<()> {
+    let now = chrono::Utc::now().timestamp();
+    let mut tx = pool.begin().await?;
+    for stage in stages {
+        sqlx::query(
+            "INSERT OR IGNORE INTO notification_stages (character_id, notification_type, stage, fired_at)
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(character_id)
+        .bind(notification_type)
+        .bind(stage)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(())
+}
+
+/// Forget fired stages below `value`, which the tracked value has risen back
+/// above; `None` forgets them all.
+pub async fn reset_fired_stages(
+    pool: &Pool,
+    character_id: i64,
+    notification_type: &str,
+    value: Option<f64>,
+) -> Result<()> {
+    sqlx::query(
+        "DELETE FROM notification_stages
+         WHERE character_id = ? AND notification_type = ? AND (? IS NULL OR stage < ?)",
+    )
+    .bind(character_id)
+    .bind(notification_type)
+    .bind(value)
+    .bind(value)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
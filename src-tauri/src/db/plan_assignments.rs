@@ -0,0 +1,82 @@
+use anyhow::Result;
+
+use super::Pool;
+
+/// Characters assigned to the plan, by character id.
+pub async fn get_plan_assignments(pool: &Pool, plan_id: i64) -> Result<Vec<i64>> {
+    let rows = sqlx::query_scalar::<_, i64>(
+        "SELECT character_id FROM plan_assignments WHERE plan_id = ? ORDER BY character_id",
+    )
+    .bind(plan_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Makes `character_ids` the plan's assignments. Characters that stay
+/// assigned keep their original `assigned_at`.
+pub async fn set_plan_assignments(pool: &Pool, plan_id: i64, character_ids: &[i64]) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    let keep = serde_json::to_string(character_ids)?;
+    sqlx::query(
+        "DELETE FROM plan_assignments
+         WHERE plan_id = ? AND character_id NOT IN (SELECT value FROM json_each(?))",
+    )
+    .bind(plan_id)
+    .bind(&keep)
+    .execute(&mut *tx)
+    .await?;
+
+    let now = chrono::Utc::now().timestamp();
+    for character_id in character_ids {
+        sqlx::query(
+            "INSERT OR IGNORE INTO plan_assignments (plan_id, character_id, assigned_at)
+             VALUES (?, ?, ?)",
+        )
+        .bind(plan_id)
+        .bind(character_id)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::{fixtures, TestDb};
+
+    #[tokio::test]
+    async fn set_reconciles_assignments() {
+        let db = TestDb::new().await.unwrap();
+        let pool = &db.pool;
+        for (id, name) in [(1, "One"), (2, "Two"), (3, "Three")] {
+            crate::db::add_character(pool, id, name).await.unwrap();
+        }
+        let plan_id = fixtures::create_skill_plan(pool, "Doctrine").await;
+
+        set_plan_assignments(pool, plan_id, &[1, 2]).await.unwrap();
+        assert_eq!(
+            get_plan_assignments(pool, plan_id).await.unwrap(),
+            vec![1, 2]
+        );
+
+        set_plan_assignments(pool, plan_id, &[2, 3, 3])
+            .await
+            .unwrap();
+        assert_eq!(
+            get_plan_assignments(pool, plan_id).await.unwrap(),
+            vec![2, 3]
+        );
+
+        set_plan_assignments(pool, plan_id, &[]).await.unwrap();
+        assert!(get_plan_assignments(pool, plan_id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
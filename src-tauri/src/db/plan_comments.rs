@@ -0,0 +1,116 @@
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::FromRow;
+use typeshare::typeshare;
+
+use super::Pool;
+use crate::ts_types::i64_ts;
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct PlanEntryComment {
+    pub comment_id: i64_ts,
+    pub plan_id: i64_ts,
+    pub skill_type_id: i64_ts,
+    pub planned_level: i64_ts,
+    pub author: String,
+    pub body: String,
+    pub created_at: i64_ts,
+}
+
+/// Comments on the plan's current entries, oldest first. Comments whose
+/// skill level has since been removed from the plan are left out.
+pub async fn get_plan_comments<'a, E>(executor: E, plan_id: i64) -> Result<Vec<PlanEntryComment>>
+where
+    E: sqlx::Executor<'a, Database = sqlx::Sqlite>,
+{
+    let comments = sqlx::query_as::<_, PlanEntryComment>(
+        "SELECT c.comment_id, c.plan_id, c.skill_type_id, c.planned_level, c.author, c.body, c.created_at
+         FROM skill_plan_entry_comments c
+         WHERE c.plan_id = ?
+           AND EXISTS (
+               SELECT 1 FROM skill_plan_entries e
+               WHERE e.plan_id = c.plan_id
+                 AND e.skill_type_id = c.skill_type_id
+                 AND e.planned_level = c.planned_level
+           )
+         ORDER BY c.created_at, c.comment_id",
+    )
+    .bind(plan_id)
+    .fetch_all(executor)
+    .await?;
+
+    Ok(comments)
+}
+
+pub async fn add_plan_comment<'a, E>(
+    executor: E,
+    plan_id: i64,
+    skill_type_id: i64,
+    planned_level: i64,
+    author: &str,
+    body: &str,
+    created_at: i64,
+) -> Result<i64>
+where
+    E: sqlx::Executor<'a, Database = sqlx::Sqlite>,
+{
+    let result = sqlx::query(
+        "INSERT INTO skill_plan_entry_comments (plan_id, skill_type_id, planned_level, author, body, created_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(plan_id)
+    .bind(skill_type_id)
+    .bind(planned_level)
+    .bind(author)
+    .bind(body)
+    .bind(created_at)
+    .execute(executor)
+    .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn delete_plan_comment(pool: &Pool, comment_id: i64) -> Result<()> {
+    sqlx::query("DELETE FROM skill_plan_entry_comments WHERE comment_id = ?")
+        .bind(comment_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn clear_plan_comments<'a, E>(executor: E, plan_id: i64) -> Result<()>
+where
+    E: sqlx::Executor<'a, Database = sqlx::Sqlite>,
+{
+    sqlx::query("DELETE FROM skill_plan_entry_comments WHERE plan_id = ?")
+        .bind(plan_id)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::{fixtures, TestDb};
+
+    #[tokio::test]
+    async fn comments_follow_skill_level_not_entry_row() {
+        // 3300 Gunnery: real, prerequisite-free skill (FK to sde_types).
+        let db = TestDb::new_with_sde().await.unwrap();
+        let plan_id = fixtures::create_skill_plan(&db.pool, "Doctrine").await;
+        fixtures::add_plan_entry(&db.pool, plan_id, 3300, 1, "Planned").await;
+
+        add_plan_comment(&db.pool, plan_id, 3300, 1, "FC", "Needed for the fit", 1)
+            .await
+            .unwrap();
+        add_plan_comment(&db.pool, plan_id, 3300, 2, "FC", "Level not in plan", 2)
+            .await
+            .unwrap();
+
+        let comments = get_plan_comments(&db.pool, plan_id).await.unwrap();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].body, "Needed for the fit");
+    }
+}
@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, Sqlite, Transaction};
+use sqlx::{FromRow, Sqlite, SqliteConnection, Transaction};
 use typeshare::typeshare;
 
 use super::Pool;
@@ -54,13 +54,24 @@ pub async fn list(pool: &Pool) -> Result<Vec<PlanGroup>> {
 /// tree never exceeds the configured nesting limit. Appends to the end of the
 /// parent's children by assigning the next free `sort_order`.
 pub async fn create(pool: &Pool, name: &str, parent_group_id: Option<i64>) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+    let group_id = create_in(&mut tx, name, parent_group_id).await?;
+    tx.commit().await?;
+    Ok(group_id)
+}
+
+/// [`create`] on a caller-supplied connection, so a folder and the plans filed
+/// into it can be created in one transaction.
+pub async fn create_in(
+    conn: &mut SqliteConnection,
+    name: &str,
+    parent_group_id: Option<i64>,
+) -> Result<i64> {
     let name = name.trim();
     if name.is_empty() {
         return Err(anyhow!("Folder name cannot be empty"));
     }
 
-    let mut tx = pool.begin().await?;
-
     let next_sort_order: i64 = if let Some(parent_id) = parent_group_id {
         let parent_depth: Option<i64> = sqlx::query_scalar(
             "WITH RECURSIVE chain(group_id, parent_group_id, depth) AS (
@@ -73,7 +84,7 @@ pub async fn create(pool: &Pool, name: &str, parent_group_id: Option<i64>) -> Re
              SELECT MAX(depth) FROM chain",
         )
         .bind(parent_id)
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut *conn)
         .await?;
 
         let depth = parent_depth.ok_or_else(|| anyhow!("Parent folder {} not found", parent_id))?;
@@ -90,14 +101,14 @@ pub async fn create(pool: &Pool, name: &str, parent_group_id: Option<i64>) -> Re
              FROM plan_groups WHERE parent_group_id = ?",
         )
         .bind(parent_id)
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut *conn)
         .await?
     } else {
         sqlx::query_scalar(
             "SELECT COALESCE(MAX(sort_order), -1) + 1
              FROM plan_groups WHERE parent_group_id IS NULL",
         )
-        .fetch_one(&mut *tx)
+        .fetch_one(&mut *conn)
         .await?
     };
 
@@ -106,10 +117,9 @@ pub async fn create(pool: &Pool, name: &str, parent_group_id: Option<i64>) -> Re
             .bind(name)
             .bind(parent_group_id)
             .bind(next_sort_order)
-            .execute(&mut *tx)
+            .execute(&mut *conn)
             .await?;
 
-    tx.commit().await?;
     Ok(result.last_insert_rowid())
 }
 
@@ -354,6 +364,21 @@ pub async fn move_node(pool: &Pool, payload: MoveNodePayload) -> Result<()> {
     Ok(())
 }
 
+/// Files a plan at the end of `group_id` (NULL = root), for callers that only
+/// choose a folder and not a position among its children.
+pub async fn move_plan_to_group(pool: &Pool, plan_id: i64, group_id: Option<i64>) -> Result<()> {
+    move_node(
+        pool,
+        MoveNodePayload {
+            kind: NodeKind::Plan,
+            id: plan_id,
+            new_parent_group_id: group_id,
+            new_sort_order: i64::MAX,
+        },
+    )
+    .await
+}
+
 /// Renumber sibling sort_order densely across plan_groups + skill_plans sharing
 /// the same parent. If `place_moved` is set, the named node is positioned at the
 /// requested index and the rest fill in around it.
@@ -701,6 +726,25 @@ mod tests {
         assert_eq!(sort, 0);
     }
 
+    #[tokio::test]
+    async fn move_plan_to_group_appends_after_existing_children() {
+        let db = TestDb::new().await.unwrap();
+        let g = create(&db.pool, "Group", None).await.unwrap();
+        let _sub = create(&db.pool, "Sub", Some(g)).await.unwrap();
+        let existing = create_plan_for_test(&db.pool, "Existing", Some(g), 1)
+            .await
+            .unwrap();
+        let p = create_plan_for_test(&db.pool, "P", None, 0).await.unwrap();
+
+        move_plan_to_group(&db.pool, p, Some(g)).await.unwrap();
+
+        assert_eq!(plan_sort(&db.pool, existing).await, Some((Some(g), 1)));
+        assert_eq!(plan_sort(&db.pool, p).await, Some((Some(g), 2)));
+
+        move_plan_to_group(&db.pool, p, None).await.unwrap();
+        assert_eq!(plan_sort(&db.pool, p).await, Some((None, 1)));
+    }
+
     #[tokio::test]
     async fn move_node_reparents_group_with_descendants() {
         let db = TestDb::new().await.unwrap();
@@ -0,0 +1,152 @@
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::{FromRow, SqliteConnection};
+use typeshare::typeshare;
+
+use super::skill_plans::{get_plan_entries, ReplacePlanEntry};
+use super::Pool;
+use crate::ts_types::i64_ts;
+
+/// Revisions kept per plan; the oldest are dropped as new ones are recorded.
+pub const MAX_REVISIONS_PER_PLAN: i64 = 100;
+
+/// The plan's entries as they were just before `action` changed them.
+#[typeshare]
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct PlanRevision {
+    pub revision_id: i64_ts,
+    pub plan_id: i64_ts,
+    /// Name of the command that made the change, e.g. `delete_plan_entry`.
+    pub action: String,
+    pub entry_count: i64_ts,
+    /// Unix seconds.
+    pub created_at: i64_ts,
+}
+
+/// Snapshot the plan's current entries ahead of `action` changing them.
+pub async fn record_revision(pool: &Pool, plan_id: i64, action: &str) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+    let revision_id = record_revision_in(&mut tx, plan_id, action).await?;
+    tx.commit().await?;
+
+    Ok(revision_id)
+}
+
+/// [`record_revision`] inside the caller's transaction, for changes that read
+/// and write the plan in one.
+pub async fn record_revision_in(
+    conn: &mut SqliteConnection,
+    plan_id: i64,
+    action: &str,
+) -> Result<i64> {
+    let entries: Vec<ReplacePlanEntry> = get_plan_entries(&mut *conn, plan_id)
+        .await?
+        .into_iter()
+        .map(|e| ReplacePlanEntry {
+            skill_type_id: e.skill_type_id,
+            planned_level: e.planned_level,
+            entry_type: e.entry_type,
+            notes: e.notes,
+            excluded: e.excluded,
+            priority: e.priority,
+        })
+        .collect();
+
+    let revision_id = sqlx::query(
+        "INSERT INTO skill_plan_revisions (plan_id, action, entries, entry_count, created_at)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(plan_id)
+    .bind(action)
+    .bind(serde_json::to_string(&entries)?)
+    .bind(entries.len() as i64)
+    .bind(chrono::Utc::now().timestamp())
+    .execute(&mut *conn)
+    .await?
+    .last_insert_rowid();
+
+    sqlx::query(
+        "DELETE FROM skill_plan_revisions
+         WHERE plan_id = ? AND revision_id NOT IN (
+             SELECT revision_id FROM skill_plan_revisions
+             WHERE plan_id = ? ORDER BY revision_id DESC LIMIT ?
+         )",
+    )
+    .bind(plan_id)
+    .bind(plan_id)
+    .bind(MAX_REVISIONS_PER_PLAN)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(revision_id)
+}
+
+/// Newest first.
+pub async fn get_plan_revisions(pool: &Pool, plan_id: i64) -> Result<Vec<PlanRevision>> {
+    let revisions = sqlx::query_as::<_, PlanRevision>(
+        "SELECT revision_id, plan_id, action, entry_count, created_at
+         FROM skill_plan_revisions WHERE plan_id = ? ORDER BY revision_id DESC",
+    )
+    .bind(plan_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(revisions)
+}
+
+/// The entries saved with `revision_id`, in plan order. `None` when the
+/// revision does not exist or belongs to another plan.
+pub async fn get_revision_entries(
+    pool: &Pool,
+    plan_id: i64,
+    revision_id: i64,
+) -> Result<Option<Vec<ReplacePlanEntry>>> {
+    let entries: Option<String> = sqlx::query_scalar(
+        "SELECT entries FROM skill_plan_revisions WHERE plan_id = ? AND revision_id = ?",
+    )
+    .bind(plan_id)
+    .bind(revision_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(entries.map(|e| serde_json::from_str(&e)).transpose()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::{fixtures, TestDb};
+
+    #[tokio::test]
+    async fn keeps_the_newest_revisions_per_plan() {
+        let db = TestDb::new().await.unwrap();
+        let plan = fixtures::create_skill_plan(&db.pool, "History").await;
+        fixtures::add_plan_entry(&db.pool, plan, 3327, 1, "Planned").await;
+
+        let first = record_revision(&db.pool, plan, "add_plan_entry")
+            .await
+            .unwrap();
+        for _ in 0..MAX_REVISIONS_PER_PLAN {
+            record_revision(&db.pool, plan, "reorder_plan_entries")
+                .await
+                .unwrap();
+        }
+
+        let revisions = get_plan_revisions(&db.pool, plan).await.unwrap();
+        assert_eq!(revisions.len() as i64, MAX_REVISIONS_PER_PLAN);
+        assert!(revisions.iter().all(|r| r.revision_id != first));
+        assert_eq!(revisions[0].entry_count, 1);
+
+        let entries = get_revision_entries(&db.pool, plan, revisions[0].revision_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(entries[0].skill_type_id, 3327);
+        assert!(
+            get_revision_entries(&db.pool, plan + 1, revisions[0].revision_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+}
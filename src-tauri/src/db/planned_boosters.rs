@@ -0,0 +1,71 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use typeshare::typeshare;
+
+use super::Pool;
+use crate::ts_types::i64_ts;
+
+/// A cerebral accelerator planned into a skill plan, taken right after the
+/// anchor entry (or at the start of the plan when there is none).
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PlannedBooster {
+    pub booster_id: i64_ts,
+    pub plan_id: i64_ts,
+    pub type_id: Option<i64_ts>,
+    pub bonus: i64_ts,
+    pub duration_seconds: i64_ts,
+    pub after_skill_type_id: Option<i64_ts>,
+    pub after_skill_level: Option<i64_ts>,
+    pub created_at: i64_ts,
+}
+
+pub async fn save_planned_booster<'a, E>(
+    executor: E,
+    plan_id: i64,
+    type_id: Option<i64>,
+    bonus: i64,
+    duration_seconds: i64,
+    after_skill_type_id: Option<i64>,
+    after_skill_level: Option<i64>,
+) -> Result<i64>
+where
+    E: sqlx::Executor<'a, Database = sqlx::Sqlite>,
+{
+    let result = sqlx::query(
+        "INSERT INTO planned_boosters (
+            plan_id, type_id, bonus, duration_seconds, after_skill_type_id, after_skill_level
+        ) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(plan_id)
+    .bind(type_id)
+    .bind(bonus)
+    .bind(duration_seconds)
+    .bind(after_skill_type_id)
+    .bind(after_skill_level)
+    .execute(executor)
+    .await?;
+
+    Ok(result.last_insert_rowid())
+}
+
+pub async fn get_plan_boosters(pool: &Pool, plan_id: i64) -> Result<Vec<PlannedBooster>> {
+    let boosters = sqlx::query_as::<_, PlannedBooster>(
+        "SELECT booster_id, plan_id, type_id, bonus, duration_seconds,
+                after_skill_type_id, after_skill_level, created_at
+         FROM planned_boosters WHERE plan_id = ? ORDER BY created_at ASC, booster_id ASC",
+    )
+    .bind(plan_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(boosters)
+}
+
+pub async fn delete_planned_booster(pool: &Pool, booster_id: i64) -> Result<()> {
+    sqlx::query("DELETE FROM planned_boosters WHERE booster_id = ?")
+        .bind(booster_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, SqliteConnection};
 use typeshare::typeshare;
 
 use super::Pool;
@@ -67,11 +67,80 @@ where
     Ok(result.last_insert_rowid())
 }
 
+/// Re-anchor and re-value a remap; moving it to a different entry is how a
+/// remap marker is reordered within its plan.
+pub async fn update_remap(
+    pool: &Pool,
+    remap_id: i64,
+    after_skill_type_id: Option<i64>,
+    after_skill_level: Option<i64>,
+    attributes: &Attributes,
+) -> Result<()> {
+    let result = sqlx::query(
+        "UPDATE remaps
+         SET after_skill_type_id = ?, after_skill_level = ?,
+             intelligence = ?, perception = ?, charisma = ?, willpower = ?, memory = ?
+         WHERE remap_id = ?",
+    )
+    .bind(after_skill_type_id)
+    .bind(after_skill_level)
+    .bind(attributes.intelligence)
+    .bind(attributes.perception)
+    .bind(attributes.charisma)
+    .bind(attributes.willpower)
+    .bind(attributes.memory)
+    .bind(remap_id)
+    .execute(pool)
+    .await?;
+    if result.rows_affected() == 0 {
+        anyhow::bail!("Remap {} not found", remap_id);
+    }
+    Ok(())
+}
+
+/// Replace the plan's own remaps (those not tied to a character) with
+/// `remaps`, given as `(after_skill_type_id, after_skill_level, attributes)`,
+/// in one transaction.
+pub async fn replace_plan_remaps(
+    pool: &Pool,
+    plan_id: i64,
+    remaps: &[(Option<i64>, Option<i64>, Attributes)],
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    replace_plan_remaps_in(&mut tx, plan_id, remaps).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// [`replace_plan_remaps`] on a caller-supplied connection.
+pub async fn replace_plan_remaps_in(
+    conn: &mut SqliteConnection,
+    plan_id: i64,
+    remaps: &[(Option<i64>, Option<i64>, Attributes)],
+) -> Result<()> {
+    sqlx::query("DELETE FROM remaps WHERE plan_id = ? AND character_id IS NULL")
+        .bind(plan_id)
+        .execute(&mut *conn)
+        .await?;
+    for (after_skill_type_id, after_skill_level, attributes) in remaps {
+        save_remap(
+            &mut *conn,
+            None,
+            Some(plan_id),
+            *after_skill_type_id,
+            *after_skill_level,
+            attributes,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
 pub async fn get_plan_remaps(pool: &Pool, plan_id: i64) -> Result<Vec<Remap>> {
     let remaps = sqlx::query_as::<_, Remap>(
         "SELECT remap_id, character_id, plan_id, after_skill_type_id, after_skill_level,
                 intelligence, perception, charisma, willpower, memory, created_at
-         FROM remaps WHERE plan_id = ? ORDER BY created_at ASC",
+         FROM remaps WHERE plan_id = ? ORDER BY created_at ASC, remap_id ASC",
     )
     .bind(plan_id)
     .fetch_all(pool)
@@ -106,3 +175,60 @@ pub async fn delete_plan_remaps(pool: &Pool, plan_id: i64) -> Result<()> {
         .await?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::{fixtures, TestDb};
+
+    fn attributes(intelligence: i64) -> Attributes {
+        Attributes {
+            intelligence,
+            perception: 17,
+            charisma: 17,
+            willpower: 17,
+            memory: 17,
+        }
+    }
+
+    #[tokio::test]
+    async fn replace_plan_remaps_keeps_character_remaps() {
+        let db = TestDb::new().await.unwrap();
+        let plan_id = fixtures::create_skill_plan(&db.pool, "Plan").await;
+        crate::db::add_character(&db.pool, 1, "Pilot")
+            .await
+            .unwrap();
+        save_remap(
+            &db.pool,
+            Some(1),
+            Some(plan_id),
+            None,
+            None,
+            &attributes(20),
+        )
+        .await
+        .unwrap();
+        save_remap(&db.pool, None, Some(plan_id), None, None, &attributes(21))
+            .await
+            .unwrap();
+
+        replace_plan_remaps(
+            &db.pool,
+            plan_id,
+            &[(None, None, attributes(27)), (None, None, attributes(22))],
+        )
+        .await
+        .unwrap();
+
+        let remaps = get_plan_remaps(&db.pool, plan_id).await.unwrap();
+        let intelligence: Vec<i64> = remaps.iter().map(|r| r.intelligence).collect();
+        assert_eq!(intelligence, vec![20, 27, 22]);
+
+        update_remap(&db.pool, remaps[2].remap_id, None, None, &attributes(25))
+            .await
+            .unwrap();
+        assert!(update_remap(&db.pool, 99_999, None, None, &attributes(25))
+            .await
+            .is_err());
+    }
+}
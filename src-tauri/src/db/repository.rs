@@ -0,0 +1,289 @@
+//! Storage traits for the records most of the app reads and writes:
+//! characters, skill plans and notifications. The SQLite pool implements them
+//! by delegating to the functions in the sibling modules, so code written
+//! against a trait can later run on another backend (an in-memory store in
+//! tests, synced storage for cloud profiles) without changes.
+//!
+//! The traits cover the common operations only; queries that lean on SQLite
+//! itself (FTS search, paging, transactions spanning several tables) stay as
+//! free functions on [`Pool`].
+//!
+//! Only tests go through the traits so far, so the module is built for tests
+//! until the commands are moved onto it.
+
+use anyhow::Result;
+
+use super::characters::{self, Character};
+use super::notifications::{self, Notification};
+use super::skill_plans::{self, ReplacePlanEntry, SkillPlan, SkillPlanEntry};
+use super::Pool;
+
+#[async_trait::async_trait]
+pub trait CharacterRepository: Send + Sync {
+    async fn get_character(&self, character_id: i64) -> Result<Option<Character>>;
+    async fn add_character(&self, character_id: i64, character_name: &str) -> Result<()>;
+    /// Stamps the lapse time on an Omega -> Alpha transition and clears it on
+    /// the way back.
+    async fn update_character_omega_status(&self, character_id: i64, is_omega: bool) -> Result<()>;
+    /// `Some` pins the status over what refreshes report; `None` unpins it.
+    async fn set_character_omega_override(
+        &self,
+        character_id: i64,
+        is_omega: Option<bool>,
+    ) -> Result<()>;
+    async fn get_character_omega_lapsed_at(&self, character_id: i64) -> Result<Option<String>>;
+    async fn delete_character(&self, character_id: i64) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+pub trait PlanRepository: Send + Sync {
+    async fn create_skill_plan(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        auto_prerequisites: bool,
+        group_id: Option<i64>,
+    ) -> Result<i64>;
+    async fn get_skill_plan(&self, plan_id: i64) -> Result<Option<SkillPlan>>;
+    async fn get_all_skill_plans(&self) -> Result<Vec<SkillPlan>>;
+    async fn delete_skill_plan(&self, plan_id: i64) -> Result<()>;
+    /// Entries in training order.
+    async fn get_plan_entries(&self, plan_id: i64) -> Result<Vec<SkillPlanEntry>>;
+    /// Replaces every entry of the plan; entry ids are not preserved.
+    async fn replace_plan_entries(&self, plan_id: i64, entries: &[ReplacePlanEntry]) -> Result<()>;
+    async fn reorder_plan_entries(&self, plan_id: i64, entry_ids: &[i64]) -> Result<()>;
+}
+
+#[async_trait::async_trait]
+pub trait NotificationRepository: Send + Sync {
+    /// Newest first.
+    async fn get_notifications(
+        &self,
+        character_id: Option<i64>,
+        status: Option<&str>,
+    ) -> Result<Vec<Notification>>;
+    async fn create_notification(
+        &self,
+        character_id: i64,
+        notification_type: &str,
+        title: &str,
+        message: &str,
+    ) -> Result<i64>;
+    async fn dismiss_notification(&self, notification_id: i64) -> Result<()>;
+    async fn has_active_notification(
+        &self,
+        character_id: i64,
+        notification_type: &str,
+    ) -> Result<bool>;
+    /// Dismisses the active notification of this type; false when there was
+    /// none.
+    async fn clear_notification(&self, character_id: i64, notification_type: &str) -> Result<bool>;
+}
+
+#[async_trait::async_trait]
+impl CharacterRepository for Pool {
+    async fn get_character(&self, character_id: i64) -> Result<Option<Character>> {
+        characters::get_character(self, character_id).await
+    }
+
+    async fn add_character(&self, character_id: i64, character_name: &str) -> Result<()> {
+        characters::add_character(self, character_id, character_name).await
+    }
+
+    async fn update_character_omega_status(&self, character_id: i64, is_omega: bool) -> Result<()> {
+        characters::update_character_omega_status(self, character_id, is_omega).await
+    }
+
+    async fn set_character_omega_override(
+        &self,
+        character_id: i64,
+        is_omega: Option<bool>,
+    ) -> Result<()> {
+        characters::set_character_omega_override(self, character_id, is_omega).await
+    }
+
+    async fn get_character_omega_lapsed_at(&self, character_id: i64) -> Result<Option<String>> {
+        characters::get_character_omega_lapsed_at(self, character_id).await
+    }
+
+    async fn delete_character(&self, character_id: i64) -> Result<()> {
+        characters::delete_character(self, character_id).await
+    }
+}
+
+#[async_trait::async_trait]
+impl PlanRepository for Pool {
+    async fn create_skill_plan(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        auto_prerequisites: bool,
+        group_id: Option<i64>,
+    ) -> Result<i64> {
+        skill_plans::create_skill_plan(self, name, description, auto_prerequisites, group_id).await
+    }
+
+    async fn get_skill_plan(&self, plan_id: i64) -> Result<Option<SkillPlan>> {
+        skill_plans::get_skill_plan(self, plan_id).await
+    }
+
+    async fn get_all_skill_plans(&self) -> Result<Vec<SkillPlan>> {
+        skill_plans::get_all_skill_plans(self).await
+    }
+
+    async fn delete_skill_plan(&self, plan_id: i64) -> Result<()> {
+        skill_plans::delete_skill_plan(self, plan_id).await
+    }
+
+    async fn get_plan_entries(&self, plan_id: i64) -> Result<Vec<SkillPlanEntry>> {
+        skill_plans::get_plan_entries(self, plan_id).await
+    }
+
+    async fn replace_plan_entries(&self, plan_id: i64, entries: &[ReplacePlanEntry]) -> Result<()> {
+        skill_plans::replace_plan_entries(self, plan_id, entries).await
+    }
+
+    async fn reorder_plan_entries(&self, plan_id: i64, entry_ids: &[i64]) -> Result<()> {
+        skill_plans::reorder_plan_entries(self, plan_id, entry_ids).await
+    }
+}
+
+#[async_trait::async_trait]
+impl NotificationRepository for Pool {
+    async fn get_notifications(
+        &self,
+        character_id: Option<i64>,
+        status: Option<&str>,
+    ) -> Result<Vec<Notification>> {
+        notifications::get_notifications(self, character_id, status).await
+    }
+
+    async fn create_notification(
+        &self,
+        character_id: i64,
+        notification_type: &str,
+        title: &str,
+        message: &str,
+    ) -> Result<i64> {
+        notifications::create_notification(self, character_id, notification_type, title, message)
+            .await
+    }
+
+    async fn dismiss_notification(&self, notification_id: i64) -> Result<()> {
+        notifications::dismiss_notification(self, notification_id).await
+    }
+
+    async fn has_active_notification(
+        &self,
+        character_id: i64,
+        notification_type: &str,
+    ) -> Result<bool> {
+        notifications::has_active_notification(self, character_id, notification_type).await
+    }
+
+    async fn clear_notification(&self, character_id: i64, notification_type: &str) -> Result<bool> {
+        notifications::clear_notification(self, character_id, notification_type).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::{MemoryRepository, TestDb};
+
+    fn entry(skill_type_id: i64, planned_level: i64) -> ReplacePlanEntry {
+        ReplacePlanEntry {
+            skill_type_id,
+            planned_level,
+            entry_type: skill_plans::ENTRY_TYPE_PLANNED.to_string(),
+            notes: None,
+            excluded: false,
+            priority: skill_plans::DEFAULT_ENTRY_PRIORITY,
+        }
+    }
+
+    async fn plans_round_trip(repo: &impl PlanRepository) {
+        let plan_id = repo
+            .create_skill_plan("Plan", Some("notes"), true, None)
+            .await
+            .unwrap();
+        repo.replace_plan_entries(plan_id, &[entry(100, 1), entry(200, 1), entry(100, 2)])
+            .await
+            .unwrap();
+
+        let entries = repo.get_plan_entries(plan_id).await.unwrap();
+        let mut ids: Vec<i64> = entries.iter().map(|e| e.entry_id).collect();
+        ids.swap(0, 1);
+        repo.reorder_plan_entries(plan_id, &ids).await.unwrap();
+        let order: Vec<(i64, i64)> = repo
+            .get_plan_entries(plan_id)
+            .await
+            .unwrap()
+            .iter()
+            .map(|e| (e.skill_type_id, e.planned_level))
+            .collect();
+        assert_eq!(order, vec![(200, 1), (100, 1), (100, 2)]);
+
+        let plan = repo.get_skill_plan(plan_id).await.unwrap().unwrap();
+        assert_eq!(plan.description.as_deref(), Some("notes"));
+        assert_eq!(repo.get_all_skill_plans().await.unwrap().len(), 1);
+
+        repo.delete_skill_plan(plan_id).await.unwrap();
+        assert!(repo.get_skill_plan(plan_id).await.unwrap().is_none());
+        assert!(repo.get_plan_entries(plan_id).await.unwrap().is_empty());
+    }
+
+    async fn notifications_round_trip(repo: &(impl NotificationRepository + CharacterRepository)) {
+        repo.add_character(1, "Pilot").await.unwrap();
+        let first = repo
+            .create_notification(1, "skill_queue_low", "Queue low", "1h left")
+            .await
+            .unwrap();
+        repo.create_notification(1, "omega_lapsed", "Alpha", "Omega lapsed")
+            .await
+            .unwrap();
+
+        assert!(repo
+            .has_active_notification(1, "skill_queue_low")
+            .await
+            .unwrap());
+        repo.dismiss_notification(first).await.unwrap();
+        assert!(!repo
+            .has_active_notification(1, "skill_queue_low")
+            .await
+            .unwrap());
+
+        assert!(repo.clear_notification(1, "omega_lapsed").await.unwrap());
+        assert!(!repo.clear_notification(1, "omega_lapsed").await.unwrap());
+        assert!(repo
+            .get_notifications(Some(1), Some("active"))
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            repo.get_notifications(None, Some("dismissed"))
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        repo.delete_character(1).await.unwrap();
+        assert!(repo.get_character(1).await.unwrap().is_none());
+        assert!(repo.get_notifications(None, None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn plans_behave_the_same_on_every_backend() {
+        let db = TestDb::new().await.unwrap();
+        plans_round_trip(&db.pool).await;
+        plans_round_trip(&MemoryRepository::default()).await;
+    }
+
+    #[tokio::test]
+    async fn notifications_behave_the_same_on_every_backend() {
+        let db = TestDb::new().await.unwrap();
+        notifications_round_trip(&db.pool).await;
+        notifications_round_trip(&MemoryRepository::default()).await;
+    }
+}
@@ -0,0 +1,172 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use typeshare::typeshare;
+
+use super::app_settings::{get_app_setting, set_app_setting};
+use super::notifications::cleanup_old_dismissed_notifications;
+use super::Pool;
+use crate::ts_types::i64_ts;
+
+const RETENTION_KEY: &str = "retention";
+
+/// How many days of history tables to keep; `None` keeps everything.
+/// Enforced by the maintenance task and at startup.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetentionSettings {
+    pub sp_history_days: Option<i64_ts>,
+    pub queue_journal_days: Option<i64_ts>,
+    /// Only dismissed notifications expire; active ones are always kept.
+    pub dismissed_notification_days: Option<i64_ts>,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self {
+            sp_history_days: Some(365),
+            queue_journal_days: Some(365),
+            dismissed_notification_days: Some(14),
+        }
+    }
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RetentionReport {
+    pub sp_history_deleted: i64_ts,
+    pub queue_journal_deleted: i64_ts,
+    pub notifications_deleted: i64_ts,
+}
+
+impl RetentionReport {
+    pub fn total(&self) -> i64 {
+        self.sp_history_deleted + self.queue_journal_deleted + self.notifications_deleted
+    }
+}
+
+/// Disk footprint of one table, its indexes included.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, FromRow)]
+pub struct TableStorage {
+    pub table_name: String,
+    pub size_bytes: i64_ts,
+}
+
+pub async fn get_retention_settings(pool: &Pool) -> Result<RetentionSettings> {
+    let Some(raw) = get_app_setting(pool, RETENTION_KEY).await? else {
+        return Ok(RetentionSettings::default());
+    };
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+pub async fn set_retention_settings(pool: &Pool, settings: &RetentionSettings) -> Result<()> {
+    let json = serde_json::to_string(settings)?;
+    set_app_setting(pool, RETENTION_KEY, &json).await
+}
+
+/// Deletes rows older than each configured window, measured back from `now`.
+pub async fn apply_retention(
+    pool: &Pool,
+    settings: &RetentionSettings,
+    now: DateTime<Utc>,
+) -> Result<RetentionReport> {
+    let mut report = RetentionReport::default();
+
+    if let Some(days) = settings.sp_history_days {
+        let cutoff = (now - Duration::days(days)).format("%Y-%m-%d").to_string();
+        report.sp_history_deleted = sqlx::query("DELETE FROM character_sp_history WHERE day < ?")
+            .bind(cutoff)
+            .execute(pool)
+            .await?
+            .rows_affected() as i64;
+    }
+
+    if let Some(days) = settings.queue_journal_days {
+        let cutoff = (now - Duration::days(days)).timestamp();
+        report.queue_journal_deleted =
+            sqlx::query("DELETE FROM skill_queue_journal WHERE created_at < ?")
+                .bind(cutoff)
+                .execute(pool)
+                .await?
+                .rows_affected() as i64;
+    }
+
+    if let Some(days) = settings.dismissed_notification_days {
+        report.notifications_deleted =
+            cleanup_old_dismissed_notifications(pool, days).await? as i64;
+    }
+
+    Ok(report)
+}
+
+/// Tables by disk footprint, largest first, from SQLite's `dbstat` table.
+pub async fn get_storage_usage(pool: &Pool) -> Result<Vec<TableStorage>> {
+    let usage = sqlx::query_as::<_, TableStorage>(
+        "SELECT m.tbl_name AS table_name, SUM(s.pgsize) AS size_bytes
+         FROM dbstat s
+         JOIN sqlite_master m ON m.name = s.name
+         GROUP BY m.tbl_name
+         ORDER BY size_bytes DESC, table_name",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use crate::testdata::TestDb;
+    use chrono::TimeZone;
+
+    #[tokio::test]
+    async fn prunes_only_past_the_configured_windows() {
+        let db = TestDb::new().await.unwrap();
+        db::add_character(&db.pool, 1, "Trainee").await.unwrap();
+        let now = Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap();
+
+        for at in [now - Duration::days(400), now - Duration::days(10)] {
+            db::sp_history::record_sp_snapshot(&db.pool, 1, &[(3300, 1000)], 0, at)
+                .await
+                .unwrap();
+            sqlx::query(
+                "INSERT INTO skill_queue_journal (character_id, change_kind, created_at)
+                 VALUES (1, 'reordered', ?)",
+            )
+            .bind(at.timestamp())
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        }
+
+        let report = apply_retention(&db.pool, &RetentionSettings::default(), now)
+            .await
+            .unwrap();
+        assert_eq!(report.sp_history_deleted, 1);
+        assert_eq!(report.queue_journal_deleted, 1);
+
+        let keep_all = RetentionSettings {
+            sp_history_days: None,
+            queue_journal_days: None,
+            dismissed_notification_days: None,
+        };
+        let report = apply_retention(&db.pool, &keep_all, now + Duration::days(400))
+            .await
+            .unwrap();
+        assert_eq!(report.total(), 0);
+    }
+
+    #[tokio::test]
+    async fn storage_usage_lists_tables() {
+        let db = TestDb::new().await.unwrap();
+
+        let usage = get_storage_usage(&db.pool).await.unwrap();
+
+        assert!(usage.iter().any(|t| t.table_name == "characters"));
+        assert!(usage.iter().all(|t| t.size_bytes > 0));
+    }
+}
@@ -1,6 +1,8 @@
+use std::collections::{HashMap, HashSet};
+
 use anyhow::Result;
 use serde::Serialize;
-use sqlx::FromRow;
+use sqlx::{FromRow, QueryBuilder, Sqlite};
 
 use super::Pool;
 
@@ -51,3 +53,127 @@ pub async fn get_skill_group_id(pool: &Pool, type_id: i64) -> Result<Option<i64>
 
     Ok(group_id)
 }
+
+#[derive(Debug, Clone, FromRow)]
+pub struct NamedType {
+    pub type_id: i64,
+    pub name: String,
+    pub category_id: Option<i64>,
+}
+
+/// Highest level an Alpha clone can train each skill to; skills missing from
+/// the map are Omega-only.
+pub async fn get_alpha_skill_caps(pool: &Pool) -> Result<HashMap<i64, i64>> {
+    let rows = sqlx::query_as::<_, (i64, i64)>("SELECT type_id, max_level FROM sde_alpha_skills")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// The skills among `skill_type_ids` flagged `canNotBeTrainedOnTrial`, which
+/// an Alpha clone cannot train at all.
+pub async fn get_omega_only_skills(pool: &Pool, skill_type_ids: &[i64]) -> Result<HashSet<i64>> {
+    let rows = sqlx::query_scalar::<_, i64>(
+        "SELECT type_id FROM sde_type_dogma_attributes
+         WHERE attribute_id = 1047 AND value = 1
+           AND type_id IN (SELECT CAST(value AS INTEGER) FROM json_each(?))",
+    )
+    .bind(serde_json::to_string(skill_type_ids)?)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// Case-insensitive lookup of a published type by its English name, as used
+/// by pasted fits and other text formats.
+pub async fn get_type_by_name(pool: &Pool, name: &str) -> Result<Option<NamedType>> {
+    let row = sqlx::query_as::<_, NamedType>(
+        "SELECT type_id, name, category_id FROM sde_types
+         WHERE name = ? COLLATE NOCASE AND published = 1
+         LIMIT 1",
+    )
+    .bind(name)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row)
+}
+
+pub async fn get_type_effect_ids(pool: &Pool, type_id: i64) -> Result<Vec<i64>> {
+    let effects = sqlx::query_scalar::<_, i64>(
+        "SELECT effect_id FROM sde_type_dogma_effects WHERE type_id = ?",
+    )
+    .bind(type_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(effects)
+}
+
+/// Every skill (and level) needed to use all of `type_ids`, including the
+/// prerequisites of those skills, keyed by skill id with the highest level any
+/// of them asks for.
+pub async fn get_required_skills_for_types(
+    pool: &Pool,
+    type_ids: &[i64],
+) -> Result<HashMap<i64, i64>> {
+    if type_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let mut builder = QueryBuilder::<Sqlite>::new(
+        "WITH RECURSIVE req(skill_id, level) AS (
+            SELECT required_skill_id, required_level FROM sde_skill_requirements
+            WHERE skill_type_id IN (",
+    );
+    let mut separated = builder.separated(", ");
+    for type_id in type_ids {
+        separated.push_bind(type_id);
+    }
+    separated.push_unseparated(
+        ")
+            UNION
+            SELECT r.required_skill_id, r.required_level
+            FROM sde_skill_requirements r
+            JOIN req ON r.skill_type_id = req.skill_id
+        )
+        SELECT skill_id, MAX(level) FROM req GROUP BY skill_id",
+    );
+
+    let rows = builder
+        .build_query_as::<(i64, i64)>()
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// Skills `type_ids` require directly (without their prerequisites), keyed by
+/// skill id with the highest level any of them asks for.
+pub async fn get_direct_required_skills_for_types(
+    pool: &Pool,
+    type_ids: &[i64],
+) -> Result<HashMap<i64, i64>> {
+    if type_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let mut builder = QueryBuilder::<Sqlite>::new(
+        "SELECT required_skill_id, MAX(required_level) FROM sde_skill_requirements
+         WHERE skill_type_id IN (",
+    );
+    let mut separated = builder.separated(", ");
+    for type_id in type_ids {
+        separated.push_bind(type_id);
+    }
+    separated.push_unseparated(") GROUP BY required_skill_id");
+
+    let rows = builder
+        .build_query_as::<(i64, i64)>()
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.into_iter().collect())
+}
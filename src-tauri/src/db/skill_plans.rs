@@ -1,12 +1,19 @@
-use anyhow::Result;
-use serde::Serialize;
-use sqlx::FromRow;
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqliteConnection};
 
 use super::Pool;
+use crate::skill_plans::Attributes;
 
 pub const ENTRY_TYPE_PLANNED: &str = "Planned";
 pub const ENTRY_TYPE_PREREQUISITE: &str = "Prerequisite";
 
+/// EVEMon's scale: 1 is the highest priority, 5 the lowest.
+pub const ENTRY_PRIORITIES: std::ops::RangeInclusive<i64> = 1..=5;
+pub const DEFAULT_ENTRY_PRIORITY: i64 = 3;
+
 #[derive(Debug, Clone, Serialize, FromRow)]
 pub struct SkillPlan {
     pub plan_id: i64,
@@ -17,6 +24,7 @@ pub struct SkillPlan {
     pub updated_at: i64,
     pub group_id: Option<i64>,
     pub sort_order: i64,
+    pub owner_character_id: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, FromRow)]
@@ -28,6 +36,10 @@ pub struct SkillPlanEntry {
     pub sort_order: i64,
     pub entry_type: String,
     pub notes: Option<String>,
+    /// Left out of training time, comparisons and optimisation.
+    pub excluded: bool,
+    /// See [`ENTRY_PRIORITIES`].
+    pub priority: i64,
 }
 
 pub async fn create_skill_plan(
@@ -36,6 +48,19 @@ pub async fn create_skill_plan(
     description: Option<&str>,
     auto_prerequisites: bool,
     group_id: Option<i64>,
+) -> Result<i64> {
+    let mut conn = pool.acquire().await?;
+    create_skill_plan_in(&mut conn, name, description, auto_prerequisites, group_id).await
+}
+
+/// [`create_skill_plan`] on a caller-supplied connection, so a plan can be
+/// created inside a larger transaction.
+pub async fn create_skill_plan_in(
+    conn: &mut SqliteConnection,
+    name: &str,
+    description: Option<&str>,
+    auto_prerequisites: bool,
+    group_id: Option<i64>,
 ) -> Result<i64> {
     let now = chrono::Utc::now().timestamp();
 
@@ -53,7 +78,7 @@ pub async fn create_skill_plan(
             )
             .bind(gid)
             .bind(gid)
-            .fetch_one(pool)
+            .fetch_one(&mut *conn)
             .await?
         }
         None => {
@@ -64,7 +89,7 @@ pub async fn create_skill_plan(
                      SELECT sort_order AS so FROM plan_groups WHERE parent_group_id IS NULL
                  )",
             )
-            .fetch_one(pool)
+            .fetch_one(&mut *conn)
             .await?
         }
     };
@@ -79,7 +104,7 @@ pub async fn create_skill_plan(
     .bind(now)
     .bind(group_id)
     .bind(sort_order)
-    .execute(pool)
+    .execute(&mut *conn)
     .await?;
 
     let plan_id = result.last_insert_rowid();
@@ -89,7 +114,8 @@ pub async fn create_skill_plan(
 
 pub async fn get_all_skill_plans(pool: &Pool) -> Result<Vec<SkillPlan>> {
     let plans = sqlx::query_as::<_, SkillPlan>(
-        "SELECT plan_id, name, description, auto_prerequisites, created_at, updated_at, group_id, sort_order
+        "SELECT plan_id, name, description, auto_prerequisites, created_at, updated_at, group_id, sort_order,
+                owner_character_id
          FROM skill_plans
          ORDER BY COALESCE(group_id, -1), sort_order, plan_id",
     )
@@ -104,7 +130,8 @@ where
     E: sqlx::Executor<'a, Database = sqlx::Sqlite>,
 {
     let plan = sqlx::query_as::<_, SkillPlan>(
-        "SELECT plan_id, name, description, auto_prerequisites, created_at, updated_at, group_id, sort_order
+        "SELECT plan_id, name, description, auto_prerequisites, created_at, updated_at, group_id, sort_order,
+                owner_character_id
          FROM skill_plans WHERE plan_id = ?",
     )
     .bind(plan_id)
@@ -136,6 +163,25 @@ pub async fn update_skill_plan(
     Ok(())
 }
 
+/// `None` clears the owner.
+pub async fn set_plan_owner<'a, E>(
+    executor: E,
+    plan_id: i64,
+    character_id: Option<i64>,
+) -> Result<()>
+where
+    E: sqlx::Executor<'a, Database = sqlx::Sqlite>,
+{
+    sqlx::query("UPDATE skill_plans SET owner_character_id = ?, updated_at = ? WHERE plan_id = ?")
+        .bind(character_id)
+        .bind(chrono::Utc::now().timestamp())
+        .bind(plan_id)
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
+
 pub async fn delete_skill_plan(pool: &Pool, plan_id: i64) -> Result<()> {
     sqlx::query("DELETE FROM skill_plans WHERE plan_id = ?")
         .bind(plan_id)
@@ -150,7 +196,8 @@ where
     E: sqlx::Executor<'a, Database = sqlx::Sqlite>,
 {
     let entries = sqlx::query_as::<_, SkillPlanEntry>(
-        "SELECT entry_id, plan_id, skill_type_id, planned_level, sort_order, entry_type, notes
+        "SELECT entry_id, plan_id, skill_type_id, planned_level, sort_order, entry_type, notes,
+                excluded, priority
          FROM skill_plan_entries
          WHERE plan_id = ?
          ORDER BY sort_order",
@@ -162,14 +209,82 @@ where
     Ok(entries)
 }
 
+/// Optional filters for [`get_plan_entries_page`]. `status` is one of
+/// `complete`, `in_progress` or `not_started`, judged against `character_id`.
+#[derive(Debug, Clone, Default)]
+pub struct PlanEntryFilter {
+    pub entry_type: Option<String>,
+    pub status: Option<String>,
+    pub character_id: Option<i64>,
+}
+
+/// One page of a plan's entries in plan order, with the number of entries that
+/// match `filter` across all pages.
+pub async fn get_plan_entries_page(
+    pool: &Pool,
+    plan_id: i64,
+    offset: i64,
+    limit: i64,
+    filter: &PlanEntryFilter,
+) -> Result<(Vec<SkillPlanEntry>, i64)> {
+    const FILTERED: &str = "FROM skill_plan_entries e
+         LEFT JOIN character_skills cs
+           ON cs.skill_id = e.skill_type_id AND cs.character_id = ?4
+         WHERE e.plan_id = ?1
+           AND (?2 IS NULL OR e.entry_type = ?2)
+           AND (?3 IS NULL OR ?3 = CASE
+                 WHEN COALESCE(cs.trained_skill_level, 0) >= e.planned_level THEN 'complete'
+                 WHEN COALESCE(cs.trained_skill_level, 0) > 0 THEN 'in_progress'
+                 ELSE 'not_started'
+               END)";
+
+    let count_sql = format!("SELECT COUNT(*) {}", FILTERED);
+    let total: i64 = sqlx::query_scalar(sqlx::AssertSqlSafe(count_sql.as_str()))
+        .bind(plan_id)
+        .bind(&filter.entry_type)
+        .bind(&filter.status)
+        .bind(filter.character_id)
+        .fetch_one(pool)
+        .await?;
+
+    let entries_sql = format!(
+        "SELECT e.entry_id, e.plan_id, e.skill_type_id, e.planned_level, e.sort_order,
+                e.entry_type, e.notes, e.excluded, e.priority
+         {}
+         ORDER BY e.sort_order
+         LIMIT ?5 OFFSET ?6",
+        FILTERED
+    );
+    let entries = sqlx::query_as::<_, SkillPlanEntry>(sqlx::AssertSqlSafe(entries_sql.as_str()))
+        .bind(plan_id)
+        .bind(&filter.entry_type)
+        .bind(&filter.status)
+        .bind(filter.character_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+    Ok((entries, total))
+}
+
 /// A single entry to write when replacing a plan's entries wholesale.
 /// `sort_order` is derived from the position in the supplied slice, so it is
 /// not carried here.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ReplacePlanEntry {
     pub skill_type_id: i64,
     pub planned_level: i64,
     pub entry_type: String,
     pub notes: Option<String>,
+    pub excluded: bool,
+    /// Defaulted for revisions recorded before priorities existed.
+    #[serde(default = "default_entry_priority")]
+    pub priority: i64,
+}
+
+fn default_entry_priority() -> i64 {
+    DEFAULT_ENTRY_PRIORITY
 }
 
 /// Replace a plan's entries with an exact supplied snapshot: clear every
@@ -191,8 +306,8 @@ pub async fn replace_plan_entries(
 
     for (index, entry) in entries.iter().enumerate() {
         sqlx::query(
-            "INSERT INTO skill_plan_entries (plan_id, skill_type_id, planned_level, sort_order, entry_type, notes)
-             VALUES (?, ?, ?, ?, ?, ?)",
+            "INSERT INTO skill_plan_entries (plan_id, skill_type_id, planned_level, sort_order, entry_type, notes, excluded, priority)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
         )
         .bind(plan_id)
         .bind(entry.skill_type_id)
@@ -200,6 +315,8 @@ pub async fn replace_plan_entries(
         .bind(index as i64)
         .bind(&entry.entry_type)
         .bind(&entry.notes)
+        .bind(entry.excluded)
+        .bind(entry.priority)
         .execute(&mut *tx)
         .await?;
     }
@@ -215,6 +332,59 @@ pub async fn replace_plan_entries(
     Ok(())
 }
 
+/// Copy a plan and its entries into a new plan named `name`, filed at the end
+/// of the source plan's folder. Levels at or below the level in `trained`
+/// (skill id to trained level) are left out, so a template can be stamped out
+/// for a character without the skills it already has; pass an empty map for a
+/// plain duplicate. The copy keeps the source's owner.
+pub async fn copy_skill_plan(
+    pool: &Pool,
+    plan_id: i64,
+    name: &str,
+    trained: &HashMap<i64, i64>,
+) -> Result<i64> {
+    let mut tx = pool.begin().await?;
+
+    let plan = get_skill_plan(&mut *tx, plan_id)
+        .await?
+        .ok_or_else(|| anyhow!("Plan {} not found", plan_id))?;
+    let entries = get_plan_entries(&mut *tx, plan_id).await?;
+
+    let new_plan_id = create_skill_plan_in(
+        &mut tx,
+        name,
+        plan.description.as_deref(),
+        plan.auto_prerequisites != 0,
+        plan.group_id,
+    )
+    .await?;
+
+    let remaining = entries
+        .iter()
+        .filter(|e| e.planned_level > trained.get(&e.skill_type_id).copied().unwrap_or(0));
+    for (index, entry) in remaining.enumerate() {
+        sqlx::query(
+            "INSERT INTO skill_plan_entries (plan_id, skill_type_id, planned_level, sort_order, entry_type, notes, excluded, priority)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(new_plan_id)
+        .bind(entry.skill_type_id)
+        .bind(entry.planned_level)
+        .bind(index as i64)
+        .bind(&entry.entry_type)
+        .bind(&entry.notes)
+        .bind(entry.excluded)
+        .bind(entry.priority)
+        .execute(&mut *tx)
+        .await?;
+    }
+    set_plan_owner(&mut *tx, new_plan_id, plan.owner_character_id).await?;
+
+    tx.commit().await?;
+
+    Ok(new_plan_id)
+}
+
 pub async fn get_plan_nodes_in_order(pool: &Pool, plan_id: i64) -> Result<Vec<(i64, i64)>> {
     let nodes = sqlx::query_as::<_, (i64, i64)>(
         "SELECT skill_type_id, planned_level
@@ -229,12 +399,34 @@ pub async fn get_plan_nodes_in_order(pool: &Pool, plan_id: i64) -> Result<Vec<(i
     Ok(nodes)
 }
 
+/// Sets `priority` on each of `entry_ids` that belongs to the plan; returns
+/// how many were changed.
+pub async fn set_entries_priority(
+    pool: &Pool,
+    plan_id: i64,
+    entry_ids: &[i64],
+    priority: i64,
+) -> Result<u64> {
+    let result = sqlx::query(
+        "UPDATE skill_plan_entries SET priority = ?
+         WHERE plan_id = ? AND entry_id IN (SELECT value FROM json_each(?))",
+    )
+    .bind(priority)
+    .bind(plan_id)
+    .bind(serde_json::to_string(entry_ids)?)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
 pub async fn update_plan_entry(
     pool: &Pool,
     entry_id: i64,
     planned_level: Option<i64>,
     entry_type: Option<&str>,
     notes: Option<&str>,
+    excluded: Option<bool>,
 ) -> Result<()> {
     let mut updates = Vec::new();
 
@@ -250,6 +442,10 @@ pub async fn update_plan_entry(
         updates.push("notes = ?");
     }
 
+    if excluded.is_some() {
+        updates.push("excluded = ?");
+    }
+
     if updates.is_empty() {
         return Ok(());
     }
@@ -273,6 +469,10 @@ pub async fn update_plan_entry(
         query_builder = query_builder.bind(notes_val);
     }
 
+    if let Some(excluded_val) = excluded {
+        query_builder = query_builder.bind(excluded_val);
+    }
+
     query_builder = query_builder.bind(entry_id);
 
     query_builder.execute(pool).await?;
@@ -291,7 +491,18 @@ pub async fn delete_plan_entry(pool: &Pool, entry_id: i64) -> Result<()> {
 
 pub async fn reorder_plan_entries(pool: &Pool, plan_id: i64, entry_ids: &[i64]) -> Result<()> {
     let mut tx = pool.begin().await?;
+    reorder_plan_entries_in(&mut tx, plan_id, entry_ids).await?;
+    tx.commit().await?;
+
+    Ok(())
+}
 
+/// [`reorder_plan_entries`] on a caller-supplied connection.
+pub async fn reorder_plan_entries_in(
+    conn: &mut SqliteConnection,
+    plan_id: i64,
+    entry_ids: &[i64],
+) -> Result<()> {
     for (index, entry_id) in entry_ids.iter().enumerate() {
         sqlx::query(
             "UPDATE skill_plan_entries SET sort_order = ? WHERE entry_id = ? AND plan_id = ?",
@@ -299,10 +510,24 @@ pub async fn reorder_plan_entries(pool: &Pool, plan_id: i64, entry_ids: &[i64])
         .bind(index as i64)
         .bind(entry_id)
         .bind(plan_id)
-        .execute(&mut *tx)
+        .execute(&mut *conn)
         .await?;
     }
 
+    Ok(())
+}
+
+/// Persist a reordering optimization: the new entry order and the plan's
+/// remaps (see [`super::remaps::replace_plan_remaps`]) in one transaction.
+pub async fn apply_plan_optimization(
+    pool: &Pool,
+    plan_id: i64,
+    entry_ids: &[i64],
+    remaps: &[(Option<i64>, Option<i64>, Attributes)],
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    reorder_plan_entries_in(&mut tx, plan_id, entry_ids).await?;
+    super::remaps::replace_plan_remaps_in(&mut tx, plan_id, remaps).await?;
     tx.commit().await?;
 
     Ok(())
@@ -319,7 +544,43 @@ pub async fn get_skill_type_id_by_name(pool: &Pool, skill_name: &str) -> Result<
     Ok(type_id)
 }
 
+/// FTS5 query for a user search: every word must appear, the last one as a
+/// prefix so results narrow while typing. `None` when nothing searchable is
+/// left.
+pub fn fts_prefix_query(query: &str) -> Option<String> {
+    let words: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| format!("\"{}\"", w))
+        .collect();
+    if words.is_empty() {
+        return None;
+    }
+    Some(format!("{}*", words.join(" ")))
+}
+
+/// Skills matching `query`, best first: name hits rank above description
+/// hits. Falls back to a substring match when the index finds nothing, so
+/// mid-word fragments still turn something up.
 pub async fn search_skills(pool: &Pool, query: &str) -> Result<Vec<(i64, String)>> {
+    if let Some(fts_query) = fts_prefix_query(query) {
+        let skills = sqlx::query_as::<_, (i64, String)>(
+            "SELECT t.type_id, t.name FROM sde_types_fts f
+             JOIN sde_types t ON t.type_id = f.rowid
+             WHERE sde_types_fts MATCH ?
+             AND t.group_id IN (SELECT group_id FROM sde_groups WHERE category_id = 16)
+             AND t.published = 1
+             ORDER BY bm25(sde_types_fts, 10.0, 1.0), t.name
+             LIMIT 100",
+        )
+        .bind(&fts_query)
+        .fetch_all(pool)
+        .await?;
+        if !skills.is_empty() {
+            return Ok(skills);
+        }
+    }
+
     let search_pattern = format!("%{}%", query);
     let skills = sqlx::query_as::<_, (i64, String)>(
         "SELECT type_id, name FROM sde_types
@@ -336,6 +597,68 @@ pub async fn search_skills(pool: &Pool, query: &str) -> Result<Vec<(i64, String)
     Ok(skills)
 }
 
+/// One place a plan search matched: `field` is `name`, `description`,
+/// `notes` or `skill`, and `text` is the matched value (for skills, the
+/// skill name with its planned level).
+#[derive(Debug, Clone, FromRow)]
+pub struct PlanSearchRow {
+    pub plan_id: i64,
+    pub plan_name: String,
+    pub field: String,
+    pub text: String,
+}
+
+/// Splits a trailing level off a search ("Marauders V", "Marauders 5").
+fn split_level(query: &str) -> (&str, Option<i64>) {
+    if let Some((name, level)) = query.rsplit_once(' ') {
+        let level = crate::utils::parse_level(level).filter(|l| (1..=5).contains(l));
+        if level.is_some() && !name.trim().is_empty() {
+            return (name.trim(), level);
+        }
+    }
+    (query, None)
+}
+
+/// Plans whose name, description or entry notes contain `query`, or that
+/// contain a skill whose name does. A trailing level in the query only
+/// matches skills planned to exactly that level.
+pub async fn search_plans(pool: &Pool, query: &str) -> Result<Vec<PlanSearchRow>> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pattern = format!("%{}%", query);
+    let (skill_query, level) = split_level(query);
+    let skill_pattern = format!("%{}%", skill_query);
+
+    let rows = sqlx::query_as::<_, PlanSearchRow>(
+        "SELECT plan_id, name AS plan_name, 'name' AS field, name AS text
+         FROM skill_plans WHERE name LIKE ?1
+         UNION ALL
+         SELECT plan_id, name, 'description', description
+         FROM skill_plans WHERE description LIKE ?1
+         UNION ALL
+         SELECT p.plan_id, p.name, 'notes', e.notes
+         FROM skill_plan_entries e
+         JOIN skill_plans p ON p.plan_id = e.plan_id
+         WHERE e.notes LIKE ?1
+         UNION ALL
+         SELECT p.plan_id, p.name, 'skill', t.name || ' ' || e.planned_level
+         FROM skill_plan_entries e
+         JOIN skill_plans p ON p.plan_id = e.plan_id
+         JOIN sde_types t ON t.type_id = e.skill_type_id
+         WHERE t.name LIKE ?2 AND (?3 IS NULL OR e.planned_level = ?3)
+         ORDER BY plan_name, plan_id",
+    )
+    .bind(&pattern)
+    .bind(&skill_pattern)
+    .bind(level)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
 pub async fn get_entry_type<'a, E>(
     executor: E,
     plan_id: i64,
@@ -391,3 +714,152 @@ pub async fn get_entry_details_by_id(
 
     Ok(details)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::{fixtures, TestDb};
+
+    #[test]
+    fn fts_query_prefixes_the_last_word() {
+        assert_eq!(
+            fts_prefix_query("small proj").as_deref(),
+            Some("\"small\" \"proj\"*")
+        );
+        assert_eq!(
+            fts_prefix_query("Caldari \"Frigate\"").as_deref(),
+            Some("\"Caldari\" \"Frigate\"*")
+        );
+        assert_eq!(fts_prefix_query(" - ").as_deref(), None);
+    }
+
+    #[tokio::test]
+    async fn search_skills_ranks_name_matches_and_falls_back_to_substrings() {
+        let db = TestDb::new_with_sde().await.unwrap();
+
+        let skills = search_skills(&db.pool, "gunn").await.unwrap();
+        assert_eq!(skills.first().map(|s| s.1.as_str()), Some("Gunnery"));
+
+        // "unnery" is not a word prefix, so only the LIKE fallback finds it.
+        let skills = search_skills(&db.pool, "unnery").await.unwrap();
+        assert!(skills.iter().any(|s| s.1 == "Gunnery"));
+    }
+
+    #[tokio::test]
+    async fn copy_skill_plan_drops_levels_already_trained() {
+        let db = TestDb::new().await.unwrap();
+        let plan_id = fixtures::create_skill_plan(&db.pool, "Source").await;
+        for level in 1..=3 {
+            fixtures::add_plan_entry(&db.pool, plan_id, 100, level, ENTRY_TYPE_PLANNED).await;
+        }
+        fixtures::add_plan_entry(&db.pool, plan_id, 200, 1, ENTRY_TYPE_PREREQUISITE).await;
+
+        let copy_id = copy_skill_plan(&db.pool, plan_id, "Copy", &HashMap::new())
+            .await
+            .unwrap();
+        let copy = get_skill_plan(&db.pool, copy_id).await.unwrap().unwrap();
+        assert_eq!(copy.name, "Copy");
+        assert_eq!(get_plan_entries(&db.pool, copy_id).await.unwrap().len(), 4);
+
+        let trained = HashMap::from([(100, 2), (200, 1)]);
+        let template_id = copy_skill_plan(&db.pool, plan_id, "Template", &trained)
+            .await
+            .unwrap();
+        let entries = get_plan_entries(&db.pool, template_id).await.unwrap();
+        let levels: Vec<(i64, i64, i64)> = entries
+            .iter()
+            .map(|e| (e.skill_type_id, e.planned_level, e.sort_order))
+            .collect();
+        assert_eq!(levels, vec![(100, 3, 0)]);
+    }
+
+    #[tokio::test]
+    async fn plan_entries_page_filters_and_counts_across_pages() {
+        let db = TestDb::new().await.unwrap();
+        let plan_id = fixtures::create_skill_plan(&db.pool, "Long").await;
+        for level in 1..=5 {
+            fixtures::add_plan_entry(&db.pool, plan_id, 100, level, ENTRY_TYPE_PLANNED).await;
+        }
+        fixtures::add_plan_entry(&db.pool, plan_id, 200, 1, ENTRY_TYPE_PREREQUISITE).await;
+        crate::db::add_character(&db.pool, 1, "Pilot")
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO character_skills
+             (character_id, skill_id, active_skill_level, skillpoints_in_skill, trained_skill_level)
+             VALUES (1, 100, 2, 1415, 2)",
+        )
+        .execute(&db.pool)
+        .await
+        .unwrap();
+
+        let (page, total) =
+            get_plan_entries_page(&db.pool, plan_id, 2, 2, &PlanEntryFilter::default())
+                .await
+                .unwrap();
+        assert_eq!(total, 6);
+        let levels: Vec<i64> = page.iter().map(|e| e.planned_level).collect();
+        assert_eq!(levels, vec![3, 4]);
+
+        let prerequisites = PlanEntryFilter {
+            entry_type: Some(ENTRY_TYPE_PREREQUISITE.to_string()),
+            ..PlanEntryFilter::default()
+        };
+        let (page, total) = get_plan_entries_page(&db.pool, plan_id, 0, 10, &prerequisites)
+            .await
+            .unwrap();
+        assert_eq!((page.len(), total), (1, 1));
+
+        let open = PlanEntryFilter {
+            status: Some("in_progress".to_string()),
+            character_id: Some(1),
+            ..PlanEntryFilter::default()
+        };
+        let (page, total) = get_plan_entries_page(&db.pool, plan_id, 0, 1, &open)
+            .await
+            .unwrap();
+        assert_eq!(total, 3);
+        assert_eq!(page[0].planned_level, 3);
+    }
+
+    #[tokio::test]
+    async fn apply_plan_optimization_reorders_and_replaces_remaps() {
+        let db = TestDb::new().await.unwrap();
+        let plan_id = fixtures::create_skill_plan(&db.pool, "Plan").await;
+        let first = fixtures::add_plan_entry(&db.pool, plan_id, 100, 1, "Planned").await;
+        let second = fixtures::add_plan_entry(&db.pool, plan_id, 200, 1, "Planned").await;
+        let attributes = |intelligence| Attributes {
+            intelligence,
+            perception: 17,
+            charisma: 17,
+            willpower: 17,
+            memory: 17,
+        };
+        crate::db::remaps::save_remap(&db.pool, None, Some(plan_id), None, None, &attributes(20))
+            .await
+            .unwrap();
+
+        apply_plan_optimization(
+            &db.pool,
+            plan_id,
+            &[second, first],
+            &[(Some(200), Some(1), attributes(27))],
+        )
+        .await
+        .unwrap();
+
+        let order: Vec<i64> = get_plan_entries(&db.pool, plan_id)
+            .await
+            .unwrap()
+            .iter()
+            .map(|e| e.entry_id)
+            .collect();
+        assert_eq!(order, vec![second, first]);
+        let remaps = crate::db::remaps::get_plan_remaps(&db.pool, plan_id)
+            .await
+            .unwrap();
+        assert_eq!(remaps.len(), 1);
+        assert_eq!(remaps[0].after_skill_type_id, Some(200));
+        assert_eq!(remaps[0].intelligence, 27);
+    }
+}
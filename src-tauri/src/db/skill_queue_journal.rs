@@ -0,0 +1,184 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use typeshare::typeshare;
+
+use super::Pool;
+use crate::ts_types::i64_ts;
+
+/// One queue entry as remembered between refreshes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueueSnapshotEntry {
+    pub skill_id: i64,
+    pub level: i64,
+    /// RFC 3339; `None` while the queue is paused.
+    pub finish_date: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueChangeKind {
+    Added,
+    Removed,
+    Reordered,
+}
+
+impl QueueChangeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QueueChangeKind::Added => "added",
+            QueueChangeKind::Removed => "removed",
+            QueueChangeKind::Reordered => "reordered",
+        }
+    }
+}
+
+/// A change to write to the journal; `skill_id`/`level` are `None` for a
+/// reorder, which is recorded once per refresh.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueueChange {
+    pub kind: QueueChangeKind,
+    pub skill_id: Option<i64>,
+    pub level: Option<i64>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct QueueJournalEntry {
+    pub id: i64_ts,
+    pub character_id: i64_ts,
+    /// `added`, `removed` or `reordered`.
+    pub change_kind: String,
+    pub skill_id: Option<i64_ts>,
+    pub skill_name: Option<String>,
+    pub level: Option<i64_ts>,
+    /// Unix seconds.
+    pub created_at: i64_ts,
+}
+
+pub async fn get_queue_snapshot(
+    pool: &Pool,
+    character_id: i64,
+) -> Result<Option<Vec<QueueSnapshotEntry>>> {
+    let raw = sqlx::query_scalar::<_, String>(
+        "SELECT entries FROM skill_queue_snapshots WHERE character_id = ?",
+    )
+    .bind(character_id)
+    .fetch_optional(pool)
+    .await?;
+
+    // An unreadable snapshot is treated as missing; the next refresh rewrites it.
+    Ok(raw.and_then(|raw| serde_json::from_str(&raw).ok()))
+}
+
+/// Replaces the character's snapshot and journals `changes`, atomically.
+pub async fn record_queue_snapshot(
+    pool: &Pool,
+    character_id: i64,
+    entries: &[QueueSnapshotEntry],
+    changes: &[QueueChange],
+    now: i64,
+) -> Result<()> {
+    let json = serde_json::to_string(entries)?;
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        "INSERT INTO skill_queue_snapshots (character_id, entries, captured_at)
+         VALUES (?, ?, ?)
+         ON CONFLICT(character_id) DO UPDATE SET
+             entries = excluded.entries,
+             captured_at = excluded.captured_at",
+    )
+    .bind(character_id)
+    .bind(&json)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    for change in changes {
+        sqlx::query(
+            "INSERT INTO skill_queue_journal (character_id, change_kind, skill_id, level, created_at)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(character_id)
+        .bind(change.kind.as_str())
+        .bind(change.skill_id)
+        .bind(change.level)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Most recent journal rows first.
+pub async fn get_queue_journal(
+    pool: &Pool,
+    character_id: i64,
+    limit: i64,
+) -> Result<Vec<QueueJournalEntry>> {
+    let rows = sqlx::query_as::<_, QueueJournalEntry>(
+        "SELECT j.id, j.character_id, j.change_kind, j.skill_id, t.name AS skill_name,
+                j.level, j.created_at
+         FROM skill_queue_journal j
+         LEFT JOIN sde_types t ON t.type_id = j.skill_id
+         WHERE j.character_id = ?
+         ORDER BY j.created_at DESC, j.id DESC
+         LIMIT ?",
+    )
+    .bind(character_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    #[tokio::test]
+    async fn snapshot_and_journal_round_trip() {
+        let db = TestDb::new().await.unwrap();
+        crate::db::add_character(&db.pool, 1, "Queue")
+            .await
+            .unwrap();
+        assert_eq!(get_queue_snapshot(&db.pool, 1).await.unwrap(), None);
+
+        let entries = vec![QueueSnapshotEntry {
+            skill_id: 3300,
+            level: 4,
+            finish_date: None,
+        }];
+        let changes = vec![
+            QueueChange {
+                kind: QueueChangeKind::Added,
+                skill_id: Some(3300),
+                level: Some(4),
+            },
+            QueueChange {
+                kind: QueueChangeKind::Reordered,
+                skill_id: None,
+                level: None,
+            },
+        ];
+        record_queue_snapshot(&db.pool, 1, &entries, &changes, 100)
+            .await
+            .unwrap();
+        record_queue_snapshot(&db.pool, 1, &entries, &[], 200)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            get_queue_snapshot(&db.pool, 1).await.unwrap(),
+            Some(entries)
+        );
+        let journal = get_queue_journal(&db.pool, 1, 10).await.unwrap();
+        assert_eq!(journal.len(), 2);
+        assert_eq!(journal[0].change_kind, "reordered");
+        assert_eq!(journal[1].skill_id, Some(3300));
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use sqlx::{sqlite::SqliteRow, FromRow, Row};
+use typeshare::typeshare;
+
+use super::Pool;
+use crate::ts_types::i64_ts;
+
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SkillSp {
+    pub skill_id: i64_ts,
+    pub skillpoints: i64_ts,
+}
+
+/// A character's SP as of the last refresh on `day`.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpHistoryPoint {
+    /// `YYYY-MM-DD`, UTC.
+    pub day: String,
+    /// Allocated SP plus unallocated SP.
+    pub total_sp: i64_ts,
+    pub unallocated_sp: i64_ts,
+    pub skills: Vec<SkillSp>,
+    /// Unix seconds.
+    pub recorded_at: i64_ts,
+}
+
+impl<'r> FromRow<'r, SqliteRow> for SpHistoryPoint {
+    fn from_row(row: &'r SqliteRow) -> Result<Self, sqlx::Error> {
+        let raw: String = row.get("skills");
+        let skills: BTreeMap<i64, i64> = serde_json::from_str(&raw).unwrap_or_default();
+        Ok(SpHistoryPoint {
+            day: row.get("day"),
+            total_sp: row.get("total_sp"),
+            unallocated_sp: row.get("unallocated_sp"),
+            skills: skills
+                .into_iter()
+                .map(|(skill_id, skillpoints)| SkillSp {
+                    skill_id,
+                    skillpoints,
+                })
+                .collect(),
+            recorded_at: row.get("recorded_at"),
+        })
+    }
+}
+
+fn day_of(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d").to_string()
+}
+
+/// Stores the character's SP for `now`'s day, replacing an earlier snapshot
+/// from the same day. `skills` is (skill id, skillpoints in skill).
+pub async fn record_sp_snapshot(
+    pool: &Pool,
+    character_id: i64,
+    skills: &[(i64, i64)],
+    unallocated_sp: i64,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    let by_skill: BTreeMap<i64, i64> = skills.iter().copied().collect();
+    let total_sp = by_skill.values().sum::<i64>() + unallocated_sp;
+
+    sqlx::query(
+        "INSERT INTO character_sp_history (character_id, day, total_sp, unallocated_sp, skills, recorded_at)
+         VALUES (?, ?, ?, ?, ?, ?)
+         ON CONFLICT(character_id, day) DO UPDATE SET
+             total_sp = excluded.total_sp,
+             unallocated_sp = excluded.unallocated_sp,
+             skills = excluded.skills,
+             recorded_at = excluded.recorded_at",
+    )
+    .bind(character_id)
+    .bind(day_of(now))
+    .bind(total_sp)
+    .bind(unallocated_sp)
+    .bind(serde_json::to_string(&by_skill)?)
+    .bind(now.timestamp())
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Daily snapshots from the last `days` days, oldest first.
+pub async fn get_sp_history(
+    pool: &Pool,
+    character_id: i64,
+    days: i64,
+    now: DateTime<Utc>,
+) -> Result<Vec<SpHistoryPoint>> {
+    let since = day_of(now - Duration::days(days.max(1) - 1));
+    let rows = sqlx::query_as::<_, SpHistoryPoint>(
+        "SELECT day, total_sp, unallocated_sp, skills, recorded_at
+         FROM character_sp_history
+         WHERE character_id = ? AND day >= ?
+         ORDER BY day",
+    )
+    .bind(character_id)
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+    use chrono::TimeZone;
+
+    #[tokio::test]
+    async fn one_snapshot_per_day_within_the_window() {
+        let db = TestDb::new().await.unwrap();
+        crate::db::add_character(&db.pool, 1, "Trainee")
+            .await
+            .unwrap();
+        let day_one = Utc.with_ymd_and_hms(2026, 3, 1, 8, 0, 0).unwrap();
+
+        record_sp_snapshot(&db.pool, 1, &[(3300, 1000)], 0, day_one)
+            .await
+            .unwrap();
+        // A later refresh on the same day replaces the first.
+        record_sp_snapshot(
+            &db.pool,
+            1,
+            &[(3300, 1500)],
+            0,
+            day_one + Duration::hours(6),
+        )
+        .await
+        .unwrap();
+        record_sp_snapshot(
+            &db.pool,
+            1,
+            &[(3300, 4000), (3301, 250)],
+            500,
+            day_one + Duration::days(1),
+        )
+        .await
+        .unwrap();
+
+        let now = day_one + Duration::days(1);
+        let history = get_sp_history(&db.pool, 1, 30, now).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].day, "2026-03-01");
+        assert_eq!(history[0].total_sp, 1500);
+        assert_eq!(history[1].total_sp, 4750);
+        assert_eq!(
+            history[1].skills,
+            vec![
+                SkillSp {
+                    skill_id: 3300,
+                    skillpoints: 4000
+                },
+                SkillSp {
+                    skill_id: 3301,
+                    skillpoints: 250
+                },
+            ]
+        );
+
+        let today_only = get_sp_history(&db.pool, 1, 1, now).await.unwrap();
+        assert_eq!(today_only.len(), 1);
+        assert_eq!(today_only[0].day, "2026-03-02");
+    }
+}
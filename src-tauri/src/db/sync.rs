@@ -0,0 +1,59 @@
+use anyhow::Result;
+use sqlx::FromRow;
+
+use super::Pool;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct SyncStateRow {
+    pub entity_key: String,
+    pub content_hash: Option<String>,
+    pub modified_at: i64,
+    pub deleted: bool,
+}
+
+/// Give every plan and plan group a random sync id if it does not have one yet.
+pub async fn ensure_sync_ids(pool: &Pool) -> Result<()> {
+    sqlx::query(
+        "UPDATE skill_plans SET sync_id = lower(hex(randomblob(16))) WHERE sync_id IS NULL",
+    )
+    .execute(pool)
+    .await?;
+    sqlx::query(
+        "UPDATE plan_groups SET sync_id = lower(hex(randomblob(16))) WHERE sync_id IS NULL",
+    )
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+pub async fn get_sync_state(pool: &Pool) -> Result<Vec<SyncStateRow>> {
+    let rows = sqlx::query_as::<_, SyncStateRow>(
+        "SELECT entity_key, content_hash, modified_at, deleted FROM sync_state",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn replace_sync_state(pool: &Pool, rows: &[SyncStateRow]) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query("DELETE FROM sync_state")
+        .execute(&mut *tx)
+        .await?;
+
+    for row in rows {
+        sqlx::query(
+            "INSERT INTO sync_state (entity_key, content_hash, modified_at, deleted) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&row.entity_key)
+        .bind(&row.content_hash)
+        .bind(row.modified_at)
+        .bind(row.deleted)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
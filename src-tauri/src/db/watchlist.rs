@@ -0,0 +1,232 @@
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::FromRow;
+use typeshare::typeshare;
+
+use super::Pool;
+use crate::affiliation::Affiliation;
+use crate::ts_types::i64_ts;
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct WatchedCharacter {
+    pub character_id: i64_ts,
+    pub character_name: String,
+    pub corporation_id: i64_ts,
+    pub corporation_name: String,
+    pub alliance_id: Option<i64_ts>,
+    pub alliance_name: Option<String>,
+    pub portrait_url: String,
+    /// Unix seconds.
+    pub added_at: i64_ts,
+    /// Unix seconds.
+    pub last_checked_at: i64_ts,
+}
+
+impl WatchedCharacter {
+    pub fn affiliation(&self) -> Affiliation {
+        Affiliation {
+            corporation_id: self.corporation_id,
+            corporation_name: self.corporation_name.clone(),
+            alliance_id: self.alliance_id,
+            alliance_name: self.alliance_name.clone(),
+        }
+    }
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct WatchedCharacterChange {
+    pub id: i64_ts,
+    pub character_id: i64_ts,
+    pub old_corporation_id: i64_ts,
+    pub old_corporation_name: String,
+    pub old_alliance_id: Option<i64_ts>,
+    pub old_alliance_name: Option<String>,
+    pub new_corporation_id: i64_ts,
+    pub new_corporation_name: String,
+    pub new_alliance_id: Option<i64_ts>,
+    pub new_alliance_name: Option<String>,
+    /// Unix seconds.
+    pub changed_at: i64_ts,
+}
+
+const WATCHED_COLUMNS: &str = "character_id, character_name, corporation_id, corporation_name, \
+     alliance_id, alliance_name, portrait_url, added_at, last_checked_at";
+
+pub async fn get_watched_characters(pool: &Pool) -> Result<Vec<WatchedCharacter>> {
+    let sql = format!(
+        "SELECT {} FROM watched_characters ORDER BY character_name COLLATE NOCASE",
+        WATCHED_COLUMNS
+    );
+    let rows = sqlx::query_as::<_, WatchedCharacter>(sqlx::AssertSqlSafe(sql.as_str()))
+        .fetch_all(pool)
+        .await?;
+    Ok(rows)
+}
+
+pub async fn get_watched_character(
+    pool: &Pool,
+    character_id: i64,
+) -> Result<Option<WatchedCharacter>> {
+    let sql = format!(
+        "SELECT {} FROM watched_characters WHERE character_id = ?",
+        WATCHED_COLUMNS
+    );
+    let row = sqlx::query_as::<_, WatchedCharacter>(sqlx::AssertSqlSafe(sql.as_str()))
+        .bind(character_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(row)
+}
+
+/// Stores the latest public info for a watched character, adding it if new.
+/// When its corporation or alliance changed since the last check the change
+/// is journaled and the previous affiliation returned.
+pub async fn record_watched_character(
+    pool: &Pool,
+    character_id: i64,
+    character_name: &str,
+    affiliation: &Affiliation,
+    portrait_url: &str,
+    now: i64,
+) -> Result<Option<Affiliation>> {
+    let mut tx = pool.begin().await?;
+
+    let sql = format!(
+        "SELECT {} FROM watched_characters WHERE character_id = ?",
+        WATCHED_COLUMNS
+    );
+    let previous = sqlx::query_as::<_, WatchedCharacter>(sqlx::AssertSqlSafe(sql.as_str()))
+        .bind(character_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .map(|w| w.affiliation())
+        .filter(|previous| affiliation.differs_from(previous));
+
+    if let Some(previous) = &previous {
+        sqlx::query(
+            "INSERT INTO watched_character_changes (
+                 character_id, old_corporation_id, old_corporation_name, old_alliance_id,
+                 old_alliance_name, new_corporation_id, new_corporation_name, new_alliance_id,
+                 new_alliance_name, changed_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(character_id)
+        .bind(previous.corporation_id)
+        .bind(&previous.corporation_name)
+        .bind(previous.alliance_id)
+        .bind(&previous.alliance_name)
+        .bind(affiliation.corporation_id)
+        .bind(&affiliation.corporation_name)
+        .bind(affiliation.alliance_id)
+        .bind(&affiliation.alliance_name)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query(
+        "INSERT INTO watched_characters (
+             character_id, character_name, corporation_id, corporation_name, alliance_id,
+             alliance_name, portrait_url, added_at, last_checked_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(character_id) DO UPDATE SET
+             character_name = excluded.character_name,
+             corporation_id = excluded.corporation_id,
+             corporation_name = excluded.corporation_name,
+             alliance_id = excluded.alliance_id,
+             alliance_name = excluded.alliance_name,
+             portrait_url = excluded.portrait_url,
+             last_checked_at = excluded.last_checked_at",
+    )
+    .bind(character_id)
+    .bind(character_name)
+    .bind(affiliation.corporation_id)
+    .bind(&affiliation.corporation_name)
+    .bind(affiliation.alliance_id)
+    .bind(&affiliation.alliance_name)
+    .bind(portrait_url)
+    .bind(now)
+    .bind(now)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(previous)
+}
+
+pub async fn remove_watched_character(pool: &Pool, character_id: i64) -> Result<()> {
+    sqlx::query("DELETE FROM watched_characters WHERE character_id = ?")
+        .bind(character_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Most recent first.
+pub async fn get_watched_character_changes(
+    pool: &Pool,
+    character_id: i64,
+) -> Result<Vec<WatchedCharacterChange>> {
+    let rows = sqlx::query_as::<_, WatchedCharacterChange>(
+        "SELECT id, character_id, old_corporation_id, old_corporation_name, old_alliance_id,
+                old_alliance_name, new_corporation_id, new_corporation_name, new_alliance_id,
+                new_alliance_name, changed_at
+         FROM watched_character_changes
+         WHERE character_id = ?
+         ORDER BY changed_at DESC, id DESC",
+    )
+    .bind(character_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    fn affiliation(corporation_id: i64, alliance_id: Option<i64>) -> Affiliation {
+        Affiliation {
+            corporation_id,
+            corporation_name: format!("Corp {}", corporation_id),
+            alliance_id,
+            alliance_name: alliance_id.map(|id| format!("Alliance {}", id)),
+        }
+    }
+
+    #[tokio::test]
+    async fn journals_only_membership_changes() {
+        let db = TestDb::new().await.unwrap();
+
+        let first = record_watched_character(&db.pool, 9, "Spy", &affiliation(1, None), "url", 100)
+            .await
+            .unwrap();
+        assert_eq!(first, None);
+        let same = record_watched_character(&db.pool, 9, "Spy", &affiliation(1, None), "url", 200)
+            .await
+            .unwrap();
+        assert_eq!(same, None);
+
+        let moved =
+            record_watched_character(&db.pool, 9, "Spy", &affiliation(2, Some(5)), "url", 300)
+                .await
+                .unwrap();
+        assert_eq!(moved, Some(affiliation(1, None)));
+
+        let watched = get_watched_character(&db.pool, 9).await.unwrap().unwrap();
+        assert_eq!(watched.added_at, 100);
+        assert_eq!(watched.last_checked_at, 300);
+        assert_eq!(watched.alliance_name.as_deref(), Some("Alliance 5"));
+
+        let changes = get_watched_character_changes(&db.pool, 9).await.unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].new_corporation_id, 2);
+
+        remove_watched_character(&db.pool, 9).await.unwrap();
+        assert!(get_watched_characters(&db.pool).await.unwrap().is_empty());
+    }
+}
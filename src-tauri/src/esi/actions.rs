@@ -0,0 +1,84 @@
+//! Uncached, side-effecting ESI requests (the `esi-ui.*` endpoints that drive
+//! the running EVE client, saving fittings). These return nothing worth
+//! caching, but still feed the per-character rate-limit store like every other
+//! ESI call.
+
+use anyhow::{bail, Context, Result};
+use reqwest::header::ACCEPT_LANGUAGE;
+
+use super::cached::{check_error_limit, RateLimitStore};
+
+async fn send_post(
+    client: &reqwest::Client,
+    endpoint_path: &str,
+    build: impl FnOnce(reqwest::RequestBuilder) -> reqwest::RequestBuilder,
+    rate_limits: &RateLimitStore,
+    character_id: i64,
+) -> Result<reqwest::Response> {
+    let url = super::BASE_URL
+        .parse::<reqwest::Url>()
+        .context("Invalid base URL")?
+        .join(endpoint_path)
+        .context("Failed to construct request URL")?;
+
+    let req_builder = client
+        .post(url)
+        .header(ACCEPT_LANGUAGE, "en")
+        .header("x-compatibility-date", "2020-01-01")
+        .header("x-tenant", "tranquility");
+    check_error_limit(rate_limits).await?;
+    let response = build(req_builder).send().await?;
+    let status = response.status();
+
+    rate_limits.write().await.record_response(
+        character_id,
+        endpoint_path,
+        status.as_u16(),
+        response.headers(),
+    );
+
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        bail!("ESI returned {} for {}: {}", status, endpoint_path, body);
+    }
+    Ok(response)
+}
+
+pub async fn post_action(
+    client: &reqwest::Client,
+    endpoint_path: &str,
+    query: &[(&str, String)],
+    rate_limits: &RateLimitStore,
+    character_id: i64,
+) -> Result<()> {
+    send_post(
+        client,
+        endpoint_path,
+        |req| req.query(query),
+        rate_limits,
+        character_id,
+    )
+    .await?;
+    Ok(())
+}
+
+pub async fn post_json<B: serde::Serialize, T: serde::de::DeserializeOwned>(
+    client: &reqwest::Client,
+    endpoint_path: &str,
+    body: &B,
+    rate_limits: &RateLimitStore,
+    character_id: i64,
+) -> Result<T> {
+    let response = send_post(
+        client,
+        endpoint_path,
+        |req| req.json(body),
+        rate_limits,
+        character_id,
+    )
+    .await?;
+    response
+        .json::<T>()
+        .await
+        .context("Failed to deserialize response")
+}
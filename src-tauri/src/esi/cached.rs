@@ -1,11 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use chrono::Utc;
 use reqwest::header::{HeaderMap, HeaderValue, ACCEPT_LANGUAGE, IF_NONE_MATCH};
 use serde::Serialize;
-use tokio::sync::RwLock;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::{RwLock, Semaphore, SemaphorePermit};
+use typeshare::typeshare;
 
 use crate::cache;
 use crate::db;
@@ -19,7 +21,271 @@ pub struct RateLimitInfo {
     pub updated_at: chrono::DateTime<Utc>,
 }
 
-pub type RateLimitStore = Arc<RwLock<HashMap<i64, HashMap<String, RateLimitInfo>>>>;
+/// How long 420/429 responses are kept for the rate-limit dashboard.
+const THROTTLE_RETENTION_MINUTES: i64 = 60;
+
+/// Below this many errors left in ESI's window, requests are spaced out until
+/// the reset and the frontend is warned.
+const ERROR_LIMIT_SLOW_AT: i32 = 20;
+/// Below this many, requests are refused until the reset; running out gets
+/// the whole app banned from ESI for the rest of the window.
+const ERROR_LIMIT_REJECT_AT: i32 = 5;
+
+/// Most per-character fetches that passes over every character run at once.
+pub const MAX_CONCURRENT_FETCHES: usize = 6;
+
+/// Shared by every all-character pass, so two passes running together (the
+/// tray poll and the watchlist, say) still stay within the limit.
+static FETCH_PERMITS: Semaphore = Semaphore::const_new(MAX_CONCURRENT_FETCHES);
+
+#[derive(Debug, Clone)]
+pub struct ThrottleEvent {
+    pub route: String,
+    pub group: Option<String>,
+    pub status: u16,
+    pub at: chrono::DateTime<Utc>,
+}
+
+/// ESI's global error budget, shared by every request from this IP.
+#[derive(Debug, Clone)]
+pub struct ErrorLimit {
+    pub remain: i32,
+    pub reset_at: chrono::DateTime<Utc>,
+    warned: bool,
+    warned_rejecting: bool,
+}
+
+/// Sent once each time the error budget drops below
+/// [`ERROR_LIMIT_SLOW_AT`], and again when it drops below
+/// [`ERROR_LIMIT_REJECT_AT`].
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct RateLimitWarning {
+    pub error_limit_remain: i32,
+    pub reset_at: String,
+    /// Requests are being refused, not just slowed, until `reset_at`.
+    pub rejecting: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorLimitGate {
+    Proceed,
+    Delay(std::time::Duration),
+    Reject(std::time::Duration),
+}
+
+#[derive(Debug, Default)]
+pub struct RateLimitState {
+    /// Latest rate-limit headers per character and rate-limit group.
+    pub limits: HashMap<i64, HashMap<String, RateLimitInfo>>,
+    /// Route families (see [`route_family`]) seen under each rate-limit group.
+    pub group_routes: HashMap<String, BTreeSet<String>>,
+    /// Throttled (420 error-limited / 429 rate-limited) responses, oldest first.
+    pub throttled: VecDeque<ThrottleEvent>,
+    /// From the latest response carrying `X-ESI-Error-Limit-*` headers.
+    pub error_limit: Option<ErrorLimit>,
+    /// Earliest time the next request may go out while requests are spaced.
+    next_request_at: Option<chrono::DateTime<Utc>>,
+    /// Where [`RateLimitWarning`]s go; set once the app is running.
+    pub warnings: Option<UnboundedSender<RateLimitWarning>>,
+    /// Counts since startup, for the metrics endpoint.
+    pub stats: EsiStats,
+}
+
+/// Running totals of ESI responses since the app started.
+#[derive(Debug, Clone, Default)]
+pub struct EsiStats {
+    /// Error responses (4xx/5xx) by status code.
+    pub errors: BTreeMap<u16, u64>,
+    /// Reads answered by the cache: unexpired entries and 304s.
+    pub cache_hits: u64,
+    /// Reads that needed a full response from ESI.
+    pub cache_misses: u64,
+}
+
+impl RateLimitState {
+    /// Record the rate-limit headers and throttling status of one ESI response.
+    pub fn record_response(
+        &mut self,
+        character_id: i64,
+        endpoint_path: &str,
+        status: u16,
+        headers: &HeaderMap,
+    ) {
+        let info = extract_rate_limit_info(headers);
+        let route = route_family(endpoint_path);
+        let group = info.as_ref().map(|i| i.group.clone());
+
+        if let Some(info) = info {
+            self.group_routes
+                .entry(info.group.clone())
+                .or_default()
+                .insert(route.clone());
+            self.limits
+                .entry(character_id)
+                .or_default()
+                .insert(info.group.clone(), info);
+        }
+
+        if status >= 400 {
+            *self.stats.errors.entry(status).or_default() += 1;
+        }
+        if status == 420 || status == 429 {
+            self.throttled.push_back(ThrottleEvent {
+                route,
+                group,
+                status,
+                at: Utc::now(),
+            });
+        }
+        self.prune_throttled(Utc::now());
+
+        if let Some((remain, reset_in)) = extract_error_limit(headers) {
+            self.record_error_limit(remain, Utc::now() + reset_in);
+        }
+    }
+
+    fn record_error_limit(&mut self, remain: i32, reset_at: chrono::DateTime<Utc>) {
+        let rejecting = remain < ERROR_LIMIT_REJECT_AT;
+        let previous = self
+            .error_limit
+            .as_ref()
+            .filter(|_| remain < ERROR_LIMIT_SLOW_AT);
+        let mut limit = ErrorLimit {
+            remain,
+            reset_at,
+            warned: previous.is_some_and(|e| e.warned),
+            warned_rejecting: rejecting && previous.is_some_and(|e| e.warned_rejecting),
+        };
+        if (remain < ERROR_LIMIT_SLOW_AT && !limit.warned) || (rejecting && !limit.warned_rejecting)
+        {
+            limit.warned = true;
+            limit.warned_rejecting = rejecting;
+            let warning = RateLimitWarning {
+                error_limit_remain: remain,
+                reset_at: reset_at.to_rfc3339(),
+                rejecting,
+            };
+            log::warn!(
+                "ESI error limit low: {} errors left until {}",
+                remain,
+                warning.reset_at
+            );
+            if let Some(warnings) = &self.warnings {
+                let _ = warnings.send(warning);
+            }
+        }
+        self.error_limit = Some(limit);
+    }
+
+    /// Whether a request may go out now. Low budgets spread the remaining
+    /// errors over what is left of the window; an almost spent one refuses
+    /// requests until it resets.
+    pub fn error_limit_gate(&self, now: chrono::DateTime<Utc>) -> ErrorLimitGate {
+        let Some(limit) = &self.error_limit else {
+            return ErrorLimitGate::Proceed;
+        };
+        let Ok(reset_in) = (limit.reset_at - now).to_std() else {
+            return ErrorLimitGate::Proceed;
+        };
+        if limit.remain < ERROR_LIMIT_REJECT_AT {
+            ErrorLimitGate::Reject(reset_in)
+        } else if limit.remain < ERROR_LIMIT_SLOW_AT {
+            ErrorLimitGate::Delay(reset_in / limit.remain.max(1) as u32)
+        } else {
+            ErrorLimitGate::Proceed
+        }
+    }
+
+    /// [`Self::error_limit_gate`] for a request about to go out. Spaced
+    /// requests take turns: each waits for the slot after the one before it,
+    /// so concurrent fetches do not all wake at once.
+    pub fn take_request_slot(&mut self, now: chrono::DateTime<Utc>) -> ErrorLimitGate {
+        let gate = self.error_limit_gate(now);
+        let ErrorLimitGate::Delay(spacing) = gate else {
+            self.next_request_at = None;
+            return gate;
+        };
+        let slot = self.next_request_at.filter(|at| *at > now).unwrap_or(now);
+        self.next_request_at = Some(slot + chrono::Duration::from_std(spacing).unwrap_or_default());
+        match (slot - now).to_std() {
+            Ok(wait) if !wait.is_zero() => ErrorLimitGate::Delay(wait),
+            _ => ErrorLimitGate::Proceed,
+        }
+    }
+
+    /// How many characters an all-character pass should fetch at once: one at
+    /// a time while ESI has throttled us in the last minute or any group is
+    /// down to its last fifth, otherwise [`MAX_CONCURRENT_FETCHES`].
+    pub fn fetch_concurrency(&self, now: chrono::DateTime<Utc>) -> usize {
+        let recently_throttled = self
+            .throttled
+            .back()
+            .is_some_and(|e| now - e.at < chrono::Duration::minutes(1));
+        let nearly_spent = self
+            .limits
+            .values()
+            .flat_map(|groups| groups.values())
+            .any(|info| info.remaining * 5 < info.limit);
+        if recently_throttled || nearly_spent {
+            1
+        } else {
+            MAX_CONCURRENT_FETCHES
+        }
+    }
+
+    pub fn prune_throttled(&mut self, now: chrono::DateTime<Utc>) {
+        let cutoff = now - chrono::Duration::minutes(THROTTLE_RETENTION_MINUTES);
+        while self.throttled.front().is_some_and(|e| e.at < cutoff) {
+            self.throttled.pop_front();
+        }
+    }
+}
+
+pub type RateLimitStore = Arc<RwLock<RateLimitState>>;
+
+/// Holds back an outgoing request while ESI's error budget is low; see
+/// [`RateLimitState::error_limit_gate`].
+pub async fn check_error_limit(rate_limits: &RateLimitStore) -> Result<()> {
+    let gate = rate_limits.write().await.take_request_slot(Utc::now());
+    match gate {
+        ErrorLimitGate::Proceed => Ok(()),
+        ErrorLimitGate::Delay(delay) => {
+            tokio::time::sleep(delay).await;
+            Ok(())
+        }
+        ErrorLimitGate::Reject(reset_in) => anyhow::bail!(
+            "ESI error limit nearly exhausted; requests resume in {}s",
+            reset_in.as_secs()
+        ),
+    }
+}
+
+/// Waits for a slot among the [`MAX_CONCURRENT_FETCHES`] shared by
+/// all-character passes; hold it for the duration of one character's fetch.
+pub async fn acquire_fetch_permit() -> SemaphorePermit<'static> {
+    FETCH_PERMITS
+        .acquire()
+        .await
+        .expect("fetch semaphore is never closed")
+}
+
+/// Collapse an endpoint path to its route family by replacing numeric path
+/// segments: `characters/123/skillqueue` -> `characters/{id}/skillqueue`.
+pub fn route_family(endpoint_path: &str) -> String {
+    let path = endpoint_path.split('?').next().unwrap_or_default();
+    path.trim_matches('/')
+        .split('/')
+        .map(|segment| {
+            if !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
 
 pub fn extract_rate_limit_info(headers: &HeaderMap) -> Option<RateLimitInfo> {
     let group = headers.get("x-ratelimit-group")?.to_str().ok()?.to_string();
@@ -42,6 +308,14 @@ pub fn extract_rate_limit_info(headers: &HeaderMap) -> Option<RateLimitInfo> {
     })
 }
 
+/// `X-ESI-Error-Limit-Remain` and the time until `X-ESI-Error-Limit-Reset`.
+fn extract_error_limit(headers: &HeaderMap) -> Option<(i32, chrono::Duration)> {
+    let header = |name: &str| -> Option<i64> { headers.get(name)?.to_str().ok()?.parse().ok() };
+    let remain = header("x-esi-error-limit-remain")?;
+    let reset = header("x-esi-error-limit-reset")?;
+    Some((remain as i32, chrono::Duration::seconds(reset)))
+}
+
 fn parse_limit_str(limit_str: &str) -> Option<(i32, i32)> {
     let parts: Vec<&str> = limit_str.split('/').collect();
     if parts.len() != 2 {
@@ -71,6 +345,7 @@ pub async fn fetch_cached<T: serde::de::DeserializeOwned>(
         if !entry.is_expired() {
             let data: T = serde_json::from_str(&entry.response_body)
                 .context("Failed to deserialize cached response")?;
+            rate_limits.write().await.stats.cache_hits += 1;
             return Ok(Some(data));
         }
     }
@@ -86,33 +361,34 @@ pub async fn fetch_cached<T: serde::de::DeserializeOwned>(
     req_builder = req_builder.header("x-compatibility-date", "2020-01-01");
     req_builder = req_builder.header("x-tenant", "tranquility");
 
-    // If we have an ETag (even if expired), use it for conditional request
-    if let Some(entry) = &cached_entry {
-        if let Some(etag) = &entry.etag {
-            let header_value = HeaderValue::from_str(etag.as_str())?;
-            req_builder = req_builder.header(IF_NONE_MATCH, header_value);
-        }
+    // An expired entry with an ETag turns the fetch into a conditional
+    // request; an unchanged resource comes back as a body-less 304.
+    if let Some(etag) = cached_entry.as_ref().and_then(|e| e.etag.as_deref()) {
+        let header_value = HeaderValue::from_str(&cache::if_none_match(etag))?;
+        req_builder = req_builder.header(IF_NONE_MATCH, header_value);
     }
 
+    check_error_limit(rate_limits).await?;
     let response = req_builder.send().await?;
     let status = response.status();
     let headers = response.headers().clone();
 
-    if let Some(info) = extract_rate_limit_info(&headers) {
-        let mut store = rate_limits.write().await;
-        store
-            .entry(character_id)
-            .or_insert_with(HashMap::new)
-            .insert(info.group.clone(), info);
-    }
+    rate_limits.write().await.record_response(
+        character_id,
+        endpoint_path,
+        status.as_u16(),
+        &headers,
+    );
 
     // 304 Not Modified: Cache is still valid, update expiration and return cached data
     if status.as_u16() == 304 {
         if let Some(entry) = cached_entry {
+            let etag = cache::extract_etag(&headers);
             let expires_at = cache::extract_expires(&headers);
-            cache::update_cache_expiration(pool, cache_key, expires_at).await?;
+            cache::refresh_cache_entry(pool, cache_key, etag.as_deref(), expires_at).await?;
             let data: T = serde_json::from_str(&entry.response_body)
                 .context("Failed to deserialize cached response")?;
+            rate_limits.write().await.stats.cache_hits += 1;
             return Ok(Some(data));
         }
     }
@@ -121,11 +397,13 @@ pub async fn fetch_cached<T: serde::de::DeserializeOwned>(
     if status.is_success() {
         let body_bytes = response.bytes().await?;
         let body_str = String::from_utf8_lossy(&body_bytes);
+        super::schema::check_drift(endpoint_path, &body_str);
 
         let etag = cache::extract_etag(&headers);
         let expires_at = cache::extract_expires(&headers);
 
         cache::set_cached_response(pool, cache_key, etag.as_deref(), expires_at, &body_str).await?;
+        rate_limits.write().await.stats.cache_misses += 1;
 
         let data: T = serde_json::from_str(&body_str).context("Failed to deserialize response")?;
         return Ok(Some(data));
@@ -133,3 +411,115 @@ pub async fn fetch_cached<T: serde::de::DeserializeOwned>(
 
     Ok(None)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(limit: i32, remaining: i32) -> RateLimitInfo {
+        RateLimitInfo {
+            group: "char-detail".to_string(),
+            limit,
+            remaining,
+            window_minutes: 15,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn fetch_concurrency_drops_to_one_when_limits_run_low() {
+        let now = Utc::now();
+        let mut state = RateLimitState::default();
+        assert_eq!(state.fetch_concurrency(now), MAX_CONCURRENT_FETCHES);
+
+        state
+            .limits
+            .entry(1)
+            .or_default()
+            .insert("char-detail".to_string(), info(600, 500));
+        assert_eq!(state.fetch_concurrency(now), MAX_CONCURRENT_FETCHES);
+
+        state
+            .limits
+            .entry(2)
+            .or_default()
+            .insert("char-detail".to_string(), info(600, 100));
+        assert_eq!(state.fetch_concurrency(now), 1);
+
+        state.limits.clear();
+        state.throttled.push_back(ThrottleEvent {
+            route: "characters/{id}/skillqueue".to_string(),
+            group: None,
+            status: 429,
+            at: now - chrono::Duration::seconds(30),
+        });
+        assert_eq!(state.fetch_concurrency(now), 1);
+        assert_eq!(
+            state.fetch_concurrency(now + chrono::Duration::minutes(2)),
+            MAX_CONCURRENT_FETCHES
+        );
+    }
+
+    #[test]
+    fn low_error_budget_delays_then_rejects_and_warns_once() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut state = RateLimitState {
+            warnings: Some(tx),
+            ..RateLimitState::default()
+        };
+        let now = Utc::now();
+        let reset_at = now + chrono::Duration::seconds(40);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-esi-error-limit-remain", HeaderValue::from_static("100"));
+        headers.insert("x-esi-error-limit-reset", HeaderValue::from_static("40"));
+        state.record_response(1, "characters/1/skills", 200, &headers);
+        assert_eq!(state.error_limit_gate(now), ErrorLimitGate::Proceed);
+
+        state.record_error_limit(10, reset_at);
+        assert!(matches!(
+            state.error_limit_gate(now),
+            ErrorLimitGate::Delay(d) if d <= std::time::Duration::from_secs(4)
+        ));
+        state.record_error_limit(3, reset_at);
+        assert!(matches!(
+            state.error_limit_gate(now),
+            ErrorLimitGate::Reject(_)
+        ));
+        assert_eq!(
+            state.error_limit_gate(reset_at + chrono::Duration::seconds(1)),
+            ErrorLimitGate::Proceed
+        );
+
+        let warning = rx.try_recv().unwrap();
+        assert_eq!(warning.error_limit_remain, 10);
+        assert!(!warning.rejecting);
+        let warning = rx.try_recv().unwrap();
+        assert_eq!(warning.error_limit_remain, 3);
+        assert!(warning.rejecting);
+        state.record_error_limit(2, reset_at);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn spaced_requests_take_turns() {
+        let mut state = RateLimitState::default();
+        let now = Utc::now();
+        // 10 errors left over 40s: one request every 4s.
+        state.record_error_limit(10, now + chrono::Duration::seconds(40));
+
+        let waits: Vec<_> = (0..3).map(|_| state.take_request_slot(now)).collect();
+        assert_eq!(
+            waits,
+            vec![
+                ErrorLimitGate::Proceed,
+                ErrorLimitGate::Delay(std::time::Duration::from_secs(4)),
+                ErrorLimitGate::Delay(std::time::Duration::from_secs(8)),
+            ]
+        );
+        assert_eq!(
+            state.take_request_slot(now + chrono::Duration::seconds(20)),
+            ErrorLimitGate::Proceed
+        );
+    }
+}
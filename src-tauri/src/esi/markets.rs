@@ -0,0 +1,123 @@
+//! Regional market prices from the public orders endpoint. Needs no token;
+//! requests are cached and rate-limited under character id 0 like the other
+//! public lookups. Filtering by type keeps each response to one page.
+
+use anyhow::Result;
+use serde::Deserialize;
+
+use crate::db;
+use crate::db::market_prices::MarketPrice;
+
+/// The Forge, home of Jita.
+pub const THE_FORGE_REGION_ID: i64 = 10000002;
+
+const PUBLIC_RATE_LIMIT_ID: i64 = 0;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct EsiMarketOrder {
+    pub price: f64,
+    pub is_buy_order: bool,
+    pub volume_remain: i64,
+}
+
+/// Lowest sell and highest buy across `orders`, with the volume on each side.
+pub fn summarize_orders(
+    type_id: i64,
+    region_id: i64,
+    orders: &[EsiMarketOrder],
+    updated_at: i64,
+) -> MarketPrice {
+    let side = |buy: bool| orders.iter().filter(move |o| o.is_buy_order == buy);
+    MarketPrice {
+        type_id,
+        region_id,
+        sell_min: side(false).map(|o| o.price).min_by(f64::total_cmp),
+        buy_max: side(true).map(|o| o.price).max_by(f64::total_cmp),
+        sell_volume: side(false).map(|o| o.volume_remain).sum(),
+        buy_volume: side(true).map(|o| o.volume_remain).sum(),
+        updated_at,
+    }
+}
+
+/// Current price of `type_id` in `region_id`; `None` when ESI returned
+/// nothing for it.
+pub async fn fetch_region_price(
+    pool: &db::Pool,
+    client: &reqwest::Client,
+    rate_limits: &super::RateLimitStore,
+    region_id: i64,
+    type_id: i64,
+) -> Result<Option<MarketPrice>> {
+    let endpoint_path = format!(
+        "markets/{}/orders/?order_type=all&type_id={}",
+        region_id, type_id
+    );
+    let cache_key = format!("{}:0", endpoint_path);
+    let orders: Option<Vec<EsiMarketOrder>> = super::fetch_cached(
+        pool,
+        client,
+        &endpoint_path,
+        &cache_key,
+        rate_limits,
+        PUBLIC_RATE_LIMIT_ID,
+    )
+    .await?;
+
+    Ok(orders.map(|orders| {
+        summarize_orders(type_id, region_id, &orders, chrono::Utc::now().timestamp())
+    }))
+}
+
+/// Fetches and stores every tracked type's price in The Forge. A type that
+/// fails is logged and keeps its previous price.
+pub async fn refresh_market_prices(
+    pool: &db::Pool,
+    rate_limits: &super::RateLimitStore,
+) -> Result<Vec<MarketPrice>> {
+    let client = reqwest::Client::new();
+    for type_id in db::market_prices::get_market_type_ids(pool).await? {
+        match fetch_region_price(pool, &client, rate_limits, THE_FORGE_REGION_ID, type_id).await {
+            Ok(Some(price)) => db::market_prices::upsert_market_price(pool, &price).await?,
+            Ok(None) => {}
+            Err(e) => eprintln!("Failed to fetch market price for type {}: {}", type_id, e),
+        }
+    }
+    db::market_prices::get_market_prices(pool).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(price: f64, is_buy_order: bool, volume_remain: i64) -> EsiMarketOrder {
+        EsiMarketOrder {
+            price,
+            is_buy_order,
+            volume_remain,
+        }
+    }
+
+    #[test]
+    fn summary_takes_best_price_per_side() {
+        let orders = [
+            order(5_100_000.0, false, 10),
+            order(5_000_000.0, false, 5),
+            order(4_800_000.0, true, 100),
+            order(4_900_000.0, true, 1),
+        ];
+
+        let price = summarize_orders(44992, THE_FORGE_REGION_ID, &orders, 0);
+
+        assert_eq!(price.sell_min, Some(5_000_000.0));
+        assert_eq!(price.buy_max, Some(4_900_000.0));
+        assert_eq!(price.sell_volume, 15);
+        assert_eq!(price.buy_volume, 101);
+    }
+
+    #[test]
+    fn summary_without_buy_orders_has_no_buy_price() {
+        let price = summarize_orders(40520, THE_FORGE_REGION_ID, &[order(1.0, false, 1)], 0);
+        assert_eq!(price.buy_max, None);
+        assert_eq!(price.buy_volume, 0);
+    }
+}
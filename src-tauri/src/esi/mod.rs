@@ -1,11 +1,19 @@
+pub mod actions;
 pub mod cached;
+pub mod markets;
+pub mod raw;
+pub mod schema;
 pub mod scopes;
 #[rustfmt::skip]
 pub mod client;
 #[rustfmt::skip]
 pub mod types;
 
-pub use cached::{fetch_cached, RateLimitInfo, RateLimitStore};
+pub use actions::{post_action, post_json};
+pub use cached::{
+    acquire_fetch_permit, fetch_cached, EsiStats, RateLimitInfo, RateLimitState, RateLimitStore,
+    RateLimitWarning,
+};
 pub use client::BASE_URL;
 pub use scopes::{EsiScope, BASE_SCOPES};
 pub use types::*;
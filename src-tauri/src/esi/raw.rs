@@ -0,0 +1,310 @@
+//! Arbitrary ESI requests for the explorer in settings, for endpoints the UI
+//! does not surface yet. GETs share the response cache and conditional
+//! requests with [`fetch_cached`](super::fetch_cached); other methods go out
+//! uncached. Every response feeds the rate-limit store, and the raw body and
+//! headers come back as-is, error statuses included.
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{bail, Context, Result};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT_LANGUAGE, IF_NONE_MATCH};
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use super::cached::RateLimitStore;
+use crate::auth;
+use crate::cache;
+use crate::db;
+use crate::db::app_settings::EsiExplorerSettings;
+use crate::esi_helpers;
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EsiMethod {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+impl EsiMethod {
+    fn as_reqwest(self) -> reqwest::Method {
+        match self {
+            EsiMethod::Get => reqwest::Method::GET,
+            EsiMethod::Post => reqwest::Method::POST,
+            EsiMethod::Put => reqwest::Method::PUT,
+            EsiMethod::Delete => reqwest::Method::DELETE,
+        }
+    }
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct RawEsiResponse {
+    pub status: u16,
+    /// Response headers; for a cache hit only what the cache keeps (ETag and
+    /// expiry).
+    pub headers: HashMap<String, String>,
+    /// Response body as sent by ESI, normally JSON.
+    pub body: String,
+    pub from_cache: bool,
+}
+
+/// Normalizes a user-entered path to one relative to the ESI base URL,
+/// refusing anything that could leave it.
+pub fn normalize_path(path: &str) -> Result<String> {
+    let path = path.trim().trim_start_matches('/');
+    if path.is_empty() {
+        bail!("ESI path is empty");
+    }
+    if path.contains("://") || path.contains('?') || path.contains('#') {
+        bail!("ESI path must be a bare path; pass query parameters separately");
+    }
+    // URL parsing reads a backslash as `/` and decodes `%2e` as `.`, which would
+    // slip a host or `..` segment past the checks below.
+    if path.contains('\\') || path.contains('%') {
+        bail!("ESI path must not contain backslashes or percent-encoding");
+    }
+    if path
+        .split('/')
+        .any(|segment| segment == ".." || segment == ".")
+    {
+        bail!("ESI path must not contain relative segments");
+    }
+    Ok(path.to_string())
+}
+
+/// Joins a normalized path onto the ESI base URL and checks the result is
+/// still under it, so a request made with the character's token can only go
+/// to ESI.
+fn request_url(endpoint_path: &str) -> Result<reqwest::Url> {
+    let base = super::BASE_URL
+        .parse::<reqwest::Url>()
+        .context("Invalid base URL")?;
+    let url = base
+        .join(endpoint_path)
+        .context("Failed to construct request URL")?;
+
+    let base_dir = &base.path()[..=base.path().rfind('/').unwrap_or(0)];
+    if url.scheme() != base.scheme()
+        || url.host_str() != base.host_str()
+        || url.port_or_known_default() != base.port_or_known_default()
+        || !url.path().starts_with(base_dir)
+    {
+        bail!("ESI path must stay under {}", super::BASE_URL);
+    }
+    Ok(url)
+}
+
+/// The path plus its sorted query string, so identical requests share a
+/// cache entry.
+fn endpoint_with_query(path: &str, query: &BTreeMap<String, String>) -> String {
+    if query.is_empty() {
+        return path.to_string();
+    }
+    let query: Vec<String> = query.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    format!("{}?{}", path, query.join("&"))
+}
+
+fn header_map(headers: &HeaderMap) -> HashMap<String, String> {
+    headers
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+fn cached_response(entry: &cache::CacheEntry) -> RawEsiResponse {
+    let mut headers = HashMap::new();
+    if let Some(etag) = &entry.etag {
+        headers.insert("etag".to_string(), etag.clone());
+    }
+    if let Some(expires) = chrono::DateTime::from_timestamp(entry.expires_at, 0) {
+        headers.insert("expires".to_string(), expires.to_rfc2822());
+    }
+    RawEsiResponse {
+        status: 200,
+        headers,
+        body: entry.response_body.clone(),
+        from_cache: true,
+    }
+}
+
+pub async fn raw_request(
+    pool: &db::Pool,
+    client: &reqwest::Client,
+    method: EsiMethod,
+    path: &str,
+    query: &BTreeMap<String, String>,
+    rate_limits: &RateLimitStore,
+    character_id: i64,
+) -> Result<RawEsiResponse> {
+    let endpoint_path = normalize_path(path)?;
+    let cache_key =
+        cache::build_cache_key(&endpoint_with_query(&endpoint_path, query), character_id);
+
+    let cached_entry = if method == EsiMethod::Get {
+        cache::get_cached_response(pool, &cache_key).await?
+    } else {
+        None
+    };
+    if let Some(entry) = cached_entry.as_ref().filter(|e| !e.is_expired()) {
+        return Ok(cached_response(entry));
+    }
+
+    let url = request_url(&endpoint_path)?;
+
+    let mut req_builder = client
+        .request(method.as_reqwest(), url)
+        .query(query)
+        .header(ACCEPT_LANGUAGE, "en")
+        .header("x-compatibility-date", "2020-01-01")
+        .header("x-tenant", "tranquility");
+    if let Some(etag) = cached_entry.as_ref().and_then(|e| e.etag.as_deref()) {
+        let header_value = HeaderValue::from_str(&cache::if_none_match(etag))?;
+        req_builder = req_builder.header(IF_NONE_MATCH, header_value);
+    }
+
+    super::cached::check_error_limit(rate_limits).await?;
+    let response = req_builder.send().await?;
+    let status = response.status();
+    let headers = response.headers().clone();
+
+    rate_limits.write().await.record_response(
+        character_id,
+        &endpoint_path,
+        status.as_u16(),
+        &headers,
+    );
+
+    if status.as_u16() == 304 {
+        if let Some(entry) = cached_entry {
+            let etag = cache::extract_etag(&headers);
+            let expires_at = cache::extract_expires(&headers);
+            cache::refresh_cache_entry(pool, &cache_key, etag.as_deref(), expires_at).await?;
+            return Ok(RawEsiResponse {
+                status: 304,
+                headers: header_map(&headers),
+                body: entry.response_body,
+                from_cache: true,
+            });
+        }
+    }
+
+    let body = String::from_utf8_lossy(&response.bytes().await?).into_owned();
+    if method == EsiMethod::Get && status.is_success() {
+        let etag = cache::extract_etag(&headers);
+        let expires_at = cache::extract_expires(&headers);
+        cache::set_cached_response(pool, &cache_key, etag.as_deref(), expires_at, &body).await?;
+    }
+
+    Ok(RawEsiResponse {
+        status: status.as_u16(),
+        headers: header_map(&headers),
+        body,
+        from_cache: false,
+    })
+}
+
+fn check_allowed(settings: &EsiExplorerSettings, method: EsiMethod) -> Result<()> {
+    if !settings.enabled {
+        bail!("The ESI explorer is disabled; enable it in settings first");
+    }
+    if method != EsiMethod::Get && !settings.allow_writes {
+        bail!("Only GET requests are allowed; enable writes for the ESI explorer in settings");
+    }
+    Ok(())
+}
+
+/// Explorer entry point: checks the settings gate, then sends the request as
+/// `character_id` (authenticated) or anonymously for public endpoints.
+pub async fn explorer_request(
+    pool: &db::Pool,
+    rate_limits: &RateLimitStore,
+    character_id: Option<i64>,
+    method: EsiMethod,
+    path: &str,
+    query: &BTreeMap<String, String>,
+) -> Result<RawEsiResponse> {
+    let settings = db::app_settings::get_esi_explorer_settings(pool).await?;
+    check_allowed(&settings, method)?;
+
+    match character_id {
+        Some(character_id) => {
+            let access_token = auth::ensure_valid_access_token(pool, character_id).await?;
+            let client = esi_helpers::create_authenticated_client(&access_token)?;
+            raw_request(
+                pool,
+                &client,
+                method,
+                path,
+                query,
+                rate_limits,
+                character_id,
+            )
+            .await
+        }
+        None => {
+            let client = reqwest::Client::new();
+            raw_request(pool, &client, method, path, query, rate_limits, 0).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_stay_under_the_base_url() {
+        assert_eq!(
+            normalize_path(" /characters/1/skills/ ").unwrap(),
+            "characters/1/skills/"
+        );
+        assert!(normalize_path("").is_err());
+        assert!(normalize_path("https://example.com/").is_err());
+        assert!(normalize_path("characters/../../x").is_err());
+        assert!(normalize_path("markets/prices?x=1").is_err());
+        assert!(normalize_path("\\\\evil.example/x").is_err());
+        assert!(normalize_path("characters/%2e%2e/%2e%2e/x").is_err());
+        assert!(normalize_path("characters/%2E%2E/x").is_err());
+    }
+
+    #[test]
+    fn request_urls_never_leave_the_esi_host() {
+        let base = super::super::BASE_URL.parse::<reqwest::Url>().unwrap();
+        let url = request_url("characters/1/skills/").unwrap();
+        assert_eq!(url.host_str(), base.host_str());
+        assert!(url.path().ends_with("/characters/1/skills/"));
+
+        // Both would resolve off ESI if they got past normalize_path.
+        assert!(request_url("\\\\evil.example/x").is_err());
+        assert!(request_url("//evil.example/x").is_err());
+    }
+
+    #[test]
+    fn explorer_is_off_and_read_only_by_default() {
+        let mut settings = EsiExplorerSettings::default();
+        assert!(check_allowed(&settings, EsiMethod::Get).is_err());
+
+        settings.enabled = true;
+        assert!(check_allowed(&settings, EsiMethod::Get).is_ok());
+        assert!(check_allowed(&settings, EsiMethod::Post).is_err());
+
+        settings.allow_writes = true;
+        assert!(check_allowed(&settings, EsiMethod::Delete).is_ok());
+    }
+
+    #[test]
+    fn cache_key_includes_sorted_query() {
+        let query = BTreeMap::from([
+            ("type_id".to_string(), "44992".to_string()),
+            ("order_type".to_string(), "sell".to_string()),
+        ]);
+        assert_eq!(
+            endpoint_with_query("markets/10000002/orders", &query),
+            "markets/10000002/orders?order_type=sell&type_id=44992"
+        );
+        assert_eq!(endpoint_with_query("status", &BTreeMap::new()), "status");
+    }
+}
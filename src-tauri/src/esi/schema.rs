@@ -0,0 +1,307 @@
+//! Hand-written response types for the ESI payloads read straight from the
+//! cache (outside the generated client), plus a drift detector. Unknown
+//! fields are tolerated when deserializing, but every fresh response for a
+//! known route is compared with the expected shape and each unexpected or
+//! missing field is logged once per route, so ESI schema changes show up in
+//! the logs before they break parsing.
+
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::cached::route_family;
+
+#[allow(dead_code)] // Mirrors the ESI payload; not every field is read yet.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SkillQueueEntry {
+    pub skill_id: i64,
+    pub finished_level: i64,
+    pub queue_position: i64,
+    pub start_date: Option<String>,
+    pub finish_date: Option<String>,
+    pub training_start_sp: Option<i64>,
+    pub level_start_sp: Option<i64>,
+    pub level_end_sp: Option<i64>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct HomeLocation {
+    pub location_id: Option<i64>,
+    pub location_type: Option<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct JumpClone {
+    pub implants: Vec<i64>,
+    pub jump_clone_id: i64,
+    pub location_id: i64,
+    pub location_type: String,
+    pub name: Option<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Deserialize)]
+pub struct CharacterClones {
+    pub home_location: Option<HomeLocation>,
+    pub jump_clones: Vec<JumpClone>,
+    pub last_clone_jump_date: Option<String>,
+    pub last_station_change_date: Option<String>,
+}
+
+/// Implant type ids of the active clone.
+pub type CharacterImplants = Vec<i64>;
+
+pub enum Shape {
+    Object(&'static [Field]),
+    ArrayOf(&'static Shape),
+    Scalar,
+}
+
+pub struct Field {
+    pub name: &'static str,
+    pub required: bool,
+    pub shape: Shape,
+}
+
+const fn required(name: &'static str, shape: Shape) -> Field {
+    Field {
+        name,
+        required: true,
+        shape,
+    }
+}
+
+const fn optional(name: &'static str, shape: Shape) -> Field {
+    Field {
+        name,
+        required: false,
+        shape,
+    }
+}
+
+const SKILL_QUEUE_ENTRY: &[Field] = &[
+    required("skill_id", Shape::Scalar),
+    required("finished_level", Shape::Scalar),
+    required("queue_position", Shape::Scalar),
+    optional("start_date", Shape::Scalar),
+    optional("finish_date", Shape::Scalar),
+    optional("training_start_sp", Shape::Scalar),
+    optional("level_start_sp", Shape::Scalar),
+    optional("level_end_sp", Shape::Scalar),
+];
+
+const HOME_LOCATION: &[Field] = &[
+    optional("location_id", Shape::Scalar),
+    optional("location_type", Shape::Scalar),
+];
+
+const JUMP_CLONE: &[Field] = &[
+    required("implants", Shape::ArrayOf(&Shape::Scalar)),
+    required("jump_clone_id", Shape::Scalar),
+    required("location_id", Shape::Scalar),
+    required("location_type", Shape::Scalar),
+    optional("name", Shape::Scalar),
+];
+
+const CHARACTER_CLONES: &[Field] = &[
+    optional("home_location", Shape::Object(HOME_LOCATION)),
+    required("jump_clones", Shape::ArrayOf(&Shape::Object(JUMP_CLONE))),
+    optional("last_clone_jump_date", Shape::Scalar),
+    optional("last_station_change_date", Shape::Scalar),
+];
+
+struct EndpointSchema {
+    route: &'static str,
+    shape: Shape,
+    parses: fn(&Value) -> bool,
+}
+
+fn parses<T: DeserializeOwned>(value: &Value) -> bool {
+    T::deserialize(value).is_ok()
+}
+
+const ENDPOINTS: &[EndpointSchema] = &[
+    EndpointSchema {
+        route: "characters/{id}/skillqueue",
+        shape: Shape::ArrayOf(&Shape::Object(SKILL_QUEUE_ENTRY)),
+        parses: parses::<Vec<SkillQueueEntry>>,
+    },
+    EndpointSchema {
+        route: "characters/{id}/clones",
+        shape: Shape::Object(CHARACTER_CLONES),
+        parses: parses::<CharacterClones>,
+    },
+    EndpointSchema {
+        route: "characters/{id}/implants",
+        shape: Shape::ArrayOf(&Shape::Scalar),
+        parses: parses::<CharacterImplants>,
+    },
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Drift {
+    /// A field ESI now sends that the schema does not know.
+    Unexpected(String),
+    /// A required field ESI no longer sends.
+    Missing(String),
+    /// The body no longer deserializes into the typed struct.
+    Unparseable,
+}
+
+/// Differences between `value` and `shape`. Field paths use `[]` for array
+/// elements, e.g. `jump_clones[].name`.
+pub fn detect_drift(value: &Value, shape: &Shape) -> Vec<Drift> {
+    let mut drift = Vec::new();
+    collect_drift("", value, shape, &mut drift);
+    let mut seen = HashSet::new();
+    drift.retain(|d| seen.insert(d.clone()));
+    drift
+}
+
+fn field_path(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", prefix, name)
+    }
+}
+
+fn collect_drift(prefix: &str, value: &Value, shape: &Shape, drift: &mut Vec<Drift>) {
+    match (shape, value) {
+        (Shape::Object(fields), Value::Object(map)) => {
+            for key in map.keys() {
+                if !fields.iter().any(|f| f.name == key) {
+                    drift.push(Drift::Unexpected(field_path(prefix, key)));
+                }
+            }
+            for field in fields.iter() {
+                match map.get(field.name) {
+                    Some(Value::Null) | None if field.required => {
+                        drift.push(Drift::Missing(field_path(prefix, field.name)))
+                    }
+                    Some(child) => {
+                        collect_drift(&field_path(prefix, field.name), child, &field.shape, drift)
+                    }
+                    None => {}
+                }
+            }
+        }
+        (Shape::ArrayOf(element), Value::Array(items)) => {
+            let prefix = format!("{}[]", prefix);
+            for item in items {
+                collect_drift(&prefix, item, element, drift);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn reported() -> &'static Mutex<HashSet<(String, Drift)>> {
+    static REPORTED: OnceLock<Mutex<HashSet<(String, Drift)>>> = OnceLock::new();
+    REPORTED.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Checks a fresh response body against the schema for its route, if one is
+/// known, and logs drift not already reported for that route.
+pub fn check_drift(endpoint_path: &str, body: &str) {
+    let route = route_family(endpoint_path);
+    let Some(schema) = ENDPOINTS.iter().find(|e| e.route == route) else {
+        return;
+    };
+    let Ok(value) = serde_json::from_str::<Value>(body) else {
+        return;
+    };
+
+    let mut drift = detect_drift(&value, &schema.shape);
+    if !(schema.parses)(&value) {
+        drift.push(Drift::Unparseable);
+    }
+
+    let Ok(mut reported) = reported().lock() else {
+        return;
+    };
+    for item in drift {
+        if !reported.insert((route.clone(), item.clone())) {
+            continue;
+        }
+        match item {
+            Drift::Unexpected(field) => {
+                eprintln!("esi drift: {} sent unexpected field `{}`", route, field)
+            }
+            Drift::Missing(field) => {
+                eprintln!("esi drift: {} is missing required field `{}`", route, field)
+            }
+            Drift::Unparseable => {
+                eprintln!("esi drift: {} no longer matches its response type", route)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema(route: &str) -> &'static EndpointSchema {
+        ENDPOINTS.iter().find(|e| e.route == route).unwrap()
+    }
+
+    #[test]
+    fn current_payloads_have_no_drift() {
+        let queue = json!([{
+            "skill_id": 3300,
+            "finished_level": 4,
+            "queue_position": 0,
+            "start_date": "2026-01-01T00:00:00Z",
+            "finish_date": "2026-01-02T00:00:00Z",
+            "training_start_sp": 1000,
+            "level_start_sp": 1000,
+            "level_end_sp": 5000
+        }]);
+        let skill_queue = schema("characters/{id}/skillqueue");
+        assert!(detect_drift(&queue, &skill_queue.shape).is_empty());
+        assert!((skill_queue.parses)(&queue));
+
+        let clones = json!({
+            "home_location": { "location_id": 60003760, "location_type": "station" },
+            "jump_clones": [{
+                "implants": [9899],
+                "jump_clone_id": 1,
+                "location_id": 60003760,
+                "location_type": "station"
+            }]
+        });
+        let character_clones = schema("characters/{id}/clones");
+        assert!(detect_drift(&clones, &character_clones.shape).is_empty());
+        assert!((character_clones.parses)(&clones));
+    }
+
+    #[test]
+    fn reports_unexpected_and_missing_fields_once() {
+        let queue = json!([
+            { "skill_id": 3300, "finished_level": 4, "queue_position": 0, "skill_rank": 1 },
+            { "skill_id": 3301, "queue_position": 1, "skill_rank": 1 }
+        ]);
+        let shape = &schema("characters/{id}/skillqueue").shape;
+        assert_eq!(
+            detect_drift(&queue, shape),
+            vec![
+                Drift::Unexpected("[].skill_rank".into()),
+                Drift::Missing("[].finished_level".into()),
+            ]
+        );
+
+        let clones = json!({ "jump_clones": [{ "implants": [], "jump_clone_id": 1,
+            "location_id": 1, "location_type": "station", "clone_state": "ok" }] });
+        assert_eq!(
+            detect_drift(&clones, &schema("characters/{id}/clones").shape),
+            vec![Drift::Unexpected("jump_clones[].clone_state".into())]
+        );
+    }
+}
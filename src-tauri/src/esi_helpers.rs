@@ -117,6 +117,22 @@ pub async fn get_cached_character_skills(
             .await
             .ok();
 
+        let skill_sp: Vec<(i64, i64)> = skills_data
+            .iter()
+            .map(|(skill_id, _, skillpoints, _)| (*skill_id, *skillpoints))
+            .collect();
+        if let Err(e) = db::sp_history::record_sp_snapshot(
+            pool,
+            character_id,
+            &skill_sp,
+            unallocated_sp,
+            chrono::Utc::now(),
+        )
+        .await
+        {
+            eprintln!("Failed to record SP history for {}: {}", character_id, e);
+        }
+
         Ok(Some(data))
     } else {
         Ok(None)
@@ -272,3 +288,22 @@ pub async fn get_cached_region_info(
     let cache_key = format!("{}:0", endpoint_path);
     esi::fetch_cached(pool, client, &endpoint_path, &cache_key, rate_limits, 0).await
 }
+
+pub async fn get_cached_character_wallet(
+    pool: &db::Pool,
+    client: &reqwest::Client,
+    character_id: i64,
+    rate_limits: &esi::RateLimitStore,
+) -> Result<Option<f64>> {
+    let endpoint_path = format!("characters/{}/wallet", character_id);
+    let cache_key = cache::build_cache_key(&endpoint_path, character_id);
+    esi::fetch_cached::<f64>(
+        pool,
+        client,
+        &endpoint_path,
+        &cache_key,
+        rate_limits,
+        character_id,
+    )
+    .await
+}
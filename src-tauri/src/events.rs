@@ -0,0 +1,330 @@
+//! Every Tauri event exchanged between the backend and the frontend, in one
+//! place: its name, its payload type and which side emits it. Backend code
+//! emits through the helpers here rather than with string literals, and
+//! `list_event_contracts` exposes the table so the frontend listeners can be
+//! checked against it.
+//!
+//! Per-character data goes out on `character:{id}:{channel}`; the payload type
+//! for each channel is fixed by [`CharacterEvent`], so a payload cannot be
+//! sent on the wrong channel.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use typeshare::typeshare;
+
+use crate::auth::reauth::ReauthRequired;
+use crate::commands::accounts::AccountsUpdated;
+use crate::commands::esi_snapshot::CharacterSnapshot;
+use crate::commands::notifications::NotificationResponse;
+use crate::db::watchlist::WatchedCharacter;
+use crate::esi::RateLimitWarning;
+use crate::refresh::events::{
+    AttributesPayload, ClonesPayload, LocationPayload, OverviewRow, QueuePayload, SkillsPayload,
+};
+use crate::skill_plans::optimization::OptimizationProgress;
+use crate::startup::StartupStatus;
+
+pub const STARTUP_COMPLETE: &str = "startup-complete";
+pub const STARTUP_PROGRESS: &str = "startup:progress";
+pub const AUTH_SUCCESS: &str = "auth-success";
+pub const AUTH_ERROR: &str = "auth-error";
+pub const AUTH_REAUTH_REQUIRED: &str = "auth:reauth-required";
+pub const NOTIFICATIONS_CHANGED: &str = "notifications:changed";
+pub const NOTIFICATION_DATA_UPDATED: &str = "notification:data-updated";
+pub const WATCHLIST_CHANGED: &str = "watchlist:changed";
+pub const RATE_LIMIT_WARNING: &str = "rate-limit-warning";
+pub const OPTIMIZATION_PROGRESS: &str = "optimization-progress";
+pub const ACCOUNTS_UPDATED: &str = "accounts:updated";
+pub const ESI_SNAPSHOT_UPDATED: &str = "esi-snapshot:updated";
+/// Emitted by the deep-link plugin with the opened URL as its payload.
+pub const DEEP_LINK_NEW_URL: &str = "deep-link://new-url";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharacterChannel {
+    Queue,
+    Skills,
+    Attributes,
+    Location,
+    Clones,
+    Overview,
+}
+
+impl CharacterChannel {
+    pub const ALL: [CharacterChannel; 6] = [
+        CharacterChannel::Queue,
+        CharacterChannel::Skills,
+        CharacterChannel::Attributes,
+        CharacterChannel::Location,
+        CharacterChannel::Clones,
+        CharacterChannel::Overview,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CharacterChannel::Queue => "queue",
+            CharacterChannel::Skills => "skills",
+            CharacterChannel::Attributes => "attributes",
+            CharacterChannel::Location => "location",
+            CharacterChannel::Clones => "clones",
+            CharacterChannel::Overview => "overview",
+        }
+    }
+
+    fn payload_type(&self) -> &'static str {
+        match self {
+            CharacterChannel::Queue => "QueuePayload",
+            CharacterChannel::Skills => "SkillsPayload",
+            CharacterChannel::Attributes => "AttributesPayload",
+            CharacterChannel::Location => "LocationPayload",
+            CharacterChannel::Clones => "ClonesPayload",
+            CharacterChannel::Overview => "OverviewRow | null",
+        }
+    }
+}
+
+pub fn character_event_name(character_id: i64, channel: CharacterChannel) -> String {
+    format!("character:{}:{}", character_id, channel.as_str())
+}
+
+/// A payload that is emitted on one per-character channel.
+pub trait CharacterEvent: Serialize + Clone {
+    const CHANNEL: CharacterChannel;
+}
+
+impl CharacterEvent for QueuePayload {
+    const CHANNEL: CharacterChannel = CharacterChannel::Queue;
+}
+
+impl CharacterEvent for SkillsPayload {
+    const CHANNEL: CharacterChannel = CharacterChannel::Skills;
+}
+
+impl CharacterEvent for AttributesPayload {
+    const CHANNEL: CharacterChannel = CharacterChannel::Attributes;
+}
+
+impl CharacterEvent for LocationPayload {
+    const CHANNEL: CharacterChannel = CharacterChannel::Location;
+}
+
+impl CharacterEvent for ClonesPayload {
+    const CHANNEL: CharacterChannel = CharacterChannel::Clones;
+}
+
+/// `null` when the character has no queue data yet.
+impl CharacterEvent for Option<OverviewRow> {
+    const CHANNEL: CharacterChannel = CharacterChannel::Overview;
+}
+
+pub fn emit_character<P: CharacterEvent>(
+    app: &AppHandle,
+    character_id: i64,
+    payload: &P,
+) -> tauri::Result<()> {
+    app.emit(&character_event_name(character_id, P::CHANNEL), payload)
+}
+
+pub fn emit_startup_complete(app: &AppHandle) -> tauri::Result<()> {
+    app.emit(STARTUP_COMPLETE, ())
+}
+
+pub fn emit_startup_progress(app: &AppHandle, status: &StartupStatus) -> tauri::Result<()> {
+    app.emit(STARTUP_PROGRESS, status)
+}
+
+pub fn emit_auth_success(app: &AppHandle, character_id: i64) -> tauri::Result<()> {
+    app.emit(AUTH_SUCCESS, character_id)
+}
+
+pub fn emit_auth_error(app: &AppHandle, message: &str) -> tauri::Result<()> {
+    app.emit(AUTH_ERROR, message)
+}
+
+pub fn emit_reauth_required(app: &AppHandle, required: &ReauthRequired) -> tauri::Result<()> {
+    app.emit(AUTH_REAUTH_REQUIRED, required)
+}
+
+pub fn emit_notifications_changed(
+    app: &AppHandle,
+    notifications: &[NotificationResponse],
+) -> tauri::Result<()> {
+    app.emit(NOTIFICATIONS_CHANGED, notifications)
+}
+
+pub fn emit_watchlist_changed(app: &AppHandle, watched: &[WatchedCharacter]) -> tauri::Result<()> {
+    app.emit(WATCHLIST_CHANGED, watched)
+}
+
+pub fn emit_rate_limit_warning(app: &AppHandle, warning: &RateLimitWarning) -> tauri::Result<()> {
+    app.emit(RATE_LIMIT_WARNING, warning)
+}
+
+pub fn emit_optimization_progress(
+    app: &AppHandle,
+    progress: &OptimizationProgress,
+) -> tauri::Result<()> {
+    app.emit(OPTIMIZATION_PROGRESS, progress)
+}
+
+pub fn emit_accounts_updated(app: &AppHandle, updated: &AccountsUpdated) -> tauri::Result<()> {
+    app.emit(ACCOUNTS_UPDATED, updated)
+}
+
+pub fn emit_esi_snapshot_updated(
+    app: &AppHandle,
+    snapshots: &[CharacterSnapshot],
+) -> tauri::Result<()> {
+    app.emit(ESI_SNAPSHOT_UPDATED, snapshots)
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EventDirection {
+    BackendToFrontend,
+    FrontendToBackend,
+    /// Emitted by a Tauri plugin and consumed by the backend.
+    Plugin,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct EventContract {
+    /// Event name; per-character channels use `{characterId}` as placeholder.
+    pub name: String,
+    pub direction: EventDirection,
+    /// Generated TypeScript type of the payload (`null` for no payload).
+    pub payload_type: String,
+    pub description: String,
+}
+
+fn contract(
+    name: &str,
+    direction: EventDirection,
+    payload_type: &str,
+    description: &str,
+) -> EventContract {
+    EventContract {
+        name: name.to_string(),
+        direction,
+        payload_type: payload_type.to_string(),
+        description: description.to_string(),
+    }
+}
+
+pub fn contracts() -> Vec<EventContract> {
+    let mut contracts = vec![
+        contract(
+            STARTUP_COMPLETE,
+            EventDirection::BackendToFrontend,
+            "null",
+            "Startup reached the ready phase.",
+        ),
+        contract(
+            STARTUP_PROGRESS,
+            EventDirection::BackendToFrontend,
+            "StartupStatus",
+            "Startup moved to a new phase or another character finished its first refresh.",
+        ),
+        contract(
+            AUTH_SUCCESS,
+            EventDirection::BackendToFrontend,
+            "number",
+            "A character finished the SSO flow; payload is its character id.",
+        ),
+        contract(
+            AUTH_ERROR,
+            EventDirection::BackendToFrontend,
+            "string",
+            "The SSO callback failed; payload is the error message.",
+        ),
+        contract(
+            AUTH_REAUTH_REQUIRED,
+            EventDirection::BackendToFrontend,
+            "ReauthRequired",
+            "A character's token lacks scopes for enabled features; re-login with reauthorize_character.",
+        ),
+        contract(
+            NOTIFICATIONS_CHANGED,
+            EventDirection::BackendToFrontend,
+            "NotificationResponse[]",
+            "Full snapshot of notifications after any change.",
+        ),
+        contract(
+            NOTIFICATION_DATA_UPDATED,
+            EventDirection::FrontendToBackend,
+            "DataUpdatedPayload",
+            "Asks the backend to run notification checks for fresh data.",
+        ),
+        contract(
+            WATCHLIST_CHANGED,
+            EventDirection::BackendToFrontend,
+            "WatchedCharacter[]",
+            "A watched character changed corporation or alliance; full watchlist.",
+        ),
+        contract(
+            RATE_LIMIT_WARNING,
+            EventDirection::BackendToFrontend,
+            "RateLimitWarning",
+            "ESI's error budget is low; requests are slowed or refused until it resets.",
+        ),
+        contract(
+            OPTIMIZATION_PROGRESS,
+            EventDirection::BackendToFrontend,
+            "OptimizationProgress",
+            "Remap search segments evaluated so far for the optimization with this id.",
+        ),
+        contract(
+            ACCOUNTS_UPDATED,
+            EventDirection::BackendToFrontend,
+            "AccountsUpdated",
+            "Fresh accounts and characters after a cached response was served.",
+        ),
+        contract(
+            ESI_SNAPSHOT_UPDATED,
+            EventDirection::BackendToFrontend,
+            "CharacterSnapshot[]",
+            "The whole fresh ESI snapshot after a cached one was served; replaces it.",
+        ),
+        contract(
+            DEEP_LINK_NEW_URL,
+            EventDirection::Plugin,
+            "string",
+            "An eveauth-skillmon:// URL was opened; carries the SSO callback.",
+        ),
+    ];
+    for channel in CharacterChannel::ALL {
+        contracts.push(contract(
+            &format!("character:{{characterId}}:{}", channel.as_str()),
+            EventDirection::BackendToFrontend,
+            channel.payload_type(),
+            "Per-character data pushed by the refresher.",
+        ));
+    }
+    contracts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn contract_names_are_unique() {
+        let contracts = contracts();
+        let names: HashSet<&str> = contracts.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names.len(), contracts.len());
+    }
+
+    #[test]
+    fn character_channels_follow_the_naming_scheme() {
+        assert_eq!(
+            character_event_name(12345, QueuePayload::CHANNEL),
+            "character:12345:queue"
+        );
+        assert_eq!(
+            character_event_name(1, <Option<OverviewRow>>::CHANNEL),
+            "character:1:overview"
+        );
+    }
+}
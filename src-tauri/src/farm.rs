@@ -0,0 +1,534 @@
+//! Skill farm rotation advice: which of a farm character's clones to train in
+//! until the next extraction, weighing the extra SP from better attribute
+//! implants against the chance of losing them in risky space.
+//!
+//! A farm is assumed to be remapped for what it trains, so the two highest
+//! base attributes are taken as the training pair. ISK figures come from the
+//! user-entered [`FarmPrices`]; with no prices the advice falls back to raw SP.
+//! [`extraction_summary`] covers the steady-state side: how much a character
+//! can extract now and per month, and whether that pays for its Omega time.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use typeshare::typeshare;
+
+use crate::db::farm::{FarmPrices, FarmSettings};
+use crate::ts_types::i64_ts;
+use crate::{db, utils};
+
+/// SP removed by one Skill Extractor and granted by one Large Skill Injector.
+const SP_PER_EXTRACTION: f64 = 500_000.0;
+
+/// Allocated SP a Skill Extractor can never take a character below.
+const EXTRACTION_FLOOR_SP: i64 = 5_500_000;
+
+/// PLEX for one month of Omega bought in game.
+pub(crate) const OMEGA_PLEX_PER_MONTH: f64 = 500.0;
+
+const HOURS_PER_MONTH: f64 = 30.0 * 24.0;
+
+/// Assumed chance per cycle of getting podded while living in that space.
+const WORMHOLE_POD_LOSS_RISK: f64 = 0.10;
+const NULLSEC_POD_LOSS_RISK: f64 = 0.05;
+
+/// (name, attribute bonus dogma id), in the order ties are broken.
+const ATTRIBUTES: [(&str, i64); 5] = [
+    ("intelligence", 176),
+    ("memory", 177),
+    ("perception", 178),
+    ("willpower", 179),
+    ("charisma", 175),
+];
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct FarmCloneOption {
+    pub clone_id: i64_ts,
+    pub name: Option<String>,
+    pub location_name: Option<String>,
+    pub is_current: bool,
+    pub primary_bonus: i64_ts,
+    pub secondary_bonus: i64_ts,
+    pub sp_per_hour: f64,
+    pub cycle_sp: i64_ts,
+    /// Pod-loss risk times the value of the clone's implants.
+    pub expected_loss_isk: f64,
+    /// Extraction profit for the cycle minus `expected_loss_isk`; `None`
+    /// without injector/extractor prices.
+    pub net_isk: Option<f64>,
+}
+
+/// +5 versus +4 attribute implants over one cycle.
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct FarmImplantMath {
+    pub extra_sp_per_cycle: i64_ts,
+    pub extra_isk_per_cycle: Option<f64>,
+    pub set_cost_difference: f64,
+    /// Cycles of extra output needed to cover the price difference, after the
+    /// expected loss of the dearer set.
+    pub payback_cycles: Option<f64>,
+    pub plus_five_worth_it: Option<bool>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct FarmRotationAdvice {
+    pub character_id: i64_ts,
+    pub primary_attribute: String,
+    pub secondary_attribute: String,
+    pub next_extraction_at: Option<i64_ts>,
+    pub cycle_hours: f64,
+    pub pod_loss_risk: f64,
+    pub recommended_clone_id: Option<i64_ts>,
+    pub reason: String,
+    pub options: Vec<FarmCloneOption>,
+    pub implant_math: FarmImplantMath,
+}
+
+/// What a character yields as an extraction farm. ISK figures are `None`
+/// until the prices they need are set.
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtractionSummary {
+    pub character_id: i64_ts,
+    /// Allocated SP; unallocated SP cannot be extracted.
+    pub total_sp: i64_ts,
+    pub sp_per_hour: f64,
+    /// SP above the 5.5M extraction floor.
+    pub extractable_sp: i64_ts,
+    pub extractors_now: i64_ts,
+    pub monthly_sp: i64_ts,
+    pub extractors_per_month: f64,
+    /// Injector price minus extractor price.
+    pub profit_per_extraction: Option<f64>,
+    pub monthly_profit_isk: Option<f64>,
+    /// 500 PLEX at the set PLEX price.
+    pub omega_cost_isk: Option<f64>,
+    pub monthly_net_isk: Option<f64>,
+    /// SP/hour at which extraction profit just pays for Omega.
+    pub break_even_sp_per_hour: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CloneCandidate {
+    pub clone_id: i64,
+    pub name: Option<String>,
+    pub location_name: Option<String>,
+    pub is_current: bool,
+    /// Attribute bonus dogma id -> total bonus from the clone's implants.
+    pub bonuses: HashMap<i64, i64>,
+}
+
+/// Inputs for [`advise`] that describe the character rather than its clones.
+#[derive(Debug, Clone)]
+pub struct FarmProfile {
+    pub character_id: i64,
+    /// Attribute bonus dogma id -> value without implants.
+    pub base_attributes: HashMap<i64, i64>,
+    pub is_omega: bool,
+    pub now: i64,
+}
+
+pub fn pod_loss_risk(settings: &FarmSettings) -> f64 {
+    let mut survive = 1.0;
+    if settings.wormhole_risk {
+        survive *= 1.0 - WORMHOLE_POD_LOSS_RISK;
+    }
+    if settings.nullsec_risk {
+        survive *= 1.0 - NULLSEC_POD_LOSS_RISK;
+    }
+    1.0 - survive
+}
+
+/// Minutes from `now` to the scheduled extraction, or one full cycle when no
+/// extraction is scheduled (or the scheduled one has passed).
+fn cycle_minutes(settings: &FarmSettings, now: i64) -> f64 {
+    match settings.next_extraction_at {
+        Some(at) if at > now => (at - now) as f64 / 60.0,
+        _ => settings.cycle_days.max(1) as f64 * 24.0 * 60.0,
+    }
+}
+
+fn isk_per_sp(prices: &FarmPrices) -> Option<f64> {
+    (prices.large_skill_injector > 0.0 && prices.skill_extractor > 0.0)
+        .then(|| (prices.large_skill_injector - prices.skill_extractor) / SP_PER_EXTRACTION)
+}
+
+fn set_value(primary_bonus: i64, secondary_bonus: i64, prices: &FarmPrices) -> f64 {
+    match primary_bonus.min(secondary_bonus) {
+        b if b >= 5 => prices.plus_five_set,
+        4 => prices.plus_four_set,
+        _ => 0.0,
+    }
+}
+
+fn training_pair(base: &HashMap<i64, i64>) -> ((&'static str, i64), (&'static str, i64)) {
+    let mut ranked: Vec<(usize, (&'static str, i64))> =
+        ATTRIBUTES.iter().copied().enumerate().collect();
+    ranked.sort_by_key(|(order, (_, id))| (-base.get(id).copied().unwrap_or(0), *order));
+    (ranked[0].1, ranked[1].1)
+}
+
+pub fn advise(
+    profile: &FarmProfile,
+    settings: &FarmSettings,
+    prices: &FarmPrices,
+    clones: &[CloneCandidate],
+) -> FarmRotationAdvice {
+    let ((primary_name, primary_id), (secondary_name, secondary_id)) =
+        training_pair(&profile.base_attributes);
+    let base_primary = profile
+        .base_attributes
+        .get(&primary_id)
+        .copied()
+        .unwrap_or(0);
+    let base_secondary = profile
+        .base_attributes
+        .get(&secondary_id)
+        .copied()
+        .unwrap_or(0);
+
+    let minutes = cycle_minutes(settings, profile.now);
+    let risk = pod_loss_risk(settings);
+    let isk_per_sp = isk_per_sp(prices);
+    let cycle_sp = |primary_bonus: i64, secondary_bonus: i64| {
+        utils::calculate_sp_per_minute(
+            base_primary + primary_bonus,
+            base_secondary + secondary_bonus,
+            profile.is_omega,
+        ) * minutes
+    };
+
+    let options: Vec<FarmCloneOption> = clones
+        .iter()
+        .map(|clone| {
+            let primary_bonus = clone.bonuses.get(&primary_id).copied().unwrap_or(0);
+            let secondary_bonus = clone.bonuses.get(&secondary_id).copied().unwrap_or(0);
+            let sp = cycle_sp(primary_bonus, secondary_bonus);
+            let expected_loss_isk = risk * set_value(primary_bonus, secondary_bonus, prices);
+            FarmCloneOption {
+                clone_id: clone.clone_id,
+                name: clone.name.clone(),
+                location_name: clone.location_name.clone(),
+                is_current: clone.is_current,
+                primary_bonus,
+                secondary_bonus,
+                sp_per_hour: sp / minutes * 60.0,
+                cycle_sp: sp as i64,
+                expected_loss_isk,
+                net_isk: isk_per_sp.map(|rate| sp * rate - expected_loss_isk),
+            }
+        })
+        .collect();
+
+    let best = options.iter().max_by(|a, b| {
+        let score = |o: &FarmCloneOption| o.net_isk.unwrap_or(o.cycle_sp as f64);
+        score(a)
+            .total_cmp(&score(b))
+            .then(a.is_current.cmp(&b.is_current))
+    });
+
+    let reason = match best {
+        None => "No clones are known for this character yet; refresh it first.".to_string(),
+        Some(best) if best.is_current => {
+            "Stay in the current clone; no other clone does better this cycle.".to_string()
+        }
+        Some(best) => {
+            let label = best
+                .name
+                .clone()
+                .or_else(|| best.location_name.clone())
+                .unwrap_or_else(|| format!("clone {}", best.clone_id));
+            match best.net_isk {
+                Some(net) => format!(
+                    "Jump to {} (+{}/+{}): about {:.0} ISK net this cycle after pod-loss risk.",
+                    label, best.primary_bonus, best.secondary_bonus, net
+                ),
+                None => format!(
+                    "Jump to {} (+{}/+{}) for the most SP; set injector and extractor prices to weigh pod-loss risk.",
+                    label, best.primary_bonus, best.secondary_bonus
+                ),
+            }
+        }
+    };
+
+    let extra_sp = cycle_sp(5, 5) - cycle_sp(4, 4);
+    let extra_isk = isk_per_sp.map(|rate| extra_sp * rate);
+    let set_cost_difference = prices.plus_five_set - prices.plus_four_set;
+    let extra_loss = risk * set_cost_difference;
+    let (payback_cycles, plus_five_worth_it) = match extra_isk {
+        Some(extra) if set_cost_difference > 0.0 => {
+            let net = extra - extra_loss;
+            (
+                (net > 0.0).then(|| set_cost_difference / net),
+                Some(net > 0.0),
+            )
+        }
+        _ => (None, None),
+    };
+
+    FarmRotationAdvice {
+        character_id: profile.character_id,
+        primary_attribute: primary_name.to_string(),
+        secondary_attribute: secondary_name.to_string(),
+        next_extraction_at: settings.next_extraction_at,
+        cycle_hours: minutes / 60.0,
+        pod_loss_risk: risk,
+        recommended_clone_id: best.map(|b| b.clone_id),
+        reason,
+        options,
+        implant_math: FarmImplantMath {
+            extra_sp_per_cycle: extra_sp as i64,
+            extra_isk_per_cycle: extra_isk,
+            set_cost_difference,
+            payback_cycles,
+            plus_five_worth_it,
+        },
+    }
+}
+
+pub fn summarize_extraction(
+    character_id: i64,
+    total_sp: i64,
+    sp_per_hour: f64,
+    prices: &FarmPrices,
+) -> ExtractionSummary {
+    let extractable_sp = (total_sp - EXTRACTION_FLOOR_SP).max(0);
+    let monthly_sp = sp_per_hour * HOURS_PER_MONTH;
+    let extractors_per_month = monthly_sp / SP_PER_EXTRACTION;
+
+    let profit_per_extraction = isk_per_sp(prices).map(|rate| rate * SP_PER_EXTRACTION);
+    let monthly_profit_isk = profit_per_extraction.map(|profit| profit * extractors_per_month);
+    let omega_cost_isk = (prices.plex > 0.0).then(|| prices.plex * OMEGA_PLEX_PER_MONTH);
+    let monthly_net_isk = monthly_profit_isk
+        .zip(omega_cost_isk)
+        .map(|(profit, omega)| profit - omega);
+    let break_even_sp_per_hour = isk_per_sp(prices)
+        .filter(|rate| *rate > 0.0)
+        .zip(omega_cost_isk)
+        .map(|(rate, omega)| omega / rate / HOURS_PER_MONTH);
+
+    ExtractionSummary {
+        character_id,
+        total_sp,
+        sp_per_hour,
+        extractable_sp,
+        extractors_now: extractable_sp / SP_PER_EXTRACTION as i64,
+        monthly_sp: monthly_sp as i64,
+        extractors_per_month,
+        profit_per_extraction,
+        monthly_profit_isk,
+        omega_cost_isk,
+        monthly_net_isk,
+        break_even_sp_per_hour,
+    }
+}
+
+async fn implant_bonuses(pool: &db::Pool, implant_ids: &[i64]) -> Result<HashMap<i64, i64>> {
+    let per_implant = db::get_implant_attribute_bonuses(pool, implant_ids).await?;
+    let mut totals: HashMap<i64, i64> = HashMap::new();
+    for implant_id in implant_ids {
+        for (attribute_id, bonus) in per_implant.get(implant_id).into_iter().flatten() {
+            *totals.entry(*attribute_id).or_default() += bonus;
+        }
+    }
+    Ok(totals)
+}
+
+pub async fn rotation_advice(pool: &db::Pool, character_id: i64) -> Result<FarmRotationAdvice> {
+    let Some(character) = db::get_character(pool, character_id).await? else {
+        bail!("Character {} not found", character_id);
+    };
+    let Some(attributes) = db::get_character_attributes(pool, character_id).await? else {
+        bail!("No attributes stored for this character yet; refresh it first");
+    };
+
+    let mut clones = Vec::new();
+    for clone in db::get_character_clones(pool, character_id).await? {
+        let implant_ids: Vec<i64> = db::get_clone_implants(pool, clone.id)
+            .await?
+            .into_iter()
+            .map(|i| i.implant_type_id)
+            .collect();
+        clones.push(CloneCandidate {
+            clone_id: clone.id,
+            name: clone.name,
+            location_name: clone.location_name,
+            is_current: clone.is_current,
+            bonuses: implant_bonuses(pool, &implant_ids).await?,
+        });
+    }
+
+    // Stored attributes include the active clone's implants.
+    let current_bonuses = clones
+        .iter()
+        .find(|c| c.is_current)
+        .map(|c| c.bonuses.clone())
+        .unwrap_or_default();
+    let stored = [
+        (175, attributes.charisma),
+        (176, attributes.intelligence),
+        (177, attributes.memory),
+        (178, attributes.perception),
+        (179, attributes.willpower),
+    ];
+    let base_attributes = stored
+        .into_iter()
+        .map(|(id, value)| (id, value - current_bonuses.get(&id).copied().unwrap_or(0)))
+        .collect();
+
+    let settings = db::farm::get_farm_settings(pool, character_id).await?;
+    let prices = db::farm::get_farm_prices(pool).await?;
+    let profile = FarmProfile {
+        character_id,
+        base_attributes,
+        is_omega: character.is_omega,
+        now: chrono::Utc::now().timestamp(),
+    };
+
+    Ok(advise(&profile, &settings, &prices, &clones))
+}
+
+/// The character's extraction yield, training at its current attributes in
+/// the two highest of them.
+pub async fn extraction_summary(pool: &db::Pool, character_id: i64) -> Result<ExtractionSummary> {
+    let Some(character) = db::get_character(pool, character_id).await? else {
+        bail!("Character {} not found", character_id);
+    };
+    let Some(attributes) = db::get_character_attributes(pool, character_id).await? else {
+        bail!("No attributes stored for this character yet; refresh it first");
+    };
+
+    let total_sp: i64 = db::get_character_skills(pool, character_id)
+        .await?
+        .iter()
+        .map(|s| s.skillpoints_in_skill)
+        .sum();
+    let current: HashMap<i64, i64> = HashMap::from([
+        (175, attributes.charisma),
+        (176, attributes.intelligence),
+        (177, attributes.memory),
+        (178, attributes.perception),
+        (179, attributes.willpower),
+    ]);
+    let ((_, primary_id), (_, secondary_id)) = training_pair(&current);
+    let sp_per_hour = utils::calculate_sp_per_minute(
+        current[&primary_id],
+        current[&secondary_id],
+        character.is_omega,
+    ) * 60.0;
+
+    let prices = db::farm::get_farm_prices(pool).await?;
+    Ok(summarize_extraction(
+        character_id,
+        total_sp,
+        sp_per_hour,
+        &prices,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> FarmProfile {
+        FarmProfile {
+            character_id: 1,
+            // Int/Mem farm remap: 27/21 before implants.
+            base_attributes: HashMap::from([(175, 17), (176, 27), (177, 21), (178, 17), (179, 17)]),
+            is_omega: true,
+            now: 0,
+        }
+    }
+
+    fn clone(clone_id: i64, bonus: i64, is_current: bool) -> CloneCandidate {
+        CloneCandidate {
+            clone_id,
+            name: Some(format!("+{}", bonus)),
+            location_name: None,
+            is_current,
+            bonuses: HashMap::from([(176, bonus), (177, bonus)]),
+        }
+    }
+
+    fn prices() -> FarmPrices {
+        FarmPrices {
+            large_skill_injector: 900_000_000.0,
+            skill_extractor: 400_000_000.0,
+            plus_four_set: 100_000_000.0,
+            plus_five_set: 1_000_000_000.0,
+            plex: 5_000_000.0,
+        }
+    }
+
+    #[test]
+    fn safe_space_prefers_the_best_implants() {
+        let settings = FarmSettings::default_for(1);
+        let clones = [clone(10, 4, true), clone(11, 5, false)];
+
+        let advice = advise(&profile(), &settings, &prices(), &clones);
+
+        assert_eq!(advice.primary_attribute, "intelligence");
+        assert_eq!(advice.secondary_attribute, "memory");
+        assert_eq!(advice.recommended_clone_id, Some(11));
+        assert_eq!(advice.pod_loss_risk, 0.0);
+        // +1/+1 is 1.5 SP/min over 30 days.
+        assert_eq!(advice.implant_math.extra_sp_per_cycle, 64_800);
+    }
+
+    #[test]
+    fn wormhole_risk_can_make_plus_fives_not_worth_it() {
+        let mut settings = FarmSettings::default_for(1);
+        settings.wormhole_risk = true;
+        let clones = [clone(10, 4, true), clone(11, 5, false)];
+
+        let advice = advise(&profile(), &settings, &prices(), &clones);
+
+        // 64,800 SP is ~64.8M ISK of extra output against 90M expected extra loss.
+        assert_eq!(advice.recommended_clone_id, Some(10));
+        assert_eq!(advice.implant_math.plus_five_worth_it, Some(false));
+        assert_eq!(advice.implant_math.payback_cycles, None);
+    }
+
+    #[test]
+    fn without_prices_falls_back_to_sp() {
+        let mut settings = FarmSettings::default_for(1);
+        settings.nullsec_risk = true;
+        let clones = [clone(10, 4, true), clone(11, 5, false)];
+
+        let advice = advise(&profile(), &settings, &FarmPrices::default(), &clones);
+
+        assert_eq!(advice.recommended_clone_id, Some(11));
+        assert!(advice.options.iter().all(|o| o.net_isk.is_none()));
+        assert_eq!(advice.implant_math.plus_five_worth_it, None);
+    }
+
+    #[test]
+    fn extraction_summary_counts_sp_above_the_floor() {
+        // 2,700 SP/hour is a +5 Int/Mem farm: 1,944,000 SP a month.
+        let summary = summarize_extraction(1, 7_000_000, 2_700.0, &prices());
+
+        assert_eq!(summary.extractable_sp, 1_500_000);
+        assert_eq!(summary.extractors_now, 3);
+        assert_eq!(summary.monthly_sp, 1_944_000);
+        assert_eq!(summary.profit_per_extraction, Some(500_000_000.0));
+        // 3.888 extractions at 500M against 2.5B of PLEX.
+        assert_eq!(summary.omega_cost_isk, Some(2_500_000_000.0));
+        assert!((summary.monthly_net_isk.unwrap() + 556_000_000.0).abs() < 1.0);
+        assert!((summary.break_even_sp_per_hour.unwrap() - 3_472.2).abs() < 0.1);
+    }
+
+    #[test]
+    fn extraction_summary_below_the_floor_and_without_prices() {
+        let summary = summarize_extraction(1, 5_000_000, 1_800.0, &FarmPrices::default());
+
+        assert_eq!(summary.extractable_sp, 0);
+        assert_eq!(summary.extractors_now, 0);
+        assert_eq!(summary.monthly_profit_isk, None);
+        assert_eq!(summary.monthly_net_isk, None);
+        assert_eq!(summary.break_even_sp_per_hour, None);
+    }
+}
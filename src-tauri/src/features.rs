@@ -8,21 +8,30 @@ use typeshare::typeshare;
 pub enum FeatureId {
     #[serde(rename = "contracts")]
     Contracts,
+    #[serde(rename = "fittings")]
+    Fittings,
     #[serde(rename = "industry")]
     Industry,
+    #[serde(rename = "in-game-windows")]
+    InGameWindows,
     #[serde(rename = "locations")]
     Locations,
     #[serde(rename = "waypoints")]
     Waypoints,
+    #[serde(rename = "wallet")]
+    Wallet,
 }
 
 impl FeatureId {
     pub fn as_str(&self) -> &'static str {
         match self {
             FeatureId::Contracts => "contracts",
+            FeatureId::Fittings => "fittings",
             FeatureId::Industry => "industry",
+            FeatureId::InGameWindows => "in-game-windows",
             FeatureId::Locations => "locations",
             FeatureId::Waypoints => "waypoints",
+            FeatureId::Wallet => "wallet",
         }
     }
 
@@ -44,6 +53,41 @@ impl std::str::FromStr for FeatureId {
     }
 }
 
+/// Per-character data a user can switch off for an alt, whatever scopes its
+/// token carries. Refresh loops and notification checkers skip disabled ones.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum CharacterModule {
+    Wallet,
+    Clones,
+    Location,
+}
+
+impl CharacterModule {
+    pub const ALL: [CharacterModule; 3] = [
+        CharacterModule::Wallet,
+        CharacterModule::Clones,
+        CharacterModule::Location,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CharacterModule::Wallet => "wallet",
+            CharacterModule::Clones => "clones",
+            CharacterModule::Location => "location",
+        }
+    }
+}
+
+impl std::str::FromStr for CharacterModule {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_plain::from_str(s).map_err(|_| ())
+    }
+}
+
 #[typeshare]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OptionalFeature {
@@ -61,6 +105,12 @@ pub fn get_optional_features() -> Vec<OptionalFeature> {
             description: "View your character's contracts and their details.".to_string(),
             scopes: vec![EsiScope::ReadCharacterContractsV1],
         },
+        OptionalFeature {
+            id: FeatureId::Fittings,
+            name: "Fittings".to_string(),
+            description: "View your saved fittings and save imported fits to the game.".to_string(),
+            scopes: vec![EsiScope::ReadFittingsV1, EsiScope::WriteFittingsV1],
+        },
         OptionalFeature {
             id: FeatureId::Industry,
             name: "Industry".to_string(),
@@ -68,6 +118,12 @@ pub fn get_optional_features() -> Vec<OptionalFeature> {
                 .to_string(),
             scopes: vec![EsiScope::ReadCharacterIndustryJobsV1],
         },
+        OptionalFeature {
+            id: FeatureId::InGameWindows,
+            name: "In-game windows".to_string(),
+            description: "Open market and info windows in the EVE client".to_string(),
+            scopes: vec![EsiScope::OpenWindowV1],
+        },
         OptionalFeature {
             id: FeatureId::Locations,
             name: "Locations".to_string(),
@@ -85,5 +141,154 @@ pub fn get_optional_features() -> Vec<OptionalFeature> {
             description: "Set waypoints in the EVE client".to_string(),
             scopes: vec![EsiScope::WriteWaypointV1],
         },
+        OptionalFeature {
+            id: FeatureId::Wallet,
+            name: "Wallet".to_string(),
+            description: "Read wallet balances to estimate how long they can pay for Omega."
+                .to_string(),
+            scopes: vec![EsiScope::ReadCharacterWalletV1],
+        },
     ]
 }
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FeatureScopeStatus {
+    /// Enabled and every scope it needs is on the token.
+    Granted,
+    /// Enabled but the token lacks some of its scopes.
+    Missing,
+    /// Disabled, yet the token still carries some of its scopes.
+    Unused,
+    /// Disabled and none of its scopes were requested.
+    NotRequested,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct FeatureScopeAudit {
+    pub feature_id: FeatureId,
+    pub name: String,
+    pub enabled: bool,
+    pub status: FeatureScopeStatus,
+    pub granted_scopes: Vec<String>,
+    pub missing_scopes: Vec<String>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct ScopeAudit {
+    pub granted_scopes: Vec<String>,
+    pub missing_base_scopes: Vec<String>,
+    pub features: Vec<FeatureScopeAudit>,
+    /// Granted scopes that neither the base set nor an enabled feature needs.
+    pub unneeded_scopes: Vec<String>,
+    /// The smallest scope set covering the base scopes and enabled features,
+    /// i.e. what a re-authorization would request.
+    pub minimal_scopes: Vec<String>,
+}
+
+/// Compare a token's granted scopes with what the app needs given the enabled
+/// features.
+pub fn audit_scopes(granted: &[String], enabled: &[FeatureId]) -> ScopeAudit {
+    let has = |scope: &EsiScope| granted.iter().any(|g| g == scope.as_str());
+    let names = |scopes: &[EsiScope]| -> Vec<String> {
+        scopes.iter().map(|s| s.as_str().to_string()).collect()
+    };
+
+    let mut needed: Vec<EsiScope> = crate::esi::BASE_SCOPES.to_vec();
+    let mut features = Vec::new();
+    for feature in get_optional_features() {
+        let is_enabled = enabled.contains(&feature.id);
+        let (have, lack): (Vec<EsiScope>, Vec<EsiScope>) =
+            feature.scopes.iter().copied().partition(|s| has(s));
+        let status = match (is_enabled, lack.is_empty(), have.is_empty()) {
+            (true, true, _) => FeatureScopeStatus::Granted,
+            (true, false, _) => FeatureScopeStatus::Missing,
+            (false, _, false) => FeatureScopeStatus::Unused,
+            (false, _, true) => FeatureScopeStatus::NotRequested,
+        };
+        if is_enabled {
+            for scope in &feature.scopes {
+                if !needed.contains(scope) {
+                    needed.push(*scope);
+                }
+            }
+        }
+        features.push(FeatureScopeAudit {
+            feature_id: feature.id,
+            name: feature.name,
+            enabled: is_enabled,
+            status,
+            granted_scopes: names(&have),
+            missing_scopes: names(&lack),
+        });
+    }
+
+    let missing_base: Vec<EsiScope> = crate::esi::BASE_SCOPES
+        .iter()
+        .copied()
+        .filter(|s| !has(s))
+        .collect();
+    let mut granted_scopes = granted.to_vec();
+    granted_scopes.sort();
+    let mut unneeded_scopes: Vec<String> = granted_scopes
+        .iter()
+        .filter(|g| !needed.iter().any(|s| s.as_str() == g.as_str()))
+        .cloned()
+        .collect();
+    unneeded_scopes.dedup();
+    let mut minimal_scopes = names(&needed);
+    minimal_scopes.sort();
+
+    ScopeAudit {
+        granted_scopes,
+        missing_base_scopes: names(&missing_base),
+        features,
+        unneeded_scopes,
+        minimal_scopes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audit_classifies_features_and_flags_unneeded_scopes() {
+        let mut granted: Vec<String> = crate::esi::BASE_SCOPES
+            .iter()
+            .map(|s| s.as_str().to_string())
+            .collect();
+        granted.push(EsiScope::ReadCharacterContractsV1.as_str().to_string());
+        granted.push(EsiScope::WriteWaypointV1.as_str().to_string());
+        granted.push("esi-mail.read_mail.v1".to_string());
+
+        let audit = audit_scopes(&granted, &[FeatureId::Contracts, FeatureId::Industry]);
+        let status = |id: FeatureId| {
+            audit
+                .features
+                .iter()
+                .find(|f| f.feature_id == id)
+                .unwrap()
+                .status
+        };
+
+        assert!(audit.missing_base_scopes.is_empty());
+        assert_eq!(status(FeatureId::Contracts), FeatureScopeStatus::Granted);
+        assert_eq!(status(FeatureId::Industry), FeatureScopeStatus::Missing);
+        assert_eq!(status(FeatureId::Waypoints), FeatureScopeStatus::Unused);
+        assert_eq!(
+            status(FeatureId::Locations),
+            FeatureScopeStatus::NotRequested
+        );
+        assert_eq!(
+            audit.unneeded_scopes,
+            vec!["esi-mail.read_mail.v1", "esi-ui.write_waypoint.v1"]
+        );
+        assert!(audit
+            .minimal_scopes
+            .contains(&EsiScope::ReadCharacterIndustryJobsV1.as_str().to_string()));
+    }
+}
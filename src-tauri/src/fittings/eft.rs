@@ -0,0 +1,145 @@
+//! Parser for the EFT text format that Pyfa, the in-game "Copy to clipboard"
+//! and most fitting sites produce:
+//!
+//! ```text
+//! [Rifter, My Rifter]
+//! Damage Control I
+//!
+//! 1MN Afterburner I
+//!
+//! 200mm AutoCannon I, EMP S
+//! [Empty High slot]
+//!
+//! Warrior I x3
+//! ```
+//!
+//! Only names and quantities are extracted here; which slot each item goes in
+//! is decided from the SDE when the fit is resolved.
+
+use anyhow::{bail, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EftItem {
+    pub name: String,
+    pub quantity: i64,
+    /// Set for ammo/scripts loaded into a module (`Module, Charge`).
+    pub loaded_charge: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EftFit {
+    pub ship_name: String,
+    pub name: String,
+    pub items: Vec<EftItem>,
+}
+
+pub fn parse(text: &str) -> Result<EftFit> {
+    let mut lines = text.lines().map(str::trim).filter(|l| !l.is_empty());
+
+    let Some(header) = lines.next() else {
+        bail!("The fit is empty");
+    };
+    let Some(inner) = header.strip_prefix('[').and_then(|h| h.strip_suffix(']')) else {
+        bail!("Expected a [Ship, Fit name] header, found \"{}\"", header);
+    };
+    let (ship_name, name) = match inner.split_once(',') {
+        Some((ship, name)) => (ship.trim(), name.trim()),
+        None => (inner.trim(), ""),
+    };
+    if ship_name.is_empty() {
+        bail!("The fit header does not name a ship");
+    }
+
+    let mut items = Vec::new();
+    for line in lines {
+        // Placeholders for unfitted slots, e.g. "[Empty Low slot]".
+        if line.starts_with('[') {
+            continue;
+        }
+        let line = line.trim_end_matches("/OFFLINE").trim();
+
+        if let Some((module, charge)) = line.split_once(',') {
+            items.push(EftItem {
+                name: module.trim().to_string(),
+                quantity: 1,
+                loaded_charge: false,
+            });
+            items.push(EftItem {
+                name: charge.trim().to_string(),
+                quantity: 1,
+                loaded_charge: true,
+            });
+            continue;
+        }
+
+        let (item_name, quantity) = split_quantity(line);
+        items.push(EftItem {
+            name: item_name.to_string(),
+            quantity,
+            loaded_charge: false,
+        });
+    }
+
+    Ok(EftFit {
+        ship_name: ship_name.to_string(),
+        name: name.to_string(),
+        items,
+    })
+}
+
+/// Split a trailing ` xN` stack size off an item line ("Warrior I x3").
+fn split_quantity(line: &str) -> (&str, i64) {
+    if let Some((name, count)) = line.rsplit_once(" x") {
+        if let Ok(quantity) = count.trim().parse::<i64>() {
+            if quantity > 0 {
+                return (name.trim(), quantity);
+            }
+        }
+    }
+    (line, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_modules_charges_and_stacks() {
+        let fit = parse(
+            "[Rifter, My Rifter]\n\
+             Damage Control I\n\
+             [Empty Low slot]\n\
+             \n\
+             1MN Afterburner I /OFFLINE\n\
+             \n\
+             200mm AutoCannon I, EMP S\n\
+             \n\
+             Warrior I x3\n",
+        )
+        .unwrap();
+
+        assert_eq!(fit.ship_name, "Rifter");
+        assert_eq!(fit.name, "My Rifter");
+        let names: Vec<(&str, i64, bool)> = fit
+            .items
+            .iter()
+            .map(|i| (i.name.as_str(), i.quantity, i.loaded_charge))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Damage Control I", 1, false),
+                ("1MN Afterburner I", 1, false),
+                ("200mm AutoCannon I", 1, false),
+                ("EMP S", 1, true),
+                ("Warrior I", 3, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_text_without_header() {
+        assert!(parse("Damage Control I").is_err());
+        assert!(parse("   \n").is_err());
+    }
+}
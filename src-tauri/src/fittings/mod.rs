@@ -0,0 +1,343 @@
+//! Saved ship fittings (`esi-fittings.*`): listing the character's in-game
+//! fits, checking them against the character's skills, and saving fits pasted
+//! as EFT text back to the game.
+
+pub mod eft;
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::auth::{self, oauth::check_token_scopes};
+use crate::ts_types::{i64_ts, usize_ts};
+use crate::{cache, db, esi, esi_helpers, utils};
+
+const DRONE_CATEGORY_ID: i64 = 18;
+const FIGHTER_CATEGORY_ID: i64 = 87;
+
+// Dogma effects that mark which rack a module fits in.
+const LO_POWER_EFFECT_ID: i64 = 11;
+const HI_POWER_EFFECT_ID: i64 = 12;
+const MED_POWER_EFFECT_ID: i64 = 13;
+const RIG_SLOT_EFFECT_ID: i64 = 2663;
+const SUBSYSTEM_EFFECT_ID: i64 = 3772;
+
+/// ESI caps fitting names at 50 characters.
+const MAX_FITTING_NAME_LEN: usize = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EsiFittingItem {
+    pub type_id: i64,
+    pub flag: String,
+    pub quantity: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EsiFitting {
+    pub fitting_id: i64,
+    pub name: String,
+    pub description: String,
+    pub ship_type_id: i64,
+    pub items: Vec<EsiFittingItem>,
+}
+
+#[derive(Debug, Serialize)]
+struct NewFitting<'a> {
+    name: &'a str,
+    description: &'a str,
+    ship_type_id: i64,
+    items: &'a [EsiFittingItem],
+}
+
+#[derive(Debug, Deserialize)]
+struct NewFittingResponse {
+    fitting_id: i64,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct FittingSkillRequirement {
+    pub skill_id: i64_ts,
+    pub skill_name: String,
+    pub required_level: i64_ts,
+    pub trained_level: i64_ts,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct FittingSummary {
+    pub fitting_id: i64_ts,
+    pub name: String,
+    pub description: String,
+    pub ship_type_id: i64_ts,
+    pub ship_name: String,
+    pub item_count: usize_ts,
+    /// Skills (including prerequisites) the character is still short of.
+    pub missing_skills: Vec<FittingSkillRequirement>,
+}
+
+async fn authenticated_client(
+    pool: &db::Pool,
+    character_id: i64,
+    scope: esi::EsiScope,
+) -> Result<reqwest::Client> {
+    let missing = check_token_scopes(pool, character_id, &[scope]).await?;
+    if !missing.is_empty() {
+        bail!(
+            "Character is missing the {} scope; enable Fittings and re-authorize it",
+            scope.as_str()
+        );
+    }
+    let access_token = auth::ensure_valid_access_token(pool, character_id).await?;
+    esi_helpers::create_authenticated_client(&access_token)
+}
+
+fn fittings_endpoint(character_id: i64) -> String {
+    format!("characters/{}/fittings", character_id)
+}
+
+/// Fetch the character's saved fits, honouring the ESI cache.
+pub async fn fetch_fittings(
+    pool: &db::Pool,
+    rate_limits: &esi::RateLimitStore,
+    character_id: i64,
+) -> Result<Vec<EsiFitting>> {
+    let client = authenticated_client(pool, character_id, esi::EsiScope::ReadFittingsV1).await?;
+    let endpoint_path = fittings_endpoint(character_id);
+    let cache_key = cache::build_cache_key(&endpoint_path, character_id);
+    let fittings = esi::fetch_cached::<Vec<EsiFitting>>(
+        pool,
+        &client,
+        &endpoint_path,
+        &cache_key,
+        rate_limits,
+        character_id,
+    )
+    .await?
+    .unwrap_or_default();
+    Ok(fittings)
+}
+
+/// Skills needed to fly the ship and use every fitted item, compared with the
+/// character's active levels. Only shortfalls are returned.
+pub async fn missing_skills(
+    pool: &db::Pool,
+    character_id: i64,
+    type_ids: &[i64],
+) -> Result<Vec<FittingSkillRequirement>> {
+    let required = db::sde::get_required_skills_for_types(pool, type_ids).await?;
+    let trained: HashMap<i64, i64> = db::get_character_skills(pool, character_id)
+        .await?
+        .into_iter()
+        .map(|s| (s.skill_id, s.active_skill_level))
+        .collect();
+
+    let mut missing: Vec<(i64, i64, i64)> = required
+        .into_iter()
+        .filter_map(|(skill_id, required_level)| {
+            let trained_level = trained.get(&skill_id).copied().unwrap_or(0);
+            (trained_level < required_level).then_some((skill_id, required_level, trained_level))
+        })
+        .collect();
+    missing.sort();
+
+    let skill_ids: Vec<i64> = missing.iter().map(|(id, _, _)| *id).collect();
+    let names = utils::get_type_names(pool, &skill_ids)
+        .await
+        .map_err(anyhow::Error::msg)?;
+
+    Ok(missing
+        .into_iter()
+        .map(
+            |(skill_id, required_level, trained_level)| FittingSkillRequirement {
+                skill_id,
+                skill_name: names
+                    .get(&skill_id)
+                    .cloned()
+                    .unwrap_or_else(|| format!("Unknown Skill {}", skill_id)),
+                required_level,
+                trained_level,
+            },
+        )
+        .collect())
+}
+
+pub async fn summarize(
+    pool: &db::Pool,
+    character_id: i64,
+    fittings: Vec<EsiFitting>,
+) -> Result<Vec<FittingSummary>> {
+    let ship_ids: Vec<i64> = fittings.iter().map(|f| f.ship_type_id).collect();
+    let ship_names = utils::get_type_names(pool, &ship_ids)
+        .await
+        .map_err(anyhow::Error::msg)?;
+
+    let mut summaries = Vec::with_capacity(fittings.len());
+    for fitting in fittings {
+        let mut type_ids: Vec<i64> = fitting.items.iter().map(|i| i.type_id).collect();
+        type_ids.push(fitting.ship_type_id);
+        type_ids.sort_unstable();
+        type_ids.dedup();
+
+        summaries.push(FittingSummary {
+            fitting_id: fitting.fitting_id,
+            ship_name: ship_names
+                .get(&fitting.ship_type_id)
+                .cloned()
+                .unwrap_or_else(|| format!("Unknown Ship {}", fitting.ship_type_id)),
+            item_count: fitting.items.len(),
+            missing_skills: missing_skills(pool, character_id, &type_ids).await?,
+            name: fitting.name,
+            description: fitting.description,
+            ship_type_id: fitting.ship_type_id,
+        });
+    }
+    Ok(summaries)
+}
+
+/// Resolve an EFT block against the SDE into an ESI fitting: ship type id plus
+/// items with their slot flags. Unknown item names are reported all at once.
+pub async fn resolve_eft(
+    pool: &db::Pool,
+    text: &str,
+) -> Result<(eft::EftFit, i64, Vec<EsiFittingItem>)> {
+    let fit = eft::parse(text)?;
+
+    let Some(ship) = db::sde::get_type_by_name(pool, &fit.ship_name).await? else {
+        bail!("Unknown ship \"{}\"", fit.ship_name);
+    };
+
+    let mut unknown = Vec::new();
+    let mut next_slot: HashMap<&'static str, i64> = HashMap::new();
+    let mut cargo: Vec<(String, i64, i64)> = Vec::new();
+    let mut items = Vec::new();
+
+    for item in &fit.items {
+        let Some(resolved) = db::sde::get_type_by_name(pool, &item.name).await? else {
+            unknown.push(item.name.clone());
+            continue;
+        };
+
+        let rack = if item.loaded_charge {
+            None
+        } else {
+            let effects: HashSet<i64> = db::sde::get_type_effect_ids(pool, resolved.type_id)
+                .await?
+                .into_iter()
+                .collect();
+            slot_prefix(&effects)
+        };
+
+        if let Some(prefix) = rack {
+            let index = next_slot.entry(prefix).or_insert(0);
+            items.push(EsiFittingItem {
+                type_id: resolved.type_id,
+                flag: format!("{}{}", prefix, index),
+                quantity: 1,
+            });
+            *index += 1;
+            continue;
+        }
+
+        let flag = match resolved.category_id {
+            Some(DRONE_CATEGORY_ID) => "DroneBay",
+            Some(FIGHTER_CATEGORY_ID) => "FighterBay",
+            _ => "Cargo",
+        };
+        cargo.push((flag.to_string(), resolved.type_id, item.quantity));
+    }
+
+    if !unknown.is_empty() {
+        bail!("Unknown items in fit: {}", unknown.join(", "));
+    }
+
+    // Merge repeated charges/drones into one stack per bay.
+    let mut stacks: Vec<EsiFittingItem> = Vec::new();
+    for (flag, type_id, quantity) in cargo {
+        match stacks
+            .iter_mut()
+            .find(|s| s.flag == flag && s.type_id == type_id)
+        {
+            Some(stack) => stack.quantity += quantity,
+            None => stacks.push(EsiFittingItem {
+                type_id,
+                flag,
+                quantity,
+            }),
+        }
+    }
+    items.extend(stacks);
+
+    Ok((fit, ship.type_id, items))
+}
+
+fn slot_prefix(effects: &HashSet<i64>) -> Option<&'static str> {
+    if effects.contains(&LO_POWER_EFFECT_ID) {
+        Some("LoSlot")
+    } else if effects.contains(&MED_POWER_EFFECT_ID) {
+        Some("MedSlot")
+    } else if effects.contains(&HI_POWER_EFFECT_ID) {
+        Some("HiSlot")
+    } else if effects.contains(&RIG_SLOT_EFFECT_ID) {
+        Some("RigSlot")
+    } else if effects.contains(&SUBSYSTEM_EFFECT_ID) {
+        Some("SubSystemSlot")
+    } else {
+        None
+    }
+}
+
+/// Save an EFT fit to the character's in-game fittings and return its new id.
+pub async fn push_eft(
+    pool: &db::Pool,
+    rate_limits: &esi::RateLimitStore,
+    character_id: i64,
+    text: &str,
+) -> Result<i64> {
+    let (fit, ship_type_id, items) = resolve_eft(pool, text).await?;
+    let client = authenticated_client(pool, character_id, esi::EsiScope::WriteFittingsV1).await?;
+
+    let name: String = if fit.name.is_empty() {
+        fit.ship_name.clone()
+    } else {
+        fit.name.clone()
+    }
+    .chars()
+    .take(MAX_FITTING_NAME_LEN)
+    .collect();
+    let body = NewFitting {
+        name: &name,
+        description: "Imported from skillmon",
+        ship_type_id,
+        items: &items,
+    };
+
+    let endpoint_path = fittings_endpoint(character_id);
+    let response: NewFittingResponse =
+        esi::post_json(&client, &endpoint_path, &body, rate_limits, character_id)
+            .await
+            .context("ESI rejected the fitting")?;
+
+    // Expire the cached list so the new fit shows up on the next fetch.
+    let cache_key = cache::build_cache_key(&endpoint_path, character_id);
+    cache::update_cache_expiration(pool, &cache_key, 0).await?;
+
+    Ok(response.fitting_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slot_prefix_follows_rack_effects() {
+        let set = |ids: &[i64]| ids.iter().copied().collect::<HashSet<i64>>();
+        assert_eq!(slot_prefix(&set(&[LO_POWER_EFFECT_ID])), Some("LoSlot"));
+        assert_eq!(slot_prefix(&set(&[MED_POWER_EFFECT_ID])), Some("MedSlot"));
+        assert_eq!(slot_prefix(&set(&[HI_POWER_EFFECT_ID, 42])), Some("HiSlot"));
+        assert_eq!(slot_prefix(&set(&[RIG_SLOT_EFFECT_ID])), Some("RigSlot"));
+        assert_eq!(slot_prefix(&set(&[])), None);
+    }
+}
@@ -0,0 +1,289 @@
+//! One snapshot of every subsystem for the diagnostics screen: the database,
+//! the imported SDE, ESI's error budget, each character's refresher and token,
+//! and the scheduled background tasks. Nothing here calls ESI, so it answers
+//! quickly even when the network is what is broken.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use typeshare::typeshare;
+
+use crate::db::characters::Character;
+use crate::esi::cached::ErrorLimitGate;
+use crate::esi::RateLimitState;
+use crate::refresh::RefreshHealth;
+use crate::scheduler::{ScheduledTask, ScheduledTaskStatus};
+use crate::ts_types::i64_ts;
+use crate::{auth, db};
+
+/// An SDE older than this is flagged; CCP publishes builds every few weeks.
+const SDE_STALE_DAYS: i64 = 60;
+
+/// A refresher that has not succeeded for this long is flagged.
+const REFRESH_STALE_SECS: i64 = 2 * 60 * 60;
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct DatabaseHealth {
+    pub status: HealthStatus,
+    pub error: Option<String>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct SdeHealth {
+    pub status: HealthStatus,
+    pub build_number: Option<i64_ts>,
+    /// Unix seconds.
+    pub imported_at: Option<i64_ts>,
+    pub age_days: Option<i64_ts>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct EsiHealth {
+    pub status: HealthStatus,
+    /// `closed` (requests flow), `slowed` or `open` (requests refused until
+    /// the error budget resets).
+    pub circuit: String,
+    pub error_limit_remain: Option<i32>,
+    pub error_limit_reset_at: Option<String>,
+    pub throttled_last_hour: i64_ts,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct CharacterHealth {
+    pub status: HealthStatus,
+    pub character_id: i64_ts,
+    pub character_name: String,
+    /// Unix seconds of the last refresh pass where any fetch succeeded.
+    pub last_refresh_at: Option<i64_ts>,
+    pub has_token: bool,
+    pub token_error: Option<String>,
+    /// Scopes enabled features need that the token lacks.
+    pub missing_scopes: Vec<String>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskHealth {
+    pub status: HealthStatus,
+    pub task: ScheduledTask,
+    pub paused: bool,
+    /// Unix seconds.
+    pub last_run_at: Option<i64_ts>,
+    pub last_error: Option<String>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct AppHealth {
+    /// The worst status of any subsystem.
+    pub status: HealthStatus,
+    pub database: DatabaseHealth,
+    pub sde: SdeHealth,
+    pub esi: EsiHealth,
+    pub characters: Vec<CharacterHealth>,
+    pub tasks: Vec<TaskHealth>,
+}
+
+pub fn sde_status(imported_at: Option<i64>, now: DateTime<Utc>) -> (HealthStatus, Option<i64>) {
+    let Some(imported_at) = imported_at else {
+        return (HealthStatus::Error, None);
+    };
+    let age_days = (now.timestamp() - imported_at) / 86_400;
+    let status = if age_days > SDE_STALE_DAYS {
+        HealthStatus::Warning
+    } else {
+        HealthStatus::Ok
+    };
+    (status, Some(age_days))
+}
+
+pub fn esi_health(state: &RateLimitState, now: DateTime<Utc>) -> EsiHealth {
+    let (status, circuit) = match state.error_limit_gate(now) {
+        ErrorLimitGate::Proceed => (HealthStatus::Ok, "closed"),
+        ErrorLimitGate::Delay(_) => (HealthStatus::Warning, "slowed"),
+        ErrorLimitGate::Reject(_) => (HealthStatus::Error, "open"),
+    };
+    let hour_ago = now - chrono::Duration::hours(1);
+    EsiHealth {
+        status,
+        circuit: circuit.to_string(),
+        error_limit_remain: state.error_limit.as_ref().map(|l| l.remain),
+        error_limit_reset_at: state.error_limit.as_ref().map(|l| l.reset_at.to_rfc3339()),
+        throttled_last_hour: state.throttled.iter().filter(|e| e.at >= hour_ago).count() as i64,
+    }
+}
+
+pub fn character_status(
+    has_token: bool,
+    refresh: &RefreshHealth,
+    missing_scopes: &[String],
+    now: i64,
+) -> HealthStatus {
+    if !has_token || refresh.token_error.is_some() {
+        return HealthStatus::Error;
+    }
+    let stale = refresh
+        .last_success_at
+        .is_none_or(|at| now - at > REFRESH_STALE_SECS);
+    if stale || !missing_scopes.is_empty() {
+        HealthStatus::Warning
+    } else {
+        HealthStatus::Ok
+    }
+}
+
+fn task_health(status: ScheduledTaskStatus) -> TaskHealth {
+    TaskHealth {
+        status: if status.last_error.is_some() {
+            HealthStatus::Warning
+        } else {
+            HealthStatus::Ok
+        },
+        task: status.task,
+        paused: status.paused,
+        last_run_at: status.last_run_at,
+        last_error: status.last_error,
+    }
+}
+
+async fn database_health(pool: &db::Pool) -> DatabaseHealth {
+    match sqlx::query_scalar::<_, i64>("SELECT 1")
+        .fetch_one(pool)
+        .await
+    {
+        Ok(_) => DatabaseHealth {
+            status: HealthStatus::Ok,
+            error: None,
+        },
+        Err(e) => DatabaseHealth {
+            status: HealthStatus::Error,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+pub async fn app_health(
+    pool: &db::Pool,
+    characters: &[Character],
+    refresh: &HashMap<i64, RefreshHealth>,
+    esi: EsiHealth,
+    tasks: Vec<ScheduledTaskStatus>,
+) -> Result<AppHealth> {
+    let now = Utc::now();
+    let database = database_health(pool).await;
+
+    let metadata = sqlx::query_as::<_, (i64, i64)>(
+        "SELECT build_number, imported_at FROM sde_metadata LIMIT 1",
+    )
+    .fetch_optional(pool)
+    .await
+    .unwrap_or_default();
+    let (sde_status, age_days) = sde_status(metadata.map(|m| m.1), now);
+    let sde = SdeHealth {
+        status: sde_status,
+        build_number: metadata.map(|m| m.0),
+        imported_at: metadata.map(|m| m.1),
+        age_days,
+    };
+
+    let mut character_health = Vec::with_capacity(characters.len());
+    for character in characters {
+        let has_token = db::get_tokens(pool, character.character_id)
+            .await?
+            .is_some();
+        let missing_scopes = auth::reauth::reauth_required(pool, character.character_id)
+            .await?
+            .map(|r| r.missing_scopes)
+            .unwrap_or_default();
+        let refresh = refresh
+            .get(&character.character_id)
+            .cloned()
+            .unwrap_or_default();
+        character_health.push(CharacterHealth {
+            status: character_status(has_token, &refresh, &missing_scopes, now.timestamp()),
+            character_id: character.character_id,
+            character_name: character.character_name.clone(),
+            last_refresh_at: refresh.last_success_at,
+            has_token,
+            token_error: refresh.token_error,
+            missing_scopes,
+        });
+    }
+
+    let tasks: Vec<TaskHealth> = tasks.into_iter().map(task_health).collect();
+
+    let status = [database.status, sde.status, esi.status]
+        .into_iter()
+        .chain(character_health.iter().map(|c| c.status))
+        .chain(tasks.iter().map(|t| t.status))
+        .max()
+        .unwrap_or(HealthStatus::Ok);
+
+    Ok(AppHealth {
+        status,
+        database,
+        sde,
+        esi,
+        characters: character_health,
+        tasks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statuses_flag_stale_and_broken_subsystems() {
+        let now = Utc::now();
+        assert_eq!(sde_status(None, now), (HealthStatus::Error, None));
+        assert_eq!(
+            sde_status(Some(now.timestamp() - 90 * 86_400), now),
+            (HealthStatus::Warning, Some(90))
+        );
+
+        let fresh = RefreshHealth {
+            last_success_at: Some(now.timestamp() - 60),
+            token_error: None,
+            ..RefreshHealth::default()
+        };
+        let ts = now.timestamp();
+        assert_eq!(character_status(true, &fresh, &[], ts), HealthStatus::Ok);
+        assert_eq!(
+            character_status(false, &fresh, &[], ts),
+            HealthStatus::Error
+        );
+        assert_eq!(
+            character_status(true, &RefreshHealth::default(), &[], ts),
+            HealthStatus::Warning
+        );
+        let broken = RefreshHealth {
+            token_error: Some("invalid_grant".to_string()),
+            ..fresh.clone()
+        };
+        assert_eq!(
+            character_status(true, &broken, &[], ts),
+            HealthStatus::Error
+        );
+
+        assert_eq!(
+            esi_health(&RateLimitState::default(), now).circuit,
+            "closed"
+        );
+    }
+}
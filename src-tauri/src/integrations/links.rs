@@ -0,0 +1,75 @@
+use anyhow::Result;
+use serde::Serialize;
+use typeshare::typeshare;
+
+use crate::auth::oauth::check_token_scopes;
+use crate::db;
+use crate::esi::EsiScope;
+use crate::ts_types::i64_ts;
+
+const ZKILLBOARD_BASE_URL: &str = "https://zkillboard.com";
+const EVEWHO_BASE_URL: &str = "https://evewho.com";
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct ExternalLinks {
+    pub character_id: i64_ts,
+    pub zkillboard_url: String,
+    pub evewho_url: String,
+    /// True when the character's token carries `esi-ui.open_window.v1`, so the
+    /// info window can be opened in the running EVE client instead.
+    pub in_game_show_info_available: bool,
+}
+
+pub fn zkillboard_character_url(character_id: i64) -> String {
+    format!("{}/character/{}/", ZKILLBOARD_BASE_URL, character_id)
+}
+
+pub fn evewho_character_url(character_id: i64) -> String {
+    format!("{}/character/{}", EVEWHO_BASE_URL, character_id)
+}
+
+pub async fn external_links(pool: &db::Pool, character_id: i64) -> Result<ExternalLinks> {
+    // A character without tokens (or with a token missing the scope) simply
+    // falls back to the web links.
+    let in_game_show_info_available =
+        match check_token_scopes(pool, character_id, &[EsiScope::OpenWindowV1]).await {
+            Ok(missing) => missing.is_empty(),
+            Err(_) => false,
+        };
+
+    Ok(ExternalLinks {
+        character_id,
+        zkillboard_url: zkillboard_character_url(character_id),
+        evewho_url: evewho_character_url(character_id),
+        in_game_show_info_available,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    #[test]
+    fn builds_canonical_character_urls() {
+        assert_eq!(
+            zkillboard_character_url(2112625428),
+            "https://zkillboard.com/character/2112625428/"
+        );
+        assert_eq!(
+            evewho_character_url(2112625428),
+            "https://evewho.com/character/2112625428"
+        );
+    }
+
+    #[tokio::test]
+    async fn show_info_unavailable_without_tokens() {
+        let db = TestDb::new().await.unwrap();
+        db::add_character(&db.pool, 1, "Test").await.unwrap();
+
+        let links = external_links(&db.pool, 1).await.unwrap();
+        assert!(!links.in_game_show_info_available);
+        assert_eq!(links.zkillboard_url, "https://zkillboard.com/character/1/");
+    }
+}
@@ -0,0 +1,6 @@
+//! Hand-offs to places outside skillmon: third-party websites and the EVE
+//! client itself.
+
+pub mod links;
+pub mod pyfa;
+pub mod ui;
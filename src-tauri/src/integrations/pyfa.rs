@@ -0,0 +1,168 @@
+//! Character export for pyfa's manual "Import character" dialog, so fitting
+//! simulations can use skillmon's data without giving pyfa its own ESI token.
+//!
+//! pyfa reads the EVEMon character XML layout: it takes the first `<name>`,
+//! the first `<securityStatus>` and every `<skill typeID level>` element, so
+//! those are always written. pyfa's character import ignores implants; they are
+//! exported separately as one implant name per line, the format pyfa's implant
+//! set editor pastes from the clipboard.
+
+use std::io::Cursor;
+
+use anyhow::{bail, Context, Result};
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::writer::Writer;
+use serde::Serialize;
+use typeshare::typeshare;
+
+use crate::ts_types::{i64_ts, usize_ts};
+use crate::{db, utils};
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct PyfaCharacterExport {
+    pub character_id: i64_ts,
+    pub character_name: String,
+    /// Suggested file name for the XML, e.g. `Pilot Name.xml`.
+    pub file_name: String,
+    pub xml: String,
+    /// Active clone's implants, one name per line; empty when none are plugged in.
+    pub implants: String,
+    pub skill_count: usize_ts,
+    pub implant_count: usize_ts,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyfaSkill {
+    pub type_id: i64,
+    pub name: String,
+    pub level: i64,
+    pub skillpoints: i64,
+}
+
+fn write_text_element(writer: &mut Writer<Cursor<Vec<u8>>>, name: &str, text: &str) -> Result<()> {
+    writer.write_event(Event::Start(BytesStart::new(name)))?;
+    writer.write_event(Event::Text(BytesText::new(text)))?;
+    writer.write_event(Event::End(BytesEnd::new(name)))?;
+    Ok(())
+}
+
+pub fn render_character_xml(
+    character_id: i64,
+    character_name: &str,
+    skills: &[PyfaSkill],
+) -> Result<String> {
+    let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
+
+    writer
+        .write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))
+        .context("Failed to write XML declaration")?;
+    writer.write_event(Event::Start(BytesStart::new("SerializableCCPCharacter")))?;
+    write_text_element(&mut writer, "characterID", &character_id.to_string())?;
+    write_text_element(&mut writer, "name", character_name)?;
+    // Not tracked by skillmon; pyfa requires the element to be present.
+    write_text_element(&mut writer, "securityStatus", "0")?;
+
+    writer.write_event(Event::Start(BytesStart::new("skills")))?;
+    for skill in skills {
+        let type_id = skill.type_id.to_string();
+        let level = skill.level.to_string();
+        let skillpoints = skill.skillpoints.to_string();
+        let mut element = BytesStart::new("skill");
+        element.push_attribute(("typeID", type_id.as_str()));
+        element.push_attribute(("name", skill.name.as_str()));
+        element.push_attribute(("level", level.as_str()));
+        element.push_attribute(("skillpoints", skillpoints.as_str()));
+        element.push_attribute(("ownsBook", "true"));
+        element.push_attribute(("isKnown", "true"));
+        writer.write_event(Event::Empty(element))?;
+    }
+    writer.write_event(Event::End(BytesEnd::new("skills")))?;
+    writer.write_event(Event::End(BytesEnd::new("SerializableCCPCharacter")))?;
+
+    String::from_utf8(writer.into_inner().into_inner()).context("Exported XML is not UTF-8")
+}
+
+/// Build the pyfa export from the character's stored skills (active levels,
+/// i.e. what an Alpha clone can actually use) and its current clone's implants.
+pub async fn export_character(pool: &db::Pool, character_id: i64) -> Result<PyfaCharacterExport> {
+    let Some(character) = db::get_character(pool, character_id).await? else {
+        bail!("Character {} not found", character_id);
+    };
+
+    let stored = db::get_character_skills(pool, character_id).await?;
+    if stored.is_empty() {
+        bail!("No skills stored for this character yet; refresh it first");
+    }
+
+    let mut implant_ids = Vec::new();
+    for clone in db::get_character_clones(pool, character_id).await? {
+        if clone.is_current {
+            implant_ids = db::get_clone_implants(pool, clone.id)
+                .await?
+                .into_iter()
+                .map(|i| i.implant_type_id)
+                .collect();
+            break;
+        }
+    }
+
+    let mut type_ids: Vec<i64> = stored.iter().map(|s| s.skill_id).collect();
+    type_ids.extend(&implant_ids);
+    let names = utils::get_type_names(pool, &type_ids)
+        .await
+        .map_err(anyhow::Error::msg)?;
+
+    let mut skills: Vec<PyfaSkill> = stored
+        .into_iter()
+        .filter(|s| s.active_skill_level > 0)
+        .map(|s| PyfaSkill {
+            name: names
+                .get(&s.skill_id)
+                .cloned()
+                .unwrap_or_else(|| format!("Unknown Skill {}", s.skill_id)),
+            type_id: s.skill_id,
+            level: s.active_skill_level,
+            skillpoints: s.skillpoints_in_skill,
+        })
+        .collect();
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let implants: Vec<&str> = implant_ids
+        .iter()
+        .filter_map(|id| names.get(id).map(String::as_str))
+        .collect();
+
+    Ok(PyfaCharacterExport {
+        character_id,
+        file_name: format!("{}.xml", character.character_name),
+        xml: render_character_xml(character_id, &character.character_name, &skills)?,
+        implants: implants.join("\n"),
+        skill_count: skills.len(),
+        implant_count: implants.len(),
+        character_name: character.character_name,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xml_carries_the_elements_pyfa_reads() {
+        let skills = vec![PyfaSkill {
+            type_id: 3300,
+            name: "Gunnery".to_string(),
+            level: 5,
+            skillpoints: 256000,
+        }];
+        let xml = render_character_xml(90000001, "Pilot & Co", &skills).unwrap();
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+        assert!(xml.contains("<name>Pilot &amp; Co</name>"));
+        assert!(xml.contains("<securityStatus>0</securityStatus>"));
+        assert!(xml.contains(
+            "<skill typeID=\"3300\" name=\"Gunnery\" level=\"5\" skillpoints=\"256000\""
+        ));
+    }
+}
@@ -0,0 +1,185 @@
+//! Actions performed in the running EVE client through the `esi-ui.*`
+//! endpoints. ESI only accepts these while the character is logged in; the
+//! client silently ignores them otherwise.
+
+use anyhow::{bail, Result};
+
+use crate::auth::{self, oauth::check_token_scopes};
+use crate::db;
+use crate::esi::{self, EsiScope};
+use crate::esi_helpers;
+
+async fn require_scope(pool: &db::Pool, character_id: i64, scope: EsiScope) -> Result<()> {
+    let missing = check_token_scopes(pool, character_id, &[scope]).await?;
+    if !missing.is_empty() {
+        bail!(
+            "Character is missing the {} scope; re-authorize it to enable this action",
+            scope.as_str()
+        );
+    }
+    Ok(())
+}
+
+async fn post_ui_action(
+    pool: &db::Pool,
+    rate_limits: &esi::RateLimitStore,
+    character_id: i64,
+    scope: EsiScope,
+    endpoint_path: &str,
+    query: &[(&str, String)],
+) -> Result<()> {
+    require_scope(pool, character_id, scope).await?;
+    let access_token = auth::ensure_valid_access_token(pool, character_id).await?;
+    let client = esi_helpers::create_authenticated_client(&access_token)?;
+    esi::post_action(&client, endpoint_path, query, rate_limits, character_id).await
+}
+
+pub async fn open_market_details(
+    pool: &db::Pool,
+    rate_limits: &esi::RateLimitStore,
+    character_id: i64,
+    type_id: i64,
+) -> Result<()> {
+    post_ui_action(
+        pool,
+        rate_limits,
+        character_id,
+        EsiScope::OpenWindowV1,
+        "ui/openwindow/marketdetails",
+        &[("type_id", type_id.to_string())],
+    )
+    .await
+}
+
+pub async fn open_information(
+    pool: &db::Pool,
+    rate_limits: &esi::RateLimitStore,
+    character_id: i64,
+    target_id: i64,
+) -> Result<()> {
+    post_ui_action(
+        pool,
+        rate_limits,
+        character_id,
+        EsiScope::OpenWindowV1,
+        "ui/openwindow/information",
+        &[("target_id", target_id.to_string())],
+    )
+    .await
+}
+
+/// Solar system a clone sits in, from the station/structure rows stored during
+/// clone sync. Errors when the clone is not the character's or its location
+/// has never been resolved.
+async fn clone_solar_system(pool: &db::Pool, character_id: i64, clone_id: i64) -> Result<i64> {
+    let clones = db::get_character_clones(pool, character_id).await?;
+    let Some(clone) = clones.into_iter().find(|c| c.id == clone_id) else {
+        bail!("Clone {} does not belong to this character", clone_id);
+    };
+
+    let system_id = match clone.location_type.as_str() {
+        "station" => db::get_station(pool, clone.location_id)
+            .await?
+            .map(|s| s.system_id),
+        "structure" => db::get_structure(pool, clone.location_id)
+            .await?
+            .map(|s| s.solar_system_id),
+        _ => None,
+    };
+    match system_id {
+        Some(id) if id > 0 => Ok(id),
+        _ => bail!("The location of this clone is unknown; refresh clones and try again"),
+    }
+}
+
+pub async fn set_waypoint_to_clone(
+    pool: &db::Pool,
+    rate_limits: &esi::RateLimitStore,
+    character_id: i64,
+    clone_id: i64,
+) -> Result<()> {
+    let system_id = clone_solar_system(pool, character_id, clone_id).await?;
+    post_ui_action(
+        pool,
+        rate_limits,
+        character_id,
+        EsiScope::WriteWaypointV1,
+        "ui/autopilot/waypoint",
+        &[
+            ("add_to_beginning", "false".to_string()),
+            ("clear_other_waypoints", "true".to_string()),
+            ("destination_id", system_id.to_string()),
+        ],
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    #[tokio::test]
+    async fn refuses_without_open_window_scope() {
+        let db = TestDb::new().await.unwrap();
+        db::add_character(&db.pool, 1, "Test").await.unwrap();
+        db::set_tokens(&db.pool, 1, "access", "refresh", i64::MAX, None)
+            .await
+            .unwrap();
+
+        let rate_limits = esi::RateLimitStore::default();
+        let err = open_information(&db.pool, &rate_limits, 1, 1)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("esi-ui.open_window.v1"));
+    }
+
+    #[tokio::test]
+    async fn resolves_clone_system_from_stored_locations() {
+        let db = TestDb::new().await.unwrap();
+        db::add_character(&db.pool, 1, "Test").await.unwrap();
+        db::add_character(&db.pool, 2, "Other").await.unwrap();
+        db::upsert_station(&db.pool, 60003760, "Jita IV - Moon 4", 30000142, None, None)
+            .await
+            .unwrap();
+        db::set_character_clones(
+            &db.pool,
+            1,
+            &[(
+                Some(10),
+                None,
+                "station".to_string(),
+                60003760,
+                false,
+                vec![],
+            )],
+        )
+        .await
+        .unwrap();
+        db::set_character_clones(
+            &db.pool,
+            2,
+            &[(
+                Some(20),
+                None,
+                "structure".to_string(),
+                1_000_000_000_001,
+                false,
+                vec![],
+            )],
+        )
+        .await
+        .unwrap();
+
+        let own = db::get_character_clones(&db.pool, 1).await.unwrap();
+        assert_eq!(
+            clone_solar_system(&db.pool, 1, own[0].id).await.unwrap(),
+            30000142
+        );
+
+        // Another character's clone is rejected, as is an unresolved structure.
+        let other = db::get_character_clones(&db.pool, 2).await.unwrap();
+        assert!(clone_solar_system(&db.pool, 1, other[0].id).await.is_err());
+        assert!(clone_solar_system(&db.pool, 2, other[0].id).await.is_err());
+    }
+}
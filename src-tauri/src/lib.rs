@@ -1,10 +1,9 @@
-use std::sync::{
-    atomic::{AtomicU8, Ordering},
-    Arc, Mutex,
-};
+use std::sync::Mutex;
 
-use tauri::{Emitter, Listener, Manager, WindowEvent};
+use tauri::{Listener, Manager, WindowEvent};
 
+mod affiliation;
+mod app_log;
 mod auth;
 mod cache;
 mod clone_sync;
@@ -12,14 +11,31 @@ mod commands;
 mod db;
 mod esi;
 mod esi_helpers;
+mod events;
+mod farm;
 mod features;
+mod fittings;
+mod health;
+mod integrations;
+mod maintenance;
+mod metrics;
 mod notifications;
+mod omega_budget;
+mod onboarding;
+mod queue_math;
 mod refresh;
+mod response_cache;
+mod scheduler;
 mod sde;
 mod skill_plans;
+mod skill_summary;
+mod startup;
+mod storage;
+mod sync;
 mod tray;
 pub mod ts_types;
 mod utils;
+mod watchlist;
 
 #[cfg(test)]
 pub mod testdata;
@@ -27,13 +43,14 @@ pub mod testdata;
 pub use commands::auth::AuthStateMap;
 pub use notifications::checkers::skill_queue_low::NOTIFICATION_TYPE_SKILL_QUEUE_LOW;
 
-type StartupState = Arc<AtomicU8>;
+/// How long the splash screen waits for characters' first refresh.
+const STARTUP_REFRESH_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
 
 #[tauri::command]
-async fn is_startup_complete(
-    startup_state: tauri::State<'_, StartupState>,
-) -> Result<bool, String> {
-    Ok(startup_state.load(Ordering::SeqCst) == 0)
+async fn get_startup_status(
+    startup_state: tauri::State<'_, startup::StartupState>,
+) -> Result<startup::StartupStatus, String> {
+    Ok(startup_state.status())
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -44,25 +61,72 @@ pub fn run() {
     let mut builder = tauri::Builder::default()
         .setup(|app| {
             tauri::async_runtime::block_on(async {
-                let pool = db::init_db(app.handle()).await?;
+                let startup_state = startup::StartupState::new();
+                app.manage(startup_state.clone());
+
+                match app.path().app_log_dir() {
+                    Ok(log_dir) => {
+                        if let Err(e) = app_log::init(&log_dir) {
+                            eprintln!("Failed to set up the log file: {}", e);
+                        }
+                    }
+                    Err(e) => eprintln!("Failed to resolve the app log directory: {}", e),
+                }
+
+                let storage_location = storage::resolve(app.handle())?;
+                let keyring = if storage_location.portable {
+                    auth::token_store::KeyringStore::for_data_dir(&storage_location.data_dir)?
+                } else {
+                    auth::token_store::KeyringStore::default()
+                };
+                let token_store =
+                    auth::token_store::init(&storage_location.token_key_path(), keyring)?;
+                log::info!("Storing tokens in the {} store", token_store);
+                let pool = db::init_db(&storage_location).await?;
+                let read_pool = db::init_read_pool(&storage_location).await?;
+                app.manage(storage_location);
                 app.manage(pool);
+                app.manage(read_pool);
 
-                match db::cleanup_old_dismissed_notifications(app.state::<db::Pool>().inner()).await
+                match auth::token_store::migrate_plaintext_tokens(app.state::<db::Pool>().inner())
+                    .await
                 {
-                    Ok(n) => log::info!("Cleaned up {} old dismissed notifications", n),
-                    Err(e) => log::warn!("Failed to clean up old notifications: {}", e),
+                    Ok(0) => {}
+                    Ok(n) => log::info!("Moved {} characters' tokens out of the database", n),
+                    Err(e) => log::warn!("Failed to migrate plaintext tokens: {}", e),
+                }
+
+                match db::get_boolean_app_setting(
+                    app.state::<db::Pool>().inner(),
+                    app_log::DEBUG_LOGGING_SETTING,
+                )
+                .await
+                {
+                    Ok(enabled) => app_log::set_debug(enabled),
+                    Err(e) => log::warn!("Failed to read debug_logging setting: {}", e),
+                }
+
+                match maintenance::prune(app.state::<db::Pool>().inner()).await {
+                    Ok(report) => log::info!("Pruned {} rows past retention", report.total()),
+                    Err(e) => log::warn!("Failed to apply retention settings: {}", e),
                 }
 
                 app.manage(AuthStateMap::default());
-                app.manage(Arc::new(tokio::sync::RwLock::new(
-                    std::collections::HashMap::<
-                        i64,
-                        std::collections::HashMap<String, esi::RateLimitInfo>,
-                    >::new(),
-                )));
-
-                let startup_state: StartupState = Arc::new(AtomicU8::new(1));
-                app.manage(startup_state.clone());
+                let rate_limits = esi::RateLimitStore::default();
+                let (warning_tx, mut warning_rx) = tokio::sync::mpsc::unbounded_channel();
+                rate_limits.write().await.warnings = Some(warning_tx);
+                let warning_app = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    while let Some(warning) = warning_rx.recv().await {
+                        if let Err(e) = events::emit_rate_limit_warning(&warning_app, &warning) {
+                            log::warn!("Failed to emit rate limit warning: {}", e);
+                        }
+                    }
+                });
+                app.manage(rate_limits);
+                app.manage(refresh::WindowActivityStore::default());
+                app.manage(skill_plans::optimization::OptimizationRegistry::default());
+                app.manage(response_cache::ResponseCache::default());
 
                 let start_minimized =
                     db::get_boolean_app_setting(app.state::<db::Pool>().inner(), "start_minimized")
@@ -76,10 +140,14 @@ pub fn run() {
                     if let Some(window) = app.get_webview_window("main") {
                         let _ = window.show();
                     }
+                } else {
+                    app.state::<refresh::WindowActivityStore>()
+                        .mark_hidden(chrono::Utc::now().timestamp());
                 }
 
                 let pool_for_tray = app.state::<db::Pool>().inner().clone();
                 let rate_limits_for_tray = app.state::<esi::RateLimitStore>().inner().clone();
+                let activity = app.state::<refresh::WindowActivityStore>().inner().clone();
 
                 let supervisor = Mutex::new(refresh::RefreshSupervisor::new());
 
@@ -87,6 +155,8 @@ pub fn run() {
                 let characters_for_refresh = db::get_all_characters(&pool_for_tray)
                     .await
                     .unwrap_or_default();
+                startup_state
+                    .expect_characters(characters_for_refresh.iter().map(|c| c.character_id));
                 {
                     let mut sup = supervisor.lock().unwrap();
                     for character in characters_for_refresh {
@@ -95,12 +165,20 @@ pub fn run() {
                             pool_for_tray.clone(),
                             app.handle().clone(),
                             rate_limits_for_tray.clone(),
+                            activity.clone(),
                         );
                     }
                 }
 
                 app.manage(supervisor);
 
+                let metrics_server = metrics::MetricsServer::default();
+                match db::app_settings::get_metrics_server_settings(&pool_for_tray).await {
+                    Ok(settings) => metrics_server.apply(app.handle(), &settings),
+                    Err(e) => log::warn!("Failed to read metrics server settings: {}", e),
+                }
+                app.manage(metrics_server);
+
                 let training_count_item = tauri::menu::MenuItem::with_id(
                     app,
                     "training_count",
@@ -108,6 +186,8 @@ pub fn run() {
                     true,
                     None::<&str>,
                 )?;
+                let accounts_submenu =
+                    tauri::menu::Submenu::with_id(app, "accounts", "Accounts", false)?;
                 let show_item =
                     tauri::menu::MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
                 let quit_item =
@@ -115,7 +195,12 @@ pub fn run() {
 
                 let menu = tauri::menu::Menu::with_items(
                     app,
-                    &[&training_count_item, &show_item, &quit_item],
+                    &[
+                        &training_count_item,
+                        &accounts_submenu,
+                        &show_item,
+                        &quit_item,
+                    ],
                 )?;
 
                 let icon = tauri::image::Image::from_bytes(include_bytes!("../icons/32x32.png"))
@@ -127,42 +212,32 @@ pub fn run() {
                     .tooltip("skillmon")
                     .build(app)?;
 
-                let training_count_item_clone = training_count_item.clone();
-                let app_handle_for_updates = app.handle().clone();
-                tauri::async_runtime::spawn(async move {
-                    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-                    tray::update_tray_menu(
-                        &app_handle_for_updates,
-                        &pool_for_tray,
-                        &rate_limits_for_tray,
-                        &training_count_item_clone,
-                    )
-                    .await;
-
-                    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
-                    loop {
-                        interval.tick().await;
-                        tray::update_tray_menu(
-                            &app_handle_for_updates,
-                            &pool_for_tray,
-                            &rate_limits_for_tray,
-                            &training_count_item_clone,
-                        )
-                        .await;
-                    }
+                let scheduler = scheduler::Scheduler::default();
+                scheduler.spawn(scheduler::TaskContext {
+                    app: app.handle().clone(),
+                    pool: pool_for_tray,
+                    rate_limits: rate_limits_for_tray,
+                    training_count_item: training_count_item.clone(),
+                    accounts_submenu: accounts_submenu.clone(),
                 });
+                app.manage(scheduler);
 
                 let pool = app.state::<db::Pool>().inner().clone();
                 let app_handle = app.handle().clone();
-                let startup_state_clone = startup_state.clone();
                 tauri::async_runtime::spawn(async move {
-                    match sde::ensure_latest(&app_handle, &pool).await {
+                    startup_state.advance(&app_handle, startup::StartupPhase::SdeChecking);
+                    let on_import =
+                        || startup_state.advance(&app_handle, startup::StartupPhase::SdeImporting);
+                    match sde::ensure_latest(&app_handle, &pool, on_import).await {
                         Ok(_) => eprintln!("SDE import completed successfully"),
                         Err(err) => eprintln!("SDE import failed: {:#}", err),
                     }
 
-                    startup_state_clone.store(0, Ordering::SeqCst);
-                    let _ = app_handle.emit("startup-complete", ());
+                    startup_state.advance(&app_handle, startup::StartupPhase::RefreshingCharacters);
+                    startup_state
+                        .wait_for_characters(STARTUP_REFRESH_TIMEOUT)
+                        .await;
+                    startup_state.advance(&app_handle, startup::StartupPhase::Ready);
                 });
 
                 let callback_url = std::env::var("EVE_CALLBACK_URL").unwrap_or_else(|_| {
@@ -196,7 +271,7 @@ pub fn run() {
                 let app_handle = app.handle().clone();
                 app_handle
                     .clone()
-                    .listen("deep-link://new-url", move |event| {
+                    .listen(events::DEEP_LINK_NEW_URL, move |event| {
                         let url_str = event.payload();
                         if url_str.starts_with("eveauth-skillmon://callback") {
                             let url = url::Url::parse(url_str).ok();
@@ -222,7 +297,10 @@ pub fn run() {
                                         )
                                         .await
                                         {
-                                            let _ = app_handle.emit("auth-error", e.to_string());
+                                            let _ = events::emit_auth_error(
+                                                &app_handle,
+                                                &e.to_string(),
+                                            );
                                         }
                                     });
                                 }
@@ -236,9 +314,15 @@ pub fn run() {
                     app.state::<esi::RateLimitStore>().inner().clone();
                 let processor = std::sync::Arc::new(notifications::NotificationProcessor::new());
                 let processor_clone = processor.clone();
+                tauri::async_runtime::spawn(notifications::run_scheduled_checks(
+                    app_handle.clone(),
+                    pool_for_notifications.clone(),
+                    rate_limits_for_notifications.clone(),
+                    processor.clone(),
+                ));
                 app_handle
                         .clone()
-                        .listen(notifications::EVENT_DATA_UPDATED, move |event| {
+                        .listen(events::NOTIFICATION_DATA_UPDATED, move |event| {
                             let app_handle = app_handle.clone();
                             let pool = pool_for_notifications.clone();
                             let rate_limits = rate_limits_for_notifications.clone();
@@ -294,7 +378,7 @@ pub fn run() {
                                 )
                                 .await
                                 {
-                                    let _ = app_handle.emit("auth-error", e.to_string());
+                                    let _ = events::emit_auth_error(&app_handle, &e.to_string());
                                 }
                             });
                         }
@@ -336,17 +420,39 @@ pub fn run() {
             }
             _ => {}
         })
-        .on_window_event(|window, event| {
-            if let WindowEvent::CloseRequested { api, .. } = event {
+        .on_window_event(|window, event| match event {
+            WindowEvent::CloseRequested { api, .. } => {
                 window.hide().unwrap_or_default();
                 api.prevent_close();
+                window
+                    .state::<refresh::WindowActivityStore>()
+                    .mark_hidden(chrono::Utc::now().timestamp());
             }
+            WindowEvent::Focused(true) => {
+                // Back from the tray: refreshers resume full cadence right away.
+                if window
+                    .state::<refresh::WindowActivityStore>()
+                    .mark_visible()
+                {
+                    window
+                        .state::<Mutex<refresh::RefreshSupervisor>>()
+                        .lock()
+                        .unwrap()
+                        .poke_all();
+                }
+            }
+            _ => {}
         })
         .invoke_handler(tauri::generate_handler![
             commands::auth::get_base_scope_strings,
             commands::auth::start_eve_login,
-            is_startup_complete,
+            commands::auth::reauthorize_character,
+            get_startup_status,
             commands::characters::logout_character,
+            commands::characters::set_character_omega_override,
+            commands::characters::get_character_membership_history,
+            commands::characters::get_attribute_history,
+            commands::characters::get_sp_history,
             commands::accounts::get_accounts_and_characters,
             commands::accounts::create_account,
             commands::accounts::update_account_name,
@@ -356,58 +462,105 @@ pub fn run() {
             commands::accounts::reorder_accounts,
             commands::accounts::reorder_characters_in_account,
             commands::accounts::reorder_unassigned_characters,
+            commands::accounts::bulk_create_accounts,
+            commands::accounts::suggest_account_prefixes,
+            commands::accounts::auto_group_characters_by_prefix,
             commands::skill_queues::force_refresh_skill_queue,
+            commands::skill_queues::get_skill_queue_journal,
+            commands::activity_feed::get_activity_feed,
+            commands::audit_log::get_audit_log,
             commands::skills::get_sde_skills_with_groups,
             commands::skills::get_skill_details,
+            commands::skills::queue_next_level_estimate,
+            commands::skills::sp_needed,
+            commands::skills::get_skill_summary,
             commands::sde::refresh_sde,
+            commands::sde::get_sde_status,
             commands::clones::update_clone_name,
             commands::sde::get_type_names,
             commands::rate_limits::get_rate_limits,
+            commands::rate_limits::get_rate_limit_dashboard,
             commands::notifications::dismiss_notification,
             commands::notifications::request_notifications_snapshot,
             commands::notifications::get_notification_settings,
             commands::notifications::upsert_notification_setting,
+            commands::notifications::get_notification_webhooks,
+            commands::notifications::create_notification_webhook,
+            commands::notifications::update_notification_webhook,
+            commands::notifications::delete_notification_webhook,
+            commands::notifications::test_notification_webhook,
             commands::skill_plans::create_skill_plan,
             commands::skill_plans::create_merged_skill_plan,
             commands::skill_plans::merge_plans_into,
+            commands::skill_plans::move_entries_to_plan,
             commands::skill_plans::replace_plan_entries,
             commands::skill_plans::create_plan_from_character,
+            commands::skill_plans::duplicate_skill_plan,
+            commands::skill_plans::create_plan_from_template,
+            commands::skill_plans::suggest_next_goals,
+            commands::skill_plans::create_plan_for_goal,
             commands::skill_plans::preview_plan_from_character,
             commands::skill_plans::get_all_skill_plans,
             commands::skill_plans::get_skill_plan,
             commands::skill_plans::get_skill_plan_with_entries,
+            commands::skill_plans::set_plan_owner,
+            commands::skill_plans::get_plan_entries_page,
             commands::skill_plans::update_skill_plan,
             commands::skill_plans::delete_skill_plan,
             commands::skill_plans::add_plan_entry,
             commands::skill_plans::update_plan_entry,
+            commands::skill_plans::set_plan_entry_priority,
             commands::skill_plans::delete_plan_entry,
             commands::skill_plans::remove_skill_level,
             commands::skill_plans::remove_skill,
             commands::skill_plans::remove_skill_and_prerequisites,
             commands::skill_plans::reorder_plan_entries,
+            commands::skill_plans::sort_plan,
             commands::skill_plans::validate_reorder,
             commands::skill_plans::validate_skill_plan,
             commands::skill_plans::import_skill_plan_text,
             commands::skill_plans::import_skill_plan_xml,
+            commands::skill_plans::import_skill_plan_emp,
+            commands::skill_plans::import_plan_from_fitting,
+            commands::skill_plans::import_evemon_settings,
             commands::skill_plans::export_skill_plan_text,
+            commands::skill_plans::export_skill_plan_clipboard,
             commands::skill_plans::export_skill_plan_xml,
             commands::skill_plans::export_skill_plan_json,
             commands::skill_plans::import_skill_plan_json,
             commands::skill_plans::search_skills,
+            commands::skill_plans::search_plans,
             commands::skill_plans::compare_skill_plan_with_character,
             commands::skill_plans::compare_skill_plan_with_all_characters,
+            commands::skill_plans::get_plan_assignments,
+            commands::skill_plans::set_plan_assignments,
+            commands::skill_plans::validate_plan_for_alpha,
             commands::skill_plans::simulate_skill_plan,
+            commands::skill_plans::simulate_plan_what_if,
+            commands::skill_plans::sync_plan_with_queue,
+            commands::skill_plans::compare_remap_schedules,
+            commands::skill_plans::save_planned_booster,
+            commands::skill_plans::get_planned_boosters,
+            commands::skill_plans::delete_planned_booster,
             commands::skill_plans::optimize_plan_attributes,
+            commands::skill_plans::recommend_plan_implants,
             commands::skill_plans::optimize_plan_reordering,
+            commands::skill_plans::apply_plan_optimization,
+            commands::skill_plans::optimize_across_plans,
+            commands::skill_plans::cancel_optimization,
             commands::plan_groups::list_plan_groups,
             commands::plan_groups::create_plan_group,
             commands::plan_groups::rename_plan_group,
             commands::plan_groups::delete_plan_group,
             commands::plan_groups::move_node,
+            commands::plan_groups::move_plan_to_group,
             commands::remaps::save_remap,
+            commands::remaps::update_remap,
+            commands::remaps::save_planned_remaps,
             commands::remaps::get_plan_remaps,
             commands::remaps::get_character_remaps,
             commands::remaps::delete_remap,
+            commands::remaps::get_remap_wizard,
             commands::settings::get_app_settings,
             commands::settings::set_boolean_app_setting,
             commands::settings::get_expanded_plan_groups,
@@ -416,9 +569,75 @@ pub fn run() {
             commands::settings::set_excluded_comparison_characters,
             commands::settings::get_enabled_features,
             commands::settings::set_feature_enabled,
+            commands::settings::get_character_features,
+            commands::settings::set_character_features,
+            commands::settings::get_metrics_server_settings,
+            commands::settings::set_metrics_server_settings,
             commands::settings::get_optional_features,
             commands::settings::get_character_feature_scope_status,
-            commands::esi_snapshot::get_esi_snapshot
+            commands::settings::get_character_scopes,
+            commands::settings::get_refresh_dampening,
+            commands::settings::set_refresh_dampening,
+            commands::settings::get_sde_download_settings,
+            commands::settings::set_sde_download_settings,
+            commands::settings::get_sde_import_mode,
+            commands::settings::set_sde_import_mode,
+            commands::settings::get_level_format,
+            commands::settings::set_level_format,
+            commands::maintenance::run_maintenance_now,
+            commands::health::get_app_health,
+            commands::scheduler::get_scheduler_status,
+            commands::scheduler::get_scheduler_settings,
+            commands::scheduler::set_scheduler_settings,
+            commands::scheduler::pause_scheduled_task,
+            commands::scheduler::resume_scheduled_task,
+            commands::storage::get_storage_info,
+            commands::storage::migrate_data_directory,
+            commands::storage::reset_data_directory,
+            commands::storage::get_storage_usage,
+            commands::storage::get_retention_settings,
+            commands::storage::set_retention_settings,
+            commands::storage::restart_app,
+            commands::sync::get_sync_config,
+            commands::sync::set_sync_config,
+            commands::sync::sync_now,
+            commands::esi_snapshot::get_esi_snapshot,
+            commands::esi_explorer::esi_raw_request,
+            commands::esi_explorer::get_esi_explorer_settings,
+            commands::esi_explorer::set_esi_explorer_settings,
+            commands::widget::get_widget_payload,
+            commands::integrations::get_external_links,
+            commands::integrations::open_character_on_zkill,
+            commands::integrations::open_market_window_ingame,
+            commands::integrations::show_info_ingame,
+            commands::integrations::set_waypoint_to_clone,
+            commands::integrations::export_character_for_pyfa,
+            commands::fittings::get_fittings,
+            commands::fittings::push_eft_fitting,
+            commands::onboarding::get_onboarding_suggestions,
+            commands::onboarding::apply_onboarding_choices,
+            commands::plan_comments::get_plan_entry_comments,
+            commands::plan_comments::add_plan_entry_comment,
+            commands::plan_comments::delete_plan_entry_comment,
+            commands::plan_history::get_plan_history,
+            commands::plan_history::revert_plan_to_revision,
+            commands::farm::get_farm_settings,
+            commands::farm::set_farm_settings,
+            commands::farm::get_farm_prices,
+            commands::farm::set_farm_prices,
+            commands::farm::get_farm_rotation_advice,
+            commands::farm::get_extraction_summary,
+            commands::farm::get_omega_budget,
+            commands::events::list_event_contracts,
+            commands::markets::get_market_prices,
+            commands::markets::refresh_market_prices,
+            commands::markets::get_market_type_ids,
+            commands::markets::set_market_type_ids,
+            commands::watchlist::get_watchlist,
+            commands::watchlist::add_watched_character,
+            commands::watchlist::remove_watched_character,
+            commands::watchlist::get_watched_character_changes,
+            commands::watchlist::refresh_watchlist
         ]);
 
     #[cfg(feature = "e2e-testing")]
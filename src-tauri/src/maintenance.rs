@@ -0,0 +1,68 @@
+//! Background database maintenance. Runs at most once per interval and only
+//! while the main window is hidden or unfocused, so a full VACUUM never stalls
+//! someone who is actively using the app.
+
+use tauri::{AppHandle, Manager};
+
+use crate::db;
+
+const LAST_MAINTENANCE_KEY: &str = "last_maintenance_at";
+const MAINTENANCE_INTERVAL_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Run maintenance if a week has passed and the window is idle. Polled by
+/// the scheduler's maintenance task.
+pub async fn run_if_due(app: &AppHandle, pool: &db::Pool) -> anyhow::Result<()> {
+    if !is_idle(app) || !is_due(pool).await {
+        return Ok(());
+    }
+    let report = run(pool).await?;
+    eprintln!(
+        "maintenance: pruned {} rows, reclaimed {} bytes (full vacuum: {})",
+        report.pruned_rows, report.reclaimed_bytes, report.full_vacuum
+    );
+    Ok(())
+}
+
+/// Delete history older than the user's retention settings.
+pub async fn prune(pool: &db::Pool) -> anyhow::Result<db::retention::RetentionReport> {
+    let settings = db::retention::get_retention_settings(pool).await?;
+    db::retention::apply_retention(pool, &settings, chrono::Utc::now()).await
+}
+
+/// Run maintenance now and record the time, regardless of idleness. Pruning
+/// runs first so the vacuum reclaims the freed pages.
+pub async fn run(pool: &db::Pool) -> anyhow::Result<db::maintenance::MaintenanceReport> {
+    let pruned = prune(pool).await?;
+    let mut report = db::maintenance::run_maintenance(pool).await?;
+    report.pruned_rows = pruned.total();
+    db::app_settings::set_app_setting(
+        pool,
+        LAST_MAINTENANCE_KEY,
+        &chrono::Utc::now().timestamp().to_string(),
+    )
+    .await?;
+    Ok(report)
+}
+
+async fn is_due(pool: &db::Pool) -> bool {
+    let last = db::app_settings::get_app_setting(pool, LAST_MAINTENANCE_KEY)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse::<i64>().ok());
+    match last {
+        Some(last) => chrono::Utc::now().timestamp() - last >= MAINTENANCE_INTERVAL_SECS,
+        None => true,
+    }
+}
+
+fn is_idle(app: &AppHandle) -> bool {
+    match app.get_webview_window("main") {
+        Some(window) => {
+            let visible = window.is_visible().unwrap_or(false);
+            let focused = window.is_focused().unwrap_or(false);
+            !visible || !focused
+        }
+        None => true,
+    }
+}
@@ -0,0 +1,369 @@
+//! Prometheus text-format metrics on `http://127.0.0.1:<port>/metrics`, for
+//! users who alert on their skill queues from their own monitoring. Opt-in via
+//! [`MetricsServerSettings`]; nothing listens while it is off.
+//!
+//! Series are labelled by character id only, and requests naming any host
+//! other than the loopback address are refused, so a web page that rebinds
+//! its own name to 127.0.0.1 cannot read the endpoint.
+
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    routing::get,
+    Router,
+};
+use chrono::Utc;
+use tauri::{AppHandle, Manager};
+use tokio_util::sync::CancellationToken;
+
+use crate::db::app_settings::MetricsServerSettings;
+use crate::esi::EsiStats;
+use crate::refresh::{RefreshHealth, RefreshSupervisor};
+use crate::{cache, db, esi, queue_math};
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// What one character contributes to the metrics.
+#[derive(Debug, Clone)]
+pub struct CharacterMetrics {
+    pub character_id: i64,
+    pub refresh: RefreshHealth,
+    /// `None` when no queue is cached yet.
+    pub queue: Option<QueueMetrics>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct QueueMetrics {
+    pub paused: bool,
+    /// Zero for an empty queue; not meaningful while paused.
+    pub remaining: chrono::Duration,
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+pub fn render(characters: &[CharacterMetrics], stats: &EsiStats) -> String {
+    let mut out = String::new();
+    let labels = |c: &CharacterMetrics| format!("character_id=\"{}\"", c.character_id);
+
+    header(
+        &mut out,
+        "skillmon_refresh_duration_seconds",
+        "gauge",
+        "Duration of the character's last successful refresh pass.",
+    );
+    for c in characters {
+        if let Some(duration) = c.refresh.last_pass_duration {
+            let _ = writeln!(
+                out,
+                "skillmon_refresh_duration_seconds{{{}}} {}",
+                labels(c),
+                duration.as_secs_f64()
+            );
+        }
+    }
+
+    header(
+        &mut out,
+        "skillmon_refresh_last_success_timestamp_seconds",
+        "gauge",
+        "Unix time of the character's last successful refresh pass.",
+    );
+    for c in characters {
+        if let Some(at) = c.refresh.last_success_at {
+            let _ = writeln!(
+                out,
+                "skillmon_refresh_last_success_timestamp_seconds{{{}}} {}",
+                labels(c),
+                at
+            );
+        }
+    }
+
+    header(
+        &mut out,
+        "skillmon_skill_queue_paused",
+        "gauge",
+        "1 while the character's skill queue is paused in game.",
+    );
+    for c in characters {
+        if let Some(queue) = c.queue {
+            let _ = writeln!(
+                out,
+                "skillmon_skill_queue_paused{{{}}} {}",
+                labels(c),
+                u8::from(queue.paused)
+            );
+        }
+    }
+
+    header(
+        &mut out,
+        "skillmon_skill_queue_hours_remaining",
+        "gauge",
+        "Training time left in the character's skill queue; absent while paused.",
+    );
+    for c in characters {
+        if let Some(queue) = c.queue.filter(|q| !q.paused) {
+            let _ = writeln!(
+                out,
+                "skillmon_skill_queue_hours_remaining{{{}}} {}",
+                labels(c),
+                queue.remaining.num_seconds() as f64 / 3600.0
+            );
+        }
+    }
+
+    header(
+        &mut out,
+        "skillmon_esi_errors_total",
+        "counter",
+        "ESI error responses since startup, by status code.",
+    );
+    for (status, count) in &stats.errors {
+        let _ = writeln!(
+            out,
+            "skillmon_esi_errors_total{{status=\"{}\"}} {}",
+            status, count
+        );
+    }
+
+    header(
+        &mut out,
+        "skillmon_esi_cache_requests_total",
+        "counter",
+        "Cached ESI reads since startup, by whether the cache answered them.",
+    );
+    let _ = writeln!(
+        out,
+        "skillmon_esi_cache_requests_total{{result=\"hit\"}} {}",
+        stats.cache_hits
+    );
+    let _ = writeln!(
+        out,
+        "skillmon_esi_cache_requests_total{{result=\"miss\"}} {}",
+        stats.cache_misses
+    );
+
+    header(
+        &mut out,
+        "skillmon_esi_cache_hit_ratio",
+        "gauge",
+        "Share of cached ESI reads answered without a full response.",
+    );
+    let total = stats.cache_hits + stats.cache_misses;
+    let ratio = if total == 0 {
+        0.0
+    } else {
+        stats.cache_hits as f64 / total as f64
+    };
+    let _ = writeln!(out, "skillmon_esi_cache_hit_ratio {}", ratio);
+
+    out
+}
+
+async fn queue_metrics(pool: &db::Pool, character_id: i64) -> Result<Option<QueueMetrics>> {
+    let endpoint_path = format!("characters/{}/skillqueue", character_id);
+    let cache_key = cache::build_cache_key(&endpoint_path, character_id);
+    let Some(entry) = cache::get_cached_response(pool, &cache_key).await? else {
+        return Ok(None);
+    };
+    let queue: Vec<esi::CharactersSkillqueueSkill> = serde_json::from_str(&entry.response_body)?;
+    Ok(Some(
+        match queue_math::remaining_training(&queue, Utc::now()) {
+            Some(remaining) => QueueMetrics {
+                paused: false,
+                remaining,
+            },
+            None => QueueMetrics {
+                paused: true,
+                remaining: chrono::Duration::zero(),
+            },
+        },
+    ))
+}
+
+async fn collect(app: &AppHandle) -> Result<String> {
+    let pool = app.state::<db::Pool>().inner().clone();
+    let stats = app
+        .state::<esi::RateLimitStore>()
+        .read()
+        .await
+        .stats
+        .clone();
+    let health = app
+        .state::<Mutex<RefreshSupervisor>>()
+        .lock()
+        .unwrap()
+        .all_health();
+
+    let mut characters = Vec::new();
+    for character in db::get_all_characters(&pool).await? {
+        characters.push(CharacterMetrics {
+            character_id: character.character_id,
+            refresh: health
+                .get(&character.character_id)
+                .cloned()
+                .unwrap_or_default(),
+            queue: queue_metrics(&pool, character.character_id).await?,
+        });
+    }
+
+    Ok(render(&characters, &stats))
+}
+
+/// Whether a request's `Host` header names this listener by its loopback
+/// address or `localhost`.
+fn host_allowed(host: Option<&str>, port: u16) -> bool {
+    host.is_some_and(|host| {
+        host == format!("127.0.0.1:{}", port) || host == format!("localhost:{}", port)
+    })
+}
+
+#[derive(Clone)]
+struct ServerState {
+    app: AppHandle,
+    port: u16,
+}
+
+async fn metrics(
+    State(state): State<ServerState>,
+    headers: HeaderMap,
+) -> Result<([(header::HeaderName, &'static str); 1], String), StatusCode> {
+    let host = headers.get(header::HOST).and_then(|h| h.to_str().ok());
+    if !host_allowed(host, state.port) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    match collect(&state.app).await {
+        Ok(body) => Ok(([(header::CONTENT_TYPE, CONTENT_TYPE)], body)),
+        Err(e) => {
+            eprintln!("metrics: failed to collect: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+async fn serve(app: AppHandle, port: u16, cancel: CancellationToken) {
+    let router = Router::new()
+        .route("/metrics", get(metrics))
+        .with_state(ServerState { app, port });
+    let addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+
+    // A listener being replaced may still hold the port for a moment.
+    let mut attempts = 0;
+    let listener = loop {
+        match tokio::net::TcpListener::bind(addr).await {
+            Ok(listener) => break listener,
+            Err(e) if attempts < 5 => {
+                attempts += 1;
+                eprintln!("metrics: bind {} failed, retrying: {}", addr, e);
+                tokio::time::sleep(Duration::from_millis(200)).await;
+            }
+            Err(e) => {
+                eprintln!("metrics: could not listen on {}: {}", addr, e);
+                return;
+            }
+        }
+    };
+
+    if let Err(e) = axum::serve(listener, router)
+        .with_graceful_shutdown(cancel.cancelled_owned())
+        .await
+    {
+        eprintln!("metrics: server error: {}", e);
+    }
+}
+
+/// The running metrics listener, if any.
+#[derive(Default)]
+pub struct MetricsServer(Mutex<Option<CancellationToken>>);
+
+impl MetricsServer {
+    /// Stops the current listener and starts a new one when `settings` ask
+    /// for it.
+    pub fn apply(&self, app: &AppHandle, settings: &MetricsServerSettings) {
+        let mut running = self.0.lock().unwrap();
+        if let Some(cancel) = running.take() {
+            cancel.cancel();
+        }
+        if !settings.enabled {
+            return;
+        }
+        let Ok(port) = u16::try_from(settings.port) else {
+            eprintln!("metrics: invalid port {}", settings.port);
+            return;
+        };
+        let cancel = CancellationToken::new();
+        tauri::async_runtime::spawn(serve(app.clone(), port, cancel.clone()));
+        *running = Some(cancel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_prometheus_text() {
+        let characters = vec![
+            CharacterMetrics {
+                character_id: 1,
+                refresh: RefreshHealth {
+                    last_success_at: Some(1_700_000_000),
+                    last_pass_duration: Some(Duration::from_millis(1500)),
+                    ..RefreshHealth::default()
+                },
+                queue: Some(QueueMetrics {
+                    paused: false,
+                    remaining: chrono::Duration::minutes(90),
+                }),
+            },
+            CharacterMetrics {
+                character_id: 2,
+                refresh: RefreshHealth::default(),
+                queue: Some(QueueMetrics {
+                    paused: true,
+                    remaining: chrono::Duration::zero(),
+                }),
+            },
+        ];
+        let stats = EsiStats {
+            errors: [(502, 2)].into_iter().collect(),
+            cache_hits: 3,
+            cache_misses: 1,
+        };
+
+        let text = render(&characters, &stats);
+        let pilot = r#"character_id="1""#;
+        assert!(text.contains(&format!(
+            "skillmon_refresh_duration_seconds{{{}}} 1.5",
+            pilot
+        )));
+        assert!(text.contains(&format!(
+            "skillmon_skill_queue_hours_remaining{{{}}} 1.5",
+            pilot
+        )));
+        assert!(text.contains(r#"skillmon_skill_queue_paused{character_id="2"} 1"#));
+        assert!(!text.contains(r#"skillmon_skill_queue_hours_remaining{character_id="2""#));
+        assert!(text.contains(r#"skillmon_esi_errors_total{status="502"} 2"#));
+        assert!(text.contains("skillmon_esi_cache_hit_ratio 0.75"));
+    }
+
+    #[test]
+    fn only_loopback_hosts_on_our_port_are_served() {
+        assert!(host_allowed(Some("127.0.0.1:9184"), 9184));
+        assert!(host_allowed(Some("localhost:9184"), 9184));
+
+        assert!(!host_allowed(None, 9184));
+        assert!(!host_allowed(Some("localhost:80"), 9184));
+        assert!(!host_allowed(Some("evil.example:9184"), 9184));
+        assert!(!host_allowed(Some("localhost"), 9184));
+    }
+}
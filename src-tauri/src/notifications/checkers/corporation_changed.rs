@@ -0,0 +1,93 @@
+use anyhow::Result;
+
+use crate::db;
+use crate::notifications::{self, sinks, DataType, NotificationChecker, NotificationContext};
+
+pub const NOTIFICATION_TYPE_CORPORATION_CHANGED: &str = "corporation_changed";
+
+/// Fires once per membership change recorded in the character's membership
+/// history (e.g. an inactive alt kicked back to an NPC corporation). On unless
+/// the character's setting row disables it.
+pub struct CorporationChangedChecker;
+
+#[async_trait::async_trait]
+impl NotificationChecker for CorporationChangedChecker {
+    fn notification_type(&self) -> &'static str {
+        NOTIFICATION_TYPE_CORPORATION_CHANGED
+    }
+
+    fn data_triggers(&self) -> &[DataType] {
+        &[DataType::Affiliation]
+    }
+
+    async fn check(&self, ctx: &NotificationContext<'_>, character_id: i64) -> Result<()> {
+        let setting = db::get_notification_setting(
+            ctx.pool,
+            character_id,
+            NOTIFICATION_TYPE_CORPORATION_CHANGED,
+        )
+        .await?;
+        if !setting.map(|s| s.enabled).unwrap_or(true) {
+            let cleared = db::clear_notification(
+                ctx.pool,
+                character_id,
+                NOTIFICATION_TYPE_CORPORATION_CHANGED,
+            )
+            .await?;
+            if cleared {
+                if let Err(e) = notifications::emit_snapshot(ctx.app, ctx.pool).await {
+                    eprintln!("Failed to emit notifications snapshot: {}", e);
+                }
+            }
+            return Ok(());
+        }
+
+        let history = db::membership::get_membership_history(ctx.pool, character_id).await?;
+        let [current, previous, ..] = history.as_slice() else {
+            return Ok(());
+        };
+
+        // Once per change: a dismissed notification for this change stays dismissed.
+        if db::has_notification_since(
+            ctx.pool,
+            character_id,
+            NOTIFICATION_TYPE_CORPORATION_CHANGED,
+            &current.observed_at,
+        )
+        .await?
+        {
+            return Ok(());
+        }
+
+        let title = "Corporation Changed";
+        let message = format!(
+            "Left {} and is now in {}",
+            previous.affiliation().label(),
+            current.affiliation().label()
+        );
+
+        db::create_notification(
+            ctx.pool,
+            character_id,
+            NOTIFICATION_TYPE_CORPORATION_CHANGED,
+            title,
+            &message,
+        )
+        .await?;
+
+        if let Err(e) = notifications::emit_snapshot(ctx.app, ctx.pool).await {
+            eprintln!("Failed to emit notifications snapshot: {}", e);
+        }
+
+        sinks::deliver(
+            ctx,
+            character_id,
+            NOTIFICATION_TYPE_CORPORATION_CHANGED,
+            title,
+            &message,
+        )
+        .await;
+
+        Ok(())
+    }
+}
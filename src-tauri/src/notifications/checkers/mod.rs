@@ -1,3 +1,15 @@
+pub mod corporation_changed;
+pub mod omega_lapsed;
+pub mod omega_runway_low;
+pub mod refresh_stale;
+pub mod skill_queue_changed;
 pub mod skill_queue_low;
+pub mod skill_queue_stalled;
 
+pub use corporation_changed::CorporationChangedChecker;
+pub use omega_lapsed::OmegaLapsedChecker;
+pub use omega_runway_low::OmegaRunwayLowChecker;
+pub use refresh_stale::RefreshStaleChecker;
+pub use skill_queue_changed::SkillQueueChangedChecker;
 pub use skill_queue_low::SkillQueueLowChecker;
+pub use skill_queue_stalled::{SkillQueueEmptyChecker, SkillQueuePausedChecker};
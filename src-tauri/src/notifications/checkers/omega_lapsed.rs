@@ -0,0 +1,94 @@
+use anyhow::Result;
+
+use crate::db;
+use crate::notifications::{self, sinks, DataType, NotificationChecker, NotificationContext};
+
+pub const NOTIFICATION_TYPE_OMEGA_LAPSED: &str = "omega_lapsed";
+
+/// Fires once when a character previously inferred as Omega now looks like an
+/// Alpha (see `infer_is_omega` in refresh/enrichment.rs). Unlike skill queue
+/// low this has no config, so it is on unless the character's setting row
+/// explicitly disables it.
+pub struct OmegaLapsedChecker;
+
+#[async_trait::async_trait]
+impl NotificationChecker for OmegaLapsedChecker {
+    fn notification_type(&self) -> &'static str {
+        NOTIFICATION_TYPE_OMEGA_LAPSED
+    }
+
+    fn data_triggers(&self) -> &[DataType] {
+        &[DataType::SkillQueue]
+    }
+
+    async fn check(&self, ctx: &NotificationContext<'_>, character_id: i64) -> Result<()> {
+        let setting =
+            db::get_notification_setting(ctx.pool, character_id, NOTIFICATION_TYPE_OMEGA_LAPSED)
+                .await?;
+        let enabled = setting.map(|s| s.enabled).unwrap_or(true);
+
+        let Some(character) = db::get_character(ctx.pool, character_id).await? else {
+            return Ok(());
+        };
+
+        if !enabled || character.is_omega {
+            let cleared =
+                db::clear_notification(ctx.pool, character_id, NOTIFICATION_TYPE_OMEGA_LAPSED)
+                    .await?;
+            if cleared {
+                if let Err(e) = notifications::emit_snapshot(ctx.app, ctx.pool).await {
+                    eprintln!("Failed to emit notifications snapshot: {}", e);
+                }
+            }
+            return Ok(());
+        }
+
+        // No stamp means no refresh or override confirmed the character as
+        // Omega before it read as Alpha (a new Alpha character, or the column
+        // predates the lapse) — nothing "dropped".
+        let Some(lapsed_at) = db::get_character_omega_lapsed_at(ctx.pool, character_id).await?
+        else {
+            return Ok(());
+        };
+
+        // Once per lapse: a dismissed notification for this lapse stays dismissed.
+        if db::has_notification_since(
+            ctx.pool,
+            character_id,
+            NOTIFICATION_TYPE_OMEGA_LAPSED,
+            &lapsed_at,
+        )
+        .await?
+        {
+            return Ok(());
+        }
+
+        let title = "Omega Lapsed";
+        let message = "Training rate and skill levels indicate this character is now Alpha; \
+                       training time estimates use the Alpha rate";
+
+        db::create_notification(
+            ctx.pool,
+            character_id,
+            NOTIFICATION_TYPE_OMEGA_LAPSED,
+            title,
+            message,
+        )
+        .await?;
+
+        if let Err(e) = notifications::emit_snapshot(ctx.app, ctx.pool).await {
+            eprintln!("Failed to emit notifications snapshot: {}", e);
+        }
+
+        sinks::deliver(
+            ctx,
+            character_id,
+            NOTIFICATION_TYPE_OMEGA_LAPSED,
+            title,
+            message,
+        )
+        .await;
+
+        Ok(())
+    }
+}
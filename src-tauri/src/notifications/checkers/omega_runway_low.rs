@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use anyhow::Result;
+
+use crate::db;
+use crate::notifications::{self, sinks, DataType, NotificationChecker, NotificationContext};
+use crate::omega_budget;
+
+pub const NOTIFICATION_TYPE_OMEGA_RUNWAY_LOW: &str = "omega_runway_low";
+
+const DEFAULT_THRESHOLD_MONTHS: f64 = 3.0;
+
+/// Fires when the combined wallets can pay for fewer months of Omega and MCT
+/// than the configured `threshold_months` (see omega_budget.rs). The budget
+/// spans every account, so this is opt-in: it only runs for characters with
+/// an enabled setting row.
+pub struct OmegaRunwayLowChecker;
+
+#[async_trait::async_trait]
+impl NotificationChecker for OmegaRunwayLowChecker {
+    fn notification_type(&self) -> &'static str {
+        NOTIFICATION_TYPE_OMEGA_RUNWAY_LOW
+    }
+
+    fn data_triggers(&self) -> &[DataType] {
+        &[]
+    }
+
+    /// Wallets and PLEX prices are not refreshed as data types, so this polls.
+    fn check_interval(&self) -> Option<Duration> {
+        Some(Duration::from_secs(6 * 60 * 60))
+    }
+
+    async fn check(&self, ctx: &NotificationContext<'_>, character_id: i64) -> Result<()> {
+        let setting = db::get_notification_setting(
+            ctx.pool,
+            character_id,
+            NOTIFICATION_TYPE_OMEGA_RUNWAY_LOW,
+        )
+        .await?;
+
+        let Some(setting) = setting.filter(|s| s.enabled) else {
+            let cleared =
+                db::clear_notification(ctx.pool, character_id, NOTIFICATION_TYPE_OMEGA_RUNWAY_LOW)
+                    .await?;
+            if cleared {
+                if let Err(e) = notifications::emit_snapshot(ctx.app, ctx.pool).await {
+                    eprintln!("Failed to emit notifications snapshot: {}", e);
+                }
+            }
+            return Ok(());
+        };
+
+        let threshold_months = setting
+            .config
+            .as_deref()
+            .and_then(|config| serde_json::from_str::<serde_json::Value>(config).ok())
+            .and_then(|config| config.get("threshold_months").and_then(|v| v.as_f64()))
+            .unwrap_or(DEFAULT_THRESHOLD_MONTHS);
+
+        let budget = omega_budget::omega_budget(ctx.pool, ctx.rate_limits).await?;
+        // Unknown PLEX price or nothing to pay for - skip this notification
+        let Some(runway_months) = budget.runway_months else {
+            return Ok(());
+        };
+
+        let has_active =
+            db::has_active_notification(ctx.pool, character_id, NOTIFICATION_TYPE_OMEGA_RUNWAY_LOW)
+                .await?;
+
+        if runway_months < threshold_months {
+            if !has_active {
+                let title = "Omega Runway Low";
+                let message = format!(
+                    "Wallets cover {:.1} months of Omega and MCT (below {} month threshold)",
+                    runway_months, threshold_months
+                );
+
+                db::create_notification(
+                    ctx.pool,
+                    character_id,
+                    NOTIFICATION_TYPE_OMEGA_RUNWAY_LOW,
+                    title,
+                    &message,
+                )
+                .await?;
+
+                if let Err(e) = notifications::emit_snapshot(ctx.app, ctx.pool).await {
+                    eprintln!("Failed to emit notifications snapshot: {}", e);
+                }
+
+                sinks::deliver(
+                    ctx,
+                    character_id,
+                    NOTIFICATION_TYPE_OMEGA_RUNWAY_LOW,
+                    title,
+                    &message,
+                )
+                .await;
+            }
+        } else if has_active {
+            let cleared =
+                db::clear_notification(ctx.pool, character_id, NOTIFICATION_TYPE_OMEGA_RUNWAY_LOW)
+                    .await?;
+            if cleared {
+                if let Err(e) = notifications::emit_snapshot(ctx.app, ctx.pool).await {
+                    eprintln!("Failed to emit notifications snapshot: {}", e);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
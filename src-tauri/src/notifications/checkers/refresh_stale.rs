@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::Utc;
+
+use crate::db;
+use crate::notifications::{self, sinks, DataType, NotificationChecker, NotificationContext};
+
+pub const NOTIFICATION_TYPE_REFRESH_STALE: &str = "refresh_stale";
+
+const DEFAULT_THRESHOLD_DAYS: f64 = 7.0;
+
+/// Fires when a character has gone `threshold_days` without a successful
+/// refresh, which usually means its refresh token expired or was revoked and
+/// no other alert for it can fire. On unless the character's setting row
+/// disables it; clears once a refresh succeeds again.
+pub struct RefreshStaleChecker;
+
+/// Whole days since `last_success_at` when that is past the threshold.
+pub fn stale_days(last_success_at: i64, now: i64, threshold_days: f64) -> Option<i64> {
+    let days = (now - last_success_at) as f64 / 86_400.0;
+    (days >= threshold_days).then_some(days.floor() as i64)
+}
+
+#[async_trait::async_trait]
+impl NotificationChecker for RefreshStaleChecker {
+    fn notification_type(&self) -> &'static str {
+        NOTIFICATION_TYPE_REFRESH_STALE
+    }
+
+    fn data_triggers(&self) -> &[DataType] {
+        &[]
+    }
+
+    /// A dead token produces no data updates, so this polls.
+    fn check_interval(&self) -> Option<Duration> {
+        Some(Duration::from_secs(60 * 60))
+    }
+
+    async fn check(&self, ctx: &NotificationContext<'_>, character_id: i64) -> Result<()> {
+        let setting =
+            db::get_notification_setting(ctx.pool, character_id, NOTIFICATION_TYPE_REFRESH_STALE)
+                .await?;
+        let enabled = setting.as_ref().map(|s| s.enabled).unwrap_or(true);
+        let threshold_days = setting
+            .and_then(|s| s.config)
+            .and_then(|config| serde_json::from_str::<serde_json::Value>(&config).ok())
+            .and_then(|config| config.get("threshold_days").and_then(|v| v.as_f64()))
+            .unwrap_or(DEFAULT_THRESHOLD_DAYS);
+
+        let last_success_at =
+            db::characters::get_last_refresh_success_at(ctx.pool, character_id).await?;
+        let stale = match last_success_at {
+            Some(at) if enabled => stale_days(at, Utc::now().timestamp(), threshold_days),
+            _ => None,
+        };
+
+        let Some(days) = stale else {
+            let cleared =
+                db::clear_notification(ctx.pool, character_id, NOTIFICATION_TYPE_REFRESH_STALE)
+                    .await?;
+            if cleared {
+                if let Err(e) = notifications::emit_snapshot(ctx.app, ctx.pool).await {
+                    eprintln!("Failed to emit notifications snapshot: {}", e);
+                }
+            }
+            return Ok(());
+        };
+
+        if db::has_active_notification(ctx.pool, character_id, NOTIFICATION_TYPE_REFRESH_STALE)
+            .await?
+        {
+            return Ok(());
+        }
+
+        let title = "Character Not Refreshing";
+        let message = format!(
+            "No successful refresh in {} days; the login has likely expired. \
+             Re-authorize the character to resume skill queue alerts",
+            days
+        );
+
+        db::create_notification(
+            ctx.pool,
+            character_id,
+            NOTIFICATION_TYPE_REFRESH_STALE,
+            title,
+            &message,
+        )
+        .await?;
+
+        if let Err(e) = notifications::emit_snapshot(ctx.app, ctx.pool).await {
+            eprintln!("Failed to emit notifications snapshot: {}", e);
+        }
+
+        sinks::deliver(
+            ctx,
+            character_id,
+            NOTIFICATION_TYPE_REFRESH_STALE,
+            title,
+            &message,
+        )
+        .await;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_once_the_threshold_has_passed() {
+        let now = 1_700_000_000;
+        assert_eq!(stale_days(now - 6 * 86_400, now, 7.0), None);
+        assert_eq!(stale_days(now - 7 * 86_400, now, 7.0), Some(7));
+        assert_eq!(stale_days(now - 10 * 86_400 - 3_600, now, 7.0), Some(10));
+        assert_eq!(stale_days(now - 12 * 3_600, now, 0.5), Some(0));
+    }
+}
@@ -0,0 +1,277 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use crate::cache;
+use crate::db;
+use crate::db::skill_queue_journal::{QueueChange, QueueChangeKind, QueueSnapshotEntry};
+use crate::esi::schema::SkillQueueEntry;
+use crate::notifications::{self, DataType, NotificationChecker, NotificationContext};
+
+pub const NOTIFICATION_TYPE_SKILL_QUEUE_CHANGED: &str = "skill_queue_changed";
+
+/// Compares each fetched queue against the last one seen and journals any
+/// difference that training alone cannot explain: ESI has no write access to
+/// the queue, so such a change was made in game, possibly by someone else on
+/// a shared account. Journaling always happens; the informational
+/// notification is on unless the character's setting row disables it.
+pub struct SkillQueueChangedChecker;
+
+#[async_trait::async_trait]
+impl NotificationChecker for SkillQueueChangedChecker {
+    fn notification_type(&self) -> &'static str {
+        NOTIFICATION_TYPE_SKILL_QUEUE_CHANGED
+    }
+
+    fn data_triggers(&self) -> &[DataType] {
+        &[DataType::SkillQueue]
+    }
+
+    async fn check(&self, ctx: &NotificationContext<'_>, character_id: i64) -> Result<()> {
+        let Some(current) = get_cached_queue_entries(ctx.pool, character_id).await? else {
+            return Ok(());
+        };
+
+        let now = Utc::now();
+        let previous = db::skill_queue_journal::get_queue_snapshot(ctx.pool, character_id).await?;
+        // First sighting only establishes the baseline.
+        let changes = previous
+            .map(|previous| diff_queue(&previous, &current, now))
+            .unwrap_or_default();
+
+        db::skill_queue_journal::record_queue_snapshot(
+            ctx.pool,
+            character_id,
+            &current,
+            &changes,
+            now.timestamp(),
+        )
+        .await?;
+
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        let name = db::get_character(ctx.pool, character_id)
+            .await?
+            .map(|c| c.character_name)
+            .unwrap_or_else(|| character_id.to_string());
+        db::activity_feed::record_activity(
+            ctx.pool,
+            Some(character_id),
+            db::activity_feed::ACTIVITY_QUEUE_CHANGED,
+            &format!(
+                "Queue changed for {}: {}",
+                name,
+                summarize_changes(&changes)
+            ),
+        )
+        .await?;
+
+        let setting = db::get_notification_setting(
+            ctx.pool,
+            character_id,
+            NOTIFICATION_TYPE_SKILL_QUEUE_CHANGED,
+        )
+        .await?;
+        if !setting.map(|s| s.enabled).unwrap_or(true) {
+            return Ok(());
+        }
+
+        // Keep a single active notice describing the latest edit.
+        db::clear_notification(
+            ctx.pool,
+            character_id,
+            NOTIFICATION_TYPE_SKILL_QUEUE_CHANGED,
+        )
+        .await?;
+        db::create_notification(
+            ctx.pool,
+            character_id,
+            NOTIFICATION_TYPE_SKILL_QUEUE_CHANGED,
+            "Skill Queue Changed",
+            &describe_changes(&changes),
+        )
+        .await?;
+
+        if let Err(e) = notifications::emit_snapshot(ctx.app, ctx.pool).await {
+            eprintln!("Failed to emit notifications snapshot: {}", e);
+        }
+
+        Ok(())
+    }
+}
+
+/// Differences between two queue snapshots, ignoring entries that dropped off
+/// because their training finished before `now`.
+pub fn diff_queue(
+    previous: &[QueueSnapshotEntry],
+    current: &[QueueSnapshotEntry],
+    now: DateTime<Utc>,
+) -> Vec<QueueChange> {
+    let key = |e: &QueueSnapshotEntry| (e.skill_id, e.level);
+    let current_keys: HashSet<(i64, i64)> = current.iter().map(key).collect();
+    let previous_keys: HashSet<(i64, i64)> = previous.iter().map(key).collect();
+
+    let completed = |e: &QueueSnapshotEntry| {
+        e.finish_date
+            .as_deref()
+            .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+            .is_some_and(|d| d.with_timezone(&Utc) <= now)
+    };
+
+    let mut changes = Vec::new();
+    for entry in previous {
+        if !current_keys.contains(&key(entry)) && !completed(entry) {
+            changes.push(QueueChange {
+                kind: QueueChangeKind::Removed,
+                skill_id: Some(entry.skill_id),
+                level: Some(entry.level),
+            });
+        }
+    }
+    for entry in current {
+        if !previous_keys.contains(&key(entry)) {
+            changes.push(QueueChange {
+                kind: QueueChangeKind::Added,
+                skill_id: Some(entry.skill_id),
+                level: Some(entry.level),
+            });
+        }
+    }
+
+    let kept_previous: Vec<_> = previous
+        .iter()
+        .map(key)
+        .filter(|k| current_keys.contains(k))
+        .collect();
+    let kept_current: Vec<_> = current
+        .iter()
+        .map(key)
+        .filter(|k| previous_keys.contains(k))
+        .collect();
+    if kept_previous != kept_current {
+        changes.push(QueueChange {
+            kind: QueueChangeKind::Reordered,
+            skill_id: None,
+            level: None,
+        });
+    }
+
+    changes
+}
+
+fn describe_changes(changes: &[QueueChange]) -> String {
+    format!(
+        "Skill queue was edited in game: {}. See the queue journal for details",
+        summarize_changes(changes)
+    )
+}
+
+fn summarize_changes(changes: &[QueueChange]) -> String {
+    let count = |kind| changes.iter().filter(|c| c.kind == kind).count();
+    let added = count(QueueChangeKind::Added);
+    let removed = count(QueueChangeKind::Removed);
+
+    let mut parts = Vec::new();
+    if added > 0 {
+        parts.push(format!("{} added", added));
+    }
+    if removed > 0 {
+        parts.push(format!("{} removed", removed));
+    }
+    if count(QueueChangeKind::Reordered) > 0 {
+        parts.push("reordered".to_string());
+    }
+    parts.join(", ")
+}
+
+async fn get_cached_queue_entries(
+    pool: &db::Pool,
+    character_id: i64,
+) -> Result<Option<Vec<QueueSnapshotEntry>>> {
+    let endpoint_path = format!("characters/{}/skillqueue", character_id);
+    let cache_key = cache::build_cache_key(&endpoint_path, character_id);
+
+    let mut queue_data = match cache::get_cached_response(pool, &cache_key).await? {
+        Some(entry) => serde_json::from_str::<Vec<SkillQueueEntry>>(&entry.response_body)?,
+        None => return Ok(None),
+    };
+    queue_data.sort_by_key(|item| item.queue_position);
+
+    Ok(Some(
+        queue_data
+            .into_iter()
+            .map(|item| QueueSnapshotEntry {
+                skill_id: item.skill_id,
+                level: item.finished_level,
+                finish_date: item.finish_date,
+            })
+            .collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(skill_id: i64, level: i64, finish_date: Option<&str>) -> QueueSnapshotEntry {
+        QueueSnapshotEntry {
+            skill_id,
+            level,
+            finish_date: finish_date.map(str::to_string),
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-01-10T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn completed_training_is_not_a_change() {
+        let previous = vec![
+            entry(3300, 4, Some("2026-01-09T00:00:00Z")),
+            entry(3300, 5, Some("2026-01-20T00:00:00Z")),
+        ];
+        let current = vec![entry(3300, 5, Some("2026-01-20T00:00:00Z"))];
+        assert!(diff_queue(&previous, &current, now()).is_empty());
+    }
+
+    #[test]
+    fn detects_added_removed_and_reordered() {
+        let previous = vec![
+            entry(3300, 4, Some("2026-01-11T00:00:00Z")),
+            entry(3327, 3, Some("2026-01-12T00:00:00Z")),
+            entry(3301, 1, Some("2026-01-13T00:00:00Z")),
+        ];
+        let current = vec![
+            entry(3327, 3, Some("2026-01-11T00:00:00Z")),
+            entry(3300, 4, Some("2026-01-12T00:00:00Z")),
+            entry(3392, 2, Some("2026-01-13T00:00:00Z")),
+        ];
+        let changes = diff_queue(&previous, &current, now());
+        let kinds: Vec<_> = changes.iter().map(|c| c.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                QueueChangeKind::Removed,
+                QueueChangeKind::Added,
+                QueueChangeKind::Reordered
+            ]
+        );
+        assert_eq!(changes[0].skill_id, Some(3301));
+        assert_eq!(changes[1].skill_id, Some(3392));
+    }
+
+    #[test]
+    fn removal_from_paused_queue_is_a_change() {
+        let previous = vec![entry(3300, 4, None), entry(3327, 3, None)];
+        let current = vec![entry(3327, 3, None)];
+        let changes = diff_queue(&previous, &current, now());
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].kind, QueueChangeKind::Removed);
+    }
+}
@@ -1,10 +1,13 @@
+use std::time::Duration;
+
 use anyhow::Result;
-use chrono::{DateTime, Utc};
-use tauri_plugin_notification::NotificationExt;
+use chrono::Utc;
 
 use crate::cache;
 use crate::db;
-use crate::notifications::{self, DataType, NotificationChecker, NotificationContext};
+use crate::esi::schema::SkillQueueEntry;
+use crate::notifications::{self, sinks, DataType, NotificationChecker, NotificationContext};
+use crate::queue_math;
 
 pub const NOTIFICATION_TYPE_SKILL_QUEUE_LOW: &str = "skill_queue_low";
 
@@ -20,6 +23,12 @@ impl NotificationChecker for SkillQueueLowChecker {
         &[DataType::SkillQueue]
     }
 
+    /// The queue runs down between refreshes, and a paused refresh loop
+    /// (e.g. an expired token) would otherwise never notice.
+    fn check_interval(&self) -> Option<Duration> {
+        Some(Duration::from_secs(15 * 60))
+    }
+
     async fn check(&self, ctx: &NotificationContext<'_>, character_id: i64) -> Result<()> {
         let setting =
             db::get_notification_setting(ctx.pool, character_id, NOTIFICATION_TYPE_SKILL_QUEUE_LOW)
@@ -27,6 +36,13 @@ impl NotificationChecker for SkillQueueLowChecker {
 
         if let Some(setting) = setting {
             if !setting.enabled {
+                db::reset_fired_stages(
+                    ctx.pool,
+                    character_id,
+                    NOTIFICATION_TYPE_SKILL_QUEUE_LOW,
+                    None,
+                )
+                .await?;
                 let cleared = db::clear_notification(
                     ctx.pool,
                     character_id,
@@ -41,24 +57,26 @@ impl NotificationChecker for SkillQueueLowChecker {
                 return Ok(());
             }
 
-            let threshold_hours: f64 = if let Some(config_str) = &setting.config {
-                if let Ok(config) = serde_json::from_str::<serde_json::Value>(config_str) {
-                    config
-                        .get("threshold_hours")
-                        .and_then(|v| v.as_f64())
-                        .unwrap_or(24.0)
-                } else {
-                    24.0
-                }
-            } else {
-                24.0
-            };
+            let config = setting
+                .config
+                .as_deref()
+                .and_then(|config| serde_json::from_str::<serde_json::Value>(config).ok());
+            let stages = config.as_ref().map(stages_from_config).unwrap_or_default();
 
             let total_hours = match get_cached_queue_hours(ctx.pool, character_id).await? {
                 Some(hours) => hours,
                 None => return Ok(()), // Paused queue or missing cache - skip this notification
             };
 
+            if !stages.is_empty() {
+                return check_stages(ctx, character_id, &stages, total_hours).await;
+            }
+
+            let threshold_hours = config
+                .as_ref()
+                .and_then(|config| config.get("threshold_hours").and_then(|v| v.as_f64()))
+                .unwrap_or(24.0);
+
             let has_active = db::has_active_notification(
                 ctx.pool,
                 character_id,
@@ -79,13 +97,6 @@ impl NotificationChecker for SkillQueueLowChecker {
                         hours_str, threshold_hours
                     );
 
-                    let character_name = db::get_character(ctx.pool, character_id)
-                        .await
-                        .ok()
-                        .flatten()
-                        .map(|c| c.character_name)
-                        .unwrap_or_else(|| format!("Character {}", character_id));
-
                     db::create_notification(
                         ctx.pool,
                         character_id,
@@ -99,17 +110,14 @@ impl NotificationChecker for SkillQueueLowChecker {
                         eprintln!("Failed to emit notifications snapshot: {}", e);
                     }
 
-                    let notification_title = format!("{} - {}", character_name, title);
-                    if let Err(e) = ctx
-                        .app
-                        .notification()
-                        .builder()
-                        .title(&notification_title)
-                        .body(&message)
-                        .show()
-                    {
-                        eprintln!("Failed to send system notification: {}", e);
-                    }
+                    sinks::deliver(
+                        ctx,
+                        character_id,
+                        NOTIFICATION_TYPE_SKILL_QUEUE_LOW,
+                        title,
+                        &message,
+                    )
+                    .await;
                 }
             } else if has_active {
                 let cleared = db::clear_notification(
@@ -130,39 +138,161 @@ impl NotificationChecker for SkillQueueLowChecker {
     }
 }
 
+/// `stages_hours` from the config, e.g. `[72, 24, 4, 0]`, highest first.
+/// Empty means the single `threshold_hours` mode.
+fn stages_from_config(config: &serde_json::Value) -> Vec<f64> {
+    let mut stages: Vec<f64> = config
+        .get("stages_hours")
+        .and_then(|v| v.as_array())
+        .map(|stages| {
+            stages
+                .iter()
+                .filter_map(|v| v.as_f64())
+                .filter(|h| *h >= 0.0)
+                .collect()
+        })
+        .unwrap_or_default();
+    stages.sort_by(|a, b| b.total_cmp(a));
+    stages.dedup();
+    stages
+}
+
+/// The stage to notify for with `total_hours` left, if one is due: the lowest
+/// stage reached that has not fired yet. A queue is at a stage once its time
+/// left is at or below it, so stage 0 means empty.
+fn due_stage(stages: &[f64], fired: &[f64], total_hours: f64) -> Option<f64> {
+    stages
+        .iter()
+        .copied()
+        .filter(|stage| total_hours <= *stage)
+        .filter(|stage| !fired.contains(stage))
+        .reduce(f64::min)
+}
+
+/// Multi-stage mode: one notification per stage per drain cycle, replacing the
+/// previous stage's. Reaching a stage also counts the higher ones it skipped
+/// as fired; topping the queue back up above a stage re-arms it.
+async fn check_stages(
+    ctx: &NotificationContext<'_>,
+    character_id: i64,
+    stages: &[f64],
+    total_hours: f64,
+) -> Result<()> {
+    db::reset_fired_stages(
+        ctx.pool,
+        character_id,
+        NOTIFICATION_TYPE_SKILL_QUEUE_LOW,
+        Some(total_hours),
+    )
+    .await?;
+
+    let reached: Vec<f64> = stages
+        .iter()
+        .copied()
+        .filter(|stage| total_hours <= *stage)
+        .collect();
+    if reached.is_empty() {
+        let cleared =
+            db::clear_notification(ctx.pool, character_id, NOTIFICATION_TYPE_SKILL_QUEUE_LOW)
+                .await?;
+        if cleared {
+            if let Err(e) = notifications::emit_snapshot(ctx.app, ctx.pool).await {
+                eprintln!("Failed to emit notifications snapshot: {}", e);
+            }
+        }
+        return Ok(());
+    }
+
+    let fired =
+        db::get_fired_stages(ctx.pool, character_id, NOTIFICATION_TYPE_SKILL_QUEUE_LOW).await?;
+    let Some(stage) = due_stage(stages, &fired, total_hours) else {
+        return Ok(());
+    };
+
+    let (title, message) = if stage == 0.0 {
+        (
+            "Skill Queue Empty",
+            "Skill queue has run out of training time".to_string(),
+        )
+    } else {
+        let hours_str = if total_hours < 1.0 {
+            format!("{:.1} hours", total_hours)
+        } else {
+            format!("{:.0} hours", total_hours)
+        };
+        (
+            "Skill Queue Low",
+            format!(
+                "Skill queue has {} remaining (below {} hour stage)",
+                hours_str, stage
+            ),
+        )
+    };
+
+    db::clear_notification(ctx.pool, character_id, NOTIFICATION_TYPE_SKILL_QUEUE_LOW).await?;
+    db::create_notification(
+        ctx.pool,
+        character_id,
+        NOTIFICATION_TYPE_SKILL_QUEUE_LOW,
+        title,
+        &message,
+    )
+    .await?;
+    db::record_fired_stages(
+        ctx.pool,
+        character_id,
+        NOTIFICATION_TYPE_SKILL_QUEUE_LOW,
+        &reached,
+    )
+    .await?;
+
+    if let Err(e) = notifications::emit_snapshot(ctx.app, ctx.pool).await {
+        eprintln!("Failed to emit notifications snapshot: {}", e);
+    }
+
+    sinks::deliver(
+        ctx,
+        character_id,
+        NOTIFICATION_TYPE_SKILL_QUEUE_LOW,
+        title,
+        &message,
+    )
+    .await;
+
+    Ok(())
+}
+
 async fn get_cached_queue_hours(pool: &db::Pool, character_id: i64) -> Result<Option<f64>> {
     let endpoint_path = format!("characters/{}/skillqueue", character_id);
     let cache_key = cache::build_cache_key(&endpoint_path, character_id);
 
     let queue_data = match cache::get_cached_response(pool, &cache_key).await? {
-        Some(entry) => serde_json::from_str::<Vec<serde_json::Value>>(&entry.response_body)?,
+        Some(entry) => serde_json::from_str::<Vec<SkillQueueEntry>>(&entry.response_body)?,
         None => return Ok(None), // No cache = skip notification check
     };
 
-    let now = Utc::now();
-    let mut has_skills = false;
-    let mut has_finish_dates = false;
-    let mut last_finish: Option<DateTime<Utc>> = None;
-
-    for item in &queue_data {
-        has_skills = true;
-        if let Some(finish_str) = item.get("finish_date").and_then(|v| v.as_str()) {
-            has_finish_dates = true;
-            if let Ok(finish_dt) = DateTime::parse_from_rfc3339(finish_str) {
-                let finish_utc = finish_dt.with_timezone(&Utc);
-                if now < finish_utc && last_finish.is_none_or(|prev| finish_utc > prev) {
-                    last_finish = Some(finish_utc);
-                }
-            }
-        }
-    }
+    // A paused queue has no remaining time and skips the check
+    Ok(queue_math::remaining_training(&queue_data, Utc::now())
+        .map(|remaining| remaining.num_seconds() as f64 / 3600.0))
+}
 
-    // Paused queue (skills exist but no finish dates) - skip
-    if has_skills && !has_finish_dates {
-        return Ok(None);
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(Some(last_finish.map_or(0.0, |finish| {
-        (finish - now).num_seconds() as f64 / 3600.0
-    })))
+    #[test]
+    fn stages_fire_once_from_highest_to_empty() {
+        let stages = stages_from_config(&serde_json::json!({ "stages_hours": [4, 72, 0, 24, 24] }));
+        assert_eq!(stages, vec![72.0, 24.0, 4.0, 0.0]);
+
+        assert_eq!(due_stage(&stages, &[], 100.0), None);
+        assert_eq!(due_stage(&stages, &[], 60.0), Some(72.0));
+        assert_eq!(due_stage(&stages, &[72.0], 60.0), None);
+        // A refresh gap that skips a stage notifies for the lowest one reached.
+        assert_eq!(due_stage(&stages, &[72.0], 3.0), Some(4.0));
+        assert_eq!(due_stage(&stages, &[72.0, 24.0, 4.0], 0.0), Some(0.0));
+        assert_eq!(due_stage(&stages, &[72.0, 24.0, 4.0, 0.0], 0.0), None);
+
+        assert!(stages_from_config(&serde_json::json!({ "threshold_hours": 24 })).is_empty());
+    }
 }
@@ -0,0 +1,197 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+
+use crate::cache;
+use crate::db;
+use crate::esi::schema::SkillQueueEntry;
+use crate::notifications::{self, sinks, DataType, NotificationChecker, NotificationContext};
+use crate::queue_math::{self, QueueItem};
+
+pub const NOTIFICATION_TYPE_SKILL_QUEUE_PAUSED: &str = "skill_queue_paused";
+pub const NOTIFICATION_TYPE_SKILL_QUEUE_EMPTY: &str = "skill_queue_empty";
+
+/// Why a queue is not training. A paused queue still holds entries but has no
+/// finish dates (paused in game); an empty one has nothing left to train.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueueStall {
+    Paused,
+    Empty,
+}
+
+impl QueueStall {
+    fn notification_type(self) -> &'static str {
+        match self {
+            QueueStall::Paused => NOTIFICATION_TYPE_SKILL_QUEUE_PAUSED,
+            QueueStall::Empty => NOTIFICATION_TYPE_SKILL_QUEUE_EMPTY,
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            QueueStall::Paused => "Skill Queue Paused",
+            QueueStall::Empty => "Skill Queue Empty",
+        }
+    }
+
+    fn message(self) -> &'static str {
+        match self {
+            QueueStall::Paused => "Training is paused in game; queued skills are not progressing",
+            QueueStall::Empty => "Nothing is queued; no skill points are being trained",
+        }
+    }
+}
+
+/// `None` while something is (or will resume) training at `now`.
+pub fn queue_stall<T: QueueItem>(queue: &[T], now: DateTime<Utc>) -> Option<QueueStall> {
+    if queue_math::is_paused(queue) {
+        return Some(QueueStall::Paused);
+    }
+    if queue.iter().any(|item| queue_math::is_pending(item, now)) {
+        return None;
+    }
+    Some(QueueStall::Empty)
+}
+
+/// Fires while the queue is paused. On unless the character's setting row
+/// disables it; clears once training resumes.
+pub struct SkillQueuePausedChecker;
+
+/// Fires while the queue is empty, including once the last queued level
+/// finishes. That happens on the clock, so it also runs on a schedule.
+pub struct SkillQueueEmptyChecker;
+
+#[async_trait::async_trait]
+impl NotificationChecker for SkillQueuePausedChecker {
+    fn notification_type(&self) -> &'static str {
+        NOTIFICATION_TYPE_SKILL_QUEUE_PAUSED
+    }
+
+    fn data_triggers(&self) -> &[DataType] {
+        &[DataType::SkillQueue]
+    }
+
+    async fn check(&self, ctx: &NotificationContext<'_>, character_id: i64) -> Result<()> {
+        check_stall(ctx, character_id, QueueStall::Paused).await
+    }
+}
+
+#[async_trait::async_trait]
+impl NotificationChecker for SkillQueueEmptyChecker {
+    fn notification_type(&self) -> &'static str {
+        NOTIFICATION_TYPE_SKILL_QUEUE_EMPTY
+    }
+
+    fn data_triggers(&self) -> &[DataType] {
+        &[DataType::SkillQueue]
+    }
+
+    fn check_interval(&self) -> Option<Duration> {
+        Some(Duration::from_secs(15 * 60))
+    }
+
+    async fn check(&self, ctx: &NotificationContext<'_>, character_id: i64) -> Result<()> {
+        check_stall(ctx, character_id, QueueStall::Empty).await
+    }
+}
+
+async fn check_stall(
+    ctx: &NotificationContext<'_>,
+    character_id: i64,
+    stall: QueueStall,
+) -> Result<()> {
+    let notification_type = stall.notification_type();
+    let setting = db::get_notification_setting(ctx.pool, character_id, notification_type).await?;
+    let enabled = setting.map(|s| s.enabled).unwrap_or(true);
+
+    // Missing cache: nothing known about the queue, leave any notice as is.
+    let Some(queue_data) = get_cached_queue(ctx.pool, character_id).await? else {
+        return Ok(());
+    };
+
+    if !enabled || queue_stall(&queue_data, Utc::now()) != Some(stall) {
+        let cleared = db::clear_notification(ctx.pool, character_id, notification_type).await?;
+        if cleared {
+            if let Err(e) = notifications::emit_snapshot(ctx.app, ctx.pool).await {
+                eprintln!("Failed to emit notifications snapshot: {}", e);
+            }
+        }
+        return Ok(());
+    }
+
+    if db::has_active_notification(ctx.pool, character_id, notification_type).await? {
+        return Ok(());
+    }
+
+    let title = stall.title();
+    let message = stall.message();
+
+    db::create_notification(ctx.pool, character_id, notification_type, title, message).await?;
+
+    if let Err(e) = notifications::emit_snapshot(ctx.app, ctx.pool).await {
+        eprintln!("Failed to emit notifications snapshot: {}", e);
+    }
+
+    sinks::deliver(ctx, character_id, notification_type, title, message).await;
+
+    Ok(())
+}
+
+async fn get_cached_queue(
+    pool: &db::Pool,
+    character_id: i64,
+) -> Result<Option<Vec<SkillQueueEntry>>> {
+    let endpoint_path = format!("characters/{}/skillqueue", character_id);
+    let cache_key = cache::build_cache_key(&endpoint_path, character_id);
+
+    match cache::get_cached_response(pool, &cache_key).await? {
+        Some(entry) => Ok(Some(serde_json::from_str(&entry.response_body)?)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::esi;
+    use chrono::Duration;
+
+    fn item(
+        start: Option<DateTime<Utc>>,
+        finish: Option<DateTime<Utc>>,
+    ) -> esi::CharactersSkillqueueSkill {
+        esi::CharactersSkillqueueSkill {
+            skill_id: 3300,
+            finished_level: 3,
+            queue_position: 0,
+            start_date: start,
+            finish_date: finish,
+            training_start_sp: Some(0),
+            level_start_sp: Some(0),
+            level_end_sp: Some(1000),
+        }
+    }
+
+    #[test]
+    fn distinguishes_paused_from_empty() {
+        let now = Utc::now();
+        let hour = Duration::hours(1);
+
+        let none: [esi::CharactersSkillqueueSkill; 0] = [];
+        assert_eq!(queue_stall(&none, now), Some(QueueStall::Empty));
+        assert_eq!(
+            queue_stall(&[item(None, None), item(None, None)], now),
+            Some(QueueStall::Paused)
+        );
+        assert_eq!(
+            queue_stall(&[item(Some(now - hour), Some(now + hour))], now),
+            None
+        );
+        // Everything queued has finished since the last refresh.
+        assert_eq!(
+            queue_stall(&[item(Some(now - hour * 2), Some(now - hour))], now),
+            Some(QueueStall::Empty)
+        );
+    }
+}
@@ -1,18 +1,24 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
-use tauri::{AppHandle, Emitter};
+use tauri::AppHandle;
 
 use crate::commands::notifications::NotificationResponse;
 use crate::db;
 use crate::esi;
+use crate::events;
 
 pub mod checkers;
+pub mod sinks;
+
+/// How often [`run_scheduled_checks`] wakes to see which checkers are due.
+const SCHEDULE_TICK: Duration = Duration::from_secs(60);
 
 pub struct NotificationContext<'a> {
     pub app: &'a AppHandle,
     pub pool: &'a db::Pool,
-    #[allow(dead_code)] // May be used by future notification checkers
     pub rate_limits: &'a esi::RateLimitStore,
 }
 
@@ -24,6 +30,8 @@ pub enum DataType {
     Attributes,
     Clones,
     Location,
+    /// Corporation/alliance membership, from public ESI.
+    Affiliation,
 }
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
@@ -36,17 +44,27 @@ pub struct DataUpdatedPayload {
 pub trait NotificationChecker: Send + Sync {
     fn notification_type(&self) -> &'static str;
     fn data_triggers(&self) -> &[DataType];
+    /// Cadence for re-running the check for every character while no new
+    /// data arrives, for checks that depend on the clock. `None` (the
+    /// default) runs it only on its data triggers.
+    fn check_interval(&self) -> Option<Duration> {
+        None
+    }
     async fn check(&self, ctx: &NotificationContext<'_>, character_id: i64) -> Result<()>;
 }
 
 pub struct NotificationProcessor {
     checkers: Vec<Arc<dyn NotificationChecker>>,
+    /// When each scheduled checker last ran, by notification type. Starts at
+    /// construction so the first pass waits one interval behind the refresh.
+    last_scheduled: Mutex<HashMap<&'static str, Instant>>,
 }
 
 impl NotificationProcessor {
     pub fn new() -> Self {
         let mut processor = Self {
             checkers: Vec::new(),
+            last_scheduled: Mutex::new(HashMap::new()),
         };
         processor.register_checkers();
         processor
@@ -54,6 +72,62 @@ impl NotificationProcessor {
 
     fn register_checkers(&mut self) {
         self.checkers.push(Arc::new(checkers::SkillQueueLowChecker));
+        self.checkers
+            .push(Arc::new(checkers::SkillQueuePausedChecker));
+        self.checkers
+            .push(Arc::new(checkers::SkillQueueEmptyChecker));
+        self.checkers.push(Arc::new(checkers::OmegaLapsedChecker));
+        self.checkers
+            .push(Arc::new(checkers::OmegaRunwayLowChecker));
+        self.checkers
+            .push(Arc::new(checkers::SkillQueueChangedChecker));
+        self.checkers
+            .push(Arc::new(checkers::CorporationChangedChecker));
+        self.checkers.push(Arc::new(checkers::RefreshStaleChecker));
+    }
+
+    /// Scheduled checkers whose interval has elapsed at `now`, marked as run.
+    fn take_due(&self, now: Instant) -> Vec<Arc<dyn NotificationChecker>> {
+        let mut last_scheduled = self.last_scheduled.lock().unwrap();
+        self.checkers
+            .iter()
+            .filter(|checker| {
+                let Some(interval) = checker.check_interval() else {
+                    return false;
+                };
+                let last = last_scheduled
+                    .entry(checker.notification_type())
+                    .or_insert(now);
+                if now.duration_since(*last) < interval {
+                    return false;
+                }
+                *last = now;
+                true
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Runs every due scheduled checker for every character.
+    pub async fn process_scheduled(&self, ctx: &NotificationContext<'_>) -> Result<()> {
+        let due = self.take_due(Instant::now());
+        if due.is_empty() {
+            return Ok(());
+        }
+        let characters = db::get_all_characters(ctx.pool).await?;
+        for checker in due {
+            for character in &characters {
+                if let Err(e) = checker.check(ctx, character.character_id).await {
+                    eprintln!(
+                        "Scheduled notification check failed for {} (character {}): {}",
+                        checker.notification_type(),
+                        character.character_id,
+                        e
+                    );
+                }
+            }
+        }
+        Ok(())
     }
 
     pub async fn process_data_updated(
@@ -84,8 +158,26 @@ impl Default for NotificationProcessor {
     }
 }
 
-pub const EVENT_DATA_UPDATED: &str = "notification:data-updated";
-pub const EVENT_NOTIFICATIONS_CHANGED: &str = "notifications:changed";
+/// Background loop running the clock-driven checkers between data refreshes.
+pub async fn run_scheduled_checks(
+    app: AppHandle,
+    pool: db::Pool,
+    rate_limits: esi::RateLimitStore,
+    processor: Arc<NotificationProcessor>,
+) {
+    let mut ticker = tokio::time::interval(SCHEDULE_TICK);
+    loop {
+        ticker.tick().await;
+        let ctx = NotificationContext {
+            app: &app,
+            pool: &pool,
+            rate_limits: &rate_limits,
+        };
+        if let Err(e) = processor.process_scheduled(&ctx).await {
+            eprintln!("Failed to run scheduled notification checks: {}", e);
+        }
+    }
+}
 
 pub async fn emit_snapshot(app: &AppHandle, pool: &db::Pool) -> Result<()> {
     let notifications = db::get_notifications(pool, None, None).await?;
@@ -93,7 +185,64 @@ pub async fn emit_snapshot(app: &AppHandle, pool: &db::Pool) -> Result<()> {
         .into_iter()
         .map(NotificationResponse::from)
         .collect();
-    app.emit(EVENT_NOTIFICATIONS_CHANGED, payload)
+    events::emit_notifications_changed(app, &payload)
         .map_err(|e| anyhow::anyhow!("Failed to emit notifications snapshot: {}", e))?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Scheduled(&'static str, Option<Duration>);
+
+    #[async_trait::async_trait]
+    impl NotificationChecker for Scheduled {
+        fn notification_type(&self) -> &'static str {
+            self.0
+        }
+
+        fn data_triggers(&self) -> &[DataType] {
+            &[]
+        }
+
+        fn check_interval(&self) -> Option<Duration> {
+            self.1
+        }
+
+        async fn check(&self, _ctx: &NotificationContext<'_>, _character_id: i64) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn scheduled_checkers_run_once_per_interval() {
+        let processor = NotificationProcessor {
+            checkers: vec![
+                Arc::new(Scheduled("fast", Some(Duration::from_secs(60)))),
+                Arc::new(Scheduled("slow", Some(Duration::from_secs(600)))),
+                Arc::new(Scheduled("data_only", None)),
+            ],
+            last_scheduled: Mutex::new(HashMap::new()),
+        };
+        let types = |due: Vec<Arc<dyn NotificationChecker>>| {
+            due.iter()
+                .map(|c| c.notification_type())
+                .collect::<Vec<_>>()
+        };
+        let start = Instant::now();
+
+        assert!(processor.take_due(start).is_empty());
+        assert_eq!(
+            types(processor.take_due(start + Duration::from_secs(60))),
+            vec!["fast"]
+        );
+        assert!(processor
+            .take_due(start + Duration::from_secs(90))
+            .is_empty());
+        assert_eq!(
+            types(processor.take_due(start + Duration::from_secs(600))),
+            vec!["fast", "slow"]
+        );
+    }
+}
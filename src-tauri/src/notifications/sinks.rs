@@ -0,0 +1,154 @@
+//! Where a raised notification is delivered besides the in-app list: the OS
+//! notification centre and any Discord webhooks the type is routed to.
+//! Checkers call [`deliver`] once after storing the notification; a failing
+//! sink is logged and never stops the others.
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::db;
+use crate::db::notification_webhooks::NotificationWebhook;
+use crate::notifications::NotificationContext;
+
+pub struct OutgoingNotification<'a> {
+    pub character_name: String,
+    pub notification_type: &'a str,
+    pub title: &'a str,
+    pub message: &'a str,
+}
+
+impl OutgoingNotification<'_> {
+    fn heading(&self) -> String {
+        format!("{} - {}", self.character_name, self.title)
+    }
+}
+
+#[async_trait::async_trait]
+pub trait NotificationSink: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn deliver(
+        &self,
+        ctx: &NotificationContext<'_>,
+        notification: &OutgoingNotification<'_>,
+    ) -> Result<()>;
+}
+
+pub struct SystemSink;
+
+#[async_trait::async_trait]
+impl NotificationSink for SystemSink {
+    fn name(&self) -> &'static str {
+        "system"
+    }
+
+    async fn deliver(
+        &self,
+        ctx: &NotificationContext<'_>,
+        notification: &OutgoingNotification<'_>,
+    ) -> Result<()> {
+        ctx.app
+            .notification()
+            .builder()
+            .title(notification.heading())
+            .body(notification.message)
+            .show()?;
+        Ok(())
+    }
+}
+
+/// Posts to every enabled webhook the notification's type is routed to.
+pub struct DiscordSink;
+
+#[derive(Serialize)]
+struct DiscordEmbed<'a> {
+    title: String,
+    description: &'a str,
+}
+
+#[derive(Serialize)]
+struct DiscordPayload<'a> {
+    username: &'static str,
+    embeds: [DiscordEmbed<'a>; 1],
+}
+
+pub async fn post_to_webhook(
+    webhook: &NotificationWebhook,
+    notification: &OutgoingNotification<'_>,
+) -> Result<()> {
+    let payload = DiscordPayload {
+        username: "skillmon",
+        embeds: [DiscordEmbed {
+            title: notification.heading(),
+            description: notification.message,
+        }],
+    };
+    let response = reqwest::Client::new()
+        .post(&webhook.url)
+        .json(&payload)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        bail!(
+            "Discord webhook '{}' returned {}",
+            webhook.name,
+            response.status()
+        );
+    }
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl NotificationSink for DiscordSink {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    async fn deliver(
+        &self,
+        ctx: &NotificationContext<'_>,
+        notification: &OutgoingNotification<'_>,
+    ) -> Result<()> {
+        let webhooks = db::notification_webhooks::get_webhooks_for_type(
+            ctx.pool,
+            notification.notification_type,
+        )
+        .await?;
+        for webhook in &webhooks {
+            if let Err(e) = post_to_webhook(webhook, notification).await {
+                eprintln!("Failed to post notification: {}", e);
+            }
+        }
+        Ok(())
+    }
+}
+
+const SINKS: [&dyn NotificationSink; 2] = [&SystemSink, &DiscordSink];
+
+/// Sends a notification the checker has just stored to every sink.
+pub async fn deliver(
+    ctx: &NotificationContext<'_>,
+    character_id: i64,
+    notification_type: &str,
+    title: &str,
+    message: &str,
+) {
+    let character_name = db::get_character(ctx.pool, character_id)
+        .await
+        .ok()
+        .flatten()
+        .map(|c| c.character_name)
+        .unwrap_or_else(|| format!("Character {}", character_id));
+    let notification = OutgoingNotification {
+        character_name,
+        notification_type,
+        title,
+        message,
+    };
+
+    for sink in SINKS {
+        if let Err(e) = sink.deliver(ctx, &notification).await {
+            eprintln!("Failed to send {} notification: {}", sink.name(), e);
+        }
+    }
+}
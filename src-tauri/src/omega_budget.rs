@@ -0,0 +1,249 @@
+//! How many months of Omega the liquid ISK in the characters' wallets can buy.
+//!
+//! Each account needs one month of Omega (500 PLEX) if any of its characters
+//! is Omega, plus one Multiple Character Training certificate (485 PLEX) for
+//! every character training beyond the first. Training is read from the cached
+//! skill queues so the estimate costs no queue requests; wallets come from ESI
+//! for characters whose token carries the wallet scope and whose wallet module
+//! is on. Characters without an account count as an account of their own.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use typeshare::typeshare;
+
+use crate::esi::schema::SkillQueueEntry;
+use crate::esi::EsiScope;
+use crate::farm::OMEGA_PLEX_PER_MONTH;
+use crate::features::CharacterModule;
+use crate::ts_types::{i64_ts, usize_ts};
+use crate::{auth, cache, db, esi, esi_helpers, queue_math};
+
+/// PLEX for one month of Multiple Character Training bought in game.
+const MCT_PLEX_PER_MONTH: f64 = 485.0;
+
+const PLEX_TYPE_ID: i64 = 44992;
+
+#[derive(Debug, Clone)]
+pub struct BudgetCharacter {
+    pub character_id: i64,
+    pub account_id: Option<i64>,
+    pub is_omega: bool,
+    pub training: bool,
+    /// None when the wallet could not be read.
+    pub wallet_isk: Option<f64>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccountUpkeep {
+    /// None for a character not assigned to an account.
+    pub account_id: Option<i64_ts>,
+    pub character_ids: Vec<i64_ts>,
+    pub omega: bool,
+    pub training_characters: usize_ts,
+    pub mct_slots: usize_ts,
+    pub plex_per_month: f64,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OmegaBudget {
+    pub wallet_isk: f64,
+    /// Characters whose wallet was not included (no wallet scope or the
+    /// request failed).
+    pub characters_without_wallet: Vec<i64_ts>,
+    /// ISK per PLEX; None when no market or farm price is known.
+    pub plex_price: Option<f64>,
+    pub plex_per_month: f64,
+    pub isk_per_month: Option<f64>,
+    /// None when the price is unknown or nothing needs paying for.
+    pub runway_months: Option<f64>,
+    pub accounts: Vec<AccountUpkeep>,
+}
+
+pub fn summarize(characters: &[BudgetCharacter], plex_price: Option<f64>) -> OmegaBudget {
+    let mut groups: BTreeMap<(Option<i64>, i64), Vec<&BudgetCharacter>> = BTreeMap::new();
+    for character in characters {
+        let key = match character.account_id {
+            Some(account_id) => (Some(account_id), 0),
+            None => (None, character.character_id),
+        };
+        groups.entry(key).or_default().push(character);
+    }
+
+    let accounts: Vec<AccountUpkeep> = groups
+        .into_iter()
+        .map(|((account_id, _), members)| {
+            let omega = members.iter().any(|c| c.is_omega);
+            let training_characters = members.iter().filter(|c| c.training).count();
+            let mct_slots = if omega {
+                training_characters.saturating_sub(1)
+            } else {
+                0
+            };
+            let plex_per_month = if omega {
+                OMEGA_PLEX_PER_MONTH + mct_slots as f64 * MCT_PLEX_PER_MONTH
+            } else {
+                0.0
+            };
+            AccountUpkeep {
+                account_id,
+                character_ids: members.iter().map(|c| c.character_id).collect(),
+                omega,
+                training_characters,
+                mct_slots,
+                plex_per_month,
+            }
+        })
+        .collect();
+
+    let wallet_isk = characters.iter().filter_map(|c| c.wallet_isk).sum();
+    let characters_without_wallet = characters
+        .iter()
+        .filter(|c| c.wallet_isk.is_none())
+        .map(|c| c.character_id)
+        .collect();
+    let plex_per_month: f64 = accounts.iter().map(|a| a.plex_per_month).sum();
+    let isk_per_month = plex_price.map(|price| price * plex_per_month);
+    let runway_months = isk_per_month
+        .filter(|&cost| cost > 0.0)
+        .map(|cost| wallet_isk / cost);
+
+    OmegaBudget {
+        wallet_isk,
+        characters_without_wallet,
+        plex_price,
+        plex_per_month,
+        isk_per_month,
+        runway_months,
+        accounts,
+    }
+}
+
+/// The market's lowest PLEX sell order, falling back to the price entered for
+/// the farm calculator.
+async fn plex_price(pool: &db::Pool) -> Result<Option<f64>> {
+    let market = db::market_prices::get_market_price(pool, PLEX_TYPE_ID)
+        .await?
+        .and_then(|price| price.sell_min);
+    if market.is_some() {
+        return Ok(market);
+    }
+    let farm = db::farm::get_farm_prices(pool).await?.plex;
+    Ok((farm > 0.0).then_some(farm))
+}
+
+async fn is_training(pool: &db::Pool, character_id: i64) -> Result<bool> {
+    let endpoint_path = format!("characters/{}/skillqueue", character_id);
+    let cache_key = cache::build_cache_key(&endpoint_path, character_id);
+    let Some(entry) = cache::get_cached_response(pool, &cache_key).await? else {
+        return Ok(false);
+    };
+    let queue: Vec<SkillQueueEntry> = serde_json::from_str(&entry.response_body)?;
+    Ok(queue_math::currently_training(&queue, Utc::now()).is_some())
+}
+
+async fn wallet_balance(
+    pool: &db::Pool,
+    character_id: i64,
+    rate_limits: &esi::RateLimitStore,
+) -> Result<Option<f64>> {
+    if !db::character_modules::is_module_enabled(pool, character_id, CharacterModule::Wallet)
+        .await?
+    {
+        return Ok(None);
+    }
+    let missing =
+        auth::oauth::check_token_scopes(pool, character_id, &[EsiScope::ReadCharacterWalletV1])
+            .await?;
+    if !missing.is_empty() {
+        return Ok(None);
+    }
+    let access_token = auth::ensure_valid_access_token(pool, character_id).await?;
+    let client = esi_helpers::create_authenticated_client(&access_token)?;
+    esi_helpers::get_cached_character_wallet(pool, &client, character_id, rate_limits).await
+}
+
+pub async fn omega_budget(
+    pool: &db::Pool,
+    rate_limits: &esi::RateLimitStore,
+) -> Result<OmegaBudget> {
+    let mut characters = Vec::new();
+    for character in db::get_all_characters(pool).await? {
+        let wallet_isk = match wallet_balance(pool, character.character_id, rate_limits).await {
+            Ok(balance) => balance,
+            Err(e) => {
+                eprintln!(
+                    "Failed to fetch wallet for character {}: {}",
+                    character.character_id, e
+                );
+                None
+            }
+        };
+        characters.push(BudgetCharacter {
+            character_id: character.character_id,
+            account_id: character.account_id,
+            is_omega: character.is_omega,
+            training: is_training(pool, character.character_id).await?,
+            wallet_isk,
+        });
+    }
+    Ok(summarize(&characters, plex_price(pool).await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn character(
+        character_id: i64,
+        account_id: Option<i64>,
+        training: bool,
+        wallet_isk: Option<f64>,
+    ) -> BudgetCharacter {
+        BudgetCharacter {
+            character_id,
+            account_id,
+            is_omega: true,
+            training,
+            wallet_isk,
+        }
+    }
+
+    #[test]
+    fn summarize_charges_omega_and_mct_per_account() {
+        let characters = vec![
+            character(1, Some(10), true, Some(3_000_000_000.0)),
+            character(2, Some(10), true, Some(1_000_000_000.0)),
+            character(3, Some(10), false, None),
+            character(4, None, true, Some(0.0)),
+            BudgetCharacter {
+                is_omega: false,
+                ..character(5, None, true, None)
+            },
+        ];
+
+        let budget = summarize(&characters, Some(4_000_000.0));
+
+        assert_eq!(budget.accounts.len(), 3);
+        let account = budget
+            .accounts
+            .iter()
+            .find(|a| a.account_id == Some(10))
+            .unwrap();
+        assert_eq!(account.training_characters, 2);
+        assert_eq!(account.mct_slots, 1);
+        assert_eq!(account.plex_per_month, 985.0);
+        assert_eq!(budget.plex_per_month, 1485.0);
+        assert_eq!(budget.wallet_isk, 4_000_000_000.0);
+        assert_eq!(budget.characters_without_wallet, vec![3, 5]);
+        assert_eq!(budget.isk_per_month, Some(5_940_000_000.0));
+        let runway = budget.runway_months.unwrap();
+        assert!((runway - 4.0 / 5.94).abs() < 1e-9);
+
+        assert_eq!(summarize(&characters, None).runway_months, None);
+    }
+}
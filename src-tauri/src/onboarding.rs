@@ -0,0 +1,498 @@
+//! First-run suggestions for a newly added character: which skill goals to
+//! work towards, starter plans bundling those goals, and notification
+//! defaults. Suggestions are computed from the character's skills, SP and
+//! active implants; applying them creates everything in one transaction.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::db;
+use crate::notifications::checkers::{
+    omega_lapsed::NOTIFICATION_TYPE_OMEGA_LAPSED,
+    refresh_stale::NOTIFICATION_TYPE_REFRESH_STALE,
+    skill_queue_low::NOTIFICATION_TYPE_SKILL_QUEUE_LOW,
+    skill_queue_stalled::{
+        NOTIFICATION_TYPE_SKILL_QUEUE_EMPTY, NOTIFICATION_TYPE_SKILL_QUEUE_PAUSED,
+    },
+};
+use crate::skill_plans::graph::{PlanDag, PlanNode};
+use crate::ts_types::{i64_ts, usize_ts};
+
+/// Characters below this many total SP are treated as brand-new players.
+const NEW_PLAYER_SP_THRESHOLD: i64 = 5_000_000;
+
+struct GoalSetDef {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    skills: &'static [(&'static str, i64)],
+}
+
+struct PlanTemplateDef {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    goal_set_ids: &'static [&'static str],
+}
+
+const GOAL_SETS: &[GoalSetDef] = &[
+    GoalSetDef {
+        id: "fitting",
+        name: "Fitting fundamentals",
+        description: "CPU, powergrid and tanking basics that make almost every fit work.",
+        skills: &[
+            ("CPU Management", 4),
+            ("Power Grid Management", 4),
+            ("Hull Upgrades", 3),
+            ("Mechanics", 3),
+            ("Shield Upgrades", 3),
+        ],
+    },
+    GoalSetDef {
+        id: "navigation",
+        name: "Getting around",
+        description: "Faster sub-warp speed, warp and alignment.",
+        skills: &[
+            ("Navigation", 4),
+            ("Warp Drive Operation", 3),
+            ("Evasive Maneuvering", 3),
+            ("Spaceship Command", 3),
+        ],
+    },
+    GoalSetDef {
+        id: "drones",
+        name: "Drones",
+        description: "Light drones for damage on nearly any hull.",
+        skills: &[
+            ("Drones", 4),
+            ("Light Drone Operation", 4),
+            ("Drone Avionics", 3),
+        ],
+    },
+    GoalSetDef {
+        id: "cybernetics",
+        name: "Implants",
+        description: "Cybernetics to plug in attribute implants and train faster.",
+        skills: &[("Cybernetics", 4)],
+    },
+    GoalSetDef {
+        id: "exploration",
+        name: "Exploration",
+        description: "Scanning down and hacking data and relic sites.",
+        skills: &[("Astrometrics", 3), ("Hacking", 3), ("Archaeology", 3)],
+    },
+    GoalSetDef {
+        id: "mining",
+        name: "Mining",
+        description: "Ore mining in a mining frigate.",
+        skills: &[("Mining", 4), ("Mining Frigate", 3), ("Astrogeology", 3)],
+    },
+];
+
+const PLAN_TEMPLATES: &[PlanTemplateDef] = &[
+    PlanTemplateDef {
+        id: "starter-combat",
+        name: "Starter: combat pilot",
+        description: "Fitting, navigation and drones for missions and PvE.",
+        goal_set_ids: &["fitting", "navigation", "drones"],
+    },
+    PlanTemplateDef {
+        id: "starter-explorer",
+        name: "Starter: explorer",
+        description: "Fitting, navigation and the scanning/hacking core.",
+        goal_set_ids: &["fitting", "navigation", "exploration"],
+    },
+    PlanTemplateDef {
+        id: "starter-miner",
+        name: "Starter: miner",
+        description: "Fitting, mining and drones for protection.",
+        goal_set_ids: &["fitting", "mining", "drones"],
+    },
+];
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct GoalSetSuggestion {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// Skill levels (including prerequisites) the character has yet to train.
+    pub remaining_skill_levels: usize_ts,
+    pub recommended: bool,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanTemplateSuggestion {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub goal_set_ids: Vec<String>,
+    pub recommended: bool,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationDefaultSuggestion {
+    pub notification_type: String,
+    pub config: Option<String>,
+    pub reason: String,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct OnboardingSuggestions {
+    pub character_id: i64_ts,
+    pub total_sp: i64_ts,
+    pub is_new_player: bool,
+    pub has_implants: bool,
+    pub goal_sets: Vec<GoalSetSuggestion>,
+    pub plan_templates: Vec<PlanTemplateSuggestion>,
+    pub notification_defaults: Vec<NotificationDefaultSuggestion>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Deserialize)]
+pub struct OnboardingChoices {
+    #[serde(default)]
+    pub goal_set_ids: Vec<String>,
+    #[serde(default)]
+    pub plan_template_ids: Vec<String>,
+    #[serde(default)]
+    pub notification_types: Vec<String>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct OnboardingResult {
+    pub created_plan_ids: Vec<i64_ts>,
+    pub enabled_notification_types: Vec<String>,
+}
+
+struct CharacterProfile {
+    is_omega: bool,
+    total_sp: i64,
+    has_implants: bool,
+    trained: HashMap<i64, i64>,
+}
+
+async fn load_profile(pool: &db::Pool, character_id: i64) -> Result<CharacterProfile> {
+    let Some(character) = db::get_character(pool, character_id).await? else {
+        bail!("Character {} not found", character_id);
+    };
+    let skills = db::get_character_skills(pool, character_id).await?;
+    let total_sp =
+        character.unallocated_sp + skills.iter().map(|s| s.skillpoints_in_skill).sum::<i64>();
+
+    let mut has_implants = false;
+    for clone in db::get_character_clones(pool, character_id).await? {
+        if clone.is_current {
+            has_implants = !db::get_clone_implants(pool, clone.id).await?.is_empty();
+            break;
+        }
+    }
+
+    Ok(CharacterProfile {
+        is_omega: character.is_omega,
+        total_sp,
+        has_implants,
+        trained: skills
+            .into_iter()
+            .map(|s| (s.skill_id, s.trained_skill_level))
+            .collect(),
+    })
+}
+
+/// The untrained skill levels needed to reach every goal in `goal_sets`,
+/// prerequisites included, in trainable order. Goals whose skill is missing
+/// from the SDE are skipped. The flag marks levels that are goals themselves
+/// rather than prerequisites.
+async fn goal_nodes(
+    pool: &db::Pool,
+    goal_sets: &[&GoalSetDef],
+    trained: &HashMap<i64, i64>,
+) -> Result<Vec<(PlanNode, bool)>> {
+    let mut dag = PlanDag::new();
+    let mut goals = Vec::new();
+    for set in goal_sets {
+        for (skill_name, level) in set.skills {
+            let Some(skill_type_id) =
+                db::skill_plans::get_skill_type_id_by_name(pool, skill_name).await?
+            else {
+                continue;
+            };
+            let node = PlanNode {
+                skill_type_id,
+                level: *level,
+            };
+            dag.add_recursive(pool, node).await?;
+            goals.push(node);
+        }
+    }
+
+    let goal_set: HashSet<PlanNode> = goals.iter().copied().collect();
+    Ok(dag
+        .topological_sort(&goals)
+        .into_iter()
+        .filter(|node| trained.get(&node.skill_type_id).copied().unwrap_or(0) < node.level)
+        .map(|node| (node, goal_set.contains(&node)))
+        .collect())
+}
+
+fn find_goal_set(id: &str) -> Result<&'static GoalSetDef> {
+    GOAL_SETS
+        .iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| anyhow::anyhow!("Unknown goal set: {}", id))
+}
+
+fn find_plan_template(id: &str) -> Result<&'static PlanTemplateDef> {
+    PLAN_TEMPLATES
+        .iter()
+        .find(|t| t.id == id)
+        .ok_or_else(|| anyhow::anyhow!("Unknown plan template: {}", id))
+}
+
+fn notification_defaults(profile: &CharacterProfile) -> Vec<NotificationDefaultSuggestion> {
+    let mut defaults = vec![
+        NotificationDefaultSuggestion {
+            notification_type: NOTIFICATION_TYPE_SKILL_QUEUE_LOW.to_string(),
+            config: Some(r#"{"threshold_hours":24}"#.to_string()),
+            reason: "Warns when less than a day of training is queued.".to_string(),
+        },
+        NotificationDefaultSuggestion {
+            notification_type: NOTIFICATION_TYPE_SKILL_QUEUE_PAUSED.to_string(),
+            config: None,
+            reason: "Warns when training is paused in game.".to_string(),
+        },
+        NotificationDefaultSuggestion {
+            notification_type: NOTIFICATION_TYPE_SKILL_QUEUE_EMPTY.to_string(),
+            config: None,
+            reason: "Warns when nothing is left in the queue.".to_string(),
+        },
+        NotificationDefaultSuggestion {
+            notification_type: NOTIFICATION_TYPE_REFRESH_STALE.to_string(),
+            config: Some(r#"{"threshold_days":7}"#.to_string()),
+            reason: "Warns when the character has not refreshed for a week, \
+                     usually an expired login."
+                .to_string(),
+        },
+    ];
+    if profile.is_omega {
+        defaults.push(NotificationDefaultSuggestion {
+            notification_type: NOTIFICATION_TYPE_OMEGA_LAPSED.to_string(),
+            config: None,
+            reason: "Warns when Omega runs out and training slows down.".to_string(),
+        });
+    }
+    defaults
+}
+
+fn is_goal_set_recommended(set: &GoalSetDef, profile: &CharacterProfile, remaining: usize) -> bool {
+    if remaining == 0 {
+        return false;
+    }
+    match set.id {
+        "cybernetics" => !profile.has_implants,
+        "fitting" | "navigation" => true,
+        _ => profile.total_sp < NEW_PLAYER_SP_THRESHOLD,
+    }
+}
+
+pub async fn suggestions(pool: &db::Pool, character_id: i64) -> Result<OnboardingSuggestions> {
+    let profile = load_profile(pool, character_id).await?;
+    let is_new_player = profile.total_sp < NEW_PLAYER_SP_THRESHOLD;
+
+    let mut goal_sets = Vec::with_capacity(GOAL_SETS.len());
+    for set in GOAL_SETS {
+        let remaining = goal_nodes(pool, &[set], &profile.trained).await?.len();
+        goal_sets.push(GoalSetSuggestion {
+            id: set.id.to_string(),
+            name: set.name.to_string(),
+            description: set.description.to_string(),
+            remaining_skill_levels: remaining,
+            recommended: is_goal_set_recommended(set, &profile, remaining),
+        });
+    }
+
+    let plan_templates = PLAN_TEMPLATES
+        .iter()
+        .enumerate()
+        .map(|(index, template)| PlanTemplateSuggestion {
+            id: template.id.to_string(),
+            name: template.name.to_string(),
+            description: template.description.to_string(),
+            goal_set_ids: template
+                .goal_set_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect(),
+            // The combat starter covers the most common first steps.
+            recommended: is_new_player && index == 0,
+        })
+        .collect();
+
+    Ok(OnboardingSuggestions {
+        character_id,
+        total_sp: profile.total_sp,
+        is_new_player,
+        has_implants: profile.has_implants,
+        goal_sets,
+        plan_templates,
+        notification_defaults: notification_defaults(&profile),
+    })
+}
+
+/// Create one plan per chosen template and per chosen goal set, and enable the
+/// chosen notifications with their suggested config. Nothing is written unless
+/// every choice is valid.
+pub async fn apply(
+    pool: &db::Pool,
+    character_id: i64,
+    choices: &OnboardingChoices,
+) -> Result<OnboardingResult> {
+    let profile = load_profile(pool, character_id).await?;
+
+    let mut plans: Vec<(String, String, Vec<(PlanNode, bool)>)> = Vec::new();
+    for id in &choices.plan_template_ids {
+        let template = find_plan_template(id)?;
+        let sets = template
+            .goal_set_ids
+            .iter()
+            .map(|id| find_goal_set(id))
+            .collect::<Result<Vec<_>>>()?;
+        let nodes = goal_nodes(pool, &sets, &profile.trained).await?;
+        plans.push((
+            template.name.to_string(),
+            template.description.to_string(),
+            nodes,
+        ));
+    }
+    for id in &choices.goal_set_ids {
+        let set = find_goal_set(id)?;
+        let nodes = goal_nodes(pool, &[set], &profile.trained).await?;
+        plans.push((set.name.to_string(), set.description.to_string(), nodes));
+    }
+
+    let defaults = notification_defaults(&profile);
+    let mut notifications = Vec::with_capacity(choices.notification_types.len());
+    for notification_type in &choices.notification_types {
+        let Some(default) = defaults
+            .iter()
+            .find(|d| &d.notification_type == notification_type)
+        else {
+            bail!(
+                "No default available for notification {}",
+                notification_type
+            );
+        };
+        notifications.push(default);
+    }
+
+    let mut tx = pool.begin().await?;
+    let mut created_plan_ids = Vec::with_capacity(plans.len());
+    for (name, description, nodes) in &plans {
+        let plan_id =
+            db::skill_plans::create_skill_plan_in(&mut tx, name, Some(description), true, None)
+                .await?;
+        for (index, (node, is_goal)) in nodes.iter().enumerate() {
+            let entry_type = if *is_goal {
+                db::skill_plans::ENTRY_TYPE_PLANNED
+            } else {
+                db::skill_plans::ENTRY_TYPE_PREREQUISITE
+            };
+            sqlx::query(
+                "INSERT INTO skill_plan_entries (plan_id, skill_type_id, planned_level, sort_order, entry_type, notes)
+                 VALUES (?, ?, ?, ?, ?, NULL)",
+            )
+            .bind(plan_id)
+            .bind(node.skill_type_id)
+            .bind(node.level)
+            .bind(index as i64)
+            .bind(entry_type)
+            .execute(&mut *tx)
+            .await?;
+        }
+        created_plan_ids.push(plan_id);
+    }
+    for default in &notifications {
+        db::upsert_notification_setting(
+            &mut *tx,
+            character_id,
+            &default.notification_type,
+            true,
+            default.config.as_deref(),
+        )
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(OnboardingResult {
+        created_plan_ids,
+        enabled_notification_types: notifications
+            .iter()
+            .map(|d| d.notification_type.clone())
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    #[test]
+    fn templates_only_reference_known_goal_sets() {
+        for template in PLAN_TEMPLATES {
+            for id in template.goal_set_ids {
+                assert!(find_goal_set(id).is_ok(), "{} -> {}", template.id, id);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn apply_rejects_unknown_choices_without_writing() {
+        let db = TestDb::new().await.unwrap();
+        db::add_character(&db.pool, 1, "Test").await.unwrap();
+
+        let choices = OnboardingChoices {
+            goal_set_ids: vec!["fitting".to_string(), "nonsense".to_string()],
+            plan_template_ids: vec![],
+            notification_types: vec![NOTIFICATION_TYPE_SKILL_QUEUE_LOW.to_string()],
+        };
+        assert!(apply(&db.pool, 1, &choices).await.is_err());
+
+        assert!(db::skill_plans::get_all_skill_plans(&db.pool)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(db::get_notification_settings(&db.pool, 1)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn apply_enables_notification_defaults() {
+        let db = TestDb::new().await.unwrap();
+        db::add_character(&db.pool, 1, "Test").await.unwrap();
+
+        let choices = OnboardingChoices {
+            goal_set_ids: vec![],
+            plan_template_ids: vec![],
+            notification_types: vec![NOTIFICATION_TYPE_SKILL_QUEUE_LOW.to_string()],
+        };
+        let result = apply(&db.pool, 1, &choices).await.unwrap();
+        assert!(result.created_plan_ids.is_empty());
+
+        let setting = db::get_notification_setting(&db.pool, 1, NOTIFICATION_TYPE_SKILL_QUEUE_LOW)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(setting.enabled);
+        assert_eq!(setting.config.as_deref(), Some(r#"{"threshold_hours":24}"#));
+    }
+}
@@ -0,0 +1,413 @@
+//! Skill queue timing and SP arithmetic shared by the refresher, the widget
+//! payload, the tray and the notification checkers: which entry is training,
+//! how far along it is, whether the queue is paused and how long it runs.
+//! Works on any queue entry shape through [`QueueItem`], so the generated
+//! ESI type and the cache-read [`SkillQueueEntry`] share one implementation.
+
+use chrono::{DateTime, Utc};
+
+use crate::esi;
+use crate::esi::schema::SkillQueueEntry;
+use crate::{db, utils};
+
+pub trait QueueItem {
+    fn start_date(&self) -> Option<DateTime<Utc>>;
+    fn finish_date(&self) -> Option<DateTime<Utc>>;
+    fn training_start_sp(&self) -> Option<i64>;
+    fn level_start_sp(&self) -> Option<i64>;
+    fn level_end_sp(&self) -> Option<i64>;
+}
+
+impl QueueItem for esi::CharactersSkillqueueSkill {
+    fn start_date(&self) -> Option<DateTime<Utc>> {
+        self.start_date
+    }
+    fn finish_date(&self) -> Option<DateTime<Utc>> {
+        self.finish_date
+    }
+    fn training_start_sp(&self) -> Option<i64> {
+        self.training_start_sp
+    }
+    fn level_start_sp(&self) -> Option<i64> {
+        self.level_start_sp
+    }
+    fn level_end_sp(&self) -> Option<i64> {
+        self.level_end_sp
+    }
+}
+
+fn parse_date(date: Option<&str>) -> Option<DateTime<Utc>> {
+    date.and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+        .map(|d| d.with_timezone(&Utc))
+}
+
+impl QueueItem for SkillQueueEntry {
+    fn start_date(&self) -> Option<DateTime<Utc>> {
+        parse_date(self.start_date.as_deref())
+    }
+    fn finish_date(&self) -> Option<DateTime<Utc>> {
+        parse_date(self.finish_date.as_deref())
+    }
+    fn training_start_sp(&self) -> Option<i64> {
+        self.training_start_sp
+    }
+    fn level_start_sp(&self) -> Option<i64> {
+        self.level_start_sp
+    }
+    fn level_end_sp(&self) -> Option<i64> {
+        self.level_end_sp
+    }
+}
+
+/// True while `now` falls inside the entry's training window.
+pub fn is_training<T: QueueItem>(item: &T, now: DateTime<Utc>) -> bool {
+    matches!(
+        (item.start_date(), item.finish_date()),
+        (Some(start), Some(finish)) if now >= start && now < finish
+    )
+}
+
+/// True until the entry's finish date has passed (always for a paused queue).
+pub fn is_pending<T: QueueItem>(item: &T, now: DateTime<Utc>) -> bool {
+    item.finish_date().is_none_or(|finish| now < finish)
+}
+
+pub fn currently_training<T: QueueItem>(queue: &[T], now: DateTime<Utc>) -> Option<&T> {
+    queue.iter().find(|item| is_training(*item, now))
+}
+
+/// A queue with entries but no finish dates has been paused in game.
+pub fn is_paused<T: QueueItem>(queue: &[T]) -> bool {
+    !queue.is_empty() && queue.iter().all(|item| item.finish_date().is_none())
+}
+
+/// Training time left in the whole queue; zero for an empty or finished
+/// queue, `None` when paused.
+pub fn remaining_training<T: QueueItem>(
+    queue: &[T],
+    now: DateTime<Utc>,
+) -> Option<chrono::Duration> {
+    if is_paused(queue) {
+        return None;
+    }
+    let last_finish = queue
+        .iter()
+        .filter_map(|item| item.finish_date())
+        .filter(|finish| *finish > now)
+        .max();
+    Some(last_finish.map_or(chrono::Duration::zero(), |finish| finish - now))
+}
+
+/// When the next queued level finishes; `None` for a paused, empty or
+/// finished queue.
+pub fn next_completion<T: QueueItem>(queue: &[T], now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    queue
+        .iter()
+        .filter_map(|item| item.finish_date())
+        .filter(|finish| *finish > now)
+        .min()
+}
+
+/// SP in the skill at `now` for an entry that is training, interpolated from
+/// the SP at training start to the level's end SP. `None` when not training.
+pub fn training_sp_at<T: QueueItem>(item: &T, now: DateTime<Utc>) -> Option<f64> {
+    if !is_training(item, now) {
+        return None;
+    }
+    let (start, finish) = (item.start_date()?, item.finish_date()?);
+    let level_start = item.level_start_sp().unwrap_or(0);
+    let from = item.training_start_sp().unwrap_or(level_start);
+    let to = item.level_end_sp().unwrap_or(from);
+
+    let duration = (finish - start).num_seconds() as f64;
+    let elapsed = (now - start).num_seconds() as f64;
+    if duration <= 0.0 {
+        return Some(from as f64);
+    }
+    Some(from as f64 + (to - from) as f64 * (elapsed / duration).clamp(0.0, 1.0))
+}
+
+/// 0.0–1.0 progress through the level being trained, counting SP trained
+/// before the current session. Zero when not training.
+pub fn level_progress<T: QueueItem>(item: &T, now: DateTime<Utc>) -> f64 {
+    let Some(sp) = training_sp_at(item, now) else {
+        return 0.0;
+    };
+    let level_start = item.level_start_sp().unwrap_or(0);
+    let span = (item.level_end_sp().unwrap_or(level_start) - level_start) as f64;
+    if span > 0.0 {
+        ((sp - level_start as f64) / span).clamp(0.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+/// SP to show for a queue entry. A training entry is interpolated; a waiting
+/// one uses `progress_tracker` (SP reached by an earlier entry for the same
+/// skill in this queue), then the character's known SP, clamped to the level.
+pub fn current_sp<T: QueueItem>(
+    item: &T,
+    known_sp: Option<i64>,
+    progress_tracker: Option<i64>,
+    now: DateTime<Utc>,
+) -> i64 {
+    if let Some(sp) = training_sp_at(item, now) {
+        let sp = sp as i64;
+        return item.level_end_sp().map_or(sp, |end| sp.min(end));
+    }
+
+    let mut sp = progress_tracker
+        .or(known_sp)
+        .or(item.training_start_sp())
+        .or(item.level_start_sp())
+        .unwrap_or(0);
+    if let Some(start) = item.level_start_sp() {
+        sp = sp.max(start);
+    }
+    if let Some(end) = item.level_end_sp() {
+        sp = sp.min(end);
+    }
+    sp
+}
+
+/// SP still needed to finish the entry's level from `current_sp`; `None`
+/// without the level's SP bounds.
+pub fn sp_remaining<T: QueueItem>(item: &T, current_sp: i64) -> Option<i64> {
+    let (start, end) = (item.level_start_sp()?, item.level_end_sp()?);
+    Some((end - current_sp.max(start)).max(0))
+}
+
+/// 0–100 completion of the entry's level at `current_sp`, the scale the queue
+/// view renders. A level with no SP span counts as complete.
+pub fn percent_complete<T: QueueItem>(item: &T, current_sp: i64) -> Option<f64> {
+    let (start, end) = (item.level_start_sp()?, item.level_end_sp()?);
+    let span = end - start;
+    if span <= 0 {
+        return Some(100.0);
+    }
+    Some(((current_sp - start) as f64 / span as f64 * 100.0).clamp(0.0, 100.0))
+}
+
+/// Seconds of training left for the entry. Scheduled entries use their ESI
+/// window, which already accounts for implants and boosters; a paused entry
+/// falls back to the remaining SP at `sp_per_minute`.
+pub fn seconds_remaining<T: QueueItem>(
+    item: &T,
+    current_sp: i64,
+    sp_per_minute: Option<f64>,
+    now: DateTime<Utc>,
+) -> Option<i64> {
+    if let (Some(start), Some(finish)) = (item.start_date(), item.finish_date()) {
+        return Some((finish - start.max(now)).num_seconds().max(0));
+    }
+    let rate = sp_per_minute.filter(|rate| *rate > 0.0)?;
+    let remaining = sp_remaining(item, current_sp)?;
+    Some((remaining as f64 / rate * 60.0).round() as i64)
+}
+
+/// SP/minute the entry is actually training at, from its ESI window.
+pub fn observed_sp_per_minute<T: QueueItem>(item: &T) -> Option<f64> {
+    let (start, finish) = (item.start_date()?, item.finish_date()?);
+    let (start_sp, end_sp) = (item.training_start_sp()?, item.level_end_sp()?);
+    let minutes = (finish - start).num_minutes() as f64;
+    (minutes > 0.0).then(|| (end_sp - start_sp) as f64 / minutes)
+}
+
+/// Attribute value for a dogma attribute id (164 charisma … 168 willpower).
+pub fn attribute_value(attrs: &db::CharacterAttributes, attr_id: i64) -> i64 {
+    match attr_id {
+        164 => attrs.charisma,
+        165 => attrs.intelligence,
+        166 => attrs.memory,
+        167 => attrs.perception,
+        168 => attrs.willpower,
+        _ => 0,
+    }
+}
+
+/// Expected training rate for a skill; `None` without the character's
+/// attributes or the skill's attribute pair.
+pub fn sp_per_minute(
+    attrs: Option<&db::CharacterAttributes>,
+    skill: Option<&utils::SkillAttributes>,
+    is_omega: bool,
+) -> Option<f64> {
+    let (attrs, skill) = (attrs?, skill?);
+    let (primary, secondary) = (skill.primary_attribute?, skill.secondary_attribute?);
+    Some(utils::calculate_sp_per_minute(
+        attribute_value(attrs, primary),
+        attribute_value(attrs, secondary),
+        is_omega,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn item(
+        start: Option<DateTime<Utc>>,
+        finish: Option<DateTime<Utc>>,
+        training_start_sp: i64,
+    ) -> esi::CharactersSkillqueueSkill {
+        esi::CharactersSkillqueueSkill {
+            skill_id: 3300,
+            finished_level: 3,
+            queue_position: 0,
+            start_date: start,
+            finish_date: finish,
+            training_start_sp: Some(training_start_sp),
+            level_start_sp: Some(0),
+            level_end_sp: Some(1000),
+        }
+    }
+
+    fn attrs() -> db::CharacterAttributes {
+        db::CharacterAttributes {
+            character_id: 1,
+            charisma: 17,
+            intelligence: 27,
+            memory: 21,
+            perception: 20,
+            willpower: 20,
+            bonus_remaps: None,
+            accrued_remap_cooldown_date: None,
+            last_remap_date: None,
+        }
+    }
+
+    #[test]
+    fn training_in_progress_interpolates_from_training_start() {
+        let now = Utc::now();
+        let training = item(
+            Some(now - Duration::minutes(30)),
+            Some(now + Duration::minutes(30)),
+            500,
+        );
+        assert!(is_training(&training, now));
+        assert_eq!(current_sp(&training, Some(500), None, now), 750);
+        assert!((level_progress(&training, now) - 0.75).abs() < 0.01);
+        assert_eq!(observed_sp_per_minute(&training), Some(500.0 / 60.0));
+    }
+
+    #[test]
+    fn paused_queue_has_no_training_or_remaining_time() {
+        let now = Utc::now();
+        let queue = vec![item(None, None, 200), item(None, None, 0)];
+        assert!(is_paused(&queue));
+        assert!(currently_training(&queue, now).is_none());
+        assert_eq!(remaining_training(&queue, now), None);
+        assert_eq!(current_sp(&queue[0], Some(200), None, now), 200);
+        assert_eq!(level_progress(&queue[0], now), 0.0);
+
+        let empty: Vec<esi::CharactersSkillqueueSkill> = Vec::new();
+        assert!(!is_paused(&empty));
+        assert_eq!(remaining_training(&empty, now), Some(Duration::zero()));
+    }
+
+    #[test]
+    fn multi_level_same_skill_uses_progress_tracker() {
+        let now = Utc::now();
+        let level_four = esi::CharactersSkillqueueSkill {
+            finished_level: 4,
+            start_date: Some(now + Duration::minutes(30)),
+            finish_date: Some(now + Duration::hours(5)),
+            training_start_sp: Some(1000),
+            level_start_sp: Some(1000),
+            level_end_sp: Some(5000),
+            ..item(None, None, 0)
+        };
+        // Level 3 finishes at 1000 SP; level 4 starts from there even though
+        // the character's known SP is still below it.
+        assert_eq!(current_sp(&level_four, Some(400), Some(1000), now), 1000);
+        assert_eq!(current_sp(&level_four, Some(400), None, now), 1000);
+
+        let queue = vec![
+            item(
+                Some(now - Duration::minutes(30)),
+                Some(now + Duration::minutes(30)),
+                0,
+            ),
+            level_four,
+        ];
+        assert_eq!(
+            remaining_training(&queue, now).map(|d| d.num_minutes()),
+            Some(300)
+        );
+        assert_eq!(
+            next_completion(&queue, now),
+            Some(now + Duration::minutes(30))
+        );
+        assert_eq!(next_completion(&queue, now + Duration::hours(6)), None);
+    }
+
+    #[test]
+    fn remaining_time_and_completion() {
+        let now = Utc::now();
+        let training = item(
+            Some(now - Duration::minutes(30)),
+            Some(now + Duration::minutes(30)),
+            500,
+        );
+        let sp = current_sp(&training, Some(500), None, now);
+        assert_eq!(sp_remaining(&training, sp), Some(250));
+        assert_eq!(percent_complete(&training, sp), Some(75.0));
+        assert_eq!(seconds_remaining(&training, sp, Some(1.0), now), Some(1800));
+
+        let queued = item(
+            Some(now + Duration::minutes(30)),
+            Some(now + Duration::minutes(90)),
+            0,
+        );
+        assert_eq!(seconds_remaining(&queued, 0, None, now), Some(3600));
+
+        let paused = item(None, None, 400);
+        assert_eq!(seconds_remaining(&paused, 400, Some(10.0), now), Some(3600));
+        assert_eq!(seconds_remaining(&paused, 400, None, now), None);
+        assert_eq!(percent_complete(&paused, 400), Some(40.0));
+        assert_eq!(sp_remaining(&paused, 2000), Some(0));
+    }
+
+    #[test]
+    fn missing_attributes_give_no_rate() {
+        let skill = utils::SkillAttributes {
+            primary_attribute: Some(165),
+            secondary_attribute: Some(166),
+            rank: Some(1),
+        };
+        assert_eq!(sp_per_minute(None, Some(&skill), true), None);
+        assert_eq!(sp_per_minute(Some(&attrs()), None, true), None);
+        let no_pair = utils::SkillAttributes {
+            primary_attribute: None,
+            ..skill.clone()
+        };
+        assert_eq!(sp_per_minute(Some(&attrs()), Some(&no_pair), true), None);
+        assert_eq!(
+            sp_per_minute(Some(&attrs()), Some(&skill), true),
+            Some(37.5)
+        );
+        assert_eq!(
+            sp_per_minute(Some(&attrs()), Some(&skill), false),
+            Some(18.75)
+        );
+    }
+
+    #[test]
+    fn cache_entries_parse_their_dates() {
+        let entry = SkillQueueEntry {
+            skill_id: 3300,
+            finished_level: 1,
+            queue_position: 0,
+            start_date: None,
+            finish_date: Some("2026-01-02T00:00:00Z".into()),
+            training_start_sp: None,
+            level_start_sp: None,
+            level_end_sp: None,
+        };
+        assert_eq!(
+            entry.finish_date().map(|d| d.to_rfc3339()),
+            Some("2026-01-02T00:00:00+00:00".to_string())
+        );
+    }
+}
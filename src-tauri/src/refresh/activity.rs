@@ -0,0 +1,72 @@
+//! Main-window visibility as seen by the refreshers. While the window has sat
+//! hidden in the tray past the configured threshold, refreshers keep polling
+//! notification-critical data (the skill queue) but fetch everything else only
+//! once per dampened interval. Showing the window restores full cadence.
+
+use std::sync::{Arc, Mutex};
+
+use crate::db::app_settings::RefreshDampening;
+
+#[derive(Debug, Default)]
+pub struct WindowActivity {
+    /// Unix seconds when the window was hidden; `None` while it is visible.
+    hidden_since: Mutex<Option<i64>>,
+}
+
+pub type WindowActivityStore = Arc<WindowActivity>;
+
+impl WindowActivity {
+    /// Record the window going to the tray. Keeps the earliest timestamp if
+    /// it was already hidden.
+    pub fn mark_hidden(&self, now: i64) {
+        let mut hidden_since = self.hidden_since.lock().unwrap();
+        if hidden_since.is_none() {
+            *hidden_since = Some(now);
+        }
+    }
+
+    /// Record the window being shown. Returns true if it had been hidden.
+    pub fn mark_visible(&self) -> bool {
+        self.hidden_since.lock().unwrap().take().is_some()
+    }
+
+    pub fn is_dampened(&self, settings: &RefreshDampening, now: i64) -> bool {
+        if settings.hidden_after_minutes <= 0 {
+            return false;
+        }
+        match *self.hidden_since.lock().unwrap() {
+            Some(since) => now - since >= settings.hidden_after_minutes * 60,
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dampens_only_after_threshold_and_until_shown() {
+        let settings = RefreshDampening {
+            hidden_after_minutes: 30,
+            dampened_interval_minutes: 60,
+        };
+        let activity = WindowActivity::default();
+        assert!(!activity.is_dampened(&settings, 10_000));
+
+        activity.mark_hidden(1_000);
+        activity.mark_hidden(2_000);
+        assert!(!activity.is_dampened(&settings, 1_000 + 29 * 60));
+        assert!(activity.is_dampened(&settings, 1_000 + 30 * 60));
+
+        let disabled = RefreshDampening {
+            hidden_after_minutes: 0,
+            ..settings.clone()
+        };
+        assert!(!activity.is_dampened(&disabled, 1_000_000));
+
+        assert!(activity.mark_visible());
+        assert!(!activity.mark_visible());
+        assert!(!activity.is_dampened(&settings, 1_000_000));
+    }
+}
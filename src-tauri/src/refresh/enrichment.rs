@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use sqlx::{QueryBuilder, Row, Sqlite};
 
-use crate::{cache, db, esi, utils};
+use crate::{cache, db, esi, queue_math, utils};
 
 use super::events;
 
@@ -48,17 +48,6 @@ async fn get_skill_group_info(pool: &db::Pool, skill_ids: &[i64]) -> HashMap<i64
     result
 }
 
-fn attr_value_from_id(attrs: &db::CharacterAttributes, attr_id: i64) -> i64 {
-    match attr_id {
-        164 => attrs.charisma,
-        165 => attrs.intelligence,
-        166 => attrs.memory,
-        167 => attrs.perception,
-        168 => attrs.willpower,
-        _ => 0,
-    }
-}
-
 /// Infer Omega vs Alpha from ESI data, since ESI exposes no subscription field.
 /// Defaults to Omega; only returns false when a positive Alpha signal fires.
 /// See docs/context/eve.md (Alpha inference). Mirrors the two documented signals.
@@ -79,31 +68,20 @@ fn infer_is_omega(
 
     // Signal 2 — Active training rate < 55% of the expected Omega rate. Only
     // fires while a skill is actively training. Less reliable than signal 1.
-    let now = chrono::Utc::now();
-    let currently_training = raw_queue.iter().find(|item| {
-        matches!((item.start_date, item.finish_date), (Some(start), Some(finish)) if now >= start && now < finish)
-    });
+    let currently_training = queue_math::currently_training(raw_queue, chrono::Utc::now());
 
     if let (Some(item), Some(attrs)) = (currently_training, db_attrs) {
-        if let (Some(start), Some(finish), Some(start_sp), Some(end_sp)) = (
-            item.start_date,
-            item.finish_date,
-            item.training_start_sp,
-            item.level_end_sp,
+        if let (Some(actual_rate), Some(skill_attr)) = (
+            queue_math::observed_sp_per_minute(item),
+            skill_attrs.get(&item.skill_id),
         ) {
-            let duration_mins = (finish - start).num_minutes() as f64;
-            if duration_mins > 0.0 {
-                if let Some(skill_attr) = skill_attrs.get(&item.skill_id) {
-                    if let (Some(p_id), Some(s_id)) =
-                        (skill_attr.primary_attribute, skill_attr.secondary_attribute)
-                    {
-                        let actual_rate = (end_sp - start_sp) as f64 / duration_mins;
-                        let expected_omega_rate = attr_value_from_id(attrs, p_id) as f64
-                            + attr_value_from_id(attrs, s_id) as f64 / 2.0;
-                        if expected_omega_rate > 0.0 && actual_rate < expected_omega_rate * 0.55 {
-                            return false;
-                        }
-                    }
+            if let (Some(p_id), Some(s_id)) =
+                (skill_attr.primary_attribute, skill_attr.secondary_attribute)
+            {
+                let expected_omega_rate = queue_math::attribute_value(attrs, p_id) as f64
+                    + queue_math::attribute_value(attrs, s_id) as f64 / 2.0;
+                if expected_omega_rate > 0.0 && actual_rate < expected_omega_rate * 0.55 {
+                    return false;
                 }
             }
         }
@@ -182,69 +160,6 @@ fn compute_breakdown_from_db_attrs(
     })
 }
 
-fn compute_current_sp_for_item(
-    item: &esi::CharactersSkillqueueSkill,
-    known_sp: Option<i64>,
-    progress_tracker: Option<i64>,
-) -> i64 {
-    let is_training = if let (Some(start), Some(finish)) = (item.start_date, item.finish_date) {
-        let now = chrono::Utc::now();
-        now >= start && now < finish
-    } else {
-        false
-    };
-
-    let base_sp = known_sp
-        .or(item.training_start_sp)
-        .or(item.level_start_sp)
-        .unwrap_or(0);
-
-    let mut progress_sp = if is_training {
-        if let (Some(start), Some(finish)) = (item.start_date, item.finish_date) {
-            let now = chrono::Utc::now();
-            let total_duration = (finish - start).num_seconds() as f64;
-            let elapsed = (now - start).num_seconds() as f64;
-
-            if total_duration > 0.0 && elapsed > 0.0 {
-                let total_sp_needed =
-                    item.level_end_sp.unwrap_or(0) - item.level_start_sp.unwrap_or(0);
-                let sp_gained = (total_sp_needed as f64 * (elapsed / total_duration)) as i64;
-                let calculated = base_sp + sp_gained;
-                if let Some(end) = item.level_end_sp {
-                    calculated.min(end)
-                } else {
-                    calculated
-                }
-            } else {
-                base_sp
-            }
-        } else {
-            base_sp
-        }
-    } else {
-        progress_tracker
-            .or(known_sp)
-            .or(item.training_start_sp)
-            .or(item.level_start_sp)
-            .unwrap_or(0)
-    };
-
-    if is_training {
-        if let Some(end) = item.level_end_sp {
-            progress_sp = progress_sp.min(end);
-        }
-    } else {
-        if let Some(start) = item.level_start_sp {
-            progress_sp = progress_sp.max(start);
-        }
-        if let Some(end) = item.level_end_sp {
-            progress_sp = progress_sp.min(end);
-        }
-    }
-
-    progress_sp
-}
-
 pub async fn enrich_queue(
     pool: &db::Pool,
     character_id: i64,
@@ -303,6 +218,8 @@ pub async fn enrich_queue(
 
     // Recompute Omega status every refresh. This is the only live path that
     // writes is_omega; without it a stale persisted value never self-corrects.
+    // Write it even when unchanged: a new character starts as Omega with no
+    // confirmed status, and a later lapse is only stamped after one.
     // Skip when the skills read failed — Signal 1 needs the real skill list, and
     // inferring from an error-empty Vec would persist a misclassification.
     let is_omega = if skills_loaded {
@@ -312,29 +229,33 @@ pub async fn enrich_queue(
             &raw_queue,
             &skill_attrs,
         );
-        if inferred != character.is_omega {
-            if let Err(e) = db::update_character_omega_status(pool, character_id, inferred).await {
-                eprintln!("refresh: update omega status {}: {}", character_id, e);
+        if let Err(e) = db::update_character_omega_status(pool, character_id, inferred).await {
+            eprintln!("refresh: update omega status {}: {}", character_id, e);
+        }
+        // Training rates follow the user's override, like the stored status.
+        match db::get_character_omega_override(pool, character_id).await {
+            Ok(pinned) => pinned.unwrap_or(inferred),
+            Err(e) => {
+                eprintln!("refresh: omega override {}: {}", character_id, e);
+                inferred
             }
         }
-        inferred
     } else {
         character.is_omega
     };
 
-    let is_paused =
-        !raw_queue.is_empty() && raw_queue.iter().all(|item| item.finish_date.is_none());
+    let is_paused = queue_math::is_paused(&raw_queue);
 
     let now = chrono::Utc::now();
     let mut progress_map: HashMap<i64, i64> = HashMap::new();
 
     let queue: Vec<events::SkillQueueItem> = raw_queue
         .iter()
-        .filter(|item| item.finish_date.map(|fd| now < fd).unwrap_or(true))
+        .filter(|item| queue_math::is_pending(*item, now))
         .map(|item| {
             let known_sp = skill_sp_map.get(&item.skill_id).copied();
             let tracker = progress_map.get(&item.skill_id).copied();
-            let current_sp = compute_current_sp_for_item(item, known_sp, tracker);
+            let current_sp = queue_math::current_sp(item, known_sp, tracker, now);
 
             if let Some(end) = item.level_end_sp {
                 progress_map.insert(item.skill_id, current_sp.max(end));
@@ -342,23 +263,11 @@ pub async fn enrich_queue(
                 progress_map.insert(item.skill_id, current_sp);
             }
 
-            let (sp_per_minute, primary_attribute, secondary_attribute, rank) =
-                if let Some(sa) = skill_attrs.get(&item.skill_id) {
-                    let spm = if let (Some(p_id), Some(s_id), Some(attrs)) = (
-                        sa.primary_attribute,
-                        sa.secondary_attribute,
-                        db_attrs.as_ref(),
-                    ) {
-                        let pv = attr_value_from_id(attrs, p_id);
-                        let sv = attr_value_from_id(attrs, s_id);
-                        Some(utils::calculate_sp_per_minute(pv, sv, is_omega))
-                    } else {
-                        None
-                    };
-                    (spm, sa.primary_attribute, sa.secondary_attribute, sa.rank)
-                } else {
-                    (None, None, None, None)
-                };
+            let skill_attr = skill_attrs.get(&item.skill_id);
+            let sp_per_minute = queue_math::sp_per_minute(db_attrs.as_ref(), skill_attr, is_omega);
+            let (primary_attribute, secondary_attribute, rank) = skill_attr
+                .map(|sa| (sa.primary_attribute, sa.secondary_attribute, sa.rank))
+                .unwrap_or((None, None, None));
 
             events::SkillQueueItem {
                 skill_id: item.skill_id as i32,
@@ -375,6 +284,14 @@ pub async fn enrich_queue(
                 rank,
                 sp_per_minute,
                 current_sp: Some(current_sp),
+                percent_complete: queue_math::percent_complete(item, current_sp),
+                sp_remaining: queue_math::sp_remaining(item, current_sp),
+                seconds_remaining: queue_math::seconds_remaining(
+                    item,
+                    current_sp,
+                    sp_per_minute,
+                    now,
+                ),
             }
         })
         .collect();
@@ -500,7 +417,7 @@ pub async fn enrich_skills_from_db(
                 let now = chrono::Utc::now();
                 raw_queue
                     .into_iter()
-                    .filter(|item| item.finish_date.map(|fd| now < fd).unwrap_or(true))
+                    .filter(|item| queue_math::is_pending(item, now))
                     .map(|item| item.skill_id)
                     .collect()
             } else {
@@ -571,16 +488,9 @@ pub async fn enrich_attributes(
             .map(|d| d.to_rfc3339()),
     };
 
-    let implant_ids = get_active_clone_implant_ids(pool, character_id).await;
-    let implant_bonuses = if implant_ids.is_empty() {
-        HashMap::new()
-    } else {
-        db::get_implant_attribute_bonuses(pool, &implant_ids)
-            .await
-            .unwrap_or_default()
-    };
-
-    let breakdown = compute_breakdown_from_db_attrs(&db_attrs, &implant_ids, &implant_bonuses);
+    let breakdown = active_clone_breakdown(pool, character_id, &db_attrs).await;
+    let (last_remap_date, accrued_remap_cooldown_date) =
+        remap_dates_with_history(pool, character_id, &db_attrs).await;
 
     events::AttributesPayload {
         character_id: character_id as i32,
@@ -591,11 +501,8 @@ pub async fn enrich_attributes(
         perception: breakdown[3].clone(),
         willpower: breakdown[4].clone(),
         bonus_remaps: raw_attrs.bonus_remaps.map(|v| v as i32),
-        last_remap_date: raw_attrs.last_remap_date.as_ref().map(|d| d.to_rfc3339()),
-        accrued_remap_cooldown_date: raw_attrs
-            .accrued_remap_cooldown_date
-            .as_ref()
-            .map(|d| d.to_rfc3339()),
+        last_remap_date,
+        accrued_remap_cooldown_date,
     }
 }
 
@@ -610,16 +517,9 @@ pub async fn enrich_attributes_from_db(
         .ok()
         .flatten()?;
 
-    let implant_ids = get_active_clone_implant_ids(pool, character_id).await;
-    let implant_bonuses = if implant_ids.is_empty() {
-        HashMap::new()
-    } else {
-        db::get_implant_attribute_bonuses(pool, &implant_ids)
-            .await
-            .unwrap_or_default()
-    };
-
-    let breakdown = compute_breakdown_from_db_attrs(&db_attrs, &implant_ids, &implant_bonuses);
+    let breakdown = active_clone_breakdown(pool, character_id, &db_attrs).await;
+    let (last_remap_date, accrued_remap_cooldown_date) =
+        remap_dates_with_history(pool, character_id, &db_attrs).await;
 
     Some(events::AttributesPayload {
         character_id: character_id as i32,
@@ -630,11 +530,100 @@ pub async fn enrich_attributes_from_db(
         perception: breakdown[3].clone(),
         willpower: breakdown[4].clone(),
         bonus_remaps: db_attrs.bonus_remaps.map(|v| v as i32),
-        last_remap_date: db_attrs.last_remap_date.clone(),
-        accrued_remap_cooldown_date: db_attrs.accrued_remap_cooldown_date.clone(),
+        last_remap_date,
+        accrued_remap_cooldown_date,
     })
 }
 
+async fn active_clone_breakdown(
+    pool: &db::Pool,
+    character_id: i64,
+    db_attrs: &db::CharacterAttributes,
+) -> [events::AttributeBreakdown; 5] {
+    let implant_ids = get_active_clone_implant_ids(pool, character_id).await;
+    let implant_bonuses = if implant_ids.is_empty() {
+        HashMap::new()
+    } else {
+        db::get_implant_attribute_bonuses(pool, &implant_ids)
+            .await
+            .unwrap_or_default()
+    };
+
+    compute_breakdown_from_db_attrs(db_attrs, &implant_ids, &implant_bonuses)
+}
+
+/// Snapshots the stored attributes, with the implant and accelerator
+/// contributions detected for the active clone, when anything changed.
+pub async fn record_attribute_history(pool: &db::Pool, character_id: i64) {
+    let Some(db_attrs) = db::get_character_attributes(pool, character_id)
+        .await
+        .ok()
+        .flatten()
+    else {
+        return;
+    };
+    let breakdown = active_clone_breakdown(pool, character_id, &db_attrs).await;
+
+    let observed = db::attribute_history::ObservedAttributes {
+        totals: std::array::from_fn(|i| breakdown[i].total),
+        implants: std::array::from_fn(|i| breakdown[i].implants),
+        accelerator: breakdown[0].accelerator,
+        bonus_remaps: db_attrs.bonus_remaps,
+        last_remap_date: db_attrs.last_remap_date.clone(),
+    };
+    if let Err(e) = db::attribute_history::record_attribute_snapshot(
+        pool,
+        character_id,
+        &observed,
+        chrono::Utc::now().timestamp(),
+    )
+    .await
+    {
+        eprintln!("refresh: attribute history {}: {}", character_id, e);
+    }
+}
+
+const REMAP_COOLDOWN_DAYS: i64 = 365;
+
+/// Remap dates for the payload. ESI's are used when it reports a last remap;
+/// otherwise the last primary remap seen in the attribute history stands in,
+/// with the cooldown running a year from it.
+async fn remap_dates_with_history(
+    pool: &db::Pool,
+    character_id: i64,
+    db_attrs: &db::CharacterAttributes,
+) -> (Option<String>, Option<String>) {
+    let observed = if db_attrs.last_remap_date.is_none() {
+        db::attribute_history::get_last_observed_remap(pool, character_id)
+            .await
+            .ok()
+            .flatten()
+            .and_then(|snapshot| snapshot.remapped_at())
+    } else {
+        None
+    };
+    fill_remap_dates(db_attrs, observed, chrono::Utc::now())
+}
+
+fn fill_remap_dates(
+    db_attrs: &db::CharacterAttributes,
+    observed_remap: Option<chrono::DateTime<chrono::Utc>>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> (Option<String>, Option<String>) {
+    let (Some(remapped_at), None) = (observed_remap, &db_attrs.last_remap_date) else {
+        return (
+            db_attrs.last_remap_date.clone(),
+            db_attrs.accrued_remap_cooldown_date.clone(),
+        );
+    };
+    let cooldown_ends = remapped_at + chrono::Duration::days(REMAP_COOLDOWN_DAYS);
+    let cooldown = db_attrs
+        .accrued_remap_cooldown_date
+        .clone()
+        .or_else(|| (cooldown_ends > now).then(|| cooldown_ends.to_rfc3339()));
+    (Some(remapped_at.to_rfc3339()), cooldown)
+}
+
 async fn get_active_clone_implant_ids(pool: &db::Pool, character_id: i64) -> Vec<i64> {
     let clones = db::get_character_clones(pool, character_id)
         .await
@@ -1170,6 +1159,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn observed_remap_fills_missing_remap_dates() {
+        let now = chrono::Utc::now();
+        let remapped_at = now - chrono::Duration::days(30);
+
+        let (last, cooldown) = fill_remap_dates(&attrs(27, 21, 20, 20), Some(remapped_at), now);
+        assert_eq!(last, Some(remapped_at.to_rfc3339()));
+        assert_eq!(
+            cooldown,
+            Some((remapped_at + chrono::Duration::days(365)).to_rfc3339())
+        );
+
+        let long_ago = now - chrono::Duration::days(400);
+        let (_, cooldown) = fill_remap_dates(&attrs(27, 21, 20, 20), Some(long_ago), now);
+        assert_eq!(cooldown, None);
+
+        let reported = db::CharacterAttributes {
+            last_remap_date: Some("2026-01-01T00:00:00+00:00".into()),
+            ..attrs(27, 21, 20, 20)
+        };
+        let (last, cooldown) = fill_remap_dates(&reported, Some(remapped_at), now);
+        assert_eq!(last.as_deref(), Some("2026-01-01T00:00:00+00:00"));
+        assert_eq!(cooldown, None);
+    }
+
     fn skill(skill_id: i64, active: i64, trained: i64) -> db::CharacterSkill {
         db::CharacterSkill {
             character_id: 1,
@@ -1278,4 +1292,46 @@ mod tests {
         let skills = vec![skill(88377, 5, 5)];
         assert!(infer_is_omega(Some(&a), &skills, &[], &doomsday_attr_map()));
     }
+
+    #[tokio::test]
+    async fn refresh_confirms_new_omega_so_a_later_lapse_is_stamped() {
+        let db = crate::testdata::TestDb::new().await.unwrap();
+        db::add_character(&db.pool, 1, "Pilot").await.unwrap();
+
+        enrich_queue(&db.pool, 1, Vec::new()).await;
+        assert!(db::get_character_omega_lapsed_at(&db.pool, 1)
+            .await
+            .unwrap()
+            .is_none());
+
+        // A skill trained above the Alpha cap: the account lapsed.
+        db::set_character_skills(&db.pool, 1, &[(3300, 4, 256_000, 5)])
+            .await
+            .unwrap();
+        let payload = enrich_queue(&db.pool, 1, Vec::new()).await;
+        assert!(!payload.is_omega);
+        assert!(db::get_character_omega_lapsed_at(&db.pool, 1)
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn refresh_uses_the_omega_override_over_the_inferred_status() {
+        let db = crate::testdata::TestDb::new().await.unwrap();
+        db::add_character(&db.pool, 1, "Pilot").await.unwrap();
+        db::characters::set_character_omega_override(&db.pool, 1, Some(false))
+            .await
+            .unwrap();
+
+        // Nothing here says Alpha, so inference alone would report Omega.
+        let payload = enrich_queue(&db.pool, 1, Vec::new()).await;
+        assert!(!payload.is_omega);
+
+        db::characters::set_character_omega_override(&db.pool, 1, None)
+            .await
+            .unwrap();
+        let payload = enrich_queue(&db.pool, 1, Vec::new()).await;
+        assert!(payload.is_omega);
+    }
 }
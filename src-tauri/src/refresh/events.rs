@@ -23,6 +23,10 @@ pub struct SkillQueueItem {
     pub rank: Option<i64_ts>,
     pub sp_per_minute: Option<f64>,
     pub current_sp: Option<i64_ts>,
+    /// 0–100 through the level, from `current_sp`.
+    pub percent_complete: Option<f64>,
+    pub sp_remaining: Option<i64_ts>,
+    pub seconds_remaining: Option<i64_ts>,
 }
 
 #[typeshare]
@@ -1,31 +1,115 @@
 use std::collections::HashMap;
-use std::sync::Arc;
-use tauri::Emitter;
+use std::sync::{Arc, Mutex};
+use tauri::Manager;
 use tokio::sync::Notify;
 use tokio::time::Duration;
 use tokio_util::sync::CancellationToken;
 
 use rand::RngExt;
 
-use crate::{auth, cache, db, esi, esi_helpers, notifications};
+use crate::features::CharacterModule;
+use crate::scheduler::{ScheduledTask, Scheduler};
+use crate::{auth, cache, db, esi, esi_helpers, notifications, queue_math};
 
+pub mod activity;
 pub mod enrichment;
 pub mod events;
 
+pub use activity::WindowActivityStore;
+
+/// How long after a level's finish date to refresh, so ESI has moved on.
+const COMPLETION_BUFFER_SECS: i64 = 5;
+
 pub struct RefresherHandle {
     pub cancel: CancellationToken,
     pub poke: Arc<Notify>,
     pub join_handle: tokio::task::JoinHandle<()>,
 }
 
+/// What a character's refresher last saw, for the diagnostics screen.
+#[derive(Debug, Clone, Default)]
+pub struct RefreshHealth {
+    /// Unix seconds of the last pass where any fetch succeeded.
+    pub last_success_at: Option<i64>,
+    /// How long that pass took, token refresh to notification checks.
+    pub last_pass_duration: Option<Duration>,
+    /// Why the last pass could not get an access token; cleared once it can.
+    pub token_error: Option<String>,
+}
+
 pub struct RefreshSupervisor {
     handles: HashMap<i64, RefresherHandle>,
+    health: Arc<Mutex<HashMap<i64, RefreshHealth>>>,
+}
+
+async fn cached_hash(pool: &db::Pool, endpoint: &str, character_id: i64) -> Option<String> {
+    let key = cache::build_cache_key(endpoint, character_id);
+    cache::get_content_hash(pool, &key).await.ok().flatten()
+}
+
+/// Content hashes of the cached responses a refresher last passed on, so a
+/// 304 or an identical 200 is neither emitted nor handed to checkers again.
+#[derive(Default)]
+struct SeenResponses(HashMap<String, String>);
+
+impl SeenResponses {
+    /// Whether any of `endpoints` now caches a different body than last time.
+    /// Everything counts as changed on the first pass, as do entries cached
+    /// before hashes were stored.
+    async fn changed(&mut self, pool: &db::Pool, endpoints: &[String], character_id: i64) -> bool {
+        let mut changed = false;
+        for endpoint in endpoints {
+            let key = cache::build_cache_key(endpoint, character_id);
+            match cached_hash(pool, endpoint, character_id).await {
+                Some(hash) => {
+                    if self.0.get(&key) != Some(&hash) {
+                        self.0.insert(key, hash);
+                        changed = true;
+                    }
+                }
+                None => {
+                    self.0.remove(&key);
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Treat `endpoint` as unseen, e.g. after its data failed to sync.
+    fn forget(&mut self, endpoint: &str, character_id: i64) {
+        self.0
+            .remove(&cache::build_cache_key(endpoint, character_id));
+    }
+}
+
+/// Logs a skills response that differs from the cached one in the activity
+/// feed; unchanged or still-cached responses are not worth an entry.
+async fn record_skills_fetched(pool: &db::Pool, character_id: i64, skill_count: usize) {
+    let name = match db::get_character(pool, character_id).await {
+        Ok(Some(character)) => character.character_name,
+        _ => character_id.to_string(),
+    };
+    if let Err(e) = db::activity_feed::record_activity(
+        pool,
+        Some(character_id),
+        db::activity_feed::ACTIVITY_SKILLS_FETCHED,
+        &format!("Fetched {} skills for {}", skill_count, name),
+    )
+    .await
+    {
+        eprintln!(
+            "refresh: failed to record activity for {}: {}",
+            character_id, e
+        );
+    }
 }
 
 impl RefreshSupervisor {
     pub fn new() -> Self {
         Self {
             handles: HashMap::new(),
+            health: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -35,11 +119,13 @@ impl RefreshSupervisor {
         pool: db::Pool,
         app_handle: tauri::AppHandle,
         rate_limits: esi::RateLimitStore,
+        activity: WindowActivityStore,
     ) {
         let cancel = CancellationToken::new();
         let poke = Arc::new(Notify::new());
         let cancel_clone = cancel.clone();
         let poke_clone = poke.clone();
+        let health = self.health.clone();
 
         let handle = tokio::spawn(async move {
             let notification_processor = notifications::NotificationProcessor::new();
@@ -47,16 +133,46 @@ impl RefreshSupervisor {
             // Per-character last-known location IDs for ESI name resolution gating
             let mut last_location_ids = enrichment::LocationIds::none();
 
+            // What was last passed on, so unchanged responses are not.
+            let mut seen = SeenResponses::default();
+
+            // When non-critical data was last fetched; gates fetches while dampened.
+            let mut last_full_refresh: Option<tokio::time::Instant> = None;
+
+            // Last scope gap announced, so the event fires once per change.
+            let mut reported_reauth: Option<auth::reauth::ReauthRequired> = None;
+
+            // Set when this pass was woken by a level finishing. The queue is
+            // then passed on even if ESI still serves the same response, since
+            // what is training and the SP trained are worked out from the clock.
+            let mut completion_due = false;
+
             loop {
                 if cancel_clone.is_cancelled() {
                     return;
                 }
 
+                let pass_started = tokio::time::Instant::now();
                 let access_token = match auth::ensure_valid_access_token(&pool, character_id).await
                 {
-                    Ok(token) => token,
+                    Ok(token) => {
+                        health
+                            .lock()
+                            .unwrap()
+                            .entry(character_id)
+                            .or_default()
+                            .token_error = None;
+                        token
+                    }
                     Err(e) => {
                         eprintln!("refresh: token error for {}: {}", character_id, e);
+                        health
+                            .lock()
+                            .unwrap()
+                            .entry(character_id)
+                            .or_default()
+                            .token_error = Some(e.to_string());
+                        crate::startup::character_refreshed(&app_handle, character_id);
                         tokio::select! {
                             _ = tokio::time::sleep(Duration::from_secs(300)) => {}
                             _ = poke_clone.notified() => {}
@@ -66,10 +182,27 @@ impl RefreshSupervisor {
                     }
                 };
 
+                match auth::reauth::reauth_required(&pool, character_id).await {
+                    Ok(required) => {
+                        if required != reported_reauth {
+                            if let Some(required) = &required {
+                                if let Err(e) =
+                                    crate::events::emit_reauth_required(&app_handle, required)
+                                {
+                                    eprintln!("refresh: failed to emit reauth event: {}", e);
+                                }
+                            }
+                            reported_reauth = required;
+                        }
+                    }
+                    Err(e) => eprintln!("refresh: scope check failed for {}: {}", character_id, e),
+                }
+
                 let client = match esi_helpers::create_authenticated_client(&access_token) {
                     Ok(c) => c,
                     Err(e) => {
                         eprintln!("refresh: client error for {}: {}", character_id, e);
+                        crate::startup::character_refreshed(&app_handle, character_id);
                         tokio::select! {
                             _ = tokio::time::sleep(Duration::from_secs(300)) => {}
                             _ = poke_clone.notified() => {}
@@ -79,10 +212,42 @@ impl RefreshSupervisor {
                     }
                 };
 
+                let dampening = db::app_settings::get_refresh_dampening(&pool)
+                    .await
+                    .unwrap_or_default();
+                let dampened = activity.is_dampened(&dampening, chrono::Utc::now().timestamp());
+                let dampened_interval =
+                    Duration::from_secs(dampening.dampened_interval_minutes.max(1) as u64 * 60);
+                let full_refresh = !dampened
+                    || last_full_refresh.is_none_or(|at| at.elapsed() >= dampened_interval);
+                if full_refresh {
+                    last_full_refresh = Some(tokio::time::Instant::now());
+                }
+
                 let mut any_success = false;
+                let mut queue_changed = false;
+                let mut skills_changed = false;
+                let mut attributes_changed = false;
+                let mut location_changed = false;
+                let mut clones_changed = false;
+                let mut affiliation_changed = false;
                 let mut queue_skill_ids: Vec<i64> = vec![];
+                let mut next_completion = None;
                 let queue_now = chrono::Utc::now();
 
+                // Modules the user switched off for this character are neither
+                // fetched nor handed to notification checkers.
+                let disabled_modules =
+                    match db::character_modules::get_disabled_modules(&pool, character_id).await {
+                        Ok(disabled) => disabled,
+                        Err(e) => {
+                            eprintln!("refresh: module settings for {}: {}", character_id, e);
+                            Vec::new()
+                        }
+                    };
+                let location_enabled = !disabled_modules.contains(&CharacterModule::Location);
+                let clones_enabled = !disabled_modules.contains(&CharacterModule::Clones);
+
                 // ── Queue ─────────────────────────────────────────────────────
                 match esi_helpers::get_cached_skill_queue(
                     &pool,
@@ -94,6 +259,15 @@ impl RefreshSupervisor {
                 {
                     Ok(Some(queue_data)) => {
                         any_success = true;
+                        queue_changed = seen
+                            .changed(
+                                &pool,
+                                &[format!("characters/{}/skillqueue", character_id)],
+                                character_id,
+                            )
+                            .await
+                            || completion_due;
+                        next_completion = queue_math::next_completion(&queue_data, queue_now);
                         queue_skill_ids = queue_data
                             .iter()
                             .filter(|item| {
@@ -101,20 +275,23 @@ impl RefreshSupervisor {
                             })
                             .map(|item| item.skill_id)
                             .collect();
+                        // Enriched either way: it re-infers Omega status.
                         let payload =
                             enrichment::enrich_queue(&pool, character_id, queue_data).await;
-                        if let Err(e) =
-                            app_handle.emit(&format!("character:{}:queue", character_id), &payload)
-                        {
-                            eprintln!("refresh: emit error queue {}: {}", character_id, e);
+                        if queue_changed {
+                            if let Err(e) =
+                                crate::events::emit_character(&app_handle, character_id, &payload)
+                            {
+                                eprintln!("refresh: emit error queue {}: {}", character_id, e);
+                            }
                         }
                     }
                     Ok(None) => {
                         if let Some(payload) =
                             enrichment::enrich_queue_from_db(&pool, character_id).await
                         {
-                            if let Err(e) = app_handle
-                                .emit(&format!("character:{}:queue", character_id), &payload)
+                            if let Err(e) =
+                                crate::events::emit_character(&app_handle, character_id, &payload)
                             {
                                 eprintln!(
                                     "refresh: emit error queue (cached) {}: {}",
@@ -126,180 +303,315 @@ impl RefreshSupervisor {
                     Err(e) => eprintln!("refresh: fetch error queue {}: {}", character_id, e),
                 }
 
-                // ── Skills ────────────────────────────────────────────────────
-                match esi_helpers::get_cached_character_skills(
-                    &pool,
-                    &client,
-                    character_id,
-                    &rate_limits,
-                )
-                .await
-                {
-                    Ok(Some(skills_data)) => {
-                        any_success = true;
-                        let payload = enrichment::enrich_skills(
-                            &pool,
-                            character_id,
-                            &skills_data,
-                            &queue_skill_ids,
-                        )
-                        .await;
-                        if let Err(e) =
-                            app_handle.emit(&format!("character:{}:skills", character_id), &payload)
-                        {
-                            eprintln!("refresh: emit error skills {}: {}", character_id, e);
+                // Everything below the queue is not notification-critical and
+                // waits for the dampened interval while the window is hidden.
+                if full_refresh {
+                    // ── Skills ────────────────────────────────────────────────────
+                    let skills_endpoint = format!("characters/{}/skills", character_id);
+                    let previous_skills = cached_hash(&pool, &skills_endpoint, character_id).await;
+                    match esi_helpers::get_cached_character_skills(
+                        &pool,
+                        &client,
+                        character_id,
+                        &rate_limits,
+                    )
+                    .await
+                    {
+                        Ok(Some(skills_data)) => {
+                            any_success = true;
+                            if cached_hash(&pool, &skills_endpoint, character_id).await
+                                != previous_skills
+                            {
+                                record_skills_fetched(
+                                    &pool,
+                                    character_id,
+                                    skills_data.skills.len(),
+                                )
+                                .await;
+                            }
+                            skills_changed = seen
+                                .changed(&pool, &[skills_endpoint.clone()], character_id)
+                                .await;
+                            // Skills are flagged by whether they are queued.
+                            if skills_changed || queue_changed {
+                                let payload = enrichment::enrich_skills(
+                                    &pool,
+                                    character_id,
+                                    &skills_data,
+                                    &queue_skill_ids,
+                                )
+                                .await;
+                                if let Err(e) = crate::events::emit_character(
+                                    &app_handle,
+                                    character_id,
+                                    &payload,
+                                ) {
+                                    eprintln!("refresh: emit error skills {}: {}", character_id, e);
+                                }
+                            }
                         }
-                    }
-                    Ok(None) => {
-                        if let Some(payload) =
-                            enrichment::enrich_skills_from_db(&pool, character_id).await
-                        {
-                            if let Err(e) = app_handle
-                                .emit(&format!("character:{}:skills", character_id), &payload)
+                        Ok(None) => {
+                            if let Some(payload) =
+                                enrichment::enrich_skills_from_db(&pool, character_id).await
                             {
-                                eprintln!(
-                                    "refresh: emit error skills (cached) {}: {}",
-                                    character_id, e
-                                );
+                                if let Err(e) = crate::events::emit_character(
+                                    &app_handle,
+                                    character_id,
+                                    &payload,
+                                ) {
+                                    eprintln!(
+                                        "refresh: emit error skills (cached) {}: {}",
+                                        character_id, e
+                                    );
+                                }
                             }
                         }
+                        Err(e) => eprintln!("refresh: fetch error skills {}: {}", character_id, e),
                     }
-                    Err(e) => eprintln!("refresh: fetch error skills {}: {}", character_id, e),
-                }
 
-                // ── Attributes ────────────────────────────────────────────────
-                match esi_helpers::get_cached_character_attributes(
-                    &pool,
-                    &client,
-                    character_id,
-                    &rate_limits,
-                )
-                .await
-                {
-                    Ok(Some(attrs)) => {
-                        any_success = true;
-                        let payload =
-                            enrichment::enrich_attributes(&pool, character_id, &attrs).await;
-                        if let Err(e) = app_handle
-                            .emit(&format!("character:{}:attributes", character_id), &payload)
-                        {
-                            eprintln!("refresh: emit error attributes {}: {}", character_id, e);
+                    // ── Attributes ────────────────────────────────────────────────
+                    match esi_helpers::get_cached_character_attributes(
+                        &pool,
+                        &client,
+                        character_id,
+                        &rate_limits,
+                    )
+                    .await
+                    {
+                        Ok(Some(attrs)) => {
+                            any_success = true;
+                            attributes_changed = seen
+                                .changed(
+                                    &pool,
+                                    &[format!("characters/{}/attributes", character_id)],
+                                    character_id,
+                                )
+                                .await;
+                            if attributes_changed {
+                                let payload =
+                                    enrichment::enrich_attributes(&pool, character_id, &attrs)
+                                        .await;
+                                if let Err(e) = crate::events::emit_character(
+                                    &app_handle,
+                                    character_id,
+                                    &payload,
+                                ) {
+                                    eprintln!(
+                                        "refresh: emit error attributes {}: {}",
+                                        character_id, e
+                                    );
+                                }
+                            }
+                        }
+                        Ok(None) => {
+                            if let Some(payload) =
+                                enrichment::enrich_attributes_from_db(&pool, character_id).await
+                            {
+                                if let Err(e) = crate::events::emit_character(
+                                    &app_handle,
+                                    character_id,
+                                    &payload,
+                                ) {
+                                    eprintln!(
+                                        "refresh: emit error attributes (cached) {}: {}",
+                                        character_id, e
+                                    );
+                                }
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("refresh: fetch error attributes {}: {}", character_id, e)
                         }
                     }
-                    Ok(None) => {
+
+                    // The queue payload carries skill points, attributes and the
+                    // Omega status inferred from them, so resend it when those
+                    // moved under an unchanged queue.
+                    if !queue_changed && (skills_changed || attributes_changed) {
                         if let Some(payload) =
-                            enrichment::enrich_attributes_from_db(&pool, character_id).await
+                            enrichment::enrich_queue_from_db(&pool, character_id).await
                         {
-                            if let Err(e) = app_handle
-                                .emit(&format!("character:{}:attributes", character_id), &payload)
+                            if let Err(e) =
+                                crate::events::emit_character(&app_handle, character_id, &payload)
                             {
-                                eprintln!(
-                                    "refresh: emit error attributes (cached) {}: {}",
-                                    character_id, e
-                                );
+                                eprintln!("refresh: emit error queue {}: {}", character_id, e);
                             }
                         }
                     }
-                    Err(e) => {
-                        eprintln!("refresh: fetch error attributes {}: {}", character_id, e)
-                    }
-                }
 
-                // ── Location ──────────────────────────────────────────────────
-                match esi_helpers::get_cached_character_location(
-                    &pool,
-                    &client,
-                    character_id,
-                    &rate_limits,
-                )
-                .await
-                {
-                    Ok(Some(loc)) => {
-                        any_success = true;
-                        if let Some(payload) = enrichment::enrich_location(
+                    // ── Location ──────────────────────────────────────────────────
+                    if location_enabled {
+                        match esi_helpers::get_cached_character_location(
                             &pool,
                             &client,
                             character_id,
                             &rate_limits,
-                            &last_location_ids,
                         )
                         .await
                         {
-                            last_location_ids = enrichment::LocationIds {
-                                solar_system_id: Some(loc.solar_system_id),
-                                station_id: loc.station_id,
-                                structure_id: loc.structure_id,
-                            };
-                            if let Err(e) = app_handle
-                                .emit(&format!("character:{}:location", character_id), &payload)
-                            {
-                                eprintln!("refresh: emit error location {}: {}", character_id, e);
+                            Ok(Some(loc)) => {
+                                any_success = true;
+                                if let Some(payload) = enrichment::enrich_location(
+                                    &pool,
+                                    &client,
+                                    character_id,
+                                    &rate_limits,
+                                    &last_location_ids,
+                                )
+                                .await
+                                {
+                                    last_location_ids = enrichment::LocationIds {
+                                        solar_system_id: Some(loc.solar_system_id),
+                                        station_id: loc.station_id,
+                                        structure_id: loc.structure_id,
+                                    };
+                                    let endpoints = ["location", "ship", "online", "implants"]
+                                        .map(|e| format!("characters/{}/{}", character_id, e));
+                                    location_changed =
+                                        seen.changed(&pool, &endpoints, character_id).await;
+                                    if location_changed {
+                                        if let Err(e) = crate::events::emit_character(
+                                            &app_handle,
+                                            character_id,
+                                            &payload,
+                                        ) {
+                                            eprintln!(
+                                                "refresh: emit error location {}: {}",
+                                                character_id, e
+                                            );
+                                        }
+                                    }
+                                }
                             }
-                        }
-                    }
-                    Ok(None) => {
-                        if let Some(payload) =
-                            enrichment::enrich_location_db_only(&pool, character_id).await
-                        {
-                            if let Err(e) = app_handle
-                                .emit(&format!("character:{}:location", character_id), &payload)
-                            {
-                                eprintln!(
-                                    "refresh: emit error location (cached) {}: {}",
-                                    character_id, e
-                                );
+                            Ok(None) => {
+                                if let Some(payload) =
+                                    enrichment::enrich_location_db_only(&pool, character_id).await
+                                {
+                                    if let Err(e) = crate::events::emit_character(
+                                        &app_handle,
+                                        character_id,
+                                        &payload,
+                                    ) {
+                                        eprintln!(
+                                            "refresh: emit error location (cached) {}: {}",
+                                            character_id, e
+                                        );
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                eprintln!("refresh: fetch error location {}: {}", character_id, e)
                             }
                         }
                     }
-                    Err(e) => eprintln!("refresh: fetch error location {}: {}", character_id, e),
-                }
 
-                // ── Clones ────────────────────────────────────────────────────
-                match esi_helpers::get_cached_character_clones(
-                    &pool,
-                    &client,
-                    character_id,
-                    &rate_limits,
-                )
-                .await
-                {
-                    Ok(Some(clones_data)) => {
-                        any_success = true;
-                        if let Err(e) = crate::clone_sync::sync_character_clones_to_db(
+                    // ── Clones ────────────────────────────────────────────────────
+                    if clones_enabled {
+                        match esi_helpers::get_cached_character_clones(
                             &pool,
                             &client,
                             character_id,
                             &rate_limits,
-                            &clones_data,
                         )
                         .await
                         {
-                            eprintln!("refresh: clone DB sync {}: {}", character_id, e);
-                        } else {
-                            let payload = enrichment::enrich_clones(&pool, character_id).await;
-                            if let Err(e) = app_handle
-                                .emit(&format!("character:{}:clones", character_id), &payload)
-                            {
-                                eprintln!("refresh: emit error clones {}: {}", character_id, e);
+                            Ok(Some(clones_data)) => {
+                                any_success = true;
+                                let clones_endpoint = format!("characters/{}/clones", character_id);
+                                clones_changed = seen
+                                    .changed(&pool, &[clones_endpoint.clone()], character_id)
+                                    .await;
+                                // Unchanged clones were synced on the pass that saw them.
+                                if clones_changed {
+                                    if let Err(e) = crate::clone_sync::sync_character_clones_to_db(
+                                        &pool,
+                                        &client,
+                                        character_id,
+                                        &rate_limits,
+                                        &clones_data,
+                                    )
+                                    .await
+                                    {
+                                        eprintln!("refresh: clone DB sync {}: {}", character_id, e);
+                                        seen.forget(&clones_endpoint, character_id);
+                                        clones_changed = false;
+                                    } else {
+                                        let payload =
+                                            enrichment::enrich_clones(&pool, character_id).await;
+                                        if let Err(e) = crate::events::emit_character(
+                                            &app_handle,
+                                            character_id,
+                                            &payload,
+                                        ) {
+                                            eprintln!(
+                                                "refresh: emit error clones {}: {}",
+                                                character_id, e
+                                            );
+                                        }
+                                    }
+                                }
+                            }
+                            Ok(None) => {
+                                let payload = enrichment::enrich_clones(&pool, character_id).await;
+                                if !payload.clones.is_empty() {
+                                    if let Err(e) = crate::events::emit_character(
+                                        &app_handle,
+                                        character_id,
+                                        &payload,
+                                    ) {
+                                        eprintln!(
+                                            "refresh: emit error clones (cached) {}: {}",
+                                            character_id, e
+                                        );
+                                    }
+                                }
+                            }
+                            Err(e) => {
+                                eprintln!("refresh: fetch error clones {}: {}", character_id, e)
                             }
                         }
                     }
-                    Ok(None) => {
-                        let payload = enrichment::enrich_clones(&pool, character_id).await;
-                        if !payload.clones.is_empty() {
-                            if let Err(e) = app_handle
-                                .emit(&format!("character:{}:clones", character_id), &payload)
+
+                    // Attributes are snapshotted after clones so an implant swap is
+                    // attributed to the clone actually in use.
+                    enrichment::record_attribute_history(&pool, character_id).await;
+
+                    // ── Affiliation ───────────────────────────────────────────────
+                    match crate::affiliation::fetch_public_character(
+                        &pool,
+                        &client,
+                        &rate_limits,
+                        character_id,
+                    )
+                    .await
+                    {
+                        Ok(Some(public)) => {
+                            // Public endpoints are cached under character id 0.
+                            let endpoint = format!("characters/{}", character_id);
+                            affiliation_changed = seen.changed(&pool, &[endpoint.clone()], 0).await;
+                            match db::membership::record_membership(
+                                &pool,
+                                character_id,
+                                &public.affiliation,
+                            )
+                            .await
                             {
-                                eprintln!(
-                                    "refresh: emit error clones (cached) {}: {}",
-                                    character_id, e
-                                );
+                                Ok(recorded) => affiliation_changed |= recorded,
+                                Err(e) => {
+                                    eprintln!("refresh: membership record {}: {}", character_id, e);
+                                    seen.forget(&endpoint, 0);
+                                }
                             }
                         }
+                        Ok(None) => {}
+                        Err(e) => {
+                            eprintln!("refresh: fetch error affiliation {}: {}", character_id, e)
+                        }
                     }
-                    Err(e) => eprintln!("refresh: fetch error clones {}: {}", character_id, e),
                 }
 
+                crate::startup::character_refreshed(&app_handle, character_id);
+
                 if !any_success {
                     tokio::select! {
                         _ = tokio::time::sleep(Duration::from_secs(300)) => {}
@@ -309,19 +621,30 @@ impl RefreshSupervisor {
                     continue;
                 }
 
-                // Process notifications for each fetched resource type
-                let data_types = [
-                    notifications::DataType::SkillQueue,
-                    notifications::DataType::Skills,
-                    notifications::DataType::Attributes,
-                    notifications::DataType::Clones,
-                    notifications::DataType::Location,
-                ];
+                // Process notifications for each resource type that changed.
+                // Omega status is inferred from skills and attributes too, so
+                // those rerun the queue checkers. Disabled modules never change.
+                let mut data_types = Vec::new();
+                if queue_changed || skills_changed || attributes_changed {
+                    data_types.push(notifications::DataType::SkillQueue);
+                }
+                for (changed, data_type) in [
+                    (skills_changed, notifications::DataType::Skills),
+                    (attributes_changed, notifications::DataType::Attributes),
+                    (clones_changed, notifications::DataType::Clones),
+                    (location_changed, notifications::DataType::Location),
+                    (affiliation_changed, notifications::DataType::Affiliation),
+                ] {
+                    if changed {
+                        data_types.push(data_type);
+                    }
+                }
                 let ctx = notifications::NotificationContext {
                     app: &app_handle,
                     pool: &pool,
                     rate_limits: &rate_limits,
                 };
+                let anything_changed = !data_types.is_empty();
                 for data_type in data_types {
                     if let Err(e) = notification_processor
                         .process_data_updated(&ctx, data_type, character_id)
@@ -334,22 +657,44 @@ impl RefreshSupervisor {
                     }
                 }
 
+                let succeeded_at = chrono::Utc::now().timestamp();
+                {
+                    let mut health = health.lock().unwrap();
+                    let health = health.entry(character_id).or_default();
+                    health.last_success_at = Some(succeeded_at);
+                    health.last_pass_duration = Some(pass_started.elapsed());
+                }
+                if let Err(e) =
+                    db::characters::record_refresh_success(&pool, character_id, succeeded_at).await
+                {
+                    eprintln!("refresh: record success for {}: {}", character_id, e);
+                }
+
                 // ── Overview ─────────────────────────────────────────────────
-                let overview_row = enrichment::compute_overview_row(&pool, character_id).await;
-                if let Err(e) = app_handle.emit(
-                    &format!("character:{}:overview", character_id),
-                    &overview_row,
-                ) {
-                    eprintln!("refresh: emit error overview {}: {}", character_id, e);
+                if anything_changed {
+                    let overview_row = enrichment::compute_overview_row(&pool, character_id).await;
+                    if let Err(e) =
+                        crate::events::emit_character(&app_handle, character_id, &overview_row)
+                    {
+                        eprintln!("refresh: emit error overview {}: {}", character_id, e);
+                    }
                 }
 
-                let endpoints = [
-                    format!("characters/{}/skillqueue", character_id),
-                    format!("characters/{}/attributes", character_id),
-                    format!("characters/{}/skills", character_id),
-                    format!("characters/{}/clones", character_id),
-                    format!("characters/{}/location", character_id),
-                ];
+                // While dampened only the queue's cache expiry sets the wake-up;
+                // the rest is picked up once the dampened interval has passed.
+                let mut endpoints = vec![format!("characters/{}/skillqueue", character_id)];
+                if !dampened {
+                    endpoints.extend([
+                        format!("characters/{}/attributes", character_id),
+                        format!("characters/{}/skills", character_id),
+                    ]);
+                    if clones_enabled {
+                        endpoints.push(format!("characters/{}/clones", character_id));
+                    }
+                    if location_enabled {
+                        endpoints.push(format!("characters/{}/location", character_id));
+                    }
+                }
 
                 let mut expires_list: Vec<i64> = Vec::new();
                 for endpoint in &endpoints {
@@ -364,13 +709,30 @@ impl RefreshSupervisor {
                 let secs_until = (min_expires - now).clamp(30, 3600);
                 let jitter_range = secs_until / 10;
                 let jitter = rand::rng().random_range(-jitter_range.max(1)..=jitter_range.max(1));
-                let sleep_secs = (secs_until + jitter).clamp(30, 3600) as u64;
+                let mut sleep_secs = (secs_until + jitter).clamp(30, 3600) as u64;
 
-                tokio::select! {
-                    _ = tokio::time::sleep(Duration::from_secs(sleep_secs)) => {}
-                    _ = poke_clone.notified() => {}
-                    _ = cancel_clone.cancelled() => { return; }
+                // Wake right after the training level finishes rather than at
+                // the next cache expiry, so it shows as done within seconds.
+                let until_completion = next_completion.map(|finish| {
+                    ((finish.timestamp() - now + COMPLETION_BUFFER_SECS).max(1)) as u64
+                });
+                let wakes_for_completion = until_completion.is_some_and(|secs| secs < sleep_secs);
+                if let Some(secs) = until_completion.filter(|_| wakes_for_completion) {
+                    sleep_secs = secs;
                 }
+
+                if completion_due {
+                    // The tray polls on its own interval; catch it up too.
+                    if let Some(scheduler) = app_handle.try_state::<Scheduler>() {
+                        scheduler.run_now(ScheduledTask::SkillQueue);
+                    }
+                }
+
+                completion_due = tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_secs(sleep_secs)) => wakes_for_completion,
+                    _ = poke_clone.notified() => false,
+                    _ = cancel_clone.cancelled() => { return; }
+                };
             }
         });
 
@@ -406,4 +768,24 @@ impl RefreshSupervisor {
             handle.poke.notify_one();
         }
     }
+
+    pub fn health(&self, character_id: i64) -> RefreshHealth {
+        self.health
+            .lock()
+            .unwrap()
+            .get(&character_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Health of every character that has finished a pass.
+    pub fn all_health(&self) -> HashMap<i64, RefreshHealth> {
+        self.health.lock().unwrap().clone()
+    }
+
+    pub fn poke_all(&self) {
+        for handle in self.handles.values() {
+            handle.poke.notify_one();
+        }
+    }
 }
@@ -0,0 +1,51 @@
+//! The last response of the expensive read commands the window makes when it
+//! opens, kept in memory as JSON. A caller that passes `allow_cached` gets it
+//! back straight away, flagged `is_cached`, while a fresh one is computed in
+//! the background and pushed to the frontend as an event.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+#[derive(Debug, Default)]
+pub struct ResponseCache {
+    entries: Mutex<HashMap<String, serde_json::Value>>,
+}
+
+impl ResponseCache {
+    /// `None` when nothing is stored under `key` or it no longer deserializes
+    /// as `T`.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let value = self.entries.lock().unwrap().get(key).cloned()?;
+        serde_json::from_value(value).ok()
+    }
+
+    pub fn put<T: Serialize>(&self, key: &str, response: &T) {
+        match serde_json::to_value(response) {
+            Ok(value) => {
+                self.entries.lock().unwrap().insert(key.to_string(), value);
+            }
+            Err(e) => log::warn!("Failed to cache response for {}: {}", key, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn returns_the_latest_response_per_key() {
+        let cache = ResponseCache::default();
+        assert_eq!(cache.get::<Vec<i64>>("queues"), None);
+
+        cache.put("queues", &vec![1, 2]);
+        cache.put("queues", &vec![3]);
+        cache.put("skills", &"all");
+        assert_eq!(cache.get::<Vec<i64>>("queues"), Some(vec![3]));
+        assert_eq!(cache.get::<String>("skills"), Some("all".to_string()));
+        assert_eq!(cache.get::<Vec<i64>>("skills"), None);
+    }
+}
@@ -0,0 +1,384 @@
+//! Recurring background work that is not tied to one character: the tray's
+//! training poll, market prices, the SDE version check, the watchlist and
+//! database maintenance. Each task runs on its own loop with an interval from
+//! [`SchedulerSettings`], re-read every round, and a jittered first run so
+//! they do not all fire together at startup.
+//!
+//! Per-character ESI refreshes stay with the refresh supervisor and
+//! notification checks with `notifications::run_scheduled_checks`; both pace
+//! themselves per character.
+//!
+//! Pausing is in-memory and lasts until resumed or the app restarts. A paused
+//! task keeps its schedule and skips the runs that fall due.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use rand::RngExt;
+use serde::{Deserialize, Serialize};
+use tauri::menu::{MenuItem, Submenu};
+use tauri::{AppHandle, Wry};
+use tokio::sync::Notify;
+use tokio::time::{Duration, Instant};
+use typeshare::typeshare;
+
+use crate::db::app_settings::{get_app_setting, set_app_setting};
+use crate::ts_types::i64_ts;
+use crate::{db, esi, maintenance, sde, tray, watchlist};
+
+const SCHEDULER_KEY: &str = "scheduler";
+const MIN_INTERVAL_SECS: i64 = 10;
+const MAX_START_JITTER: Duration = Duration::from_secs(60);
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScheduledTask {
+    /// Polls every character's queue for the tray menu.
+    SkillQueue,
+    MarketPrices,
+    SdeCheck,
+    Watchlist,
+    /// Checks whether weekly maintenance is due and the window is idle.
+    Maintenance,
+}
+
+impl ScheduledTask {
+    pub const ALL: [ScheduledTask; 5] = [
+        ScheduledTask::SkillQueue,
+        ScheduledTask::MarketPrices,
+        ScheduledTask::SdeCheck,
+        ScheduledTask::Watchlist,
+        ScheduledTask::Maintenance,
+    ];
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|t| *t == self).unwrap()
+    }
+
+    fn interval_secs(self, settings: &SchedulerSettings) -> i64 {
+        match self {
+            ScheduledTask::SkillQueue => settings.skill_queue_secs,
+            ScheduledTask::MarketPrices => settings.market_prices_secs,
+            ScheduledTask::SdeCheck => settings.sde_check_secs,
+            ScheduledTask::Watchlist => settings.watchlist_secs,
+            ScheduledTask::Maintenance => settings.maintenance_secs,
+        }
+    }
+
+    pub fn interval(self, settings: &SchedulerSettings) -> Duration {
+        Duration::from_secs(self.interval_secs(settings).max(MIN_INTERVAL_SECS) as u64)
+    }
+
+    /// Delay before the first run, before jitter. Startup already checks the
+    /// SDE and prunes, so those wait a full interval.
+    fn first_run_delay(self, settings: &SchedulerSettings) -> Duration {
+        match self {
+            ScheduledTask::SkillQueue => Duration::from_secs(5),
+            ScheduledTask::MarketPrices => Duration::from_secs(60),
+            ScheduledTask::Watchlist => Duration::ZERO,
+            ScheduledTask::SdeCheck | ScheduledTask::Maintenance => self.interval(settings),
+        }
+    }
+}
+
+/// Seconds between runs of each [`ScheduledTask`]; values below 10 are
+/// treated as 10.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SchedulerSettings {
+    pub skill_queue_secs: i64_ts,
+    pub market_prices_secs: i64_ts,
+    pub sde_check_secs: i64_ts,
+    pub watchlist_secs: i64_ts,
+    pub maintenance_secs: i64_ts,
+}
+
+impl Default for SchedulerSettings {
+    fn default() -> Self {
+        Self {
+            skill_queue_secs: 30,
+            market_prices_secs: 6 * 60 * 60,
+            sde_check_secs: 24 * 60 * 60,
+            watchlist_secs: 60 * 60,
+            maintenance_secs: 60 * 60,
+        }
+    }
+}
+
+pub async fn get_scheduler_settings(pool: &db::Pool) -> Result<SchedulerSettings> {
+    let Some(raw) = get_app_setting(pool, SCHEDULER_KEY).await? else {
+        return Ok(SchedulerSettings::default());
+    };
+    Ok(serde_json::from_str(&raw).unwrap_or_default())
+}
+
+pub async fn set_scheduler_settings(pool: &db::Pool, settings: &SchedulerSettings) -> Result<()> {
+    let json = serde_json::to_string(settings)?;
+    set_app_setting(pool, SCHEDULER_KEY, &json).await
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduledTaskStatus {
+    pub task: ScheduledTask,
+    pub interval_secs: i64_ts,
+    pub paused: bool,
+    pub running: bool,
+    pub last_run_at: Option<i64_ts>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct TaskState {
+    paused: bool,
+    running: bool,
+    last_run_at: Option<i64>,
+    last_error: Option<String>,
+}
+
+/// Everything the task bodies need.
+pub struct TaskContext {
+    pub app: AppHandle,
+    pub pool: db::Pool,
+    pub rate_limits: esi::RateLimitStore,
+    pub training_count_item: MenuItem<Wry>,
+    pub accounts_submenu: Submenu<Wry>,
+}
+
+/// Shared handle to the running tasks, managed as Tauri state.
+#[derive(Clone, Default)]
+pub struct Scheduler {
+    tasks: Arc<Mutex<HashMap<ScheduledTask, TaskState>>>,
+    settings_changed: Arc<Notify>,
+    /// One per task, in [`ScheduledTask::ALL`] order; see [`Scheduler::run_now`].
+    run_now: Arc<[Notify; ScheduledTask::ALL.len()]>,
+}
+
+fn start_jitter(interval: Duration) -> Duration {
+    let max = (interval / 10).min(MAX_START_JITTER);
+    Duration::from_millis(rand::rng().random_range(0..=max.as_millis() as u64))
+}
+
+impl Scheduler {
+    pub fn spawn(&self, ctx: TaskContext) {
+        let ctx = Arc::new(ctx);
+        for task in ScheduledTask::ALL {
+            let scheduler = self.clone();
+            let ctx = ctx.clone();
+            tauri::async_runtime::spawn(async move { scheduler.run_loop(task, &ctx).await });
+        }
+    }
+
+    async fn run_loop(&self, task: ScheduledTask, ctx: &TaskContext) {
+        let settings = get_scheduler_settings(&ctx.pool).await.unwrap_or_default();
+        let mut last_run: Option<Instant> = None;
+        let mut next_run = Instant::now()
+            + task.first_run_delay(&settings)
+            + start_jitter(task.interval(&settings));
+
+        let run_now = &self.run_now[task.index()];
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep_until(next_run) => {}
+                _ = run_now.notified() => {}
+                _ = self.settings_changed.notified() => {
+                    // Reschedule from the last run with the new interval.
+                    if let Some(last_run) = last_run {
+                        let settings = get_scheduler_settings(&ctx.pool).await.unwrap_or_default();
+                        next_run = last_run + task.interval(&settings);
+                    }
+                    continue;
+                }
+            }
+
+            if self.begin(task) {
+                let result = run_task(task, ctx).await;
+                if let Err(e) = &result {
+                    eprintln!("scheduler: {:?} failed: {:#}", task, e);
+                }
+                self.finish(task, result.err().map(|e| e.to_string()));
+            }
+            last_run = Some(Instant::now());
+
+            let settings = get_scheduler_settings(&ctx.pool).await.unwrap_or_default();
+            next_run = Instant::now() + task.interval(&settings);
+        }
+    }
+
+    /// Marks the task running; `false` (skip this run) while it is paused.
+    fn begin(&self, task: ScheduledTask) -> bool {
+        let mut tasks = self.tasks.lock().unwrap();
+        let state = tasks.entry(task).or_default();
+        if state.paused {
+            return false;
+        }
+        state.running = true;
+        true
+    }
+
+    fn finish(&self, task: ScheduledTask, error: Option<String>) {
+        let mut tasks = self.tasks.lock().unwrap();
+        let state = tasks.entry(task).or_default();
+        state.running = false;
+        state.last_run_at = Some(chrono::Utc::now().timestamp());
+        state.last_error = error;
+    }
+
+    /// Pauses or resumes `task`, or every task when `None`.
+    pub fn set_paused(&self, task: Option<ScheduledTask>, paused: bool) {
+        let mut tasks = self.tasks.lock().unwrap();
+        for t in ScheduledTask::ALL {
+            if task.is_none_or(|task| task == t) {
+                tasks.entry(t).or_default().paused = paused;
+            }
+        }
+    }
+
+    /// Pauses every task that is not already paused and waits for any run in
+    /// progress to finish. Returns the tasks it paused, for [`Scheduler::resume`],
+    /// so tasks the user paused stay paused.
+    pub async fn pause_all_and_wait(&self) -> Vec<ScheduledTask> {
+        let paused = {
+            let mut tasks = self.tasks.lock().unwrap();
+            ScheduledTask::ALL
+                .into_iter()
+                .filter(|t| {
+                    let state = tasks.entry(*t).or_default();
+                    !std::mem::replace(&mut state.paused, true)
+                })
+                .collect()
+        };
+        while self.tasks.lock().unwrap().values().any(|s| s.running) {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        paused
+    }
+
+    pub fn resume(&self, tasks: &[ScheduledTask]) {
+        let mut tasks_state = self.tasks.lock().unwrap();
+        for task in tasks {
+            tasks_state.entry(*task).or_default().paused = false;
+        }
+    }
+
+    /// Runs `task` as soon as it is idle instead of at its next interval; the
+    /// interval then restarts from that run.
+    pub fn run_now(&self, task: ScheduledTask) {
+        self.run_now[task.index()].notify_one();
+    }
+
+    /// Wakes every task so a new interval applies without waiting out the old.
+    pub fn settings_changed(&self) {
+        self.settings_changed.notify_waiters();
+    }
+
+    pub fn status(&self, settings: &SchedulerSettings) -> Vec<ScheduledTaskStatus> {
+        let tasks = self.tasks.lock().unwrap();
+        ScheduledTask::ALL
+            .iter()
+            .map(|&task| {
+                let state = tasks.get(&task).cloned().unwrap_or_default();
+                ScheduledTaskStatus {
+                    task,
+                    interval_secs: task.interval(settings).as_secs() as i64,
+                    paused: state.paused,
+                    running: state.running,
+                    last_run_at: state.last_run_at,
+                    last_error: state.last_error,
+                }
+            })
+            .collect()
+    }
+}
+
+async fn run_task(task: ScheduledTask, ctx: &TaskContext) -> Result<()> {
+    match task {
+        ScheduledTask::SkillQueue => {
+            tray::update_tray_menu(
+                &ctx.app,
+                &ctx.pool,
+                &ctx.rate_limits,
+                &ctx.training_count_item,
+                &ctx.accounts_submenu,
+            )
+            .await;
+            Ok(())
+        }
+        ScheduledTask::MarketPrices => {
+            esi::markets::refresh_market_prices(&ctx.pool, &ctx.rate_limits).await?;
+            Ok(())
+        }
+        ScheduledTask::SdeCheck => sde::ensure_latest(&ctx.app, &ctx.pool, || {}).await,
+        ScheduledTask::Watchlist => {
+            watchlist::refresh_all(&ctx.app, &ctx.pool, &ctx.rate_limits).await
+        }
+        ScheduledTask::Maintenance => maintenance::run_if_due(&ctx.app, &ctx.pool).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intervals_are_clamped_and_pausing_skips_runs() {
+        let settings = SchedulerSettings {
+            skill_queue_secs: 1,
+            ..SchedulerSettings::default()
+        };
+        assert_eq!(
+            ScheduledTask::SkillQueue.interval(&settings),
+            Duration::from_secs(10)
+        );
+        assert!(start_jitter(Duration::from_secs(30)) <= Duration::from_secs(3));
+        assert!(start_jitter(Duration::from_secs(86_400)) <= MAX_START_JITTER);
+
+        let scheduler = Scheduler::default();
+        scheduler.set_paused(None, true);
+        scheduler.set_paused(Some(ScheduledTask::Watchlist), false);
+        assert!(!scheduler.begin(ScheduledTask::SkillQueue));
+        assert!(scheduler.begin(ScheduledTask::Watchlist));
+        scheduler.finish(ScheduledTask::Watchlist, Some("offline".to_string()));
+
+        let status = scheduler.status(&settings);
+        let watchlist = status
+            .iter()
+            .find(|s| s.task == ScheduledTask::Watchlist)
+            .unwrap();
+        assert!(!watchlist.running && !watchlist.paused);
+        assert_eq!(watchlist.last_error.as_deref(), Some("offline"));
+    }
+
+    #[tokio::test]
+    async fn pause_all_waits_for_running_tasks_and_resume_keeps_user_pauses() {
+        let scheduler = Scheduler::default();
+        scheduler.set_paused(Some(ScheduledTask::SdeCheck), true);
+        assert!(scheduler.begin(ScheduledTask::Watchlist));
+
+        let finisher = {
+            let scheduler = scheduler.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                scheduler.finish(ScheduledTask::Watchlist, None);
+            })
+        };
+        let paused = scheduler.pause_all_and_wait().await;
+        finisher.await.unwrap();
+
+        let settings = SchedulerSettings::default();
+        assert!(scheduler
+            .status(&settings)
+            .iter()
+            .all(|s| !s.running && s.paused));
+        assert!(!paused.contains(&ScheduledTask::SdeCheck));
+        assert_eq!(paused.len(), ScheduledTask::ALL.len() - 1);
+
+        scheduler.resume(&paused);
+        for status in scheduler.status(&settings) {
+            assert_eq!(status.paused, status.task == ScheduledTask::SdeCheck);
+        }
+    }
+}
@@ -0,0 +1,406 @@
+//! Resumable download of the SDE archive. When the server supports byte
+//! ranges the archive is fetched over several connections at once, each range
+//! into its own `.partN` file next to the archive. A manifest records the
+//! range layout, so after a failure (or an app restart) every range resumes
+//! from the bytes already on disk instead of starting over. The joined
+//! archive is checked to be a complete zip before it is handed to extraction.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use futures_util::StreamExt;
+use reqwest::header::{ACCEPT_RANGES, CONTENT_LENGTH, RANGE};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use zip::ZipArchive;
+
+use super::TARGET_FILES;
+use crate::db::app_settings::SdeDownloadSettings;
+
+const MAX_CONNECTIONS: usize = 8;
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Byte range `start..=end` of the archive; `end` is `None` when the size is
+/// unknown and the range runs to the end of the body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Segment {
+    start: u64,
+    end: Option<u64>,
+}
+
+impl Segment {
+    fn len(&self) -> Option<u64> {
+        self.end.map(|end| end - self.start + 1)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Manifest {
+    url: String,
+    total_len: Option<u64>,
+    segments: Vec<Segment>,
+}
+
+/// Splits `total_len` bytes into at most `connections` contiguous segments.
+fn split_segments(total_len: u64, connections: usize) -> Vec<Segment> {
+    let count = (connections.max(1) as u64).min(total_len.max(1));
+    let size = total_len.div_ceil(count);
+    (0..count)
+        .map(|i| i * size)
+        .take_while(|start| *start < total_len)
+        .map(|start| Segment {
+            start,
+            end: Some((start + size).min(total_len) - 1),
+        })
+        .collect()
+}
+
+fn part_path(dest: &Path, index: usize) -> PathBuf {
+    let mut name = dest.as_os_str().to_owned();
+    name.push(format!(".part{}", index));
+    PathBuf::from(name)
+}
+
+fn manifest_path(dest: &Path) -> PathBuf {
+    let mut name = dest.as_os_str().to_owned();
+    name.push(".parts.json");
+    PathBuf::from(name)
+}
+
+/// Size of the archive and whether the server serves byte ranges.
+async fn probe(client: &reqwest::Client, url: &str) -> (Option<u64>, bool) {
+    let Ok(response) = client.head(url).send().await else {
+        return (None, false);
+    };
+    if !response.status().is_success() {
+        return (None, false);
+    }
+    let headers = response.headers();
+    let total_len = headers
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    let ranges = headers
+        .get(ACCEPT_RANGES)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("bytes"));
+    (total_len, ranges)
+}
+
+async fn file_len(path: &Path) -> u64 {
+    fs::metadata(path).await.map(|m| m.len()).unwrap_or(0)
+}
+
+async fn remove_parts(dest: &Path, manifest: &Manifest) {
+    for index in 0..manifest.segments.len() {
+        fs::remove_file(part_path(dest, index)).await.ok();
+    }
+    fs::remove_file(manifest_path(dest)).await.ok();
+}
+
+/// Reuses the manifest left by an interrupted download when it describes the
+/// same archive and layout; otherwise clears the stale parts and starts anew.
+async fn prepare_manifest(dest: &Path, wanted: Manifest) -> Result<Manifest> {
+    let path = manifest_path(dest);
+    if let Ok(raw) = fs::read_to_string(&path).await {
+        if let Ok(existing) = serde_json::from_str::<Manifest>(&raw) {
+            if existing == wanted {
+                return Ok(existing);
+            }
+            remove_parts(dest, &existing).await;
+        }
+    }
+    fs::write(&path, serde_json::to_string(&wanted)?)
+        .await
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(wanted)
+}
+
+/// One attempt at fetching the rest of a segment into `part`.
+async fn fetch_segment(
+    client: &reqwest::Client,
+    url: &str,
+    segment: Segment,
+    part: &Path,
+    ranged: bool,
+) -> Result<()> {
+    let have = file_len(part).await;
+    if segment.len().is_some_and(|len| have >= len) {
+        return Ok(());
+    }
+
+    let mut request = client.get(url);
+    if ranged || have > 0 {
+        let from = segment.start + have;
+        let range = match segment.end {
+            Some(end) => format!("bytes={}-{}", from, end),
+            None => format!("bytes={}-", from),
+        };
+        request = request.header(RANGE, range);
+    }
+    let response = request.send().await?;
+
+    let append = match response.status() {
+        StatusCode::PARTIAL_CONTENT => true,
+        // Nothing left past what is already on disk.
+        StatusCode::RANGE_NOT_SATISFIABLE if have > 0 && segment.end.is_none() => return Ok(()),
+        // The server ignored the range; only a single whole-file segment can
+        // use the full body.
+        status if status.is_success() && segment.start == 0 && !ranged => false,
+        status => bail!("failed to download SDE zip {}: {}", url, status),
+    };
+
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(part)
+        .await
+        .with_context(|| format!("failed to open {}", part.display()))?;
+
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        file.write_all(&chunk?).await?;
+    }
+    file.flush().await?;
+
+    let have = file_len(part).await;
+    if let Some(len) = segment.len() {
+        if have != len {
+            bail!("SDE download range ended at {} of {} bytes", have, len);
+        }
+    }
+    Ok(())
+}
+
+/// Fetches a segment, retrying with exponential backoff. Every retry resumes
+/// from the bytes already written.
+async fn download_segment(
+    client: &reqwest::Client,
+    url: &str,
+    segment: Segment,
+    part: PathBuf,
+    ranged: bool,
+    max_retries: u32,
+) -> Result<()> {
+    let mut attempt = 0;
+    loop {
+        match fetch_segment(client, url, segment, &part, ranged).await {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < max_retries => {
+                attempt += 1;
+                let backoff = Duration::from_secs(1 << attempt.min(5)).min(MAX_BACKOFF);
+                eprintln!(
+                    "SDE download of bytes {}+ failed (attempt {}/{}): {:#}; retrying in {:?}",
+                    segment.start, attempt, max_retries, e, backoff
+                );
+                tokio::time::sleep(backoff).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+async fn join_parts(dest: &Path, manifest: &Manifest) -> Result<()> {
+    let mut out = fs::File::create(dest)
+        .await
+        .with_context(|| format!("failed to create {}", dest.display()))?;
+    for index in 0..manifest.segments.len() {
+        let mut part = fs::File::open(part_path(dest, index)).await?;
+        tokio::io::copy(&mut part, &mut out).await?;
+    }
+    out.flush().await?;
+
+    if let Some(total_len) = manifest.total_len {
+        let len = file_len(dest).await;
+        if len != total_len {
+            bail!("SDE zip is {} bytes, expected {}", len, total_len);
+        }
+    }
+    Ok(())
+}
+
+/// Checks that `zip_path` is a readable archive holding every file the
+/// importer needs, decompressing each one so a CRC mismatch is caught here
+/// rather than halfway through the import.
+pub async fn verify_zip(zip_path: &Path) -> Result<()> {
+    let zip_path = zip_path.to_path_buf();
+    tokio::task::spawn_blocking(move || -> Result<()> {
+        let file = std::fs::File::open(&zip_path)
+            .with_context(|| format!("failed to open zip at {}", zip_path.display()))?;
+        let mut archive = ZipArchive::new(file)
+            .with_context(|| format!("failed to read zip archive {}", zip_path.display()))?;
+        for name in TARGET_FILES {
+            let mut entry = archive
+                .by_name(name)
+                .with_context(|| format!("missing {} in archive", name))?;
+            let mut sink = std::io::sink();
+            std::io::copy(&mut entry, &mut sink)
+                .with_context(|| format!("{} in archive is corrupt", name))?;
+        }
+        Ok(())
+    })
+    .await?
+}
+
+/// Lower-case hex SHA-256 of the file at `path`.
+async fn sha256_file(path: &Path) -> Result<String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || -> Result<String> {
+        let mut file = std::fs::File::open(&path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        let mut hasher = Sha256::new();
+        let mut buf = vec![0u8; 64 * 1024];
+        loop {
+            let read = std::io::Read::read(&mut file, &mut buf)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buf[..read]);
+        }
+        Ok(hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect())
+    })
+    .await?
+}
+
+/// Compares the archive with the checksum published in the metadata feed,
+/// given as bare hex or prefixed `sha256:`.
+pub async fn verify_checksum(zip_path: &Path, expected: &str) -> Result<()> {
+    let expected = expected.trim();
+    let expected = expected
+        .strip_prefix("sha256:")
+        .unwrap_or(expected)
+        .to_ascii_lowercase();
+    let actual = sha256_file(zip_path).await?;
+    if actual != expected {
+        bail!(
+            "SDE zip checksum mismatch: expected sha256 {}, got {}",
+            expected,
+            actual
+        );
+    }
+    Ok(())
+}
+
+/// Downloads `url` to `dest`, resuming any earlier partial download, and
+/// verifies the result. An archive already at `dest` that verifies (left by
+/// an import that failed later) is reused as is.
+pub async fn download(url: &str, dest: &Path, settings: &SdeDownloadSettings) -> Result<()> {
+    if fs::try_exists(dest).await.unwrap_or(false) {
+        if verify_zip(dest).await.is_ok() {
+            return Ok(());
+        }
+        fs::remove_file(dest).await.ok();
+    }
+
+    let client = reqwest::Client::new();
+    let (total_len, ranged) = probe(&client, url).await;
+    let connections = (settings.connections.max(1) as usize).min(MAX_CONNECTIONS);
+    let segments = match total_len {
+        Some(total_len) if ranged => split_segments(total_len, connections),
+        _ => vec![Segment {
+            start: 0,
+            end: total_len.map(|len| len.saturating_sub(1)),
+        }],
+    };
+    let manifest = prepare_manifest(
+        dest,
+        Manifest {
+            url: url.to_string(),
+            total_len,
+            segments,
+        },
+    )
+    .await?;
+
+    let max_retries = settings.max_retries.max(0) as u32;
+    let downloads = manifest
+        .segments
+        .iter()
+        .enumerate()
+        .map(|(index, segment)| {
+            download_segment(
+                &client,
+                url,
+                *segment,
+                part_path(dest, index),
+                ranged,
+                max_retries,
+            )
+        });
+    futures_util::future::try_join_all(downloads).await?;
+
+    // Parts that do not add up are discarded too, so the next attempt
+    // starts clean instead of failing the same way.
+    let joined = join_parts(dest, &manifest).await;
+    remove_parts(dest, &manifest).await;
+    joined?;
+
+    if let Err(e) = verify_zip(dest).await {
+        fs::remove_file(dest).await.ok();
+        return Err(e.context("downloaded SDE zip failed verification"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_into_contiguous_segments() {
+        let segments = split_segments(10, 3);
+        assert_eq!(
+            segments,
+            vec![
+                Segment {
+                    start: 0,
+                    end: Some(3)
+                },
+                Segment {
+                    start: 4,
+                    end: Some(7)
+                },
+                Segment {
+                    start: 8,
+                    end: Some(9)
+                },
+            ]
+        );
+        assert_eq!(segments.iter().filter_map(|s| s.len()).sum::<u64>(), 10);
+
+        assert_eq!(split_segments(2, 8).len(), 2);
+        assert_eq!(split_segments(100, 0).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn truncated_archive_fails_verification() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sde.zip");
+        std::fs::write(&path, b"PK\x03\x04 not really a zip").unwrap();
+        assert!(verify_zip(&path).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn checksum_accepts_prefixed_and_rejects_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sde.zip");
+        std::fs::write(&path, b"abc").unwrap();
+        let digest = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+        verify_checksum(&path, digest).await.unwrap();
+        verify_checksum(&path, &format!("sha256:{}", digest.to_uppercase()))
+            .await
+            .unwrap();
+        assert!(verify_checksum(&path, "00").await.is_err());
+    }
+}
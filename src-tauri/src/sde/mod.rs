@@ -4,17 +4,24 @@ use std::{
 };
 
 use anyhow::{Context, Result};
-use futures_util::StreamExt;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sqlx::{QueryBuilder, Row, Sqlite, SqliteConnection, SqlitePool};
 use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
 use tokio::{
     fs,
-    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    io::{AsyncBufReadExt, BufReader},
 };
+use typeshare::typeshare;
 use zip::ZipArchive;
 
+use crate::db::app_settings::SdeImportMode;
+use crate::storage::StorageLocation;
+use crate::ts_types::i64_ts;
+
+mod download;
+
 const LATEST_METADATA_URL: &str =
     "https://developers.eveonline.com/static-data/tranquility/latest.jsonl";
 const ZIP_URL_TEMPLATE: &str = "https://developers.eveonline.com/static-data/tranquility/eve-online-static-data-{build}-jsonl.zip";
@@ -27,6 +34,26 @@ const TARGET_FILES: &[&str] = &[
     "dogmaEffects.jsonl",
     "typeDogma.jsonl",
     "characterAttributes.jsonl",
+    "cloneGrades.jsonl",
+];
+
+/// Categories kept in [`SdeImportMode::Minimal`]: skills, and implants
+/// (boosters such as accelerators live in the implant category too).
+const MINIMAL_CATEGORY_IDS: &[i64] = &[16, 20];
+
+/// Tables reported by [`status`], in import order.
+const STATUS_TABLES: &[&str] = &[
+    "sde_categories",
+    "sde_groups",
+    "sde_types",
+    "sde_dogma_attributes",
+    "sde_dogma_effects",
+    "sde_type_dogma_attributes",
+    "sde_type_dogma_effects",
+    "sde_skill_requirements",
+    "sde_character_attributes",
+    "sde_alpha_skills",
+    "sde_required_for",
 ];
 
 type GroupInsertRow = (i64, Option<i64>, String, Option<i64>, bool);
@@ -72,6 +99,9 @@ struct LatestBuild {
     build_number: i64,
     #[serde(rename = "releaseDate")]
     release_date: String,
+    /// SHA-256 of the build's zip, when the feed publishes one.
+    #[serde(default, alias = "sha256")]
+    checksum: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -188,30 +218,55 @@ struct CharacterAttributeRow {
     icon_id: Option<i64>,
 }
 
-pub async fn ensure_latest(app: &AppHandle, pool: &SqlitePool) -> Result<()> {
-    ensure_latest_inner(app, pool, false).await
+#[derive(Debug, Deserialize)]
+struct CloneGradeRow {
+    #[serde(default)]
+    skills: Vec<CloneGradeSkill>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CloneGradeSkill {
+    #[serde(rename = "typeID")]
+    type_id: i64,
+    level: i64,
+}
+
+/// Imports the latest SDE build if it is not the one installed. `on_import`
+/// runs once the check has found a new build, before the download starts.
+pub async fn ensure_latest(
+    app: &AppHandle,
+    pool: &SqlitePool,
+    on_import: impl FnOnce(),
+) -> Result<()> {
+    ensure_latest_inner(app, pool, false, on_import).await
 }
 
 pub async fn force_refresh(app: &AppHandle, pool: &SqlitePool) -> Result<()> {
-    ensure_latest_inner(app, pool, true).await
+    ensure_latest_inner(app, pool, true, || {}).await
 }
 
-async fn ensure_latest_inner(app: &AppHandle, pool: &SqlitePool, force: bool) -> Result<()> {
+async fn ensure_latest_inner(
+    app: &AppHandle,
+    pool: &SqlitePool,
+    force: bool,
+    on_import: impl FnOnce(),
+) -> Result<()> {
     let latest = fetch_latest_build().await?;
+    let mode = crate::db::app_settings::get_sde_import_mode(pool)
+        .await
+        .unwrap_or_default();
 
     if !force {
-        if let Some(current) = current_build(pool).await? {
-            if current == latest.build_number {
+        if let Some((build, imported_mode)) = current_build(pool).await? {
+            if build == latest.build_number && imported_mode == mode.as_str() {
                 return Ok(());
             }
         }
     }
 
-    let sde_dir = app
-        .path()
-        .app_data_dir()
-        .context("failed to resolve app data directory")?
-        .join("sde");
+    on_import();
+
+    let sde_dir = app.state::<StorageLocation>().sde_dir();
 
     fs::create_dir_all(&sde_dir)
         .await
@@ -222,11 +277,36 @@ async fn ensure_latest_inner(app: &AppHandle, pool: &SqlitePool, force: bool) ->
         latest.build_number
     ));
 
-    download_zip(&latest, &zip_path).await?;
+    let url = ZIP_URL_TEMPLATE.replace("{build}", &latest.build_number.to_string());
+    let settings = crate::db::app_settings::get_sde_download_settings(pool)
+        .await
+        .unwrap_or_default();
+    download::download(&url, &zip_path, &settings).await?;
+
+    if let Some(expected) = latest.checksum.as_deref() {
+        if let Err(e) = download::verify_checksum(&zip_path, expected).await {
+            // A mismatched archive is truncated or tampered with; never import
+            // it, and drop it so the next attempt downloads afresh.
+            fs::remove_file(&zip_path).await.ok();
+            notify_import_refused(app);
+            return Err(e);
+        }
+    }
 
     let extracted_paths = extract_selected_files(&zip_path, &sde_dir).await?;
 
-    import_from_files(pool, &extracted_paths, &latest).await?;
+    import_from_files(pool, &extracted_paths, &latest, mode).await?;
+
+    if let Err(e) = crate::db::activity_feed::record_activity(
+        pool,
+        None,
+        crate::db::activity_feed::ACTIVITY_SDE_UPDATED,
+        &format!("SDE updated to build {}", latest.build_number),
+    )
+    .await
+    {
+        eprintln!("Failed to record SDE update activity: {}", e);
+    }
 
     // Clean up temporary files after successful import
     fs::remove_file(&zip_path).await.ok();
@@ -237,6 +317,83 @@ async fn ensure_latest_inner(app: &AppHandle, pool: &SqlitePool, force: bool) ->
     Ok(())
 }
 
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct SdeTableCount {
+    pub table: String,
+    pub rows: i64_ts,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct SdeStatus {
+    /// `None` when no build has been imported yet.
+    pub build_number: Option<i64_ts>,
+    pub release_date: Option<String>,
+    /// Unix seconds.
+    pub imported_at: Option<i64_ts>,
+    pub import_mode: Option<String>,
+    pub tables: Vec<SdeTableCount>,
+    /// `None` when the latest build could not be fetched.
+    pub latest_build_number: Option<i64_ts>,
+    pub update_available: Option<bool>,
+}
+
+/// What is imported, and whether the published feed has a newer build. The
+/// feed is only consulted here; an unreachable feed is not an error.
+pub async fn status(pool: &SqlitePool) -> Result<SdeStatus> {
+    let metadata = sqlx::query_as::<Sqlite, (i64, Option<String>, i64, String)>(
+        "SELECT build_number, release_date, imported_at, import_mode FROM sde_metadata LIMIT 1",
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    let mut tables = Vec::with_capacity(STATUS_TABLES.len());
+    for table in STATUS_TABLES {
+        // Table names come from the fixed STATUS_TABLES list.
+        let sql = format!("SELECT COUNT(*) FROM {}", table);
+        let rows: i64 = sqlx::query_scalar(sqlx::AssertSqlSafe(sql.as_str()))
+            .fetch_one(pool)
+            .await?;
+        tables.push(SdeTableCount {
+            table: (*table).to_string(),
+            rows,
+        });
+    }
+
+    let latest_build_number = match fetch_latest_build().await {
+        Ok(latest) => Some(latest.build_number),
+        Err(e) => {
+            eprintln!("Failed to fetch latest SDE build: {}", e);
+            None
+        }
+    };
+    let build_number = metadata.as_ref().map(|m| m.0);
+    let update_available = latest_build_number.map(|latest| build_number != Some(latest));
+
+    Ok(SdeStatus {
+        build_number,
+        release_date: metadata.as_ref().and_then(|m| m.1.clone()),
+        imported_at: metadata.as_ref().map(|m| m.2),
+        import_mode: metadata.map(|m| m.3),
+        tables,
+        latest_build_number,
+        update_available,
+    })
+}
+
+fn notify_import_refused(app: &AppHandle) {
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("Game data update refused")
+        .body("The downloaded SDE did not match its published checksum and was discarded.")
+        .show()
+    {
+        eprintln!("Failed to send system notification: {}", e);
+    }
+}
+
 async fn fetch_latest_build() -> Result<LatestBuild> {
     let response = reqwest::get(LATEST_METADATA_URL).await?;
     if !response.status().is_success() {
@@ -248,35 +405,29 @@ async fn fetch_latest_build() -> Result<LatestBuild> {
     Ok(build)
 }
 
-async fn current_build(pool: &SqlitePool) -> Result<Option<i64>> {
-    let row = sqlx::query::<Sqlite>("SELECT build_number FROM sde_metadata LIMIT 1")
-        .fetch_optional(pool)
-        .await?;
-    Ok(row.map(|r| r.get::<i64, _>(0)))
-}
-
-async fn download_zip(latest: &LatestBuild, zip_path: &Path) -> Result<()> {
-    let url = ZIP_URL_TEMPLATE.replace("{build}", &latest.build_number.to_string());
-    let response = reqwest::get(&url).await?;
-
-    if !response.status().is_success() {
-        anyhow::bail!("failed to download SDE zip {}: {}", url, response.status());
-    }
-
-    let mut stream = response.bytes_stream();
-    let mut file = fs::File::create(zip_path)
-        .await
-        .with_context(|| format!("failed to create {}", zip_path.display()))?;
-
-    while let Some(chunk) = stream.next().await {
-        let data = chunk?;
-        file.write_all(&data).await?;
+/// Returned by features that need ships and items when only a minimal SDE is
+/// imported.
+pub const REQUIRES_FULL_SDE: &str =
+    "This requires the full SDE; switch the SDE import mode to Full in Settings";
+
+/// Fails with [`REQUIRES_FULL_SDE`] when the imported build is a minimal one,
+/// so fittings and ship goals say why instead of coming back empty.
+pub async fn require_full_import(pool: &SqlitePool) -> Result<()> {
+    let mode = current_build(pool).await?.map(|(_, mode)| mode);
+    if mode.as_deref() == Some(SdeImportMode::Minimal.as_str()) {
+        anyhow::bail!(REQUIRES_FULL_SDE);
     }
-
-    file.flush().await?;
     Ok(())
 }
 
+/// The imported build number and the mode it was imported with.
+async fn current_build(pool: &SqlitePool) -> Result<Option<(i64, String)>> {
+    let row = sqlx::query::<Sqlite>("SELECT build_number, import_mode FROM sde_metadata LIMIT 1")
+        .fetch_optional(pool)
+        .await?;
+    Ok(row.map(|r| (r.get::<i64, _>(0), r.get::<String, _>(1))))
+}
+
 async fn extract_selected_files(
     zip_path: &Path,
     output_dir: &Path,
@@ -317,18 +468,21 @@ async fn extract_selected_files(
 pub async fn import_from_files_for_test(
     pool: &SqlitePool,
     files: &HashMap<String, PathBuf>,
+    mode: SdeImportMode,
 ) -> Result<()> {
     let latest = LatestBuild {
         build_number: 0,
         release_date: "test".to_string(),
+        checksum: None,
     };
-    import_from_files(pool, files, &latest).await
+    import_from_files(pool, files, &latest, mode).await
 }
 
 async fn import_from_files(
     pool: &SqlitePool,
     files: &HashMap<String, PathBuf>,
     latest: &LatestBuild,
+    mode: SdeImportMode,
 ) -> Result<()> {
     let categories = files
         .get("categories.jsonl")
@@ -351,6 +505,9 @@ async fn import_from_files(
     let character_attributes = files
         .get("characterAttributes.jsonl")
         .context("characterAttributes.jsonl path missing")?;
+    let clone_grades = files
+        .get("cloneGrades.jsonl")
+        .context("cloneGrades.jsonl path missing")?;
 
     let mut tx = pool.begin().await?;
 
@@ -365,9 +522,12 @@ async fn import_from_files(
     import_groups(&mut tx, groups)
         .await
         .context("failed to import groups")?;
-    import_types(&mut tx, types)
+    import_types(&mut tx, types, mode)
         .await
         .context("failed to import types")?;
+    rebuild_type_search_index(&mut tx)
+        .await
+        .context("failed to index types")?;
     import_dogma_attributes(&mut tx, dogma_attributes)
         .await
         .context("failed to import dogma attributes")?;
@@ -377,10 +537,16 @@ async fn import_from_files(
     import_type_dogma(&mut tx, type_dogma)
         .await
         .context("failed to import type dogma")?;
+    rebuild_required_for(&mut tx)
+        .await
+        .context("failed to index skill requirements")?;
     import_character_attributes(&mut tx, character_attributes)
         .await
         .context("failed to import character attributes")?;
-    upsert_metadata(&mut tx, latest)
+    import_alpha_skills(&mut tx, clone_grades)
+        .await
+        .context("failed to import clone grades")?;
+    upsert_metadata(&mut tx, latest, mode)
         .await
         .context("failed to update metadata")?;
 
@@ -389,6 +555,9 @@ async fn import_from_files(
 }
 
 async fn clear_tables(conn: &mut SqliteConnection) -> Result<()> {
+    sqlx::query::<Sqlite>("DELETE FROM sde_required_for")
+        .execute(&mut *conn)
+        .await?;
     sqlx::query::<Sqlite>("DELETE FROM sde_skill_requirements")
         .execute(&mut *conn)
         .await?;
@@ -416,6 +585,9 @@ async fn clear_tables(conn: &mut SqliteConnection) -> Result<()> {
     sqlx::query::<Sqlite>("DELETE FROM sde_character_attributes")
         .execute(&mut *conn)
         .await?;
+    sqlx::query::<Sqlite>("DELETE FROM sde_alpha_skills")
+        .execute(&mut *conn)
+        .await?;
     sqlx::query::<Sqlite>("DELETE FROM sde_metadata")
         .execute(&mut *conn)
         .await?;
@@ -531,7 +703,17 @@ async fn insert_groups(conn: &mut SqliteConnection, rows: &[GroupInsertRow]) ->
     Ok(())
 }
 
-async fn import_types(conn: &mut SqliteConnection, path: &Path) -> Result<()> {
+async fn import_types(conn: &mut SqliteConnection, path: &Path, mode: SdeImportMode) -> Result<()> {
+    // types.jsonl does not always carry categoryID, so resolve it through
+    // the groups imported just before.
+    let group_categories: HashMap<i64, i64> = sqlx::query_as::<Sqlite, (i64, i64)>(
+        "SELECT group_id, category_id FROM sde_groups WHERE category_id IS NOT NULL",
+    )
+    .fetch_all(&mut *conn)
+    .await?
+    .into_iter()
+    .collect();
+
     let file = fs::File::open(path).await?;
     let reader = BufReader::new(file);
     let mut lines = reader.lines();
@@ -543,6 +725,12 @@ async fn import_types(conn: &mut SqliteConnection, path: &Path) -> Result<()> {
         if !row.published.unwrap_or(false) {
             continue;
         }
+        let category_id = row
+            .category_id
+            .or_else(|| group_categories.get(&row.group_id).copied());
+        if !keeps_category(mode, category_id) {
+            continue;
+        }
         let name = extract_text(row.name).unwrap_or_default();
         let description = extract_text(row.description);
 
@@ -900,12 +1088,93 @@ async fn insert_character_attributes(
     Ok(())
 }
 
-async fn upsert_metadata(conn: &mut SqliteConnection, latest: &LatestBuild) -> Result<()> {
+/// Every Alpha grade (one per race) lists the same skills; the highest level
+/// across grades is kept in case a future grade differs.
+async fn import_alpha_skills(conn: &mut SqliteConnection, path: &Path) -> Result<()> {
+    let file = fs::File::open(path).await?;
+    let reader = BufReader::new(file);
+    let mut lines = reader.lines();
+    let mut max_levels: HashMap<i64, i64> = HashMap::new();
+
+    while let Some(line) = lines.next_line().await? {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let row: CloneGradeRow = serde_json::from_str(line)?;
+        for skill in row.skills {
+            let level = max_levels.entry(skill.type_id).or_insert(0);
+            *level = (*level).max(skill.level);
+        }
+    }
+
+    let rows: Vec<(i64, i64)> = max_levels.into_iter().collect();
+    for chunk in rows.chunks(256) {
+        let mut builder =
+            QueryBuilder::<Sqlite>::new("INSERT INTO sde_alpha_skills (type_id, max_level) ");
+        builder.push_values(chunk.iter(), |mut b, row| {
+            b.push_bind(row.0).push_bind(row.1);
+        });
+        builder.build().execute(&mut *conn).await?;
+    }
+
+    Ok(())
+}
+
+/// `sde_types_fts` only mirrors `sde_types`, so it is rebuilt wholesale once
+/// the types are in.
+async fn rebuild_type_search_index(conn: &mut SqliteConnection) -> Result<()> {
+    sqlx::query("INSERT INTO sde_types_fts (sde_types_fts) VALUES ('rebuild')")
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// `sde_required_for` is derived from the requirements, types, groups and
+/// categories; same query as in migration 039.
+async fn rebuild_required_for(conn: &mut SqliteConnection) -> Result<()> {
     sqlx::query(
-        "INSERT INTO sde_metadata (build_number, release_date, imported_at) VALUES (?, ?, strftime('%s','now'))",
+        "INSERT INTO sde_required_for
+           (required_skill_id, type_id, required_level, category_id, category_name, group_id, group_name)
+         SELECT
+           sr.required_skill_id,
+           sr.skill_type_id,
+           MAX(sr.required_level),
+           COALESCE(t.category_id, g.category_id),
+           COALESCE(c.name, cg.name),
+           t.group_id,
+           g.name
+         FROM sde_skill_requirements sr
+         JOIN sde_types t ON sr.skill_type_id = t.type_id
+         LEFT JOIN sde_groups g ON t.group_id = g.group_id
+         LEFT JOIN sde_categories c ON t.category_id = c.category_id
+         LEFT JOIN sde_categories cg ON g.category_id = cg.category_id
+         WHERE t.published = 1
+         GROUP BY sr.required_skill_id, sr.skill_type_id",
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+fn keeps_category(mode: SdeImportMode, category_id: Option<i64>) -> bool {
+    match mode {
+        SdeImportMode::Full => true,
+        SdeImportMode::Minimal => category_id.is_some_and(|c| MINIMAL_CATEGORY_IDS.contains(&c)),
+    }
+}
+
+async fn upsert_metadata(
+    conn: &mut SqliteConnection,
+    latest: &LatestBuild,
+    mode: SdeImportMode,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO sde_metadata (build_number, release_date, imported_at, import_mode) VALUES (?, ?, strftime('%s','now'), ?)",
     )
     .bind(latest.build_number)
     .bind(&latest.release_date)
+    .bind(mode.as_str())
     .execute(conn)
     .await?;
     Ok(())
@@ -922,3 +1191,67 @@ fn extract_text(value: Option<Value>) -> Option<String> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimal_mode_keeps_only_skills_and_implants() {
+        assert!(keeps_category(SdeImportMode::Full, Some(6)));
+        assert!(keeps_category(SdeImportMode::Full, None));
+        assert!(keeps_category(SdeImportMode::Minimal, Some(16)));
+        assert!(keeps_category(SdeImportMode::Minimal, Some(20)));
+        assert!(!keeps_category(SdeImportMode::Minimal, Some(6)));
+        assert!(!keeps_category(SdeImportMode::Minimal, None));
+    }
+
+    #[tokio::test]
+    async fn minimal_import_is_refused_by_full_sde_features() {
+        let db = crate::testdata::TestDb::new().await.unwrap();
+        require_full_import(&db.pool).await.unwrap();
+
+        let latest = LatestBuild {
+            build_number: 1,
+            release_date: "2026-01-01".to_string(),
+            checksum: None,
+        };
+        let mut conn = db.pool.acquire().await.unwrap();
+        upsert_metadata(&mut conn, &latest, SdeImportMode::Minimal)
+            .await
+            .unwrap();
+        drop(conn);
+
+        let err = require_full_import(&db.pool).await.unwrap_err();
+        assert_eq!(err.to_string(), REQUIRES_FULL_SDE);
+    }
+
+    #[tokio::test]
+    async fn required_for_index_matches_live_requirements() {
+        const SPACESHIP_COMMAND: i64 = 3327;
+        let db = crate::testdata::TestDb::new_with_sde().await.unwrap();
+
+        let indexed: Vec<(i64, i64)> = sqlx::query_as(
+            "SELECT type_id, required_level FROM sde_required_for
+             WHERE required_skill_id = ? ORDER BY type_id",
+        )
+        .bind(SPACESHIP_COMMAND)
+        .fetch_all(&db.pool)
+        .await
+        .unwrap();
+        let live: Vec<(i64, i64)> = sqlx::query_as(
+            "SELECT sr.skill_type_id, MAX(sr.required_level)
+             FROM sde_skill_requirements sr
+             JOIN sde_types t ON sr.skill_type_id = t.type_id
+             WHERE sr.required_skill_id = ? AND t.published = 1
+             GROUP BY sr.skill_type_id ORDER BY sr.skill_type_id",
+        )
+        .bind(SPACESHIP_COMMAND)
+        .fetch_all(&db.pool)
+        .await
+        .unwrap();
+
+        assert!(!indexed.is_empty());
+        assert_eq!(indexed, live);
+    }
+}
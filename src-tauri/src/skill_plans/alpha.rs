@@ -0,0 +1,250 @@
+//! What an Alpha clone cannot train. The halved training rate lives in
+//! `utils::calculate_sp_per_minute`; this covers the other two limits: skills
+//! capped below the planned level (or not trainable at all) by the SDE's
+//! clone grades, and the ceiling on total skill points.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+use typeshare::typeshare;
+
+use crate::db;
+use crate::db::skill_plans::SkillPlanEntry;
+use crate::ts_types::i64_ts;
+
+/// Alphas stop training once the character holds this many skill points.
+pub const ALPHA_SP_CAP: i64 = 5_000_000;
+
+/// The clone a projection trains under. Plans viewed without a character are
+/// projected as Omega.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloneState {
+    Omega,
+    Alpha {
+        /// Skill points the character holds now.
+        total_sp: i64,
+    },
+}
+
+impl CloneState {
+    pub async fn for_character(pool: &db::Pool, character_id: Option<i64>) -> anyhow::Result<Self> {
+        let Some(character_id) = character_id else {
+            return Ok(Self::Omega);
+        };
+        let character = db::get_character(pool, character_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Character {} not found", character_id))?;
+        if character.is_omega {
+            return Ok(Self::Omega);
+        }
+        let total_sp = db::get_character_skills(pool, character_id)
+            .await?
+            .iter()
+            .map(|s| s.skillpoints_in_skill)
+            .sum();
+        Ok(Self::Alpha { total_sp })
+    }
+
+    pub fn is_omega(self) -> bool {
+        self == Self::Omega
+    }
+
+    /// SP the clone can still train; `None` when unlimited.
+    pub fn sp_room(self) -> Option<i64> {
+        match self {
+            Self::Omega => None,
+            Self::Alpha { total_sp } => Some((ALPHA_SP_CAP - total_sp).max(0)),
+        }
+    }
+}
+
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OmegaOnlyEntry {
+    pub entry_id: i64_ts,
+    pub skill_type_id: i64_ts,
+    pub planned_level: i64_ts,
+    /// Highest level an Alpha can train; 0 for Omega-only skills.
+    pub alpha_max_level: i64_ts,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AlphaPlanWarnings {
+    /// Entries planned above what an Alpha can train.
+    pub omega_only_entries: Vec<OmegaOnlyEntry>,
+    /// First entry that would take the character past [`ALPHA_SP_CAP`]; it
+    /// and every later entry cannot be trained as Alpha.
+    pub sp_cap_entry_id: Option<i64_ts>,
+}
+
+impl AlphaPlanWarnings {
+    pub fn is_empty(&self) -> bool {
+        self.omega_only_entries.is_empty() && self.sp_cap_entry_id.is_none()
+    }
+
+    /// Whether an Alpha can train `entries[index]`.
+    pub fn blocks(&self, entries: &[SkillPlanEntry], index: usize) -> bool {
+        let entry_id = entries[index].entry_id;
+        let past_cap = self
+            .sp_cap_entry_id
+            .and_then(|cap_id| entries.iter().position(|e| e.entry_id == cap_id))
+            .is_some_and(|cap_index| index >= cap_index);
+        past_cap
+            || self
+                .omega_only_entries
+                .iter()
+                .any(|e| e.entry_id == entry_id)
+    }
+}
+
+pub fn omega_only_entries(
+    entries: &[SkillPlanEntry],
+    alpha_caps: &HashMap<i64, i64>,
+) -> Vec<OmegaOnlyEntry> {
+    entries
+        .iter()
+        .filter(|e| !e.excluded)
+        .filter_map(|e| {
+            let alpha_max_level = alpha_caps.get(&e.skill_type_id).copied().unwrap_or(0);
+            (e.planned_level > alpha_max_level).then_some(OmegaOnlyEntry {
+                entry_id: e.entry_id,
+                skill_type_id: e.skill_type_id,
+                planned_level: e.planned_level,
+                alpha_max_level,
+            })
+        })
+        .collect()
+}
+
+/// Entries an Alpha cannot train: skills flagged `canNotBeTrainedOnTrial`
+/// (`omega_only_skills`) and, once clone grades are imported, levels above
+/// their cap.
+pub fn alpha_blocked_entries(
+    entries: &[SkillPlanEntry],
+    alpha_caps: &HashMap<i64, i64>,
+    omega_only_skills: &HashSet<i64>,
+) -> Vec<OmegaOnlyEntry> {
+    let mut blocked = if alpha_caps.is_empty() {
+        Vec::new()
+    } else {
+        omega_only_entries(entries, alpha_caps)
+    };
+    for entry in entries.iter().filter(|e| !e.excluded) {
+        if omega_only_skills.contains(&entry.skill_type_id)
+            && !blocked.iter().any(|b| b.entry_id == entry.entry_id)
+        {
+            blocked.push(OmegaOnlyEntry {
+                entry_id: entry.entry_id,
+                skill_type_id: entry.skill_type_id,
+                planned_level: entry.planned_level,
+                alpha_max_level: 0,
+            });
+        }
+    }
+    blocked.sort_by_key(|b| {
+        entries
+            .iter()
+            .position(|e| e.entry_id == b.entry_id)
+            .unwrap_or(usize::MAX)
+    });
+    blocked
+}
+
+/// `missing_sp[i]` is the SP `entries[i]` still needs; `total_sp` is what the
+/// character holds now. Omega-only entries are skipped when adding up SP, as
+/// an Alpha never trains them.
+pub fn plan_warnings(
+    entries: &[SkillPlanEntry],
+    missing_sp: &[i64],
+    alpha_caps: &HashMap<i64, i64>,
+    total_sp: i64,
+) -> AlphaPlanWarnings {
+    let omega_only_entries = omega_only_entries(entries, alpha_caps);
+    let mut trained = total_sp;
+    let mut sp_cap_entry_id = None;
+    for (entry, &missing) in entries.iter().zip(missing_sp) {
+        if omega_only_entries
+            .iter()
+            .any(|o| o.entry_id == entry.entry_id)
+        {
+            continue;
+        }
+        trained += missing;
+        if missing > 0 && trained > ALPHA_SP_CAP {
+            sp_cap_entry_id = Some(entry.entry_id);
+            break;
+        }
+    }
+    AlphaPlanWarnings {
+        omega_only_entries,
+        sp_cap_entry_id,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(entry_id: i64, skill_type_id: i64, planned_level: i64) -> SkillPlanEntry {
+        SkillPlanEntry {
+            entry_id,
+            plan_id: 1,
+            skill_type_id,
+            planned_level,
+            sort_order: entry_id,
+            entry_type: "Planned".to_string(),
+            notes: None,
+            excluded: false,
+            priority: crate::db::skill_plans::DEFAULT_ENTRY_PRIORITY,
+        }
+    }
+
+    #[test]
+    fn flags_capped_skills_and_the_entry_crossing_the_sp_cap() {
+        let entries = vec![
+            entry(1, 3300, 4),
+            entry(2, 3300, 5),
+            entry(3, 9999, 1),
+            entry(4, 3301, 3),
+            entry(5, 3301, 4),
+        ];
+        let caps = HashMap::from([(3300, 4), (3301, 4)]);
+        let missing = [100_000, 200_000, 1_000, 40_000, 80_000];
+
+        let warnings = plan_warnings(&entries, &missing, &caps, 4_850_000);
+
+        let omega_only: Vec<(i64, i64)> = warnings
+            .omega_only_entries
+            .iter()
+            .map(|e| (e.entry_id, e.alpha_max_level))
+            .collect();
+        assert_eq!(omega_only, vec![(2, 4), (3, 0)]);
+        // 4.85M + 100k + 40k stays under the cap; the next 80k crosses it.
+        assert_eq!(warnings.sp_cap_entry_id, Some(5));
+        assert!(warnings.blocks(&entries, 1));
+        assert!(!warnings.blocks(&entries, 3));
+        assert!(warnings.blocks(&entries, 4));
+
+        assert!(plan_warnings(&entries[..1], &missing[..1], &caps, 0).is_empty());
+    }
+
+    #[test]
+    fn dogma_flag_blocks_entries_without_clone_grades() {
+        let entries = vec![entry(1, 3300, 1), entry(2, 3301, 2), entry(3, 3300, 5)];
+        let omega_only = HashSet::from([3301]);
+
+        let blocked: Vec<i64> = alpha_blocked_entries(&entries, &HashMap::new(), &omega_only)
+            .iter()
+            .map(|b| b.entry_id)
+            .collect();
+        assert_eq!(blocked, vec![2]);
+
+        let caps = HashMap::from([(3300, 4)]);
+        let blocked: Vec<i64> = alpha_blocked_entries(&entries, &caps, &omega_only)
+            .iter()
+            .map(|b| b.entry_id)
+            .collect();
+        assert_eq!(blocked, vec![2, 3]);
+    }
+}
@@ -0,0 +1,71 @@
+//! Plan text for pasting into the EVE client: the skill queue's "import from
+//! clipboard" and the market's multibuy window.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClipboardFormat {
+    /// One "Skill Name Level" line per level, in training order.
+    SkillQueue,
+    /// One "Skill Name<TAB>1" line per skillbook.
+    Multibuy,
+}
+
+fn skill_name(names: &HashMap<i64, String>, skill_type_id: i64) -> String {
+    names
+        .get(&skill_type_id)
+        .cloned()
+        .unwrap_or_else(|| format!("Unknown Skill ({})", skill_type_id))
+}
+
+/// `levels` as `(skill_type_id, level)` in training order. Levels stay arabic
+/// whatever the level format setting; the client does not read roman ones.
+pub fn to_text(
+    levels: &[(i64, i64)],
+    names: &HashMap<i64, String>,
+    format: ClipboardFormat,
+) -> String {
+    match format {
+        ClipboardFormat::SkillQueue => levels
+            .iter()
+            .map(|(skill_type_id, level)| {
+                format!("{} {}", skill_name(names, *skill_type_id), level)
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        ClipboardFormat::Multibuy => {
+            let mut seen = HashSet::new();
+            levels
+                .iter()
+                .filter(|(skill_type_id, _)| seen.insert(*skill_type_id))
+                .map(|(skill_type_id, _)| format!("{}\t1", skill_name(names, *skill_type_id)))
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_queue_lines_and_one_book_per_skill() {
+        let names = HashMap::from([(3327, "Spaceship Command".to_string())]);
+        let levels = [(3327, 1), (3327, 2), (3300, 1)];
+
+        assert_eq!(
+            to_text(&levels, &names, ClipboardFormat::SkillQueue),
+            "Spaceship Command 1\nSpaceship Command 2\nUnknown Skill (3300) 1"
+        );
+        assert_eq!(
+            to_text(&levels, &names, ClipboardFormat::Multibuy),
+            "Spaceship Command\t1\nUnknown Skill (3300)\t1"
+        );
+    }
+}
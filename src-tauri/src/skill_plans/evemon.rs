@@ -0,0 +1,510 @@
+//! Import of an EVEMon `settings.xml` backup: every character's plans plus the
+//! characters' display details, so a whole EVEMon install can be brought over
+//! in one step.
+//!
+//! The parts of the file that are read look like:
+//!
+//! ```text
+//! <Settings>
+//!   <characters>
+//!     <ccp guid="…" id="90000001" name="Pilot">
+//!       <corporationName>…</corporationName>
+//!       <allianceName>…</allianceName>
+//!     </ccp>
+//!   </characters>
+//!   <plans>
+//!     <plan name="Gunnery" owner="…guid…">
+//!       <description>…</description>
+//!       <entry skillID="3300" skill="Gunnery" level="3" priority="1" type="Planned">
+//!         <notes>…</notes>
+//!       </entry>
+//!     </plan>
+//!   </plans>
+//! </Settings>
+//! ```
+
+use std::collections::HashSet;
+
+use anyhow::{bail, Context, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::reader::Reader;
+use serde::Serialize;
+use typeshare::typeshare;
+
+use crate::ts_types::{i64_ts, usize_ts};
+use crate::{db, utils};
+
+const UNKNOWN_OWNER_FOLDER: &str = "Unknown character";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EvemonCharacter {
+    pub guid: String,
+    pub character_id: Option<i64>,
+    pub name: String,
+    pub corporation_name: Option<String>,
+    pub alliance_name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvemonPlanEntry {
+    pub skill_id: i64,
+    pub skill_name: String,
+    pub level: i64,
+    pub priority: i64,
+    pub entry_type: String,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EvemonPlan {
+    pub name: String,
+    pub owner: String,
+    pub description: Option<String>,
+    pub entries: Vec<EvemonPlanEntry>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EvemonSettings {
+    pub characters: Vec<EvemonCharacter>,
+    pub plans: Vec<EvemonPlan>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct EvemonImportedCharacter {
+    pub character_id: Option<i64_ts>,
+    pub name: String,
+    pub corporation_name: Option<String>,
+    pub alliance_name: Option<String>,
+    pub group_id: Option<i64_ts>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct EvemonImportedPlan {
+    pub plan_id: i64_ts,
+    pub name: String,
+    pub character_name: Option<String>,
+    pub entry_count: usize_ts,
+    /// Entries dropped because the skill is not in the SDE or the level is
+    /// out of range, as "Skill name level".
+    pub skipped_entries: Vec<String>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct EvemonImportResult {
+    pub group_id: i64_ts,
+    pub characters: Vec<EvemonImportedCharacter>,
+    pub plans: Vec<EvemonImportedPlan>,
+}
+
+/// Collects element text across text and entity-reference events; the raw
+/// markup is unescaped once the element closes.
+#[derive(Default)]
+struct TextCapture {
+    raw: String,
+}
+
+impl TextCapture {
+    fn push(&mut self, bytes: &[u8]) -> Result<()> {
+        self.raw
+            .push_str(std::str::from_utf8(bytes).context("Invalid UTF-8 in XML")?);
+        Ok(())
+    }
+
+    fn push_reference(&mut self, bytes: &[u8]) -> Result<()> {
+        self.raw.push('&');
+        self.push(bytes)?;
+        self.raw.push(';');
+        Ok(())
+    }
+
+    fn take(&mut self) -> Result<Option<String>> {
+        let raw = std::mem::take(&mut self.raw);
+        let text = quick_xml::escape::unescape(raw.trim())
+            .context("Failed to unescape XML text")?
+            .into_owned();
+        Ok((!text.is_empty()).then_some(text))
+    }
+}
+
+fn attribute(element: &BytesStart, name: &[u8]) -> Result<Option<String>> {
+    for attr in element.attributes().flatten() {
+        if attr.key.as_ref() == name {
+            let value = std::str::from_utf8(&attr.value).context("Invalid UTF-8 in XML")?;
+            let value =
+                quick_xml::escape::unescape(value).context("Failed to unescape XML attribute")?;
+            return Ok(Some(value.into_owned()));
+        }
+    }
+    Ok(None)
+}
+
+fn parse_character(element: &BytesStart) -> Result<EvemonCharacter> {
+    Ok(EvemonCharacter {
+        guid: attribute(element, b"guid")?.unwrap_or_default(),
+        character_id: attribute(element, b"id")?.and_then(|id| id.parse().ok()),
+        name: attribute(element, b"name")?.unwrap_or_default(),
+        ..Default::default()
+    })
+}
+
+fn parse_entry(element: &BytesStart) -> Result<Option<EvemonPlanEntry>> {
+    let skill_id = attribute(element, b"skillID")?.and_then(|v| v.parse::<i64>().ok());
+    let level = attribute(element, b"level")?.and_then(|v| v.parse::<i64>().ok());
+    let (Some(skill_id), Some(level)) = (skill_id, level) else {
+        return Ok(None);
+    };
+    Ok(Some(EvemonPlanEntry {
+        skill_id,
+        skill_name: attribute(element, b"skill")?.unwrap_or_default(),
+        level,
+        priority: attribute(element, b"priority")?
+            .and_then(|v| v.parse().ok())
+            .filter(|p| db::skill_plans::ENTRY_PRIORITIES.contains(p))
+            .unwrap_or(db::skill_plans::DEFAULT_ENTRY_PRIORITY),
+        entry_type: match attribute(element, b"type")?.as_deref() {
+            Some(db::skill_plans::ENTRY_TYPE_PREREQUISITE) => {
+                db::skill_plans::ENTRY_TYPE_PREREQUISITE.to_string()
+            }
+            _ => db::skill_plans::ENTRY_TYPE_PLANNED.to_string(),
+        },
+        notes: None,
+    }))
+}
+
+pub fn parse_settings(xml: &str) -> Result<EvemonSettings> {
+    let mut reader = Reader::from_str(xml);
+    let mut buf = Vec::new();
+
+    let mut settings = EvemonSettings::default();
+    let mut path: Vec<Vec<u8>> = Vec::new();
+    let mut text = TextCapture::default();
+    let mut character: Option<EvemonCharacter> = None;
+    let mut plan: Option<EvemonPlan> = None;
+    let mut entry: Option<EvemonPlanEntry> = None;
+    let mut saw_settings = false;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => {
+                let name = e.name().as_ref().to_vec();
+                let parent = path.last().map(Vec::as_slice);
+                match (parent, name.as_slice()) {
+                    (None, b"Settings") => saw_settings = true,
+                    (Some(b"characters"), b"ccp" | b"uri") => {
+                        character = Some(parse_character(&e)?);
+                    }
+                    (Some(b"plans"), b"plan") => {
+                        plan = Some(EvemonPlan {
+                            name: attribute(&e, b"name")?.unwrap_or_default(),
+                            owner: attribute(&e, b"owner")?.unwrap_or_default(),
+                            ..Default::default()
+                        });
+                    }
+                    (Some(b"plan"), b"entry") => entry = parse_entry(&e)?,
+                    _ => {}
+                }
+                text = TextCapture::default();
+                path.push(name);
+            }
+            Ok(Event::Empty(e)) => {
+                let parent = path.last().map(Vec::as_slice);
+                match (parent, e.name().as_ref()) {
+                    (Some(b"characters"), b"ccp" | b"uri") => {
+                        settings.characters.push(parse_character(&e)?);
+                    }
+                    (Some(b"plan"), b"entry") => {
+                        if let (Some(plan), Some(entry)) = (plan.as_mut(), parse_entry(&e)?) {
+                            plan.entries.push(entry);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Text(e)) => text.push(&e)?,
+            Ok(Event::GeneralRef(e)) => text.push_reference(&e)?,
+            Ok(Event::End(_)) => {
+                let name = path.pop().unwrap_or_default();
+                let parent = path.last().map(Vec::as_slice);
+                match (parent, name.as_slice()) {
+                    (Some(b"characters"), b"ccp" | b"uri") => {
+                        settings.characters.extend(character.take());
+                    }
+                    (Some(b"ccp" | b"uri"), field) => {
+                        if let Some(character) = character.as_mut() {
+                            match field {
+                                b"name" if character.name.is_empty() => {
+                                    character.name = text.take()?.unwrap_or_default();
+                                }
+                                b"corporationName" => character.corporation_name = text.take()?,
+                                b"allianceName" => character.alliance_name = text.take()?,
+                                _ => {}
+                            }
+                        }
+                    }
+                    (Some(b"plans"), b"plan") => settings.plans.extend(plan.take()),
+                    (Some(b"plan"), b"description") => {
+                        if let Some(plan) = plan.as_mut() {
+                            plan.description = text.take()?;
+                        }
+                    }
+                    (Some(b"plan"), b"entry") => {
+                        if let (Some(plan), Some(entry)) = (plan.as_mut(), entry.take()) {
+                            plan.entries.push(entry);
+                        }
+                    }
+                    (Some(b"entry"), b"notes") => {
+                        if let Some(entry) = entry.as_mut() {
+                            entry.notes = text.take()?;
+                        }
+                    }
+                    _ => {}
+                }
+                text = TextCapture::default();
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => bail!("XML parsing error: {}", e),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if !saw_settings {
+        bail!("Not an EVEMon settings file (no <Settings> root element)");
+    }
+    Ok(settings)
+}
+
+/// Create a folder named after `source_name` holding one subfolder per
+/// character, each with that character's plans. Everything is written in one
+/// transaction; entries whose skill is unknown to the SDE are skipped and
+/// reported per plan.
+pub async fn import(pool: &db::Pool, source_name: &str, xml: &str) -> Result<EvemonImportResult> {
+    let settings = parse_settings(xml)?;
+    if settings.plans.is_empty() {
+        bail!("The settings file contains no plans");
+    }
+    let level_format = db::app_settings::get_level_format(pool).await?;
+
+    let mut skill_ids: Vec<i64> = settings
+        .plans
+        .iter()
+        .flat_map(|p| p.entries.iter().map(|e| e.skill_id))
+        .collect();
+    skill_ids.sort_unstable();
+    skill_ids.dedup();
+    let known_skills = utils::get_type_names(pool, &skill_ids)
+        .await
+        .map_err(anyhow::Error::msg)?;
+
+    let mut tx = pool.begin().await?;
+    let group_id =
+        db::plan_groups::create_in(&mut tx, &format!("EVEMon ({})", source_name), None).await?;
+
+    let mut characters: Vec<EvemonImportedCharacter> = settings
+        .characters
+        .iter()
+        .map(|c| EvemonImportedCharacter {
+            character_id: c.character_id,
+            name: c.name.clone(),
+            corporation_name: c.corporation_name.clone(),
+            alliance_name: c.alliance_name.clone(),
+            group_id: None,
+        })
+        .collect();
+    let mut orphan_group_id: Option<i64> = None;
+
+    let mut plans = Vec::with_capacity(settings.plans.len());
+    for plan in &settings.plans {
+        let owner = settings
+            .characters
+            .iter()
+            .position(|c| !plan.owner.is_empty() && c.guid == plan.owner);
+
+        let folder_id = match owner {
+            Some(index) => match characters[index].group_id {
+                Some(id) => id,
+                None => {
+                    let name = match characters[index].name.trim() {
+                        "" => UNKNOWN_OWNER_FOLDER,
+                        name => name,
+                    };
+                    let id = db::plan_groups::create_in(&mut tx, name, Some(group_id)).await?;
+                    characters[index].group_id = Some(id);
+                    id
+                }
+            },
+            None => match orphan_group_id {
+                Some(id) => id,
+                None => {
+                    let id =
+                        db::plan_groups::create_in(&mut tx, UNKNOWN_OWNER_FOLDER, Some(group_id))
+                            .await?;
+                    orphan_group_id = Some(id);
+                    id
+                }
+            },
+        };
+
+        let name = match plan.name.trim() {
+            "" => "Imported plan",
+            name => name,
+        };
+        let plan_id = db::skill_plans::create_skill_plan_in(
+            &mut tx,
+            name,
+            plan.description.as_deref(),
+            false,
+            Some(folder_id),
+        )
+        .await?;
+
+        let mut seen = HashSet::new();
+        let mut skipped_entries = Vec::new();
+        let mut entry_count = 0;
+        for entry in &plan.entries {
+            if !known_skills.contains_key(&entry.skill_id) || !(1..=5).contains(&entry.level) {
+                let skill_name = if entry.skill_name.is_empty() {
+                    format!("Skill {}", entry.skill_id)
+                } else {
+                    entry.skill_name.clone()
+                };
+                skipped_entries.push(format!(
+                    "{} {}",
+                    skill_name,
+                    utils::format_level(entry.level, level_format)
+                ));
+                continue;
+            }
+            if !seen.insert((entry.skill_id, entry.level)) {
+                continue;
+            }
+            sqlx::query(
+                "INSERT INTO skill_plan_entries (plan_id, skill_type_id, planned_level, sort_order, entry_type, notes, priority)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(plan_id)
+            .bind(entry.skill_id)
+            .bind(entry.level)
+            .bind(entry_count as i64)
+            .bind(&entry.entry_type)
+            .bind(&entry.notes)
+            .bind(entry.priority)
+            .execute(&mut *tx)
+            .await?;
+            entry_count += 1;
+        }
+
+        plans.push(EvemonImportedPlan {
+            plan_id,
+            name: name.to_string(),
+            character_name: owner.map(|index| characters[index].name.clone()),
+            entry_count,
+            skipped_entries,
+        });
+    }
+    tx.commit().await?;
+
+    Ok(EvemonImportResult {
+        group_id,
+        characters,
+        plans,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::TestDb;
+
+    const SETTINGS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<Settings revision="4000">
+  <characters>
+    <ccp guid="aaaa" id="90000001" name="First Pilot">
+      <corporationName>Pilots &amp; Co</corporationName>
+      <allianceName>Alliance</allianceName>
+    </ccp>
+    <uri guid="bbbb" name="File Pilot" />
+  </characters>
+  <plans>
+    <plan name="Guns" owner="aaaa">
+      <description>Turret basics</description>
+      <entry skillID="3300" skill="Gunnery" level="1" priority="3" type="Prerequisite" />
+      <entry skillID="3300" skill="Gunnery" level="2" priority="1" type="Planned">
+        <notes>Train first</notes>
+      </entry>
+      <entry skillID="999999999" skill="Removed Skill" level="1" priority="3" type="Planned" />
+    </plan>
+    <plan name="Orphan" owner="cccc">
+      <entry skillID="3327" skill="Spaceship Command" level="1" type="Planned" />
+    </plan>
+  </plans>
+</Settings>"#;
+
+    #[test]
+    fn parses_characters_plans_and_entries() {
+        let settings = parse_settings(SETTINGS).unwrap();
+
+        assert_eq!(settings.characters.len(), 2);
+        let first = &settings.characters[0];
+        assert_eq!(first.character_id, Some(90000001));
+        assert_eq!(first.name, "First Pilot");
+        assert_eq!(first.corporation_name.as_deref(), Some("Pilots & Co"));
+        assert_eq!(settings.characters[1].name, "File Pilot");
+
+        assert_eq!(settings.plans.len(), 2);
+        let guns = &settings.plans[0];
+        assert_eq!(guns.owner, "aaaa");
+        assert_eq!(guns.description.as_deref(), Some("Turret basics"));
+        assert_eq!(guns.entries.len(), 3);
+        assert_eq!(guns.entries[0].entry_type, "Prerequisite");
+        assert_eq!(guns.entries[1].priority, 1);
+        assert_eq!(guns.entries[1].notes.as_deref(), Some("Train first"));
+        assert_eq!(
+            guns.entries[0].priority,
+            db::skill_plans::DEFAULT_ENTRY_PRIORITY
+        );
+    }
+
+    #[test]
+    fn rejects_other_xml() {
+        assert!(parse_settings("<plan name=\"x\"></plan>").is_err());
+    }
+
+    #[tokio::test]
+    async fn import_files_plans_under_character_folders() {
+        let db = TestDb::new_with_sde().await.unwrap();
+
+        let result = import(&db.pool, "settings.xml", SETTINGS).await.unwrap();
+
+        let groups = db::plan_groups::list(&db.pool).await.unwrap();
+        let root = groups
+            .iter()
+            .find(|g| g.group_id == result.group_id)
+            .unwrap();
+        assert_eq!(root.name, "EVEMon (settings.xml)");
+        let children: Vec<&str> = groups
+            .iter()
+            .filter(|g| g.parent_group_id == Some(result.group_id))
+            .map(|g| g.name.as_str())
+            .collect();
+        assert_eq!(children, vec!["First Pilot", UNKNOWN_OWNER_FOLDER]);
+
+        let guns = &result.plans[0];
+        assert_eq!(guns.character_name.as_deref(), Some("First Pilot"));
+        assert_eq!(guns.entry_count, 2);
+        assert_eq!(guns.skipped_entries, vec!["Removed Skill 1"]);
+
+        let entries = db::skill_plans::get_plan_entries(&db.pool, guns.plan_id)
+            .await
+            .unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].notes.as_deref(), Some("Train first"));
+        assert_eq!(entries[1].priority, 1);
+
+        assert_eq!(result.plans[1].character_name, None);
+        assert!(result.characters[1].group_id.is_none());
+    }
+}
@@ -157,6 +157,47 @@ impl PlanDag {
         result
     }
 
+    /// Kahn's algorithm that always takes the available node with the smallest
+    /// `key`, so the result is as close to key order as the prerequisites
+    /// allow. Nodes caught in a cycle are left out, as in `topological_sort`.
+    pub fn topological_sort_by_key<K: Ord>(&self, key: impl Fn(&PlanNode) -> K) -> Vec<PlanNode> {
+        let mut in_degree: HashMap<PlanNode, usize> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let degree = self.dependencies.get(node).map_or(0, |deps| {
+                    deps.iter().filter(|d| self.nodes.contains(d)).count()
+                });
+                (*node, degree)
+            })
+            .collect();
+
+        let mut available: Vec<PlanNode> = in_degree
+            .iter()
+            .filter(|(_, &deg)| deg == 0)
+            .map(|(&node, _)| node)
+            .collect();
+        let mut result = Vec::with_capacity(self.nodes.len());
+
+        while let Some(index) = (0..available.len()).min_by_key(|&i| key(&available[i])) {
+            let node = available.swap_remove(index);
+            result.push(node);
+
+            if let Some(deps) = self.dependents.get(&node) {
+                for dep in deps {
+                    if let Some(degree) = in_degree.get_mut(dep) {
+                        *degree -= 1;
+                        if *degree == 0 {
+                            available.push(*dep);
+                        }
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
     pub fn validate(&self, current_order: &[PlanNode]) -> ValidationResult {
         let mut errors = Vec::new();
         let mut warnings = Vec::new();
@@ -0,0 +1,360 @@
+//! Adding imported entries to an existing plan. Every importer that targets a
+//! plan (text, EVEMon XML and .emp, EFT fittings) parses into
+//! [`ImportedEntry`] values and hands them here, so one
+//! [`ConflictStrategy`] decides what happens to skills the plan already has.
+//! Missing prerequisites are always added and the plan is re-sorted.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use super::graph::{PlanDag, PlanNode};
+use crate::db;
+use crate::ts_types::usize_ts;
+
+const PLANNED: &str = "Planned";
+const PREREQUISITE: &str = "Prerequisite";
+
+/// What to do when an imported skill is already in the plan.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConflictStrategy {
+    /// Leave skills already in the plan alone. All of their imported levels
+    /// are dropped.
+    Skip,
+    /// The import wins. Shared levels take its entry type, and its notes if
+    /// it has any. Levels above the imported target are removed unless
+    /// another entry still needs them.
+    Overwrite,
+    /// Train to the higher of the two targets. Shared levels are only
+    /// upgraded from Prerequisite to Planned and take imported notes if there
+    /// are any.
+    #[default]
+    KeepHigherLevel,
+    /// Leave existing entries where they are and queue the import after them
+    /// in its own order. A plan holds each skill level once, so levels it
+    /// already has are not repeated and count as skipped.
+    AppendDuplicates,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedEntry {
+    pub skill_type_id: i64,
+    pub level: i64,
+    pub entry_type: String,
+    pub notes: Option<String>,
+}
+
+impl ImportedEntry {
+    pub fn planned(skill_type_id: i64, level: i64) -> Self {
+        Self {
+            skill_type_id,
+            level,
+            entry_type: PLANNED.to_string(),
+            notes: None,
+        }
+    }
+
+    fn node(&self) -> PlanNode {
+        PlanNode {
+            skill_type_id: self.skill_type_id,
+            level: self.level,
+        }
+    }
+}
+
+/// Counts of plan rows; prerequisites pulled in by the import are included
+/// in `added`.
+#[typeshare]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ImportSummary {
+    pub added: usize_ts,
+    pub updated: usize_ts,
+    /// Imported entries the strategy left out.
+    pub skipped: usize_ts,
+    /// Removed by [`ConflictStrategy::Overwrite`].
+    pub removed: usize_ts,
+}
+
+#[derive(Debug, Clone)]
+struct ExistingEntry {
+    entry_type: String,
+    notes: Option<String>,
+}
+
+/// The imported entries a strategy lets through, and the number dropped.
+fn admitted<'a>(
+    existing: &HashMap<PlanNode, ExistingEntry>,
+    imported: &'a [ImportedEntry],
+    strategy: ConflictStrategy,
+) -> (Vec<&'a ImportedEntry>, usize) {
+    let existing_skills: HashSet<i64> = existing.keys().map(|n| n.skill_type_id).collect();
+    let admitted: Vec<&ImportedEntry> = imported
+        .iter()
+        .filter(|entry| match strategy {
+            ConflictStrategy::Skip => !existing_skills.contains(&entry.skill_type_id),
+            ConflictStrategy::AppendDuplicates => !existing.contains_key(&entry.node()),
+            ConflictStrategy::Overwrite | ConflictStrategy::KeepHigherLevel => true,
+        })
+        .collect();
+    let skipped = imported.len() - admitted.len();
+    (admitted, skipped)
+}
+
+/// Entry type and notes for a shared level, or `None` to leave it as is.
+fn resolve_shared(
+    current: &ExistingEntry,
+    entry: &ImportedEntry,
+    strategy: ConflictStrategy,
+) -> Option<(String, Option<String>)> {
+    let resolved = match strategy {
+        ConflictStrategy::Overwrite => (
+            entry.entry_type.clone(),
+            entry.notes.clone().or_else(|| current.notes.clone()),
+        ),
+        ConflictStrategy::KeepHigherLevel => {
+            let entry_type = if entry.entry_type == PLANNED {
+                PLANNED.to_string()
+            } else {
+                current.entry_type.clone()
+            };
+            (
+                entry_type,
+                entry.notes.clone().or_else(|| current.notes.clone()),
+            )
+        }
+        ConflictStrategy::Skip | ConflictStrategy::AppendDuplicates => return None,
+    };
+    (resolved.0 != current.entry_type || resolved.1 != current.notes).then_some(resolved)
+}
+
+pub async fn import_entries(
+    pool: &db::Pool,
+    plan_id: i64,
+    imported: &[ImportedEntry],
+    strategy: ConflictStrategy,
+) -> Result<ImportSummary> {
+    let existing: HashMap<PlanNode, ExistingEntry> =
+        db::skill_plans::get_plan_entries(pool, plan_id)
+            .await?
+            .into_iter()
+            .map(|e| {
+                (
+                    PlanNode {
+                        skill_type_id: e.skill_type_id,
+                        level: e.planned_level,
+                    },
+                    ExistingEntry {
+                        entry_type: e.entry_type,
+                        notes: e.notes,
+                    },
+                )
+            })
+            .collect();
+    let (admitted, skipped) = admitted(&existing, imported, strategy);
+    let mut summary = ImportSummary {
+        skipped,
+        ..ImportSummary::default()
+    };
+
+    let (mut dag, current_nodes) = PlanDag::build_from_plan(pool, plan_id)
+        .await
+        .context("Failed to build DAG")?;
+    for entry in &admitted {
+        dag.add_recursive(pool, entry.node())
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to add prerequisites for skill {}",
+                    entry.skill_type_id
+                )
+            })?;
+    }
+
+    let mut removed: Vec<PlanNode> = Vec::new();
+    if strategy == ConflictStrategy::Overwrite {
+        let mut targets: HashMap<i64, i64> = HashMap::new();
+        for entry in &admitted {
+            let target = targets.entry(entry.skill_type_id).or_default();
+            *target = (*target).max(entry.level);
+        }
+        // Highest level first, so each removal frees the level below it.
+        let mut candidates: Vec<PlanNode> = existing
+            .keys()
+            .filter(|n| targets.get(&n.skill_type_id).is_some_and(|t| n.level > *t))
+            .copied()
+            .collect();
+        candidates.sort_by_key(|n| std::cmp::Reverse(n.level));
+        for node in candidates {
+            let needed = dag
+                .dependents
+                .get(&node)
+                .is_some_and(|deps| deps.iter().any(|d| dag.nodes.contains(d)));
+            if !needed {
+                dag.nodes.remove(&node);
+                removed.push(node);
+            }
+        }
+    }
+
+    let mut preferred_order = current_nodes;
+    preferred_order.retain(|n| dag.nodes.contains(n));
+    if strategy == ConflictStrategy::AppendDuplicates {
+        preferred_order.extend(admitted.iter().map(|e| e.node()));
+    }
+    let sorted_nodes = dag.topological_sort(&preferred_order);
+
+    let imported_by_node: HashMap<PlanNode, &ImportedEntry> =
+        admitted.iter().map(|e| (e.node(), *e)).collect();
+
+    let mut tx = pool.begin().await?;
+    for node in &removed {
+        sqlx::query(
+            "DELETE FROM skill_plan_entries
+             WHERE plan_id = ? AND skill_type_id = ? AND planned_level = ?",
+        )
+        .bind(plan_id)
+        .bind(node.skill_type_id)
+        .bind(node.level)
+        .execute(&mut *tx)
+        .await?;
+    }
+    summary.removed = removed.len();
+
+    for (index, node) in sorted_nodes.iter().enumerate() {
+        let entry = imported_by_node.get(node);
+        match existing.get(node) {
+            Some(current) => {
+                let resolved = entry.and_then(|e| resolve_shared(current, e, strategy));
+                if let Some((entry_type, notes)) = &resolved {
+                    sqlx::query(
+                        "UPDATE skill_plan_entries SET sort_order = ?, entry_type = ?, notes = ?
+                         WHERE plan_id = ? AND skill_type_id = ? AND planned_level = ?",
+                    )
+                    .bind(index as i64)
+                    .bind(entry_type)
+                    .bind(notes)
+                    .bind(plan_id)
+                    .bind(node.skill_type_id)
+                    .bind(node.level)
+                    .execute(&mut *tx)
+                    .await?;
+                    summary.updated += 1;
+                } else {
+                    sqlx::query(
+                        "UPDATE skill_plan_entries SET sort_order = ?
+                         WHERE plan_id = ? AND skill_type_id = ? AND planned_level = ?",
+                    )
+                    .bind(index as i64)
+                    .bind(plan_id)
+                    .bind(node.skill_type_id)
+                    .bind(node.level)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+            }
+            None => {
+                let entry_type = entry.map_or(PREREQUISITE, |e| e.entry_type.as_str());
+                sqlx::query(
+                    "INSERT INTO skill_plan_entries
+                     (plan_id, skill_type_id, planned_level, sort_order, entry_type, notes)
+                     VALUES (?, ?, ?, ?, ?, ?)",
+                )
+                .bind(plan_id)
+                .bind(node.skill_type_id)
+                .bind(node.level)
+                .bind(index as i64)
+                .bind(entry_type)
+                .bind(entry.and_then(|e| e.notes.as_deref()))
+                .execute(&mut *tx)
+                .await?;
+                summary.added += 1;
+            }
+        }
+    }
+    tx.commit().await?;
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::{fixtures, TestDb};
+
+    async fn plan_levels(pool: &db::Pool, plan_id: i64) -> Vec<(i64, i64, String)> {
+        db::skill_plans::get_plan_entries(pool, plan_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.skill_type_id, e.planned_level, e.entry_type))
+            .collect()
+    }
+
+    async fn plan_with(pool: &db::Pool, entries: &[(i64, i64, &str)]) -> i64 {
+        let plan_id = fixtures::create_skill_plan(pool, "Plan").await;
+        for (skill, level, entry_type) in entries {
+            fixtures::add_plan_entry(pool, plan_id, *skill, *level, entry_type).await;
+        }
+        plan_id
+    }
+
+    // Skill 100 and 200 have no SDE requirements in the test database, so only
+    // the previous-level chain applies.
+    #[tokio::test]
+    async fn strategies_resolve_shared_skills_differently() {
+        let db = TestDb::new().await.unwrap();
+        let existing = [(100, 1, PREREQUISITE), (100, 2, PLANNED), (100, 3, PLANNED)];
+        let imported = [
+            ImportedEntry::planned(100, 1),
+            ImportedEntry::planned(200, 1),
+        ];
+
+        let plan_id = plan_with(&db.pool, &existing).await;
+        let summary = import_entries(&db.pool, plan_id, &imported, ConflictStrategy::Skip)
+            .await
+            .unwrap();
+        assert_eq!((summary.added, summary.updated, summary.skipped), (1, 0, 1));
+
+        let plan_id = plan_with(&db.pool, &existing).await;
+        let summary = import_entries(
+            &db.pool,
+            plan_id,
+            &imported,
+            ConflictStrategy::KeepHigherLevel,
+        )
+        .await
+        .unwrap();
+        assert_eq!((summary.added, summary.updated, summary.removed), (1, 1, 0));
+        assert!(plan_levels(&db.pool, plan_id)
+            .await
+            .contains(&(100, 3, PLANNED.to_string())));
+
+        let plan_id = plan_with(&db.pool, &existing).await;
+        let summary = import_entries(&db.pool, plan_id, &imported, ConflictStrategy::Overwrite)
+            .await
+            .unwrap();
+        assert_eq!(summary.removed, 2);
+        assert_eq!(
+            plan_levels(&db.pool, plan_id).await,
+            vec![(100, 1, PLANNED.to_string()), (200, 1, PLANNED.to_string())]
+        );
+
+        let plan_id = plan_with(&db.pool, &existing).await;
+        let summary = import_entries(
+            &db.pool,
+            plan_id,
+            &imported,
+            ConflictStrategy::AppendDuplicates,
+        )
+        .await
+        .unwrap();
+        assert_eq!((summary.added, summary.skipped), (1, 1));
+        let levels = plan_levels(&db.pool, plan_id).await;
+        assert_eq!(levels.last().unwrap().0, 200);
+        assert_eq!(levels[0], (100, 1, PREREQUISITE.to_string()));
+    }
+}
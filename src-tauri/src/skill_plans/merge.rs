@@ -56,6 +56,9 @@ mod tests {
             level,
             entry_type: entry_type.to_string(),
             notes: notes.map(str::to_string),
+            comments: Vec::new(),
+            excluded: false,
+            priority: crate::db::skill_plans::DEFAULT_ENTRY_PRIORITY,
         }
     }
 
@@ -1,8 +1,19 @@
+pub mod alpha;
+pub mod clipboard;
+pub mod evemon;
 pub mod graph;
+pub mod import;
 pub mod merge;
+pub mod next_goals;
+pub mod next_level;
 pub mod optimization;
 pub mod plan_from_character;
+pub mod queue_sync;
+pub mod remap_compare;
+pub mod remap_wizard;
 pub mod simulation;
+pub mod sorting;
+pub mod what_if;
 
 use serde::{Deserialize, Serialize};
 use typeshare::typeshare;
@@ -26,6 +37,15 @@ pub struct PlannedRemap {
     pub attributes: Attributes,
 }
 
+/// One message in an entry's comment thread (plan format v2).
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillmonPlanComment {
+    pub author: String,
+    pub body: String,
+    pub created_at: i64_ts,
+}
+
 #[typeshare]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SkillmonPlanEntry {
@@ -33,6 +53,41 @@ pub struct SkillmonPlanEntry {
     pub level: i64_ts,
     pub entry_type: String,
     pub notes: Option<String>,
+    /// Absent in v1 files.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<SkillmonPlanComment>,
+    /// Left out of training time estimates; only written when set.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub excluded: bool,
+    /// Absent before v4, and only written when not the default.
+    #[serde(
+        default = "default_entry_priority",
+        skip_serializing_if = "is_default_entry_priority"
+    )]
+    pub priority: i64_ts,
+}
+
+fn default_entry_priority() -> i64 {
+    crate::db::skill_plans::DEFAULT_ENTRY_PRIORITY
+}
+
+fn is_default_entry_priority(priority: &i64) -> bool {
+    *priority == crate::db::skill_plans::DEFAULT_ENTRY_PRIORITY
+}
+
+impl From<crate::db::skill_plans::SkillPlanEntry> for SkillmonPlanEntry {
+    /// Comments are stored separately; see [`attach_comments`].
+    fn from(e: crate::db::skill_plans::SkillPlanEntry) -> Self {
+        SkillmonPlanEntry {
+            skill_type_id: e.skill_type_id,
+            level: e.planned_level,
+            entry_type: e.entry_type,
+            notes: e.notes,
+            comments: Vec::new(),
+            excluded: e.excluded,
+            priority: e.priority,
+        }
+    }
 }
 
 #[typeshare]
@@ -43,6 +98,29 @@ pub struct SkillmonPlanRemap {
     pub attributes: Attributes,
 }
 
+/// A planned cerebral accelerator (plan format v3).
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkillmonPlanBooster {
+    pub type_id: Option<i64_ts>,
+    pub bonus: i64_ts,
+    pub duration_seconds: i64_ts,
+    pub after_skill_type_id: Option<i64_ts>,
+    pub after_skill_level: Option<i64_ts>,
+}
+
+impl From<crate::db::planned_boosters::PlannedBooster> for SkillmonPlanBooster {
+    fn from(b: crate::db::planned_boosters::PlannedBooster) -> Self {
+        SkillmonPlanBooster {
+            type_id: b.type_id,
+            bonus: b.bonus,
+            duration_seconds: b.duration_seconds,
+            after_skill_type_id: b.after_skill_type_id,
+            after_skill_level: b.after_skill_level,
+        }
+    }
+}
+
 #[typeshare]
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SkillmonPlan {
@@ -52,8 +130,79 @@ pub struct SkillmonPlan {
     pub auto_prerequisites: bool,
     pub entries: Vec<SkillmonPlanEntry>,
     pub remaps: Vec<SkillmonPlanRemap>,
+    /// Absent before v3.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub boosters: Vec<SkillmonPlanBooster>,
 }
 
 impl SkillmonPlan {
-    pub const CURRENT_VERSION: i32 = 1;
+    /// v2 added per-entry comment threads, v3 planned boosters, v4 entry
+    /// priorities.
+    pub const CURRENT_VERSION: i32 = 4;
+
+    /// Bring a plan read from any earlier format version up to
+    /// [`Self::CURRENT_VERSION`]. Every step so far only added optional
+    /// fields, which deserialize to their defaults, so upgrading is a matter
+    /// of checking the version is one we know.
+    pub fn migrate(mut self) -> anyhow::Result<Self> {
+        if self.version > Self::CURRENT_VERSION {
+            anyhow::bail!(
+                "Invalid plan: format version {} is newer than this version of skillmon supports",
+                self.version
+            );
+        }
+        if self.version < 1 {
+            anyhow::bail!("Invalid plan: unknown format version {}", self.version);
+        }
+        if let Some(entry) = self
+            .entries
+            .iter()
+            .find(|e| !crate::db::skill_plans::ENTRY_PRIORITIES.contains(&e.priority))
+        {
+            anyhow::bail!(
+                "Invalid plan: entry priority {} is not between 1 and 5",
+                entry.priority
+            );
+        }
+        self.version = Self::CURRENT_VERSION;
+        Ok(self)
+    }
+}
+
+/// Hang each stored comment off the entry for the same skill level.
+pub fn attach_comments(
+    entries: &mut [SkillmonPlanEntry],
+    comments: Vec<crate::db::plan_comments::PlanEntryComment>,
+) {
+    for comment in comments {
+        if let Some(entry) = entries
+            .iter_mut()
+            .find(|e| e.skill_type_id == comment.skill_type_id && e.level == comment.planned_level)
+        {
+            entry.comments.push(SkillmonPlanComment {
+                author: comment.author,
+                body: comment.body,
+                created_at: comment.created_at,
+            });
+        }
+    }
+}
+
+/// Count every excluded entry as already trained in `sp_map`, so estimators
+/// spend no time on it while later levels of the same skill still start from
+/// the excluded level.
+pub fn credit_excluded_entries(
+    entries: &[crate::db::skill_plans::SkillPlanEntry],
+    skill_attributes: &std::collections::HashMap<i64, crate::utils::SkillAttributes>,
+    sp_map: &mut std::collections::HashMap<i64, i64>,
+) {
+    for entry in entries.iter().filter(|e| e.excluded) {
+        let rank = skill_attributes
+            .get(&entry.skill_type_id)
+            .and_then(|a| a.rank)
+            .unwrap_or(1);
+        let sp = crate::utils::calculate_sp_for_level(rank, entry.planned_level as i32);
+        let current = sp_map.entry(entry.skill_type_id).or_insert(0);
+        *current = (*current).max(sp);
+    }
 }
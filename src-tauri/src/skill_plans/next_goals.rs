@@ -0,0 +1,499 @@
+//! "What should I train next": concrete ships, weapons and drones that build
+//! on the skill groups a character has put the most SP into, each with the
+//! skill levels still missing so it can be turned into a plan in one step.
+//!
+//! A goal's anchor is the directly required skill in one of the character's
+//! strongest specialty groups (say Minmatar Cruiser for a Rupture), and it has
+//! to be started already or have every prerequisite trained. Goals are picked
+//! cheapest first, taking turns between those groups and at most one per
+//! anchor, so the list covers several directions instead of every meta variant
+//! of one module. Assets are not synced, so goals come from skills
+//! alone.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+use sqlx::FromRow;
+use typeshare::typeshare;
+
+use crate::skill_summary::{self, SkillRow, SPECIALTY_GROUPS};
+use crate::ts_types::i64_ts;
+use crate::{db, utils};
+
+const MAX_GOALS: usize = 5;
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GoalKind {
+    Ship,
+    Weapon,
+    Drone,
+}
+
+impl GoalKind {
+    /// The kind of item a skill group leads to, paired with the SDE category
+    /// it lives in.
+    fn for_skill_group(group_name: &str) -> Option<(GoalKind, i64)> {
+        match group_name {
+            "Spaceship Command" => Some((GoalKind::Ship, 6)),
+            "Gunnery" | "Missiles" => Some((GoalKind::Weapon, 7)),
+            "Drones" => Some((GoalKind::Drone, 18)),
+            _ => None,
+        }
+    }
+}
+
+/// A published ship, module or drone that could become a goal.
+#[derive(Debug, Clone, FromRow)]
+pub struct GoalType {
+    pub type_id: i64,
+    pub name: String,
+    pub group_name: String,
+    pub category_id: i64,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GoalEntry {
+    pub skill_id: i64_ts,
+    pub skill_name: String,
+    pub level: i64_ts,
+    /// False for levels only needed as prerequisites.
+    pub is_goal: bool,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NextGoal {
+    pub type_id: i64_ts,
+    pub type_name: String,
+    /// The item's own group, e.g. Cruiser or Projectile Weapon.
+    pub group_name: String,
+    pub kind: GoalKind,
+    /// The specialty group the goal builds on.
+    pub skill_group: String,
+    pub anchor_skill_id: i64_ts,
+    pub anchor_skill_name: String,
+    pub anchor_level: i64_ts,
+    pub missing_sp: i64_ts,
+    /// Untrained levels in training order, prerequisites first.
+    pub entries: Vec<GoalEntry>,
+}
+
+/// Skill or item -> its directly required skills, each at the highest level.
+type Requirements = HashMap<i64, Vec<(i64, i64)>>;
+
+fn requirement_map(requirements: &[(i64, i64, i64)]) -> Requirements {
+    let mut map: HashMap<i64, HashMap<i64, i64>> = HashMap::new();
+    for &(type_id, skill_id, level) in requirements {
+        let required = map.entry(type_id).or_default().entry(skill_id).or_default();
+        *required = (*required).max(level);
+    }
+    map.into_iter()
+        .map(|(type_id, skills)| {
+            let mut skills: Vec<(i64, i64)> = skills.into_iter().collect();
+            skills.sort();
+            (type_id, skills)
+        })
+        .collect()
+}
+
+struct Expansion<'a> {
+    requirements: &'a Requirements,
+    trained: &'a HashMap<i64, &'a SkillRow>,
+    planned: HashMap<i64, i64>,
+    entries: Vec<(i64, i64, bool)>,
+}
+
+impl Expansion<'_> {
+    fn add(&mut self, skill_id: i64, level: i64, is_goal: bool) {
+        let from = self
+            .planned
+            .get(&skill_id)
+            .copied()
+            .unwrap_or_else(|| trained_level(self.trained, skill_id));
+        if from >= level {
+            return;
+        }
+        // Claim the levels before recursing so a cycle in the data ends here.
+        self.planned.insert(skill_id, level);
+        if let Some(prerequisites) = self.requirements.get(&skill_id) {
+            for &(required, required_level) in prerequisites {
+                self.add(required, required_level, false);
+            }
+        }
+        for l in from + 1..=level {
+            self.entries.push((skill_id, l, is_goal));
+        }
+    }
+}
+
+/// The untrained levels needed for `type_id` and the SP they take, counting
+/// the progress already made in each skill.
+fn expand_goal(
+    type_id: i64,
+    requirements: &Requirements,
+    trained: &HashMap<i64, &SkillRow>,
+    ranks: &HashMap<i64, i64>,
+) -> (Vec<GoalEntry>, i64) {
+    let mut expansion = Expansion {
+        requirements,
+        trained,
+        planned: HashMap::new(),
+        entries: Vec::new(),
+    };
+    for &(skill_id, level) in requirements.get(&type_id).into_iter().flatten() {
+        expansion.add(skill_id, level, true);
+    }
+
+    let missing_sp = expansion
+        .planned
+        .iter()
+        .map(|(skill_id, level)| {
+            let rank = ranks.get(skill_id).copied().unwrap_or(1);
+            let sp = trained
+                .get(skill_id)
+                .map(|s| s.skillpoints_in_skill)
+                .unwrap_or(0);
+            utils::sp_needed(rank, sp, *level as i32)
+        })
+        .sum();
+    let entries = expansion
+        .entries
+        .into_iter()
+        .map(|(skill_id, level, is_goal)| GoalEntry {
+            skill_id,
+            skill_name: trained
+                .get(&skill_id)
+                .map(|s| s.skill_name.clone())
+                .unwrap_or_default(),
+            level,
+            is_goal,
+        })
+        .collect();
+    (entries, missing_sp)
+}
+
+fn trained_level(trained: &HashMap<i64, &SkillRow>, skill_id: i64) -> i64 {
+    trained
+        .get(&skill_id)
+        .map(|s| s.trained_skill_level)
+        .unwrap_or(0)
+}
+
+/// Specialty groups by SP, most first, leaving out untouched groups unless the
+/// character has started none of them.
+fn strongest_groups(skills: &[SkillRow]) -> Vec<(&'static str, i64)> {
+    let mut groups: Vec<(&'static str, i64)> = SPECIALTY_GROUPS
+        .iter()
+        .map(|group| {
+            let sp = skills
+                .iter()
+                .filter(|s| s.group_name == *group)
+                .map(|s| s.skillpoints_in_skill)
+                .sum();
+            (*group, sp)
+        })
+        .collect();
+    groups.sort_by(|a, b| b.1.cmp(&a.1));
+    if groups.iter().any(|(_, sp)| *sp > 0) {
+        groups.retain(|(_, sp)| *sp > 0);
+    }
+    groups
+}
+
+/// `requirements` holds (skill or item, directly required skill, level) rows.
+pub fn suggest(
+    skills: &[SkillRow],
+    types: &[GoalType],
+    requirements: &[(i64, i64, i64)],
+    ranks: &HashMap<i64, i64>,
+) -> Vec<NextGoal> {
+    let requirements = requirement_map(requirements);
+    let by_id: HashMap<i64, &SkillRow> = skills.iter().map(|s| (s.skill_id, s)).collect();
+    let groups = strongest_groups(skills);
+
+    let mut candidates: Vec<NextGoal> = Vec::new();
+    for goal_type in types {
+        let Some(direct) = requirements.get(&goal_type.type_id) else {
+            continue;
+        };
+        let anchor = groups.iter().find_map(|&(group, _)| {
+            let (kind, category_id) = GoalKind::for_skill_group(group)?;
+            if category_id != goal_type.category_id {
+                return None;
+            }
+            direct.iter().find_map(|&(skill_id, level)| {
+                let skill = by_id.get(&skill_id)?;
+                let in_reach = skill.trained_skill_level > 0
+                    || requirements.get(&skill_id).into_iter().flatten().all(
+                        |&(required, required_level)| {
+                            trained_level(&by_id, required) >= required_level
+                        },
+                    );
+                (skill.group_name == group && in_reach).then_some((group, kind, *skill, level))
+            })
+        });
+        let Some((group, kind, anchor, anchor_level)) = anchor else {
+            continue;
+        };
+
+        let (entries, missing_sp) = expand_goal(goal_type.type_id, &requirements, &by_id, ranks);
+        if entries.is_empty() {
+            continue;
+        }
+        candidates.push(NextGoal {
+            type_id: goal_type.type_id,
+            type_name: goal_type.name.clone(),
+            group_name: goal_type.group_name.clone(),
+            kind,
+            skill_group: group.to_string(),
+            anchor_skill_id: anchor.skill_id,
+            anchor_skill_name: anchor.skill_name.clone(),
+            anchor_level,
+            missing_sp,
+            entries,
+        });
+    }
+    candidates.sort_by(|a, b| {
+        a.missing_sp
+            .cmp(&b.missing_sp)
+            .then_with(|| a.type_name.cmp(&b.type_name))
+    });
+
+    let mut goals: Vec<NextGoal> = Vec::new();
+    let mut used_anchors: HashSet<i64> = HashSet::new();
+    loop {
+        let before = goals.len();
+        for (group, _) in &groups {
+            if goals.len() == MAX_GOALS {
+                return goals;
+            }
+            let next = candidates.iter().position(|c| {
+                c.skill_group == *group && !used_anchors.contains(&c.anchor_skill_id)
+            });
+            if let Some(index) = next {
+                let goal = candidates.remove(index);
+                used_anchors.insert(goal.anchor_skill_id);
+                goals.push(goal);
+            }
+        }
+        if goals.len() == before {
+            return goals;
+        }
+    }
+}
+
+async fn load_goal_data(
+    pool: &db::Pool,
+    character_id: i64,
+) -> Result<(Vec<SkillRow>, Vec<(i64, i64, i64)>, HashMap<i64, i64>)> {
+    let skills = skill_summary::load_skill_rows(pool, character_id).await?;
+    let requirements: Vec<(i64, i64, i64)> = sqlx::query_as(
+        "SELECT skill_type_id, required_skill_id, MAX(required_level)
+         FROM sde_skill_requirements
+         GROUP BY skill_type_id, required_skill_id",
+    )
+    .fetch_all(pool)
+    .await?;
+    let skill_ids: Vec<i64> = skills.iter().map(|s| s.skill_id).collect();
+    let ranks: HashMap<i64, i64> = utils::get_skill_attributes(pool, &skill_ids)
+        .await
+        .map_err(anyhow::Error::msg)?
+        .into_iter()
+        .map(|(id, attrs)| (id, attrs.rank.unwrap_or(1)))
+        .collect();
+    Ok((skills, requirements, ranks))
+}
+
+pub async fn suggest_next_goals(pool: &db::Pool, character_id: i64) -> Result<Vec<NextGoal>> {
+    let (skills, requirements, ranks) = load_goal_data(pool, character_id).await?;
+    let types = sqlx::query_as::<_, GoalType>(
+        "SELECT t.type_id, t.name, g.name AS group_name, t.category_id
+         FROM sde_types t
+         JOIN sde_groups g ON g.group_id = t.group_id
+         WHERE t.published = 1 AND t.category_id IN (6, 7, 18)",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(suggest(&skills, &types, &requirements, &ranks))
+}
+
+/// Creates a plan owned by the character with every level it still needs for
+/// `type_id`, named after the item and the character.
+pub async fn create_goal_plan(pool: &db::Pool, character_id: i64, type_id: i64) -> Result<i64> {
+    let Some(character) = db::get_character(pool, character_id).await? else {
+        bail!("Character {} not found", character_id);
+    };
+    let Some(type_name) = utils::get_type_names(pool, &[type_id])
+        .await
+        .map_err(anyhow::Error::msg)?
+        .remove(&type_id)
+    else {
+        bail!("Type {} not found", type_id);
+    };
+    let (skills, requirements, ranks) = load_goal_data(pool, character_id).await?;
+    let requirements = requirement_map(&requirements);
+    let by_id: HashMap<i64, &SkillRow> = skills.iter().map(|s| (s.skill_id, s)).collect();
+    let (entries, _) = expand_goal(type_id, &requirements, &by_id, &ranks);
+    if entries.is_empty() {
+        bail!("{} has no skills left to train", type_name);
+    }
+
+    let name = format!("{} ({})", type_name, character.character_name);
+    let mut tx = pool.begin().await?;
+    let plan_id = db::skill_plans::create_skill_plan_in(&mut tx, &name, None, true, None).await?;
+    for (index, entry) in entries.iter().enumerate() {
+        let entry_type = if entry.is_goal {
+            db::skill_plans::ENTRY_TYPE_PLANNED
+        } else {
+            db::skill_plans::ENTRY_TYPE_PREREQUISITE
+        };
+        sqlx::query(
+            "INSERT INTO skill_plan_entries (plan_id, skill_type_id, planned_level, sort_order, entry_type, notes)
+             VALUES (?, ?, ?, ?, ?, NULL)",
+        )
+        .bind(plan_id)
+        .bind(entry.skill_id)
+        .bind(entry.level)
+        .bind(index as i64)
+        .bind(entry_type)
+        .execute(&mut *tx)
+        .await?;
+    }
+    db::skill_plans::set_plan_owner(&mut *tx, plan_id, Some(character_id)).await?;
+    tx.commit().await?;
+
+    Ok(plan_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skill(id: i64, name: &str, group: &str, level: i64, sp: i64) -> SkillRow {
+        SkillRow {
+            skill_id: id,
+            skill_name: name.to_string(),
+            group_id: 0,
+            group_name: group.to_string(),
+            trained_skill_level: level,
+            skillpoints_in_skill: sp,
+        }
+    }
+
+    fn goal_type(id: i64, name: &str, group: &str, category_id: i64) -> GoalType {
+        GoalType {
+            type_id: id,
+            name: name.to_string(),
+            group_name: group.to_string(),
+            category_id,
+        }
+    }
+
+    #[test]
+    fn suggests_cheapest_goal_per_anchor_across_strong_groups() {
+        let skills = [
+            skill(1, "Spaceship Command", "Spaceship Command", 3, 8_000),
+            skill(2, "Minmatar Frigate", "Spaceship Command", 3, 8_000),
+            skill(3, "Minmatar Destroyer", "Spaceship Command", 0, 0),
+            skill(4, "Gunnery", "Gunnery", 2, 1_415),
+            skill(5, "Small Projectile Turret", "Gunnery", 2, 1_415),
+            skill(6, "Caldari Frigate", "Spaceship Command", 0, 0),
+            skill(7, "Drones", "Drones", 0, 0),
+            skill(8, "Amarr Frigate", "Spaceship Command", 0, 0),
+        ];
+        let types = [
+            goal_type(100, "Rifter", "Frigate", 6),
+            goal_type(101, "Thrasher", "Destroyer", 6),
+            goal_type(102, "Wolf", "Assault Frigate", 6),
+            goal_type(103, "Slasher", "Frigate", 6),
+            goal_type(104, "Merlin", "Frigate", 6),
+            goal_type(105, "Punisher", "Frigate", 6),
+            goal_type(200, "200mm AutoCannon I", "Projectile Weapon", 7),
+            goal_type(201, "150mm Light AutoCannon I", "Projectile Weapon", 7),
+            goal_type(300, "Hobgoblin I", "Combat Drone", 18),
+        ];
+        let requirements = [
+            (2, 1, 1),
+            (3, 2, 3),
+            (3, 1, 3),
+            (5, 4, 1),
+            (6, 1, 1),
+            (7, 1, 1),
+            (8, 1, 4),
+            (100, 2, 1),
+            (101, 3, 1),
+            (102, 2, 5),
+            (103, 2, 4),
+            (104, 6, 1),
+            (105, 8, 1),
+            (200, 5, 3),
+            (201, 5, 1),
+            (300, 7, 1),
+        ];
+        let ranks = HashMap::from([(2, 2), (3, 2), (5, 1)]);
+
+        let goals = suggest(&skills, &types, &requirements, &ranks);
+
+        // The Rifter and the 150mm are usable already, Amarr Frigate needs more
+        // Spaceship Command first, Drones is untouched and the Wolf shares
+        // Minmatar Frigate with the cheaper Slasher.
+        let picked: Vec<(&str, GoalKind, i64)> = goals
+            .iter()
+            .map(|g| (g.type_name.as_str(), g.kind, g.missing_sp))
+            .collect();
+        assert_eq!(
+            picked,
+            vec![
+                ("Merlin", GoalKind::Ship, 250),
+                ("200mm AutoCannon I", GoalKind::Weapon, 6_585),
+                ("Thrasher", GoalKind::Ship, 500),
+                ("Slasher", GoalKind::Ship, 82_510),
+            ]
+        );
+
+        let slasher = &goals[3];
+        assert_eq!(slasher.anchor_skill_name, "Minmatar Frigate");
+        assert_eq!(
+            slasher
+                .entries
+                .iter()
+                .map(|e| (e.skill_id, e.level, e.is_goal))
+                .collect::<Vec<_>>(),
+            vec![(2, 4, true)]
+        );
+    }
+
+    #[test]
+    fn prerequisites_come_before_the_levels_that_need_them() {
+        let skills = [
+            skill(1, "Spaceship Command", "Spaceship Command", 1, 250),
+            skill(2, "Minmatar Frigate", "Spaceship Command", 1, 250),
+            skill(3, "Minmatar Destroyer", "Spaceship Command", 0, 0),
+        ];
+        let requirements = requirement_map(&[(2, 1, 1), (3, 2, 3), (3, 1, 3), (101, 3, 2)]);
+        let by_id: HashMap<i64, &SkillRow> = skills.iter().map(|s| (s.skill_id, s)).collect();
+
+        let (entries, missing_sp) = expand_goal(101, &requirements, &by_id, &HashMap::new());
+
+        assert_eq!(
+            entries
+                .iter()
+                .map(|e| (e.skill_id, e.level, e.is_goal))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, 2, false),
+                (1, 3, false),
+                (2, 2, false),
+                (2, 3, false),
+                (3, 1, true),
+                (3, 2, true),
+            ]
+        );
+        assert_eq!(missing_sp, 2 * (8_000 - 250) + 1_415);
+    }
+}
@@ -0,0 +1,175 @@
+//! Time to the next level of one skill on the character sheet, at the
+//! character's current attributes and at the best remap for that skill alone,
+//! without building a plan.
+
+use std::collections::HashSet;
+
+use anyhow::{bail, Result};
+use chrono::Utc;
+use serde::Serialize;
+use typeshare::typeshare;
+
+use super::optimization::generate_distributions;
+use super::remap_wizard::{load_profile, WizardDemand, WizardProfile};
+use super::Attributes;
+use crate::ts_types::i64_ts;
+use crate::{cache, db, esi, queue_math, utils};
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct NextLevelEstimate {
+    pub character_id: i64_ts,
+    pub skill_id: i64_ts,
+    pub current_level: i64_ts,
+    pub next_level: i64_ts,
+    /// SP in the skill now, including progress if it is training.
+    pub current_sp: i64_ts,
+    pub sp_to_next_level: i64_ts,
+    pub sp_per_hour: f64,
+    pub seconds: i64_ts,
+    /// Remap points above the base of 17 that train this skill fastest.
+    pub optimal_attributes: Attributes,
+    pub optimal_sp_per_hour: f64,
+    pub optimal_seconds: i64_ts,
+}
+
+fn seconds_at(sp: i64, sp_per_minute: f64) -> i64 {
+    if sp_per_minute <= 0.0 {
+        return 0;
+    }
+    (sp as f64 / sp_per_minute * 60.0).ceil() as i64
+}
+
+/// `demand` is the remaining SP to the next level with the skill's pair.
+pub fn estimate(
+    character_id: i64,
+    current_level: i64,
+    current_sp: i64,
+    demand: &WizardDemand,
+    profile: &WizardProfile,
+) -> NextLevelEstimate {
+    let used: HashSet<i64> = [demand.primary, demand.secondary]
+        .into_iter()
+        .flatten()
+        .collect();
+    let current_rate = profile.rate(&profile.current_remap, demand);
+    let (optimal_attributes, optimal_rate) = generate_distributions(&used).into_iter().fold(
+        (profile.current_remap.clone(), current_rate),
+        |best, dist| {
+            let rate = profile.rate(&dist, demand);
+            if rate > best.1 {
+                (dist, rate)
+            } else {
+                best
+            }
+        },
+    );
+
+    NextLevelEstimate {
+        character_id,
+        skill_id: demand.skill_type_id,
+        current_level,
+        next_level: demand.level,
+        current_sp,
+        sp_to_next_level: demand.sp,
+        sp_per_hour: current_rate * 60.0,
+        seconds: seconds_at(demand.sp, current_rate),
+        optimal_attributes,
+        optimal_sp_per_hour: optimal_rate * 60.0,
+        optimal_seconds: seconds_at(demand.sp, optimal_rate),
+    }
+}
+
+pub async fn next_level_estimate(
+    pool: &db::Pool,
+    character_id: i64,
+    skill_id: i64,
+) -> Result<NextLevelEstimate> {
+    let Some(skill) = db::get_character_skills(pool, character_id)
+        .await?
+        .into_iter()
+        .find(|s| s.skill_id == skill_id)
+    else {
+        bail!("Skill {} is not on the character sheet", skill_id);
+    };
+    if skill.trained_skill_level >= 5 {
+        bail!("Skill {} is already trained to level 5", skill_id);
+    }
+
+    let next_level = skill.trained_skill_level + 1;
+    let endpoint_path = format!("characters/{}/skillqueue", character_id);
+    let cache_key = cache::build_cache_key(&endpoint_path, character_id);
+    let training_sp = cache::get_cached_response(pool, &cache_key)
+        .await?
+        .and_then(|entry| {
+            serde_json::from_str::<Vec<esi::CharactersSkillqueueSkill>>(&entry.response_body).ok()
+        })
+        .unwrap_or_default()
+        .iter()
+        .filter(|item| item.skill_id == skill_id && item.finished_level == next_level)
+        .find_map(|item| queue_math::training_sp_at(item, Utc::now()));
+    let current_sp = training_sp.map_or(skill.skillpoints_in_skill, |sp| {
+        (sp as i64).max(skill.skillpoints_in_skill)
+    });
+
+    let attrs = utils::get_skill_attributes(pool, &[skill_id])
+        .await
+        .map_err(anyhow::Error::msg)?
+        .remove(&skill_id);
+    let rank = attrs.as_ref().and_then(|a| a.rank).unwrap_or(1);
+    let demand = WizardDemand {
+        skill_type_id: skill_id,
+        level: next_level,
+        primary: attrs.as_ref().and_then(|a| a.primary_attribute),
+        secondary: attrs.as_ref().and_then(|a| a.secondary_attribute),
+        sp: (utils::calculate_sp_for_level(rank, next_level as i32) - current_sp).max(0),
+    };
+
+    let profile = load_profile(pool, character_id).await?;
+    Ok(estimate(
+        character_id,
+        skill.trained_skill_level,
+        current_sp,
+        &demand,
+        &profile,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn optimal_remap_favours_the_skills_attribute_pair() {
+        let profile = WizardProfile {
+            current_remap: Attributes {
+                charisma: 0,
+                intelligence: 0,
+                memory: 0,
+                perception: 0,
+                willpower: 14,
+            },
+            implants: Attributes::default(),
+            accelerator_bonus: 0,
+            is_omega: true,
+        };
+        // Rank 1 level 4 from level 3: 45255 - 8000 SP, Int/Mem.
+        let demand = WizardDemand {
+            skill_type_id: 3300,
+            level: 4,
+            primary: Some(165),
+            secondary: Some(166),
+            sp: 37255,
+        };
+
+        let result = estimate(1, 3, 8000, &demand, &profile);
+
+        assert_eq!(result.optimal_attributes.intelligence, 10);
+        assert_eq!(result.optimal_attributes.memory, 4);
+        // Current: 17 + 17/2 = 25.5 SP/min; optimal: 27 + 21/2 = 37.5 SP/min.
+        assert_eq!(result.sp_per_hour, 25.5 * 60.0);
+        assert_eq!(result.optimal_sp_per_hour, 37.5 * 60.0);
+        assert!(result.optimal_seconds < result.seconds);
+        assert_eq!(result.seconds, (37255.0_f64 / 25.5 * 60.0).ceil() as i64);
+    }
+}
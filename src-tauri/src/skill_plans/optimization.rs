@@ -1,19 +1,141 @@
 use crate::db;
 use crate::skill_plans::{Attributes, PlannedRemap};
-use crate::ts_types::i64_ts;
+use crate::ts_types::{i64_ts, usize_ts};
 use crate::utils;
 use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use typeshare::typeshare;
 
 const TOTAL_REMAP_POINTS: i64 = 14;
 const MAX_POINTS_PER_ATTR: i64 = 10;
 
+/// Minimum gap between two progress reports from the remap search.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Payload of the `optimization-progress` event: how many of the remap
+/// search's segments have been evaluated.
+#[typeshare]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct OptimizationProgress {
+    pub optimization_id: String,
+    pub evaluated: usize_ts,
+    pub total: usize_ts,
+}
+
+type ProgressFn = dyn Fn(usize, usize) + Send + Sync;
+
+/// Cancellation and progress reporting for one run of the remap search.
+/// The default never cancels and reports nowhere.
+#[derive(Clone, Default)]
+pub struct OptimizationControl {
+    cancelled: Arc<AtomicBool>,
+    on_progress: Option<Arc<ProgressFn>>,
+}
+
+impl OptimizationControl {
+    pub fn new(
+        cancelled: Arc<AtomicBool>,
+        on_progress: impl Fn(usize, usize) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            cancelled,
+            on_progress: Some(Arc::new(on_progress)),
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    fn report(&self, evaluated: usize, total: usize) {
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(evaluated, total);
+        }
+    }
+}
+
+/// Cancellation flags of the optimizations in flight, keyed by the id the
+/// frontend passed with the request. Managed as Tauri state.
+#[derive(Default)]
+pub struct OptimizationRegistry {
+    running: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl OptimizationRegistry {
+    pub fn register(&self, optimization_id: &str) -> Arc<AtomicBool> {
+        let flag = Arc::new(AtomicBool::new(false));
+        self.running
+            .lock()
+            .unwrap()
+            .insert(optimization_id.to_string(), flag.clone());
+        flag
+    }
+
+    pub fn finish(&self, optimization_id: &str) {
+        self.running.lock().unwrap().remove(optimization_id);
+    }
+
+    /// Returns false when no optimization with this id is running.
+    pub fn cancel(&self, optimization_id: &str) -> bool {
+        match self.running.lock().unwrap().get(optimization_id) {
+            Some(flag) => {
+                flag.store(true, std::sync::atomic::Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
 #[typeshare]
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct OptimizationResult {
     pub recommended_remap: PlannedRemap,
     pub original_seconds: i64_ts,
     pub optimized_seconds: i64_ts,
+    /// Only filled in when asked for; see [`implant_scenarios`].
+    pub implant_scenarios: Option<Vec<ImplantScenario>>,
+}
+
+/// Training the plan with the recommended remap and a uniform set of +N
+/// attribute implants.
+#[typeshare]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ImplantScenario {
+    pub bonus: i64_ts,
+    /// The implants worth buying: one per attribute the plan trains with.
+    pub implant_type_ids: Vec<i64_ts>,
+    pub training_seconds: i64_ts,
+    /// Compared with no attribute implants.
+    pub days_saved: f64,
+    /// Cheapest sell orders from the market cache. `None` while any of the
+    /// implants has no cached price, e.g. before they are added to the
+    /// tracked market types.
+    pub isk_cost: Option<f64>,
+}
+
+/// One implant of an [`ImplantRecommendation`].
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct RecommendedImplant {
+    pub attribute_id: i64_ts,
+    pub bonus: i64_ts,
+    pub type_id: i64_ts,
+    pub isk_cost: f64,
+}
+
+/// The attribute implants that train the plan fastest within a budget.
+#[typeshare]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ImplantRecommendation {
+    /// Empty when no priced implant fits the budget or helps the plan.
+    pub implants: Vec<RecommendedImplant>,
+    pub training_seconds: i64_ts,
+    /// Compared with no attribute implants.
+    pub seconds_saved: i64_ts,
+    pub isk_cost: f64,
 }
 
 #[typeshare]
@@ -34,6 +156,25 @@ pub struct ReorderOptimizationResult {
     pub optimized_seconds: i64_ts,
 }
 
+/// A remap in a schedule that spans several plans: before `entry_index` of
+/// plan `plan_id`.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct CrossPlanRemap {
+    pub plan_id: i64_ts,
+    pub entry_index: usize_ts,
+    pub attributes: Attributes,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CrossPlanOptimizationResult {
+    pub plan_ids: Vec<i64_ts>,
+    pub recommended_remaps: Vec<CrossPlanRemap>,
+    pub original_seconds: i64_ts,
+    pub optimized_seconds: i64_ts,
+}
+
 struct EntryDemand {
     primary: Option<i64>,
     secondary: Option<i64>,
@@ -42,14 +183,34 @@ struct EntryDemand {
 
 type AttrPairDemand = HashMap<(Option<i64>, Option<i64>), i64>;
 
+/// Implant grades compared by [`implant_scenarios`], with the suffix the SDE
+/// gives that grade's attribute implants.
+const IMPLANT_GRADES: [(i64, Option<&str>); 4] = [
+    (0, None),
+    (3, Some("Basic")),
+    (4, Some("Standard")),
+    (5, Some("Improved")),
+];
+
+/// Attribute id and the name of its implant line, as in "Ocular Filter - Basic".
+const ATTRIBUTE_IMPLANTS: [(i64, &str); 5] = [
+    (165, "Cybernetic Subprocessor"),
+    (166, "Memory Augmentation"),
+    (167, "Ocular Filter"),
+    (168, "Neural Boost"),
+    (164, "Social Adaptation Chip"),
+];
+
 pub async fn optimize_plan_reordering(
     pool: &db::Pool,
     plan_id: i64,
     implants: &Attributes,
     baseline_remap: &Attributes,
     accelerator_bonus: i64,
+    is_omega: bool,
     current_sp_map: &HashMap<i64, i64>,
     max_remaps: i64,
+    control: &OptimizationControl,
 ) -> anyhow::Result<ReorderOptimizationResult> {
     // 1. Get current entries and attributes
     let entries = db::skill_plans::get_plan_entries(pool, plan_id).await?;
@@ -61,6 +222,9 @@ pub async fn optimize_plan_reordering(
     let skill_attributes = utils::get_skill_attributes(pool, &skill_type_ids)
         .await
         .map_err(|e| anyhow::anyhow!(e))?;
+    let mut credited_sp = current_sp_map.clone();
+    super::credit_excluded_entries(&entries, &skill_attributes, &mut credited_sp);
+    let current_sp_map = &credited_sp;
 
     // 2. Build DAG
     let (dag, _) = crate::skill_plans::graph::PlanDag::build_from_plan(pool, plan_id).await?;
@@ -72,6 +236,7 @@ pub async fn optimize_plan_reordering(
         implants,
         baseline_remap,
         accelerator_bonus,
+        is_omega,
         current_sp_map,
         &skill_attributes,
     )
@@ -141,6 +306,7 @@ pub async fn optimize_plan_reordering(
     let subtree_scores = memo;
 
     // 3. Greedy topological sort by subtree ratio score
+    let selection = crate::app_log::span("reorder: cluster selection");
     let mut optimized_entries = Vec::new();
     let mut in_degree: HashMap<crate::skill_plans::graph::PlanNode, usize> = HashMap::new();
     for node in &dag.nodes {
@@ -158,22 +324,20 @@ pub async fn optimize_plan_reordering(
 
     let mut current_sim_sp_map = current_sp_map.clone();
 
-    while !available.is_empty() {
-        // Pick the available node with the highest subtree score (weighted average ratio)
-        let node = *available
-            .iter()
-            .max_by(|a, b| {
-                let (ws_a, sp_a) = subtree_scores.get(a).unwrap_or(&(0.0, 0));
-                let (ws_b, sp_b) = subtree_scores.get(b).unwrap_or(&(0.0, 0));
-
-                let score_a = if *sp_a > 0 { ws_a / *sp_a as f64 } else { 0.0 };
-                let score_b = if *sp_b > 0 { ws_b / *sp_b as f64 } else { 0.0 };
+    let tie_breaks: HashMap<crate::skill_plans::graph::PlanNode, (i64, usize)> = entries
+        .iter()
+        .enumerate()
+        .map(|(index, e)| {
+            let node = crate::skill_plans::graph::PlanNode {
+                skill_type_id: e.skill_type_id,
+                level: e.planned_level,
+            };
+            (node, (e.priority, index))
+        })
+        .collect();
 
-                score_a
-                    .partial_cmp(&score_b)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            })
-            .unwrap();
+    while !available.is_empty() {
+        let node = next_reorder_node(&available, &subtree_scores, &tie_breaks);
 
         available.remove(&node);
 
@@ -207,6 +371,8 @@ pub async fn optimize_plan_reordering(
         }
     }
 
+    drop(selection);
+
     // 4. Calculate optimal remaps at entry-level granularity
     let num_entries = optimized_entries.len();
     if num_entries == 0 {
@@ -218,16 +384,254 @@ pub async fn optimize_plan_reordering(
         });
     }
 
+    let training_order: Vec<(i64, i64)> = optimized_entries
+        .iter()
+        .map(|e| (e.skill_type_id, e.planned_level))
+        .collect();
+    let (recommended_remaps, total_optimized_seconds) = schedule_remaps_blocking(
+        training_order,
+        &skill_attributes,
+        current_sp_map,
+        implants,
+        baseline_remap,
+        accelerator_bonus,
+        is_omega,
+        max_remaps,
+        control,
+    )
+    .await?;
+
+    // 5. Calculate original time (No Remap, Original Order)
+    let original_opt = optimize_plan_attributes_internal(
+        pool,
+        &entries,
+        implants,
+        baseline_remap,
+        accelerator_bonus,
+        is_omega,
+        current_sp_map,
+        &skill_attributes,
+    )
+    .await?;
+    let original_seconds = original_opt.original_seconds;
+
+    Ok(ReorderOptimizationResult {
+        optimized_entries,
+        recommended_remaps,
+        original_seconds,
+        optimized_seconds: total_optimized_seconds.ceil() as i64,
+    })
+}
+
+/// The available node with the highest subtree score (weighted average
+/// ratio). Equal scores go to the higher-priority entry, then to the one
+/// earlier in the plan.
+fn next_reorder_node(
+    available: &std::collections::HashSet<crate::skill_plans::graph::PlanNode>,
+    subtree_scores: &HashMap<crate::skill_plans::graph::PlanNode, (f64, i64)>,
+    tie_breaks: &HashMap<crate::skill_plans::graph::PlanNode, (i64, usize)>,
+) -> crate::skill_plans::graph::PlanNode {
+    let score = |node: &crate::skill_plans::graph::PlanNode| {
+        let (weighted_sp, sp) = subtree_scores.get(node).unwrap_or(&(0.0, 0));
+        if *sp > 0 {
+            weighted_sp / *sp as f64
+        } else {
+            0.0
+        }
+    };
+    let tie_break = |node: &crate::skill_plans::graph::PlanNode| {
+        tie_breaks
+            .get(node)
+            .copied()
+            .unwrap_or((i64::MAX, usize::MAX))
+    };
+    *available
+        .iter()
+        .max_by(|a, b| {
+            score(a)
+                .partial_cmp(&score(b))
+                .unwrap_or(std::cmp::Ordering::Equal)
+                // Lower (priority, position) wins, so compare reversed.
+                .then_with(|| tie_break(b).cmp(&tie_break(a)))
+        })
+        .unwrap()
+}
+
+/// Plans one remap schedule for a character working through `plan_ids` one
+/// after another, each in its own order. Starts from the character's current
+/// remap, implants and skills.
+pub async fn optimize_across_plans(
+    pool: &db::Pool,
+    character_id: i64,
+    plan_ids: &[i64],
+    max_remaps: i64,
+    control: &OptimizationControl,
+) -> anyhow::Result<CrossPlanOptimizationResult> {
+    let profile = super::remap_wizard::load_profile(pool, character_id).await?;
+    let is_omega = db::get_character(pool, character_id)
+        .await?
+        .is_none_or(|c| c.is_omega);
+    let current_sp_map: HashMap<i64, i64> = db::get_character_skills(pool, character_id)
+        .await?
+        .into_iter()
+        .map(|s| (s.skill_id, s.skillpoints_in_skill))
+        .collect();
+
+    optimize_across_plans_internal(
+        pool,
+        plan_ids,
+        &profile.implants,
+        &profile.current_remap,
+        profile.accelerator_bonus,
+        is_omega,
+        &current_sp_map,
+        max_remaps,
+        control,
+    )
+    .await
+}
+
+async fn optimize_across_plans_internal(
+    pool: &db::Pool,
+    plan_ids: &[i64],
+    implants: &Attributes,
+    baseline_remap: &Attributes,
+    accelerator_bonus: i64,
+    is_omega: bool,
+    current_sp_map: &HashMap<i64, i64>,
+    max_remaps: i64,
+    control: &OptimizationControl,
+) -> anyhow::Result<CrossPlanOptimizationResult> {
+    // Each entry remembers its plan and its index there, to map the remap
+    // boundaries back.
+    let mut entries = Vec::new();
+    let mut positions = Vec::new();
+    for &plan_id in plan_ids {
+        let plan_entries = db::skill_plans::get_plan_entries(pool, plan_id).await?;
+        positions.extend((0..plan_entries.len()).map(|index| (plan_id, index)));
+        entries.extend(plan_entries);
+    }
+    if entries.is_empty() {
+        return Err(anyhow::anyhow!("Plans are empty"));
+    }
+
+    let skill_type_ids: Vec<i64> = entries.iter().map(|e| e.skill_type_id).collect();
+    let skill_attributes = utils::get_skill_attributes(pool, &skill_type_ids)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let mut credited_sp = current_sp_map.clone();
+    super::credit_excluded_entries(&entries, &skill_attributes, &mut credited_sp);
+
+    let training_order: Vec<(i64, i64)> = entries
+        .iter()
+        .map(|e| (e.skill_type_id, e.planned_level))
+        .collect();
+    let (remaps, optimized_seconds) = schedule_remaps_blocking(
+        training_order,
+        &skill_attributes,
+        &credited_sp,
+        implants,
+        baseline_remap,
+        accelerator_bonus,
+        is_omega,
+        max_remaps,
+        control,
+    )
+    .await?;
+
+    let original = optimize_plan_attributes_internal(
+        pool,
+        &entries,
+        implants,
+        baseline_remap,
+        accelerator_bonus,
+        is_omega,
+        current_sp_map,
+        &skill_attributes,
+    )
+    .await?;
+
+    Ok(CrossPlanOptimizationResult {
+        plan_ids: plan_ids.to_vec(),
+        recommended_remaps: remaps
+            .into_iter()
+            .map(|remap| {
+                let (plan_id, entry_index) = positions[remap.entry_index];
+                CrossPlanRemap {
+                    plan_id,
+                    entry_index,
+                    attributes: remap.attributes,
+                }
+            })
+            .collect(),
+        original_seconds: original.original_seconds,
+        optimized_seconds: optimized_seconds.ceil() as i64,
+    })
+}
+
+/// [`schedule_remaps`] on the blocking pool; on long plans it runs for
+/// minutes and would otherwise stall the async runtime.
+#[allow(clippy::too_many_arguments)]
+async fn schedule_remaps_blocking(
+    entries: Vec<(i64, i64)>,
+    skill_attributes: &HashMap<i64, crate::utils::SkillAttributes>,
+    current_sp_map: &HashMap<i64, i64>,
+    implants: &Attributes,
+    baseline_remap: &Attributes,
+    accelerator_bonus: i64,
+    is_omega: bool,
+    max_remaps: i64,
+    control: &OptimizationControl,
+) -> anyhow::Result<(Vec<PlannedRemap>, f64)> {
+    let skill_attributes = skill_attributes.clone();
+    let current_sp_map = current_sp_map.clone();
+    let implants = implants.clone();
+    let baseline_remap = baseline_remap.clone();
+    let control = control.clone();
+    tokio::task::spawn_blocking(move || {
+        schedule_remaps(
+            &entries,
+            &skill_attributes,
+            &current_sp_map,
+            &implants,
+            &baseline_remap,
+            accelerator_bonus,
+            is_omega,
+            max_remaps,
+            &control,
+        )
+    })
+    .await?
+}
+
+/// Best remap points for training `entries` (skill, level) in this order with
+/// up to `max_remaps` remaps, starting from `baseline_remap`. Returns the
+/// remaps, indexed into `entries`, and the total training seconds. Fails only
+/// when `control` is cancelled.
+#[allow(clippy::too_many_arguments)]
+fn schedule_remaps(
+    entries: &[(i64, i64)],
+    skill_attributes: &HashMap<i64, crate::utils::SkillAttributes>,
+    current_sp_map: &HashMap<i64, i64>,
+    implants: &Attributes,
+    baseline_remap: &Attributes,
+    accelerator_bonus: i64,
+    is_omega: bool,
+    max_remaps: i64,
+    control: &OptimizationControl,
+) -> anyhow::Result<(Vec<PlannedRemap>, f64)> {
+    let num_entries = entries.len();
+
     // Precompute attribute pairs and SP to train for each entry
     let mut entry_demands = Vec::with_capacity(num_entries);
     let mut current_sim_sp = current_sp_map.clone();
     let mut used_attributes = std::collections::HashSet::new();
 
-    for entry in &optimized_entries {
-        let attr = skill_attributes.get(&entry.skill_type_id).unwrap();
+    for &(skill_type_id, planned_level) in entries {
+        let attr = skill_attributes.get(&skill_type_id).unwrap();
         let rank = attr.rank.unwrap_or(1);
-        let target_sp = utils::calculate_sp_for_level(rank, entry.planned_level as i32);
-        let start_sp = *current_sim_sp.get(&entry.skill_type_id).unwrap_or(&0);
+        let target_sp = utils::calculate_sp_for_level(rank, planned_level as i32);
+        let start_sp = *current_sim_sp.get(&skill_type_id).unwrap_or(&0);
         let sp_to_train = (target_sp - start_sp).max(0);
 
         if sp_to_train > 0 {
@@ -237,7 +641,7 @@ pub async fn optimize_plan_reordering(
             if let Some(s) = attr.secondary_attribute {
                 used_attributes.insert(s);
             }
-            current_sim_sp.insert(entry.skill_type_id, target_sp);
+            current_sim_sp.insert(skill_type_id, target_sp);
         }
 
         entry_demands.push(EntryDemand {
@@ -280,7 +684,7 @@ pub async fn optimize_plan_reordering(
                 accelerator_bonus,
                 demand.secondary,
             );
-            let sp_per_min = utils::calculate_sp_per_minute(p_val, s_val, true);
+            let sp_per_min = utils::calculate_sp_per_minute(p_val, s_val, is_omega);
             if sp_per_min > 0.0 {
                 baseline_entry_times.push((demand.sp_to_train as f64 / sp_per_min) * 60.0);
             } else {
@@ -292,6 +696,13 @@ pub async fn optimize_plan_reordering(
     }
 
     let m_limit = max_remaps.max(0) as usize;
+    let _solve = crate::app_log::span("remap DP solve");
+    log::debug!(
+        "remap DP: {} entries, up to {} remaps, {} distributions",
+        num_entries,
+        m_limit,
+        all_distributions.len()
+    );
 
     // cost[i][j] = min time for entries i..j with its optimal remap
     let mut opt_segment_results: HashMap<(usize, usize), (f64, Attributes)> = HashMap::new();
@@ -328,7 +739,7 @@ pub async fn optimize_plan_reordering(
             for ((p, s), sp) in &segment_demand {
                 let p_val = get_effective_attr_value(dist, implants, accelerator_bonus, *p);
                 let s_val = get_effective_attr_value(dist, implants, accelerator_bonus, *s);
-                let sp_per_min = utils::calculate_sp_per_minute(p_val, s_val, true);
+                let sp_per_min = utils::calculate_sp_per_minute(p_val, s_val, is_omega);
                 if sp_per_min > 0.0 {
                     total_seconds += (*sp as f64 / sp_per_min) * 60.0;
                 }
@@ -352,10 +763,34 @@ pub async fn optimize_plan_reordering(
         row[num_entries] = (0.0, num_entries, Attributes::default());
     }
 
+    // Segments the search evaluates: one per start for m=1, then every
+    // (i, j) pair for each further remap.
+    let total_segments = if m_limit == 0 {
+        0
+    } else {
+        num_entries + (m_limit - 1) * num_entries * num_entries.saturating_sub(1) / 2
+    };
+    let mut evaluated = 0;
+    let mut last_report = Instant::now();
+    let mut row_done = |segments: usize| -> anyhow::Result<()> {
+        if control.is_cancelled() {
+            anyhow::bail!("Optimization cancelled");
+        }
+        evaluated += segments;
+        if last_report.elapsed() >= PROGRESS_INTERVAL {
+            control.report(evaluated, total_segments);
+            last_report = Instant::now();
+        }
+        Ok(())
+    };
+
     // m=1: optimal time for i..num_entries using one remap at i.
-    for i in (0..num_entries).rev() {
-        if let Some((t_seg, attr_seg)) = get_segment_cost(i, num_entries) {
-            dp_remap[1][i] = (t_seg, num_entries, attr_seg.clone());
+    if m_limit >= 1 {
+        for i in (0..num_entries).rev() {
+            if let Some((t_seg, attr_seg)) = get_segment_cost(i, num_entries) {
+                dp_remap[1][i] = (t_seg, num_entries, attr_seg.clone());
+            }
+            row_done(1)?;
         }
     }
 
@@ -382,8 +817,10 @@ pub async fn optimize_plan_reordering(
                 }
             }
             dp_remap[m][i] = best;
+            row_done(num_entries - i - 1)?;
         }
     }
+    control.report(total_segments, total_segments);
 
     // Now consider the baseline period at the beginning.
     // We can train 0..k entries with baseline_remap, then use m_limit remaps for k..num_entries.
@@ -408,8 +845,6 @@ pub async fn optimize_plan_reordering(
         }
     }
 
-    let total_optimized_seconds = best_total_time;
-
     // Reconstruct remaps
     let mut recommended_remaps = Vec::new();
     if best_k < num_entries {
@@ -437,25 +872,7 @@ pub async fn optimize_plan_reordering(
         }
     }
 
-    // 5. Calculate original time (No Remap, Original Order)
-    let original_opt = optimize_plan_attributes_internal(
-        pool,
-        &entries,
-        implants,
-        baseline_remap,
-        accelerator_bonus,
-        current_sp_map,
-        &skill_attributes,
-    )
-    .await?;
-    let original_seconds = original_opt.original_seconds;
-
-    Ok(ReorderOptimizationResult {
-        optimized_entries,
-        recommended_remaps,
-        original_seconds,
-        optimized_seconds: total_optimized_seconds.ceil() as i64,
-    })
+    Ok((recommended_remaps, best_total_time))
 }
 
 pub async fn optimize_plan_attributes(
@@ -464,64 +881,234 @@ pub async fn optimize_plan_attributes(
     implants: &Attributes,
     baseline_remap: &Attributes,
     accelerator_bonus: i64,
+    is_omega: bool,
     current_sp_map: &HashMap<i64, i64>,
+    compare_implants: bool,
 ) -> anyhow::Result<OptimizationResult> {
     let skill_type_ids: Vec<i64> = entries.iter().map(|e| e.skill_type_id).collect();
     let skill_attributes = utils::get_skill_attributes(pool, &skill_type_ids)
         .await
         .map_err(|e| anyhow::anyhow!(e))?;
 
-    optimize_plan_attributes_internal(
+    let mut result = optimize_plan_attributes_internal(
         pool,
         entries,
         implants,
         baseline_remap,
         accelerator_bonus,
+        is_omega,
         current_sp_map,
         &skill_attributes,
     )
-    .await
+    .await?;
+
+    if compare_implants {
+        let (demand, used_attributes) = plan_demand(entries, current_sp_map, &skill_attributes)?;
+        result.implant_scenarios = Some(
+            implant_scenarios(
+                pool,
+                &demand,
+                &used_attributes,
+                &result.recommended_remap.attributes,
+                accelerator_bonus,
+                is_omega,
+            )
+            .await?,
+        );
+    }
+    Ok(result)
 }
 
-async fn optimize_plan_attributes_internal(
-    _pool: &db::Pool,
-    entries: &[crate::db::skill_plans::SkillPlanEntry],
-    implants: &Attributes,
-    baseline_remap: &Attributes,
+/// Answers "are +5s worth it for this plan": training time with the given
+/// remap under each of [`IMPLANT_GRADES`], and what the implants cost.
+async fn implant_scenarios(
+    pool: &db::Pool,
+    demand: &AttrPairDemand,
+    used_attributes: &std::collections::HashSet<i64>,
+    remap: &Attributes,
     accelerator_bonus: i64,
-    current_sp_map: &HashMap<i64, i64>,
-    skill_attributes: &HashMap<i64, crate::utils::SkillAttributes>,
-) -> anyhow::Result<OptimizationResult> {
-    // 1. Calculate SP demand per (primary, secondary) pair
-    let mut demand_map: HashMap<(Option<i64>, Option<i64>), i64> = HashMap::new();
-    let mut used_attributes = std::collections::HashSet::new();
+    is_omega: bool,
+) -> anyhow::Result<Vec<ImplantScenario>> {
+    let no_implants = demand_seconds(
+        demand,
+        remap,
+        &Attributes::default(),
+        accelerator_bonus,
+        is_omega,
+    );
+
+    let mut scenarios = Vec::with_capacity(IMPLANT_GRADES.len());
+    for (bonus, grade) in IMPLANT_GRADES {
+        let mut implants = Attributes::default();
+        let mut implant_type_ids = Vec::new();
+        let mut isk_cost = Some(0.0);
+        for (attribute_id, line) in ATTRIBUTE_IMPLANTS {
+            if !used_attributes.contains(&attribute_id) {
+                continue;
+            }
+            let Some(grade) = grade else {
+                continue;
+            };
+            set_attribute(&mut implants, attribute_id, bonus);
+
+            let Some((type_id, price)) = implant_offer(pool, line, grade).await? else {
+                isk_cost = None;
+                continue;
+            };
+            implant_type_ids.push(type_id);
+            isk_cost = isk_cost.zip(price).map(|(total, price)| total + price);
+        }
 
-    let mut simulated_sp = current_sp_map.clone(); // Correctly track SP deltas
+        let seconds = demand_seconds(demand, remap, &implants, accelerator_bonus, is_omega);
+        scenarios.push(ImplantScenario {
+            bonus,
+            implant_type_ids,
+            training_seconds: seconds.ceil() as i64,
+            days_saved: (no_implants - seconds) / 86_400.0,
+            isk_cost,
+        });
+    }
+    Ok(scenarios)
+}
 
-    for entry in entries {
-        let skill_attr = skill_attributes.get(&entry.skill_type_id).ok_or_else(|| {
-            anyhow::anyhow!("Attributes not found for skill {}", entry.skill_type_id)
-        })?;
+/// Type id and cheapest cached sell price of an implant, e.g. line "Ocular
+/// Filter" in grade "Basic". `None` when the SDE has no such implant.
+async fn implant_offer(
+    pool: &db::Pool,
+    line: &str,
+    grade: &str,
+) -> anyhow::Result<Option<(i64, Option<f64>)>> {
+    let name = format!("{} - {}", line, grade);
+    let Some(implant) = db::sde::get_type_by_name(pool, &name).await? else {
+        return Ok(None);
+    };
+    let price = db::market_prices::get_market_price(pool, implant.type_id)
+        .await?
+        .and_then(|p| p.sell_min);
+    Ok(Some((implant.type_id, price)))
+}
 
-        let rank = skill_attr.rank.unwrap_or(1);
-        let total_sp_needed = utils::calculate_sp_for_level(rank, entry.planned_level as i32);
-        let current_sp = *simulated_sp.get(&entry.skill_type_id).unwrap_or(&0);
-        let sp_remaining = (total_sp_needed - current_sp).max(0);
+/// Picks a +3/+4/+5 implant (or none) for each attribute the plan trains with,
+/// minimising training time with `remap` while the sell prices add up to no
+/// more than `budget_isk`. Implants without a cached price are never picked.
+pub async fn optimize_implants(
+    pool: &db::Pool,
+    entries: &[crate::db::skill_plans::SkillPlanEntry],
+    remap: &Attributes,
+    accelerator_bonus: i64,
+    is_omega: bool,
+    current_sp_map: &HashMap<i64, i64>,
+    budget_isk: f64,
+) -> anyhow::Result<ImplantRecommendation> {
+    let skill_type_ids: Vec<i64> = entries.iter().map(|e| e.skill_type_id).collect();
+    let skill_attributes = utils::get_skill_attributes(pool, &skill_type_ids)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+    let (demand, used_attributes) = plan_demand(entries, current_sp_map, &skill_attributes)?;
 
-        if sp_remaining > 0 {
-            let key = (skill_attr.primary_attribute, skill_attr.secondary_attribute);
-            *demand_map.entry(key).or_insert(0) += sp_remaining;
-            simulated_sp.insert(entry.skill_type_id, total_sp_needed); // Use total_sp_needed for next entries of same skill
+    // Per used attribute, the priced implants to choose from.
+    let mut choices: Vec<Vec<RecommendedImplant>> = Vec::new();
+    for (attribute_id, line) in ATTRIBUTE_IMPLANTS {
+        if !used_attributes.contains(&attribute_id) {
+            continue;
+        }
+        let mut offers = Vec::new();
+        for (bonus, grade) in IMPLANT_GRADES {
+            let Some(grade) = grade else {
+                continue;
+            };
+            if let Some((type_id, Some(isk_cost))) = implant_offer(pool, line, grade).await? {
+                offers.push(RecommendedImplant {
+                    attribute_id,
+                    bonus,
+                    type_id,
+                    isk_cost,
+                });
+            }
+        }
+        choices.push(offers);
+    }
 
-            if let Some(p) = skill_attr.primary_attribute {
-                used_attributes.insert(p);
+    let no_implants = demand_seconds(
+        &demand,
+        remap,
+        &Attributes::default(),
+        accelerator_bonus,
+        is_omega,
+    );
+    let mut best = (no_implants, 0.0, Vec::new());
+
+    // At most 4^5 combinations: every attribute picks one offer or none.
+    let mut picks = vec![None; choices.len()];
+    loop {
+        let chosen: Vec<&RecommendedImplant> = picks
+            .iter()
+            .zip(&choices)
+            .filter_map(|(pick, offers)| pick.map(|i: usize| &offers[i]))
+            .collect();
+        let cost: f64 = chosen.iter().map(|i| i.isk_cost).sum();
+        if cost <= budget_isk {
+            let mut implants = Attributes::default();
+            for implant in &chosen {
+                set_attribute(&mut implants, implant.attribute_id, implant.bonus);
             }
-            if let Some(s) = skill_attr.secondary_attribute {
-                used_attributes.insert(s);
+            let seconds = demand_seconds(&demand, remap, &implants, accelerator_bonus, is_omega);
+            if seconds < best.0 || (seconds == best.0 && cost < best.1) {
+                best = (seconds, cost, chosen.into_iter().cloned().collect());
+            }
+        }
+
+        // Advance to the next combination, odometer style.
+        let mut slot = 0;
+        while slot < picks.len() {
+            picks[slot] = match picks[slot] {
+                None if !choices[slot].is_empty() => Some(0),
+                Some(i) if i + 1 < choices[slot].len() => Some(i + 1),
+                _ => None,
+            };
+            if picks[slot].is_some() {
+                break;
             }
+            slot += 1;
+        }
+        if slot == picks.len() {
+            break;
         }
     }
 
+    let (seconds, isk_cost, implants) = best;
+    Ok(ImplantRecommendation {
+        implants,
+        training_seconds: seconds.ceil() as i64,
+        seconds_saved: (no_implants - seconds).round() as i64,
+        isk_cost,
+    })
+}
+
+fn set_attribute(attributes: &mut Attributes, attribute_id: i64, value: i64) {
+    match attribute_id {
+        165 => attributes.intelligence = value,
+        166 => attributes.memory = value,
+        167 => attributes.perception = value,
+        168 => attributes.willpower = value,
+        164 => attributes.charisma = value,
+        _ => {}
+    }
+}
+
+async fn optimize_plan_attributes_internal(
+    _pool: &db::Pool,
+    entries: &[crate::db::skill_plans::SkillPlanEntry],
+    implants: &Attributes,
+    baseline_remap: &Attributes,
+    accelerator_bonus: i64,
+    is_omega: bool,
+    current_sp_map: &HashMap<i64, i64>,
+    skill_attributes: &HashMap<i64, crate::utils::SkillAttributes>,
+) -> anyhow::Result<OptimizationResult> {
+    // 1. Calculate SP demand per (primary, secondary) pair
+    let (demand_map, used_attributes) = plan_demand(entries, current_sp_map, skill_attributes)?;
+
     if demand_map.is_empty() {
         return Ok(OptimizationResult {
             recommended_remap: PlannedRemap {
@@ -530,6 +1117,7 @@ async fn optimize_plan_attributes_internal(
             },
             original_seconds: 0,
             optimized_seconds: 0,
+            implant_scenarios: None,
         });
     }
 
@@ -541,16 +1129,8 @@ async fn optimize_plan_attributes_internal(
 
     // 3. Find distribution that minimizes total time
     for dist in distributions {
-        let mut total_seconds = 0.0;
-        for ((primary_id, secondary_id), sp) in &demand_map {
-            let p_val = get_effective_attr_value(&dist, implants, accelerator_bonus, *primary_id);
-            let s_val = get_effective_attr_value(&dist, implants, accelerator_bonus, *secondary_id);
-            let sp_per_min = utils::calculate_sp_per_minute(p_val, s_val, true);
-            if sp_per_min > 0.0 {
-                total_seconds += (*sp as f64 / sp_per_min) * 60.0;
-            }
-        }
-
+        let total_seconds =
+            demand_seconds(&demand_map, &dist, implants, accelerator_bonus, is_omega);
         if total_seconds < min_seconds {
             min_seconds = total_seconds;
             best_attributes = dist;
@@ -558,17 +1138,13 @@ async fn optimize_plan_attributes_internal(
     }
 
     // 4. Calculate baseline for comparison (baseline remap + implants + accelerator)
-    let mut original_seconds = 0.0;
-    for ((primary_id, secondary_id), sp) in &demand_map {
-        let p_val =
-            get_effective_attr_value(baseline_remap, implants, accelerator_bonus, *primary_id);
-        let s_val =
-            get_effective_attr_value(baseline_remap, implants, accelerator_bonus, *secondary_id);
-        let sp_per_min = utils::calculate_sp_per_minute(p_val, s_val, true);
-        if sp_per_min > 0.0 {
-            original_seconds += (*sp as f64 / sp_per_min) * 60.0;
-        }
-    }
+    let original_seconds = demand_seconds(
+        &demand_map,
+        baseline_remap,
+        implants,
+        accelerator_bonus,
+        is_omega,
+    );
 
     Ok(OptimizationResult {
         recommended_remap: PlannedRemap {
@@ -577,10 +1153,72 @@ async fn optimize_plan_attributes_internal(
         },
         original_seconds: original_seconds.ceil() as i64,
         optimized_seconds: min_seconds.ceil() as i64,
+        implant_scenarios: None,
     })
 }
 
-fn generate_distributions(used_ids: &std::collections::HashSet<i64>) -> Vec<Attributes> {
+/// SP still to train per (primary, secondary) attribute pair, and every
+/// attribute that demand uses.
+fn plan_demand(
+    entries: &[crate::db::skill_plans::SkillPlanEntry],
+    current_sp_map: &HashMap<i64, i64>,
+    skill_attributes: &HashMap<i64, crate::utils::SkillAttributes>,
+) -> anyhow::Result<(AttrPairDemand, std::collections::HashSet<i64>)> {
+    let mut demand_map: AttrPairDemand = HashMap::new();
+    let mut used_attributes = std::collections::HashSet::new();
+
+    let mut simulated_sp = current_sp_map.clone(); // Correctly track SP deltas
+    super::credit_excluded_entries(entries, skill_attributes, &mut simulated_sp);
+
+    for entry in entries {
+        let skill_attr = skill_attributes.get(&entry.skill_type_id).ok_or_else(|| {
+            anyhow::anyhow!("Attributes not found for skill {}", entry.skill_type_id)
+        })?;
+
+        let rank = skill_attr.rank.unwrap_or(1);
+        let total_sp_needed = utils::calculate_sp_for_level(rank, entry.planned_level as i32);
+        let current_sp = *simulated_sp.get(&entry.skill_type_id).unwrap_or(&0);
+        let sp_remaining = (total_sp_needed - current_sp).max(0);
+
+        if sp_remaining > 0 {
+            let key = (skill_attr.primary_attribute, skill_attr.secondary_attribute);
+            *demand_map.entry(key).or_insert(0) += sp_remaining;
+            simulated_sp.insert(entry.skill_type_id, total_sp_needed); // Use total_sp_needed for next entries of same skill
+
+            if let Some(p) = skill_attr.primary_attribute {
+                used_attributes.insert(p);
+            }
+            if let Some(s) = skill_attr.secondary_attribute {
+                used_attributes.insert(s);
+            }
+        }
+    }
+
+    Ok((demand_map, used_attributes))
+}
+
+/// Seconds to train `demand` with the given remap, implants and accelerator,
+/// at the Alpha rate unless `is_omega`.
+fn demand_seconds(
+    demand: &AttrPairDemand,
+    remap: &Attributes,
+    implants: &Attributes,
+    accelerator_bonus: i64,
+    is_omega: bool,
+) -> f64 {
+    let mut total_seconds = 0.0;
+    for ((primary_id, secondary_id), sp) in demand {
+        let p_val = get_effective_attr_value(remap, implants, accelerator_bonus, *primary_id);
+        let s_val = get_effective_attr_value(remap, implants, accelerator_bonus, *secondary_id);
+        let sp_per_min = utils::calculate_sp_per_minute(p_val, s_val, is_omega);
+        if sp_per_min > 0.0 {
+            total_seconds += (*sp as f64 / sp_per_min) * 60.0;
+        }
+    }
+    total_seconds
+}
+
+pub(super) fn generate_distributions(used_ids: &std::collections::HashSet<i64>) -> Vec<Attributes> {
     let mut results = Vec::new();
     let mut current = [0i64; 5];
 
@@ -661,7 +1299,7 @@ fn calculate_ratio(
     }
 }
 
-fn get_effective_attr_value(
+pub(super) fn get_effective_attr_value(
     remap: &Attributes,
     implants: &Attributes,
     accelerator_bonus: i64,
@@ -681,8 +1319,35 @@ fn get_effective_attr_value(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::skill_plans::graph::PlanNode;
     use crate::testdata::{fixtures, TestDb};
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn reorder_ties_go_to_priority_then_plan_order() {
+        let node = |skill_type_id| PlanNode {
+            skill_type_id,
+            level: 1,
+        };
+        let available: HashSet<PlanNode> = [node(1), node(2), node(3)].into();
+        let scores: HashMap<PlanNode, (f64, i64)> = [
+            (node(1), (100.0, 100)),
+            (node(2), (100.0, 100)),
+            (node(3), (100.0, 100)),
+        ]
+        .into();
+
+        let mut tie_breaks: HashMap<PlanNode, (i64, usize)> =
+            [(node(1), (3, 0)), (node(2), (1, 1)), (node(3), (1, 2))].into();
+        assert_eq!(next_reorder_node(&available, &scores, &tie_breaks), node(2));
+
+        tie_breaks.insert(node(2), (3, 1));
+        assert_eq!(next_reorder_node(&available, &scores, &tie_breaks), node(3));
+
+        let mut better = scores.clone();
+        better.insert(node(1), (150.0, 100));
+        assert_eq!(next_reorder_node(&available, &better, &tie_breaks), node(1));
+    }
 
     #[tokio::test]
     async fn test_optimize_plan_attributes_basic() {
@@ -700,12 +1365,29 @@ mod tests {
         let baseline = Attributes::default();
         let current_sp = HashMap::new();
 
-        let result =
-            optimize_plan_attributes(&db.pool, &entries, &implants, &baseline, 0, &current_sp)
-                .await
-                .unwrap();
+        let result = optimize_plan_attributes(
+            &db.pool,
+            &entries,
+            &implants,
+            &baseline,
+            0,
+            true,
+            &current_sp,
+            true,
+        )
+        .await
+        .unwrap();
 
         assert!(result.optimized_seconds <= result.original_seconds);
+        let scenarios = result.implant_scenarios.as_ref().unwrap();
+        assert_eq!(
+            scenarios.iter().map(|s| s.bonus).collect::<Vec<_>>(),
+            vec![0, 3, 4, 5]
+        );
+        assert_eq!(scenarios[0].days_saved, 0.0);
+        assert_eq!(scenarios[0].isk_cost, Some(0.0));
+        assert!(scenarios[3].training_seconds < scenarios[2].training_seconds);
+        assert!(scenarios[3].days_saved > scenarios[1].days_saved);
         // Spaceship Command is Per/Wil. Optimal remap should favor Per/Wil.
         assert!(
             result.recommended_remap.attributes.perception > 0
@@ -715,6 +1397,71 @@ mod tests {
         assert!(result.original_seconds > 0);
     }
 
+    #[tokio::test]
+    async fn test_optimize_implants_within_budget() {
+        let db = TestDb::new_with_sde().await.unwrap();
+        let plan_id = fixtures::create_skill_plan(&db.pool, "Test Implants").await;
+        // Spaceship Command (Per/Wil)
+        fixtures::add_plan_entry(&db.pool, plan_id, 3327, 5, "Planned").await;
+        let entries = crate::db::skill_plans::get_plan_entries(&db.pool, plan_id)
+            .await
+            .unwrap();
+
+        let mut ocular_improved = 0;
+        for line in ["Ocular Filter", "Neural Boost"] {
+            for (grade, price) in [("Basic", 1e6), ("Standard", 5e6), ("Improved", 20e6)] {
+                let name = format!("{} - {}", line, grade);
+                let implant = db::sde::get_type_by_name(&db.pool, &name)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                if name == "Ocular Filter - Improved" {
+                    ocular_improved = implant.type_id;
+                }
+                db::market_prices::upsert_market_price(
+                    &db.pool,
+                    &db::market_prices::MarketPrice {
+                        type_id: implant.type_id,
+                        region_id: 10000002,
+                        sell_min: Some(price),
+                        buy_max: None,
+                        sell_volume: 0,
+                        buy_volume: 0,
+                        updated_at: 0,
+                    },
+                )
+                .await
+                .unwrap();
+            }
+        }
+
+        let remap = Attributes::default();
+        let current_sp = HashMap::new();
+        let result = optimize_implants(&db.pool, &entries, &remap, 0, true, &current_sp, 21e6)
+            .await
+            .unwrap();
+
+        // +5 on the primary attribute beats +4/+4 for the same budget.
+        assert_eq!(result.isk_cost, 21e6);
+        assert_eq!(result.implants.len(), 2);
+        assert!(result
+            .implants
+            .iter()
+            .any(|i| i.type_id == ocular_improved && i.bonus == 5));
+        assert!(result
+            .implants
+            .iter()
+            .any(|i| i.attribute_id == 168 && i.bonus == 3));
+        assert!(result.seconds_saved > 0);
+
+        let broke = optimize_implants(&db.pool, &entries, &remap, 0, true, &current_sp, 0.0)
+            .await
+            .unwrap();
+        assert!(broke.implants.is_empty());
+        assert_eq!(broke.seconds_saved, 0);
+        assert!(broke.training_seconds > result.training_seconds);
+    }
+
     #[tokio::test]
     async fn test_optimize_plan_reordering_with_remaps() {
         let db = TestDb::new_with_sde().await.unwrap();
@@ -738,8 +1485,10 @@ mod tests {
             &implants,
             &baseline,
             0,
+            true,
             &current_sp,
             2, // max 2 remaps
+            &OptimizationControl::default(),
         )
         .await
         .unwrap();
@@ -748,6 +1497,47 @@ mod tests {
         assert!(result.optimized_seconds <= result.original_seconds);
     }
 
+    #[tokio::test]
+    async fn test_optimize_across_plans_maps_remaps_to_plan_entries() {
+        let db = TestDb::new_with_sde().await.unwrap();
+
+        // An Int/Mem plan followed by a Per/Wil plan.
+        let science = fixtures::create_skill_plan(&db.pool, "Science").await;
+        for level in 1..=5 {
+            fixtures::add_plan_entry(&db.pool, science, 3402, level, "Planned").await;
+        }
+        let command = fixtures::create_skill_plan(&db.pool, "Command").await;
+        for level in 1..=5 {
+            fixtures::add_plan_entry(&db.pool, command, 3327, level, "Planned").await;
+        }
+
+        let result = optimize_across_plans_internal(
+            &db.pool,
+            &[science, command],
+            &Attributes::default(),
+            &Attributes::default(),
+            0,
+            true,
+            &HashMap::new(),
+            2,
+            &OptimizationControl::default(),
+        )
+        .await
+        .unwrap();
+
+        assert!(result.optimized_seconds < result.original_seconds);
+        assert_eq!(result.recommended_remaps.len(), 2);
+        assert_eq!(
+            (
+                result.recommended_remaps[0].plan_id,
+                result.recommended_remaps[0].entry_index
+            ),
+            (science, 0)
+        );
+        assert_eq!(result.recommended_remaps[1].plan_id, command);
+        assert!(result.recommended_remaps[1].attributes.perception > 0);
+    }
+
     #[tokio::test]
     async fn test_optimize_real_character_plan() {
         let db = TestDb::new_with_sde().await.unwrap();
@@ -800,10 +1590,19 @@ mod tests {
             current_sp.insert(s.skill_id, s.skillpoints_in_skill);
         }
 
-        let result =
-            optimize_plan_reordering(&db.pool, plan_id, &implants, &baseline, 0, &current_sp, 1)
-                .await
-                .unwrap();
+        let result = optimize_plan_reordering(
+            &db.pool,
+            plan_id,
+            &implants,
+            &baseline,
+            0,
+            true,
+            &current_sp,
+            1,
+            &OptimizationControl::default(),
+        )
+        .await
+        .unwrap();
 
         // Calculate total SP trained
         let entries = db::skill_plans::get_plan_entries(&db.pool, plan_id)
@@ -918,4 +1717,58 @@ mod tests {
 
         assert!(result.optimized_seconds <= result.original_seconds);
     }
+
+    #[test]
+    fn test_schedule_remaps_reports_progress_and_cancels() {
+        let skill_attributes: HashMap<i64, crate::utils::SkillAttributes> = (1..=4)
+            .map(|skill_id| {
+                let attr = crate::utils::SkillAttributes {
+                    primary_attribute: Some(165),
+                    secondary_attribute: Some(166),
+                    rank: Some(1),
+                };
+                (skill_id, attr)
+            })
+            .collect();
+        let entries: Vec<(i64, i64)> = (1..=4).map(|skill_id| (skill_id, 1)).collect();
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let control = OptimizationControl::new(Arc::new(AtomicBool::new(false)), move |e, t| {
+            sink.lock().unwrap().push((e, t))
+        });
+
+        schedule_remaps(
+            &entries,
+            &skill_attributes,
+            &HashMap::new(),
+            &Attributes::default(),
+            &Attributes::default(),
+            0,
+            true,
+            2,
+            &control,
+        )
+        .unwrap();
+        // 4 single-remap segments plus 6 (i, j) pairs for the second remap.
+        assert_eq!(reports.lock().unwrap().last(), Some(&(10, 10)));
+
+        let registry = OptimizationRegistry::default();
+        let cancelled = registry.register("run");
+        assert!(registry.cancel("run"));
+        let control = OptimizationControl::new(cancelled, |_, _| {});
+        let result = schedule_remaps(
+            &entries,
+            &skill_attributes,
+            &HashMap::new(),
+            &Attributes::default(),
+            &Attributes::default(),
+            0,
+            true,
+            2,
+            &control,
+        );
+        assert!(result.is_err());
+        registry.finish("run");
+        assert!(!registry.cancel("run"));
+    }
 }
@@ -0,0 +1,216 @@
+//! Reconciles a plan with a character's live skill queue: which entries the
+//! queue already covers, what is left to queue, and what the queue trains
+//! that the plan does not ask for.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use typeshare::typeshare;
+
+use super::clipboard::{self, ClipboardFormat};
+use crate::db::skill_plans::SkillPlanEntry;
+use crate::ts_types::i64_ts;
+use crate::{cache, db, esi, queue_math, utils};
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryQueueStatus {
+    Trained,
+    Queued,
+    /// Neither trained nor in the queue.
+    Missing,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanQueueEntry {
+    pub entry_id: i64_ts,
+    pub skill_type_id: i64_ts,
+    pub level: i64_ts,
+    pub status: EntryQueueStatus,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueuedLevel {
+    pub skill_type_id: i64_ts,
+    pub level: i64_ts,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanQueueSync {
+    /// Every entry the plan trains, in plan order; excluded entries are left
+    /// out.
+    pub entries: Vec<PlanQueueEntry>,
+    /// Levels in the queue that no plan entry reaches.
+    pub queued_not_in_plan: Vec<QueuedLevel>,
+    /// The missing entries, one "Skill Name Level" line each, ready to paste
+    /// into the in-game skill queue.
+    pub missing_clipboard_text: String,
+}
+
+/// Marks each entry against `trained` (highest trained level per skill) and
+/// `queue` (pending levels in queue order). An entry counts as queued when
+/// the queue reaches its level for that skill.
+pub fn reconcile(
+    entries: &[SkillPlanEntry],
+    trained: &HashMap<i64, i64>,
+    queue: &[QueuedLevel],
+) -> (Vec<PlanQueueEntry>, Vec<QueuedLevel>) {
+    let mut queued_to: HashMap<i64, i64> = HashMap::new();
+    for item in queue {
+        let level = queued_to.entry(item.skill_type_id).or_default();
+        *level = (*level).max(item.level);
+    }
+    let mut planned_to: HashMap<i64, i64> = HashMap::new();
+
+    let marked = entries
+        .iter()
+        .filter(|e| !e.excluded)
+        .map(|e| {
+            let planned = planned_to.entry(e.skill_type_id).or_default();
+            *planned = (*planned).max(e.planned_level);
+
+            let status = if trained.get(&e.skill_type_id).copied().unwrap_or(0) >= e.planned_level {
+                EntryQueueStatus::Trained
+            } else if queued_to.get(&e.skill_type_id).copied().unwrap_or(0) >= e.planned_level {
+                EntryQueueStatus::Queued
+            } else {
+                EntryQueueStatus::Missing
+            };
+            PlanQueueEntry {
+                entry_id: e.entry_id,
+                skill_type_id: e.skill_type_id,
+                level: e.planned_level,
+                status,
+            }
+        })
+        .collect();
+
+    let not_in_plan = queue
+        .iter()
+        .filter(|item| planned_to.get(&item.skill_type_id).copied().unwrap_or(0) < item.level)
+        .cloned()
+        .collect();
+
+    (marked, not_in_plan)
+}
+
+pub async fn sync_plan_with_queue(
+    pool: &db::Pool,
+    plan_id: i64,
+    character_id: i64,
+    now: DateTime<Utc>,
+) -> Result<PlanQueueSync> {
+    let entries = db::skill_plans::get_plan_entries(pool, plan_id).await?;
+
+    let mut trained: HashMap<i64, i64> = db::get_character_skills(pool, character_id)
+        .await?
+        .into_iter()
+        .map(|s| (s.skill_id, s.trained_skill_level))
+        .collect();
+
+    let endpoint_path = format!("characters/{}/skillqueue", character_id);
+    let cache_key = cache::build_cache_key(&endpoint_path, character_id);
+    let mut raw_queue = cache::get_cached_response(pool, &cache_key)
+        .await?
+        .and_then(|entry| {
+            serde_json::from_str::<Vec<esi::CharactersSkillqueueSkill>>(&entry.response_body).ok()
+        })
+        .unwrap_or_default();
+    raw_queue.sort_by_key(|item| item.queue_position);
+
+    // Levels that finished since the skills were last fetched are trained.
+    let mut queue = Vec::new();
+    for item in raw_queue {
+        if queue_math::is_pending(&item, now) {
+            queue.push(QueuedLevel {
+                skill_type_id: item.skill_id,
+                level: item.finished_level,
+            });
+        } else {
+            let level = trained.entry(item.skill_id).or_default();
+            *level = (*level).max(item.finished_level);
+        }
+    }
+
+    let (marked, queued_not_in_plan) = reconcile(&entries, &trained, &queue);
+
+    let missing: Vec<(i64, i64)> = marked
+        .iter()
+        .filter(|e| e.status == EntryQueueStatus::Missing)
+        .map(|e| (e.skill_type_id, e.level))
+        .collect();
+    let skill_ids: Vec<i64> = missing
+        .iter()
+        .map(|(skill_type_id, _)| *skill_type_id)
+        .collect();
+    let names = utils::get_type_names(pool, &skill_ids)
+        .await
+        .map_err(anyhow::Error::msg)?;
+    let missing_clipboard_text = clipboard::to_text(&missing, &names, ClipboardFormat::SkillQueue);
+
+    Ok(PlanQueueSync {
+        entries: marked,
+        queued_not_in_plan,
+        missing_clipboard_text,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(entry_id: i64, skill_type_id: i64, planned_level: i64) -> SkillPlanEntry {
+        SkillPlanEntry {
+            entry_id,
+            plan_id: 1,
+            skill_type_id,
+            planned_level,
+            sort_order: entry_id,
+            entry_type: "Planned".to_string(),
+            notes: None,
+            excluded: false,
+            priority: crate::db::skill_plans::DEFAULT_ENTRY_PRIORITY,
+        }
+    }
+
+    #[test]
+    fn reconcile_marks_trained_queued_and_missing_entries() {
+        let entries = vec![entry(1, 3327, 1), entry(2, 3327, 2), entry(3, 3327, 3)];
+        let trained = HashMap::from([(3327, 1)]);
+        let queue = vec![
+            QueuedLevel {
+                skill_type_id: 3327,
+                level: 2,
+            },
+            QueuedLevel {
+                skill_type_id: 3300,
+                level: 1,
+            },
+        ];
+
+        let (marked, not_in_plan) = reconcile(&entries, &trained, &queue);
+
+        let statuses: Vec<EntryQueueStatus> = marked.iter().map(|e| e.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                EntryQueueStatus::Trained,
+                EntryQueueStatus::Queued,
+                EntryQueueStatus::Missing,
+            ]
+        );
+        assert_eq!(
+            not_in_plan,
+            vec![QueuedLevel {
+                skill_type_id: 3300,
+                level: 1,
+            }]
+        );
+    }
+}
@@ -0,0 +1,211 @@
+//! Side-by-side simulation of one plan under two remap schedules, so a user
+//! can check the optimizer's suggestion against a schedule of their own.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::Serialize;
+use typeshare::typeshare;
+
+use super::alpha::CloneState;
+use super::simulation::{self, SimulationProfile, SimulationResult};
+use super::{Attributes, PlannedRemap};
+use crate::db;
+use crate::ts_types::i64_ts;
+
+/// Training time of the entries sharing one primary/secondary attribute pair.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RemapClusterDelta {
+    pub primary_attribute_id: Option<i64_ts>,
+    pub secondary_attribute_id: Option<i64_ts>,
+    pub seconds_a: i64_ts,
+    pub seconds_b: i64_ts,
+    /// `seconds_b - seconds_a`; negative when schedule B is quicker.
+    pub difference_seconds: i64_ts,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct RemapScheduleComparison {
+    pub total_seconds_a: i64_ts,
+    pub total_seconds_b: i64_ts,
+    /// `total_seconds_b - total_seconds_a`.
+    pub difference_seconds: i64_ts,
+    /// In the order each cluster first trains under schedule A.
+    pub clusters: Vec<RemapClusterDelta>,
+    pub result_a: SimulationResult,
+    pub result_b: SimulationResult,
+}
+
+type ClusterKey = (Option<i64>, Option<i64>);
+
+fn cluster_seconds(result: &SimulationResult) -> Vec<(ClusterKey, i64)> {
+    let mut out: Vec<(ClusterKey, i64)> = Vec::new();
+    for segment in &result.segments {
+        let key = (segment.primary_attribute_id, segment.secondary_attribute_id);
+        match out.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, seconds)) => *seconds += segment.duration_seconds,
+            None => out.push((key, segment.duration_seconds)),
+        }
+    }
+    out
+}
+
+/// Per-cluster and total differences between two simulations of the same
+/// plan. Pauses count towards the totals but belong to no cluster.
+pub fn compare(result_a: SimulationResult, result_b: SimulationResult) -> RemapScheduleComparison {
+    let seconds_a = cluster_seconds(&result_a);
+    let seconds_b: HashMap<ClusterKey, i64> = cluster_seconds(&result_b).into_iter().collect();
+
+    let mut clusters: Vec<RemapClusterDelta> = seconds_a
+        .iter()
+        .map(|(key, a)| {
+            let b = seconds_b.get(key).copied().unwrap_or(0);
+            RemapClusterDelta {
+                primary_attribute_id: key.0,
+                secondary_attribute_id: key.1,
+                seconds_a: *a,
+                seconds_b: b,
+                difference_seconds: b - a,
+            }
+        })
+        .collect();
+    // Only reachable when the Alpha cap cuts schedule A short.
+    for (key, b) in cluster_seconds(&result_b) {
+        if !seconds_a.iter().any(|(k, _)| *k == key) {
+            clusters.push(RemapClusterDelta {
+                primary_attribute_id: key.0,
+                secondary_attribute_id: key.1,
+                seconds_a: 0,
+                seconds_b: b,
+                difference_seconds: b,
+            });
+        }
+    }
+
+    RemapScheduleComparison {
+        total_seconds_a: result_a.total_seconds,
+        total_seconds_b: result_b.total_seconds,
+        difference_seconds: result_b.total_seconds - result_a.total_seconds,
+        clusters,
+        result_a,
+        result_b,
+    }
+}
+
+/// Simulates `plan_id` once per schedule, with the plan's saved boosters and,
+/// when `character_id` is given, that character's trained SP and clone state.
+pub async fn compare_remap_schedules(
+    pool: &db::Pool,
+    plan_id: i64,
+    schedule_a: Vec<PlannedRemap>,
+    schedule_b: Vec<PlannedRemap>,
+    character_id: Option<i64>,
+) -> Result<RemapScheduleComparison> {
+    let entries = db::skill_plans::get_plan_entries(pool, plan_id).await?;
+
+    let mut current_sp_map = HashMap::new();
+    if let Some(character_id) = character_id {
+        for skill in db::get_character_skills(pool, character_id).await? {
+            current_sp_map.insert(skill.skill_id, skill.skillpoints_in_skill);
+        }
+    }
+    let clone = CloneState::for_character(pool, character_id).await?;
+
+    let boosters = db::planned_boosters::get_plan_boosters(pool, plan_id).await?;
+    let accelerators = simulation::planned_booster_accelerators(&entries, &boosters);
+
+    let profile = |remaps: Vec<PlannedRemap>| SimulationProfile {
+        implants: Attributes::default(),
+        remaps,
+        accelerators: accelerators.clone(),
+        pauses: Vec::new(),
+    };
+    let result_a = simulation::simulate(
+        pool,
+        &entries,
+        profile(schedule_a),
+        Some(&current_sp_map),
+        clone,
+    )
+    .await?;
+    let result_b = simulation::simulate(
+        pool,
+        &entries,
+        profile(schedule_b),
+        Some(&current_sp_map),
+        clone,
+    )
+    .await?;
+
+    Ok(compare(result_a, result_b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skill_plans::simulation::SimulationSegment;
+
+    fn segment(primary: i64, secondary: i64, duration_seconds: i64) -> SimulationSegment {
+        SimulationSegment {
+            entry_index: 0,
+            skill_type_id: 0,
+            level: 1,
+            duration_seconds,
+            start_time_seconds: 0,
+            attributes: Attributes::default(),
+            sp_per_minute: 0.0,
+            primary_attribute_id: Some(primary),
+            secondary_attribute_id: Some(secondary),
+            sp_earned: 0,
+            cumulative_sp: 0,
+        }
+    }
+
+    fn result(segments: Vec<SimulationSegment>) -> SimulationResult {
+        SimulationResult {
+            total_seconds: segments.iter().map(|s| s.duration_seconds).sum(),
+            total_sp: 0,
+            segments,
+            alpha_sp_cap_reached: false,
+            paused_seconds: 0,
+        }
+    }
+
+    #[test]
+    fn sums_each_cluster_under_both_schedules() {
+        let a = result(vec![
+            segment(165, 166, 100),
+            segment(168, 167, 50),
+            segment(165, 166, 20),
+        ]);
+        let b = result(vec![
+            segment(165, 166, 80),
+            segment(168, 167, 60),
+            segment(165, 166, 10),
+        ]);
+
+        let comparison = compare(a, b);
+        assert_eq!(comparison.difference_seconds, 150 - 170);
+        assert_eq!(
+            comparison.clusters,
+            vec![
+                RemapClusterDelta {
+                    primary_attribute_id: Some(165),
+                    secondary_attribute_id: Some(166),
+                    seconds_a: 120,
+                    seconds_b: 90,
+                    difference_seconds: -30,
+                },
+                RemapClusterDelta {
+                    primary_attribute_id: Some(168),
+                    secondary_attribute_id: Some(167),
+                    seconds_a: 50,
+                    seconds_b: 60,
+                    difference_seconds: 10,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,428 @@
+//! Guided single-remap advice for a character: what it will train over the
+//! next few days (the rest of its skill queue, then the chosen plans), the
+//! best remap to take now, and whether holding the remap until training moves
+//! on to a different attribute pair would get more done.
+//!
+//! Everything is measured as SP trained inside the horizon, so a remap that
+//! speeds up the first few days is weighed against one that suits the bulk of
+//! the work.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use super::optimization::{generate_distributions, get_effective_attr_value};
+use super::Attributes;
+use crate::ts_types::{i64_ts, usize_ts};
+use crate::{cache, db, esi, queue_math, utils};
+
+const BASE_ATTRIBUTE: i64 = 17;
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RemapRecommendation {
+    /// The current attributes are already the best single remap.
+    KeepCurrent,
+    RemapNow,
+    /// Train on to [`RemapWaitOption::entry_index`], then remap.
+    WaitForShift,
+}
+
+/// One skill level still to train, in training order.
+#[derive(Debug, Clone)]
+pub struct WizardDemand {
+    pub skill_type_id: i64,
+    pub level: i64,
+    pub primary: Option<i64>,
+    pub secondary: Option<i64>,
+    pub sp: i64,
+}
+
+/// Holding the remap until the work switches attribute pair.
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct RemapWaitOption {
+    pub entry_index: usize_ts,
+    pub skill_type_id: i64_ts,
+    pub level: i64_ts,
+    /// Training time at the current attributes before the remap.
+    pub starts_in_seconds: i64_ts,
+    pub attributes: Attributes,
+    pub sp_per_day: f64,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct RemapWizardResult {
+    pub character_id: i64_ts,
+    pub horizon_days: i64_ts,
+    /// Remap points above the base of 17, as the optimizer uses them.
+    pub current_attributes: Attributes,
+    pub recommended_attributes: Attributes,
+    pub sp_per_day_before: f64,
+    pub sp_per_day_after: f64,
+    pub recommendation: RemapRecommendation,
+    pub wait: Option<RemapWaitOption>,
+    /// A bonus remap is banked or the yearly cooldown has run out.
+    pub remap_available: bool,
+    pub next_remap_at: Option<String>,
+    pub entries_considered: usize_ts,
+}
+
+/// Fixed inputs of one wizard run besides the work itself.
+#[derive(Debug, Clone)]
+pub struct WizardProfile {
+    pub current_remap: Attributes,
+    pub implants: Attributes,
+    pub accelerator_bonus: i64,
+    pub is_omega: bool,
+}
+
+impl WizardProfile {
+    pub(super) fn rate(&self, remap: &Attributes, demand: &WizardDemand) -> f64 {
+        let p = get_effective_attr_value(
+            remap,
+            &self.implants,
+            self.accelerator_bonus,
+            demand.primary,
+        );
+        let s = get_effective_attr_value(
+            remap,
+            &self.implants,
+            self.accelerator_bonus,
+            demand.secondary,
+        );
+        utils::calculate_sp_per_minute(p, s, self.is_omega)
+    }
+
+    /// SP trained from `demands` in `minutes` with `remap`.
+    fn sp_within(&self, demands: &[WizardDemand], remap: &Attributes, minutes: f64) -> f64 {
+        let mut left = minutes;
+        let mut sp = 0.0;
+        for demand in demands {
+            let rate = self.rate(remap, demand);
+            if rate <= 0.0 {
+                continue;
+            }
+            let needed = demand.sp as f64 / rate;
+            if needed >= left {
+                return sp + left * rate;
+            }
+            left -= needed;
+            sp += demand.sp as f64;
+        }
+        sp
+    }
+
+    /// The remap training the most SP from `demands` in `minutes`, and that SP.
+    fn best_remap(&self, demands: &[WizardDemand], minutes: f64) -> (Attributes, f64) {
+        let used: HashSet<i64> = demands
+            .iter()
+            .flat_map(|d| [d.primary, d.secondary])
+            .flatten()
+            .collect();
+        let mut best = (
+            self.current_remap.clone(),
+            self.sp_within(demands, &self.current_remap, minutes),
+        );
+        for dist in generate_distributions(&used) {
+            let sp = self.sp_within(demands, &dist, minutes);
+            if sp > best.1 {
+                best = (dist, sp);
+            }
+        }
+        best
+    }
+}
+
+pub fn advise(
+    character_id: i64,
+    demands: &[WizardDemand],
+    profile: &WizardProfile,
+    horizon_days: i64,
+) -> RemapWizardResult {
+    let horizon_minutes = horizon_days.max(1) as f64 * 24.0 * 60.0;
+    let per_day = |sp: f64| sp / horizon_days.max(1) as f64;
+
+    let before = profile.sp_within(demands, &profile.current_remap, horizon_minutes);
+    let (recommended, after) = profile.best_remap(demands, horizon_minutes);
+
+    // Candidate waits: each point inside the horizon where the attribute pair
+    // changes, trained up to at the current attributes.
+    let mut wait: Option<(RemapWaitOption, f64)> = None;
+    let mut elapsed = 0.0;
+    let mut trained = 0.0;
+    for (index, demand) in demands.iter().enumerate() {
+        if elapsed >= horizon_minutes {
+            break;
+        }
+        let shifts = index > 0 && {
+            let previous = &demands[index - 1];
+            (previous.primary, previous.secondary) != (demand.primary, demand.secondary)
+        };
+        if shifts {
+            let (attributes, rest) =
+                profile.best_remap(&demands[index..], horizon_minutes - elapsed);
+            let total = trained + rest;
+            if total > wait.as_ref().map_or(after, |(_, best)| *best) {
+                wait = Some((
+                    RemapWaitOption {
+                        entry_index: index,
+                        skill_type_id: demand.skill_type_id,
+                        level: demand.level,
+                        starts_in_seconds: (elapsed * 60.0) as i64,
+                        attributes,
+                        sp_per_day: per_day(total),
+                    },
+                    total,
+                ));
+            }
+        }
+        let rate = profile.rate(&profile.current_remap, demand);
+        if rate > 0.0 {
+            elapsed += demand.sp as f64 / rate;
+            trained += demand.sp as f64;
+        }
+    }
+
+    let recommendation = if wait.is_some() {
+        RemapRecommendation::WaitForShift
+    } else if recommended == profile.current_remap || after <= before {
+        RemapRecommendation::KeepCurrent
+    } else {
+        RemapRecommendation::RemapNow
+    };
+
+    RemapWizardResult {
+        character_id,
+        horizon_days,
+        current_attributes: profile.current_remap.clone(),
+        recommended_attributes: recommended,
+        sp_per_day_before: per_day(before),
+        sp_per_day_after: per_day(after),
+        recommendation,
+        wait: wait.map(|(option, _)| option),
+        remap_available: false,
+        next_remap_at: None,
+        entries_considered: demands.len(),
+    }
+}
+
+fn attributes_from(values: [i64; 5]) -> Attributes {
+    // Snapshot order: charisma, intelligence, memory, perception, willpower.
+    Attributes {
+        charisma: values[0],
+        intelligence: values[1],
+        memory: values[2],
+        perception: values[3],
+        willpower: values[4],
+    }
+}
+
+/// The queue still to train, then the plans' entries that are neither
+/// trained, queued nor excluded.
+async fn collect_demands(
+    pool: &db::Pool,
+    character_id: i64,
+    plan_ids: &[i64],
+    now: DateTime<Utc>,
+) -> Result<Vec<WizardDemand>> {
+    let mut sp_map: HashMap<i64, i64> = db::get_character_skills(pool, character_id)
+        .await?
+        .into_iter()
+        .map(|s| (s.skill_id, s.skillpoints_in_skill))
+        .collect();
+
+    let endpoint_path = format!("characters/{}/skillqueue", character_id);
+    let cache_key = cache::build_cache_key(&endpoint_path, character_id);
+    let mut queue = cache::get_cached_response(pool, &cache_key)
+        .await?
+        .and_then(|entry| {
+            serde_json::from_str::<Vec<esi::CharactersSkillqueueSkill>>(&entry.response_body).ok()
+        })
+        .unwrap_or_default();
+    queue.retain(|item| queue_math::is_pending(item, now));
+    queue.sort_by_key(|item| item.queue_position);
+
+    let mut levels: Vec<(i64, i64, Option<i64>)> = queue
+        .iter()
+        .map(|item| {
+            if let Some(sp) = queue_math::training_sp_at(item, now) {
+                sp_map.insert(item.skill_id, sp as i64);
+            }
+            (item.skill_id, item.finished_level, item.level_end_sp)
+        })
+        .collect();
+    for plan_id in plan_ids {
+        levels.extend(
+            db::skill_plans::get_plan_entries(pool, *plan_id)
+                .await?
+                .into_iter()
+                .filter(|e| !e.excluded)
+                .map(|e| (e.skill_type_id, e.planned_level, None)),
+        );
+    }
+
+    let skill_ids: Vec<i64> = levels.iter().map(|(id, _, _)| *id).collect();
+    let skill_attributes = utils::get_skill_attributes(pool, &skill_ids)
+        .await
+        .map_err(anyhow::Error::msg)?;
+
+    let mut demands = Vec::new();
+    for (skill_type_id, level, end_sp) in levels {
+        let attrs = skill_attributes.get(&skill_type_id);
+        let target = end_sp.unwrap_or_else(|| {
+            let rank = attrs.and_then(|a| a.rank).unwrap_or(1);
+            utils::calculate_sp_for_level(rank, level as i32)
+        });
+        let current = sp_map.get(&skill_type_id).copied().unwrap_or(0);
+        if target <= current {
+            continue;
+        }
+        sp_map.insert(skill_type_id, target);
+        demands.push(WizardDemand {
+            skill_type_id,
+            level,
+            primary: attrs.and_then(|a| a.primary_attribute),
+            secondary: attrs.and_then(|a| a.secondary_attribute),
+            sp: target - current,
+        });
+    }
+    Ok(demands)
+}
+
+/// The character's remap, implants and accelerator as of its latest
+/// attribute snapshot.
+pub(super) async fn load_profile(pool: &db::Pool, character_id: i64) -> Result<WizardProfile> {
+    let Some(character) = db::get_character(pool, character_id).await? else {
+        bail!("Character {} not found", character_id);
+    };
+    let Some(snapshot) = db::attribute_history::get_attribute_history(pool, character_id)
+        .await?
+        .into_iter()
+        .next()
+    else {
+        bail!("No attribute history for this character yet; refresh it first");
+    };
+
+    let observed = snapshot.observed();
+    let allocation: [i64; 5] = std::array::from_fn(|i| {
+        observed.totals[i] - observed.implants[i] - observed.accelerator - BASE_ATTRIBUTE
+    });
+    Ok(WizardProfile {
+        current_remap: attributes_from(allocation),
+        implants: attributes_from(observed.implants),
+        accelerator_bonus: observed.accelerator,
+        is_omega: character.is_omega,
+    })
+}
+
+pub async fn remap_wizard(
+    pool: &db::Pool,
+    character_id: i64,
+    plan_ids: &[i64],
+    horizon_days: i64,
+) -> Result<RemapWizardResult> {
+    let Some(attributes) = db::get_character_attributes(pool, character_id).await? else {
+        bail!("No attributes stored for this character yet; refresh it first");
+    };
+    let profile = load_profile(pool, character_id).await?;
+
+    let now = Utc::now();
+    let demands = collect_demands(pool, character_id, plan_ids, now).await?;
+    let mut result = advise(character_id, &demands, &profile, horizon_days);
+
+    let cooldown_ends = attributes
+        .accrued_remap_cooldown_date
+        .as_deref()
+        .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+        .map(|d| d.with_timezone(&Utc))
+        .filter(|d| *d > now);
+    result.remap_available = attributes.bonus_remaps.unwrap_or(0) > 0 || cooldown_ends.is_none();
+    result.next_remap_at = cooldown_ends
+        .filter(|_| !result.remap_available)
+        .map(|d| d.to_rfc3339());
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INT: i64 = 165;
+    const MEM: i64 = 166;
+    const PER: i64 = 167;
+    const WIL: i64 = 168;
+
+    fn demand(skill_type_id: i64, pair: (i64, i64), sp: i64) -> WizardDemand {
+        WizardDemand {
+            skill_type_id,
+            level: 5,
+            primary: Some(pair.0),
+            secondary: Some(pair.1),
+            sp,
+        }
+    }
+
+    fn profile(current_remap: Attributes) -> WizardProfile {
+        WizardProfile {
+            current_remap,
+            implants: Attributes::default(),
+            accelerator_bonus: 0,
+            is_omega: true,
+        }
+    }
+
+    fn remap(intelligence: i64, memory: i64, perception: i64, willpower: i64) -> Attributes {
+        Attributes {
+            intelligence,
+            memory,
+            perception,
+            willpower,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn remaps_towards_the_work_in_the_horizon() {
+        let demands = [demand(1, (INT, MEM), 10_000_000)];
+
+        let result = advise(1, &demands, &profile(remap(0, 0, 10, 4)), 30);
+
+        assert_eq!(result.recommendation, RemapRecommendation::RemapNow);
+        assert_eq!(result.recommended_attributes, remap(10, 4, 0, 0));
+        assert!(result.sp_per_day_after > result.sp_per_day_before);
+        assert!(result.wait.is_none());
+    }
+
+    #[test]
+    fn keeps_a_remap_that_already_fits() {
+        let demands = [demand(1, (INT, MEM), 10_000_000)];
+
+        let result = advise(1, &demands, &profile(remap(10, 4, 0, 0)), 30);
+
+        assert_eq!(result.recommendation, RemapRecommendation::KeepCurrent);
+        assert_eq!(result.sp_per_day_after, result.sp_per_day_before);
+    }
+
+    #[test]
+    fn waits_when_the_current_remap_suits_what_trains_first() {
+        // About 20 days of Int/Mem at 10/4, then far more Per/Wil.
+        let demands = [
+            demand(1, (INT, MEM), 1_000_000),
+            demand(2, (PER, WIL), 10_000_000),
+        ];
+
+        let result = advise(1, &demands, &profile(remap(10, 4, 0, 0)), 60);
+
+        assert_eq!(result.recommendation, RemapRecommendation::WaitForShift);
+        let wait = result.wait.unwrap();
+        assert_eq!(wait.entry_index, 1);
+        assert_eq!(wait.attributes, remap(0, 0, 10, 4));
+        assert!(wait.sp_per_day > result.sp_per_day_after);
+    }
+}
@@ -1,4 +1,6 @@
 use crate::db;
+use crate::db::planned_boosters::PlannedBooster;
+use crate::skill_plans::alpha::CloneState;
 use crate::skill_plans::{Attributes, PlannedRemap};
 use crate::ts_types::{i64_ts, usize_ts};
 use crate::utils;
@@ -13,7 +15,11 @@ const BASE_ATTRIBUTE: i64 = 17;
 pub struct SimulationProfile {
     pub implants: Attributes,
     pub remaps: Vec<PlannedRemap>,
+    /// Accelerators on top of the plan's saved [`PlannedBooster`]s, which
+    /// `simulate_skill_plan` adds itself.
     pub accelerators: Vec<PlannedAccelerator>,
+    #[serde(default)]
+    pub pauses: Vec<TrainingPause>,
 }
 
 #[typeshare]
@@ -24,12 +30,25 @@ pub struct PlannedAccelerator {
     pub duration_seconds: i64_ts,
 }
 
+/// A stretch with no training, such as a planned account lapse. Offsets count
+/// from the start of the simulation; accelerators keep running through it.
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrainingPause {
+    pub start_seconds: i64_ts,
+    pub duration_seconds: i64_ts,
+}
+
 #[typeshare]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationResult {
     pub total_seconds: i64_ts,
     pub total_sp: i64_ts,
     pub segments: Vec<SimulationSegment>,
+    /// The Alpha SP cap stopped training before the end of the plan.
+    pub alpha_sp_cap_reached: bool,
+    /// Part of `total_seconds` spent in [`TrainingPause`]s.
+    pub paused_seconds: i64_ts,
 }
 
 #[typeshare]
@@ -53,6 +72,7 @@ pub async fn simulate(
     entries: &[crate::db::skill_plans::SkillPlanEntry],
     profile: SimulationProfile,
     current_sp_map: Option<&HashMap<i64, i64>>,
+    clone: CloneState,
 ) -> anyhow::Result<SimulationResult> {
     let skill_type_ids: Vec<i64> = entries.iter().map(|e| e.skill_type_id).collect();
     let skill_attributes = utils::get_skill_attributes(pool, &skill_type_ids)
@@ -69,9 +89,12 @@ pub async fn simulate(
     if let Some(map) = current_sp_map {
         simulated_sp = map.clone();
     }
+    super::credit_excluded_entries(entries, &skill_attributes, &mut simulated_sp);
 
     let mut current_remap_offsets = Attributes::default();
     let mut total_sp_earned: i64 = 0;
+    let sp_room = clone.sp_room();
+    let mut alpha_sp_cap_reached = false;
 
     // Sort remaps and accelerators by entry_index for easier processing
     let mut remaps = profile.remaps;
@@ -82,6 +105,15 @@ pub async fn simulate(
     accelerators.sort_by_key(|a| a.entry_index);
     let mut next_accel_idx = 0;
 
+    // (start, end) of each pause
+    let pauses: Vec<(i64, i64)> = profile
+        .pauses
+        .iter()
+        .filter(|p| p.duration_seconds > 0)
+        .map(|p| (p.start_seconds, p.start_seconds + p.duration_seconds))
+        .collect();
+    let mut paused_seconds: i64 = 0;
+
     for (idx, entry) in entries.iter().enumerate() {
         // 1. Check for remap at this entry
         while next_remap_idx < remaps.len() && remaps[next_remap_idx].entry_index == idx {
@@ -106,8 +138,24 @@ pub async fn simulate(
 
         let mut current_sp = *simulated_sp.get(&entry.skill_type_id).unwrap_or(&0);
         let mut sp_remaining = (total_sp_needed - current_sp).max(0);
+        if let Some(room) = sp_room {
+            let left = (room - total_sp_earned).max(0);
+            if sp_remaining > left {
+                sp_remaining = left;
+                alpha_sp_cap_reached = true;
+            }
+        }
 
         while sp_remaining > 0 {
+            // Wait out any pause we are in
+            while let Some(&(_, end)) = pauses
+                .iter()
+                .find(|(start, end)| *start <= current_time && current_time < *end)
+            {
+                paused_seconds += end - current_time;
+                current_time = end;
+            }
+
             // Calculate current effective attributes
             active_accelerators.retain(|(end_time, _)| *end_time > current_time);
             let accel_bonus: i64 = active_accelerators.iter().map(|(_, b)| *b).sum();
@@ -137,13 +185,15 @@ pub async fn simulate(
 
             let primary_val = get_attr_value(&effective_attrs, skill_attr.primary_attribute);
             let secondary_val = get_attr_value(&effective_attrs, skill_attr.secondary_attribute);
-            let sp_per_min = utils::calculate_sp_per_minute(primary_val, secondary_val, true);
+            let sp_per_min =
+                utils::calculate_sp_per_minute(primary_val, secondary_val, clone.is_omega());
             let sp_per_sec = sp_per_min / 60.0;
 
             // Determine how long this segment lasts
             // Segment ends when:
             // - Skill is finished
             // - Next accelerator expires
+            // - Next pause starts
             let mut duration = (sp_remaining as f64 / sp_per_sec).ceil() as i64;
 
             if let Some(next_expiry) = active_accelerators.iter().map(|(t, _)| *t).min() {
@@ -153,6 +203,15 @@ pub async fn simulate(
                 }
             }
 
+            if let Some(next_pause) = pauses
+                .iter()
+                .map(|(start, _)| *start)
+                .filter(|start| *start > current_time)
+                .min()
+            {
+                duration = duration.min(next_pause - current_time);
+            }
+
             let sp_gained = (sp_per_sec * duration as f64) as i64;
             let actual_sp_gained = sp_gained.min(sp_remaining);
 
@@ -189,15 +248,60 @@ pub async fn simulate(
             }
         }
         simulated_sp.insert(entry.skill_type_id, current_sp);
+        if alpha_sp_cap_reached {
+            break;
+        }
     }
 
     Ok(SimulationResult {
         total_seconds: current_time,
         total_sp: total_sp_earned,
         segments,
+        alpha_sp_cap_reached,
+        paused_seconds,
     })
 }
 
+/// Index of the entry that follows the anchor `(skill_type_id, level)`, the
+/// form plan remaps and boosters are stored in; no anchor is the plan start.
+/// `None` when the anchor entry is no longer in the plan.
+pub fn anchored_entry_index(
+    entries: &[crate::db::skill_plans::SkillPlanEntry],
+    after_skill_type_id: Option<i64>,
+    after_skill_level: Option<i64>,
+) -> Option<usize> {
+    match (after_skill_type_id, after_skill_level) {
+        (Some(skill_type_id), Some(level)) => entries
+            .iter()
+            .position(|e| e.skill_type_id == skill_type_id && e.planned_level == level)
+            .map(|i| i + 1),
+        _ => Some(0),
+    }
+}
+
+/// Place each booster before the entry that follows its anchor. Boosters whose
+/// anchor entry is no longer in the plan are left out.
+pub fn planned_booster_accelerators(
+    entries: &[crate::db::skill_plans::SkillPlanEntry],
+    boosters: &[PlannedBooster],
+) -> Vec<PlannedAccelerator> {
+    boosters
+        .iter()
+        .filter_map(|booster| {
+            let entry_index = anchored_entry_index(
+                entries,
+                booster.after_skill_type_id,
+                booster.after_skill_level,
+            )?;
+            Some(PlannedAccelerator {
+                entry_index,
+                bonus: booster.bonus,
+                duration_seconds: booster.duration_seconds,
+            })
+        })
+        .collect()
+}
+
 fn get_attr_value(attrs: &Attributes, attr_id: Option<i64>) -> i64 {
     match attr_id {
         Some(164) => attrs.charisma,
@@ -0,0 +1,210 @@
+//! EVEMon-style plan sorting presets. Each preset only supplies an ordering
+//! key; [`PlanDag::topological_sort_by_key`] keeps every prerequisite ahead
+//! of the skills needing it, and the current position breaks ties so a
+//! re-sort never shuffles entries the preset considers equal.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use super::graph::{PlanDag, PlanNode};
+use crate::{db, queue_math, utils};
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PlanSortPreset {
+    /// Quickest levels first, using the character's attributes when given.
+    TrainingTime,
+    /// Lowest rank first.
+    Rank,
+    /// Skills sharing a primary/secondary attribute pair kept together, so
+    /// one remap covers a run of entries.
+    AttributeCluster,
+    /// The entry's priority, 1 first.
+    Priority,
+}
+
+/// What a preset may order an entry by.
+#[derive(Debug, Clone)]
+pub struct SortableEntry {
+    pub node: PlanNode,
+    pub rank: i64,
+    pub attributes: (i64, i64),
+    pub priority: i64,
+    pub training_seconds: i64,
+}
+
+/// Plan nodes in preset order, prerequisites first. `entries` are in the
+/// plan's current order.
+pub fn sort_entries(
+    dag: &PlanDag,
+    entries: &[SortableEntry],
+    preset: PlanSortPreset,
+) -> Vec<PlanNode> {
+    let position: HashMap<PlanNode, usize> = entries
+        .iter()
+        .enumerate()
+        .map(|(i, e)| (e.node, i))
+        .collect();
+    let by_node: HashMap<PlanNode, &SortableEntry> = entries.iter().map(|e| (e.node, e)).collect();
+
+    // Clusters are ordered by where their first entry currently sits.
+    let mut cluster_order: HashMap<(i64, i64), usize> = HashMap::new();
+    for entry in entries {
+        let next = cluster_order.len();
+        cluster_order.entry(entry.attributes).or_insert(next);
+    }
+
+    dag.topological_sort_by_key(|node| {
+        let index = position.get(node).copied().unwrap_or(usize::MAX);
+        let Some(entry) = by_node.get(node) else {
+            return (i64::MAX, index);
+        };
+        let primary = match preset {
+            PlanSortPreset::TrainingTime => entry.training_seconds,
+            PlanSortPreset::Rank => entry.rank,
+            PlanSortPreset::AttributeCluster => cluster_order[&entry.attributes] as i64,
+            PlanSortPreset::Priority => entry.priority,
+        };
+        (primary, index)
+    })
+}
+
+/// Entry ids of `plan_id` in preset order, ready for
+/// [`db::skill_plans::reorder_plan_entries`].
+pub async fn sort_plan(
+    pool: &db::Pool,
+    plan_id: i64,
+    preset: PlanSortPreset,
+    character_id: Option<i64>,
+) -> anyhow::Result<Vec<i64>> {
+    let plan_entries = db::skill_plans::get_plan_entries(pool, plan_id).await?;
+    let (dag, _) = PlanDag::build_from_plan(pool, plan_id).await?;
+
+    let skill_ids: Vec<i64> = plan_entries.iter().map(|e| e.skill_type_id).collect();
+    let skill_attrs = utils::get_skill_attributes(pool, &skill_ids)
+        .await
+        .map_err(anyhow::Error::msg)?;
+    let character_attrs = match character_id {
+        Some(id) => db::get_character_attributes(pool, id).await?,
+        None => None,
+    };
+
+    let entries: Vec<SortableEntry> = plan_entries
+        .iter()
+        .map(|e| {
+            let skill = skill_attrs.get(&e.skill_type_id);
+            let rank = skill.and_then(|s| s.rank).unwrap_or(1);
+            let sp = utils::sp_for_level_slice(rank, e.planned_level as i32);
+            // Without attributes every skill trains at the same rate, so SP
+            // alone orders by time.
+            let training_seconds =
+                match queue_math::sp_per_minute(character_attrs.as_ref(), skill, true) {
+                    Some(rate) if rate > 0.0 => (sp as f64 / rate * 60.0) as i64,
+                    _ => sp,
+                };
+            SortableEntry {
+                node: PlanNode {
+                    skill_type_id: e.skill_type_id,
+                    level: e.planned_level,
+                },
+                rank,
+                attributes: skill
+                    .map(|s| {
+                        (
+                            s.primary_attribute.unwrap_or(0),
+                            s.secondary_attribute.unwrap_or(0),
+                        )
+                    })
+                    .unwrap_or((0, 0)),
+                priority: e.priority,
+                training_seconds,
+            }
+        })
+        .collect();
+
+    let entry_ids: HashMap<PlanNode, i64> = plan_entries
+        .iter()
+        .map(|e| {
+            (
+                PlanNode {
+                    skill_type_id: e.skill_type_id,
+                    level: e.planned_level,
+                },
+                e.entry_id,
+            )
+        })
+        .collect();
+
+    let sorted: Vec<i64> = sort_entries(&dag, &entries, preset)
+        .into_iter()
+        .filter_map(|node| entry_ids.get(&node).copied())
+        .collect();
+    if sorted.len() != plan_entries.len() {
+        anyhow::bail!("the plan has circular prerequisites");
+    }
+    Ok(sorted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(skill_type_id: i64, level: i64) -> PlanNode {
+        PlanNode {
+            skill_type_id,
+            level,
+        }
+    }
+
+    fn entry(node: PlanNode, rank: i64, attributes: (i64, i64), priority: i64) -> SortableEntry {
+        SortableEntry {
+            node,
+            rank,
+            attributes,
+            priority,
+            training_seconds: rank * 1000,
+        }
+    }
+
+    /// Skill 1 (rank 8) is a prerequisite of skill 2 (rank 1); skill 3
+    /// (rank 3) stands alone.
+    fn plan() -> (PlanDag, Vec<SortableEntry>) {
+        let (a, b, c) = (node(1, 1), node(2, 1), node(3, 1));
+        let mut dag = PlanDag::new();
+        dag.nodes.extend([a, b, c]);
+        dag.dependencies.entry(b).or_default().insert(a);
+        dag.dependents.entry(a).or_default().insert(b);
+        let entries = vec![
+            entry(a, 8, (165, 166), 1),
+            entry(b, 1, (167, 168), 1),
+            entry(c, 3, (165, 166), 2),
+        ];
+        (dag, entries)
+    }
+
+    #[test]
+    fn presets_reorder_within_prerequisites() {
+        let (dag, entries) = plan();
+
+        // Skill 2 has the lowest rank but must wait for skill 1.
+        assert_eq!(
+            sort_entries(&dag, &entries, PlanSortPreset::Rank),
+            vec![node(3, 1), node(1, 1), node(2, 1)]
+        );
+        assert_eq!(
+            sort_entries(&dag, &entries, PlanSortPreset::TrainingTime),
+            vec![node(3, 1), node(1, 1), node(2, 1)]
+        );
+        assert_eq!(
+            sort_entries(&dag, &entries, PlanSortPreset::AttributeCluster),
+            vec![node(1, 1), node(3, 1), node(2, 1)]
+        );
+        assert_eq!(
+            sort_entries(&dag, &entries, PlanSortPreset::Priority),
+            vec![node(1, 1), node(2, 1), node(3, 1)]
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! What-if runs of a plan: the plan's timeline with the character's training
+//! setup changed in some way, for an interactive panel. Nothing is saved.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use super::alpha::CloneState;
+use super::simulation::{self, PlannedAccelerator, SimulationProfile, TrainingPause};
+use super::{remap_wizard, Attributes, PlannedRemap};
+use crate::db;
+use crate::ts_types::{i64_ts, usize_ts};
+
+/// Accelerator bonus assumed to last the whole plan when no duration is given.
+const OPEN_ENDED_SECONDS: i64 = 10 * 365 * 86_400;
+
+/// Changes to the character's current setup. Unset fields keep what the
+/// character has now, or nothing when no character is given.
+#[typeshare]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WhatIfOverrides {
+    /// Remap points above the base of 17.
+    pub remap: Option<Attributes>,
+    pub implants: Option<Attributes>,
+    pub accelerator_bonus: Option<i64_ts>,
+    /// How long the accelerator lasts from the start; unset means all plan.
+    pub accelerator_duration_seconds: Option<i64_ts>,
+    /// Replaces the plan's saved remaps when set.
+    pub remaps: Option<Vec<PlannedRemap>>,
+    #[serde(default)]
+    pub pauses: Vec<TrainingPause>,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct WhatIfEntry {
+    pub entry_id: i64_ts,
+    pub skill_type_id: i64_ts,
+    pub level: i64_ts,
+    /// Both `None` when the character already has the level.
+    pub start_date: Option<String>,
+    pub finish_date: Option<String>,
+    pub training_seconds: i64_ts,
+}
+
+/// A remap taken before `entry_index`, at `date`.
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct WhatIfRemapPoint {
+    pub entry_index: usize_ts,
+    pub date: String,
+    pub attributes: Attributes,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct WhatIfTimeline {
+    pub entries: Vec<WhatIfEntry>,
+    pub remap_points: Vec<WhatIfRemapPoint>,
+    pub total_seconds: i64_ts,
+    pub finish_date: String,
+    pub paused_seconds: i64_ts,
+    pub alpha_sp_cap_reached: bool,
+}
+
+pub async fn simulate_what_if(
+    pool: &db::Pool,
+    plan_id: i64,
+    character_id: Option<i64>,
+    overrides: WhatIfOverrides,
+) -> Result<WhatIfTimeline> {
+    let entries = db::skill_plans::get_plan_entries(pool, plan_id).await?;
+
+    let mut current_sp_map = HashMap::new();
+    if let Some(character_id) = character_id {
+        for skill in db::get_character_skills(pool, character_id).await? {
+            current_sp_map.insert(skill.skill_id, skill.skillpoints_in_skill);
+        }
+    }
+    let clone = CloneState::for_character(pool, character_id).await?;
+
+    let needs_current = overrides.remap.is_none()
+        || overrides.implants.is_none()
+        || overrides.accelerator_bonus.is_none();
+    let current = match character_id {
+        Some(character_id) if needs_current => {
+            Some(remap_wizard::load_profile(pool, character_id).await?)
+        }
+        _ => None,
+    };
+
+    let remap = overrides
+        .remap
+        .or_else(|| current.as_ref().map(|c| c.current_remap.clone()))
+        .unwrap_or_default();
+    let implants = overrides
+        .implants
+        .or_else(|| current.as_ref().map(|c| c.implants.clone()))
+        .unwrap_or_default();
+    let accelerator_bonus = overrides
+        .accelerator_bonus
+        .or_else(|| current.as_ref().map(|c| c.accelerator_bonus))
+        .unwrap_or(0);
+
+    let plan_remaps = match overrides.remaps {
+        Some(remaps) => remaps,
+        None => db::remaps::get_plan_remaps(pool, plan_id)
+            .await?
+            .iter()
+            .filter_map(|r| {
+                let entry_index = simulation::anchored_entry_index(
+                    &entries,
+                    r.after_skill_type_id,
+                    r.after_skill_level,
+                )?;
+                Some(PlannedRemap {
+                    entry_index,
+                    attributes: r.attributes(),
+                })
+            })
+            .collect(),
+    };
+
+    let mut accelerators = Vec::new();
+    if accelerator_bonus > 0 {
+        accelerators.push(PlannedAccelerator {
+            entry_index: 0,
+            bonus: accelerator_bonus,
+            duration_seconds: overrides
+                .accelerator_duration_seconds
+                .unwrap_or(OPEN_ENDED_SECONDS),
+        });
+    }
+    let boosters = db::planned_boosters::get_plan_boosters(pool, plan_id).await?;
+    accelerators.extend(simulation::planned_booster_accelerators(
+        &entries, &boosters,
+    ));
+
+    // The starting remap goes first so a saved remap at the plan start wins.
+    let mut remaps = vec![PlannedRemap {
+        entry_index: 0,
+        attributes: remap,
+    }];
+    remaps.extend(plan_remaps.iter().cloned());
+
+    let profile = SimulationProfile {
+        implants,
+        remaps,
+        accelerators,
+        pauses: overrides.pauses,
+    };
+    let result =
+        simulation::simulate(pool, &entries, profile, Some(&current_sp_map), clone).await?;
+
+    Ok(timeline(
+        &entries,
+        &plan_remaps,
+        &result.segments,
+        result.total_seconds,
+        result.paused_seconds,
+        result.alpha_sp_cap_reached,
+        Utc::now(),
+    ))
+}
+
+fn timeline(
+    entries: &[db::skill_plans::SkillPlanEntry],
+    plan_remaps: &[PlannedRemap],
+    segments: &[simulation::SimulationSegment],
+    total_seconds: i64,
+    paused_seconds: i64,
+    alpha_sp_cap_reached: bool,
+    start: DateTime<Utc>,
+) -> WhatIfTimeline {
+    let at = |seconds: i64| (start + Duration::seconds(seconds)).to_rfc3339();
+
+    let timeline_entries = entries
+        .iter()
+        .enumerate()
+        .map(|(idx, entry)| {
+            let mut entry_segments = segments.iter().filter(|s| s.entry_index == idx);
+            let first = entry_segments.next();
+            let last = entry_segments.last().or(first);
+            let training_seconds = segments
+                .iter()
+                .filter(|s| s.entry_index == idx)
+                .map(|s| s.duration_seconds)
+                .sum();
+            WhatIfEntry {
+                entry_id: entry.entry_id,
+                skill_type_id: entry.skill_type_id,
+                level: entry.planned_level,
+                start_date: first.map(|s| at(s.start_time_seconds)),
+                finish_date: last.map(|s| at(s.start_time_seconds + s.duration_seconds)),
+                training_seconds,
+            }
+        })
+        .collect();
+
+    // A remap happens when the first entry at or after it starts training.
+    let remap_points = plan_remaps
+        .iter()
+        .map(|remap| {
+            let seconds = segments
+                .iter()
+                .find(|s| s.entry_index >= remap.entry_index)
+                .map_or(total_seconds, |s| s.start_time_seconds);
+            WhatIfRemapPoint {
+                entry_index: remap.entry_index,
+                date: at(seconds),
+                attributes: remap.attributes.clone(),
+            }
+        })
+        .collect();
+
+    WhatIfTimeline {
+        entries: timeline_entries,
+        remap_points,
+        total_seconds,
+        finish_date: at(total_seconds),
+        paused_seconds,
+        alpha_sp_cap_reached,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::{fixtures, TestDb};
+
+    #[tokio::test]
+    async fn what_if_timeline_chains_entries_and_remaps() {
+        let db = TestDb::new_with_sde().await.unwrap();
+        let plan = fixtures::create_skill_plan(&db.pool, "What if").await;
+        fixtures::add_plan_entry(&db.pool, plan, 3327, 1, "Planned").await;
+        fixtures::add_plan_entry(&db.pool, plan, 3327, 2, "Planned").await;
+
+        let per_wil = Attributes {
+            perception: 10,
+            willpower: 4,
+            ..Attributes::default()
+        };
+        let overrides = WhatIfOverrides {
+            remaps: Some(vec![PlannedRemap {
+                entry_index: 1,
+                attributes: per_wil,
+            }]),
+            ..WhatIfOverrides::default()
+        };
+        let slow = simulate_what_if(&db.pool, plan, None, overrides.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(slow.entries.len(), 2);
+        assert_eq!(slow.entries[1].start_date, slow.entries[0].finish_date);
+        assert_eq!(
+            Some(slow.remap_points[0].date.clone()),
+            slow.entries[1].start_date
+        );
+        assert_eq!(Some(slow.finish_date.clone()), slow.entries[1].finish_date);
+
+        let boosted = simulate_what_if(
+            &db.pool,
+            plan,
+            None,
+            WhatIfOverrides {
+                accelerator_bonus: Some(10),
+                ..overrides
+            },
+        )
+        .await
+        .unwrap();
+        assert!(boosted.total_seconds < slow.total_seconds);
+    }
+}
@@ -0,0 +1,265 @@
+//! The character sheet's skill overview: level V skills per group, how far a
+//! character has specialized in each weapon system and ship class, and which
+//! skills are closest to level V. Everything comes from the stored skills and
+//! the SDE, so it needs no ESI call.
+//!
+//! A specialty is one skill in a weapon or ship group (say Large Projectile
+//! Turret or Minmatar Cruiser) together with the skills in the same group that
+//! require it directly (its specialization, the next hull size up). Its score
+//! is the share of those skills' levels the character has trained.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::FromRow;
+use typeshare::typeshare;
+
+use crate::ts_types::i64_ts;
+use crate::{db, utils};
+
+/// Skill groups whose skills count as specialties.
+pub(crate) const SPECIALTY_GROUPS: [&str; 4] =
+    ["Gunnery", "Missiles", "Drones", "Spaceship Command"];
+
+const LEVEL_FIVE_CANDIDATES: usize = 10;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct SkillRow {
+    pub skill_id: i64,
+    pub skill_name: String,
+    pub group_id: i64,
+    pub group_name: String,
+    /// 0 for skills the character has not injected.
+    pub trained_skill_level: i64,
+    pub skillpoints_in_skill: i64,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GroupLevelFive {
+    pub group_id: i64_ts,
+    pub group_name: String,
+    pub level_five: i64_ts,
+    pub skill_count: i64_ts,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpecialistScore {
+    pub skill_id: i64_ts,
+    pub skill_name: String,
+    pub group_name: String,
+    pub trained_levels: i64_ts,
+    pub total_levels: i64_ts,
+    /// `trained_levels` as a percentage of `total_levels`.
+    pub score: f64,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LevelFiveCandidate {
+    pub skill_id: i64_ts,
+    pub skill_name: String,
+    pub skillpoints_in_skill: i64_ts,
+    pub sp_to_level_five: i64_ts,
+}
+
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SkillSummary {
+    pub character_id: i64_ts,
+    pub level_five_total: i64_ts,
+    /// Groups with at least one skill at V, most first.
+    pub level_five_by_group: Vec<GroupLevelFive>,
+    /// Specialties the character has started, highest score first.
+    pub specialist_scores: Vec<SpecialistScore>,
+    /// Level IV skills, least SP to go first.
+    pub level_five_candidates: Vec<LevelFiveCandidate>,
+}
+
+/// Every published skill with the character's level and SP in it.
+pub async fn load_skill_rows(pool: &db::Pool, character_id: i64) -> Result<Vec<SkillRow>> {
+    let skills = sqlx::query_as::<_, SkillRow>(
+        "SELECT t.type_id AS skill_id, t.name AS skill_name, g.group_id, g.name AS group_name,
+                COALESCE(cs.trained_skill_level, 0) AS trained_skill_level,
+                COALESCE(cs.skillpoints_in_skill, 0) AS skillpoints_in_skill
+         FROM sde_types t
+         JOIN sde_groups g ON g.group_id = t.group_id
+         LEFT JOIN character_skills cs
+           ON cs.skill_id = t.type_id AND cs.character_id = ?
+         WHERE t.category_id = 16 AND t.published = 1
+         ORDER BY t.name",
+    )
+    .bind(character_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(skills)
+}
+
+pub async fn skill_summary(pool: &db::Pool, character_id: i64) -> Result<SkillSummary> {
+    let skills = load_skill_rows(pool, character_id).await?;
+
+    let requirements: Vec<(i64, i64)> = sqlx::query_as(
+        "SELECT DISTINCT skill_type_id, required_skill_id FROM sde_skill_requirements",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let level_four: Vec<i64> = skills
+        .iter()
+        .filter(|s| s.trained_skill_level == 4)
+        .map(|s| s.skill_id)
+        .collect();
+    let ranks: HashMap<i64, i64> = utils::get_skill_attributes(pool, &level_four)
+        .await
+        .map_err(anyhow::Error::msg)?
+        .into_iter()
+        .map(|(id, attrs)| (id, attrs.rank.unwrap_or(1)))
+        .collect();
+
+    Ok(summarize(character_id, &skills, &requirements, &ranks))
+}
+
+/// `requirements` holds (skill, directly required skill) pairs.
+pub fn summarize(
+    character_id: i64,
+    skills: &[SkillRow],
+    requirements: &[(i64, i64)],
+    ranks: &HashMap<i64, i64>,
+) -> SkillSummary {
+    let mut groups: HashMap<i64, GroupLevelFive> = HashMap::new();
+    for skill in skills {
+        let group = groups
+            .entry(skill.group_id)
+            .or_insert_with(|| GroupLevelFive {
+                group_id: skill.group_id,
+                group_name: skill.group_name.clone(),
+                level_five: 0,
+                skill_count: 0,
+            });
+        group.skill_count += 1;
+        if skill.trained_skill_level >= 5 {
+            group.level_five += 1;
+        }
+    }
+    let mut level_five_by_group: Vec<GroupLevelFive> =
+        groups.into_values().filter(|g| g.level_five > 0).collect();
+    level_five_by_group.sort_by(|a, b| {
+        b.level_five
+            .cmp(&a.level_five)
+            .then_with(|| a.group_name.cmp(&b.group_name))
+    });
+    let level_five_total = level_five_by_group.iter().map(|g| g.level_five).sum();
+
+    let by_id: HashMap<i64, &SkillRow> = skills.iter().map(|s| (s.skill_id, s)).collect();
+    let mut specialist_scores: Vec<SpecialistScore> = skills
+        .iter()
+        .filter(|s| SPECIALTY_GROUPS.contains(&s.group_name.as_str()) && s.trained_skill_level > 0)
+        .map(|base| {
+            let dependents = requirements
+                .iter()
+                .filter(|(_, required)| *required == base.skill_id)
+                .filter_map(|(skill, _)| by_id.get(skill))
+                .filter(|s| s.group_id == base.group_id);
+            let members: Vec<&SkillRow> = std::iter::once(base).chain(dependents).collect();
+            let trained_levels: i64 = members.iter().map(|s| s.trained_skill_level).sum();
+            let total_levels = 5 * members.len() as i64;
+            SpecialistScore {
+                skill_id: base.skill_id,
+                skill_name: base.skill_name.clone(),
+                group_name: base.group_name.clone(),
+                trained_levels,
+                total_levels,
+                score: 100.0 * trained_levels as f64 / total_levels as f64,
+            }
+        })
+        .collect();
+    specialist_scores.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| b.total_levels.cmp(&a.total_levels))
+            .then_with(|| a.skill_name.cmp(&b.skill_name))
+    });
+
+    let mut level_five_candidates: Vec<LevelFiveCandidate> = skills
+        .iter()
+        .filter(|s| s.trained_skill_level == 4)
+        .map(|s| {
+            let rank = ranks.get(&s.skill_id).copied().unwrap_or(1);
+            LevelFiveCandidate {
+                skill_id: s.skill_id,
+                skill_name: s.skill_name.clone(),
+                skillpoints_in_skill: s.skillpoints_in_skill,
+                sp_to_level_five: utils::sp_needed(rank, s.skillpoints_in_skill, 5),
+            }
+        })
+        .collect();
+    level_five_candidates.sort_by_key(|c| c.sp_to_level_five);
+    level_five_candidates.truncate(LEVEL_FIVE_CANDIDATES);
+
+    SkillSummary {
+        character_id,
+        level_five_total,
+        level_five_by_group,
+        specialist_scores,
+        level_five_candidates,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skill(id: i64, name: &str, group: (i64, &str), level: i64, sp: i64) -> SkillRow {
+        SkillRow {
+            skill_id: id,
+            skill_name: name.to_string(),
+            group_id: group.0,
+            group_name: group.1.to_string(),
+            trained_skill_level: level,
+            skillpoints_in_skill: sp,
+        }
+    }
+
+    #[test]
+    fn summarizes_level_fives_specialties_and_candidates() {
+        let gunnery = (255, "Gunnery");
+        let science = (270, "Science");
+        let skills = [
+            skill(1, "Large Projectile Turret", gunnery, 5, 2_048_000),
+            skill(2, "Large Projectile Specialization", gunnery, 3, 360_000),
+            skill(3, "Small Projectile Turret", gunnery, 4, 45_255),
+            skill(4, "Science", science, 5, 256_000),
+            skill(5, "Research", science, 4, 45_255),
+            skill(6, "Medium Projectile Turret", gunnery, 0, 0),
+        ];
+        let requirements = [(2, 1), (5, 4)];
+        let ranks = HashMap::from([(3, 1), (5, 1)]);
+
+        let summary = summarize(7, &skills, &requirements, &ranks);
+
+        assert_eq!(summary.level_five_total, 2);
+        assert_eq!(summary.level_five_by_group.len(), 2);
+        assert_eq!(summary.level_five_by_group[0].group_name, "Gunnery");
+        assert_eq!(summary.level_five_by_group[0].skill_count, 4);
+
+        // Science is not a specialty group and untrained turrets are left out.
+        let scores: Vec<(&str, i64, i64)> = summary
+            .specialist_scores
+            .iter()
+            .map(|s| (s.skill_name.as_str(), s.trained_levels, s.total_levels))
+            .collect();
+        assert_eq!(
+            scores,
+            vec![
+                ("Large Projectile Turret", 8, 10),
+                ("Small Projectile Turret", 4, 5),
+                ("Large Projectile Specialization", 3, 5),
+            ]
+        );
+
+        assert_eq!(summary.level_five_candidates.len(), 2);
+        assert_eq!(summary.level_five_candidates[0].sp_to_level_five, 210_745);
+    }
+}
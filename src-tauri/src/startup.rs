@@ -0,0 +1,190 @@
+//! Startup phases shown on the splash screen. The phase only moves forward;
+//! every transition is broadcast as `startup:progress`, and reaching
+//! [`StartupPhase::Ready`] also emits `startup-complete`.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+use typeshare::typeshare;
+
+use crate::events;
+
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StartupPhase {
+    DbMigrating,
+    SdeChecking,
+    SdeImporting,
+    RefreshingCharacters,
+    Ready,
+}
+
+impl StartupPhase {
+    const ALL: [StartupPhase; 5] = [
+        StartupPhase::DbMigrating,
+        StartupPhase::SdeChecking,
+        StartupPhase::SdeImporting,
+        StartupPhase::RefreshingCharacters,
+        StartupPhase::Ready,
+    ];
+
+    fn from_u8(value: u8) -> StartupPhase {
+        Self::ALL
+            .get(value as usize)
+            .copied()
+            .unwrap_or(StartupPhase::Ready)
+    }
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupStatus {
+    pub phase: StartupPhase,
+    /// Characters known at launch.
+    pub characters_total: u32,
+    /// Characters whose first refresh has finished (or failed).
+    pub characters_refreshed: u32,
+}
+
+struct Inner {
+    phase: AtomicU8,
+    total: AtomicU32,
+    pending: Mutex<HashSet<i64>>,
+    refreshed: Notify,
+}
+
+#[derive(Clone)]
+pub struct StartupState {
+    inner: Arc<Inner>,
+}
+
+impl StartupState {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                phase: AtomicU8::new(StartupPhase::DbMigrating as u8),
+                total: AtomicU32::new(0),
+                pending: Mutex::new(HashSet::new()),
+                refreshed: Notify::new(),
+            }),
+        }
+    }
+
+    pub fn phase(&self) -> StartupPhase {
+        StartupPhase::from_u8(self.inner.phase.load(Ordering::SeqCst))
+    }
+
+    fn pending_count(&self) -> u32 {
+        self.inner
+            .pending
+            .lock()
+            .map(|p| p.len() as u32)
+            .unwrap_or(0)
+    }
+
+    pub fn status(&self) -> StartupStatus {
+        let total = self.inner.total.load(Ordering::SeqCst);
+        StartupStatus {
+            phase: self.phase(),
+            characters_total: total,
+            characters_refreshed: total.saturating_sub(self.pending_count()),
+        }
+    }
+
+    /// Moves to `phase` unless startup is already past it.
+    pub fn advance(&self, app: &AppHandle, phase: StartupPhase) {
+        let previous = self.inner.phase.fetch_max(phase as u8, Ordering::SeqCst);
+        if previous >= phase as u8 {
+            return;
+        }
+        if let Err(e) = events::emit_startup_progress(app, &self.status()) {
+            eprintln!("Failed to emit startup progress: {}", e);
+        }
+        if phase == StartupPhase::Ready {
+            let _ = events::emit_startup_complete(app);
+        }
+    }
+
+    /// Characters whose first refresh the splash screen waits for.
+    pub fn expect_characters(&self, character_ids: impl IntoIterator<Item = i64>) {
+        if let Ok(mut pending) = self.inner.pending.lock() {
+            pending.extend(character_ids);
+            self.inner
+                .total
+                .store(pending.len() as u32, Ordering::SeqCst);
+        }
+    }
+
+    fn character_refreshed(&self, app: &AppHandle, character_id: i64) {
+        let removed = self
+            .inner
+            .pending
+            .lock()
+            .map(|mut p| p.remove(&character_id))
+            .unwrap_or(false);
+        if !removed {
+            return;
+        }
+        self.inner.refreshed.notify_waiters();
+        if self.phase() == StartupPhase::RefreshingCharacters {
+            if let Err(e) = events::emit_startup_progress(app, &self.status()) {
+                eprintln!("Failed to emit startup progress: {}", e);
+            }
+        }
+    }
+
+    /// Waits until every expected character finished its first refresh, or
+    /// `timeout` passed, so one unreachable character cannot hold up the app.
+    pub async fn wait_for_characters(&self, timeout: Duration) {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let refreshed = self.inner.refreshed.notified();
+            if self.pending_count() == 0 {
+                return;
+            }
+            if tokio::time::timeout_at(deadline, refreshed).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Called by a character's refresher after each pass; only the first pass
+/// after launch counts towards startup.
+pub fn character_refreshed(app: &AppHandle, character_id: i64) {
+    if let Some(state) = app.try_state::<StartupState>() {
+        state.character_refreshed(app, character_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases_round_trip_through_the_atomic() {
+        for phase in StartupPhase::ALL {
+            assert_eq!(StartupPhase::from_u8(phase as u8), phase);
+        }
+        assert!(StartupPhase::SdeImporting < StartupPhase::Ready);
+    }
+
+    #[tokio::test]
+    async fn waiting_for_characters_gives_up_after_the_timeout() {
+        let state = StartupState::new();
+        state.expect_characters([1, 2]);
+        assert_eq!(state.status().characters_total, 2);
+        assert_eq!(state.status().characters_refreshed, 0);
+
+        let started = tokio::time::Instant::now();
+        state.wait_for_characters(Duration::from_millis(20)).await;
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    }
+}
@@ -0,0 +1,262 @@
+//! Where skillmon keeps its database and SDE working files.
+//!
+//! The location cannot live in the database itself, so it is resolved at
+//! startup from, in order: portable mode (`--portable` or a `portable` marker
+//! file next to the executable), a relocation recorded in `storage.json` in the
+//! platform app data directory, and finally the platform app data directory.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tauri::Manager;
+
+pub const DATABASE_FILE_NAME: &str = "database.sqlite";
+const STORAGE_CONFIG_FILE_NAME: &str = "storage.json";
+const TOKEN_KEY_FILE_NAME: &str = "token.key";
+const PORTABLE_FLAG: &str = "--portable";
+const PORTABLE_MARKER_FILE_NAME: &str = "portable";
+const PORTABLE_DATA_DIR_NAME: &str = "skillmon-data";
+
+#[derive(Debug, Clone)]
+pub struct StorageLocation {
+    pub data_dir: PathBuf,
+    /// Platform app data directory; always holds `storage.json`.
+    pub default_data_dir: PathBuf,
+    pub portable: bool,
+}
+
+impl StorageLocation {
+    pub fn database_path(&self) -> PathBuf {
+        self.data_dir.join(DATABASE_FILE_NAME)
+    }
+
+    pub fn sde_dir(&self) -> PathBuf {
+        self.data_dir.join("sde")
+    }
+
+    /// Key for tokens encrypted without a keychain. Kept with `storage.json`
+    /// so it stays on this machine when the data directory moves; a portable
+    /// install keeps it with its own data so it travels with the database.
+    pub fn token_key_path(&self) -> PathBuf {
+        let dir = if self.portable {
+            &self.data_dir
+        } else {
+            &self.default_data_dir
+        };
+        dir.join(TOKEN_KEY_FILE_NAME)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StorageConfig {
+    data_dir: Option<PathBuf>,
+}
+
+pub fn resolve(app: &tauri::AppHandle) -> Result<StorageLocation> {
+    let default_data_dir = app
+        .path()
+        .app_data_dir()
+        .context("failed to resolve app data directory")?;
+
+    if let Some(portable_dir) = portable_data_dir()? {
+        adopt_platform_token_key(&default_data_dir, &portable_dir);
+        return Ok(StorageLocation {
+            data_dir: portable_dir,
+            default_data_dir,
+            portable: true,
+        });
+    }
+
+    let data_dir = read_config(&default_data_dir)
+        .data_dir
+        .unwrap_or_else(|| default_data_dir.clone());
+
+    Ok(StorageLocation {
+        data_dir,
+        default_data_dir,
+        portable: false,
+    })
+}
+
+fn portable_data_dir() -> Result<Option<PathBuf>> {
+    let exe = std::env::current_exe().context("failed to resolve executable path")?;
+    let Some(exe_dir) = exe.parent() else {
+        return Ok(None);
+    };
+
+    let flagged = std::env::args().any(|arg| arg == PORTABLE_FLAG);
+    if flagged || exe_dir.join(PORTABLE_MARKER_FILE_NAME).exists() {
+        return Ok(Some(exe_dir.join(PORTABLE_DATA_DIR_NAME)));
+    }
+    Ok(None)
+}
+
+/// Portable installs used to keep their token key in the platform directory.
+/// Copy it (never move: a regular install may share it) so tokens sealed with
+/// it still open.
+fn adopt_platform_token_key(default_data_dir: &Path, portable_dir: &Path) {
+    let key = portable_dir.join(TOKEN_KEY_FILE_NAME);
+    let platform_key = default_data_dir.join(TOKEN_KEY_FILE_NAME);
+    if key.exists() || !platform_key.exists() {
+        return;
+    }
+    let copied = fs::create_dir_all(portable_dir).and_then(|_| fs::copy(&platform_key, &key));
+    if let Err(e) = copied {
+        eprintln!(
+            "storage: failed to copy token key to {}: {}",
+            key.display(),
+            e
+        );
+    }
+}
+
+fn read_config(default_data_dir: &Path) -> StorageConfig {
+    let path = default_data_dir.join(STORAGE_CONFIG_FILE_NAME);
+    match fs::read_to_string(&path) {
+        Ok(raw) => serde_json::from_str(&raw).unwrap_or_else(|e| {
+            eprintln!("storage: ignoring invalid {}: {}", path.display(), e);
+            StorageConfig::default()
+        }),
+        Err(_) => StorageConfig::default(),
+    }
+}
+
+/// Record `data_dir` as the location to use from the next launch. Pointing back
+/// at the default directory removes the override entirely.
+pub fn write_config(default_data_dir: &Path, data_dir: &Path) -> Result<()> {
+    fs::create_dir_all(default_data_dir).context("failed to create app data directory")?;
+    let path = default_data_dir.join(STORAGE_CONFIG_FILE_NAME);
+
+    if data_dir == default_data_dir {
+        if path.exists() {
+            fs::remove_file(&path).context("failed to remove storage config")?;
+        }
+        return Ok(());
+    }
+
+    let config = StorageConfig {
+        data_dir: Some(data_dir.to_path_buf()),
+    };
+    // Write-then-rename so a crash never leaves a truncated config behind.
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_vec_pretty(&config)?)
+        .context("failed to write storage config")?;
+    fs::rename(&tmp, &path).context("failed to replace storage config")?;
+    Ok(())
+}
+
+/// Check that `target` can receive a migrated database: absolute, different
+/// from the current location, creatable, writable, and not already holding a
+/// skillmon database that the copy would clobber.
+pub fn validate_migration_target(current: &StorageLocation, target: &Path) -> Result<()> {
+    if current.portable {
+        bail!("The data directory cannot be changed in portable mode");
+    }
+    if !target.is_absolute() {
+        bail!("The data directory must be an absolute path");
+    }
+    if target == current.data_dir {
+        bail!("Skillmon already stores its data in this directory");
+    }
+    fs::create_dir_all(target)
+        .with_context(|| format!("Cannot create directory {}", target.display()))?;
+
+    let probe = target.join(".skillmon-write-test");
+    fs::write(&probe, b"ok")
+        .with_context(|| format!("Directory {} is not writable", target.display()))?;
+    fs::remove_file(&probe).ok();
+
+    if target.join(DATABASE_FILE_NAME).exists() {
+        bail!(
+            "{} already contains a skillmon database; choose an empty directory",
+            target.display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(default_data_dir: &Path, portable: bool) -> StorageLocation {
+        StorageLocation {
+            data_dir: default_data_dir.to_path_buf(),
+            default_data_dir: default_data_dir.to_path_buf(),
+            portable,
+        }
+    }
+
+    #[test]
+    fn migration_target_must_be_a_new_writable_absolute_directory() {
+        let tmp = tempfile::tempdir().unwrap();
+        let current = location(&tmp.path().join("default"), false);
+
+        let target = tmp.path().join("nested").join("target");
+        validate_migration_target(&current, &target).unwrap();
+        assert!(target.is_dir());
+        assert!(!target.join(".skillmon-write-test").exists());
+
+        let err = validate_migration_target(&location(&current.data_dir, true), &target);
+        assert!(err.unwrap_err().to_string().contains("portable"));
+        let err = validate_migration_target(&current, Path::new("relative/dir"));
+        assert!(err.unwrap_err().to_string().contains("absolute"));
+        let err = validate_migration_target(&current, &current.data_dir);
+        assert!(err.unwrap_err().to_string().contains("already stores"));
+
+        fs::write(target.join(DATABASE_FILE_NAME), b"").unwrap();
+        let err = validate_migration_target(&current, &target);
+        assert!(err.unwrap_err().to_string().contains("already contains"));
+    }
+
+    #[test]
+    fn portable_installs_keep_the_token_key_with_their_data() {
+        let tmp = tempfile::tempdir().unwrap();
+        let default_data_dir = tmp.path().join("default");
+        let portable_dir = tmp.path().join("skillmon-data");
+
+        let mut current = location(&default_data_dir, false);
+        assert_eq!(
+            current.token_key_path(),
+            default_data_dir.join(TOKEN_KEY_FILE_NAME)
+        );
+        current.data_dir = portable_dir.clone();
+        current.portable = true;
+        assert_eq!(
+            current.token_key_path(),
+            portable_dir.join(TOKEN_KEY_FILE_NAME)
+        );
+
+        fs::create_dir_all(&default_data_dir).unwrap();
+        fs::write(default_data_dir.join(TOKEN_KEY_FILE_NAME), b"old key").unwrap();
+        adopt_platform_token_key(&default_data_dir, &portable_dir);
+        assert_eq!(fs::read(current.token_key_path()).unwrap(), b"old key");
+        assert!(default_data_dir.join(TOKEN_KEY_FILE_NAME).exists());
+
+        // A key the portable install already has is never replaced.
+        fs::write(default_data_dir.join(TOKEN_KEY_FILE_NAME), b"other key").unwrap();
+        adopt_platform_token_key(&default_data_dir, &portable_dir);
+        assert_eq!(fs::read(current.token_key_path()).unwrap(), b"old key");
+    }
+
+    #[test]
+    fn write_config_records_the_move_and_reset_removes_it() {
+        let tmp = tempfile::tempdir().unwrap();
+        let default_data_dir = tmp.path().join("default");
+        let moved = tmp.path().join("moved");
+        let config_path = default_data_dir.join(STORAGE_CONFIG_FILE_NAME);
+
+        write_config(&default_data_dir, &moved).unwrap();
+        assert_eq!(read_config(&default_data_dir).data_dir, Some(moved));
+        assert!(!config_path.with_extension("json.tmp").exists());
+
+        write_config(&default_data_dir, &default_data_dir).unwrap();
+        assert!(!config_path.exists());
+        assert_eq!(read_config(&default_data_dir).data_dir, None);
+
+        // Resetting again with no config left is not an error.
+        write_config(&default_data_dir, &default_data_dir).unwrap();
+    }
+}
@@ -0,0 +1,307 @@
+//! Remote stores for the encrypted sync blob. Both backends hold a single
+//! object; sync is whole-document, so no listing or partial updates are needed.
+
+use anyhow::{bail, Context, Result};
+use hmac::{Hmac, KeyInit, Mac};
+use reqwest::StatusCode;
+use sha2::{Digest, Sha256};
+
+use super::SyncBackendConfig;
+
+pub const SYNC_OBJECT_NAME: &str = "skillmon-sync.bin";
+
+/// The remote blob and the version it was read at.
+pub struct RemoteObject {
+    pub body: Vec<u8>,
+    pub etag: Option<String>,
+}
+
+/// What the remote must still hold for an [`upload`] to go through, so a
+/// write from another device between download and upload is not lost.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Precondition {
+    /// There was no object.
+    Absent,
+    /// The object still has this ETag.
+    Etag(String),
+    /// The store reported no ETag, so there is nothing to check against.
+    Unchecked,
+}
+
+impl Precondition {
+    pub fn for_download(remote: Option<&RemoteObject>) -> Self {
+        match remote {
+            None => Precondition::Absent,
+            Some(RemoteObject {
+                etag: Some(etag), ..
+            }) => Precondition::Etag(etag.clone()),
+            Some(_) => Precondition::Unchecked,
+        }
+    }
+
+    fn apply(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self {
+            Precondition::Absent => request.header(reqwest::header::IF_NONE_MATCH, "*"),
+            Precondition::Etag(etag) => request.header(reqwest::header::IF_MATCH, etag),
+            Precondition::Unchecked => request,
+        }
+    }
+}
+
+pub async fn download(
+    client: &reqwest::Client,
+    config: &SyncBackendConfig,
+) -> Result<Option<RemoteObject>> {
+    let request = match config {
+        SyncBackendConfig::WebDav {
+            url,
+            username,
+            password,
+        } => client
+            .get(webdav_object_url(url))
+            .basic_auth(username, Some(password)),
+        SyncBackendConfig::S3 { .. } => s3_request(client, config, reqwest::Method::GET, &[])?,
+    };
+
+    let response = request.send().await.context("Sync download failed")?;
+    match response.status() {
+        StatusCode::NOT_FOUND => Ok(None),
+        status if status.is_success() => {
+            let etag = response
+                .headers()
+                .get(reqwest::header::ETAG)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string);
+            Ok(Some(RemoteObject {
+                body: response.bytes().await?.to_vec(),
+                etag,
+            }))
+        }
+        status => bail!("Sync download failed with HTTP {}", status),
+    }
+}
+
+/// Writes `body` if the remote still matches `precondition`. Returns false
+/// when another device wrote first; download and merge again before retrying.
+pub async fn upload(
+    client: &reqwest::Client,
+    config: &SyncBackendConfig,
+    body: Vec<u8>,
+    precondition: &Precondition,
+) -> Result<bool> {
+    let request = match config {
+        SyncBackendConfig::WebDav {
+            url,
+            username,
+            password,
+        } => client
+            .put(webdav_object_url(url))
+            .basic_auth(username, Some(password))
+            .body(body),
+        SyncBackendConfig::S3 { .. } => {
+            s3_request(client, config, reqwest::Method::PUT, &body)?.body(body)
+        }
+    };
+
+    let response = precondition
+        .apply(request)
+        .send()
+        .await
+        .context("Sync upload failed")?;
+    match response.status() {
+        status if status.is_success() => Ok(true),
+        StatusCode::PRECONDITION_FAILED => Ok(false),
+        // S3 reports a conditional write racing another one as a conflict.
+        StatusCode::CONFLICT if matches!(config, SyncBackendConfig::S3 { .. }) => Ok(false),
+        status => bail!("Sync upload failed with HTTP {}", status),
+    }
+}
+
+fn webdav_object_url(base: &str) -> String {
+    format!("{}/{}", base.trim_end_matches('/'), SYNC_OBJECT_NAME)
+}
+
+/// Path-style S3 request signed with AWS Signature V4, so any S3-compatible
+/// store (AWS, R2, MinIO, B2) works with just an endpoint override.
+fn s3_request(
+    client: &reqwest::Client,
+    config: &SyncBackendConfig,
+    method: reqwest::Method,
+    body: &[u8],
+) -> Result<reqwest::RequestBuilder> {
+    let SyncBackendConfig::S3 {
+        endpoint,
+        region,
+        bucket,
+        prefix,
+        access_key_id,
+        secret_access_key,
+    } = config
+    else {
+        bail!("Not an S3 backend");
+    };
+
+    let endpoint = url::Url::parse(endpoint).context("Invalid S3 endpoint")?;
+    let host = match (endpoint.host_str(), endpoint.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        (None, _) => bail!("S3 endpoint has no host"),
+    };
+
+    let mut segments = vec![bucket.as_str()];
+    segments.extend(prefix.split('/').filter(|s| !s.is_empty()));
+    segments.push(SYNC_OBJECT_NAME);
+    let canonical_uri = segments
+        .iter()
+        .map(|s| urlencoding::encode(s).into_owned())
+        .collect::<Vec<_>>()
+        .join("/");
+    let canonical_uri = format!("/{}", canonical_uri);
+
+    let now = chrono::Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = hex(&Sha256::digest(body));
+
+    let canonical_request = format!(
+        "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+        method, canonical_uri, host, payload_hash, amz_date, payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let k_date = hmac_sha256(format!("AWS4{}", secret_access_key).as_bytes(), &date);
+    let k_region = hmac_sha256(&k_date, region);
+    let k_service = hmac_sha256(&k_region, "s3");
+    let k_signing = hmac_sha256(&k_service, "aws4_request");
+    let signature = hex(&hmac_sha256(&k_signing, &string_to_sign));
+
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+        access_key_id, scope, signature
+    );
+
+    let url = format!("{}://{}{}", endpoint.scheme(), host, canonical_uri);
+
+    Ok(client
+        .request(method, url)
+        .header("x-amz-content-sha256", payload_hash)
+        .header("x-amz-date", amz_date)
+        .header("Authorization", authorization))
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::State;
+    use axum::http::{header, HeaderMap};
+    use axum::response::{IntoResponse, Response};
+    use axum::Router;
+    use std::sync::{Arc, Mutex};
+
+    /// A WebDAV collection holding one object, with a counter for its ETag.
+    #[derive(Default)]
+    struct Remote {
+        body: Option<Vec<u8>>,
+        version: u32,
+    }
+
+    type Shared = Arc<Mutex<Remote>>;
+
+    async fn get_object(State(remote): State<Shared>) -> Response {
+        let remote = remote.lock().unwrap();
+        match &remote.body {
+            Some(body) => (
+                [(header::ETAG, format!("\"{}\"", remote.version))],
+                body.clone(),
+            )
+                .into_response(),
+            None => StatusCode::NOT_FOUND.into_response(),
+        }
+    }
+
+    async fn put_object(
+        State(remote): State<Shared>,
+        headers: HeaderMap,
+        body: axum::body::Bytes,
+    ) -> StatusCode {
+        let mut remote = remote.lock().unwrap();
+        let current = remote
+            .body
+            .as_ref()
+            .map(|_| format!("\"{}\"", remote.version));
+        let if_match = headers.get(header::IF_MATCH).and_then(|v| v.to_str().ok());
+        let allowed = match if_match {
+            Some(expected) => current.as_deref() == Some(expected),
+            None => current.is_none() || !headers.contains_key(header::IF_NONE_MATCH),
+        };
+        if !allowed {
+            return StatusCode::PRECONDITION_FAILED;
+        }
+        remote.body = Some(body.to_vec());
+        remote.version += 1;
+        StatusCode::CREATED
+    }
+
+    async fn serve() -> SyncBackendConfig {
+        let router = Router::new()
+            .route(
+                &format!("/{}", SYNC_OBJECT_NAME),
+                axum::routing::get(get_object).put(put_object),
+            )
+            .with_state(Shared::default());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        SyncBackendConfig::WebDav {
+            url: format!("http://{}", addr),
+            username: "user".to_string(),
+            password: "secret".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn upload_refuses_to_overwrite_a_newer_remote() {
+        let config = serve().await;
+        let client = reqwest::Client::new();
+
+        let none = download(&client, &config).await.unwrap();
+        let absent = Precondition::for_download(none.as_ref());
+        assert_eq!(absent, Precondition::Absent);
+        assert!(upload(&client, &config, b"first".to_vec(), &absent)
+            .await
+            .unwrap());
+        // Another device created it first.
+        assert!(!upload(&client, &config, b"late".to_vec(), &absent)
+            .await
+            .unwrap());
+
+        let read = download(&client, &config).await.unwrap();
+        let seen = Precondition::for_download(read.as_ref());
+        assert!(matches!(seen, Precondition::Etag(_)));
+        assert!(upload(&client, &config, b"other device".to_vec(), &seen)
+            .await
+            .unwrap());
+        assert!(!upload(&client, &config, b"stale".to_vec(), &seen)
+            .await
+            .unwrap());
+
+        let latest = download(&client, &config).await.unwrap().unwrap();
+        assert_eq!(latest.body, b"other device");
+    }
+}
@@ -0,0 +1,66 @@
+//! Passphrase encryption for the remote sync blob. The remote only ever sees
+//! `MAGIC || salt || nonce || AES-256-GCM ciphertext`.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, bail, Result};
+use rand::Rng;
+use sha2::Sha256;
+
+const MAGIC: &[u8; 4] = b"SKS1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const PBKDF2_ROUNDS: u32 = 210_000;
+
+pub fn encrypt(passphrase: &str, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::rng().fill_bytes(&mut salt);
+    rand::rng().fill_bytes(&mut nonce);
+
+    let key = derive_key(passphrase, &salt);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|_| anyhow!("Encryption failed"))?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+pub fn decrypt(passphrase: &str, blob: &[u8]) -> Result<Vec<u8>> {
+    let header = MAGIC.len() + SALT_LEN + NONCE_LEN;
+    if blob.len() < header || &blob[..MAGIC.len()] != MAGIC {
+        bail!("Remote sync data is not a Skillmon sync file");
+    }
+    let salt = &blob[MAGIC.len()..MAGIC.len() + SALT_LEN];
+    let nonce = &blob[MAGIC.len() + SALT_LEN..header];
+
+    let key = derive_key(passphrase, salt);
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    cipher
+        .decrypt(Nonce::from_slice(nonce), &blob[header..])
+        .map_err(|_| anyhow!("Failed to decrypt remote sync data (wrong passphrase?)"))
+}
+
+/// PBKDF2-HMAC-SHA256 stretched to the 32-byte AES-256 key.
+fn derive_key(passphrase: &str, salt: &[u8]) -> [u8; 32] {
+    pbkdf2::pbkdf2_hmac_array::<Sha256, 32>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_rejects_wrong_passphrase() {
+        let blob = encrypt("hunter2", b"plans and settings").unwrap();
+        assert_eq!(decrypt("hunter2", &blob).unwrap(), b"plans and settings");
+        assert!(decrypt("hunter3", &blob).is_err());
+        assert!(decrypt("hunter2", b"not a sync file").is_err());
+    }
+}
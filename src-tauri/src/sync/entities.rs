@@ -0,0 +1,513 @@
+//! Mapping between local tables and synced entities. Every entity is keyed by
+//! something stable across installs (sync ids, setting keys, EVE character ids)
+//! and carries its full content as JSON, so last-writer-wins can replace it
+//! wholesale.
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, Sqlite, Transaction};
+
+use crate::db;
+use crate::skill_plans::{SkillmonPlan, SkillmonPlanBooster, SkillmonPlanEntry, SkillmonPlanRemap};
+
+use super::{SyncEntity, DEVICE_LOCAL_SETTINGS};
+
+const PLAN_PREFIX: &str = "plan:";
+const GROUP_PREFIX: &str = "group:";
+const SETTING_PREFIX: &str = "setting:";
+const NOTIFICATION_PREFIX: &str = "notification:";
+
+/// Plan version written into synced entities. Pinned rather than following
+/// `SkillmonPlan::CURRENT_VERSION`: the version is part of each entity's
+/// content hash, so bumping it would make every plan look locally edited and
+/// overwrite newer remote edits. Later format additions must stay optional.
+const SYNCED_PLAN_VERSION: i32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SyncedPlan {
+    #[serde(flatten)]
+    plan: SkillmonPlan,
+    group: Option<String>,
+    sort_order: i64,
+    /// An EVE character id, so it names the same character on every install.
+    /// Left unset where that character has not been added.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    owner: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SyncedGroup {
+    name: String,
+    parent: Option<String>,
+    sort_order: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SyncedNotificationSetting {
+    enabled: bool,
+    config: Option<String>,
+}
+
+/// Current local content of every synced entity, keyed by entity key.
+pub async fn collect(pool: &db::Pool) -> Result<BTreeMap<String, serde_json::Value>> {
+    let mut out = BTreeMap::new();
+
+    let groups = sqlx::query(
+        "SELECT g.sync_id, g.name, p.sync_id AS parent_sync_id, g.sort_order
+         FROM plan_groups g LEFT JOIN plan_groups p ON g.parent_group_id = p.group_id",
+    )
+    .fetch_all(pool)
+    .await?;
+    for row in groups {
+        let sync_id: String = row.get("sync_id");
+        let group = SyncedGroup {
+            name: row.get("name"),
+            parent: row.get("parent_sync_id"),
+            sort_order: row.get("sort_order"),
+        };
+        out.insert(
+            format!("{}{}", GROUP_PREFIX, sync_id),
+            serde_json::to_value(group)?,
+        );
+    }
+
+    let plans = sqlx::query(
+        "SELECT p.plan_id, p.sync_id, p.name, p.description, p.auto_prerequisites, p.sort_order,
+                p.owner_character_id, g.sync_id AS group_sync_id
+         FROM skill_plans p LEFT JOIN plan_groups g ON p.group_id = g.group_id",
+    )
+    .fetch_all(pool)
+    .await?;
+    for row in plans {
+        let plan_id: i64 = row.get("plan_id");
+        let sync_id: String = row.get("sync_id");
+
+        let mut entries: Vec<SkillmonPlanEntry> = db::skill_plans::get_plan_entries(pool, plan_id)
+            .await?
+            .into_iter()
+            .map(SkillmonPlanEntry::from)
+            .collect();
+        crate::skill_plans::attach_comments(
+            &mut entries,
+            db::plan_comments::get_plan_comments(pool, plan_id).await?,
+        );
+        let remaps = db::remaps::get_plan_remaps(pool, plan_id)
+            .await?
+            .into_iter()
+            .map(|r| SkillmonPlanRemap {
+                after_skill_type_id: r.after_skill_type_id,
+                after_skill_level: r.after_skill_level,
+                attributes: r.attributes(),
+            })
+            .collect();
+        let boosters = db::planned_boosters::get_plan_boosters(pool, plan_id)
+            .await?
+            .into_iter()
+            .map(SkillmonPlanBooster::from)
+            .collect();
+
+        let plan = SyncedPlan {
+            plan: SkillmonPlan {
+                version: SYNCED_PLAN_VERSION,
+                name: row.get("name"),
+                description: row.get("description"),
+                auto_prerequisites: row.get::<i64, _>("auto_prerequisites") != 0,
+                entries,
+                remaps,
+                boosters,
+            },
+            group: row.get("group_sync_id"),
+            sort_order: row.get("sort_order"),
+            owner: row.get("owner_character_id"),
+        };
+        out.insert(
+            format!("{}{}", PLAN_PREFIX, sync_id),
+            serde_json::to_value(plan)?,
+        );
+    }
+
+    let settings = sqlx::query_as::<_, (String, String)>("SELECT key, value FROM app_settings")
+        .fetch_all(pool)
+        .await?;
+    for (key, value) in settings {
+        if DEVICE_LOCAL_SETTINGS.contains(&key.as_str()) {
+            continue;
+        }
+        out.insert(
+            format!("{}{}", SETTING_PREFIX, key),
+            serde_json::Value::String(value),
+        );
+    }
+
+    let notification_settings = sqlx::query_as::<_, (i64, String, i64, Option<String>)>(
+        "SELECT character_id, notification_type, enabled, config FROM notification_settings",
+    )
+    .fetch_all(pool)
+    .await?;
+    for (character_id, notification_type, enabled, config) in notification_settings {
+        let setting = SyncedNotificationSetting {
+            enabled: enabled != 0,
+            config,
+        };
+        out.insert(
+            format!(
+                "{}{}:{}",
+                NOTIFICATION_PREFIX, character_id, notification_type
+            ),
+            serde_json::to_value(setting)?,
+        );
+    }
+
+    Ok(out)
+}
+
+/// Write remote winners into the local tables in one transaction. Groups are
+/// applied before plans so plan folders resolve, and deletions run last.
+/// Entities that cannot be applied here (e.g. settings for a character this
+/// install has not added) are skipped and retried on the next sync.
+pub async fn apply(pool: &db::Pool, winners: &[(String, SyncEntity)]) -> Result<usize> {
+    let mut tx = pool.begin().await?;
+    let mut applied = 0;
+
+    let mut groups = Vec::new();
+    let mut plans = Vec::new();
+    let mut deletions = Vec::new();
+
+    for (key, entity) in winners {
+        if entity.deleted {
+            deletions.push(key.as_str());
+            continue;
+        }
+        let Some(data) = entity.data.clone() else {
+            continue;
+        };
+
+        if let Some(sync_id) = key.strip_prefix(GROUP_PREFIX) {
+            groups.push((sync_id, serde_json::from_value::<SyncedGroup>(data)?));
+        } else if let Some(sync_id) = key.strip_prefix(PLAN_PREFIX) {
+            plans.push((sync_id, serde_json::from_value::<SyncedPlan>(data)?));
+        } else if let Some(setting_key) = key.strip_prefix(SETTING_PREFIX) {
+            let serde_json::Value::String(value) = data else {
+                continue;
+            };
+            sqlx::query("INSERT OR REPLACE INTO app_settings (key, value) VALUES (?, ?)")
+                .bind(setting_key)
+                .bind(value)
+                .execute(&mut *tx)
+                .await?;
+            applied += 1;
+        } else if let Some(rest) = key.strip_prefix(NOTIFICATION_PREFIX) {
+            let Some((character_id, notification_type)) = rest.split_once(':') else {
+                continue;
+            };
+            let Ok(character_id) = character_id.parse::<i64>() else {
+                continue;
+            };
+            let setting: SyncedNotificationSetting = serde_json::from_value(data)?;
+            let result = sqlx::query(
+                "INSERT INTO notification_settings (character_id, notification_type, enabled, config)
+                 SELECT ?, ?, ?, ? WHERE EXISTS (SELECT 1 FROM characters WHERE character_id = ?)
+                 ON CONFLICT(character_id, notification_type) DO UPDATE SET enabled = excluded.enabled, config = excluded.config",
+            )
+            .bind(character_id)
+            .bind(notification_type)
+            .bind(if setting.enabled { 1 } else { 0 })
+            .bind(&setting.config)
+            .bind(character_id)
+            .execute(&mut *tx)
+            .await?;
+            applied += result.rows_affected() as usize;
+        }
+    }
+
+    // Upsert groups flat, then link parents once every group exists.
+    for (sync_id, group) in &groups {
+        sqlx::query(
+            "INSERT INTO plan_groups (name, sort_order, sync_id) VALUES (?, ?, ?)
+             ON CONFLICT(sync_id) DO UPDATE SET name = excluded.name, sort_order = excluded.sort_order",
+        )
+        .bind(&group.name)
+        .bind(group.sort_order)
+        .bind(sync_id)
+        .execute(&mut *tx)
+        .await?;
+        applied += 1;
+    }
+    for (sync_id, group) in &groups {
+        let parent_id = resolve_group(&mut tx, group.parent.as_deref()).await?;
+        sqlx::query("UPDATE plan_groups SET parent_group_id = ? WHERE sync_id = ?")
+            .bind(parent_id)
+            .bind(sync_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+
+    for (sync_id, synced) in &plans {
+        apply_plan(&mut tx, sync_id, synced).await?;
+        applied += 1;
+    }
+
+    for key in deletions {
+        if let Some(sync_id) = key.strip_prefix(PLAN_PREFIX) {
+            sqlx::query("DELETE FROM skill_plans WHERE sync_id = ?")
+                .bind(sync_id)
+                .execute(&mut *tx)
+                .await?;
+        } else if let Some(sync_id) = key.strip_prefix(GROUP_PREFIX) {
+            sqlx::query("DELETE FROM plan_groups WHERE sync_id = ?")
+                .bind(sync_id)
+                .execute(&mut *tx)
+                .await?;
+        } else if let Some(setting_key) = key.strip_prefix(SETTING_PREFIX) {
+            sqlx::query("DELETE FROM app_settings WHERE key = ?")
+                .bind(setting_key)
+                .execute(&mut *tx)
+                .await?;
+        } else if let Some(rest) = key.strip_prefix(NOTIFICATION_PREFIX) {
+            if let Some((character_id, notification_type)) = rest.split_once(':') {
+                sqlx::query(
+                    "DELETE FROM notification_settings WHERE character_id = ? AND notification_type = ?",
+                )
+                .bind(character_id.parse::<i64>().unwrap_or_default())
+                .bind(notification_type)
+                .execute(&mut *tx)
+                .await?;
+            }
+        }
+        applied += 1;
+    }
+
+    tx.commit().await?;
+    Ok(applied)
+}
+
+async fn resolve_group(
+    tx: &mut Transaction<'_, Sqlite>,
+    sync_id: Option<&str>,
+) -> Result<Option<i64>> {
+    let Some(sync_id) = sync_id else {
+        return Ok(None);
+    };
+    let group_id =
+        sqlx::query_scalar::<_, i64>("SELECT group_id FROM plan_groups WHERE sync_id = ?")
+            .bind(sync_id)
+            .fetch_optional(&mut **tx)
+            .await?;
+    Ok(group_id)
+}
+
+async fn apply_plan(
+    tx: &mut Transaction<'_, Sqlite>,
+    sync_id: &str,
+    synced: &SyncedPlan,
+) -> Result<()> {
+    let group_id = resolve_group(tx, synced.group.as_deref()).await?;
+    let now = chrono::Utc::now().timestamp();
+
+    sqlx::query(
+        "INSERT INTO skill_plans (name, description, auto_prerequisites, created_at, updated_at, group_id, sort_order, sync_id, owner_character_id)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, (SELECT character_id FROM characters WHERE character_id = ?))
+         ON CONFLICT(sync_id) DO UPDATE SET
+            name = excluded.name,
+            description = excluded.description,
+            auto_prerequisites = excluded.auto_prerequisites,
+            updated_at = excluded.updated_at,
+            group_id = excluded.group_id,
+            sort_order = excluded.sort_order,
+            owner_character_id = excluded.owner_character_id",
+    )
+    .bind(&synced.plan.name)
+    .bind(&synced.plan.description)
+    .bind(if synced.plan.auto_prerequisites { 1 } else { 0 })
+    .bind(now)
+    .bind(now)
+    .bind(group_id)
+    .bind(synced.sort_order)
+    .bind(sync_id)
+    .bind(synced.owner)
+    .execute(&mut **tx)
+    .await?;
+
+    let plan_id: i64 = sqlx::query_scalar("SELECT plan_id FROM skill_plans WHERE sync_id = ?")
+        .bind(sync_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+    sqlx::query("DELETE FROM skill_plan_entries WHERE plan_id = ?")
+        .bind(plan_id)
+        .execute(&mut **tx)
+        .await?;
+    for (index, entry) in synced.plan.entries.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO skill_plan_entries (plan_id, skill_type_id, planned_level, sort_order, entry_type, notes, excluded, priority)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(plan_id)
+        .bind(entry.skill_type_id)
+        .bind(entry.level)
+        .bind(index as i64)
+        .bind(&entry.entry_type)
+        .bind(&entry.notes)
+        .bind(entry.excluded)
+        .bind(entry.priority)
+        .execute(&mut **tx)
+        .await?;
+    }
+
+    db::plan_comments::clear_plan_comments(&mut **tx, plan_id).await?;
+    for entry in &synced.plan.entries {
+        for comment in &entry.comments {
+            db::plan_comments::add_plan_comment(
+                &mut **tx,
+                plan_id,
+                entry.skill_type_id,
+                entry.level,
+                &comment.author,
+                &comment.body,
+                comment.created_at,
+            )
+            .await?;
+        }
+    }
+
+    sqlx::query("DELETE FROM remaps WHERE plan_id = ?")
+        .bind(plan_id)
+        .execute(&mut **tx)
+        .await?;
+    for remap in &synced.plan.remaps {
+        db::remaps::save_remap(
+            &mut **tx,
+            None,
+            Some(plan_id),
+            remap.after_skill_type_id,
+            remap.after_skill_level,
+            &remap.attributes,
+        )
+        .await?;
+    }
+
+    sqlx::query("DELETE FROM planned_boosters WHERE plan_id = ?")
+        .bind(plan_id)
+        .execute(&mut **tx)
+        .await?;
+    for booster in &synced.plan.boosters {
+        db::planned_boosters::save_planned_booster(
+            &mut **tx,
+            plan_id,
+            booster.type_id,
+            booster.bonus,
+            booster.duration_seconds,
+            booster.after_skill_type_id,
+            booster.after_skill_level,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Stable content hash of an entity, used to notice local edits between syncs.
+pub fn content_hash(data: &serde_json::Value) -> String {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(data.to_string().as_bytes());
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testdata::{fixtures, TestDb};
+
+    fn winners(collected: &BTreeMap<String, serde_json::Value>) -> Vec<(String, SyncEntity)> {
+        collected
+            .iter()
+            .map(|(key, data)| {
+                (
+                    key.clone(),
+                    SyncEntity {
+                        modified_at: 1,
+                        deleted: false,
+                        data: Some(data.clone()),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn plans_round_trip_with_every_column() {
+        let source = TestDb::new().await.unwrap();
+        db::add_character(&source.pool, 1, "Owner").await.unwrap();
+        let plan = fixtures::create_skill_plan(&source.pool, "Synced").await;
+        let first = fixtures::add_plan_entry(&source.pool, plan, 3300, 1, "Planned").await;
+        let second = fixtures::add_plan_entry(&source.pool, plan, 3300, 2, "Planned").await;
+        db::skill_plans::set_entries_priority(&source.pool, plan, &[first], 1)
+            .await
+            .unwrap();
+        db::skill_plans::update_plan_entry(
+            &source.pool,
+            second,
+            None,
+            None,
+            Some("later"),
+            Some(true),
+        )
+        .await
+        .unwrap();
+        db::skill_plans::set_plan_owner(&source.pool, plan, Some(1))
+            .await
+            .unwrap();
+        db::planned_boosters::save_planned_booster(
+            &source.pool,
+            plan,
+            None,
+            10,
+            86_400,
+            Some(3300),
+            Some(1),
+        )
+        .await
+        .unwrap();
+        db::sync::ensure_sync_ids(&source.pool).await.unwrap();
+        let collected = collect(&source.pool).await.unwrap();
+
+        let target = TestDb::new().await.unwrap();
+        db::add_character(&target.pool, 1, "Owner").await.unwrap();
+        apply(&target.pool, &winners(&collected)).await.unwrap();
+        assert_eq!(collect(&target.pool).await.unwrap(), collected);
+
+        let applied = db::skill_plans::get_all_skill_plans(&target.pool)
+            .await
+            .unwrap();
+        assert_eq!(applied[0].owner_character_id, Some(1));
+        let entries = db::skill_plans::get_plan_entries(&target.pool, applied[0].plan_id)
+            .await
+            .unwrap();
+        let columns: Vec<(i64, bool, Option<&str>)> = entries
+            .iter()
+            .map(|e| (e.priority, e.excluded, e.notes.as_deref()))
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                (1, false, None),
+                (db::skill_plans::DEFAULT_ENTRY_PRIORITY, true, Some("later")),
+            ]
+        );
+        let boosters = db::planned_boosters::get_plan_boosters(&target.pool, applied[0].plan_id)
+            .await
+            .unwrap();
+        assert_eq!(boosters.len(), 1);
+        assert_eq!(boosters[0].after_skill_type_id, Some(3300));
+
+        // An install without the owner keeps the plan, unowned.
+        let other = TestDb::new().await.unwrap();
+        apply(&other.pool, &winners(&collected)).await.unwrap();
+        let applied = db::skill_plans::get_all_skill_plans(&other.pool)
+            .await
+            .unwrap();
+        assert_eq!(applied[0].owner_character_id, None);
+    }
+}
@@ -0,0 +1,507 @@
+//! Optional multi-device sync through a user-provided S3 or WebDAV store.
+//!
+//! The whole synced state lives in one encrypted document on the remote. A
+//! sync downloads it, merges it with local state last-writer-wins per entity,
+//! uploads the merged result if the remote is still the version it read (and
+//! merges again if not), then applies remote winners locally. Tokens,
+//! characters and ESI caches are never synced.
+
+pub mod backend;
+pub mod crypto;
+pub mod entities;
+
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::db;
+use crate::ts_types::{i64_ts, usize_ts};
+
+pub const SYNC_CONFIG_KEY: &str = "sync_config";
+pub const SYNC_LAST_SYNCED_AT_KEY: &str = "sync_last_synced_at";
+
+/// App settings that describe this install rather than the user, so they are
+/// never uploaded or overwritten by a sync.
+pub const DEVICE_LOCAL_SETTINGS: &[&str] = &[SYNC_CONFIG_KEY, SYNC_LAST_SYNCED_AT_KEY];
+
+const DOCUMENT_VERSION: i32 = 1;
+/// Download-merge-upload rounds before giving up on a remote that keeps
+/// changing underneath us.
+const MAX_SYNC_ATTEMPTS: usize = 3;
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "content")]
+pub enum SyncBackendConfig {
+    WebDav {
+        /// Collection URL; the sync object is stored directly inside it.
+        url: String,
+        username: String,
+        password: String,
+    },
+    S3 {
+        endpoint: String,
+        region: String,
+        bucket: String,
+        prefix: String,
+        access_key_id: String,
+        secret_access_key: String,
+    },
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConfig {
+    pub backend: SyncBackendConfig,
+    /// Encrypts the remote document; must match on every device.
+    pub passphrase: String,
+}
+
+/// [`SyncBackendConfig`] as shown to the frontend: secrets are reduced to
+/// whether one is stored.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "content")]
+pub enum SyncBackendSummary {
+    WebDav {
+        url: String,
+        username: String,
+        password_set: bool,
+    },
+    S3 {
+        endpoint: String,
+        region: String,
+        bucket: String,
+        prefix: String,
+        access_key_id: String,
+        secret_access_key_set: bool,
+    },
+}
+
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SyncConfigSummary {
+    pub backend: SyncBackendSummary,
+    pub passphrase_set: bool,
+}
+
+impl From<&SyncConfig> for SyncConfigSummary {
+    fn from(config: &SyncConfig) -> Self {
+        let backend = match &config.backend {
+            SyncBackendConfig::WebDav {
+                url,
+                username,
+                password,
+            } => SyncBackendSummary::WebDav {
+                url: url.clone(),
+                username: username.clone(),
+                password_set: !password.is_empty(),
+            },
+            SyncBackendConfig::S3 {
+                endpoint,
+                region,
+                bucket,
+                prefix,
+                access_key_id,
+                secret_access_key,
+            } => SyncBackendSummary::S3 {
+                endpoint: endpoint.clone(),
+                region: region.clone(),
+                bucket: bucket.clone(),
+                prefix: prefix.clone(),
+                access_key_id: access_key_id.clone(),
+                secret_access_key_set: !secret_access_key.is_empty(),
+            },
+        };
+        SyncConfigSummary {
+            backend,
+            passphrase_set: !config.passphrase.is_empty(),
+        }
+    }
+}
+
+impl SyncConfig {
+    /// Fills secrets left empty from `stored`, so the frontend can save other
+    /// changes without ever having read the secrets back. A backend secret is
+    /// only kept while the backend type stays the same.
+    pub fn keep_stored_secrets(&mut self, stored: &SyncConfig) {
+        if self.passphrase.is_empty() {
+            self.passphrase = stored.passphrase.clone();
+        }
+        match (&mut self.backend, &stored.backend) {
+            (
+                SyncBackendConfig::WebDav { password, .. },
+                SyncBackendConfig::WebDav {
+                    password: stored, ..
+                },
+            ) if password.is_empty() => *password = stored.clone(),
+            (
+                SyncBackendConfig::S3 {
+                    secret_access_key, ..
+                },
+                SyncBackendConfig::S3 {
+                    secret_access_key: stored,
+                    ..
+                },
+            ) if secret_access_key.is_empty() => *secret_access_key = stored.clone(),
+            _ => {}
+        }
+    }
+}
+
+#[typeshare]
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncReport {
+    pub uploaded: usize_ts,
+    pub downloaded: usize_ts,
+    pub synced_at: i64_ts,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncEntity {
+    /// Unix milliseconds of the last change this device or a peer observed.
+    pub modified_at: i64,
+    #[serde(default)]
+    pub deleted: bool,
+    #[serde(default)]
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SyncDocument {
+    version: i32,
+    entities: BTreeMap<String, SyncEntity>,
+}
+
+pub async fn get_config(pool: &db::Pool) -> Result<Option<SyncConfig>> {
+    let Some(raw) = db::app_settings::get_app_setting(pool, SYNC_CONFIG_KEY).await? else {
+        return Ok(None);
+    };
+    Ok(Some(serde_json::from_str(&raw)?))
+}
+
+pub async fn set_config(pool: &db::Pool, config: Option<&SyncConfig>) -> Result<()> {
+    match config {
+        Some(config) => {
+            let json = serde_json::to_string(config)?;
+            db::app_settings::set_app_setting(pool, SYNC_CONFIG_KEY, &json).await
+        }
+        None => db::app_settings::delete_app_setting(pool, SYNC_CONFIG_KEY).await,
+    }
+}
+
+pub async fn sync_now(pool: &db::Pool) -> Result<SyncReport> {
+    let config = get_config(pool)
+        .await?
+        .ok_or_else(|| anyhow!("Sync is not configured"))?;
+    let now = chrono::Utc::now().timestamp_millis();
+
+    db::sync::ensure_sync_ids(pool).await?;
+    let state = db::sync::get_sync_state(pool).await?;
+    let local = local_entities(entities::collect(pool).await?, &state, now);
+
+    let client = reqwest::Client::new();
+    let mut attempt = 0;
+    let outcome = loop {
+        attempt += 1;
+        let remote_object = backend::download(&client, &config.backend).await?;
+        let precondition = backend::Precondition::for_download(remote_object.as_ref());
+        let remote = match remote_object {
+            Some(object) => {
+                let plaintext = crypto::decrypt(&config.passphrase, &object.body)?;
+                let document: SyncDocument =
+                    serde_json::from_slice(&plaintext).context("Remote sync data is corrupt")?;
+                if document.version > DOCUMENT_VERSION {
+                    return Err(anyhow!(
+                        "Remote sync data was written by a newer version of Skillmon"
+                    ));
+                }
+                document.entities
+            }
+            None => BTreeMap::new(),
+        };
+
+        let outcome = merge(&local, &remote);
+        let document = SyncDocument {
+            version: DOCUMENT_VERSION,
+            entities: outcome.merged,
+        };
+        let blob = crypto::encrypt(&config.passphrase, &serde_json::to_vec(&document)?)?;
+        // Nothing is applied locally until the merged document is stored, so
+        // a lost race is simply merged again against the newer remote.
+        if backend::upload(&client, &config.backend, blob, &precondition).await? {
+            break MergeOutcome {
+                merged: document.entities,
+                ..outcome
+            };
+        }
+        if attempt == MAX_SYNC_ATTEMPTS {
+            return Err(anyhow!(
+                "Remote sync data kept changing during sync; try again"
+            ));
+        }
+    };
+
+    let downloaded = entities::apply(pool, &outcome.apply).await?;
+
+    // Record state against what is actually stored locally now, so entities
+    // that could not be applied are not mistaken for local deletions later.
+    let applied_local = entities::collect(pool).await?;
+    let rows = outcome
+        .merged
+        .iter()
+        .filter_map(|(key, entity)| {
+            if entity.deleted {
+                return Some(db::sync::SyncStateRow {
+                    entity_key: key.clone(),
+                    content_hash: None,
+                    modified_at: entity.modified_at,
+                    deleted: true,
+                });
+            }
+            applied_local.get(key).map(|data| db::sync::SyncStateRow {
+                entity_key: key.clone(),
+                content_hash: Some(entities::content_hash(data)),
+                modified_at: entity.modified_at,
+                deleted: false,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    db::sync::replace_sync_state(pool, &rows).await?;
+    db::app_settings::set_app_setting(pool, SYNC_LAST_SYNCED_AT_KEY, &now.to_string()).await?;
+
+    Ok(SyncReport {
+        uploaded: outcome.uploaded,
+        downloaded,
+        synced_at: now,
+    })
+}
+
+/// Local entities with modification times: unchanged content keeps the time
+/// from the last sync, changed or new content is stamped `now`, and entities
+/// that existed at the last sync but are gone locally become tombstones.
+fn local_entities(
+    current: BTreeMap<String, serde_json::Value>,
+    state: &[db::sync::SyncStateRow],
+    now: i64,
+) -> BTreeMap<String, SyncEntity> {
+    let state: HashMap<&str, &db::sync::SyncStateRow> =
+        state.iter().map(|r| (r.entity_key.as_str(), r)).collect();
+
+    let mut out = BTreeMap::new();
+    for (key, data) in current {
+        let hash = entities::content_hash(&data);
+        let modified_at = match state.get(key.as_str()) {
+            Some(row) if !row.deleted && row.content_hash.as_deref() == Some(hash.as_str()) => {
+                row.modified_at
+            }
+            _ => now,
+        };
+        out.insert(
+            key,
+            SyncEntity {
+                modified_at,
+                deleted: false,
+                data: Some(data),
+            },
+        );
+    }
+
+    for (key, row) in state {
+        if out.contains_key(key) {
+            continue;
+        }
+        out.insert(
+            key.to_string(),
+            SyncEntity {
+                modified_at: if row.deleted { row.modified_at } else { now },
+                deleted: true,
+                data: None,
+            },
+        );
+    }
+
+    out
+}
+
+struct MergeOutcome {
+    merged: BTreeMap<String, SyncEntity>,
+    /// Remote winners that differ from local state and must be applied.
+    apply: Vec<(String, SyncEntity)>,
+    /// Local winners the remote did not already have.
+    uploaded: usize,
+}
+
+/// Last-writer-wins per entity. Ties keep the local copy.
+fn merge(
+    local: &BTreeMap<String, SyncEntity>,
+    remote: &BTreeMap<String, SyncEntity>,
+) -> MergeOutcome {
+    let mut merged = BTreeMap::new();
+    let mut apply = Vec::new();
+    let mut uploaded = 0;
+
+    for (key, l) in local {
+        match remote.get(key) {
+            Some(r) if r.modified_at > l.modified_at => {
+                if r.deleted != l.deleted || r.data != l.data {
+                    apply.push((key.clone(), r.clone()));
+                }
+                merged.insert(key.clone(), r.clone());
+            }
+            Some(r) => {
+                if r != l {
+                    uploaded += 1;
+                }
+                merged.insert(key.clone(), l.clone());
+            }
+            None => {
+                if !l.deleted {
+                    uploaded += 1;
+                }
+                merged.insert(key.clone(), l.clone());
+            }
+        }
+    }
+
+    for (key, r) in remote {
+        if local.contains_key(key) {
+            continue;
+        }
+        if !r.deleted {
+            apply.push((key.clone(), r.clone()));
+        }
+        merged.insert(key.clone(), r.clone());
+    }
+
+    MergeOutcome {
+        merged,
+        apply,
+        uploaded,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(modified_at: i64, data: Option<&str>) -> SyncEntity {
+        SyncEntity {
+            modified_at,
+            deleted: data.is_none(),
+            data: data.map(|d| serde_json::Value::String(d.to_string())),
+        }
+    }
+
+    #[test]
+    fn newer_side_wins_per_entity() {
+        let local = BTreeMap::from([
+            ("setting:a".to_string(), entity(200, Some("local"))),
+            ("setting:b".to_string(), entity(100, Some("local"))),
+        ]);
+        let remote = BTreeMap::from([
+            ("setting:a".to_string(), entity(100, Some("remote"))),
+            ("setting:b".to_string(), entity(200, Some("remote"))),
+            ("setting:c".to_string(), entity(50, Some("remote"))),
+        ]);
+
+        let outcome = merge(&local, &remote);
+
+        assert_eq!(outcome.merged["setting:a"], entity(200, Some("local")));
+        assert_eq!(outcome.merged["setting:b"], entity(200, Some("remote")));
+        let applied: Vec<&str> = outcome.apply.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(applied, vec!["setting:b", "setting:c"]);
+        assert_eq!(outcome.uploaded, 1);
+    }
+
+    #[test]
+    fn local_deletion_becomes_tombstone_that_beats_older_remote() {
+        let state = vec![db::sync::SyncStateRow {
+            entity_key: "plan:x".to_string(),
+            content_hash: Some("old".to_string()),
+            modified_at: 100,
+            deleted: false,
+        }];
+        let local = local_entities(BTreeMap::new(), &state, 300);
+        assert_eq!(local["plan:x"], entity(300, None));
+
+        let remote = BTreeMap::from([("plan:x".to_string(), entity(100, Some("plan")))]);
+        let outcome = merge(&local, &remote);
+        assert!(outcome.apply.is_empty());
+        assert!(outcome.merged["plan:x"].deleted);
+    }
+
+    #[test]
+    fn summary_hides_secrets_and_empty_secrets_keep_stored_ones() {
+        let stored = SyncConfig {
+            backend: SyncBackendConfig::S3 {
+                endpoint: "https://s3.example".to_string(),
+                region: "eu".to_string(),
+                bucket: "skillmon".to_string(),
+                prefix: String::new(),
+                access_key_id: "AKID".to_string(),
+                secret_access_key: "s3-secret".to_string(),
+            },
+            passphrase: "correct horse".to_string(),
+        };
+
+        let summary = SyncConfigSummary::from(&stored);
+        assert!(summary.passphrase_set);
+        let json = serde_json::to_string(&summary).unwrap();
+        assert!(!json.contains("s3-secret"));
+        assert!(!json.contains("correct horse"));
+        assert!(json.contains("\"secret_access_key_set\":true"));
+
+        let mut edited = stored.clone();
+        edited.passphrase = String::new();
+        if let SyncBackendConfig::S3 {
+            bucket,
+            secret_access_key,
+            ..
+        } = &mut edited.backend
+        {
+            *bucket = "renamed".to_string();
+            secret_access_key.clear();
+        }
+        edited.keep_stored_secrets(&stored);
+        assert_eq!(edited.passphrase, "correct horse");
+        assert!(matches!(
+            &edited.backend,
+            SyncBackendConfig::S3 { bucket, secret_access_key, .. }
+                if bucket == "renamed" && secret_access_key == "s3-secret"
+        ));
+
+        // Switching backend never carries a secret across.
+        let mut webdav = SyncConfig {
+            backend: SyncBackendConfig::WebDav {
+                url: "https://dav.example".to_string(),
+                username: "pilot".to_string(),
+                password: String::new(),
+            },
+            passphrase: String::new(),
+        };
+        webdav.keep_stored_secrets(&stored);
+        assert!(matches!(
+            &webdav.backend,
+            SyncBackendConfig::WebDav { password, .. } if password.is_empty()
+        ));
+    }
+
+    #[test]
+    fn unchanged_content_keeps_previous_timestamp() {
+        let data = serde_json::Value::String("value".to_string());
+        let state = vec![db::sync::SyncStateRow {
+            entity_key: "setting:a".to_string(),
+            content_hash: Some(entities::content_hash(&data)),
+            modified_at: 100,
+            deleted: false,
+        }];
+        let current = BTreeMap::from([("setting:a".to_string(), data)]);
+
+        let local = local_entities(current, &state, 300);
+        assert_eq!(local["setting:a"].modified_at, 100);
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use anyhow::Result;
+
+use crate::db::characters::Character;
+use crate::db::notifications::Notification;
+use crate::db::repository::{CharacterRepository, NotificationRepository, PlanRepository};
+use crate::db::skill_plans::{ReplacePlanEntry, SkillPlan, SkillPlanEntry};
+
+/// SQLite's `CURRENT_TIMESTAMP` format, so timestamps compare the same way.
+fn current_timestamp() -> String {
+    chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// A character with the columns the repository keeps beside it.
+struct StoredCharacter {
+    character: Character,
+    omega_lapsed_at: Option<String>,
+    /// Last confirmed status; `None` until the first reading.
+    omega_status: Option<bool>,
+    omega_override: Option<bool>,
+}
+
+#[derive(Default)]
+struct State {
+    characters: BTreeMap<i64, StoredCharacter>,
+    plans: BTreeMap<i64, SkillPlan>,
+    entries: Vec<SkillPlanEntry>,
+    notifications: Vec<Notification>,
+    next_id: i64,
+}
+
+impl State {
+    fn next_id(&mut self) -> i64 {
+        self.next_id += 1;
+        self.next_id
+    }
+}
+
+/// In-memory backend for the `db::repository` traits, mirroring the SQLite
+/// behaviour the tests rely on (cascading deletes, ordering).
+#[derive(Default)]
+pub struct MemoryRepository {
+    state: Mutex<State>,
+}
+
+#[async_trait::async_trait]
+impl CharacterRepository for MemoryRepository {
+    async fn get_character(&self, character_id: i64) -> Result<Option<Character>> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .characters
+            .get(&character_id)
+            .map(|c| c.character.clone()))
+    }
+
+    async fn add_character(&self, character_id: i64, character_name: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.characters.contains_key(&character_id) {
+            anyhow::bail!("Character {} already exists", character_id);
+        }
+        let character = Character {
+            character_id,
+            character_name: character_name.to_string(),
+            unallocated_sp: 0,
+            account_id: None,
+            sort_order: 0,
+            is_omega: true,
+        };
+        state.characters.insert(
+            character_id,
+            StoredCharacter {
+                character,
+                omega_lapsed_at: None,
+                omega_status: None,
+                omega_override: None,
+            },
+        );
+        Ok(())
+    }
+
+    async fn update_character_omega_status(&self, character_id: i64, is_omega: bool) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(stored) = state.characters.get_mut(&character_id) {
+            let is_omega = stored.omega_override.unwrap_or(is_omega);
+            if is_omega {
+                stored.omega_lapsed_at = None;
+            } else if stored.omega_status == Some(true) {
+                stored.omega_lapsed_at = Some(current_timestamp());
+            }
+            stored.character.is_omega = is_omega;
+            stored.omega_status = Some(is_omega);
+        }
+        Ok(())
+    }
+
+    async fn set_character_omega_override(
+        &self,
+        character_id: i64,
+        is_omega: Option<bool>,
+    ) -> Result<()> {
+        {
+            let mut state = self.state.lock().unwrap();
+            if let Some(stored) = state.characters.get_mut(&character_id) {
+                stored.omega_override = is_omega;
+            }
+        }
+        if let Some(is_omega) = is_omega {
+            self.update_character_omega_status(character_id, is_omega)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn get_character_omega_lapsed_at(&self, character_id: i64) -> Result<Option<String>> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .characters
+            .get(&character_id)
+            .and_then(|c| c.omega_lapsed_at.clone()))
+    }
+
+    async fn delete_character(&self, character_id: i64) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.characters.remove(&character_id);
+        state
+            .notifications
+            .retain(|n| n.character_id != character_id);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl PlanRepository for MemoryRepository {
+    async fn create_skill_plan(
+        &self,
+        name: &str,
+        description: Option<&str>,
+        auto_prerequisites: bool,
+        group_id: Option<i64>,
+    ) -> Result<i64> {
+        let mut state = self.state.lock().unwrap();
+        let plan_id = state.next_id();
+        let now = chrono::Utc::now().timestamp();
+        let sort_order = state
+            .plans
+            .values()
+            .filter(|p| p.group_id == group_id)
+            .map(|p| p.sort_order + 1)
+            .max()
+            .unwrap_or(0);
+        state.plans.insert(
+            plan_id,
+            SkillPlan {
+                plan_id,
+                name: name.to_string(),
+                description: description.map(str::to_string),
+                auto_prerequisites: auto_prerequisites as i64,
+                created_at: now,
+                updated_at: now,
+                group_id,
+                sort_order,
+                owner_character_id: None,
+            },
+        );
+        Ok(plan_id)
+    }
+
+    async fn get_skill_plan(&self, plan_id: i64) -> Result<Option<SkillPlan>> {
+        Ok(self.state.lock().unwrap().plans.get(&plan_id).cloned())
+    }
+
+    async fn get_all_skill_plans(&self) -> Result<Vec<SkillPlan>> {
+        let state = self.state.lock().unwrap();
+        let mut plans: Vec<SkillPlan> = state.plans.values().cloned().collect();
+        plans.sort_by_key(|p| (p.group_id.unwrap_or(-1), p.sort_order, p.plan_id));
+        Ok(plans)
+    }
+
+    async fn delete_skill_plan(&self, plan_id: i64) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.plans.remove(&plan_id);
+        state.entries.retain(|e| e.plan_id != plan_id);
+        Ok(())
+    }
+
+    async fn get_plan_entries(&self, plan_id: i64) -> Result<Vec<SkillPlanEntry>> {
+        let state = self.state.lock().unwrap();
+        let mut entries: Vec<SkillPlanEntry> = state
+            .entries
+            .iter()
+            .filter(|e| e.plan_id == plan_id)
+            .cloned()
+            .collect();
+        entries.sort_by_key(|e| e.sort_order);
+        Ok(entries)
+    }
+
+    async fn replace_plan_entries(&self, plan_id: i64, entries: &[ReplacePlanEntry]) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.entries.retain(|e| e.plan_id != plan_id);
+        for (index, entry) in entries.iter().enumerate() {
+            let entry_id = state.next_id();
+            state.entries.push(SkillPlanEntry {
+                entry_id,
+                plan_id,
+                skill_type_id: entry.skill_type_id,
+                planned_level: entry.planned_level,
+                sort_order: index as i64,
+                entry_type: entry.entry_type.clone(),
+                notes: entry.notes.clone(),
+                excluded: entry.excluded,
+                priority: entry.priority,
+            });
+        }
+        if let Some(plan) = state.plans.get_mut(&plan_id) {
+            plan.updated_at = chrono::Utc::now().timestamp();
+        }
+        Ok(())
+    }
+
+    async fn reorder_plan_entries(&self, plan_id: i64, entry_ids: &[i64]) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        for (index, entry_id) in entry_ids.iter().enumerate() {
+            if let Some(entry) = state
+                .entries
+                .iter_mut()
+                .find(|e| e.entry_id == *entry_id && e.plan_id == plan_id)
+            {
+                entry.sort_order = index as i64;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl NotificationRepository for MemoryRepository {
+    async fn get_notifications(
+        &self,
+        character_id: Option<i64>,
+        status: Option<&str>,
+    ) -> Result<Vec<Notification>> {
+        let state = self.state.lock().unwrap();
+        let mut notifications: Vec<Notification> = state
+            .notifications
+            .iter()
+            .rev()
+            .filter(|n| character_id.is_none_or(|id| n.character_id == id))
+            .filter(|n| status.is_none_or(|s| n.status == s))
+            .cloned()
+            .collect();
+        notifications.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(notifications)
+    }
+
+    async fn create_notification(
+        &self,
+        character_id: i64,
+        notification_type: &str,
+        title: &str,
+        message: &str,
+    ) -> Result<i64> {
+        let mut state = self.state.lock().unwrap();
+        if !state.characters.contains_key(&character_id) {
+            anyhow::bail!("FOREIGN KEY constraint failed");
+        }
+        let id = state.next_id();
+        state.notifications.push(Notification {
+            id,
+            character_id,
+            notification_type: notification_type.to_string(),
+            title: title.to_string(),
+            message: message.to_string(),
+            status: "active".to_string(),
+            created_at: current_timestamp(),
+        });
+        Ok(id)
+    }
+
+    async fn dismiss_notification(&self, notification_id: i64) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(notification) = state
+            .notifications
+            .iter_mut()
+            .find(|n| n.id == notification_id)
+        {
+            notification.status = "dismissed".to_string();
+        }
+        Ok(())
+    }
+
+    async fn has_active_notification(
+        &self,
+        character_id: i64,
+        notification_type: &str,
+    ) -> Result<bool> {
+        let state = self.state.lock().unwrap();
+        Ok(state.notifications.iter().any(|n| {
+            n.character_id == character_id
+                && n.notification_type == notification_type
+                && n.status == "active"
+        }))
+    }
+
+    async fn clear_notification(&self, character_id: i64, notification_type: &str) -> Result<bool> {
+        let mut state = self.state.lock().unwrap();
+        let mut cleared = false;
+        for notification in state.notifications.iter_mut().filter(|n| {
+            n.character_id == character_id
+                && n.notification_type == notification_type
+                && n.status == "active"
+        }) {
+            notification.status = "dismissed".to_string();
+            cleared = true;
+        }
+        Ok(cleared)
+    }
+}
@@ -7,8 +7,11 @@ use std::path::PathBuf;
 use tempfile::NamedTempFile;
 
 pub mod fixtures;
+pub mod memory;
 pub mod sde_cache;
 
+pub use memory::MemoryRepository;
+
 pub struct TestDb {
     pub pool: Pool,
     _temp_file: NamedTempFile,
@@ -46,7 +49,12 @@ impl TestDb {
 
         let paths = sde_cache::ensure_sde_cached(&cache_dir).await?;
 
-        crate::sde::import_from_files_for_test(&db.pool, &paths).await?;
+        crate::sde::import_from_files_for_test(
+            &db.pool,
+            &paths,
+            crate::db::app_settings::SdeImportMode::Full,
+        )
+        .await?;
 
         Ok(db)
     }
@@ -23,6 +23,7 @@ const TARGET_FILES: &[&str] = &[
     "dogmaEffects.jsonl",
     "typeDogma.jsonl",
     "characterAttributes.jsonl",
+    "cloneGrades.jsonl",
 ];
 
 use lazy_static::lazy_static;
@@ -1,74 +1,217 @@
-use tauri::menu::MenuItem;
+use futures_util::stream::{self, StreamExt};
+use tauri::menu::{MenuItem, Submenu};
 use tauri::Runtime;
 
 use crate::db;
 use crate::esi;
 use crate::esi_helpers;
+use crate::queue_math;
 
-pub async fn count_training_characters(
+/// Training state of one account. Every character on an account should be
+/// training something, so an account with none is worth flagging.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountTraining {
+    pub name: String,
+    pub training: usize,
+    pub characters: usize,
+}
+
+impl AccountTraining {
+    pub fn is_idle(&self) -> bool {
+        self.training == 0
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TrainingSummary {
+    pub characters_training: usize,
+    /// Accounts in sort order; characters not assigned to one are left out.
+    pub accounts: Vec<AccountTraining>,
+}
+
+impl TrainingSummary {
+    pub fn accounts_training(&self) -> usize {
+        self.accounts.iter().filter(|a| !a.is_idle()).count()
+    }
+}
+
+/// Groups `(account id, is training)` per character by account.
+pub fn summarize(
+    accounts: &[db::accounts::Account],
+    characters: &[(Option<i64>, bool)],
+) -> TrainingSummary {
+    TrainingSummary {
+        characters_training: characters.iter().filter(|(_, training)| *training).count(),
+        accounts: accounts
+            .iter()
+            .map(|account| {
+                let on_account = characters
+                    .iter()
+                    .filter(|(account_id, _)| *account_id == Some(account.id));
+                AccountTraining {
+                    name: account.name.clone(),
+                    training: on_account.clone().filter(|(_, training)| *training).count(),
+                    characters: on_account.count(),
+                }
+            })
+            .collect(),
+    }
+}
+
+async fn is_training(
+    pool: &db::Pool,
+    rate_limits: &esi::RateLimitStore,
+    character_id: i64,
+) -> bool {
+    let Ok(access_token) = crate::auth::ensure_valid_access_token(pool, character_id).await else {
+        return false;
+    };
+    let Ok(client) = esi_helpers::create_authenticated_client(&access_token) else {
+        return false;
+    };
+    matches!(
+        esi_helpers::get_cached_skill_queue(pool, &client, character_id, rate_limits).await,
+        Ok(Some(queue_data))
+            if queue_math::currently_training(&queue_data, chrono::Utc::now()).is_some()
+    )
+}
+
+/// Checks every character's queue, a few at a time; see
+/// [`esi::RateLimitState::fetch_concurrency`].
+pub async fn get_training_summary(
     pool: &db::Pool,
     rate_limits: &esi::RateLimitStore,
-) -> Result<i32, String> {
+) -> Result<TrainingSummary, String> {
     let characters = db::get_all_characters(pool)
         .await
         .map_err(|e| format!("Failed to get characters: {}", e))?;
+    let accounts = db::get_all_accounts(pool)
+        .await
+        .map_err(|e| format!("Failed to get accounts: {}", e))?;
 
-    let mut count = 0;
-
-    for character in characters {
-        let access_token =
-            match crate::auth::ensure_valid_access_token(pool, character.character_id).await {
-                Ok(token) => token,
-                Err(_) => continue,
-            };
-
-        let client = match esi_helpers::create_authenticated_client(&access_token) {
-            Ok(client) => client,
-            Err(_) => continue,
-        };
-
-        if let Ok(Some(queue_data)) =
-            esi_helpers::get_cached_skill_queue(pool, &client, character.character_id, rate_limits)
-                .await
-        {
-            let is_training = queue_data.iter().any(|item| {
-                if let (Some(start_utc), Some(finish_utc)) = (item.start_date, item.finish_date) {
-                    let now = chrono::Utc::now();
-                    if now >= start_utc && now < finish_utc {
-                        return true;
-                    }
-                }
-                false
-            });
+    let concurrency = rate_limits
+        .read()
+        .await
+        .fetch_concurrency(chrono::Utc::now());
+    let states: Vec<(Option<i64>, bool)> = stream::iter(characters)
+        .map(|character| async move {
+            let _permit = esi::acquire_fetch_permit().await;
+            let training = is_training(pool, rate_limits, character.character_id).await;
+            (character.account_id, training)
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
 
-            if is_training {
-                count += 1;
-            }
-        }
+    Ok(summarize(&accounts, &states))
+}
+
+pub fn training_count_text(summary: Option<&TrainingSummary>) -> String {
+    let Some(summary) = summary else {
+        return "? characters training".to_string();
+    };
+    let characters = match summary.characters_training {
+        1 => "1 character training".to_string(),
+        count => format!("{} characters training", count),
+    };
+    if summary.accounts.is_empty() {
+        return characters;
     }
+    format!(
+        "{} ({} of {} accounts)",
+        characters,
+        summary.accounts_training(),
+        summary.accounts.len()
+    )
+}
 
-    Ok(count)
+fn account_text(account: &AccountTraining) -> String {
+    if account.is_idle() {
+        format!("⚠ {}: idle", account.name)
+    } else {
+        format!(
+            "{}: {} of {} training",
+            account.name, account.training, account.characters
+        )
+    }
 }
 
 pub async fn update_tray_menu<R: Runtime>(
-    _app: &tauri::AppHandle<R>,
+    app: &tauri::AppHandle<R>,
     pool: &db::Pool,
     rate_limits: &esi::RateLimitStore,
     training_count_item: &MenuItem<R>,
+    accounts_submenu: &Submenu<R>,
 ) {
-    let count = count_training_characters(pool, rate_limits)
-        .await
-        .unwrap_or(-1);
+    let summary = get_training_summary(pool, rate_limits).await.ok();
 
-    let text = if count < 0 {
-        "? characters training".to_string()
-    } else if count == 1 {
-        "1 character training".to_string()
-    } else {
-        format!("{} characters training", count)
-    };
-
-    if let Err(e) = training_count_item.set_text(&text) {
+    if let Err(e) = training_count_item.set_text(training_count_text(summary.as_ref())) {
         eprintln!("Failed to update tray menu text: {}", e);
     }
+
+    let accounts = summary.map(|s| s.accounts).unwrap_or_default();
+    if let Err(e) = rebuild_accounts_submenu(app, accounts_submenu, &accounts) {
+        eprintln!("Failed to update tray accounts menu: {}", e);
+    }
+}
+
+fn rebuild_accounts_submenu<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    submenu: &Submenu<R>,
+    accounts: &[AccountTraining],
+) -> tauri::Result<()> {
+    while submenu.remove_at(0)?.is_some() {}
+    for account in accounts {
+        submenu.append(&MenuItem::new(
+            app,
+            account_text(account),
+            false,
+            None::<&str>,
+        )?)?;
+    }
+    submenu.set_enabled(!accounts.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(id: i64, name: &str) -> db::accounts::Account {
+        db::accounts::Account {
+            id,
+            name: name.to_string(),
+            sort_order: id,
+        }
+    }
+
+    #[test]
+    fn flags_accounts_with_nobody_training() {
+        let accounts = [account(1, "Main"), account(2, "Alts")];
+        let characters = [
+            (Some(1), true),
+            (Some(1), false),
+            (Some(2), false),
+            (None, true),
+        ];
+
+        let summary = summarize(&accounts, &characters);
+
+        assert_eq!(summary.characters_training, 2);
+        assert_eq!(summary.accounts_training(), 1);
+        assert!(!summary.accounts[0].is_idle());
+        assert!(summary.accounts[1].is_idle());
+        assert_eq!(
+            training_count_text(Some(&summary)),
+            "2 characters training (1 of 2 accounts)"
+        );
+        assert_eq!(account_text(&summary.accounts[0]), "Main: 1 of 2 training");
+        assert_eq!(account_text(&summary.accounts[1]), "⚠ Alts: idle");
+    }
+
+    #[test]
+    fn without_accounts_only_counts_characters() {
+        let summary = summarize(&[], &[(None, true)]);
+        assert_eq!(training_count_text(Some(&summary)), "1 character training");
+        assert_eq!(training_count_text(None), "? characters training");
+    }
 }
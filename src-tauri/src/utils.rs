@@ -3,6 +3,7 @@ use std::collections::HashMap;
 use sqlx::{QueryBuilder, Row, Sqlite};
 
 use crate::db;
+use crate::db::app_settings::LevelFormat;
 
 #[derive(Debug, Clone)]
 pub struct SkillAttributes {
@@ -132,6 +133,44 @@ pub fn sp_for_level_slice(rank: i64, level: i32) -> i64 {
     calculate_sp_for_level(rank, level) - calculate_sp_for_level(rank, level - 1)
 }
 
+/// Highest level fully trained with `sp` skillpoints in a skill of `rank`.
+pub fn level_for_sp(rank: i64, sp: i64) -> i32 {
+    (1..=5)
+        .take_while(|level| calculate_sp_for_level(rank, *level) <= sp)
+        .last()
+        .unwrap_or(0)
+}
+
+/// SP still needed to finish `to_level` from `from_sp` skillpoints in the
+/// skill; partial progress counts, and an already-reached level needs none.
+pub fn sp_needed(rank: i64, from_sp: i64, to_level: i32) -> i64 {
+    (calculate_sp_for_level(rank, to_level) - from_sp).max(0)
+}
+
+const ROMAN_LEVELS: [&str; 5] = ["I", "II", "III", "IV", "V"];
+
+/// A skill level as written in user-facing text; levels outside 1..=5 stay
+/// arabic whatever the format.
+pub fn format_level(level: i64, format: LevelFormat) -> String {
+    let roman = usize::try_from(level - 1)
+        .ok()
+        .and_then(|index| ROMAN_LEVELS.get(index));
+    match (format, roman) {
+        (LevelFormat::Roman, Some(roman)) => roman.to_string(),
+        _ => level.to_string(),
+    }
+}
+
+/// Reads a level written either way, "4" or "IV" (any case).
+pub fn parse_level(text: &str) -> Option<i64> {
+    text.parse().ok().or_else(|| {
+        ROMAN_LEVELS
+            .iter()
+            .position(|roman| roman.eq_ignore_ascii_case(text))
+            .map(|index| index as i64 + 1)
+    })
+}
+
 #[cfg(test)]
 mod sp_slice_tests {
     use super::*;
@@ -160,4 +199,38 @@ mod sp_slice_tests {
             prev = slice;
         }
     }
+
+    #[test]
+    fn level_for_sp_matches_level_boundaries() {
+        let rank = 2;
+        assert_eq!(level_for_sp(rank, 0), 0);
+        assert_eq!(level_for_sp(rank, calculate_sp_for_level(rank, 3) - 1), 2);
+        assert_eq!(level_for_sp(rank, calculate_sp_for_level(rank, 3)), 3);
+        assert_eq!(level_for_sp(rank, i64::MAX), 5);
+    }
+
+    #[test]
+    fn levels_format_and_parse_both_ways() {
+        assert_eq!(format_level(4, LevelFormat::Roman), "IV");
+        assert_eq!(format_level(4, LevelFormat::Arabic), "4");
+        assert_eq!(format_level(0, LevelFormat::Roman), "0");
+        for level in 1..=5 {
+            assert_eq!(
+                parse_level(&format_level(level, LevelFormat::Roman)),
+                Some(level)
+            );
+        }
+        assert_eq!(parse_level("iii"), Some(3));
+        assert_eq!(parse_level("0"), Some(0));
+        assert_eq!(parse_level("VI"), None);
+    }
+
+    #[test]
+    fn sp_needed_counts_partial_progress() {
+        let rank = 3;
+        let level_four = calculate_sp_for_level(rank, 4);
+        assert_eq!(sp_needed(rank, 0, 4), level_four);
+        assert_eq!(sp_needed(rank, level_four - 100, 4), 100);
+        assert_eq!(sp_needed(rank, level_four + 1, 4), 0);
+    }
 }
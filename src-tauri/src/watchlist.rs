@@ -0,0 +1,115 @@
+//! Characters the user follows without owning them (recruiting, scouting).
+//! Everything comes from public ESI, so no token or scope is involved. A
+//! scheduled task (see `scheduler`) rechecks every watched character periodically and raises
+//! a system notification when one changes corporation or alliance.
+
+use anyhow::{bail, Result};
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::affiliation::{self, Affiliation};
+use crate::db::watchlist::WatchedCharacter;
+use crate::{db, esi, events};
+
+/// Resolves `name_or_id` and starts watching that character.
+pub async fn add(
+    pool: &db::Pool,
+    rate_limits: &esi::RateLimitStore,
+    name_or_id: &str,
+) -> Result<WatchedCharacter> {
+    let client = reqwest::Client::new();
+    let Some(character_id) =
+        affiliation::resolve_character_id(&client, rate_limits, name_or_id).await?
+    else {
+        bail!("No character named \"{}\"", name_or_id.trim());
+    };
+    if db::get_character(pool, character_id).await?.is_some() {
+        bail!("This character is already added to skillmon");
+    }
+
+    refresh_character(pool, &client, rate_limits, character_id).await?;
+    match db::watchlist::get_watched_character(pool, character_id).await? {
+        Some(watched) => Ok(watched),
+        None => bail!("Character {} not found", character_id),
+    }
+}
+
+/// Fetches and stores one character's public info. Returns the previous
+/// affiliation when it changed.
+async fn refresh_character(
+    pool: &db::Pool,
+    client: &reqwest::Client,
+    rate_limits: &esi::RateLimitStore,
+    character_id: i64,
+) -> Result<Option<Affiliation>> {
+    let Some(character) =
+        affiliation::fetch_public_character(pool, client, rate_limits, character_id).await?
+    else {
+        bail!("Character {} not found", character_id);
+    };
+
+    db::watchlist::record_watched_character(
+        pool,
+        character.character_id,
+        &character.name,
+        &character.affiliation,
+        &affiliation::portrait_url(character.character_id),
+        chrono::Utc::now().timestamp(),
+    )
+    .await
+}
+
+/// Rechecks every watched character, notifying on membership changes.
+pub async fn refresh_all(
+    app: &AppHandle,
+    pool: &db::Pool,
+    rate_limits: &esi::RateLimitStore,
+) -> Result<()> {
+    let client = reqwest::Client::new();
+    let watched = db::watchlist::get_watched_characters(pool).await?;
+    let mut changed = false;
+
+    for character in watched {
+        let previous =
+            match refresh_character(pool, &client, rate_limits, character.character_id).await {
+                Ok(previous) => previous,
+                Err(e) => {
+                    eprintln!(
+                        "watchlist: failed to refresh {}: {:#}",
+                        character.character_id, e
+                    );
+                    continue;
+                }
+            };
+        let Some(previous) = previous else {
+            continue;
+        };
+        changed = true;
+
+        let Some(current) =
+            db::watchlist::get_watched_character(pool, character.character_id).await?
+        else {
+            continue;
+        };
+        let title = format!("{} changed affiliation", current.character_name);
+        let body = format!("{} → {}", previous.label(), current.affiliation().label());
+        if let Err(e) = app
+            .notification()
+            .builder()
+            .title(&title)
+            .body(&body)
+            .show()
+        {
+            eprintln!("Failed to send system notification: {}", e);
+        }
+    }
+
+    if changed {
+        let watched = db::watchlist::get_watched_characters(pool).await?;
+        if let Err(e) = events::emit_watchlist_changed(app, &watched) {
+            eprintln!("Failed to emit watchlist update: {}", e);
+        }
+    }
+
+    Ok(())
+}
@@ -13,6 +13,29 @@ interface NotificationSettingsProps {
   characterId: number | null;
 }
 
+// On unless the character's setting row disables them.
+const DEFAULT_ON_TOGGLES = [
+  {
+    type: NOTIFICATION_TYPES.SKILL_QUEUE_PAUSED,
+    id: 'skill-queue-paused',
+    label: 'Skill Queue Paused',
+    description: 'Get notified when training is paused in game',
+  },
+  {
+    type: NOTIFICATION_TYPES.SKILL_QUEUE_EMPTY,
+    id: 'skill-queue-empty',
+    label: 'Skill Queue Empty',
+    description: 'Get notified when nothing is left in your skill queue',
+  },
+  {
+    type: NOTIFICATION_TYPES.REFRESH_STALE,
+    id: 'refresh-stale',
+    label: 'Character Not Refreshing',
+    description:
+      'Get notified when a character has not refreshed for a week, usually an expired login',
+  },
+] as const;
+
 export function NotificationSettings({
   characterId,
 }: NotificationSettingsProps) {
@@ -35,6 +58,32 @@ export function NotificationSettings({
     }
   };
 
+  // Multi-stage mode, e.g. [72, 24, 4, 0]; empty uses the single threshold.
+  const getStagesFromConfig = (configString?: string | null) => {
+    if (!configString) return '';
+    try {
+      const parsed = JSON.parse(configString) as { stages_hours?: unknown };
+      return Array.isArray(parsed.stages_hours)
+        ? parsed.stages_hours.join(', ')
+        : '';
+    } catch {
+      return '';
+    }
+  };
+
+  const parseStages = (value: string) =>
+    value
+      .split(',')
+      .map((part) => parseFloat(part.trim()))
+      .filter((hours) => !isNaN(hours) && hours >= 0);
+
+  const buildConfig = (threshold: number, stagesText: string) => {
+    const stages = parseStages(stagesText);
+    return stages.length > 0
+      ? { threshold_hours: threshold, stages_hours: stages }
+      : { threshold_hours: threshold };
+  };
+
   const computedEnabled = useMemo(
     () => skillQueueLowSetting?.enabled ?? false,
     [skillQueueLowSetting]
@@ -46,17 +95,22 @@ export function NotificationSettings({
 
   const [enabled, setEnabled] = useState(computedEnabled);
   const [thresholdHours, setThresholdHours] = useState(computedThresholdHours);
+  const [stages, setStages] = useState(() =>
+    getStagesFromConfig(skillQueueLowSetting?.config)
+  );
 
   useEffect(() => {
     if (skillQueueLowSetting) {
       startTransition(() => {
         setEnabled(skillQueueLowSetting.enabled);
         setThresholdHours(getThresholdFromConfig(skillQueueLowSetting.config));
+        setStages(getStagesFromConfig(skillQueueLowSetting.config));
       });
     } else {
       startTransition(() => {
         setEnabled(false);
         setThresholdHours(24);
+        setStages('');
       });
     }
   }, [skillQueueLowSetting]);
@@ -69,7 +123,7 @@ export function NotificationSettings({
       characterId,
       notificationType: NOTIFICATION_TYPES.SKILL_QUEUE_LOW,
       enabled: newEnabled,
-      config: newEnabled ? { threshold_hours: thresholdHours } : undefined,
+      config: newEnabled ? buildConfig(thresholdHours, stages) : undefined,
     });
   };
 
@@ -82,12 +136,23 @@ export function NotificationSettings({
           characterId,
           notificationType: NOTIFICATION_TYPES.SKILL_QUEUE_LOW,
           enabled: true,
-          config: { threshold_hours: numValue },
+          config: buildConfig(numValue, stages),
         });
       }
     }
   };
 
+  const handleStagesCommit = () => {
+    if (characterId && enabled) {
+      updateSetting.mutate({
+        characterId,
+        notificationType: NOTIFICATION_TYPES.SKILL_QUEUE_LOW,
+        enabled: true,
+        config: buildConfig(thresholdHours, stages),
+      });
+    }
+  };
+
   if (!characterId) {
     return (
       <div className="p-4 text-muted-foreground">
@@ -136,8 +201,54 @@ export function NotificationSettings({
                 You will be notified when your skill queue has less than this
                 many hours remaining
               </p>
+              <Label htmlFor="stages-hours" className="text-sm">
+                Stages (hours)
+              </Label>
+              <Input
+                id="stages-hours"
+                placeholder="72, 24, 4, 0"
+                value={stages}
+                onChange={(e) => setStages(e.target.value)}
+                onBlur={handleStagesCommit}
+                disabled={updateSetting.isPending}
+                className="w-48"
+              />
+              <p className="text-xs text-muted-foreground">
+                Optional. Notifies once as the queue drops below each stage (0
+                means empty) instead of using the threshold
+              </p>
             </div>
           )}
+          {DEFAULT_ON_TOGGLES.map((toggle) => (
+            <div
+              key={toggle.type}
+              className="flex items-center justify-between"
+            >
+              <div className="space-y-0.5 flex-1">
+                <Label htmlFor={toggle.id} className="text-base">
+                  {toggle.label}
+                </Label>
+                <p className="text-sm text-muted-foreground">
+                  {toggle.description}
+                </p>
+              </div>
+              <Switch
+                id={toggle.id}
+                checked={
+                  settings.find((s) => s.notification_type === toggle.type)
+                    ?.enabled ?? true
+                }
+                onCheckedChange={(checked) =>
+                  updateSetting.mutate({
+                    characterId,
+                    notificationType: toggle.type,
+                    enabled: checked,
+                  })
+                }
+                disabled={updateSetting.isPending}
+              />
+            </div>
+          ))}
         </div>
       </div>
     </div>
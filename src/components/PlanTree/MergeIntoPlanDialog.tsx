@@ -195,6 +195,8 @@ export function MergeIntoPlanDialog({
         planned_level: entry.planned_level,
         entry_type: entry.entry_type,
         notes: entry.notes,
+        excluded: entry.excluded,
+        priority: entry.priority,
       })
     );
 
@@ -15,11 +15,15 @@ import { Label } from '@/components/ui/label';
 import { RadioGroup, RadioGroupItem } from '@/components/ui/radio-group';
 import { Textarea } from '@/components/ui/textarea';
 import {
+  useImportPlanFromFitting,
+  useImportSkillPlanEmp,
   useImportSkillPlanJson,
   useImportSkillPlanText,
   useImportSkillPlanXml,
 } from '@/hooks/tauri/useSkillPlans';
 
+type ImportFormat = 'text' | 'xml' | 'json' | 'fitting';
+
 interface ImportPlanDialogProps {
   open: boolean;
   onOpenChange: (open: boolean) => void;
@@ -31,17 +35,38 @@ export function ImportPlanDialog({
   onOpenChange,
   planId,
 }: ImportPlanDialogProps) {
-  const [format, setFormat] = useState<'text' | 'xml' | 'json'>('text');
+  const [format, setFormat] = useState<ImportFormat>('text');
   const [text, setText] = useState('');
   const fileInputRef = useRef<HTMLInputElement>(null);
   const importTextMutation = useImportSkillPlanText();
   const importXmlMutation = useImportSkillPlanXml();
   const importJsonMutation = useImportSkillPlanJson();
+  const importEmpMutation = useImportSkillPlanEmp();
+  const importFittingMutation = useImportPlanFromFitting();
 
   const handleFileSelect = async (e: React.ChangeEvent<HTMLInputElement>) => {
     const file = e.target.files?.[0];
     if (!file) return;
 
+    // EVEMon plan files are gzipped, so they go to the backend as bytes and
+    // are imported straight away instead of being shown in the text area.
+    if (file.name.toLowerCase().endsWith('.emp')) {
+      try {
+        const bytes = new Uint8Array(await file.arrayBuffer());
+        await importEmpMutation.mutateAsync({
+          planId,
+          data: Array.from(bytes),
+        });
+        setText('');
+        onOpenChange(false);
+      } catch (err) {
+        console.error('Failed to import .emp plan:', err);
+      } finally {
+        e.target.value = '';
+      }
+      return;
+    }
+
     try {
       const contents = await file.text();
       setText(contents);
@@ -62,6 +87,9 @@ export function ImportPlanDialog({
           const planJson = JSON.parse(text);
           return importJsonMutation.mutateAsync({ plan: planJson });
         })
+        .with('fitting', () =>
+          importFittingMutation.mutateAsync({ planId, text })
+        )
         .exhaustive();
 
       setText('');
@@ -78,12 +106,16 @@ export function ImportPlanDialog({
     importTextMutation.error ||
     importXmlMutation.error ||
     importJsonMutation.error ||
+    importEmpMutation.error ||
+    importFittingMutation.error ||
     null;
 
   const isPending =
     importTextMutation.isPending ||
     importXmlMutation.isPending ||
-    importJsonMutation.isPending;
+    importJsonMutation.isPending ||
+    importEmpMutation.isPending ||
+    importFittingMutation.isPending;
 
   return (
     <Dialog open={open} onOpenChange={onOpenChange}>
@@ -91,8 +123,9 @@ export function ImportPlanDialog({
         <DialogHeader>
           <DialogTitle>Import Skill Plan</DialogTitle>
           <DialogDescription>
-            Import skills from a plain text or XML file. Paste the content below
-            or select a file.
+            Import skills from a plain text or XML file, an EVEMon .emp plan,
+            or every skill needed to fly an EFT fitting. Paste the content
+            below or select a file.
           </DialogDescription>
         </DialogHeader>
         <div className="space-y-4 py-4">
@@ -100,7 +133,7 @@ export function ImportPlanDialog({
             <Label>Format</Label>
             <RadioGroup
               value={format}
-              onValueChange={(v) => setFormat(v as 'text' | 'xml' | 'json')}
+              onValueChange={(v) => setFormat(v as ImportFormat)}
             >
               <div className="flex items-center space-x-2">
                 <RadioGroupItem value="text" id="text" />
@@ -120,6 +153,12 @@ export function ImportPlanDialog({
                   XML (PyFA/EVEMon format)
                 </Label>
               </div>
+              <div className="flex items-center space-x-2">
+                <RadioGroupItem value="fitting" id="fitting" />
+                <Label htmlFor="fitting" className="cursor-pointer">
+                  Fitting (EFT/Pyfa)
+                </Label>
+              </div>
             </RadioGroup>
           </div>
           <div className="space-y-2">
@@ -138,8 +177,9 @@ export function ImportPlanDialog({
                 type="file"
                 accept={match(format)
                   .with('text', () => '.txt')
-                  .with('json', () => '.json')
-                  .with('xml', () => '.xml')
+                  .with('json', () => '.json,.skillmon')
+                  .with('xml', () => '.xml,.emp')
+                  .with('fitting', () => '.txt,.cfg')
                   .exhaustive()}
                 onChange={handleFileSelect}
                 className="hidden"
@@ -156,6 +196,7 @@ export function ImportPlanDialog({
                 )
                 .with('json', () => 'Paste Skillmon JSON content here')
                 .with('xml', () => 'Paste XML content here')
+                .with('fitting', () => 'Paste an EFT fitting block here')
                 .exhaustive()}
               rows={12}
               className="font-mono text-sm max-h-[400px] overflow-y-auto"
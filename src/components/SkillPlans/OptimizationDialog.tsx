@@ -95,7 +95,7 @@ export function OptimizationDialog({
   const [mode, setMode] = useState<OptimizationMode>('attributes');
   const [maxRemaps, setMaxRemaps] = useState(1);
   const [persistRemaps, setPersistRemaps] = useState(true);
-  const { optimization, isLoading, error } = useOptimization(
+  const { optimization, isLoading, error, progress, cancel } = useOptimization(
     planId,
     implants,
     currentRemap,
@@ -272,7 +272,15 @@ export function OptimizationDialog({
                   <Loader2 className="h-8 w-8 animate-spin text-primary" />
                   <p className="text-sm text-muted-foreground">
                     Running optimization algorithm...
+                    {progress &&
+                      progress.total > 0 &&
+                      ` ${Math.floor((progress.evaluated / progress.total) * 100)}%`}
                   </p>
+                  {mode === 'reorder' && (
+                    <Button variant="outline" size="sm" onClick={() => cancel()}>
+                      Cancel
+                    </Button>
+                  )}
                 </div>
               ))
               .with({ error: P.not(null) }, ({ error }) => (
@@ -11,6 +11,7 @@ import { writeText } from '@tauri-apps/plugin-clipboard-manager';
 import { save } from '@tauri-apps/plugin-dialog';
 import { writeTextFile } from '@tauri-apps/plugin-fs';
 import {
+  ArrowDownUp,
   ChevronDown,
   Copy,
   Download,
@@ -39,7 +40,7 @@ import {
 import { Switch } from '@/components/ui/switch';
 import { Tabs, TabsContent, TabsList, TabsTrigger } from '@/components/ui/tabs';
 import { Textarea } from '@/components/ui/textarea';
-import type { ValidationResponse } from '@/generated/types';
+import { PlanSortPreset, type ValidationResponse } from '@/generated/types';
 import { queryKeys } from '@/hooks/tauri/queryKeys';
 import { usePlanRemaps } from '@/hooks/tauri/useRemaps';
 import {
@@ -47,6 +48,7 @@ import {
   useExportSkillPlanJson,
   useReorderPlanEntries,
   useSkillPlanWithEntries,
+  useSortPlan,
   useUpdateSkillPlan,
   useValidateReorder,
 } from '@/hooks/tauri/useSkillPlans';
@@ -108,6 +110,7 @@ export function PlanEditor({ planId }: PlanEditorProps) {
   const [proposedValidation, setProposedValidation] =
     useState<ValidationResponse | null>(null);
   const exportJsonMutation = useExportSkillPlanJson();
+  const sortPlanMutation = useSortPlan();
 
   const validationMap = useMemo(() => {
     const map = new Map<string, 'error' | 'warning'>();
@@ -268,6 +271,15 @@ export function PlanEditor({ planId }: PlanEditorProps) {
     };
   }, [localItems, isDragging, planId, validateReorderMutation]);
 
+  const handleSort = async (preset: PlanSortPreset) => {
+    try {
+      await sortPlanMutation.mutateAsync({ planId, preset });
+    } catch (err) {
+      console.error('Failed to sort plan:', err);
+      toast.error(err instanceof Error ? err.message : String(err));
+    }
+  };
+
   const handleExportJson = async () => {
     if (!data) return;
     setIsExportingJson(true);
@@ -276,7 +288,7 @@ export function PlanEditor({ planId }: PlanEditorProps) {
       const filePath = await save({
         title: 'Save Skill Plan (JSON)',
         defaultPath: `${data.plan.name}.skillmon.json`,
-        filters: [{ name: 'Skillmon Plan', extensions: ['json'] }],
+        filters: [{ name: 'Skillmon Plan', extensions: ['json', 'skillmon'] }],
       });
 
       if (filePath) {
@@ -609,6 +621,40 @@ export function PlanEditor({ planId }: PlanEditorProps) {
             Copy Text
           </Button>
 
+          <DropdownMenu>
+            <DropdownMenuTrigger asChild>
+              <Button
+                variant="outline"
+                size="sm"
+                disabled={sortPlanMutation.isPending}
+              >
+                <ArrowDownUp className="mr-2 h-4 w-4" />
+                Sort
+                <ChevronDown className="ml-2 h-4 w-4" />
+              </Button>
+            </DropdownMenuTrigger>
+            <DropdownMenuContent align="end">
+              <DropdownMenuItem
+                onClick={() => handleSort(PlanSortPreset.TrainingTime)}
+              >
+                By training time
+              </DropdownMenuItem>
+              <DropdownMenuItem onClick={() => handleSort(PlanSortPreset.Rank)}>
+                By rank
+              </DropdownMenuItem>
+              <DropdownMenuItem
+                onClick={() => handleSort(PlanSortPreset.AttributeCluster)}
+              >
+                By attribute pair
+              </DropdownMenuItem>
+              <DropdownMenuItem
+                onClick={() => handleSort(PlanSortPreset.Priority)}
+              >
+                By priority
+              </DropdownMenuItem>
+            </DropdownMenuContent>
+          </DropdownMenu>
+
           <DropdownMenu>
             <DropdownMenuTrigger asChild>
               <Button variant="outline" size="sm">
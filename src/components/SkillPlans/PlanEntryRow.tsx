@@ -238,6 +238,36 @@ export function PlanEntryRow({
     }
   };
 
+  const handleToggleExcluded = async () => {
+    const currentEntryId = entry.entry_id;
+    const wasExcluded = entry.excluded;
+
+    try {
+      await trackAction(
+        `${wasExcluded ? 'Include' : 'Exclude'} ${
+          entry.skill_name
+        } ${levelRoman}`,
+        async () => {
+          await updateEntryMutation.mutateAsync({
+            entryId: currentEntryId,
+            excluded: !wasExcluded,
+          });
+        },
+        async () => {
+          await updateEntryMutation.mutateAsync({
+            entryId: currentEntryId,
+            excluded: wasExcluded,
+          });
+        }
+      );
+    } catch (err) {
+      console.error('Failed to update entry:', err);
+      toast.error(
+        err instanceof Error ? err.message : 'Failed to update entry'
+      );
+    }
+  };
+
   const spPercentage =
     totalPlanSP > 0 ? (entry.skillpoints_for_level / totalPlanSP) * 100 : 0;
   const MIN_WIDTH_PERCENTAGE = 0.2;
@@ -250,6 +280,7 @@ export function PlanEntryRow({
           className={cn(
             'relative px-4 py-3 border-b border-border/50 transition-colors',
             isPrerequisite && 'bg-muted/30',
+            entry.excluded && 'opacity-60',
             isDragging && 'bg-accent opacity-50',
             validationStatus === 'error' &&
               'bg-destructive/10 border-destructive/50',
@@ -283,6 +314,16 @@ export function PlanEntryRow({
                 >
                   {entry.skill_name} {levelRoman}
                 </span>
+                {entry.excluded && (
+                  <span className="text-xs text-muted-foreground">
+                    Excluded from training time
+                  </span>
+                )}
+                {entry.requires_omega && (
+                  <span className="text-xs text-status-paused">
+                    Omega only
+                  </span>
+                )}
                 {entry.notes && (
                   <span className="text-xs text-muted-foreground truncate">
                     {entry.notes}
@@ -314,6 +355,11 @@ export function PlanEntryRow({
                   >
                     Add Note
                   </DropdownMenuItem>
+                  <DropdownMenuItem onClick={handleToggleExcluded}>
+                    {entry.excluded
+                      ? 'Include in Estimates'
+                      : 'Exclude from Estimates'}
+                  </DropdownMenuItem>
                   <DropdownMenuItem
                     onClick={handleRemoveSkillLevel}
                     className="text-destructive focus:text-destructive"
@@ -13,6 +13,7 @@ import { useDeleteRemap, usePlanRemaps } from '@/hooks/tauri/useRemaps';
 import { formatAttributeBonus } from '@/lib/utils';
 
 import { OptimizationDialog } from './OptimizationDialog';
+import { TrainingPausesCard } from './TrainingPausesCard';
 
 interface SimulationPanelProps {
   planId: number;
@@ -309,6 +310,11 @@ export function SimulationPanel({
           </div>
         </CardContent>
       </Card>
+
+      <TrainingPausesCard
+        pauses={profile.pauses}
+        onChange={(pauses) => onProfileChange({ ...profile, pauses })}
+      />
     </div>
   );
 }
@@ -36,7 +36,7 @@ export function SimulationTab({ planId }: SimulationTabProps) {
 
   useEffect(() => {
     if (selectedCharacterId && characterAttributes) {
-      setProfile({
+      setProfile((prev) => ({
         implants: {
           charisma: characterAttributes.charisma.implants,
           intelligence: characterAttributes.intelligence.implants,
@@ -66,9 +66,10 @@ export function SimulationTab({ planId }: SimulationTabProps) {
                 },
               ]
             : [],
-      });
+        pauses: prev.pauses,
+      }));
     } else if (!selectedCharacterId) {
-      setProfile({
+      setProfile((prev) => ({
         implants: {
           charisma: 0,
           intelligence: 0,
@@ -78,7 +79,8 @@ export function SimulationTab({ planId }: SimulationTabProps) {
         },
         remaps: [],
         accelerators: [],
-      });
+        pauses: prev.pauses,
+      }));
     }
   }, [selectedCharacterId, characterAttributes, setProfile]);
 
@@ -48,6 +48,12 @@ export function SimulationTimeline({
         <CardTitle className="h-card">Simulation Results</CardTitle>
         <div className="h-page">
           Total Time: {formatDuration(result.total_seconds)}
+          {result.paused_seconds > 0 && (
+            <span className="text-muted-foreground">
+              {' '}
+              (incl. {formatDuration(result.paused_seconds)} paused)
+            </span>
+          )}
         </div>
       </CardHeader>
       <CardContent className="flex-1 min-h-0 p-0">
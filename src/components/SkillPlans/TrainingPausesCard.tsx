@@ -0,0 +1,105 @@
+import { Plus, Trash2 } from 'lucide-react';
+import { useState } from 'react';
+
+import { Button } from '@/components/ui/button';
+import { Card, CardContent, CardHeader, CardTitle } from '@/components/ui/card';
+import { Input } from '@/components/ui/input';
+import { Label } from '@/components/ui/label';
+import type { TrainingPause } from '@/generated/types';
+
+const DAY_SECONDS = 86_400;
+
+interface TrainingPausesCardProps {
+  pauses: TrainingPause[];
+  onChange: (pauses: TrainingPause[]) => void;
+}
+
+/**
+ * Planned stretches without training (e.g. an account lapse). The simulation
+ * counts pause offsets from now, so dates are converted on the way in and out.
+ */
+export function TrainingPausesCard({
+  pauses,
+  onChange,
+}: TrainingPausesCardProps) {
+  const [startDate, setStartDate] = useState('');
+  const [days, setDays] = useState('30');
+
+  const handleAdd = () => {
+    const start = new Date(startDate).getTime();
+    const length = Number(days);
+    if (Number.isNaN(start) || !(length > 0)) return;
+
+    onChange(
+      [
+        ...pauses,
+        {
+          start_seconds: Math.max(0, Math.floor((start - Date.now()) / 1000)),
+          duration_seconds: Math.round(length * DAY_SECONDS),
+        },
+      ].sort((a, b) => a.start_seconds - b.start_seconds)
+    );
+    setStartDate('');
+  };
+
+  return (
+    <Card>
+      <CardHeader className="pb-2">
+        <CardTitle className="text-sm font-medium">Training Pauses</CardTitle>
+      </CardHeader>
+      <CardContent className="grid gap-3">
+        {pauses.map((pause, index) => (
+          <div
+            key={`${pause.start_seconds}-${index}`}
+            className="flex items-center justify-between text-sm"
+          >
+            <span>
+              {new Date(
+                Date.now() + pause.start_seconds * 1000
+              ).toLocaleDateString()}{' '}
+              · {Math.round(pause.duration_seconds / DAY_SECONDS)} days
+            </span>
+            <Button
+              variant="ghost"
+              size="icon"
+              className="h-7 w-7 text-destructive hover:text-destructive hover:bg-destructive/10"
+              onClick={() => onChange(pauses.filter((_, i) => i !== index))}
+            >
+              <Trash2 className="h-3.5 w-3.5" />
+            </Button>
+          </div>
+        ))}
+        <div className="flex items-end gap-2">
+          <div className="grid flex-1 gap-1">
+            <Label htmlFor="pause-start">Starts</Label>
+            <Input
+              id="pause-start"
+              type="date"
+              value={startDate}
+              onChange={(e) => setStartDate(e.target.value)}
+            />
+          </div>
+          <div className="grid w-20 gap-1">
+            <Label htmlFor="pause-days">Days</Label>
+            <Input
+              id="pause-days"
+              type="number"
+              min={1}
+              value={days}
+              onChange={(e) => setDays(e.target.value)}
+            />
+          </div>
+          <Button
+            variant="outline"
+            size="icon"
+            className="h-9 w-9"
+            onClick={handleAdd}
+            disabled={!startDate}
+          >
+            <Plus className="h-4 w-4" />
+          </Button>
+        </div>
+      </CardContent>
+    </Card>
+  );
+}
@@ -439,6 +439,11 @@ describe('calculateCompletionPercentage', () => {
     });
     expect(calculateCompletionPercentage(skill)).toBe(0);
   });
+
+  it('prefers the backend-computed percentComplete', () => {
+    const skill = createMockSkill({ currentSp: 0, percentComplete: 42.5 });
+    expect(calculateCompletionPercentage(skill)).toBe(42.5);
+  });
 });
 
 describe('calculateTrainingHours', () => {
@@ -570,6 +575,15 @@ describe('calculateTrainingHours', () => {
     const expectedHours = remainingSP / (10 * 60);
     expect(calculateTrainingHours(skill)).toBe(expectedHours);
   });
+
+  it('prefers the backend-computed secondsRemaining', () => {
+    const skill = createMockSkill({
+      currentSp: 0,
+      spPerMinute: 10,
+      secondsRemaining: 1800,
+    });
+    expect(calculateTrainingHours(skill)).toBe(0.5);
+  });
 });
 
 describe('isCurrentlyTraining', () => {
@@ -53,6 +53,12 @@ export function formatTimeRemaining(
 }
 
 export function calculateTimeToTrain(skill: SkillQueueItem): string | null {
+  if (skill.secondsRemaining != null) {
+    return skill.secondsRemaining <= 0
+      ? 'Complete'
+      : formatDurationFromHours(skill.secondsRemaining / 3600);
+  }
+
   if (!skill.spPerMinute || skill.spPerMinute <= 0) {
     return null;
   }
@@ -80,6 +86,10 @@ export function calculateTimeToTrain(skill: SkillQueueItem): string | null {
 }
 
 export function calculateCompletionPercentage(skill: SkillQueueItem): number {
+  if (skill.percentComplete != null) {
+    return skill.percentComplete;
+  }
+
   if (skill.levelStartSp == null || skill.levelEndSp == null) {
     return 0;
   }
@@ -109,6 +119,10 @@ export function calculateCompletionPercentage(skill: SkillQueueItem): number {
 }
 
 export function calculateTrainingHours(skill: SkillQueueItem): number {
+  if (skill.secondsRemaining != null) {
+    return Math.max(skill.secondsRemaining, 0) / 3600;
+  }
+
   if (!skill.spPerMinute || skill.spPerMinute <= 0) {
     return 0;
   }
@@ -1,5 +1,9 @@
+import type { PlanEntryFilters, SpStart } from '@/generated/types';
+
 export const queryKeys = {
   accountsAndCharacters: () => ['accountsAndCharacters'] as const,
+  accountsAndCharactersWithTraining: () =>
+    ['accountsAndCharacters', 'training'] as const,
 
   skillPlans: () => ['skillPlans'] as const,
   planGroups: () => ['planGroups'] as const,
@@ -16,13 +20,28 @@ export const queryKeys = {
   skillPlanWithEntries: (planId: number | null) =>
     ['skillPlanWithEntries', planId] as const,
   skillPlanWithEntriesAll: () => ['skillPlanWithEntries'] as const,
+  // Nested under skillPlanWithEntries so entry mutations invalidate pages too.
+  planEntriesPage: (
+    planId: number | null,
+    offset: number,
+    limit: number,
+    filters: PlanEntryFilters | null
+  ) =>
+    ['skillPlanWithEntries', planId, 'page', offset, limit, filters] as const,
+  searchPlans: (query: string) => ['searchPlans', query] as const,
   skillPlanValidation: (planId: number | null) =>
     ['skillPlanValidation', planId] as const,
   skillPlanValidationAll: () => ['skillPlanValidation'] as const,
+  alphaPlanValidation: (planId: number | null) =>
+    ['skillPlanValidation', planId, 'alpha'] as const,
   exportSkillPlanText: (planId: number | null) =>
     ['exportSkillPlanText', planId] as const,
   exportSkillPlanXml: (planId: number | null) =>
     ['exportSkillPlanXml', planId] as const,
+  exportSkillPlanClipboard: (planId: number | null, format: string) =>
+    ['exportSkillPlanClipboard', planId, format] as const,
+  exportSkillPlanClipboardByPlan: (planId: number | null) =>
+    ['exportSkillPlanClipboard', planId] as const,
   planComparison: (planId: number | null, characterId: number | null) =>
     ['planComparison', planId, characterId] as const,
   planComparisonByPlan: (planId: number) => ['planComparison', planId] as const,
@@ -30,8 +49,31 @@ export const queryKeys = {
   planComparisonAll: (planId: number | null) =>
     ['planComparisonAll', planId] as const,
   planComparisonAllRoot: () => ['planComparisonAll'] as const,
+  planQueueSync: (planId: number | null, characterId: number | null) =>
+    ['planComparison', planId, characterId, 'queue'] as const,
+  planHistory: (planId: number | null) => ['planHistory', planId] as const,
   skillPlanSimulation: (planId: number) =>
     ['skillPlanSimulation', planId] as const,
+  skillPlanSimulationAll: () => ['skillPlanSimulation'] as const,
+  plannedBoosters: (planId: number) => ['plannedBoosters', planId] as const,
+  skillPlanWhatIf: (
+    planId: number,
+    characterId: number | null,
+    overridesKey: string
+  ) =>
+    ['skillPlanSimulation', planId, 'what-if', characterId, overridesKey] as const,
+  skillPlanRemapComparison: (
+    planId: number,
+    characterId: number | null,
+    schedulesKey: string
+  ) =>
+    [
+      'skillPlanSimulation',
+      planId,
+      'remap-comparison',
+      characterId,
+      schedulesKey,
+    ] as const,
   skillPlanSimulationQuery: (
     planId: number,
     profileKey: string,
@@ -39,6 +81,7 @@ export const queryKeys = {
   ) => ['skillPlanSimulation', planId, profileKey, characterId] as const,
   skillPlanOptimization: (planId: number) =>
     ['skillPlanOptimization', planId] as const,
+  skillPlanOptimizationAll: () => ['skillPlanOptimization'] as const,
   skillPlanOptimizationQuery: (
     planId: number,
     implantsKey: string,
@@ -46,7 +89,8 @@ export const queryKeys = {
     acceleratorBonus: number,
     characterId?: number | null,
     mode?: string,
-    maxRemaps?: number
+    maxRemaps?: number,
+    compareImplants?: boolean
   ) =>
     [
       'skillPlanOptimization',
@@ -57,18 +101,58 @@ export const queryKeys = {
       characterId,
       mode,
       maxRemaps,
+      compareImplants,
+    ] as const,
+  implantRecommendation: (
+    planId: number,
+    remapKey: string,
+    acceleratorBonus: number,
+    characterId: number | null,
+    budgetIsk: number
+  ) =>
+    [
+      'skillPlanOptimization',
+      planId,
+      'implants',
+      remapKey,
+      acceleratorBonus,
+      characterId,
+      budgetIsk,
+    ] as const,
+  crossPlanOptimization: (
+    characterId: number | null,
+    planIds: number[],
+    maxRemaps: number
+  ) =>
+    [
+      'skillPlanOptimization',
+      'across',
+      characterId,
+      planIds,
+      maxRemaps,
     ] as const,
 
   notificationSettings: (characterId: number | null) =>
     ['notificationSettings', characterId] as const,
+  notificationWebhooks: () => ['notificationWebhooks'] as const,
 
   skillDetails: (skillId: number | null, characterId: number | null) =>
     ['skillDetails', skillId, characterId] as const,
+  nextLevelEstimate: (characterId: number | null, skillId: number | null) =>
+    ['nextLevelEstimate', characterId, skillId] as const,
+  spNeeded: (skillTypeId: number | null, from: SpStart, toLevel: number) =>
+    ['spNeeded', skillTypeId, from, toLevel] as const,
+  skillSummary: (characterId: number | null) =>
+    ['skillSummary', characterId] as const,
+  nextGoals: (characterId: number | null) =>
+    ['nextGoals', characterId] as const,
 
   remaps: {
     plan: (planId: number | null) => ['remaps', 'plan', planId] as const,
     character: (characterId: number | null) =>
       ['remaps', 'character', characterId] as const,
+    wizard: (characterId: number | null, planIds: number[], days: number) =>
+      ['remaps', 'wizard', characterId, planIds, days] as const,
   },
 
   simulation: (planId: number) => ['simulation', planId] as const,
@@ -82,6 +166,23 @@ export const queryKeys = {
   baseScopeStrings: () => ['base-scope-strings'] as const,
   enabledFeatures: () => ['enabled-features'] as const,
   optionalFeatures: () => ['optional-features'] as const,
+  characterFeatures: (characterId: number | null) =>
+    ['character-features', characterId] as const,
   characterFeatureScopeStatus: () =>
     ['character-feature-scope-status'] as const,
+  marketPrices: () => ['market-prices'] as const,
+  marketTypeIds: () => ['market-type-ids'] as const,
+  omegaBudget: () => ['omega-budget'] as const,
+  esiExplorerSettings: () => ['esi-explorer-settings'] as const,
+  metricsServerSettings: () => ['metrics-server-settings'] as const,
+  levelFormat: () => ['level-format'] as const,
+  schedulerSettings: () => ['scheduler-settings'] as const,
+  schedulerStatus: () => ['scheduler-status'] as const,
+  appHealth: () => ['app-health'] as const,
+  spHistory: (characterId: number | null, days: number) =>
+    ['sp-history', characterId, days] as const,
+  activityFeed: (limit: number) => ['activity-feed', limit] as const,
+  auditLog: (limit: number) => ['audit-log', limit] as const,
+  planAssignments: (planId: number | null) =>
+    ['plan-assignments', planId] as const,
 };
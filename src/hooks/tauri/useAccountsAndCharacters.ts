@@ -1,7 +1,11 @@
 import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
 import { invoke } from '@tauri-apps/api/core';
+import { useEffect } from 'react';
 
-import type { AccountsAndCharactersResponse } from '@/generated/types';
+import type {
+  AccountsAndCharactersResponse,
+  AccountsUpdated,
+} from '@/generated/types';
 
 import { queryKeys } from './queryKeys';
 
@@ -48,17 +52,83 @@ interface ReorderUnassignedCharactersParams {
   characterIds: number[];
 }
 
+// The backend's cached copy is only taken for the first load, when there is
+// nothing to show yet; the fresh one follows as `accounts:updated`.
 export function useAccountsAndCharacters() {
+  const queryClient = useQueryClient();
+
   return useQuery<AccountsAndCharactersResponse>({
     queryKey: queryKeys.accountsAndCharacters(),
     queryFn: async () => {
       return await invoke<AccountsAndCharactersResponse>(
-        'get_accounts_and_characters'
+        'get_accounts_and_characters',
+        {
+          allowCached: !queryClient.getQueryData(
+            queryKeys.accountsAndCharacters()
+          ),
+        }
+      );
+    },
+  });
+}
+
+// Same data with each character's training summary from cached queues.
+// Account mutations invalidate it through the shared key prefix.
+export function useAccountsAndCharactersWithTraining() {
+  const queryClient = useQueryClient();
+
+  return useQuery<AccountsAndCharactersResponse>({
+    queryKey: queryKeys.accountsAndCharactersWithTraining(),
+    queryFn: async () => {
+      return await invoke<AccountsAndCharactersResponse>(
+        'get_accounts_and_characters',
+        {
+          includeTraining: true,
+          allowCached: !queryClient.getQueryData(
+            queryKeys.accountsAndCharactersWithTraining()
+          ),
+        }
       );
     },
   });
 }
 
+/** Swaps a cached accounts response for the fresh one once it is ready. */
+export function useAccountsUpdatedEvents() {
+  const queryClient = useQueryClient();
+
+  useEffect(() => {
+    let cleanup: (() => void) | null = null;
+
+    const setup = async () => {
+      try {
+        const { listen } = await import('@tauri-apps/api/event');
+
+        const unlisten = await listen<AccountsUpdated>(
+          'accounts:updated',
+          ({ payload }) => {
+            queryClient.setQueryData(
+              payload.include_training
+                ? queryKeys.accountsAndCharactersWithTraining()
+                : queryKeys.accountsAndCharacters(),
+              payload.response
+            );
+          }
+        );
+
+        cleanup = () => {
+          unlisten();
+        };
+      } catch (error) {
+        console.error('Failed to setup accounts update listener:', error);
+      }
+    };
+
+    setup();
+    return () => cleanup?.();
+  }, [queryClient]);
+}
+
 export function useCreateAccount() {
   const queryClient = useQueryClient();
 
@@ -0,0 +1,13 @@
+import { useQuery } from '@tanstack/react-query';
+import { invoke } from '@tauri-apps/api/core';
+
+import type { ActivityEntry } from '@/generated/types';
+
+import { queryKeys } from './queryKeys';
+
+export function useActivityFeed(limit: number = 100) {
+  return useQuery<ActivityEntry[]>({
+    queryKey: queryKeys.activityFeed(limit),
+    queryFn: () => invoke<ActivityEntry[]>('get_activity_feed', { limit }),
+  });
+}
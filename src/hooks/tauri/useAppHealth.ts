@@ -0,0 +1,14 @@
+import { useQuery } from '@tanstack/react-query';
+import { invoke } from '@tauri-apps/api/core';
+
+import type { AppHealth } from '@/generated/types';
+
+import { queryKeys } from './queryKeys';
+
+export function useAppHealth() {
+  return useQuery<AppHealth>({
+    queryKey: queryKeys.appHealth(),
+    queryFn: () => invoke<AppHealth>('get_app_health'),
+    refetchInterval: 30_000,
+  });
+}
@@ -0,0 +1,13 @@
+import { useQuery } from '@tanstack/react-query';
+import { invoke } from '@tauri-apps/api/core';
+
+import type { AuditEntry } from '@/generated/types';
+
+import { queryKeys } from './queryKeys';
+
+export function useAuditLog(limit: number = 100) {
+  return useQuery<AuditEntry[]>({
+    queryKey: queryKeys.auditLog(limit),
+    queryFn: () => invoke<AuditEntry[]>('get_audit_log', { limit }),
+  });
+}
@@ -0,0 +1,41 @@
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { invoke } from '@tauri-apps/api/core';
+
+import type {
+  EsiExplorerSettings,
+  EsiMethod,
+  RawEsiResponse,
+} from '@/generated/types';
+
+import { queryKeys } from './queryKeys';
+
+export function useEsiExplorerSettings() {
+  return useQuery<EsiExplorerSettings>({
+    queryKey: queryKeys.esiExplorerSettings(),
+    queryFn: () => invoke<EsiExplorerSettings>('get_esi_explorer_settings'),
+  });
+}
+
+export function useSetEsiExplorerSettings() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (settings: EsiExplorerSettings) =>
+      invoke<void>('set_esi_explorer_settings', { settings }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.esiExplorerSettings(),
+      });
+    },
+  });
+}
+
+export function useEsiRawRequest() {
+  return useMutation({
+    mutationFn: (params: {
+      characterId: number | null;
+      method: EsiMethod;
+      path: string;
+      query?: Record<string, string>;
+    }) => invoke<RawEsiResponse>('esi_raw_request', params),
+  });
+}
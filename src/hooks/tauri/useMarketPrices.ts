@@ -0,0 +1,49 @@
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { invoke } from '@tauri-apps/api/core';
+
+import type { MarketPrice, OmegaBudget } from '@/generated/types';
+
+import { queryKeys } from './queryKeys';
+
+export function useMarketPrices() {
+  return useQuery<MarketPrice[]>({
+    queryKey: queryKeys.marketPrices(),
+    queryFn: () => invoke<MarketPrice[]>('get_market_prices'),
+  });
+}
+
+export function useRefreshMarketPrices() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: () => invoke<MarketPrice[]>('refresh_market_prices'),
+    onSuccess: (prices) => {
+      queryClient.setQueryData(queryKeys.marketPrices(), prices);
+      queryClient.invalidateQueries({ queryKey: queryKeys.omegaBudget() });
+    },
+  });
+}
+
+export function useMarketTypeIds() {
+  return useQuery<number[]>({
+    queryKey: queryKeys.marketTypeIds(),
+    queryFn: () => invoke<number[]>('get_market_type_ids'),
+  });
+}
+
+export function useSetMarketTypeIds() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (typeIds: number[]) =>
+      invoke<void>('set_market_type_ids', { typeIds }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.marketTypeIds() });
+    },
+  });
+}
+
+export function useOmegaBudget() {
+  return useQuery<OmegaBudget>({
+    queryKey: queryKeys.omegaBudget(),
+    queryFn: () => invoke<OmegaBudget>('get_omega_budget'),
+  });
+}
@@ -0,0 +1,64 @@
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { invoke } from '@tauri-apps/api/core';
+
+import type {
+  NotificationWebhook,
+  NotificationWebhookInput,
+} from '@/generated/types';
+
+import { queryKeys } from './queryKeys';
+
+export function useNotificationWebhooks() {
+  return useQuery<NotificationWebhook[]>({
+    queryKey: queryKeys.notificationWebhooks(),
+    queryFn: () => invoke<NotificationWebhook[]>('get_notification_webhooks'),
+  });
+}
+
+export function useCreateNotificationWebhook() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (webhook: NotificationWebhookInput) =>
+      invoke<number>('create_notification_webhook', { webhook }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.notificationWebhooks(),
+      });
+    },
+  });
+}
+
+export function useUpdateNotificationWebhook() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (params: {
+      webhookId: number;
+      webhook: NotificationWebhookInput;
+    }) => invoke<void>('update_notification_webhook', params),
+    onSuccess: () => {
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.notificationWebhooks(),
+      });
+    },
+  });
+}
+
+export function useDeleteNotificationWebhook() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (webhookId: number) =>
+      invoke<void>('delete_notification_webhook', { webhookId }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.notificationWebhooks(),
+      });
+    },
+  });
+}
+
+export function useTestNotificationWebhook() {
+  return useMutation({
+    mutationFn: (webhookId: number) =>
+      invoke<void>('test_notification_webhook', { webhookId }),
+  });
+}
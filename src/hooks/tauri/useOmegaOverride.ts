@@ -0,0 +1,25 @@
+import { useMutation, useQueryClient } from '@tanstack/react-query';
+import { invoke } from '@tauri-apps/api/core';
+
+import { queryKeys } from './queryKeys';
+
+export interface SetOmegaOverrideParams {
+  characterId: number;
+  /** `null` goes back to inferring the clone state from ESI. */
+  isOmega: boolean | null;
+}
+
+export function useSetOmegaOverride() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (params: SetOmegaOverrideParams) => {
+      return invoke<void>('set_character_omega_override', { ...params });
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.accountsAndCharacters(),
+      });
+    },
+  });
+}
@@ -1,8 +1,12 @@
 import { useQuery } from '@tanstack/react-query';
 import { invoke } from '@tauri-apps/api/core';
+import { useCallback, useEffect, useRef, useState } from 'react';
 
 import type {
   Attributes,
+  CrossPlanOptimizationResult,
+  ImplantRecommendation,
+  OptimizationProgress,
   OptimizationResult,
   ReorderOptimizationResult,
 } from '@/generated/types';
@@ -12,6 +16,55 @@ import { queryKeys } from './queryKeys';
 
 export type OptimizationMode = 'attributes' | 'reorder';
 
+// Tracks the remap search started last: its `optimization-progress` events
+// and a cancel that stops it on the backend.
+function useOptimizationRun() {
+  const runId = useRef<string | null>(null);
+  const [progress, setProgress] = useState<OptimizationProgress | null>(null);
+
+  useEffect(() => {
+    let cleanup: (() => void) | null = null;
+
+    const setup = async () => {
+      try {
+        const { listen } = await import('@tauri-apps/api/event');
+
+        const unlisten = await listen<OptimizationProgress>(
+          'optimization-progress',
+          (event) => {
+            if (event.payload.optimization_id === runId.current) {
+              setProgress(event.payload);
+            }
+          }
+        );
+
+        cleanup = () => {
+          unlisten();
+        };
+      } catch (error) {
+        console.error('Failed to setup optimization progress listener:', error);
+      }
+    };
+
+    setup();
+    return () => cleanup?.();
+  }, []);
+
+  const start = useCallback(() => {
+    const id = crypto.randomUUID();
+    runId.current = id;
+    setProgress(null);
+    return id;
+  }, []);
+
+  const cancel = useCallback(async () => {
+    if (!runId.current) return;
+    await invoke('cancel_optimization', { optimizationId: runId.current });
+  }, []);
+
+  return { start, progress, cancel };
+}
+
 export function useOptimization(
   planId: number,
   implants: Attributes,
@@ -19,8 +72,11 @@ export function useOptimization(
   acceleratorBonus: number,
   characterId?: number | null,
   mode: OptimizationMode = 'attributes',
-  maxRemaps: number = 1
+  maxRemaps: number = 1,
+  // Attributes mode only: also compare +0/+3/+4/+5 implant sets.
+  compareImplants: boolean = false
 ) {
+  const run = useOptimizationRun();
   const query = useQuery<OptimizationResult | ReorderOptimizationResult, Error>(
     {
       queryKey: queryKeys.skillPlanOptimizationQuery(
@@ -30,7 +86,8 @@ export function useOptimization(
         acceleratorBonus,
         characterId,
         mode,
-        maxRemaps
+        maxRemaps,
+        compareImplants
       ),
       queryFn: async () => {
         if (mode === 'reorder') {
@@ -41,6 +98,7 @@ export function useOptimization(
             acceleratorBonus,
             characterId: characterId || undefined,
             maxRemaps,
+            optimizationId: run.start(),
           });
         } else {
           return invoke<OptimizationResult>('optimize_plan_attributes', {
@@ -49,6 +107,7 @@ export function useOptimization(
             baselineRemap,
             acceleratorBonus,
             characterId: characterId || undefined,
+            compareImplants,
           });
         }
       },
@@ -61,5 +120,61 @@ export function useOptimization(
     isLoading: query.isLoading,
     error: query.error,
     refetch: query.refetch,
+    progress: run.progress,
+    cancel: run.cancel,
   };
 }
+
+/** Attribute implants that train the plan fastest within `budgetIsk`. */
+export function useImplantRecommendation(
+  planId: number,
+  remap: Attributes,
+  acceleratorBonus: number,
+  characterId: number | null,
+  budgetIsk: number
+) {
+  return useQuery<ImplantRecommendation, Error>({
+    queryKey: queryKeys.implantRecommendation(
+      planId,
+      stableStringify(remap),
+      acceleratorBonus,
+      characterId,
+      budgetIsk
+    ),
+    queryFn: () =>
+      invoke<ImplantRecommendation>('recommend_plan_implants', {
+        planId,
+        remap,
+        acceleratorBonus,
+        characterId: characterId || undefined,
+        budgetIsk,
+      }),
+    enabled: !!planId,
+  });
+}
+
+/** One remap schedule for a character training `planIds` in that order. */
+export function useCrossPlanOptimization(
+  characterId: number | null,
+  planIds: number[],
+  maxRemaps: number = 1
+) {
+  const run = useOptimizationRun();
+  const query = useQuery<CrossPlanOptimizationResult, Error>({
+    queryKey: queryKeys.crossPlanOptimization(characterId, planIds, maxRemaps),
+    queryFn: async () => {
+      if (characterId === null) {
+        throw new Error('Character ID is required');
+      }
+      return invoke<CrossPlanOptimizationResult>('optimize_across_plans', {
+        characterId,
+        planIds,
+        maxRemaps,
+        optimizationId: run.start(),
+      });
+    },
+    enabled: characterId !== null && planIds.length > 0,
+  });
+
+  return { ...query, progress: run.progress, cancel: run.cancel };
+}
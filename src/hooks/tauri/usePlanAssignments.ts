@@ -0,0 +1,39 @@
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { invoke } from '@tauri-apps/api/core';
+
+import type { PlanAssignmentMatrix } from '@/generated/types';
+
+import { queryKeys } from './queryKeys';
+
+export function usePlanAssignments(planId: number | null) {
+  return useQuery<PlanAssignmentMatrix | null>({
+    queryKey: queryKeys.planAssignments(planId),
+    queryFn: async () => {
+      if (!planId) {
+        return null;
+      }
+      return invoke<PlanAssignmentMatrix>('get_plan_assignments', { planId });
+    },
+    enabled: planId !== null,
+  });
+}
+
+export interface SetPlanAssignmentsParams {
+  planId: number;
+  characterIds: number[];
+}
+
+export function useSetPlanAssignments() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (params: SetPlanAssignmentsParams) => {
+      return invoke<PlanAssignmentMatrix>('set_plan_assignments', {
+        ...params,
+      });
+    },
+    onSuccess: (matrix, { planId }) => {
+      queryClient.setQueryData(queryKeys.planAssignments(planId), matrix);
+    },
+  });
+}
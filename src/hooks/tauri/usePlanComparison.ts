@@ -1,7 +1,10 @@
 import { useQuery } from '@tanstack/react-query';
 import { invoke } from '@tauri-apps/api/core';
 
-import type { PlanComparisonResponse } from '@/generated/types';
+import type {
+  PlanComparisonResponse,
+  PlanQueueSync,
+} from '@/generated/types';
 
 import { queryKeys } from './queryKeys';
 
@@ -23,3 +26,23 @@ export function usePlanComparison(
     enabled: planId !== null && characterId !== null,
   });
 }
+
+/** Which plan entries the character's live skill queue already covers. */
+export function usePlanQueueSync(
+  planId: number | null,
+  characterId: number | null
+) {
+  return useQuery<PlanQueueSync | null>({
+    queryKey: queryKeys.planQueueSync(planId, characterId),
+    queryFn: async () => {
+      if (!planId || !characterId) {
+        return null;
+      }
+      return invoke<PlanQueueSync>('sync_plan_with_queue', {
+        planId,
+        characterId,
+      });
+    },
+    enabled: planId !== null && characterId !== null,
+  });
+}
@@ -66,3 +66,17 @@ export function useMoveNode() {
     },
   });
 }
+
+export function useMovePlanToGroup() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (vars: { planId: number; groupId: number | null }) =>
+      invoke<void>('move_plan_to_group', {
+        planId: vars.planId,
+        groupId: vars.groupId,
+      }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.skillPlans() });
+    },
+  });
+}
@@ -0,0 +1,43 @@
+import { useEffect, useState } from 'react';
+
+import type { RateLimitWarning } from '@/generated/types';
+
+// Latest ESI error-budget warning, or null once its window has reset.
+export function useRateLimitWarning() {
+  const [warning, setWarning] = useState<RateLimitWarning | null>(null);
+
+  useEffect(() => {
+    let cleanup: (() => void) | null = null;
+
+    const setup = async () => {
+      try {
+        const { listen } = await import('@tauri-apps/api/event');
+
+        const unlisten = await listen<RateLimitWarning>(
+          'rate-limit-warning',
+          (event) => {
+            setWarning(event.payload);
+          }
+        );
+
+        cleanup = () => {
+          unlisten();
+        };
+      } catch (error) {
+        console.error('Failed to setup rate limit warning listener:', error);
+      }
+    };
+
+    setup();
+    return () => cleanup?.();
+  }, []);
+
+  useEffect(() => {
+    if (!warning) return;
+    const resetIn = new Date(warning.reset_at).getTime() - Date.now();
+    const timer = setTimeout(() => setWarning(null), Math.max(resetIn, 0));
+    return () => clearTimeout(timer);
+  }, [warning]);
+
+  return warning;
+}
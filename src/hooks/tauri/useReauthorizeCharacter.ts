@@ -0,0 +1,68 @@
+import { useMutation } from '@tanstack/react-query';
+import { invoke } from '@tauri-apps/api/core';
+import { useEffect, useState } from 'react';
+
+import type { EsiScope, ReauthRequired } from '@/generated/types';
+
+interface ReauthorizeCharacterParams {
+  [key: string]: unknown;
+  characterId: number;
+  additionalScopes: EsiScope[];
+}
+
+export function useReauthorizeCharacter() {
+  return useMutation({
+    mutationFn: async (params: ReauthorizeCharacterParams) => {
+      return invoke<string>('reauthorize_character', params);
+    },
+  });
+}
+
+// Characters whose token lacks scopes for enabled features, keyed by
+// character id. Cleared for a character once it logs in again.
+export function useReauthRequired() {
+  const [required, setRequired] = useState<Map<number, ReauthRequired>>(
+    new Map()
+  );
+
+  useEffect(() => {
+    let cleanup: (() => void) | null = null;
+
+    const setup = async () => {
+      try {
+        const { listen } = await import('@tauri-apps/api/event');
+
+        const unlistenRequired = await listen<ReauthRequired>(
+          'auth:reauth-required',
+          (event) => {
+            setRequired((current) =>
+              new Map(current).set(event.payload.character_id, event.payload)
+            );
+          }
+        );
+        const unlistenSuccess = await listen<number>(
+          'auth-success',
+          (event) => {
+            setRequired((current) => {
+              const next = new Map(current);
+              next.delete(event.payload);
+              return next;
+            });
+          }
+        );
+
+        cleanup = () => {
+          unlistenRequired();
+          unlistenSuccess();
+        };
+      } catch (error) {
+        console.error('Failed to setup reauth listener:', error);
+      }
+    };
+
+    setup();
+    return () => cleanup?.();
+  }, []);
+
+  return required;
+}
@@ -1,7 +1,17 @@
-import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import {
+  type QueryClient,
+  useMutation,
+  useQuery,
+  useQueryClient,
+} from '@tanstack/react-query';
 import { invoke } from '@tauri-apps/api/core';
 
-import type { Remap } from '@/generated/types';
+import type {
+  Attributes,
+  PlannedRemap,
+  Remap,
+  RemapWizardResult,
+} from '@/generated/types';
 
 import { queryKeys } from './queryKeys';
 
@@ -42,6 +52,24 @@ export function usePlanRemaps(planId: number | null) {
   });
 }
 
+export function useRemapWizard(
+  characterId: number | null,
+  planIds: number[],
+  days: number
+) {
+  return useQuery<RemapWizardResult>({
+    queryKey: queryKeys.remaps.wizard(characterId, planIds, days),
+    queryFn: async () => {
+      return await invoke<RemapWizardResult>('get_remap_wizard', {
+        characterId,
+        planIds,
+        days,
+      });
+    },
+    enabled: characterId !== null && days > 0,
+  });
+}
+
 export function useSaveRemap() {
   const queryClient = useQueryClient();
 
@@ -96,3 +124,46 @@ export function useDeleteRemap() {
     },
   });
 }
+
+function invalidatePlanRemaps(queryClient: QueryClient, planId: number) {
+  queryClient.invalidateQueries({ queryKey: queryKeys.remaps.plan(planId) });
+  queryClient.invalidateQueries({ queryKey: queryKeys.simulation(planId) });
+  queryClient.invalidateQueries({
+    queryKey: queryKeys.skillPlanWithEntries(planId),
+  });
+}
+
+// Moving a remap to another entry is done by changing its anchor.
+export function useUpdateRemap() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (params: {
+      remapId: number;
+      planId: number;
+      afterSkillTypeId: number | null;
+      afterSkillLevel: number | null;
+      attributes: Attributes;
+    }) => {
+      await invoke('update_remap', {
+        remapId: params.remapId,
+        afterSkillTypeId: params.afterSkillTypeId,
+        afterSkillLevel: params.afterSkillLevel,
+        attributes: params.attributes,
+      });
+    },
+    onSuccess: (_, params) => invalidatePlanRemaps(queryClient, params.planId),
+  });
+}
+
+// Saves an optimizer's recommended remaps into the plan, replacing its own.
+export function useSavePlannedRemaps() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (params: { planId: number; remaps: PlannedRemap[] }) => {
+      return await invoke<Remap[]>('save_planned_remaps', params);
+    },
+    onSuccess: (_, params) => invalidatePlanRemaps(queryClient, params.planId),
+  });
+}
@@ -0,0 +1,53 @@
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
+import { invoke } from '@tauri-apps/api/core';
+
+import type {
+  ScheduledTask,
+  ScheduledTaskStatus,
+  SchedulerSettings,
+} from '@/generated/types';
+
+import { queryKeys } from './queryKeys';
+
+export function useSchedulerStatus() {
+  return useQuery<ScheduledTaskStatus[]>({
+    queryKey: queryKeys.schedulerStatus(),
+    queryFn: () => invoke<ScheduledTaskStatus[]>('get_scheduler_status'),
+    refetchInterval: 10_000,
+  });
+}
+
+export function useSchedulerSettings() {
+  return useQuery<SchedulerSettings>({
+    queryKey: queryKeys.schedulerSettings(),
+    queryFn: () => invoke<SchedulerSettings>('get_scheduler_settings'),
+  });
+}
+
+export function useSetSchedulerSettings() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (settings: SchedulerSettings) =>
+      invoke<void>('set_scheduler_settings', { settings }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.schedulerSettings(),
+      });
+      queryClient.invalidateQueries({ queryKey: queryKeys.schedulerStatus() });
+    },
+  });
+}
+
+// Omit `task` to pause or resume every scheduled task.
+export function useSetScheduledTaskPaused() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: ({ task, paused }: { task?: ScheduledTask; paused: boolean }) =>
+      invoke<void>(paused ? 'pause_scheduled_task' : 'resume_scheduled_task', {
+        task: task ?? null,
+      }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.schedulerStatus() });
+    },
+  });
+}
@@ -6,7 +6,10 @@ import type {
   BaseScopeStrings,
   BooleanAppSettingKey,
   CharacterFeatureScopeStatus,
+  CharacterModule,
   FeatureId,
+  LevelFormat,
+  MetricsServerSettings,
   OptionalFeature,
 } from '@/generated/types';
 
@@ -35,6 +38,24 @@ export function useSetBooleanAppSetting() {
   });
 }
 
+export function useLevelFormat() {
+  return useQuery<LevelFormat>({
+    queryKey: queryKeys.levelFormat(),
+    queryFn: () => invoke<LevelFormat>('get_level_format'),
+  });
+}
+
+export function useSetLevelFormat() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (format: LevelFormat) =>
+      invoke<void>('set_level_format', { format }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.levelFormat() });
+    },
+  });
+}
+
 export function useBaseScopeStrings() {
   return useQuery<BaseScopeStrings>({
     queryKey: queryKeys.baseScopeStrings(),
@@ -84,6 +105,34 @@ export function useSetFeatureEnabled() {
   });
 }
 
+export function useCharacterFeatures(characterId: number | null) {
+  return useQuery<CharacterModule[]>({
+    queryKey: queryKeys.characterFeatures(characterId),
+    queryFn: () =>
+      invoke<CharacterModule[]>('get_character_features', { characterId }),
+    enabled: characterId !== null,
+  });
+}
+
+export function useSetCharacterFeatures() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: ({
+      characterId,
+      features,
+    }: {
+      characterId: number;
+      features: CharacterModule[];
+    }) => invoke<void>('set_character_features', { characterId, features }),
+    onSuccess: (_, { characterId }) => {
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.characterFeatures(characterId),
+      });
+    },
+  });
+}
+
 export function useCharacterFeatureScopeStatus() {
   return useQuery<CharacterFeatureScopeStatus[]>({
     queryKey: queryKeys.characterFeatureScopeStatus(),
@@ -94,3 +143,23 @@ export function useCharacterFeatureScopeStatus() {
     },
   });
 }
+
+export function useMetricsServerSettings() {
+  return useQuery<MetricsServerSettings>({
+    queryKey: queryKeys.metricsServerSettings(),
+    queryFn: () => invoke<MetricsServerSettings>('get_metrics_server_settings'),
+  });
+}
+
+export function useSetMetricsServerSettings() {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: (settings: MetricsServerSettings) =>
+      invoke<void>('set_metrics_server_settings', { settings }),
+    onSuccess: () => {
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.metricsServerSettings(),
+      });
+    },
+  });
+}
@@ -1,12 +1,17 @@
-import { useQuery } from '@tanstack/react-query';
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query';
 import { invoke } from '@tauri-apps/api/core';
 import { useState } from 'react';
 
 import type {
+  PlannedBooster,
+  PlannedRemap,
   Remap,
+  RemapScheduleComparison,
   SimulationProfile,
   SimulationResult,
   SkillPlanEntryResponse,
+  WhatIfOverrides,
+  WhatIfTimeline,
 } from '@/generated/types';
 import { stableStringify } from '@/lib/utils';
 
@@ -25,6 +30,7 @@ export function useSimulation(planId: number, characterId?: number | null) {
     },
     remaps: [],
     accelerators: [],
+    pauses: [],
   });
 
   const { data: planRemaps } = usePlanRemaps(planId);
@@ -92,3 +98,99 @@ export function useSimulation(planId: number, characterId?: number | null) {
     error: query.error,
   };
 }
+
+/** The plan's timeline with `overrides` applied; changes nothing. */
+export function useWhatIfSimulation(
+  planId: number,
+  characterId: number | null,
+  overrides: WhatIfOverrides
+) {
+  return useQuery<WhatIfTimeline, Error>({
+    queryKey: queryKeys.skillPlanWhatIf(
+      planId,
+      characterId,
+      stableStringify(overrides)
+    ),
+    queryFn: () =>
+      invoke<WhatIfTimeline>('simulate_plan_what_if', {
+        planId,
+        characterId: characterId || undefined,
+        overrides,
+      }),
+    enabled: !!planId,
+  });
+}
+
+/** The plan simulated under two remap schedules, side by side. */
+export function useCompareRemapSchedules(
+  planId: number,
+  characterId: number | null,
+  scheduleA: PlannedRemap[],
+  scheduleB: PlannedRemap[]
+) {
+  return useQuery<RemapScheduleComparison, Error>({
+    queryKey: queryKeys.skillPlanRemapComparison(
+      planId,
+      characterId,
+      stableStringify({ scheduleA, scheduleB })
+    ),
+    queryFn: () =>
+      invoke<RemapScheduleComparison>('compare_remap_schedules', {
+        planId,
+        scheduleA,
+        scheduleB,
+        characterId: characterId || undefined,
+      }),
+    enabled: !!planId,
+  });
+}
+
+export function usePlannedBoosters(planId: number) {
+  return useQuery<PlannedBooster[]>({
+    queryKey: queryKeys.plannedBoosters(planId),
+    queryFn: () => invoke<PlannedBooster[]>('get_planned_boosters', { planId }),
+    enabled: !!planId,
+  });
+}
+
+function invalidatePlannedBoosters(
+  queryClient: ReturnType<typeof useQueryClient>,
+  planId: number
+) {
+  queryClient.invalidateQueries({
+    queryKey: queryKeys.plannedBoosters(planId),
+  });
+  queryClient.invalidateQueries({
+    queryKey: queryKeys.skillPlanSimulation(planId),
+  });
+}
+
+export function useSavePlannedBooster() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: {
+      planId: number;
+      typeId?: number;
+      bonus: number;
+      durationSeconds: number;
+      afterSkillTypeId?: number;
+      afterSkillLevel?: number;
+    }) => invoke<number>('save_planned_booster', params),
+    onSuccess: (_, params) => {
+      invalidatePlannedBoosters(queryClient, params.planId);
+    },
+  });
+}
+
+export function useDeletePlannedBooster() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: (params: { boosterId: number; planId: number }) =>
+      invoke('delete_planned_booster', { boosterId: params.boosterId }),
+    onSuccess: (_, params) => {
+      invalidatePlannedBoosters(queryClient, params.planId);
+    },
+  });
+}
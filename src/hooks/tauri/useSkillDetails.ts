@@ -1,7 +1,13 @@
 import { useQuery } from '@tanstack/react-query';
 import { invoke } from '@tauri-apps/api/core';
 
-import type { SkillDetailsResponse } from '@/generated/types';
+import type {
+  NextLevelEstimate,
+  SkillDetailsResponse,
+  SkillSummary,
+  SpNeededResponse,
+  SpStart,
+} from '@/generated/types';
 
 import { queryKeys } from './queryKeys';
 
@@ -23,3 +29,39 @@ export function useSkillDetails(
     enabled: skillId !== null,
   });
 }
+
+export function useNextLevelEstimate(
+  characterId: number | null,
+  skillId: number | null
+) {
+  return useQuery<NextLevelEstimate>({
+    queryKey: queryKeys.nextLevelEstimate(characterId, skillId),
+    queryFn: () =>
+      invoke<NextLevelEstimate>('queue_next_level_estimate', {
+        characterId,
+        skillId,
+      }),
+    enabled: characterId !== null && skillId !== null,
+  });
+}
+
+export function useSpNeeded(
+  skillTypeId: number | null,
+  from: SpStart,
+  toLevel: number
+) {
+  return useQuery<SpNeededResponse>({
+    queryKey: queryKeys.spNeeded(skillTypeId, from, toLevel),
+    queryFn: () =>
+      invoke<SpNeededResponse>('sp_needed', { skillTypeId, from, toLevel }),
+    enabled: skillTypeId !== null,
+  });
+}
+
+export function useSkillSummary(characterId: number | null) {
+  return useQuery<SkillSummary>({
+    queryKey: queryKeys.skillSummary(characterId),
+    queryFn: () => invoke<SkillSummary>('get_skill_summary', { characterId }),
+    enabled: characterId !== null,
+  });
+}
@@ -7,7 +7,19 @@ import {
 import { invoke } from '@tauri-apps/api/core';
 
 import type {
+  ClipboardFormat,
+  ConflictStrategy,
   MergeIntoPlanResponse,
+  MoveEntriesSummary,
+  NextGoal,
+  OmegaOnlyEntry,
+  PlanEntriesPage,
+  PlanEntryFilters,
+  PlanImportResponse,
+  PlanRevision,
+  PlanSearchHit,
+  PlanSortPreset,
+  PlannedRemap,
   PreviewPlanFromCharacterResponse,
   ReplacePlanEntryInput,
   SkillmonPlan,
@@ -71,6 +83,7 @@ interface UpdatePlanEntryParams {
   entryId: number;
   plannedLevel?: number;
   notes?: string;
+  excluded?: boolean;
 }
 
 interface DeletePlanEntryParams {
@@ -78,6 +91,14 @@ interface DeletePlanEntryParams {
   entryId: number;
 }
 
+interface SortPlanParams {
+  [key: string]: unknown;
+  planId: number;
+  preset: PlanSortPreset;
+  /** Uses this character's attributes for the training-time preset. */
+  characterId?: number | null;
+}
+
 interface ReorderPlanEntriesParams {
   [key: string]: unknown;
   planId: number;
@@ -88,12 +109,32 @@ interface ImportSkillPlanTextParams {
   [key: string]: unknown;
   planId: number;
   text: string;
+  /** How to treat skills already in the plan; the backend defaults to
+   * keep-higher-level. */
+  conflictStrategy?: ConflictStrategy;
 }
 
 interface ImportSkillPlanXmlParams {
   [key: string]: unknown;
   planId: number;
   xml: string;
+  conflictStrategy?: ConflictStrategy;
+}
+
+interface ImportPlanFromFittingParams {
+  [key: string]: unknown;
+  planId: number;
+  /** EFT fitting block, as copied from Pyfa or the in-game fitting window. */
+  text: string;
+  conflictStrategy?: ConflictStrategy;
+}
+
+interface ImportSkillPlanEmpParams {
+  [key: string]: unknown;
+  planId: number;
+  /** Raw bytes of the EVEMon .emp file (gzipped XML). */
+  data: number[];
+  conflictStrategy?: ConflictStrategy;
 }
 
 interface ValidateReorderParams {
@@ -136,6 +177,7 @@ function invalidatePlanDerivedCaches(queryClient: QueryClient, planId: number) {
     queryKeys.exportSkillPlanText(planId),
     queryKeys.exportSkillPlanXml(planId),
     queryKeys.remaps.plan(planId),
+    queryKeys.planHistory(planId),
   ];
   for (const queryKey of keys) {
     queryClient.invalidateQueries({ queryKey });
@@ -164,22 +206,48 @@ export function useSkillPlan(planId: number | null) {
   });
 }
 
-export function useSkillPlanWithEntries(planId: number | null) {
+// With a character, entries also carry that character's training times.
+export function useSkillPlanWithEntries(
+  planId: number | null,
+  characterId: number | null = null
+) {
   return useQuery<SkillPlanWithEntriesResponse | null>({
-    queryKey: queryKeys.skillPlanWithEntries(planId),
+    queryKey:
+      characterId === null
+        ? queryKeys.skillPlanWithEntries(planId)
+        : [...queryKeys.skillPlanWithEntries(planId), characterId],
     queryFn: async () => {
       if (planId === null) {
         return null;
       }
       return await invoke<SkillPlanWithEntriesResponse>(
         'get_skill_plan_with_entries',
-        { planId }
+        { planId, characterId }
       );
     },
     enabled: planId !== null,
   });
 }
 
+export function usePlanEntriesPage(
+  planId: number | null,
+  offset: number,
+  limit: number,
+  filters: PlanEntryFilters | null = null
+) {
+  return useQuery<PlanEntriesPage>({
+    queryKey: queryKeys.planEntriesPage(planId, offset, limit, filters),
+    queryFn: () =>
+      invoke<PlanEntriesPage>('get_plan_entries_page', {
+        planId,
+        offset,
+        limit,
+        filters,
+      }),
+    enabled: planId !== null,
+  });
+}
+
 export function useCreateSkillPlan() {
   const queryClient = useQueryClient();
 
@@ -283,6 +351,28 @@ export function useMergePlansInto() {
   });
 }
 
+export function useMoveEntriesToPlan() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (params: {
+      sourcePlanId: number;
+      entryIds: number[];
+      targetPlanId: number;
+    }) => {
+      return await invoke<MoveEntriesSummary>('move_entries_to_plan', params);
+    },
+    onSuccess: (_data, params) => {
+      for (const planId of [params.sourcePlanId, params.targetPlanId]) {
+        queryClient.invalidateQueries({
+          queryKey: queryKeys.skillPlanWithEntries(planId),
+        });
+        invalidatePlanDerivedCaches(queryClient, planId);
+      }
+    },
+  });
+}
+
 export function useReplacePlanEntries() {
   const queryClient = useQueryClient();
 
@@ -302,6 +392,34 @@ export function useReplacePlanEntries() {
   });
 }
 
+/** Recorded changes to the plan's entries, newest first. */
+export function usePlanHistory(planId: number | null) {
+  return useQuery<PlanRevision[]>({
+    queryKey: queryKeys.planHistory(planId),
+    queryFn: async () => {
+      if (!planId) return [];
+      return await invoke<PlanRevision[]>('get_plan_history', { planId });
+    },
+    enabled: planId !== null,
+  });
+}
+
+export function useRevertPlanToRevision() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (params: { planId: number; revisionId: number }) => {
+      return await invoke('revert_plan_to_revision', params);
+    },
+    onSuccess: (_, params) => {
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.skillPlanWithEntries(params.planId),
+      });
+      invalidatePlanDerivedCaches(queryClient, params.planId);
+    },
+  });
+}
+
 export function useUpdateSkillPlan() {
   const queryClient = useQueryClient();
 
@@ -324,6 +442,38 @@ export function useUpdateSkillPlan() {
   });
 }
 
+// The owner is the default character for entry timings and exports.
+export function useSetPlanOwner() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (params: {
+      planId: number;
+      characterId: number | null;
+    }) => {
+      return await invoke('set_plan_owner', params);
+    },
+    onSuccess: (_, params) => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.skillPlans() });
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.skillPlan(params.planId),
+      });
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.skillPlanWithEntries(params.planId),
+      });
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.exportSkillPlanText(params.planId),
+      });
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.exportSkillPlanXml(params.planId),
+      });
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.exportSkillPlanClipboardByPlan(params.planId),
+      });
+    },
+  });
+}
+
 export function useDeleteSkillPlan() {
   const queryClient = useQueryClient();
 
@@ -419,6 +569,33 @@ export function useUpdatePlanEntry() {
       queryClient.invalidateQueries({
         queryKey: queryKeys.planComparisonByPlanAll(),
       });
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.skillPlanSimulationAll(),
+      });
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.skillPlanOptimizationAll(),
+      });
+    },
+  });
+}
+
+// 1 (highest) to 5 (lowest); breaks ties when the optimizer reorders.
+export function useSetPlanEntryPriority() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (params: {
+      planId: number;
+      entryIds: number[];
+      priority: number;
+    }) => {
+      return await invoke('set_plan_entry_priority', params);
+    },
+    onSuccess: (_, params) => {
+      queryClient.invalidateQueries({
+        queryKey: queryKeys.skillPlanWithEntries(params.planId),
+      });
+      invalidatePlanDerivedCaches(queryClient, params.planId);
     },
   });
 }
@@ -526,20 +703,61 @@ export function useReorderPlanEntries() {
   });
 }
 
-export function useImportSkillPlanText() {
+export function useSortPlan() {
   const queryClient = useQueryClient();
 
   return useMutation({
-    mutationFn: async (params: ImportSkillPlanTextParams) => {
+    mutationFn: async (params: SortPlanParams) => {
+      return await invoke<SkillPlanWithEntriesResponse>('sort_plan', params);
+    },
+    onSuccess: (data, params) => {
+      queryClient.setQueryData(
+        queryKeys.skillPlanWithEntries(params.planId),
+        data
+      );
+      invalidatePlanDerivedCaches(queryClient, params.planId);
+    },
+  });
+}
+
+export function useApplyPlanOptimization() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (params: {
+      planId: number;
+      optimizedEntryIds: number[];
+      remaps: PlannedRemap[];
+    }) => {
       return await invoke<SkillPlanWithEntriesResponse>(
-        'import_skill_plan_text',
+        'apply_plan_optimization',
         params
       );
     },
+    onSuccess: (data, params) => {
+      queryClient.setQueryData(
+        queryKeys.skillPlanWithEntries(params.planId),
+        data
+      );
+      invalidatePlanDerivedCaches(queryClient, params.planId);
+    },
+  });
+}
+
+export function useImportSkillPlanText() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (params: ImportSkillPlanTextParams) => {
+      return await invoke<PlanImportResponse>('import_skill_plan_text', params);
+    },
     onSuccess: (data, params) => {
       const planId = params.planId;
       // Seed from the authoritative import response; invalidate the rest.
-      queryClient.setQueryData(queryKeys.skillPlanWithEntries(planId), data);
+      queryClient.setQueryData(
+        queryKeys.skillPlanWithEntries(planId),
+        data.plan
+      );
       invalidatePlanDerivedCaches(queryClient, planId);
     },
   });
@@ -550,20 +768,69 @@ export function useImportSkillPlanXml() {
 
   return useMutation({
     mutationFn: async (params: ImportSkillPlanXmlParams) => {
-      return await invoke<SkillPlanWithEntriesResponse>(
-        'import_skill_plan_xml',
-        params
+      return await invoke<PlanImportResponse>('import_skill_plan_xml', params);
+    },
+    onSuccess: (data, params) => {
+      const planId = params.planId;
+      // Seed from the authoritative import response; invalidate the rest.
+      queryClient.setQueryData(
+        queryKeys.skillPlanWithEntries(planId),
+        data.plan
       );
+      invalidatePlanDerivedCaches(queryClient, planId);
+    },
+  });
+}
+
+export function useImportSkillPlanEmp() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (params: ImportSkillPlanEmpParams) => {
+      return await invoke<PlanImportResponse>('import_skill_plan_emp', params);
     },
     onSuccess: (data, params) => {
       const planId = params.planId;
       // Seed from the authoritative import response; invalidate the rest.
-      queryClient.setQueryData(queryKeys.skillPlanWithEntries(planId), data);
+      queryClient.setQueryData(
+        queryKeys.skillPlanWithEntries(planId),
+        data.plan
+      );
       invalidatePlanDerivedCaches(queryClient, planId);
     },
   });
 }
 
+export function useImportPlanFromFitting() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (params: ImportPlanFromFittingParams) => {
+      return await invoke<PlanImportResponse>('import_plan_from_fitting', params);
+    },
+    onSuccess: (data, params) => {
+      const planId = params.planId;
+      // Seed from the authoritative import response; invalidate the rest.
+      queryClient.setQueryData(
+        queryKeys.skillPlanWithEntries(planId),
+        data.plan
+      );
+      invalidatePlanDerivedCaches(queryClient, planId);
+    },
+  });
+}
+
+export function useSearchPlans(query: string) {
+  const trimmed = query.trim();
+  return useQuery<PlanSearchHit[]>({
+    queryKey: queryKeys.searchPlans(trimmed),
+    queryFn: async () => {
+      return await invoke<PlanSearchHit[]>('search_plans', { query: trimmed });
+    },
+    enabled: trimmed.length >= 2,
+  });
+}
+
 export function useExportSkillPlanText(planId: number | null) {
   return useQuery<string>({
     queryKey: queryKeys.exportSkillPlanText(planId),
@@ -590,6 +857,26 @@ export function useExportSkillPlanXml(planId: number | null) {
   });
 }
 
+/** The plan as paste text for the in-game skill queue or multibuy window. */
+export function useExportSkillPlanClipboard(
+  planId: number | null,
+  format: ClipboardFormat
+) {
+  return useQuery<string>({
+    queryKey: queryKeys.exportSkillPlanClipboard(planId, format),
+    queryFn: async () => {
+      if (planId === null) {
+        throw new Error('Plan ID is required');
+      }
+      return await invoke<string>('export_skill_plan_clipboard', {
+        planId,
+        format,
+      });
+    },
+    enabled: false,
+  });
+}
+
 export function useSkillPlanValidation(planId: number | null) {
   return useQuery<ValidationResponse | null>({
     queryKey: queryKeys.skillPlanValidation(planId),
@@ -605,6 +892,21 @@ export function useSkillPlanValidation(planId: number | null) {
   });
 }
 
+export function useAlphaPlanValidation(planId: number | null) {
+  return useQuery<OmegaOnlyEntry[] | null>({
+    queryKey: queryKeys.alphaPlanValidation(planId),
+    queryFn: async () => {
+      if (planId === null) {
+        return null;
+      }
+      return await invoke<OmegaOnlyEntry[]>('validate_plan_for_alpha', {
+        planId,
+      });
+    },
+    enabled: planId !== null,
+  });
+}
+
 export function useValidateReorder() {
   return useMutation({
     mutationFn: async (params: ValidateReorderParams) => {
@@ -674,3 +976,50 @@ export function useCreatePlanFromCharacter() {
     },
   });
 }
+
+export function useDuplicateSkillPlan() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (params: { planId: number; newName: string }) => {
+      return await invoke<number>('duplicate_skill_plan', params);
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.skillPlans() });
+    },
+  });
+}
+
+export function useCreatePlanFromTemplate() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (params: { templateId: number; characterId: number }) => {
+      return await invoke<number>('create_plan_from_template', params);
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.skillPlans() });
+    },
+  });
+}
+
+export function useNextGoals(characterId: number | null) {
+  return useQuery<NextGoal[]>({
+    queryKey: queryKeys.nextGoals(characterId),
+    queryFn: () => invoke<NextGoal[]>('suggest_next_goals', { characterId }),
+    enabled: characterId !== null,
+  });
+}
+
+export function useCreatePlanForGoal() {
+  const queryClient = useQueryClient();
+
+  return useMutation({
+    mutationFn: async (params: { characterId: number; typeId: number }) => {
+      return await invoke<number>('create_plan_for_goal', params);
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: queryKeys.skillPlans() });
+    },
+  });
+}
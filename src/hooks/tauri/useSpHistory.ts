@@ -0,0 +1,15 @@
+import { useQuery } from '@tanstack/react-query';
+import { invoke } from '@tauri-apps/api/core';
+
+import type { SpHistoryPoint } from '@/generated/types';
+
+import { queryKeys } from './queryKeys';
+
+export function useSpHistory(characterId: number | null, days: number) {
+  return useQuery<SpHistoryPoint[]>({
+    queryKey: queryKeys.spHistory(characterId, days),
+    queryFn: () =>
+      invoke<SpHistoryPoint[]>('get_sp_history', { characterId, days }),
+    enabled: characterId !== null,
+  });
+}
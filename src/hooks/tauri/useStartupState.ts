@@ -1,7 +1,11 @@
 import { invoke } from '@tauri-apps/api/core';
 import { useEffect, useState } from 'react';
 
+import type { StartupStatus } from '@/generated/types';
+import { StartupPhase } from '@/generated/types';
+
 export function useStartupState() {
+  const [status, setStatus] = useState<StartupStatus | null>(null);
   const [isStartingUp, setIsStartingUp] = useState(true);
 
   useEffect(() => {
@@ -9,25 +13,26 @@ export function useStartupState() {
 
     const setup = async () => {
       try {
-        // Check initial startup status
-        const initialStatus = await invoke<boolean>('is_startup_complete');
-        setIsStartingUp(!initialStatus);
-
-        // If already complete, no need to listen for events
-        if (initialStatus) {
-          return;
-        }
-
-        // Listen for startup-complete event
         const { listen } = await import('@tauri-apps/api/event');
 
-        const unlisten = await listen('startup-complete', () => {
-          setIsStartingUp(false);
-        });
-
+        // Subscribe before reading the current status so a phase change in
+        // between is not missed.
+        const unlisten = await listen<StartupStatus>(
+          'startup:progress',
+          (event) => {
+            setStatus(event.payload);
+            setIsStartingUp(event.payload.phase !== StartupPhase.Ready);
+          }
+        );
         cleanup = () => {
           unlisten();
         };
+
+        const initialStatus = await invoke<StartupStatus>('get_startup_status');
+        setStatus((current) => current ?? initialStatus);
+        if (initialStatus.phase === StartupPhase.Ready) {
+          setIsStartingUp(false);
+        }
       } catch (error) {
         console.error('Failed to setup startup state listener:', error);
         if (error instanceof Error && error.message.includes('Tauri')) {
@@ -42,5 +47,5 @@ export function useStartupState() {
     return () => cleanup?.();
   }, []);
 
-  return { isStartingUp };
+  return { isStartingUp, status };
 }
@@ -0,0 +1,76 @@
+import { beforeEach, describe, expect, it, vi } from 'vitest';
+
+import type { CharacterSnapshot, QueuePayload } from '@/generated/types';
+import { useEsiStore } from '@/stores/esiStore';
+
+import { applySnapshots } from './esiEvents';
+
+vi.mock('@tauri-apps/api/core', () => ({ invoke: vi.fn() }));
+vi.mock('@tauri-apps/api/event', () => ({ listen: vi.fn() }));
+
+function queue(label: string): QueuePayload {
+  return { label } as unknown as QueuePayload;
+}
+
+function snapshot(
+  characterId: number,
+  overrides: Partial<CharacterSnapshot> = {}
+): CharacterSnapshot {
+  return {
+    characterId,
+    characterName: `Pilot ${characterId}`,
+    queue: null,
+    skills: null,
+    attributes: null,
+    clones: [],
+    location: null,
+    remaps: [],
+    membershipHistory: [],
+    overview: null,
+    isCached: false,
+    ...overrides,
+  } as CharacterSnapshot;
+}
+
+describe('ESI snapshot hydration', () => {
+  beforeEach(() => {
+    useEsiStore.setState({
+      queues: {},
+      skills: {},
+      locations: {},
+      attributes: {},
+      clones: {},
+      overview: {},
+    });
+  });
+
+  it('keeps data an event delivered before the cached reply', () => {
+    const since = useEsiStore.getState();
+    useEsiStore.getState().setQueue(1, queue('fresh'));
+
+    applySnapshots(
+      [
+        snapshot(1, { queue: queue('cached'), isCached: true }),
+        snapshot(2, { queue: queue('cached'), isCached: true }),
+      ],
+      since
+    );
+
+    const { queues } = useEsiStore.getState();
+    expect(queues[1].data).toEqual(queue('fresh'));
+    expect(queues[2].data).toEqual(queue('cached'));
+  });
+
+  it('replaceAll drops removed characters and cleared fields', () => {
+    const store = useEsiStore.getState();
+    store.setQueue(1, queue('old'));
+    store.setQueue(2, queue('old'));
+
+    store.replaceAll([snapshot(1), snapshot(3, { queue: queue('new') })]);
+
+    const { queues, clones } = useEsiStore.getState();
+    expect(Object.keys(queues)).toEqual(['3']);
+    expect(queues[3].data).toEqual(queue('new'));
+    expect(Object.keys(clones).sort()).toEqual(['1', '3']);
+  });
+});
@@ -3,6 +3,7 @@ import { listen } from '@tauri-apps/api/event';
 
 import type {
   AttributesPayload,
+  CharacterSnapshot,
   ClonesPayload,
   LocationPayload,
   NotificationResponse,
@@ -10,6 +11,7 @@ import type {
   QueuePayload,
   SkillsPayload,
 } from '@/generated/types';
+import type { EsiStoreState, ResourceKey } from '@/stores/esiStore';
 import { useEsiStore } from '@/stores/esiStore';
 import { useNotificationsStore } from '@/stores/notificationsStore';
 
@@ -67,3 +69,33 @@ export async function bootstrapEsiEvents(
     unlistenNotifications();
   };
 }
+
+/**
+ * Hydrates the store from a snapshot reply, skipping every slice an event has
+ * written since `since` was read: that data is newer than the reply, which
+ * may have come from the backend's cache.
+ */
+export function applySnapshots(
+  snapshots: CharacterSnapshot[],
+  since: EsiStoreState
+) {
+  const store = useEsiStore.getState();
+  const untouched = (key: ResourceKey | 'overview', id: number) =>
+    store[key][id] === since[key][id];
+
+  for (const snapshot of snapshots) {
+    const id = snapshot.characterId;
+    if (snapshot.queue && untouched('queues', id))
+      store.setQueue(id, snapshot.queue);
+    if (snapshot.skills && untouched('skills', id))
+      store.setSkills(id, snapshot.skills);
+    if (snapshot.attributes && untouched('attributes', id))
+      store.setAttributes(id, snapshot.attributes);
+    if (snapshot.clones.length > 0 && untouched('clones', id))
+      store.setClones(id, snapshot.clones);
+    if (snapshot.location && untouched('locations', id))
+      store.setLocation(id, snapshot.location);
+    if (untouched('overview', id))
+      store.setOverviewRow(id, snapshot.overview ?? null);
+  }
+}
@@ -1,6 +1,11 @@
-// Must match NOTIFICATION_TYPE_SKILL_QUEUE_LOW in src-tauri/src/notifications/checkers/skill_queue_low.rs
+// Must match the NOTIFICATION_TYPE_* constants in src-tauri/src/notifications/checkers/
 export const NOTIFICATION_TYPES = {
   SKILL_QUEUE_LOW: 'skill_queue_low',
+  SKILL_QUEUE_PAUSED: 'skill_queue_paused',
+  SKILL_QUEUE_EMPTY: 'skill_queue_empty',
+  OMEGA_LAPSED: 'omega_lapsed',
+  OMEGA_RUNWAY_LOW: 'omega_runway_low',
+  REFRESH_STALE: 'refresh_stale',
 } as const;
 
 export type NotificationType =
@@ -1,6 +1,7 @@
 import { useIsFetching } from '@tanstack/react-query';
 import { createRootRoute, Link, Outlet } from '@tanstack/react-router';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { check } from '@tauri-apps/plugin-updater';
 import { Download } from 'lucide-react';
 import { useEffect, useState } from 'react';
@@ -13,20 +14,37 @@ import { Button } from '@/components/ui/button';
 import { NavigationTabs } from '@/components/ui/navigation-tabs';
 import { Toaster } from '@/components/ui/sonner';
 import { Spinner } from '@/components/ui/spinner';
-import type { CharacterSnapshot } from '@/generated/types';
-import { FeatureId } from '@/generated/types';
+import type { CharacterSnapshot, StartupStatus } from '@/generated/types';
+import { FeatureId, StartupPhase } from '@/generated/types';
+import { useAccountsUpdatedEvents } from '@/hooks/tauri/useAccountsAndCharacters';
 import { useAuthEvents } from '@/hooks/tauri/useAuthEvents';
 import { useEnabledFeatures } from '@/hooks/tauri/useSettings';
 import { useStartupState } from '@/hooks/tauri/useStartupState';
-import { bootstrapEsiEvents } from '@/lib/esiEvents';
+import { applySnapshots, bootstrapEsiEvents } from '@/lib/esiEvents';
 import { cn } from '@/lib/utils';
 import { useEsiStore } from '@/stores/esiStore';
 import { useSkillDetailStore } from '@/stores/skillDetailStore';
 import { useUpdateStore } from '@/stores/updateStore';
 
+function describeStartupStatus(status: StartupStatus | null): string {
+  switch (status?.phase) {
+    case StartupPhase.DbMigrating:
+      return 'Preparing database';
+    case StartupPhase.SdeImporting:
+      return 'Downloading game data';
+    case StartupPhase.RefreshingCharacters:
+      return status.charactersTotal > 0
+        ? `Refreshing characters (${status.charactersRefreshed}/${status.charactersTotal})`
+        : 'Refreshing characters';
+    default:
+      return 'Checking for updates';
+  }
+}
+
 function RootComponent() {
   useAuthEvents();
-  const { isStartingUp } = useStartupState();
+  useAccountsUpdatedEvents();
+  const { isStartingUp, status: startupStatus } = useStartupState();
   const isFetching = useIsFetching();
   const [addCharacterOpen, setAddCharacterOpen] = useState(false);
   const [notificationDrawerOpen, setNotificationDrawerOpen] = useState(false);
@@ -69,21 +87,29 @@ function RootComponent() {
           ...accountsData.accounts.flatMap((a) => a.characters),
         ].map((c) => c.character_id);
 
-        try {
-          const snapshots =
-            await invoke<CharacterSnapshot[]>('get_esi_snapshot');
-          const store = useEsiStore.getState();
-          for (const snapshot of snapshots) {
-            const id = snapshot.characterId;
-            if (snapshot.queue) store.setQueue(id, snapshot.queue);
-            if (snapshot.skills) store.setSkills(id, snapshot.skills);
-            if (snapshot.attributes)
-              store.setAttributes(id, snapshot.attributes);
-            if (snapshot.clones.length > 0)
-              store.setClones(id, snapshot.clones);
-            if (snapshot.location) store.setLocation(id, snapshot.location);
-            store.setOverviewRow(id, snapshot.overview ?? null);
+        // Listen first: a cached snapshot is followed by the fresh one as a
+        // single replace event, which may even beat the cached reply here.
+        let replaced = false;
+        const unlistenSnapshot = await listen<CharacterSnapshot[]>(
+          'esi-snapshot:updated',
+          ({ payload }) => {
+            replaced = true;
+            useEsiStore.getState().replaceAll(payload);
           }
+        );
+        const unlistenCharacters = await bootstrapEsiEvents(characterIds);
+        cleanup = () => {
+          unlistenSnapshot();
+          unlistenCharacters();
+        };
+
+        try {
+          const since = useEsiStore.getState();
+          const snapshots = await invoke<CharacterSnapshot[]>(
+            'get_esi_snapshot',
+            { allowCached: true }
+          );
+          if (!replaced) applySnapshots(snapshots, since);
         } catch (err) {
           const store = useEsiStore.getState();
           for (const id of characterIds) {
@@ -94,8 +120,6 @@ function RootComponent() {
             store.setError('clones', id, String(err));
           }
         }
-
-        cleanup = await bootstrapEsiEvents(characterIds);
       } catch (err) {
         console.warn('Failed to hydrate ESI store:', err);
       }
@@ -114,7 +138,7 @@ function RootComponent() {
         <div className="text-center">
           <p className="text-lg text-muted-foreground">Starting up...</p>
           <p className="text-sm text-muted-foreground mt-2">
-            Checking for updates
+            {describeStartupStatus(startupStatus)}
           </p>
         </div>
       </div>
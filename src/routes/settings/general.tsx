@@ -41,6 +41,24 @@ function GeneralPage() {
     );
   };
 
+  const handleDebugLoggingChange = (checked: boolean) => {
+    setBooleanAppSetting.mutate(
+      { key: BooleanAppSettingKey.DebugLogging, value: checked },
+      {
+        onSuccess: () => {
+          toast.success(
+            checked ? 'Debug logging enabled' : 'Debug logging disabled'
+          );
+        },
+        onError: (error) => {
+          toast.error(
+            `Failed to update setting: ${error instanceof Error ? error.message : String(error)}`
+          );
+        },
+      }
+    );
+  };
+
   return (
     <div className="max-w-2xl space-y-6">
       <div>
@@ -69,6 +87,27 @@ function GeneralPage() {
             disabled={setBooleanAppSetting.isPending}
           />
         </div>
+
+        <div className="flex items-center justify-between p-4 border rounded-lg bg-card">
+          <div className="space-y-1 pr-8">
+            <Label
+              htmlFor="debug-logging"
+              className="text-base font-medium cursor-pointer"
+            >
+              Debug Logging
+            </Label>
+            <p className="text-sm text-muted-foreground">
+              Write detailed timings, such as plan optimization steps, to the
+              log file in the app log directory.
+            </p>
+          </div>
+          <Switch
+            id="debug-logging"
+            checked={settings?.debug_logging ?? false}
+            onCheckedChange={handleDebugLoggingChange}
+            disabled={setBooleanAppSetting.isPending}
+          />
+        </div>
       </div>
     </div>
   );
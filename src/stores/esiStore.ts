@@ -2,6 +2,7 @@ import { create } from 'zustand';
 
 import type {
   AttributesPayload,
+  CharacterSnapshot,
   CloneInfo,
   LocationPayload,
   OverviewRow,
@@ -15,9 +16,14 @@ type ResourceSlice<T> = {
   lastError: string | null;
 };
 
-type ResourceKey = 'queues' | 'skills' | 'locations' | 'attributes' | 'clones';
+export type ResourceKey =
+  | 'queues'
+  | 'skills'
+  | 'locations'
+  | 'attributes'
+  | 'clones';
 
-interface EsiStoreState {
+export interface EsiStoreState {
   queues: Record<number, ResourceSlice<QueuePayload>>;
   skills: Record<number, ResourceSlice<SkillsPayload>>;
   locations: Record<number, ResourceSlice<LocationPayload>>;
@@ -33,6 +39,11 @@ interface EsiStoreState {
   setOverviewRow(characterId: number, row: OverviewRow | null): void;
   setError(resource: ResourceKey, characterId: number, error: string): void;
   clearCharacter(characterId: number): void;
+  /**
+   * Replaces everything with a full snapshot: characters missing from it are
+   * dropped and fields it leaves empty are cleared.
+   */
+  replaceAll(snapshots: CharacterSnapshot[]): void;
 }
 
 const now = () => new Date().toISOString();
@@ -138,4 +149,38 @@ export const useEsiStore = create<EsiStoreState>((set) => ({
         overview: dropOverview(s.overview),
       };
     }),
+
+  replaceAll: (snapshots) =>
+    set(() => {
+      const at = now();
+      const slices = <T>(
+        pick: (snapshot: CharacterSnapshot) => T | null | undefined
+      ): Record<number, ResourceSlice<T>> => {
+        const record: Record<number, ResourceSlice<T>> = {};
+        for (const snapshot of snapshots) {
+          const data = pick(snapshot);
+          if (data != null) {
+            record[snapshot.characterId] = {
+              data,
+              lastUpdatedAt: at,
+              lastError: null,
+            };
+          }
+        }
+        return record;
+      };
+      const overview: Record<number, OverviewRow> = {};
+      for (const snapshot of snapshots) {
+        if (snapshot.overview)
+          overview[snapshot.characterId] = snapshot.overview;
+      }
+      return {
+        queues: slices((s) => s.queue),
+        skills: slices((s) => s.skills),
+        locations: slices((s) => s.location),
+        attributes: slices((s) => s.attributes),
+        clones: slices((s) => s.clones),
+        overview,
+      };
+    }),
 }));